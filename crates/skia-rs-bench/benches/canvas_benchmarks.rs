@@ -476,7 +476,7 @@ fn bench_surface(c: &mut Criterion) {
     group.bench_function("canvas", |b| {
         b.iter_batched(
             || Surface::new_raster(&info, None).unwrap(),
-            |mut surface| surface.canvas(),
+            |mut surface| surface.canvas().width(),
             criterion::BatchSize::SmallInput,
         )
     });
@@ -6,6 +6,7 @@ use skia_rs_bench::{
     random_points, random_rects,
 };
 use skia_rs_canvas::Surface;
+use skia_rs_canvas::simd::{SimdCapabilities, fill_span_solid, fill_span_solid_with};
 use skia_rs_core::{Color, Point, Rect};
 use skia_rs_paint::{Paint, Style};
 use std::hint::black_box;
@@ -337,6 +338,42 @@ fn bench_raster_transforms(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_simd_spans(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Raster/simd_spans");
+
+    let colors = [
+        ("opaque", Color::from_argb(255, 200, 100, 50)),
+        ("blend", Color::from_argb(128, 100, 50, 25)),
+    ];
+
+    for width in [16usize, 256, 1920] {
+        group.throughput(Throughput::Elements(width as u64));
+        for (name, color) in colors {
+            let mut span = vec![96u8; width * 4];
+            group.bench_with_input(
+                BenchmarkId::new(format!("{name}_simd"), width),
+                &color,
+                |b, &color| b.iter(|| fill_span_solid(black_box(&mut span), black_box(color))),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{name}_scalar"), width),
+                &color,
+                |b, &color| {
+                    b.iter(|| {
+                        fill_span_solid_with(
+                            black_box(&mut span),
+                            black_box(color),
+                            &SimdCapabilities::scalar(),
+                        )
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_raster_clear,
@@ -346,6 +383,7 @@ criterion_group!(
     bench_raster_paths,
     bench_raster_blending,
     bench_raster_transforms,
+    bench_simd_spans,
 );
 
 criterion_main!(benches);
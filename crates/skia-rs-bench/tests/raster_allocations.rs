@@ -0,0 +1,60 @@
+//! Allocation checks for the software rasterizer.
+//!
+//! Installs [`TrackingAllocator`] as the global allocator, so this file holds a
+//! single test to keep the counters free of interference from parallel tests.
+
+use skia_rs_bench::memory::{self, TrackingAllocator};
+use skia_rs_canvas::{PixelBuffer, Rasterizer};
+use skia_rs_core::{Color, Rect};
+use skia_rs_paint::Paint;
+use skia_rs_path::{Path, PathBuilder};
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator::new();
+
+fn diamond(height: f32) -> Path {
+    let mut builder = PathBuilder::new();
+    builder
+        .move_to(128.0, 0.5)
+        .line_to(250.3, height / 2.0)
+        .line_to(128.0, height - 0.5)
+        .line_to(5.7, height / 2.0)
+        .close();
+    builder.build()
+}
+
+fn count_fill_allocations(buffer: &mut PixelBuffer, path: &Path, paint: &Paint) -> usize {
+    let mut rasterizer = Rasterizer::new(buffer);
+    let (_, stats) = memory::measure_memory("fill_path_aa", || {
+        rasterizer.fill_path_aa(path, paint);
+    });
+    stats.alloc_count
+}
+
+#[test]
+fn fill_path_aa_allocations_do_not_scale_with_rows() {
+    let mut paint = Paint::new();
+    paint.set_color32(Color::from_argb(255, 40, 80, 160));
+
+    let mut buffer = PixelBuffer::new(256, 1024);
+    let short = diamond(16.0);
+    let tall = diamond(1024.0);
+
+    // Warm up so lazily-initialized state is not counted.
+    count_fill_allocations(&mut buffer, &short, &paint);
+
+    let short_allocs = count_fill_allocations(&mut buffer, &short, &paint);
+    let tall_allocs = count_fill_allocations(&mut buffer, &tall, &paint);
+    assert_eq!(
+        short_allocs, tall_allocs,
+        "fill_path_aa allocated per row ({short_allocs} for 16 rows, {tall_allocs} for 1024 rows)"
+    );
+
+    // A narrower clip shrinks the coverage line but keeps the count fixed.
+    let mut rasterizer = Rasterizer::new(&mut buffer);
+    rasterizer.set_clip(Rect::from_xywh(0.0, 0.0, 64.0, 1024.0));
+    let (_, clipped) = memory::measure_memory("fill_path_aa clipped", || {
+        rasterizer.fill_path_aa(&tall, &paint);
+    });
+    assert_eq!(clipped.alloc_count, tall_allocs);
+}
@@ -1,5 +1,8 @@
 //! Canvas drawing interface.
 
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 use skia_rs_core::{Color, Matrix, Point, Rect, Scalar};
 use skia_rs_paint::Paint;
 use skia_rs_path::Path;
@@ -40,6 +43,12 @@ pub struct SaveLayerRec<'a> {
 }
 
 /// The main drawing interface.
+///
+/// `Canvas` tracks the matrix and clip stacks but has no pixels, so its
+/// draw calls render nothing. To draw pixels, use the
+/// [`RasterCanvas`](crate::RasterCanvas) returned by
+/// [`Surface::canvas`](crate::Surface::canvas), which also has the draws
+/// `Canvas` lacks, such as `draw_rrect` and `draw_vertices`.
 pub struct Canvas {
     /// Current transformation matrix stack.
     matrix_stack: Vec<Matrix>,
@@ -141,7 +150,7 @@ impl Canvas {
 
     /// Rotate the canvas (angle in degrees).
     pub fn rotate(&mut self, degrees: Scalar) {
-        let radians = degrees * std::f32::consts::PI / 180.0;
+        let radians = degrees * core::f32::consts::PI / 180.0;
         let matrix = Matrix::rotate(radians);
         self.concat(&matrix);
     }
@@ -322,6 +331,8 @@ impl Canvas {
     /// Draw multiple images from an atlas.
     ///
     /// Each sprite has a source rectangle in the atlas and a transformation.
+    /// This canvas has no atlas image to sample, so nothing is drawn; see
+    /// `RasterCanvas::draw_atlas`, which needs the `codec` feature.
     pub fn draw_atlas(
         &mut self,
         _atlas_bounds: &Rect,
@@ -332,8 +343,6 @@ impl Canvas {
         _sampling: FilterMode,
         _paint: Option<&Paint>,
     ) {
-        // Atlas drawing draws multiple sprites from a single texture
-        // Each sprite has an RSXform for positioning and optional color tint
     }
 
     /// Draw a Coons patch.
@@ -386,7 +395,7 @@ impl Canvas {
         font: &skia_rs_text::Font,
         paint: &Paint,
     ) {
-        let text_width = font.measure_text(text);
+        let (text_width, _) = font.measure_text(text);
         let adjusted_x = match align {
             TextAlign::Left => x,
             TextAlign::Center => x - text_width / 2.0,
@@ -484,6 +493,12 @@ pub struct RSXform {
 }
 
 impl RSXform {
+    /// Create from the scaled cosine and sine of the rotation and a
+    /// translation.
+    pub const fn new(scos: Scalar, ssin: Scalar, tx: Scalar, ty: Scalar) -> Self {
+        Self { scos, ssin, tx, ty }
+    }
+
     /// Create from rotation and scale.
     pub fn from_radians(
         scale: Scalar,
@@ -513,12 +528,14 @@ impl RSXform {
     }
 
     /// Convert to a matrix.
+    ///
+    /// The matrix rotates and scales about the origin, then translates.
     pub fn to_matrix(&self) -> Matrix {
-        // Create a combined rotation-scale-translation matrix
-        let rotation_scale = Matrix::rotate(self.ssin.atan2(self.scos));
-        let scale = (self.scos * self.scos + self.ssin * self.ssin).sqrt();
-        let scaled = rotation_scale.concat(&Matrix::scale(scale, scale));
-        scaled.concat(&Matrix::translate(self.tx, self.ty))
+        Matrix {
+            values: [
+                self.scos, -self.ssin, self.tx, self.ssin, self.scos, self.ty, 0.0, 0.0, 1.0,
+            ],
+        }
     }
 }
 
@@ -17,13 +17,19 @@
 //! composed of multiple rectangles. This is efficient for non-anti-aliased
 //! clips with complex shapes.
 
-use skia_rs_core::{IRect, Point, Rect, Region, Scalar};
+use crate::raster::{PixelBuffer, Rasterizer};
+use alloc::{sync::Arc, vec, vec::Vec};
+use skia_rs_core::{Color, IRect, Matrix, Point, Rect, Region, Scalar};
+use skia_rs_paint::Paint;
 use skia_rs_path::Path;
 
 /// A coverage mask for anti-aliased clipping.
 ///
 /// Stores per-pixel coverage values (0-255) where 0 is fully clipped
 /// and 255 is fully visible. Intermediate values provide smooth edges.
+///
+/// Clones share their coverage until one of them is modified, so saving a
+/// clip or handing it to a rasterizer doesn't copy the mask.
 #[derive(Debug, Clone)]
 pub struct ClipMask {
     /// Width in pixels.
@@ -31,7 +37,7 @@ pub struct ClipMask {
     /// Height in pixels.
     height: i32,
     /// Coverage data (one byte per pixel).
-    coverage: Vec<u8>,
+    coverage: Arc<Vec<u8>>,
     /// Bounds of the mask in device coordinates.
     bounds: IRect,
 }
@@ -43,11 +49,32 @@ impl ClipMask {
         Self {
             width,
             height,
-            coverage: vec![initial_coverage; size],
+            coverage: Arc::new(vec![initial_coverage; size]),
             bounds: IRect::new(0, 0, width, height),
         }
     }
 
+    /// Create a clip mask covering the rectangles of `region`, cropped to
+    /// `device_bounds`.
+    pub fn from_region(region: &Region, device_bounds: &IRect) -> Self {
+        let mut mask = Self::new(device_bounds.width(), device_bounds.height(), 0);
+        mask.bounds = *device_bounds;
+
+        for rect in region.iter() {
+            let Some(rect) = rect.intersect(device_bounds) else {
+                continue;
+            };
+            for y in rect.top..rect.bottom {
+                let row = ((y - device_bounds.top) * mask.width) as usize;
+                let start = row + (rect.left - device_bounds.left) as usize;
+                let end = row + (rect.right - device_bounds.left) as usize;
+                mask.coverage_mut()[start..end].fill(255);
+            }
+        }
+
+        mask
+    }
+
     /// Create a clip mask from a rectangle with anti-aliased edges.
     pub fn from_rect_aa(rect: &Rect, device_bounds: &IRect) -> Self {
         let width = device_bounds.width();
@@ -76,56 +103,118 @@ impl ClipMask {
     }
 
     /// Create a clip mask from a path with anti-aliased edges.
+    ///
+    /// Coverage is rasterized by the scanline filler, so it matches an
+    /// anti-aliased fill of the same path. Unless the path has an inverse fill
+    /// type, the mask only spans the path's bounds within `device_bounds`.
     pub fn from_path_aa(path: &Path, device_bounds: &IRect) -> Self {
-        let width = device_bounds.width();
-        let height = device_bounds.height();
-        let mut mask = Self::new(width, height, 0);
+        Self::rasterize_path(path, device_bounds, true)
+    }
 
-        // Use supersampling for path coverage
-        const SAMPLES: i32 = 4;
-        let sample_offsets: [(f32, f32); 16] = [
-            (0.125, 0.125),
-            (0.375, 0.125),
-            (0.625, 0.125),
-            (0.875, 0.125),
-            (0.125, 0.375),
-            (0.375, 0.375),
-            (0.625, 0.375),
-            (0.875, 0.375),
-            (0.125, 0.625),
-            (0.375, 0.625),
-            (0.625, 0.625),
-            (0.875, 0.625),
-            (0.125, 0.875),
-            (0.375, 0.875),
-            (0.625, 0.875),
-            (0.875, 0.875),
-        ];
+    /// Create an aliased clip mask from a path.
+    ///
+    /// Each pixel is fully covered if its center lies inside the path and
+    /// fully clipped otherwise. Like [`from_path_aa`](Self::from_path_aa),
+    /// the mask only spans the path's bounds for the normal fill types.
+    pub fn from_path(path: &Path, device_bounds: &IRect) -> Self {
+        Self::rasterize_path(path, device_bounds, false)
+    }
 
-        for y in 0..height {
-            for x in 0..width {
-                let px = (x + device_bounds.left) as f32;
-                let py = (y + device_bounds.top) as f32;
-
-                // Count samples inside the path
-                let mut inside_count = 0;
-                for (ox, oy) in &sample_offsets {
-                    let sample_x = px + ox;
-                    let sample_y = py + oy;
-                    if path.contains(Point::new(sample_x, sample_y)) {
-                        inside_count += 1;
-                    }
-                }
+    /// Fill `path` into a mask covering its bounds within `device_bounds`.
+    fn rasterize_path(path: &Path, device_bounds: &IRect, anti_alias: bool) -> Self {
+        if path.fill_type().is_inverse() {
+            let mut path = path.clone();
+            path.set_fill_type(path.fill_type().inverse());
+            return Self::rasterize_path(&path, device_bounds, anti_alias).inverted(device_bounds);
+        }
 
-                let coverage = ((inside_count * 255) / 16) as u8;
-                mask.set_coverage(x, y, coverage);
-            }
+        let bounds = path.bounds();
+        let area = if bounds.is_empty() {
+            None
+        } else {
+            bounds.round_out().intersect(device_bounds)
+        };
+        let Some(area) = area else {
+            return Self::empty_at(device_bounds);
+        };
+
+        let mut buffer = PixelBuffer::new(area.width(), area.height());
+        let mut paint = Paint::new();
+        paint.set_color32(Color::WHITE).set_anti_alias(anti_alias);
+        {
+            let mut rasterizer = Rasterizer::new(&mut buffer);
+            rasterizer.set_matrix(&Matrix::translate(
+                -area.left as Scalar,
+                -area.top as Scalar,
+            ));
+            rasterizer.draw_path(path, &paint);
         }
 
-        mask.bounds = *device_bounds;
+        let coverage = buffer
+            .pixels
+            .chunks_exact(4)
+            .map(|pixel| pixel[3])
+            .collect();
+        Self {
+            width: area.width(),
+            height: area.height(),
+            coverage: Arc::new(coverage),
+            bounds: area,
+        }
+    }
+
+    /// An empty mask, which clips everything, anchored at `device_bounds`.
+    fn empty_at(device_bounds: &IRect) -> Self {
+        let mut mask = Self::new(0, 0, 0);
+        mask.bounds = IRect::new(
+            device_bounds.left,
+            device_bounds.top,
+            device_bounds.left,
+            device_bounds.top,
+        );
         mask
     }
 
+    /// Returns the complement of this mask within `device_bounds`.
+    ///
+    /// Unlike [`invert`](Self::invert), pixels outside the mask's bounds,
+    /// which it clips, become fully visible.
+    pub fn inverted(&self, device_bounds: &IRect) -> Self {
+        let mut inverse = Self::new(device_bounds.width(), device_bounds.height(), 255);
+        inverse.bounds = *device_bounds;
+        if let Some(overlap) = self.bounds.intersect(device_bounds) {
+            let coverage = inverse.coverage_mut();
+            for y in overlap.top..overlap.bottom {
+                for x in overlap.left..overlap.right {
+                    let i = ((y - device_bounds.top) * device_bounds.width()
+                        + (x - device_bounds.left)) as usize;
+                    coverage[i] = 255 - self.get_coverage_device(x, y);
+                }
+            }
+        }
+        inverse
+    }
+
+    /// Move the mask by `(dx, dy)` device pixels.
+    pub fn offset(&mut self, dx: i32, dy: i32) {
+        self.bounds = IRect::new(
+            self.bounds.left + dx,
+            self.bounds.top + dy,
+            self.bounds.right + dx,
+            self.bounds.bottom + dy,
+        );
+    }
+
+    /// Invert the coverage, so clipped pixels become visible and vice versa.
+    ///
+    /// Only pixels within the mask's bounds change; use
+    /// [`inverted`](Self::inverted) to also show what lies outside them.
+    pub fn invert(&mut self) {
+        for coverage in self.coverage_mut() {
+            *coverage = 255 - *coverage;
+        }
+    }
+
     /// Get the coverage value at (x, y) in local coordinates.
     #[inline]
     pub fn get_coverage(&self, x: i32, y: i32) -> u8 {
@@ -147,10 +236,17 @@ impl ClipMask {
     #[inline]
     pub fn set_coverage(&mut self, x: i32, y: i32, coverage: u8) {
         if x >= 0 && x < self.width && y >= 0 && y < self.height {
-            self.coverage[(y * self.width + x) as usize] = coverage;
+            let width = self.width;
+            self.coverage_mut()[(y * width + x) as usize] = coverage;
         }
     }
 
+    /// The coverage data for writing, copied first if a clone shares it.
+    #[inline]
+    fn coverage_mut(&mut self) -> &mut [u8] {
+        Arc::make_mut(&mut self.coverage).as_mut_slice()
+    }
+
     /// Returns the bounds of this mask.
     #[inline]
     pub fn bounds(&self) -> IRect {
@@ -170,51 +266,41 @@ impl ClipMask {
     }
 
     /// Intersect this mask with another mask.
+    ///
+    /// Both masks clip everything outside their bounds, so the result is
+    /// cropped to the overlap of the two.
     pub fn intersect(&mut self, other: &ClipMask) {
-        // Find intersection bounds
         let Some(intersection) = self.bounds.intersect(&other.bounds) else {
-            // No intersection - clear the mask
-            self.coverage.fill(0);
+            *self = Self::empty_at(&self.bounds);
             return;
         };
 
-        // For pixels in the intersection, multiply coverage
+        let mut combined = Self::new(intersection.width(), intersection.height(), 0);
+        combined.bounds = intersection;
+        let coverage = combined.coverage_mut();
+        let mut i = 0;
         for y in intersection.top..intersection.bottom {
             for x in intersection.left..intersection.right {
                 let self_cov = self.get_coverage_device(x, y) as u32;
                 let other_cov = other.get_coverage_device(x, y) as u32;
-                let combined = ((self_cov * other_cov) / 255) as u8;
-
-                let lx = x - self.bounds.left;
-                let ly = y - self.bounds.top;
-                self.set_coverage(lx, ly, combined);
-            }
-        }
-
-        // Clear pixels outside the intersection
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let dx = x + self.bounds.left;
-                let dy = y + self.bounds.top;
-                if dx < intersection.left
-                    || dx >= intersection.right
-                    || dy < intersection.top
-                    || dy >= intersection.bottom
-                {
-                    self.coverage[(y * self.width + x) as usize] = 0;
-                }
+                coverage[i] = ((self_cov * other_cov) / 255) as u8;
+                i += 1;
             }
         }
+        *self = combined;
     }
 
     /// Apply a rectangular clip to this mask.
     pub fn clip_rect(&mut self, rect: &IRect) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let dx = x + self.bounds.left;
-                let dy = y + self.bounds.top;
-                if !rect.contains(dx, dy) {
-                    self.coverage[(y * self.width + x) as usize] = 0;
+        let (width, height, bounds) = (self.width, self.height, self.bounds);
+        if rect.intersect(&bounds) == Some(bounds) {
+            return;
+        }
+        let coverage = self.coverage_mut();
+        for y in 0..height {
+            for x in 0..width {
+                if !rect.contains(x + bounds.left, y + bounds.top) {
+                    coverage[(y * width + x) as usize] = 0;
                 }
             }
         }
@@ -391,12 +477,19 @@ impl ClipState {
 }
 
 /// A stack of clip states for save/restore semantics.
+///
+/// Each level caches the device bounds of its clip, intersected with every
+/// clip applied at or below it, so [`bounds`](Self::bounds) and
+/// [`quick_reject`](Self::quick_reject) don't depend on the stack depth or
+/// the shape of the clip.
 #[derive(Debug, Clone)]
 pub struct ClipStack {
-    /// Stack of saved clip states.
-    stack: Vec<ClipState>,
+    /// Stack of saved clip states and their cached bounds.
+    stack: Vec<(ClipState, Rect)>,
     /// Current clip state.
     current: ClipState,
+    /// Cached bounds of the current clip.
+    bounds: Rect,
 }
 
 impl ClipStack {
@@ -405,18 +498,20 @@ impl ClipStack {
         Self {
             stack: Vec::new(),
             current: ClipState::Rect(*device_bounds),
+            bounds: *device_bounds,
         }
     }
 
     /// Save the current clip state.
     pub fn save(&mut self) {
-        self.stack.push(self.current.clone());
+        self.stack.push((self.current.clone(), self.bounds));
     }
 
     /// Restore the previous clip state.
     pub fn restore(&mut self) {
-        if let Some(state) = self.stack.pop() {
+        if let Some((state, bounds)) = self.stack.pop() {
             self.current = state;
+            self.bounds = bounds;
         }
     }
 
@@ -438,8 +533,36 @@ impl ClipStack {
     }
 
     /// Get the current clip bounds.
+    ///
+    /// Every pixel with nonzero clip coverage lies inside these bounds.
+    #[inline]
     pub fn bounds(&self) -> Rect {
-        self.current.bounds()
+        self.bounds
+    }
+
+    /// Get the current clip bounds rounded out to whole device pixels.
+    #[inline]
+    pub fn device_clip_bounds(&self) -> IRect {
+        if self.bounds.is_empty() {
+            IRect::empty()
+        } else {
+            self.bounds.round_out()
+        }
+    }
+
+    /// Check whether a device-space rectangle lies entirely outside the clip.
+    #[inline]
+    pub fn quick_reject(&self, rect: &Rect) -> bool {
+        !rect.intersects(&self.bounds)
+    }
+
+    /// Narrow the cached bounds after a clip that kept nothing outside `limit`.
+    fn intersect_bounds(&mut self, limit: &Rect) {
+        self.bounds = self
+            .bounds
+            .intersect(limit)
+            .and_then(|bounds| bounds.intersect(&self.current.bounds()))
+            .unwrap_or(Rect::EMPTY);
     }
 
     /// Check if a point is inside the current clip.
@@ -455,6 +578,7 @@ impl ClipStack {
     /// Intersect the current clip with a rectangle.
     pub fn clip_rect(&mut self, rect: &Rect) {
         self.current.intersect_rect(rect);
+        self.intersect_bounds(rect);
     }
 
     /// Intersect the current clip with a rectangle (anti-aliased).
@@ -486,39 +610,48 @@ impl ClipStack {
                 m.intersect(&mask);
             }
         }
+        self.intersect_bounds(&rect.round_out().to_rect());
     }
 
     /// Intersect the current clip with a region.
     pub fn clip_region(&mut self, region: &Region) {
         self.current.intersect_region(region);
+        self.intersect_bounds(&region.bounds().to_rect());
+    }
+
+    /// Intersect the current clip with a coverage mask.
+    pub fn clip_mask(&mut self, mask: ClipMask) {
+        let mask_bounds = mask.bounds().to_rect();
+        match &mut self.current {
+            ClipState::Rect(r) => {
+                let mut new_mask = mask;
+                new_mask.clip_rect(&r.round_out());
+                self.current = ClipState::Mask(new_mask);
+            }
+            ClipState::Region(r) => {
+                self.current = ClipState::RegionAndMask(r.clone(), mask);
+            }
+            ClipState::Mask(m) => {
+                m.intersect(&mask);
+            }
+            ClipState::RegionAndMask(_, m) => {
+                m.intersect(&mask);
+            }
+        }
+        self.intersect_bounds(&mask_bounds);
     }
 
     /// Intersect the current clip with a path.
     pub fn clip_path(&mut self, path: &Path, device_bounds: &IRect, anti_alias: bool) {
         if anti_alias {
-            let mask = ClipMask::from_path_aa(path, device_bounds);
-            match &mut self.current {
-                ClipState::Rect(r) => {
-                    let mut new_mask = mask;
-                    new_mask.clip_rect(&r.round_out());
-                    self.current = ClipState::Mask(new_mask);
-                }
-                ClipState::Region(r) => {
-                    self.current = ClipState::RegionAndMask(r.clone(), mask);
-                }
-                ClipState::Mask(m) => {
-                    m.intersect(&mask);
-                }
-                ClipState::RegionAndMask(_, m) => {
-                    m.intersect(&mask);
-                }
-            }
+            self.clip_mask(ClipMask::from_path_aa(path, device_bounds));
         } else {
             // Non-AA path clip - convert path bounds to region
             let bounds = path.bounds();
             let region = Region::from_rect_f(&bounds);
             self.current.intersect_region(&region);
         }
+        self.intersect_bounds(&path.bounds().round_out().to_rect());
     }
 
     /// Check if the current clip is anti-aliased.
@@ -530,6 +663,7 @@ impl ClipStack {
     pub fn reset(&mut self, device_bounds: &Rect) {
         self.stack.clear();
         self.current = ClipState::Rect(*device_bounds);
+        self.bounds = *device_bounds;
     }
 }
 
@@ -555,7 +689,7 @@ mod tests {
         assert_eq!(mask.get_coverage(50, 50), 255);
 
         // Fully outside should be 0
-        assert_eq!(mask.get_coverage(0, 0), 0);
+        assert_eq!(mask.get_coverage(39, 0), 0);
         assert_eq!(mask.get_coverage(99, 99), 0);
 
         // Edge pixels should have partial coverage
@@ -563,6 +697,41 @@ mod tests {
         assert!(edge_coverage > 0 && edge_coverage < 255);
     }
 
+    #[test]
+    fn test_clip_mask_from_path_bounded() {
+        // An open triangle, which the mask closes like a fill.
+        let mut builder = skia_rs_path::PathBuilder::new();
+        builder
+            .move_to(20.0, 10.0)
+            .line_to(60.0, 50.0)
+            .line_to(20.0, 50.0);
+        let path = builder.build();
+        let device = IRect::new(0, 0, 1000, 1000);
+
+        for mask in [
+            ClipMask::from_path(&path, &device),
+            ClipMask::from_path_aa(&path, &device),
+        ] {
+            // Only the path's bounds are stored.
+            assert_eq!(mask.bounds(), IRect::new(20, 10, 60, 50));
+            assert_eq!(mask.get_coverage_device(25, 45), 255);
+            assert_eq!(mask.get_coverage_device(55, 15), 0);
+            assert_eq!(mask.get_coverage_device(500, 500), 0);
+
+            let inverse = mask.inverted(&device);
+            assert_eq!(inverse.get_coverage_device(25, 45), 0);
+            assert_eq!(inverse.get_coverage_device(500, 500), 255);
+        }
+
+        // Clones share their coverage until one is written.
+        let mask = ClipMask::from_path_aa(&path, &device);
+        let mut copy = mask.clone();
+        assert!(Arc::ptr_eq(&mask.coverage, &copy.coverage));
+        copy.set_coverage(39, 0, 7);
+        assert!(!Arc::ptr_eq(&mask.coverage, &copy.coverage));
+        assert_eq!(mask.get_coverage(39, 0), 0);
+    }
+
     #[test]
     fn test_clip_state_rect() {
         let state = ClipState::from_rect(Rect::new(10.0, 10.0, 90.0, 90.0));
@@ -645,6 +814,58 @@ mod tests {
         assert!(!stack.contains(25, 75)); // Bottom-left (not in region)
     }
 
+    #[test]
+    fn test_clip_stack_cached_bounds() {
+        let device = IRect::new(0, 0, 200, 200);
+        let mut stack = ClipStack::new(&device.to_rect());
+
+        // Alternate every kind of clip, checking the cached bounds against
+        // the intersection of everything applied so far.
+        let mut expected = vec![device.to_rect()];
+        for i in 0..40 {
+            let rect = Rect::new(
+                i as Scalar,
+                (i * 2 % 7) as Scalar,
+                (200 - i * 3 % 11) as Scalar,
+                (200 - i) as Scalar,
+            );
+            stack.save();
+            match i % 4 {
+                0 => stack.clip_rect(&rect),
+                1 => stack.clip_region(&Region::from_rect_f(&rect)),
+                2 => stack.clip_rect_aa(&rect, &device),
+                _ => {
+                    let mut builder = skia_rs_path::PathBuilder::new();
+                    builder.add_rect(&rect);
+                    stack.clip_path(&builder.build(), &device, i % 8 == 3);
+                }
+            }
+            let brute_force = expected
+                .iter()
+                .fold(rect, |acc, r| acc.intersect(r).unwrap());
+            assert_eq!(stack.bounds(), brute_force, "level {}", i + 1);
+            assert_eq!(stack.device_clip_bounds(), brute_force.round_out());
+            expected.push(brute_force);
+        }
+
+        let innermost = *expected.last().unwrap();
+        assert!(!stack.quick_reject(&innermost));
+        assert!(stack.quick_reject(&Rect::new(0.0, 0.0, innermost.left, 200.0)));
+
+        // Restoring pops back through the same bounds.
+        while stack.save_count() > 0 {
+            expected.pop();
+            stack.restore();
+            assert_eq!(stack.bounds(), *expected.last().unwrap());
+        }
+
+        // A disjoint clip empties the bounds and rejects everything.
+        stack.clip_rect(&Rect::new(0.0, 0.0, 10.0, 10.0));
+        stack.clip_rect(&Rect::new(20.0, 20.0, 30.0, 30.0));
+        assert!(stack.device_clip_bounds().is_empty());
+        assert!(stack.quick_reject(&Rect::new(0.0, 0.0, 200.0, 200.0)));
+    }
+
     #[test]
     fn test_compute_rect_coverage() {
         // Fully inside
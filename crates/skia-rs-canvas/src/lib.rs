@@ -8,10 +8,20 @@
 //! - SIMD-optimized blitting (SSE4.2, AVX2, NEON)
 //! - Advanced clipping (anti-aliased, region-based)
 //! - Save/restore layer stack
+//!
+//! ## `no_std`
+//!
+//! With default features off and `libm` on, [`PixelBuffer`] and
+//! [`Rasterizer`] work with only `alloc`. SIMD paths are then limited to the
+//! instruction sets enabled at compile time, and the `codec` and `text`
+//! features, which need `std`, are unavailable.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+extern crate alloc;
+
 pub mod canvas;
 pub mod clip;
 pub mod picture;
@@ -24,7 +34,7 @@ pub use clip::{ClipMask, ClipStack, ClipState};
 pub use picture::*;
 pub use raster::*;
 pub use simd::{SimdCapabilities, simd_capabilities};
-pub use surface::{RasterCanvas, Surface, VertexMode};
+pub use surface::{DrawStats, RasterCanvas, Surface, VertexMode};
 
 // Re-export Image for drawing
 #[cfg(feature = "codec")]
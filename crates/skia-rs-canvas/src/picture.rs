@@ -3,11 +3,14 @@
 //! Pictures are display lists that record drawing commands for later playback.
 //! This is useful for caching complex drawings, serialization, and deferred rendering.
 
-use crate::Canvas;
-use skia_rs_core::{Color, Matrix, Point, Rect, Scalar};
+use crate::{Canvas, RasterCanvas};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "codec")]
+use skia_rs_codec::Image;
+use skia_rs_core::{Color, Matrix, Point, RRect, Rect, Scalar};
 use skia_rs_paint::{BlendMode, Paint};
 use skia_rs_path::Path;
-use std::sync::Arc;
 
 /// A recorded picture that can be played back to a canvas.
 ///
@@ -36,15 +39,37 @@ impl Picture {
     }
 
     /// Play the picture back to a canvas.
+    ///
+    /// Recorded matrices are set relative to the canvas matrix at the start
+    /// of playback, as in Skia.
     pub fn playback(&self, canvas: &mut Canvas) {
+        let initial = *canvas.total_matrix();
         for command in &self.commands {
-            command.execute(canvas);
+            match command {
+                DrawCommand::SetMatrix { matrix } => canvas.set_matrix(&initial.concat(matrix)),
+                command => command.execute(canvas),
+            }
+        }
+    }
+
+    /// Play the picture back to a raster canvas, drawing its pixels.
+    ///
+    /// Recorded matrices are set relative to the canvas matrix at the start
+    /// of playback. See [`RasterCanvas::draw_picture`] to replay under an
+    /// extra matrix or paint.
+    pub fn playback_raster(&self, canvas: &mut RasterCanvas<'_>) {
+        let initial = *canvas.total_matrix();
+        for command in &self.commands {
+            match command {
+                DrawCommand::SetMatrix { matrix } => canvas.set_matrix(&initial.concat(matrix)),
+                command => command.execute_raster(canvas),
+            }
         }
     }
 
     /// Get the approximate byte size of this picture.
     pub fn approximate_bytes_used(&self) -> usize {
-        std::mem::size_of::<Self>() + self.commands.len() * std::mem::size_of::<DrawCommand>()
+        core::mem::size_of::<Self>() + self.commands.len() * core::mem::size_of::<DrawCommand>()
     }
 
     /// Get the number of operations in this picture.
@@ -195,6 +220,13 @@ pub enum DrawCommand {
         /// The paint to use.
         paint: Paint,
     },
+    /// Draw a rounded rectangle with per-corner radii.
+    DrawRRect {
+        /// The rounded rectangle.
+        rrect: RRect,
+        /// The paint to use.
+        paint: Paint,
+    },
     /// Draw a path.
     DrawPath {
         /// The path to draw.
@@ -202,6 +234,18 @@ pub enum DrawCommand {
         /// The paint to use.
         paint: Paint,
     },
+    /// Draw an image.
+    #[cfg(feature = "codec")]
+    DrawImage {
+        /// The image to draw.
+        image: Image,
+        /// Left edge of the image.
+        left: Scalar,
+        /// Top edge of the image.
+        top: Scalar,
+        /// Optional paint to apply.
+        paint: Option<Paint>,
+    },
     /// Draw another picture.
     DrawPicture {
         /// The picture to draw.
@@ -297,9 +341,17 @@ impl DrawCommand {
             } => {
                 canvas.draw_round_rect(rect, *rx, *ry, paint);
             }
+            DrawCommand::DrawRRect { rrect, paint } => {
+                let mut builder = skia_rs_path::PathBuilder::new();
+                builder.add_rrect(rrect);
+                canvas.draw_path(&builder.build(), paint);
+            }
             DrawCommand::DrawPath { path, paint } => {
                 canvas.draw_path(path, paint);
             }
+            // The abstract canvas has no pixels to draw images into.
+            #[cfg(feature = "codec")]
+            DrawCommand::DrawImage { .. } => {}
             DrawCommand::DrawPicture {
                 picture,
                 matrix,
@@ -316,6 +368,121 @@ impl DrawCommand {
             }
         }
     }
+
+    /// Execute this command on a raster canvas.
+    pub fn execute_raster(&self, canvas: &mut RasterCanvas<'_>) {
+        match self {
+            DrawCommand::Save => {
+                canvas.save();
+            }
+            DrawCommand::Restore => {
+                canvas.restore();
+            }
+            DrawCommand::SaveLayer { bounds, paint } => {
+                let rec = crate::SaveLayerRec {
+                    bounds: bounds.as_ref(),
+                    paint: paint.as_ref(),
+                    flags: crate::SaveLayerFlags::NONE,
+                };
+                canvas.save_layer(&rec);
+            }
+            DrawCommand::Translate { dx, dy } => {
+                canvas.translate(*dx, *dy);
+            }
+            DrawCommand::Scale { sx, sy } => {
+                canvas.scale(*sx, *sy);
+            }
+            DrawCommand::Rotate { degrees } => {
+                canvas.rotate(*degrees);
+            }
+            DrawCommand::Skew { sx, sy } => {
+                canvas.skew(*sx, *sy);
+            }
+            DrawCommand::Concat { matrix } => {
+                canvas.concat(matrix);
+            }
+            DrawCommand::SetMatrix { matrix } => {
+                canvas.set_matrix(matrix);
+            }
+            DrawCommand::ClipRect { rect, anti_alias } => {
+                // Without AA, a scaled or translated rect clips as device bounds.
+                if !*anti_alias && canvas.total_matrix().is_scale_translate() {
+                    canvas.clip_rect(rect);
+                } else {
+                    let mut builder = skia_rs_path::PathBuilder::new();
+                    builder.add_rect(rect);
+                    canvas.clip_path(&builder.build(), crate::ClipOp::Intersect, *anti_alias);
+                }
+            }
+            DrawCommand::ClipPath { path, anti_alias } => {
+                canvas.clip_path(path, crate::ClipOp::Intersect, *anti_alias);
+            }
+            DrawCommand::Clear { color } => {
+                canvas.clear(*color);
+            }
+            DrawCommand::DrawColor { color, blend_mode } => {
+                canvas.draw_color(*color, *blend_mode);
+            }
+            DrawCommand::DrawPoint { point, paint } => {
+                canvas.draw_point(*point, paint);
+            }
+            DrawCommand::DrawLine { p0, p1, paint } => {
+                canvas.draw_line(*p0, *p1, paint);
+            }
+            DrawCommand::DrawRect { rect, paint } => {
+                canvas.draw_rect(rect, paint);
+            }
+            DrawCommand::DrawOval { rect, paint } => {
+                canvas.draw_oval(rect, paint);
+            }
+            DrawCommand::DrawCircle {
+                center,
+                radius,
+                paint,
+            } => {
+                canvas.draw_circle(*center, *radius, paint);
+            }
+            DrawCommand::DrawArc {
+                oval,
+                start_angle,
+                sweep_angle,
+                use_center,
+                paint,
+            } => {
+                canvas.draw_arc(oval, *start_angle, *sweep_angle, *use_center, paint);
+            }
+            DrawCommand::DrawRoundRect {
+                rect,
+                rx,
+                ry,
+                paint,
+            } => {
+                canvas.draw_round_rect(rect, *rx, *ry, paint);
+            }
+            DrawCommand::DrawRRect { rrect, paint } => {
+                canvas.draw_rrect(rrect, paint);
+            }
+            DrawCommand::DrawPath { path, paint } => {
+                canvas.draw_path(path, paint);
+            }
+            #[cfg(feature = "codec")]
+            DrawCommand::DrawImage {
+                image,
+                left,
+                top,
+                paint,
+            } => {
+                canvas.draw_image(image, *left, *top, paint.as_ref());
+            }
+            DrawCommand::DrawPicture {
+                picture,
+                matrix,
+                paint,
+            } => {
+                canvas.draw_picture(picture, matrix.as_ref(), paint.as_ref());
+            }
+        }
+    }
 }
 
 /// A recorder that captures drawing commands into a Picture.
@@ -358,7 +525,7 @@ impl PictureRecorder {
             return None;
         }
         self.is_recording = false;
-        let commands = std::mem::take(&mut self.commands);
+        let commands = core::mem::take(&mut self.commands);
         Some(Arc::new(Picture::new(commands, self.cull_rect)))
     }
 
@@ -529,6 +696,14 @@ impl RecordingCanvas {
         });
     }
 
+    /// Record a draw rrect command.
+    pub fn draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {
+        self.inner.commands.push(DrawCommand::DrawRRect {
+            rrect: *rrect,
+            paint: paint.clone(),
+        });
+    }
+
     /// Record a draw path command.
     pub fn draw_path(&mut self, path: &Path, paint: &Paint) {
         self.inner.commands.push(DrawCommand::DrawPath {
@@ -537,6 +712,17 @@ impl RecordingCanvas {
         });
     }
 
+    /// Record a draw image command.
+    #[cfg(feature = "codec")]
+    pub fn draw_image(&mut self, image: &Image, left: Scalar, top: Scalar, paint: Option<&Paint>) {
+        self.inner.commands.push(DrawCommand::DrawImage {
+            image: image.clone(),
+            left,
+            top,
+            paint: paint.cloned(),
+        });
+    }
+
     /// Record a draw picture command.
     pub fn draw_picture(
         &mut self,
@@ -587,6 +773,83 @@ mod tests {
         assert!(!matrix.is_identity());
     }
 
+    #[test]
+    fn test_picture_raster_playback_translated() {
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::from_xywh(0.0, 0.0, 30.0, 30.0));
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 255, 0, 0));
+        canvas.draw_circle(Point::new(10.0, 10.0), 8.0, &paint);
+        paint.set_color32(Color::from_argb(255, 0, 0, 255));
+        canvas.draw_rect(&Rect::from_xywh(20.0, 0.0, 10.0, 10.0), &paint);
+        let picture = recorder.finish_recording().unwrap();
+
+        let mut buffer = crate::PixelBuffer::new(100, 100);
+        {
+            let mut canvas = RasterCanvas::new(&mut buffer);
+            canvas.clear(Color::WHITE);
+            for (dx, dy) in [(0.0, 0.0), (50.0, 60.0)] {
+                canvas.draw_picture(&picture, Some(&Matrix::translate(dx, dy)), None);
+            }
+            assert_eq!(canvas.save_count(), 1);
+            assert!(canvas.total_matrix().is_identity());
+        }
+
+        for (dx, dy) in [(0, 0), (50, 60)] {
+            let circle = buffer.get_pixel(10 + dx, 10 + dy).unwrap();
+            let rect = buffer.get_pixel(25 + dx, 5 + dy).unwrap();
+            assert_eq!(
+                circle,
+                Color::from_argb(255, 255, 0, 0),
+                "circle at +{dx},+{dy}"
+            );
+            assert_eq!(
+                rect,
+                Color::from_argb(255, 0, 0, 255),
+                "rect at +{dx},+{dy}"
+            );
+        }
+        assert_eq!(buffer.get_pixel(40, 40), Some(Color::WHITE));
+    }
+
+    #[test]
+    fn test_picture_raster_playback_matrix_and_clip() {
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 255, 0, 0));
+
+        // Set matrices are relative to the matrix the picture is drawn with.
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::from_xywh(0.0, 0.0, 30.0, 30.0));
+        canvas.set_matrix(&Matrix::translate(10.0, 0.0));
+        canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 10.0, 10.0), &paint);
+        let moved = recorder.finish_recording().unwrap();
+
+        // A rotated rect clip is a diamond, not its bounding box.
+        let canvas = recorder.begin_recording(Rect::from_xywh(0.0, 0.0, 100.0, 100.0));
+        canvas.translate(50.0, 50.0);
+        canvas.rotate(45.0);
+        canvas.clip_rect(&Rect::new(-10.0, -10.0, 10.0, 10.0), false);
+        canvas.draw_rect(&Rect::new(-50.0, -50.0, 50.0, 50.0), &paint);
+        let rotated = recorder.finish_recording().unwrap();
+
+        let red = Color::from_argb(255, 255, 0, 0);
+        let mut buffer = crate::PixelBuffer::new(100, 100);
+        {
+            let mut canvas = RasterCanvas::new(&mut buffer);
+            canvas.clear(Color::WHITE);
+            canvas.draw_picture(&moved, Some(&Matrix::translate(0.0, 80.0)), None);
+        }
+        assert_eq!(buffer.get_pixel(15, 85), Some(red));
+        assert_eq!(buffer.get_pixel(15, 5), Some(Color::WHITE));
+        {
+            let mut canvas = RasterCanvas::new(&mut buffer);
+            canvas.clear(Color::WHITE);
+            canvas.draw_picture(&rotated, None, None);
+        }
+        assert_eq!(buffer.get_pixel(50, 50), Some(red));
+        assert_eq!(buffer.get_pixel(59, 59), Some(Color::WHITE));
+    }
+
     #[test]
     fn test_nested_pictures() {
         // Create inner picture
@@ -23,27 +23,72 @@
 //! - **Region-based clip**: Complex clips composed of multiple rectangles
 //! - **Anti-aliased clip**: Smooth clip edges using coverage masks
 
-use skia_rs_core::{Color, IRect, Matrix, Point, Rect, Region, Scalar};
-use skia_rs_paint::{BlendMode, Paint, Style};
-use skia_rs_path::{FillType, Path, PathElement};
+use skia_rs_core::{Color, Color4f, ColorType, IRect, Matrix, Point, RRect, Rect, Region, Scalar};
+use skia_rs_paint::{BlendMode, BlurStyle, ColorFilter, Paint, Shader, Style};
+use skia_rs_path::{
+    FillType, Path, PathElement, StrokeCap, StrokeJoin, StrokeParams, conic_to_cubics,
+    stroke_to_fill,
+};
 
 use crate::clip::{ClipMask, ClipStack, ClipState};
+use crate::surface::VertexMode;
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 
 /// A pixel buffer for rasterization.
+///
+/// Pixels are stored *unpremultiplied*, and every `Color` passed to or
+/// returned from the buffer is unpremultiplied. Use [`Color::premultiply`]
+/// when handing pixels to code that expects premultiplied data.
+///
+/// The default layout is RGBA8888. [`PixelBuffer::with_color_type`] also
+/// supports the 16-bit `Rgb565` and `Argb4444` layouts for low-memory
+/// targets; writes to those are ordered-dithered to reduce banding.
 #[derive(Debug, Clone)]
 pub struct PixelBuffer {
     /// Width in pixels.
     pub width: i32,
     /// Height in pixels.
     pub height: i32,
-    /// RGBA pixel data (4 bytes per pixel).
+    /// Pixel data in the layout given by `color_type`.
     pub pixels: Vec<u8>,
     /// Row stride in bytes.
     pub stride: usize,
+    /// Layout of `pixels`: `Rgba8888`, `Rgb565` or `Argb4444`.
+    pub color_type: ColorType,
+}
+
+/// 4x4 Bayer matrix used for ordered dithering of 16-bit pixels.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Quantize an 8-bit channel to `bits` bits with a dither threshold in `0..16`.
+///
+/// Values that are exactly representable are returned unchanged for every
+/// threshold, so solid colors at full intensity never pick up noise.
+#[inline]
+fn quantize_dithered(value: u8, bits: u32, threshold: u8) -> u16 {
+    let levels = (1u32 << bits) - 1;
+    // floor(value * levels / 255 + (threshold + 0.5) / 16), in integers.
+    let scaled = u32::from(value) * levels * 32 + 255 * (2 * u32::from(threshold) + 1);
+    (scaled / (255 * 32)).min(levels) as u16
+}
+
+/// Expand a `bits`-bit channel back to 8 bits by bit replication.
+#[inline]
+fn expand_channel(value: u16, bits: u32) -> u8 {
+    let v = u32::from(value);
+    ((v << (8 - bits)) | (v >> (2 * bits - 8))) as u8
+}
+
+impl AsRef<[u8]> for PixelBuffer {
+    fn as_ref(&self) -> &[u8] {
+        &self.pixels
+    }
 }
 
 impl PixelBuffer {
-    /// Create a new pixel buffer.
+    /// Create a new RGBA8888 pixel buffer.
     pub fn new(width: i32, height: i32) -> Self {
         let stride = (width as usize) * 4;
         let pixels = vec![0u8; (height as usize) * stride];
@@ -52,7 +97,35 @@ impl PixelBuffer {
             height,
             pixels,
             stride,
+            color_type: ColorType::Rgba8888,
+        }
+    }
+
+    /// Create a pixel buffer with the given layout.
+    ///
+    /// Returns `None` for color types other than `Rgba8888`, `Rgb565` and
+    /// `Argb4444`.
+    pub fn with_color_type(width: i32, height: i32, color_type: ColorType) -> Option<Self> {
+        if !matches!(
+            color_type,
+            ColorType::Rgba8888 | ColorType::Rgb565 | ColorType::Argb4444
+        ) {
+            return None;
         }
+        let stride = (width as usize) * color_type.bytes_per_pixel();
+        Some(Self {
+            width,
+            height,
+            pixels: vec![0u8; (height as usize) * stride],
+            stride,
+            color_type,
+        })
+    }
+
+    /// Byte offset of the pixel at (x, y). The coordinates must be in bounds.
+    #[inline]
+    fn offset(&self, x: i32, y: i32) -> usize {
+        (y as usize) * self.stride + (x as usize) * self.color_type.bytes_per_pixel()
     }
 
     /// Clear the buffer with a color.
@@ -69,6 +142,16 @@ impl PixelBuffer {
             return;
         }
 
+        // 16-bit layouts are dithered per pixel.
+        if self.color_type != ColorType::Rgba8888 {
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    self.set_pixel(x, y, color);
+                }
+            }
+            return;
+        }
+
         // Create a 4-byte pattern and fill using chunks
         let pattern = [r, g, b, a];
         for chunk in self.pixels.chunks_exact_mut(4) {
@@ -76,19 +159,74 @@ impl PixelBuffer {
         }
     }
 
+    /// Fill a rectangle with a color, replacing the pixels without blending.
+    ///
+    /// The rectangle is clipped to the buffer.
+    pub fn erase(&mut self, color: Color, rect: &IRect) {
+        let Some(rect) = rect.intersect(&IRect::new(0, 0, self.width, self.height)) else {
+            return;
+        };
+
+        // 16-bit layouts are dithered per pixel.
+        if self.color_type != ColorType::Rgba8888 {
+            for y in rect.top..rect.bottom {
+                for x in rect.left..rect.right {
+                    self.set_pixel(x, y, color);
+                }
+            }
+            return;
+        }
+
+        let pattern = [color.red(), color.green(), color.blue(), color.alpha()];
+        for y in rect.top..rect.bottom {
+            let start = self.offset(rect.left, y);
+            let end = self.offset(rect.right, y);
+            for chunk in self.pixels[start..end].chunks_exact_mut(4) {
+                chunk.copy_from_slice(&pattern);
+            }
+        }
+    }
+
+    /// Check whether (x, y) lies inside the buffer.
+    #[inline]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
+    }
+
     /// Get a pixel at (x, y).
     #[inline]
     pub fn get_pixel(&self, x: i32, y: i32) -> Option<Color> {
         if x < 0 || x >= self.width || y < 0 || y >= self.height {
             return None;
         }
-        let offset = (y as usize) * self.stride + (x as usize) * 4;
-        Some(Color::from_argb(
-            self.pixels[offset + 3],
-            self.pixels[offset],
-            self.pixels[offset + 1],
-            self.pixels[offset + 2],
-        ))
+        let offset = self.offset(x, y);
+        let packed = || u16::from_le_bytes([self.pixels[offset], self.pixels[offset + 1]]);
+        Some(match self.color_type {
+            ColorType::Rgb565 => {
+                let p = packed();
+                Color::from_argb(
+                    255,
+                    expand_channel(p >> 11, 5),
+                    expand_channel((p >> 5) & 0x3F, 6),
+                    expand_channel(p & 0x1F, 5),
+                )
+            }
+            ColorType::Argb4444 => {
+                let p = packed();
+                Color::from_argb(
+                    expand_channel(p & 0xF, 4),
+                    expand_channel(p >> 12, 4),
+                    expand_channel((p >> 8) & 0xF, 4),
+                    expand_channel((p >> 4) & 0xF, 4),
+                )
+            }
+            _ => Color::from_argb(
+                self.pixels[offset + 3],
+                self.pixels[offset],
+                self.pixels[offset + 1],
+                self.pixels[offset + 2],
+            ),
+        })
     }
 
     /// Set a pixel at (x, y).
@@ -97,14 +235,35 @@ impl PixelBuffer {
         if x < 0 || x >= self.width || y < 0 || y >= self.height {
             return;
         }
-        let offset = (y as usize) * self.stride + (x as usize) * 4;
-        self.pixels[offset] = color.red();
-        self.pixels[offset + 1] = color.green();
-        self.pixels[offset + 2] = color.blue();
-        self.pixels[offset + 3] = color.alpha();
+        let offset = self.offset(x, y);
+        let threshold = BAYER_4X4[(y & 3) as usize][(x & 3) as usize];
+        let packed = match self.color_type {
+            ColorType::Rgb565 => {
+                (quantize_dithered(color.red(), 5, threshold) << 11)
+                    | (quantize_dithered(color.green(), 6, threshold) << 5)
+                    | quantize_dithered(color.blue(), 5, threshold)
+            }
+            ColorType::Argb4444 => {
+                // Alpha is rounded rather than dithered to keep edges stable.
+                (quantize_dithered(color.red(), 4, threshold) << 12)
+                    | (quantize_dithered(color.green(), 4, threshold) << 8)
+                    | (quantize_dithered(color.blue(), 4, threshold) << 4)
+                    | quantize_dithered(color.alpha(), 4, 8)
+            }
+            _ => {
+                self.pixels[offset] = color.red();
+                self.pixels[offset + 1] = color.green();
+                self.pixels[offset + 2] = color.blue();
+                self.pixels[offset + 3] = color.alpha();
+                return;
+            }
+        };
+        self.pixels[offset..offset + 2].copy_from_slice(&packed.to_le_bytes());
     }
 
     /// Blend a pixel at (x, y) using the given blend mode.
+    ///
+    /// `src` is unpremultiplied, like the stored pixels.
     #[inline]
     pub fn blend_pixel(&mut self, x: i32, y: i32, src: Color, blend_mode: BlendMode) {
         if x < 0 || x >= self.width || y < 0 || y >= self.height {
@@ -161,123 +320,74 @@ impl PixelBuffer {
 }
 
 /// Blend two colors using a blend mode.
+///
+/// Both inputs and the result are unpremultiplied. Callers holding
+/// premultiplied colors must convert them with [`Color::unpremultiply`]
+/// first.
 fn blend_colors(src: Color, dst: Color, mode: BlendMode) -> Color {
-    let sa = src.alpha() as f32 / 255.0;
-    let sr = src.red() as f32 / 255.0;
-    let sg = src.green() as f32 / 255.0;
-    let sb = src.blue() as f32 / 255.0;
-
-    let da = dst.alpha() as f32 / 255.0;
-    let dr = dst.red() as f32 / 255.0;
-    let dg = dst.green() as f32 / 255.0;
-    let db = dst.blue() as f32 / 255.0;
-
-    let (ra, rr, rg, rb) = match mode {
-        BlendMode::Clear => (0.0, 0.0, 0.0, 0.0),
-        BlendMode::Src => (sa, sr, sg, sb),
-        BlendMode::Dst => (da, dr, dg, db),
-        BlendMode::SrcOver => {
-            let a = sa + da * (1.0 - sa);
-            if a > 0.0 {
-                let r = (sr * sa + dr * da * (1.0 - sa)) / a;
-                let g = (sg * sa + dg * da * (1.0 - sa)) / a;
-                let b = (sb * sa + db * da * (1.0 - sa)) / a;
-                (a, r, g, b)
-            } else {
-                (0.0, 0.0, 0.0, 0.0)
-            }
-        }
-        BlendMode::DstOver => {
-            let a = da + sa * (1.0 - da);
-            if a > 0.0 {
-                let r = (dr * da + sr * sa * (1.0 - da)) / a;
-                let g = (dg * da + sg * sa * (1.0 - da)) / a;
-                let b = (db * da + sb * sa * (1.0 - da)) / a;
-                (a, r, g, b)
-            } else {
-                (0.0, 0.0, 0.0, 0.0)
-            }
-        }
-        BlendMode::SrcIn => {
-            let a = sa * da;
-            (a, sr, sg, sb)
-        }
-        BlendMode::DstIn => {
-            let a = da * sa;
-            (a, dr, dg, db)
-        }
-        BlendMode::SrcOut => {
-            let a = sa * (1.0 - da);
-            (a, sr, sg, sb)
-        }
-        BlendMode::DstOut => {
-            let a = da * (1.0 - sa);
-            (a, dr, dg, db)
-        }
-        BlendMode::SrcATop => {
-            let a = da;
-            let r = sr * da + dr * (1.0 - sa);
-            let g = sg * da + dg * (1.0 - sa);
-            let b = sb * da + db * (1.0 - sa);
-            (a, r, g, b)
-        }
-        BlendMode::DstATop => {
-            let a = sa;
-            let r = dr * sa + sr * (1.0 - da);
-            let g = dg * sa + sg * (1.0 - da);
-            let b = db * sa + sb * (1.0 - da);
-            (a, r, g, b)
-        }
-        BlendMode::Xor => {
-            let a = sa + da - 2.0 * sa * da;
-            let r = sr * (1.0 - da) + dr * (1.0 - sa);
-            let g = sg * (1.0 - da) + dg * (1.0 - sa);
-            let b = sb * (1.0 - da) + db * (1.0 - sa);
-            (a, r, g, b)
-        }
-        BlendMode::Plus => {
-            let a = (sa + da).min(1.0);
-            let r = (sr + dr).min(1.0);
-            let g = (sg + dg).min(1.0);
-            let b = (sb + db).min(1.0);
-            (a, r, g, b)
-        }
-        BlendMode::Multiply => {
-            let a = sa + da - sa * da;
-            let r = sr * dr;
-            let g = sg * dg;
-            let b = sb * db;
-            (a, r, g, b)
-        }
-        BlendMode::Screen => {
-            let a = sa + da - sa * da;
-            let r = sr + dr - sr * dr;
-            let g = sg + dg - sg * dg;
-            let b = sb + db - sb * db;
-            (a, r, g, b)
-        }
-        _ => {
-            // Default to SrcOver for unimplemented modes
-            let a = sa + da * (1.0 - sa);
-            if a > 0.0 {
-                let r = (sr * sa + dr * da * (1.0 - sa)) / a;
-                let g = (sg * sa + dg * da * (1.0 - sa)) / a;
-                let b = (sb * sa + db * da * (1.0 - sa)) / a;
-                (a, r, g, b)
-            } else {
-                (0.0, 0.0, 0.0, 0.0)
-            }
-        }
+    let unit = |c: Color| {
+        Color4f::new(
+            c.red() as f32 / 255.0,
+            c.green() as f32 / 255.0,
+            c.blue() as f32 / 255.0,
+            c.alpha() as f32 / 255.0,
+        )
     };
+    let out = mode.blend(unit(src), unit(dst));
 
     Color::from_argb(
-        (ra * 255.0).clamp(0.0, 255.0) as u8,
-        (rr * 255.0).clamp(0.0, 255.0) as u8,
-        (rg * 255.0).clamp(0.0, 255.0) as u8,
-        (rb * 255.0).clamp(0.0, 255.0) as u8,
+        (out.a * 255.0).clamp(0.0, 255.0) as u8,
+        (out.r * 255.0).clamp(0.0, 255.0) as u8,
+        (out.g * 255.0).clamp(0.0, 255.0) as u8,
+        (out.b * 255.0).clamp(0.0, 255.0) as u8,
     )
 }
 
+/// Largest blur sigma honored, as in Skia's `SkBlurMask`. Wider blurs look
+/// the same at any practical size but would need huge kernels and masks.
+const MAX_BLUR_SIGMA: Scalar = 532.0;
+
+/// Blur a `width` x `height` coverage mask in place with a Gaussian.
+///
+/// The kernel is separable, so rows and then columns are convolved with a
+/// normalized 1D kernel reaching `3 * sigma` pixels each way. Coverage
+/// outside the mask is treated as zero.
+fn gaussian_blur(mask: &mut [f32], width: usize, height: usize, sigma: Scalar) {
+    let radius = (3.0 * sigma).ceil() as usize;
+    if radius == 0 || width == 0 || height == 0 {
+        return;
+    }
+    let mut kernel: Vec<f32> = (0..=2 * radius)
+        .map(|i| {
+            let d = i as f32 - radius as f32;
+            (-d * d / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|k| *k /= sum);
+
+    let convolve = |src: &[f32], dst: &mut [f32], stride: usize, len: usize| {
+        for i in 0..len {
+            let lo = i.saturating_sub(radius);
+            let hi = (i + radius).min(len - 1);
+            dst[i * stride] = (lo..=hi).map(|j| src[j] * kernel[j + radius - i]).sum();
+        }
+    };
+
+    let mut line = vec![0.0; width.max(height)];
+    for y in 0..height {
+        let row = &mut mask[y * width..(y + 1) * width];
+        line[..width].copy_from_slice(row);
+        convolve(&line[..width], row, 1, width);
+    }
+    for x in 0..width {
+        for y in 0..height {
+            line[y] = mask[y * width + x];
+        }
+        convolve(&line[..height], &mut mask[x..], width, height);
+    }
+}
+
 /// Apply coverage to a color by scaling the alpha.
 #[inline]
 fn apply_coverage(color: Color, coverage: u8) -> Color {
@@ -299,6 +409,8 @@ pub struct Rasterizer<'a> {
     /// Whether to use the advanced clip stack.
     use_advanced_clip: bool,
     matrix: Matrix,
+    /// Pixel writes issued so far.
+    pixels_written: u64,
 }
 
 impl<'a> Rasterizer<'a> {
@@ -312,6 +424,46 @@ impl<'a> Rasterizer<'a> {
             clip_stack,
             use_advanced_clip: false,
             matrix: Matrix::IDENTITY,
+            pixels_written: 0,
+        }
+    }
+
+    /// Get the number of pixel writes issued by this rasterizer.
+    ///
+    /// Pixels covered by several draws are counted once per draw, so this
+    /// measures overdraw rather than the area of the buffer that changed.
+    #[inline]
+    pub fn pixels_written(&self) -> u64 {
+        self.pixels_written
+    }
+
+    /// Blend a pixel into the buffer, counting the write.
+    #[inline]
+    fn blend_pixel(&mut self, x: i32, y: i32, color: Color, blend_mode: BlendMode) {
+        if self.buffer.contains(x, y) {
+            self.pixels_written += 1;
+            self.buffer.blend_pixel(x, y, color, blend_mode);
+        }
+    }
+
+    /// Blend a pixel with coverage into the buffer, counting the write.
+    ///
+    /// The coverage is scaled by the clip coverage at `(x, y)`, so
+    /// anti-aliased clip edges blend smoothly with anti-aliased geometry.
+    #[inline]
+    fn blend_pixel_aa(
+        &mut self,
+        x: i32,
+        y: i32,
+        color: Color,
+        coverage: f32,
+        blend_mode: BlendMode,
+    ) {
+        let coverage = coverage * (self.get_clip_coverage(x, y) as f32 / 255.0);
+        if coverage > 0.0 && self.buffer.contains(x, y) {
+            self.pixels_written += 1;
+            self.buffer
+                .blend_pixel_aa(x, y, color, coverage, blend_mode);
         }
     }
 
@@ -345,9 +497,13 @@ impl<'a> Rasterizer<'a> {
     /// Clip to a region.
     ///
     /// This enables advanced clipping mode with support for complex
-    /// multi-rectangle clip areas.
+    /// multi-rectangle clip areas. A simple clip set with
+    /// [`set_clip`](Self::set_clip) is kept and intersected with the region.
     pub fn clip_region(&mut self, region: &Region) {
-        self.use_advanced_clip = true;
+        if !self.use_advanced_clip {
+            self.use_advanced_clip = true;
+            self.clip_stack.clip_rect(&self.clip);
+        }
         self.clip_stack.clip_region(region);
     }
 
@@ -361,6 +517,18 @@ impl<'a> Rasterizer<'a> {
         self.clip_stack.clip_path(path, &device_bounds, anti_alias);
     }
 
+    /// Clip to a device-space coverage mask.
+    ///
+    /// A simple clip set with [`set_clip`](Self::set_clip) is kept and
+    /// intersected with the mask.
+    pub fn clip_mask(&mut self, mask: ClipMask) {
+        if !self.use_advanced_clip {
+            self.use_advanced_clip = true;
+            self.clip_stack.clip_rect(&self.clip);
+        }
+        self.clip_stack.clip_mask(mask);
+    }
+
     /// Clip to a rectangle with optional anti-aliasing.
     pub fn clip_rect_aa(&mut self, rect: &Rect, anti_alias: bool) {
         self.use_advanced_clip = true;
@@ -430,9 +598,9 @@ impl<'a> Rasterizer<'a> {
 
         let coverage = self.get_clip_coverage(x, y);
         if coverage > 0 {
-            let color = paint.color32();
+            let color = source_color(paint);
             if coverage == 255 {
-                self.buffer.blend_pixel(x, y, color, paint.blend_mode());
+                self.blend_pixel(x, y, color, paint.blend_mode());
             } else {
                 // Apply clip coverage to pixel alpha
                 let adjusted_color = Color::from_argb(
@@ -441,8 +609,7 @@ impl<'a> Rasterizer<'a> {
                     color.green(),
                     color.blue(),
                 );
-                self.buffer
-                    .blend_pixel(x, y, adjusted_color, paint.blend_mode());
+                self.blend_pixel(x, y, adjusted_color, paint.blend_mode());
             }
         }
     }
@@ -472,17 +639,17 @@ impl<'a> Rasterizer<'a> {
         let sy = if y0 < y1 { 1 } else { -1 };
         let mut err = dx + dy;
 
-        let color = paint.color32();
+        let color = source_color(paint);
         let blend_mode = paint.blend_mode();
 
         loop {
             let coverage = self.get_clip_coverage(x0, y0);
             if coverage > 0 {
                 if coverage == 255 {
-                    self.buffer.blend_pixel(x0, y0, color, blend_mode);
+                    self.blend_pixel(x0, y0, color, blend_mode);
                 } else {
                     let adjusted = apply_coverage(color, coverage);
-                    self.buffer.blend_pixel(x0, y0, adjusted, blend_mode);
+                    self.blend_pixel(x0, y0, adjusted, blend_mode);
                 }
             }
 
@@ -506,25 +673,26 @@ impl<'a> Rasterizer<'a> {
     fn draw_line_aa(&mut self, p0: Point, p1: Point, paint: &Paint) {
         let t0 = self.matrix.map_point(p0);
         let t1 = self.matrix.map_point(p1);
+        self.draw_device_line_aa(t0, t1, source_color(paint), paint.blend_mode());
+    }
 
-        let mut x0 = t0.x;
-        let mut y0 = t0.y;
-        let mut x1 = t1.x;
-        let mut y1 = t1.y;
-
-        let color = paint.color32();
-        let blend_mode = paint.blend_mode();
+    /// Draw an anti-aliased line between points already in device space.
+    fn draw_device_line_aa(&mut self, p0: Point, p1: Point, color: Color, blend_mode: BlendMode) {
+        let mut x0 = p0.x;
+        let mut y0 = p0.y;
+        let mut x1 = p1.x;
+        let mut y1 = p1.y;
 
         let steep = (y1 - y0).abs() > (x1 - x0).abs();
 
         if steep {
-            std::mem::swap(&mut x0, &mut y0);
-            std::mem::swap(&mut x1, &mut y1);
+            core::mem::swap(&mut x0, &mut y0);
+            core::mem::swap(&mut x1, &mut y1);
         }
 
         if x0 > x1 {
-            std::mem::swap(&mut x0, &mut x1);
-            std::mem::swap(&mut y0, &mut y1);
+            core::mem::swap(&mut x0, &mut x1);
+            core::mem::swap(&mut y0, &mut y1);
         }
 
         let dx = x1 - x0;
@@ -581,16 +749,125 @@ impl<'a> Rasterizer<'a> {
         }
     }
 
+    /// Draw a shape through the paint's blur mask filter, if it has one.
+    ///
+    /// `draw` renders the shape, whose local `bounds` exclude any stroke, with
+    /// an opaque white paint into a coverage mask. The mask is blurred, shaped
+    /// by the [`BlurStyle`], and composited with the paint's color or shader
+    /// under the current clip. Returns `false`, drawing nothing, when the
+    /// paint has no blur mask filter or its sigma isn't finite and positive.
+    /// Sigma is clamped to [`MAX_BLUR_SIGMA`].
+    fn draw_blurred(
+        &mut self,
+        bounds: &Rect,
+        paint: &Paint,
+        draw: impl FnOnce(&mut Rasterizer<'_>, &Paint),
+    ) -> bool {
+        let Some((style, sigma)) = paint.mask_filter().and_then(|filter| filter.as_blur()) else {
+            return false;
+        };
+        if !sigma.is_finite() || sigma <= 0.0 {
+            return false;
+        }
+        let sigma = sigma.min(MAX_BLUR_SIGMA);
+        let radius = (3.0 * sigma).ceil() as i32;
+
+        // Strokes reach past the geometry by at most half the width times
+        // the miter limit.
+        let outset = match paint.style() {
+            Style::Fill => 0.0,
+            Style::Stroke | Style::StrokeAndFill => {
+                paint.stroke_width().max(1.0) * paint.stroke_miter().max(1.0) / 2.0
+            }
+        };
+        let shape = self
+            .matrix
+            .map_rect(&bounds.inset(-outset, -outset))
+            .round_out();
+        let Some(visible) = self
+            .clip_bounds()
+            .round_out()
+            .intersect(&self.device_bounds())
+        else {
+            return true;
+        };
+        // The mask holds the coverage within blur reach of the visible area,
+        // and the visible pixels within blur reach of the shape. Sizing it by
+        // the radius alone would make wide blurs allocate huge masks.
+        let Some(source) = shape.intersect(&visible.inset(-radius, -radius)) else {
+            return true;
+        };
+        let area = visible
+            .intersect(&shape.inset(-radius, -radius))
+            .map_or(source, |target| target.union(&source));
+
+        let mut mask = PixelBuffer::new(area.width(), area.height());
+        let mut coverage_paint = paint.clone();
+        coverage_paint
+            .set_mask_filter(None)
+            .set_shader(None)
+            .set_color_filter(None)
+            .set_color32(Color::WHITE)
+            .set_blend_mode(BlendMode::SrcOver);
+        {
+            let mut rasterizer = Rasterizer::new(&mut mask);
+            let to_mask = Matrix::translate(-area.left as Scalar, -area.top as Scalar);
+            rasterizer.set_matrix(&to_mask.concat(&self.matrix));
+            draw(&mut rasterizer, &coverage_paint);
+        }
+
+        let width = area.width() as usize;
+        let height = area.height() as usize;
+        let shape: Vec<f32> = mask
+            .pixels
+            .chunks_exact(4)
+            .map(|pixel| pixel[3] as f32 / 255.0)
+            .collect();
+        let mut blurred = shape.clone();
+        gaussian_blur(&mut blurred, width, height, sigma);
+
+        let color = source_color(paint);
+        let blend_mode = paint.blend_mode();
+        let shading = self.shading(paint);
+        for (i, (&blur, &src)) in blurred.iter().zip(&shape).enumerate() {
+            let coverage = match style {
+                BlurStyle::Normal => blur,
+                BlurStyle::Solid => blur.max(src),
+                BlurStyle::Outer => blur * (1.0 - src),
+                BlurStyle::Inner => blur * src,
+            };
+            // Quantize like an 8-bit mask, so rounding in the blur sums
+            // cannot leave fully covered pixels slightly translucent.
+            let coverage = (coverage * 255.0).round() / 255.0;
+            if coverage <= 0.0 {
+                continue;
+            }
+            let x = area.left + (i % width) as i32;
+            let y = area.top + (i / width) as i32;
+            let color = shading.as_ref().map_or(color, |s| s.color_at(x, y));
+            self.plot_aa(x, y, coverage.min(1.0), color, blend_mode);
+        }
+        true
+    }
+
+    /// Prepare the paint's shader for sampling at device pixels, if it has one.
+    fn shading<'p>(&self, paint: &'p Paint) -> Option<Shading<'p>> {
+        let shader = paint.shader()?;
+        let to_shader = match shader.local_matrix() {
+            Some(local) => self.matrix.concat(local),
+            None => self.matrix,
+        };
+        Some(Shading {
+            shader: shader.as_ref(),
+            inverse: to_shader.invert().unwrap_or(Matrix::IDENTITY),
+            color_filter: paint.color_filter().map(|f| f.as_ref() as &dyn ColorFilter),
+        })
+    }
+
     /// Plot a pixel with coverage for anti-aliasing.
     #[inline]
     fn plot_aa(&mut self, x: i32, y: i32, coverage: f32, color: Color, blend_mode: BlendMode) {
-        let clip_coverage = self.get_clip_coverage(x, y);
-        if clip_coverage > 0 {
-            // Combine line AA coverage with clip coverage
-            let combined_coverage = coverage * (clip_coverage as f32 / 255.0);
-            self.buffer
-                .blend_pixel_aa(x, y, color, combined_coverage, blend_mode);
-        }
+        self.blend_pixel_aa(x, y, color, coverage, blend_mode);
     }
 
     /// Draw a horizontal line (fast path with SIMD optimization).
@@ -619,21 +896,26 @@ impl<'a> Rasterizer<'a> {
                 let coverage = self.get_clip_coverage(x, y);
                 if coverage > 0 {
                     if coverage == 255 {
-                        self.buffer.blend_pixel(x, y, color, blend_mode);
+                        self.blend_pixel(x, y, color, blend_mode);
                     } else {
                         let adjusted = apply_coverage(color, coverage);
-                        self.buffer.blend_pixel(x, y, adjusted, blend_mode);
+                        self.blend_pixel(x, y, adjusted, blend_mode);
                     }
                 }
             }
             return;
         }
 
-        // Validate bounds
-        if start < 0 || end >= self.buffer.width || y < 0 || y >= self.buffer.height {
+        // Validate bounds; 16-bit layouts go through the per-pixel path too.
+        if start < 0
+            || end >= self.buffer.width
+            || y < 0
+            || y >= self.buffer.height
+            || self.buffer.color_type != ColorType::Rgba8888
+        {
             // Fall back to per-pixel with bounds checking
             for x in start..=end {
-                self.buffer.blend_pixel(x, y, color, blend_mode);
+                self.blend_pixel(x, y, color, blend_mode);
             }
             return;
         }
@@ -642,15 +924,22 @@ impl<'a> Rasterizer<'a> {
         let start_offset = row_offset + (start as usize) * 4;
         let end_offset = row_offset + ((end + 1) as usize) * 4;
 
-        // SIMD-optimized path for SrcOver blend mode (most common case)
+        // SIMD-optimized path for SrcOver blend mode (most common case).
+        // The SIMD blitters use premultiplied math, which only matches the
+        // unpremultiplied buffer when the source or the whole destination
+        // span is opaque; anything else blends pixel by pixel below.
         if blend_mode == BlendMode::SrcOver {
-            crate::simd::fill_span_solid(&mut self.buffer.pixels[start_offset..end_offset], color);
-            return;
+            let span = &mut self.buffer.pixels[start_offset..end_offset];
+            if color.alpha() == 255 || span.chunks_exact(4).all(|px| px[3] == 255) {
+                self.pixels_written += (end - start + 1) as u64;
+                crate::simd::fill_span_solid(span, color.premultiply());
+                return;
+            }
         }
 
         // For other blend modes, use per-pixel blending
         for x in start..=end {
-            self.buffer.blend_pixel(x, y, color, blend_mode);
+            self.blend_pixel(x, y, color, blend_mode);
         }
     }
 
@@ -666,19 +955,16 @@ impl<'a> Rasterizer<'a> {
         let blend_mode = paint.blend_mode();
 
         // Check if we have a shader
-        if let Some(shader) = paint.shader() {
+        if let Some(shading) = self.shading(paint) {
             // Shader-based fill - sample each pixel
             for y in y0..y1 {
                 for x in x0..x1 {
-                    // Sample shader at pixel center
-                    let color4f = shader.sample(x as Scalar + 0.5, y as Scalar + 0.5);
-                    let color = color4f.to_color();
-                    self.buffer.blend_pixel(x, y, color, blend_mode);
+                    self.blend_pixel(x, y, shading.color_at(x, y), blend_mode);
                 }
             }
         } else {
             // Solid color fill (fast path)
-            let color = paint.color32();
+            let color = source_color(paint);
             for y in y0..y1 {
                 self.draw_hline(x0, x1 - 1, y, color, blend_mode);
             }
@@ -700,6 +986,9 @@ impl<'a> Rasterizer<'a> {
 
     /// Draw a rectangle (filled or stroked based on paint style).
     pub fn draw_rect(&mut self, rect: &Rect, paint: &Paint) {
+        if self.draw_blurred(rect, paint, |r, p| r.draw_rect(rect, p)) {
+            return;
+        }
         match paint.style() {
             Style::Fill => self.fill_rect(rect, paint),
             Style::Stroke => self.stroke_rect(rect, paint),
@@ -717,8 +1006,17 @@ impl<'a> Rasterizer<'a> {
         let cy = tc.y.round() as i32;
         let r = (radius * self.matrix.scale_x().abs()).round() as i32;
 
-        let color = paint.color32();
+        let color = source_color(paint);
         let blend_mode = paint.blend_mode();
+        let shading = self.shading(paint);
+        let mut span = |x0: i32, x1: i32, y: i32| match &shading {
+            Some(shading) => {
+                for x in x0..=x1 {
+                    self.blend_pixel(x, y, shading.color_at(x, y), blend_mode);
+                }
+            }
+            None => self.draw_hline(x0, x1, y, color, blend_mode),
+        };
 
         let mut x = 0;
         let mut y = r;
@@ -726,10 +1024,10 @@ impl<'a> Rasterizer<'a> {
 
         while x <= y {
             // Draw horizontal lines for each octant
-            self.draw_hline(cx - x, cx + x, cy + y, color, blend_mode);
-            self.draw_hline(cx - x, cx + x, cy - y, color, blend_mode);
-            self.draw_hline(cx - y, cx + y, cy + x, color, blend_mode);
-            self.draw_hline(cx - y, cx + y, cy - x, color, blend_mode);
+            span(cx - x, cx + x, cy + y);
+            span(cx - x, cx + x, cy - y);
+            span(cx - y, cx + y, cy + x);
+            span(cx - y, cx + y, cy - x);
 
             x += 1;
             if d < 0 {
@@ -748,7 +1046,7 @@ impl<'a> Rasterizer<'a> {
         let cy = tc.y.round() as i32;
         let r = (radius * self.matrix.scale_x().abs()).round() as i32;
 
-        let color = paint.color32();
+        let color = source_color(paint);
         let blend_mode = paint.blend_mode();
 
         let mut x = 0;
@@ -757,14 +1055,14 @@ impl<'a> Rasterizer<'a> {
 
         while x <= y {
             // Plot pixels in all 8 octants
-            self.buffer.blend_pixel(cx + x, cy + y, color, blend_mode);
-            self.buffer.blend_pixel(cx - x, cy + y, color, blend_mode);
-            self.buffer.blend_pixel(cx + x, cy - y, color, blend_mode);
-            self.buffer.blend_pixel(cx - x, cy - y, color, blend_mode);
-            self.buffer.blend_pixel(cx + y, cy + x, color, blend_mode);
-            self.buffer.blend_pixel(cx - y, cy + x, color, blend_mode);
-            self.buffer.blend_pixel(cx + y, cy - x, color, blend_mode);
-            self.buffer.blend_pixel(cx - y, cy - x, color, blend_mode);
+            self.blend_pixel(cx + x, cy + y, color, blend_mode);
+            self.blend_pixel(cx - x, cy + y, color, blend_mode);
+            self.blend_pixel(cx + x, cy - y, color, blend_mode);
+            self.blend_pixel(cx - x, cy - y, color, blend_mode);
+            self.blend_pixel(cx + y, cy + x, color, blend_mode);
+            self.blend_pixel(cx - y, cy + x, color, blend_mode);
+            self.blend_pixel(cx + y, cy - x, color, blend_mode);
+            self.blend_pixel(cx - y, cy - x, color, blend_mode);
 
             x += 1;
             if d < 0 {
@@ -778,6 +1076,15 @@ impl<'a> Rasterizer<'a> {
 
     /// Draw a circle (filled or stroked based on paint style).
     pub fn draw_circle(&mut self, center: Point, radius: Scalar, paint: &Paint) {
+        let bounds = Rect::new(
+            center.x - radius,
+            center.y - radius,
+            center.x + radius,
+            center.y + radius,
+        );
+        if self.draw_blurred(&bounds, paint, |r, p| r.draw_circle(center, radius, p)) {
+            return;
+        }
         if paint.is_anti_alias() {
             self.draw_circle_aa(center, radius, paint);
         } else {
@@ -799,8 +1106,10 @@ impl<'a> Rasterizer<'a> {
         let cy = tc.y;
         let r = radius * self.matrix.scale_x().abs();
 
-        let color = paint.color32();
+        let color = source_color(paint);
         let blend_mode = paint.blend_mode();
+        let shading = self.shading(paint);
+        let color_at = |x: i32, y: i32| shading.as_ref().map_or(color, |s| s.color_at(x, y));
 
         // Calculate bounding box
         let min_x = (cx - r - 1.0).floor() as i32;
@@ -830,7 +1139,7 @@ impl<'a> Rasterizer<'a> {
                         };
 
                         if coverage > 0.0 {
-                            self.plot_aa(px, py, coverage, color, blend_mode);
+                            self.plot_aa(px, py, coverage, color_at(px, py), blend_mode);
                         }
                     }
                 }
@@ -865,7 +1174,7 @@ impl<'a> Rasterizer<'a> {
                         let coverage = outer_coverage - inner_coverage;
 
                         if coverage > 0.0 {
-                            self.plot_aa(px, py, coverage, color, blend_mode);
+                            self.plot_aa(px, py, coverage, color_at(px, py), blend_mode);
                         }
                     }
                 }
@@ -904,117 +1213,234 @@ impl<'a> Rasterizer<'a> {
         }
     }
 
+    /// Draw a rounded rectangle.
+    ///
+    /// Drawn as a path, so fills get anti-aliased corners when the paint
+    /// requests it and honor the paint's shader.
+    pub fn draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {
+        use skia_rs_path::PathBuilder;
+
+        let mut builder = PathBuilder::new();
+        builder.add_rrect(rrect);
+        self.draw_path(&builder.build(), paint);
+    }
+
     /// Draw a path.
+    ///
+    /// Fills are anti-aliased when the paint requests it.
     pub fn draw_path(&mut self, path: &Path, paint: &Paint) {
+        if self.draw_blurred(&path.bounds(), paint, |r, p| r.draw_path(path, p)) {
+            return;
+        }
         match paint.style() {
-            Style::Fill => self.fill_path(path, paint),
+            Style::Fill => self.fill_path_for_paint(path, paint),
             Style::Stroke => self.stroke_path(path, paint),
             Style::StrokeAndFill => {
-                self.fill_path(path, paint);
+                self.fill_path_for_paint(path, paint);
                 self.stroke_path(path, paint);
             }
         }
     }
 
+    /// Fill a path with one paint and stroke it with another.
+    ///
+    /// Both coverages are rendered first and each pixel is written once,
+    /// with the stroke composited over the fill, so the clip is applied in a
+    /// single pass and no background shows through where their anti-aliased
+    /// edges meet. The paints' own styles are ignored. Paints with mask
+    /// filters or blend modes other than source-over are drawn one after
+    /// the other instead.
+    pub fn draw_path_filled_and_stroked(&mut self, path: &Path, fill: &Paint, stroke: &Paint) {
+        let mut fill = fill.clone();
+        fill.set_style(Style::Fill);
+        let mut stroke = stroke.clone();
+        stroke.set_style(Style::Stroke);
+
+        let single_pass = [&fill, &stroke]
+            .iter()
+            .all(|paint| paint.mask_filter().is_none() && paint.blend_mode() == BlendMode::SrcOver);
+        if !single_pass {
+            self.draw_path(path, &fill);
+            self.draw_path(path, &stroke);
+            return;
+        }
+
+        // Strokes reach past the geometry by at most half the width times
+        // the miter limit; anti-aliasing adds a pixel.
+        let outset = stroke.stroke_width().max(1.0) * stroke.stroke_miter().max(1.0) / 2.0;
+        let shape = self
+            .matrix
+            .map_rect(&path.bounds().inset(-outset, -outset))
+            .round_out()
+            .inset(-1, -1);
+        let visible = self
+            .clip_bounds()
+            .round_out()
+            .intersect(&self.device_bounds());
+        let Some(area) = visible.and_then(|visible| shape.intersect(&visible)) else {
+            return;
+        };
+
+        let to_area =
+            Matrix::translate(-area.left as Scalar, -area.top as Scalar).concat(&self.matrix);
+        let coverage = |paint: &Paint| {
+            let mut mask = PixelBuffer::new(area.width(), area.height());
+            let mut coverage_paint = paint.clone();
+            coverage_paint
+                .set_shader(None)
+                .set_color_filter(None)
+                .set_color32(Color::WHITE);
+            let mut rasterizer = Rasterizer::new(&mut mask);
+            rasterizer.set_matrix(&to_area);
+            rasterizer.draw_path(path, &coverage_paint);
+            mask.pixels
+                .chunks_exact(4)
+                .map(|pixel| pixel[3] as f32 / 255.0)
+                .collect::<Vec<f32>>()
+        };
+        let fill_coverage = coverage(&fill);
+        let stroke_coverage = coverage(&stroke);
+
+        let fill_color = source_color(&fill);
+        let stroke_color = source_color(&stroke);
+        let fill_shading = self.shading(&fill);
+        let stroke_shading = self.shading(&stroke);
+        let width = area.width() as usize;
+        for (i, (&cf, &cs)) in fill_coverage.iter().zip(&stroke_coverage).enumerate() {
+            if cf <= 0.0 && cs <= 0.0 {
+                continue;
+            }
+            let x = area.left + (i % width) as i32;
+            let y = area.top + (i / width) as i32;
+            let f = fill_shading
+                .as_ref()
+                .map_or(fill_color, |s| s.color_at(x, y));
+            let s = stroke_shading
+                .as_ref()
+                .map_or(stroke_color, |s| s.color_at(x, y));
+
+            // Stroke over fill, in premultiplied form
+            let fa = f.alpha() as f32 / 255.0 * cf;
+            let sa = s.alpha() as f32 / 255.0 * cs;
+            let alpha = sa + fa * (1.0 - sa);
+            if alpha <= 0.0 {
+                continue;
+            }
+            let mix = |fc: u8, sc: u8| {
+                let premul = sc as f32 * sa + fc as f32 * fa * (1.0 - sa);
+                (premul / alpha).round().min(255.0) as u8
+            };
+            let color = Color::from_argb(
+                (alpha * 255.0).round() as u8,
+                mix(f.red(), s.red()),
+                mix(f.green(), s.green()),
+                mix(f.blue(), s.blue()),
+            );
+            self.plot_aa(x, y, 1.0, color, BlendMode::SrcOver);
+        }
+    }
+
+    /// Fill a path, choosing the anti-aliased rasterizer if the paint asks for it.
+    fn fill_path_for_paint(&mut self, path: &Path, paint: &Paint) {
+        if paint.is_anti_alias() {
+            self.fill_path_aa(path, paint);
+        } else {
+            self.fill_path(path, paint);
+        }
+    }
+
     /// Stroke a path.
+    ///
+    /// The paint's path effect, such as a dash, is applied first. The stroke is
+    /// then converted to an outline with [`stroke_to_fill`] using the paint's
+    /// width, cap, join and miter limit, and filled. A zero width draws a one
+    /// pixel hairline.
     fn stroke_path(&mut self, path: &Path, paint: &Paint) {
+        let effected;
+        let path = match paint.path_effect().and_then(|effect| effect.apply(path)) {
+            Some(result) => {
+                effected = result;
+                &effected
+            }
+            None => path,
+        };
+
+        if paint.stroke_width() <= 0.0 {
+            self.stroke_hairline(path, paint);
+            return;
+        }
+
+        let cap = match paint.stroke_cap() {
+            skia_rs_paint::StrokeCap::Butt => StrokeCap::Butt,
+            skia_rs_paint::StrokeCap::Round => StrokeCap::Round,
+            skia_rs_paint::StrokeCap::Square => StrokeCap::Square,
+        };
+        let join = match paint.stroke_join() {
+            skia_rs_paint::StrokeJoin::Miter => StrokeJoin::Miter,
+            skia_rs_paint::StrokeJoin::Round => StrokeJoin::Round,
+            skia_rs_paint::StrokeJoin::Bevel => StrokeJoin::Bevel,
+        };
+        let params = StrokeParams::new(paint.stroke_width())
+            .with_cap(cap)
+            .with_join(join)
+            .with_miter_limit(paint.stroke_miter());
+
+        if let Some(outline) = stroke_to_fill(path, &params) {
+            self.fill_path_for_paint(&outline, paint);
+        }
+    }
+
+    /// Stroke a path with anti-aliased lines one device pixel wide.
+    ///
+    /// The path is mapped to device space before curves are flattened, so
+    /// neither the line width nor the curve smoothness depend on the matrix.
+    fn stroke_hairline(&mut self, path: &Path, paint: &Paint) {
+        let color = source_color(paint);
+        let blend_mode = paint.blend_mode();
+        let matrix = self.matrix;
         let mut current = Point::zero();
         let mut contour_start = Point::zero();
 
         for element in path.iter() {
-            match element {
+            let points = match element {
                 PathElement::Move(p) => {
-                    current = p;
-                    contour_start = p;
-                }
-                PathElement::Line(p) => {
-                    self.draw_line(current, p, paint);
-                    current = p;
+                    current = matrix.map_point(p);
+                    contour_start = current;
+                    continue;
                 }
+                PathElement::Line(p) => vec![matrix.map_point(p)],
                 PathElement::Quad(ctrl, end) => {
-                    // Approximate with lines
-                    let steps = 16;
-                    for i in 1..=steps {
-                        let t = i as f32 / steps as f32;
-                        let mt = 1.0 - t;
-                        let p = Point::new(
-                            mt * mt * current.x + 2.0 * mt * t * ctrl.x + t * t * end.x,
-                            mt * mt * current.y + 2.0 * mt * t * ctrl.y + t * t * end.y,
-                        );
-                        self.draw_line(
-                            if i == 1 {
-                                current
-                            } else {
-                                let pt = (i - 1) as f32 / steps as f32;
-                                let pmt = 1.0 - pt;
-                                Point::new(
-                                    pmt * pmt * current.x
-                                        + 2.0 * pmt * pt * ctrl.x
-                                        + pt * pt * end.x,
-                                    pmt * pmt * current.y
-                                        + 2.0 * pmt * pt * ctrl.y
-                                        + pt * pt * end.y,
-                                )
-                            },
-                            p,
-                            paint,
-                        );
-                    }
-                    current = end;
-                }
-                PathElement::Conic(ctrl, end, _w) => {
-                    // Approximate as quad for simplicity
-                    let steps = 16;
-                    for i in 1..=steps {
-                        let t = i as f32 / steps as f32;
-                        let mt = 1.0 - t;
-                        let p = Point::new(
-                            mt * mt * current.x + 2.0 * mt * t * ctrl.x + t * t * end.x,
-                            mt * mt * current.y + 2.0 * mt * t * ctrl.y + t * t * end.y,
-                        );
-                        let prev_t = (i - 1) as f32 / steps as f32;
-                        let prev_mt = 1.0 - prev_t;
-                        let prev = Point::new(
-                            prev_mt * prev_mt * current.x
-                                + 2.0 * prev_mt * prev_t * ctrl.x
-                                + prev_t * prev_t * end.x,
-                            prev_mt * prev_mt * current.y
-                                + 2.0 * prev_mt * prev_t * ctrl.y
-                                + prev_t * prev_t * end.y,
-                        );
-                        self.draw_line(prev, p, paint);
-                    }
-                    current = end;
+                    // Raise to a cubic with the controls two thirds of the way
+                    let ctrl = matrix.map_point(ctrl);
+                    let end = matrix.map_point(end);
+                    let c1 = current.lerp(ctrl, 2.0 / 3.0);
+                    let c2 = end.lerp(ctrl, 2.0 / 3.0);
+                    flatten_cubic(current, c1, c2, end)
                 }
-                PathElement::Cubic(c1, c2, end) => {
-                    // Approximate with lines
-                    let steps = 24;
-                    let mut prev = current;
-                    for i in 1..=steps {
-                        let t = i as f32 / steps as f32;
-                        let mt = 1.0 - t;
-                        let mt2 = mt * mt;
-                        let t2 = t * t;
-                        let p = Point::new(
-                            mt2 * mt * current.x
-                                + 3.0 * mt2 * t * c1.x
-                                + 3.0 * mt * t2 * c2.x
-                                + t2 * t * end.x,
-                            mt2 * mt * current.y
-                                + 3.0 * mt2 * t * c1.y
-                                + 3.0 * mt * t2 * c2.y
-                                + t2 * t * end.y,
-                        );
-                        self.draw_line(prev, p, paint);
-                        prev = p;
+                PathElement::Conic(ctrl, end, w) => {
+                    let ctrl = matrix.map_point(ctrl);
+                    let end = matrix.map_point(end);
+                    let mut start = current;
+                    let mut points = Vec::new();
+                    for [c1, c2, p] in conic_to_cubics(current, ctrl, end, w) {
+                        points.extend(flatten_cubic(start, c1, c2, p));
+                        start = p;
                     }
-                    current = end;
+                    points
                 }
-                PathElement::Close => {
-                    if current != contour_start {
-                        self.draw_line(current, contour_start, paint);
-                    }
-                    current = contour_start;
+                PathElement::Cubic(c1, c2, end) => flatten_cubic(
+                    current,
+                    matrix.map_point(c1),
+                    matrix.map_point(c2),
+                    matrix.map_point(end),
+                ),
+                PathElement::Close => vec![contour_start],
+            };
+
+            for p in points {
+                if p != current {
+                    self.draw_device_line_aa(current, p, color, blend_mode);
+                    current = p;
                 }
             }
         }
@@ -1030,8 +1456,9 @@ impl<'a> Rasterizer<'a> {
     /// - Incremental x-intercept updates between scanlines
     fn fill_path(&mut self, path: &Path, paint: &Paint) {
         let fill_type = path.fill_type();
-        let color = paint.color32();
+        let color = source_color(paint);
         let blend_mode = paint.blend_mode();
+        let shading = self.shading(paint);
 
         // Collect edges from path
         let edges = collect_edges(path, &self.matrix);
@@ -1079,7 +1506,14 @@ impl<'a> Rasterizer<'a> {
             for (x0, x1) in spans {
                 let x_start = x0.round() as i32;
                 let x_end = x1.round() as i32;
-                if x_start < x_end {
+                if x_start >= x_end {
+                    continue;
+                }
+                if let Some(shading) = &shading {
+                    for x in x_start..x_end {
+                        self.plot_aa(x, y, 1.0, shading.color_at(x, y), blend_mode);
+                    }
+                } else {
                     self.draw_hline(x_start, x_end - 1, y, color, blend_mode);
                 }
             }
@@ -1091,11 +1525,13 @@ impl<'a> Rasterizer<'a> {
 
     /// Fill a path using anti-aliased rendering.
     ///
-    /// Uses supersampling for improved edge quality.
+    /// Uses supersampling for improved edge quality. If the paint has a
+    /// shader it is sampled at each covered pixel center.
     pub fn fill_path_aa(&mut self, path: &Path, paint: &Paint) {
         let fill_type = path.fill_type();
-        let color = paint.color32();
+        let color = source_color(paint);
         let blend_mode = paint.blend_mode();
+        let shading = self.shading(paint);
 
         // Collect edges from path
         let edges = collect_edges(path, &self.matrix);
@@ -1103,73 +1539,365 @@ impl<'a> Rasterizer<'a> {
             return;
         }
 
-        // Create Global Edge Table for initial scanline range
-        let get = GlobalEdgeTable::new(edges);
+        // Sorted by y_min, so each sample only takes the edges it newly reaches.
+        let mut get = GlobalEdgeTable::new(edges);
 
         let Some(y_start) = get.y_min() else {
             return;
         };
         let y_end = get.y_max();
 
-        let y_min = y_start.floor() as i32;
-        let y_max = y_end.ceil() as i32;
+        // Only rows and columns inside both the clip and the device can be touched.
+        let clip = self.clip_bounds();
+        let left = (clip.left.floor() as i32).max(0);
+        let right = (clip.right.ceil() as i32).min(self.buffer.width);
+        let top = (clip.top.floor() as i32).max(0);
+        let bottom = (clip.bottom.ceil() as i32).min(self.buffer.height);
+
+        let y_min = (y_start.floor() as i32).max(top);
+        let y_max = (y_end.ceil() as i32).min(bottom);
+        if left >= right || y_min >= y_max {
+            return;
+        }
 
         // 4x vertical supersampling
         const SAMPLES: usize = 4;
         let sample_offsets = [0.125f32, 0.375, 0.625, 0.875];
+        let weight = 1.0 / SAMPLES as f32;
+
+        // Scratch buffers are allocated once per fill and reused for every row.
+        let mut line = CoverageLine::new(left, right);
+        let mut aet = ActiveEdgeTable::new();
+        let mut spans = Vec::new();
+        let mut previous = None;
 
-        // Process each pixel row
         for y in y_min..y_max {
-            // Accumulate coverage for each pixel
-            let mut coverage_map: std::collections::HashMap<i32, f32> =
-                std::collections::HashMap::new();
+            line.clear();
 
             // Sample at multiple y positions within the pixel
             for &offset in &sample_offsets {
                 let scanline = y as f32 + offset;
 
-                // Re-create AET for each sample (simpler than tracking multiple)
-                let mut sample_aet = ActiveEdgeTable::new();
-                let edges = collect_edges(path, &self.matrix);
-                let mut sample_get = GlobalEdgeTable::new(edges);
+                // Samples only move down, so active edges are stepped along
+                // rather than rebuilt from the whole edge list.
+                if let Some(previous) = previous.replace(scanline) {
+                    aet.step_all_by(scanline - previous);
+                }
+                aet.add_edges(get.get_new_edges_at(scanline), scanline);
+                aet.remove_inactive(scanline);
+                if aet.is_empty() {
+                    continue;
+                }
 
-                sample_aet.add_edges(sample_get.get_new_edges_at(scanline), scanline);
+                aet.sort_by_x();
+                aet.get_spans_into(fill_type, &mut spans);
 
-                if sample_aet.is_empty() {
-                    continue;
+                for &(x0, x1) in &spans {
+                    line.accumulate(x0, x1, weight);
                 }
+            }
 
-                sample_aet.sort_by_x();
-                let spans = sample_aet.get_spans(fill_type);
+            // Render pixels with accumulated coverage
+            for (x, coverage) in line.iter() {
+                let color = match &shading {
+                    Some(shading) => shading.color_at(x, y),
+                    None => color,
+                };
+                self.plot_aa(x, y, coverage.min(1.0), color, blend_mode);
+            }
+        }
+    }
 
-                // Accumulate coverage
-                for (x0, x1) in spans {
-                    let x_start = x0.floor() as i32;
-                    let x_end = x1.ceil() as i32;
+    /// Draw a triangle mesh.
+    ///
+    /// Triangles are assembled from `positions` according to `mode`, in the
+    /// order given by `indices` if present. Per-vertex `colors` are
+    /// interpolated across each triangle in premultiplied space. If `paint`
+    /// has a shader, it is sampled at the interpolated `tex_coords`, or at the
+    /// interpolated positions when there are none, and modulated by the
+    /// vertex colors. `colors` and `tex_coords` with fewer entries than
+    /// `positions` are ignored, as are triangles with out-of-range indices.
+    ///
+    /// Pixels are sampled at their centers and edges shared by two triangles
+    /// are drawn once, so meshes blend without seams.
+    pub fn draw_vertices(
+        &mut self,
+        mode: VertexMode,
+        positions: &[Point],
+        colors: Option<&[Color]>,
+        tex_coords: Option<&[Point]>,
+        indices: Option<&[u16]>,
+        paint: &Paint,
+    ) {
+        let colors = colors.filter(|c| c.len() >= positions.len());
+        let tex_coords = tex_coords.filter(|t| t.len() >= positions.len());
+        let order: Vec<usize> = match indices {
+            Some(indices) => indices.iter().map(|&i| usize::from(i)).collect(),
+            None => (0..positions.len()).collect(),
+        };
 
-                    for x in x_start..x_end {
-                        // Calculate pixel coverage for this sample
-                        let pixel_left = x as f32;
-                        let pixel_right = (x + 1) as f32;
+        // Shader space from local space, for sampling at texture coordinates.
+        let shader = paint.shader().map(|shader| {
+            let inverse = shader
+                .local_matrix()
+                .and_then(Matrix::invert)
+                .unwrap_or(Matrix::IDENTITY);
+            (shader.as_ref(), inverse)
+        });
+        let paint_color = paint.color().premul();
+        let blend_mode = paint.blend_mode();
 
-                        let overlap_left = pixel_left.max(x0);
-                        let overlap_right = pixel_right.min(x1);
-                        let overlap = (overlap_right - overlap_left).max(0.0);
+        for [a, b, c] in triangle_indices(mode, &order) {
+            if a.max(b).max(c) >= positions.len() {
+                continue;
+            }
+            let vertex = |i: usize| MeshVertex {
+                device: self.matrix.map_point(positions[i]),
+                color: colors.map(|c| c[i].to_color4f().premul()),
+                local: tex_coords.map_or(positions[i], |t| t[i]),
+            };
+            let (va, vb, vc) = (vertex(a), vertex(b), vertex(c));
+
+            // Orient every triangle the same way so the edge ownership test
+            // below gives each shared edge to exactly one of its triangles.
+            let (vb, vc) = if edge_function(va.device, vb.device, vc.device) < 0.0 {
+                (vc, vb)
+            } else {
+                (vb, vc)
+            };
+            let area = edge_function(va.device, vb.device, vc.device);
+            if area <= 0.0 || !area.is_finite() {
+                continue;
+            }
 
-                        *coverage_map.entry(x).or_insert(0.0) += overlap / SAMPLES as f32;
+            let bounds = Rect::new(
+                va.device.x.min(vb.device.x).min(vc.device.x),
+                va.device.y.min(vb.device.y).min(vc.device.y),
+                va.device.x.max(vb.device.x).max(vc.device.x),
+                va.device.y.max(vb.device.y).max(vc.device.y),
+            );
+            let Some(bounds) = bounds.intersect(&self.clip_bounds()) else {
+                continue;
+            };
+            let Some(bounds) = bounds.round_out().intersect(&IRect::new(
+                0,
+                0,
+                self.buffer.width,
+                self.buffer.height,
+            )) else {
+                continue;
+            };
+
+            let edges = [
+                (vb.device, vc.device),
+                (vc.device, va.device),
+                (va.device, vb.device),
+            ];
+            for y in bounds.top..bounds.bottom {
+                for x in bounds.left..bounds.right {
+                    let p = Point::new(x as Scalar + 0.5, y as Scalar + 0.5);
+                    let mut weights = [0.0; 3];
+                    let inside = edges.iter().zip(&mut weights).all(|(&(from, to), w)| {
+                        *w = edge_function(from, to, p);
+                        *w > 0.0 || (*w == 0.0 && owns_edge(from, to))
+                    });
+                    if !inside {
+                        continue;
+                    }
+                    let [wa, wb, wc] = weights.map(|w| w / area);
+
+                    let vertex_color = va.color.zip(vb.color).zip(vc.color).map(|((a, b), c)| {
+                        Color4f::new(
+                            a.r * wa + b.r * wb + c.r * wc,
+                            a.g * wa + b.g * wb + c.g * wc,
+                            a.b * wa + b.b * wb + c.b * wc,
+                            a.a * wa + b.a * wb + c.a * wc,
+                        )
+                    });
+                    let shader_color = shader.map(|(shader, inverse)| {
+                        let local = Point::new(
+                            va.local.x * wa + vb.local.x * wb + vc.local.x * wc,
+                            va.local.y * wa + vb.local.y * wb + vc.local.y * wc,
+                        );
+                        let p = inverse.map_point(local);
+                        shader.sample(p.x, p.y).premul()
+                    });
+                    let color = match (vertex_color, shader_color) {
+                        (Some(v), Some(s)) => {
+                            Color4f::new(v.r * s.r, v.g * s.g, v.b * s.b, v.a * s.a)
+                        }
+                        (Some(color), None) | (None, Some(color)) => color,
+                        (None, None) => paint_color,
+                    };
+                    // Vertex and shader colors still take the paint's alpha.
+                    let color = if vertex_color.is_some() || shader_color.is_some() {
+                        let alpha = paint.alpha();
+                        Color4f::new(
+                            color.r * alpha,
+                            color.g * alpha,
+                            color.b * alpha,
+                            color.a * alpha,
+                        )
+                    } else {
+                        color
+                    };
+                    let color = filter_source(paint, color.unpremul().to_color());
+
+                    match self.get_clip_coverage(x, y) {
+                        0 => {}
+                        255 => self.blend_pixel(x, y, color, blend_mode),
+                        coverage => {
+                            self.blend_pixel(x, y, apply_coverage(color, coverage), blend_mode);
+                        }
                     }
                 }
             }
+        }
+    }
+}
 
-            // Render pixels with accumulated coverage
-            for (x, coverage) in coverage_map {
-                if coverage > 0.0 {
-                    self.buffer
-                        .blend_pixel_aa(x, y, color, coverage.min(1.0), blend_mode);
+/// A mesh vertex in device space, with its premultiplied color and the
+/// local point its shader is sampled at.
+#[derive(Clone, Copy)]
+struct MeshVertex {
+    device: Point,
+    color: Option<Color4f>,
+    local: Point,
+}
+
+/// Assemble `order` into triangles of vertex indices for `mode`.
+///
+/// Odd triangles of a strip are flipped so all triangles keep the winding
+/// of the first.
+fn triangle_indices(mode: VertexMode, order: &[usize]) -> Vec<[usize; 3]> {
+    match mode {
+        VertexMode::Triangles => order.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect(),
+        VertexMode::TriangleStrip => order
+            .windows(3)
+            .enumerate()
+            .map(|(i, t)| {
+                if i % 2 == 0 {
+                    [t[0], t[1], t[2]]
+                } else {
+                    [t[1], t[0], t[2]]
                 }
+            })
+            .collect(),
+        VertexMode::TriangleFan => match order.split_first() {
+            Some((&center, rest)) => rest.windows(2).map(|t| [center, t[0], t[1]]).collect(),
+            None => Vec::new(),
+        },
+    }
+}
+
+/// Twice the signed area of the triangle `(a, b, p)`; positive when `p` is
+/// on the inner side of the edge `a -> b` of a positively oriented triangle.
+#[inline]
+fn edge_function(a: Point, b: Point, p: Point) -> Scalar {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Whether pixel centers exactly on the edge `from -> to` belong to its
+/// triangle. A shared edge runs in opposite directions in two consistently
+/// oriented triangles, so exactly one of them owns it.
+#[inline]
+fn owns_edge(from: Point, to: Point) -> bool {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    dy < 0.0 || (dy == 0.0 && dx > 0.0)
+}
+
+/// A paint shader mapped from device space into its own coordinate space.
+///
+/// Shaders are defined in local coordinates, so each device pixel center is
+/// mapped back through the inverse of the canvas and shader local matrices.
+/// Samples pass through the paint's color filter, if it has one.
+struct Shading<'a> {
+    shader: &'a dyn Shader,
+    inverse: Matrix,
+    color_filter: Option<&'a dyn ColorFilter>,
+}
+
+impl Shading<'_> {
+    /// The shader color at the center of device pixel `(x, y)`.
+    #[inline]
+    fn color_at(&self, x: i32, y: i32) -> Color {
+        let p = self
+            .inverse
+            .map_point(Point::new(x as Scalar + 0.5, y as Scalar + 0.5));
+        let color = self.shader.sample(p.x, p.y);
+        match self.color_filter {
+            Some(filter) => filter.filter_color(color).to_color(),
+            None => color.to_color(),
+        }
+    }
+}
+
+/// The paint's solid color, passed through its color filter if it has one.
+fn source_color(paint: &Paint) -> Color {
+    match paint.color_filter() {
+        Some(filter) => filter.filter_color(paint.color()).to_color(),
+        None => paint.color32(),
+    }
+}
+
+/// Pass a source color through the paint's color filter, if it has one.
+pub(crate) fn filter_source(paint: &Paint, color: Color) -> Color {
+    match paint.color_filter() {
+        Some(filter) => filter.filter_color(color.to_color4f()).to_color(),
+        None => color,
+    }
+}
+
+/// A reusable row of per-pixel coverage values for anti-aliased fills.
+///
+/// The row spans a fixed horizontal range (normally the clip bounds) and is
+/// cleared between scanlines, so accumulating coverage never allocates.
+struct CoverageLine {
+    /// Device x coordinate of the first entry.
+    left: i32,
+    /// Accumulated coverage, one entry per pixel.
+    coverage: Vec<f32>,
+}
+
+impl CoverageLine {
+    /// Create a zeroed line covering `left..right`.
+    fn new(left: i32, right: i32) -> Self {
+        Self {
+            left,
+            coverage: vec![0.0; (right - left).max(0) as usize],
+        }
+    }
+
+    /// Reset all coverage to zero.
+    #[inline]
+    fn clear(&mut self) {
+        self.coverage.fill(0.0);
+    }
+
+    /// Add `weight` times the horizontal overlap of `x0..x1` to each pixel.
+    ///
+    /// Portions of the span outside the line are ignored.
+    fn accumulate(&mut self, x0: f32, x1: f32, weight: f32) {
+        let right = self.left + self.coverage.len() as i32;
+        let start = (x0.floor() as i32).max(self.left);
+        let end = (x1.ceil() as i32).min(right);
+
+        for x in start..end {
+            let overlap = ((x + 1) as f32).min(x1) - (x as f32).max(x0);
+            if overlap > 0.0 {
+                self.coverage[(x - self.left) as usize] += overlap * weight;
             }
         }
     }
+
+    /// Iterate over pixels with non-zero coverage as `(x, coverage)`.
+    fn iter(&self) -> impl Iterator<Item = (i32, f32)> + '_ {
+        self.coverage
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| c > 0.0)
+            .map(|(i, &c)| (self.left + i as i32, c))
+    }
 }
 
 /// An edge for scanline rasterization with winding direction.
@@ -1268,6 +1996,12 @@ impl ActiveEdge {
         self.x += self.inv_slope;
     }
 
+    /// Update x-intercept for a scanline `dy` further down.
+    #[inline]
+    fn step_by(&mut self, dy: f32) {
+        self.x += self.inv_slope * dy;
+    }
+
     /// Check if this edge is still active at the given y.
     #[inline]
     fn is_active_at(&self, y: f32) -> bool {
@@ -1290,11 +2024,11 @@ impl GlobalEdgeTable {
         edges.sort_by(|a, b| {
             a.y_min
                 .partial_cmp(&b.y_min)
-                .unwrap_or(std::cmp::Ordering::Equal)
+                .unwrap_or(core::cmp::Ordering::Equal)
                 .then_with(|| {
                     a.x_at_y_min
                         .partial_cmp(&b.x_at_y_min)
-                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .unwrap_or(core::cmp::Ordering::Equal)
                 })
         });
 
@@ -1373,9 +2107,25 @@ impl ActiveEdgeTable {
         }
     }
 
+    /// Step all edges to a scanline `dy` further down.
+    fn step_all_by(&mut self, dy: f32) {
+        for edge in &mut self.edges {
+            edge.step_by(dy);
+        }
+    }
+
     /// Get span pairs for filling using the specified fill rule.
     fn get_spans(&self, fill_type: FillType) -> Vec<(f32, f32)> {
         let mut spans = Vec::new();
+        self.get_spans_into(fill_type, &mut spans);
+        spans
+    }
+
+    /// Like [`get_spans`](Self::get_spans), but writes into a reusable buffer.
+    ///
+    /// `spans` is cleared first.
+    fn get_spans_into(&self, fill_type: FillType, spans: &mut Vec<(f32, f32)>) {
+        spans.clear();
 
         match fill_type {
             FillType::Winding | FillType::InverseWinding => {
@@ -1414,8 +2164,6 @@ impl ActiveEdgeTable {
                 }
             }
         }
-
-        spans
     }
 
     /// Check if the AET is empty.
@@ -1425,6 +2173,9 @@ impl ActiveEdgeTable {
 }
 
 /// Collect edges from a path.
+///
+/// Open contours are closed with a line back to their start, as a fill
+/// treats them.
 fn collect_edges(path: &Path, matrix: &Matrix) -> Vec<Edge> {
     let mut edges = Vec::new();
     let mut current = Point::zero();
@@ -1433,6 +2184,9 @@ fn collect_edges(path: &Path, matrix: &Matrix) -> Vec<Edge> {
     for element in path.iter() {
         match element {
             PathElement::Move(p) => {
+                if let Some(edge) = Edge::new(current, contour_start) {
+                    edges.push(edge);
+                }
                 current = matrix.map_point(p);
                 contour_start = current;
             }
@@ -1515,10 +2269,38 @@ fn collect_edges(path: &Path, matrix: &Matrix) -> Vec<Edge> {
             }
         }
     }
+    if let Some(edge) = Edge::new(current, contour_start) {
+        edges.push(edge);
+    }
 
     edges
 }
 
+/// Largest distance, in device pixels, between a hairline and its curve.
+const HAIRLINE_TOLERANCE: Scalar = 0.25;
+
+/// Flatten a device space cubic into the points of a polyline, excluding
+/// its start, that stays within [`HAIRLINE_TOLERANCE`] of the curve.
+fn flatten_cubic(p0: Point, c1: Point, c2: Point, p3: Point) -> Vec<Point> {
+    // Chord error is bounded by 3/4 of the largest second difference over
+    // the square of the step count.
+    let dd = (p0 - c1 * 2.0 + c2)
+        .length()
+        .max((c1 - c2 * 2.0 + p3).length());
+    let steps = ((0.75 * dd / HAIRLINE_TOLERANCE).sqrt().ceil() as usize).clamp(1, 256);
+
+    (1..=steps)
+        .map(|i| {
+            let t = i as Scalar / steps as Scalar;
+            let mt = 1.0 - t;
+            p0 * (mt * mt * mt)
+                + c1 * (3.0 * mt * mt * t)
+                + c2 * (3.0 * mt * t * t)
+                + p3 * (t * t * t)
+        })
+        .collect()
+}
+
 /// Create an ellipse path using cubic bezier approximation.
 fn ellipse_to_path(center: Point, rx: Scalar, ry: Scalar) -> Path {
     use skia_rs_path::PathBuilder;
@@ -1638,6 +2420,106 @@ mod tests {
         assert!(result.blue() > 100);
     }
 
+    fn assert_rgb_near(mode: BlendMode, actual: Color, expected: [u8; 3]) {
+        let actual = [actual.red(), actual.green(), actual.blue()];
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                a.abs_diff(e) <= 1,
+                "{mode:?}: got {actual:?}, expected {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_blend_separable_opaque() {
+        let src = Color::from_rgb(200, 100, 40);
+        let dst = Color::from_rgb(60, 150, 220);
+        let cases = [
+            (BlendMode::Multiply, [47, 59, 35]),
+            (BlendMode::Screen, [213, 191, 225]),
+            (BlendMode::Overlay, [94, 127, 196]),
+            (BlendMode::Darken, [60, 100, 40]),
+            (BlendMode::Lighten, [200, 150, 220]),
+            (BlendMode::ColorDodge, [255, 247, 255]),
+            (BlendMode::ColorBurn, [6, 0, 32]),
+            (BlendMode::HardLight, [171, 118, 69]),
+            (BlendMode::SoftLight, [96, 137, 199]),
+            (BlendMode::Difference, [140, 50, 180]),
+            (BlendMode::Exclusion, [166, 132, 191]),
+        ];
+        for (mode, expected) in cases {
+            let result = blend_colors(src, dst, mode);
+            assert_eq!(result.alpha(), 255, "{mode:?}");
+            assert_rgb_near(mode, result, expected);
+        }
+    }
+
+    #[test]
+    fn test_blend_non_separable_opaque() {
+        let src = Color::from_rgb(200, 120, 90);
+        let dst = Color::from_rgb(60, 150, 220);
+        let cases = [
+            (BlendMode::Hue, [217, 101, 57]),
+            (BlendMode::Saturation, [82, 144, 192]),
+            (BlendMode::Color, [190, 110, 80]),
+            (BlendMode::Luminosity, [70, 160, 230]),
+        ];
+        for (mode, expected) in cases {
+            assert_rgb_near(mode, blend_colors(src, dst, mode), expected);
+        }
+    }
+
+    #[test]
+    fn test_blend_separable_alpha_compositing() {
+        // Over a transparent destination every mode reduces to the source.
+        let src = Color::from_argb(128, 200, 100, 40);
+        let result = blend_colors(src, Color::TRANSPARENT, BlendMode::Darken);
+        assert_eq!(result.alpha(), 128);
+        assert_rgb_near(BlendMode::Darken, result, [200, 100, 40]);
+
+        // Half-covered: halfway between the destination and the blend.
+        let dst = Color::from_rgb(60, 150, 220);
+        let src = Color::from_argb(128, 255, 255, 255);
+        let result = blend_colors(src, dst, BlendMode::Difference);
+        assert_eq!(result.alpha(), 255);
+        assert_rgb_near(BlendMode::Difference, result, [127, 127, 127]);
+    }
+
+    #[test]
+    fn test_fill_rect_translucent_over_transparent() {
+        let mut buffer = PixelBuffer::new(40, 4);
+        buffer.clear(Color::TRANSPARENT);
+        // Right half starts out as 50% blue.
+        for y in 0..4 {
+            for x in 20..40 {
+                buffer.set_pixel(x, y, Color::from_argb(128, 0, 0, 255));
+            }
+        }
+
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(128, 255, 0, 0));
+        let mut rasterizer = Rasterizer::new(&mut buffer);
+        rasterizer.fill_rect(&Rect::from_xywh(0.0, 0.0, 40.0, 4.0), &paint);
+
+        // The buffer is unpremultiplied, so 50% red over nothing stays
+        // full-intensity red.
+        for x in [0, 7, 19] {
+            let pixel = buffer.get_pixel(x, 2).unwrap();
+            assert_eq!(pixel.alpha(), 128);
+            assert_eq!(pixel.red(), 255);
+            assert_eq!(pixel.blue(), 0);
+        }
+
+        let expected = blend_colors(
+            Color::from_argb(128, 255, 0, 0),
+            Color::from_argb(128, 0, 0, 255),
+            BlendMode::SrcOver,
+        );
+        for x in [20, 27, 39] {
+            assert_eq!(buffer.get_pixel(x, 2).unwrap(), expected);
+        }
+    }
+
     // ============ Active Edge Table Tests ============
 
     #[test]
@@ -1689,6 +2571,38 @@ mod tests {
         assert!((active.x - initial_x - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_active_edge_table_step_by_samples() {
+        let edges = vec![
+            Edge::new(Point::new(0.0, 0.0), Point::new(40.0, 10.0)).unwrap(),
+            Edge::new(Point::new(50.0, 2.0), Point::new(50.0, 3.0)).unwrap(),
+        ];
+        let mut get = GlobalEdgeTable::new(edges.clone());
+        let mut aet = ActiveEdgeTable::new();
+
+        let mut previous = None;
+        for sample in 0..40 {
+            let y = sample as f32 * 0.25 + 0.125;
+            if let Some(previous) = previous.replace(y) {
+                aet.step_all_by(y - previous);
+            }
+            aet.add_edges(get.get_new_edges_at(y), y);
+            aet.remove_inactive(y);
+
+            // Matches evaluating every edge at this sample from scratch.
+            let expected: Vec<f32> = edges
+                .iter()
+                .filter(|e| e.y_min <= y && y < e.y_max)
+                .map(|e| e.x_at(y))
+                .collect();
+            let actual: Vec<f32> = aet.edges.iter().map(|e| e.x).collect();
+            assert_eq!(actual.len(), expected.len(), "sample {sample}");
+            for (a, e) in actual.iter().zip(&expected) {
+                assert!((a - e).abs() < 0.001, "sample {sample}");
+            }
+        }
+    }
+
     #[test]
     fn test_global_edge_table_ordering() {
         let edges = vec![
@@ -1856,4 +2770,608 @@ mod tests {
         let overlap_pixel = buffer.get_pixel(50, 50).unwrap();
         assert_eq!(overlap_pixel.red(), 255, "Overlap should be filled");
     }
+
+    #[test]
+    fn test_fill_path_aa_matches_fill_path_on_pixel_grid() {
+        use skia_rs_path::PathBuilder;
+
+        // A pixel-aligned shape has full or zero coverage everywhere, so the
+        // AA and aliased fills must agree exactly.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(10.0, 10.0)
+            .line_to(40.0, 10.0)
+            .line_to(40.0, 20.0)
+            .line_to(20.0, 20.0)
+            .line_to(20.0, 40.0)
+            .line_to(10.0, 40.0)
+            .close();
+        let path = builder.build();
+
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 0, 0, 255));
+
+        let mut aliased = PixelBuffer::new(50, 50);
+        Rasterizer::new(&mut aliased).fill_path(&path, &paint);
+        let mut aa = PixelBuffer::new(50, 50);
+        Rasterizer::new(&mut aa).fill_path_aa(&path, &paint);
+
+        assert_eq!(aliased.pixels, aa.pixels);
+    }
+
+    #[test]
+    fn test_fill_path_aa_partial_coverage() {
+        use skia_rs_path::PathBuilder;
+
+        let mut buffer = PixelBuffer::new(20, 20);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 255, 0, 0));
+
+        let mut builder = PathBuilder::new();
+        builder.add_rect(&Rect::new(2.5, 2.0, 8.0, 6.0));
+        let path = builder.build();
+        Rasterizer::new(&mut buffer).fill_path_aa(&path, &paint);
+
+        // Left edge column is half covered, interior fully covered.
+        let edge = buffer.get_pixel(2, 4).unwrap();
+        assert!((i32::from(edge.alpha()) - 128).abs() <= 1);
+        assert_eq!(buffer.get_pixel(5, 4).unwrap().alpha(), 255);
+        assert_eq!(buffer.get_pixel(8, 4).unwrap().alpha(), 0);
+        assert_eq!(buffer.get_pixel(5, 6).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn test_fill_path_aa_drops_finished_edges() {
+        use skia_rs_path::PathBuilder;
+
+        // A "U" shape: the inner edges end partway down, so rows below them
+        // must not see stale crossings.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(4.0, 0.0)
+            .line_to(4.0, 6.0)
+            .line_to(6.0, 6.0)
+            .line_to(6.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 10.0)
+            .line_to(0.0, 10.0)
+            .close();
+        let path = builder.build();
+
+        let mut buffer = PixelBuffer::new(10, 10);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 0, 255, 0));
+        Rasterizer::new(&mut buffer).fill_path_aa(&path, &paint);
+
+        assert_eq!(buffer.get_pixel(5, 2).unwrap().alpha(), 0);
+        for x in 0..10 {
+            assert_eq!(buffer.get_pixel(x, 8).unwrap().alpha(), 255, "x = {x}");
+        }
+    }
+
+    #[test]
+    fn test_coverage_line_accumulate() {
+        let mut line = CoverageLine::new(2, 6);
+        line.accumulate(0.0, 3.5, 0.5);
+        line.accumulate(5.25, 100.0, 1.0);
+
+        let values: Vec<(i32, f32)> = line.iter().collect();
+        assert_eq!(values, vec![(2, 0.5), (3, 0.25), (5, 0.75)]);
+
+        let capacity = line.coverage.capacity();
+        line.clear();
+        assert_eq!(line.iter().count(), 0);
+        assert_eq!(line.coverage.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_draw_path_uses_aa_fill_when_requested() {
+        use skia_rs_path::PathBuilder;
+
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(5.0, 5.0)
+            .line_to(35.0, 12.0)
+            .line_to(12.0, 35.0)
+            .close();
+        let triangle = builder.build();
+
+        let render = |anti_alias: bool| {
+            let mut buffer = PixelBuffer::new(40, 40);
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_argb(255, 0, 0, 0));
+            paint.set_anti_alias(anti_alias);
+            Rasterizer::new(&mut buffer).draw_path(&triangle, &paint);
+            buffer
+        };
+        let aliased = render(false);
+        let smooth = render(true);
+
+        let alphas = |buffer: &PixelBuffer| -> Vec<u8> {
+            buffer.pixels.chunks_exact(4).map(|p| p[3]).collect()
+        };
+        let aliased = alphas(&aliased);
+        let smooth = alphas(&smooth);
+
+        // Aliased output is binary; the AA fill shades its slanted edges.
+        assert!(aliased.iter().all(|&a| a == 0 || a == 255));
+        let partial = smooth.iter().filter(|&&a| a > 0 && a < 255).count();
+        assert!(partial > 30, "only {partial} partially covered pixels");
+
+        // Both agree well inside and well outside the triangle.
+        let at = |x: usize, y: usize| y * 40 + x;
+        for (x, y) in [(15, 15), (12, 20), (20, 14)] {
+            assert_eq!(smooth[at(x, y)], 255);
+            assert_eq!(aliased[at(x, y)], 255);
+        }
+        for (x, y) in [(2, 2), (35, 35), (30, 5)] {
+            assert_eq!(smooth[at(x, y)], 0);
+            assert_eq!(aliased[at(x, y)], 0);
+        }
+
+        // Total coverage is close to the triangle's true area.
+        let area = 0.5 * ((35.0 - 5.0) * (35.0 - 5.0) - (12.0 - 5.0) * (12.0 - 5.0));
+        let covered: f32 = smooth.iter().map(|&a| f32::from(a) / 255.0).sum();
+        assert!((covered - area).abs() < area * 0.01, "{covered} vs {area}");
+    }
+
+    #[test]
+    fn test_fill_circle_samples_shader_in_local_space() {
+        use skia_rs_core::Color4f;
+        use skia_rs_paint::{TileMode, shaders};
+
+        let black = Color4f::new(0.0, 0.0, 0.0, 1.0);
+        let white = Color4f::new(1.0, 1.0, 1.0, 1.0);
+        // Horizontal gradient across the circle's local bounds.
+        let shader = shaders::linear_gradient(
+            Point::new(-20.0, 0.0),
+            Point::new(20.0, 0.0),
+            &[black, white],
+            None,
+            TileMode::Clamp,
+        )
+        .unwrap();
+
+        for anti_alias in [false, true] {
+            let mut buffer = PixelBuffer::new(64, 64);
+            let mut paint = Paint::new();
+            paint.set_shader(Some(shader.clone()));
+            paint.set_anti_alias(anti_alias);
+            let mut rasterizer = Rasterizer::new(&mut buffer);
+            rasterizer.set_matrix(&Matrix::translate(32.0, 32.0));
+            rasterizer.draw_circle(Point::zero(), 20.0, &paint);
+
+            // The gradient moves with the circle: dark on the left, mid-gray
+            // in the middle, light on the right.
+            let red = |x| buffer.get_pixel(x, 32).unwrap().red();
+            assert!(red(15) < 40, "anti_alias = {anti_alias}");
+            assert!((i32::from(red(32)) - 128).abs() <= 8);
+            assert!(red(49) > 215);
+            assert_eq!(buffer.get_pixel(32, 32).unwrap().alpha(), 255);
+        }
+    }
+
+    #[test]
+    fn test_grayscale_color_filter() {
+        use alloc::sync::Arc;
+        use skia_rs_core::Color4f;
+        use skia_rs_paint::{ColorFilter, ColorShader};
+
+        let luma = [0.2126, 0.7152, 0.0722, 0.0, 0.0];
+        #[rustfmt::skip]
+        let grayscale = <dyn ColorFilter>::matrix([
+            luma[0], luma[1], luma[2], luma[3], luma[4],
+            luma[0], luma[1], luma[2], luma[3], luma[4],
+            luma[0], luma[1], luma[2], luma[3], luma[4],
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ]);
+
+        // Solid colors and shader samples are both filtered.
+        for shaded in [false, true] {
+            let mut buffer = PixelBuffer::new(8, 8);
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_rgb(255, 0, 0));
+            if shaded {
+                let red = ColorShader::new(Color4f::new(1.0, 0.0, 0.0, 1.0));
+                paint.set_shader(Some(Arc::new(red)));
+            }
+            paint.set_color_filter(Some(grayscale.clone()));
+            Rasterizer::new(&mut buffer).fill_rect(&Rect::from_xywh(0.0, 0.0, 8.0, 8.0), &paint);
+
+            let pixel = buffer.get_pixel(4, 4).unwrap();
+            assert_eq!(
+                (pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()),
+                (54, 54, 54, 255),
+                "shaded = {shaded}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_blend_color_filter_tints() {
+        use skia_rs_paint::ColorFilter;
+
+        let tint = <dyn ColorFilter>::blend(Color::from_argb(128, 0, 0, 255), BlendMode::SrcOver);
+        let mut buffer = PixelBuffer::new(8, 8);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::WHITE);
+        paint.set_color_filter(Some(tint));
+        let mut path = skia_rs_path::PathBuilder::new();
+        path.add_rect(&Rect::from_xywh(0.0, 0.0, 8.0, 8.0));
+        Rasterizer::new(&mut buffer).fill_path(&path.build(), &paint);
+
+        // Half-way from white toward blue, still opaque.
+        let pixel = buffer.get_pixel(4, 4).unwrap();
+        assert!((i32::from(pixel.red()) - 127).abs() <= 1);
+        assert_eq!(pixel.red(), pixel.green());
+        assert_eq!((pixel.blue(), pixel.alpha()), (255, 255));
+    }
+
+    #[test]
+    fn test_color_filter_applied_once_to_coverage_draws() {
+        use alloc::sync::Arc;
+        use skia_rs_paint::{BlurMaskFilter, BlurStyle, ColorFilter};
+
+        // Halves the alpha of whatever it filters.
+        let half = <dyn ColorFilter>::blend(Color::from_argb(128, 0, 0, 0), BlendMode::DstIn);
+        let mut path = skia_rs_path::PathBuilder::new();
+        path.add_rect(&Rect::from_xywh(4.0, 4.0, 24.0, 24.0));
+        let path = path.build();
+
+        let mut paint = Paint::new();
+        paint.set_color32(Color::WHITE);
+        paint.set_color_filter(Some(half));
+        let mut stroke = paint.clone();
+        stroke.set_stroke_width(2.0);
+
+        let mut buffer = PixelBuffer::new(32, 32);
+        Rasterizer::new(&mut buffer).draw_path_filled_and_stroked(&path, &paint, &stroke);
+        assert_eq!(buffer.get_pixel(16, 16).unwrap().alpha(), 128);
+
+        paint.set_mask_filter(Some(Arc::new(BlurMaskFilter::new(BlurStyle::Solid, 1.0))));
+        let mut buffer = PixelBuffer::new(32, 32);
+        Rasterizer::new(&mut buffer).draw_path(&path, &paint);
+        assert_eq!(buffer.get_pixel(16, 16).unwrap().alpha(), 128);
+    }
+
+    #[test]
+    fn test_fill_rect_with_runtime_shader() {
+        use alloc::sync::Arc;
+        use skia_rs_paint::{RuntimeEffect, UniformData};
+
+        let effect = Arc::new(
+            RuntimeEffect::make_for_shader(
+                r#"
+                uniform float2 size;
+                half4 main(float2 p) {
+                    float2 uv = p / size;
+                    return vec4(uv.x, 0, 0, 1);
+                }
+                "#,
+            )
+            .unwrap(),
+        );
+        let uniforms = UniformData::from_floats(&effect, &[64.0, 8.0]);
+        let shader = effect.make_shader(&uniforms, &[]).unwrap();
+
+        let mut buffer = PixelBuffer::new(64, 8);
+        let mut paint = Paint::new();
+        paint.set_shader(Some(Arc::new(shader)));
+        Rasterizer::new(&mut buffer).fill_rect(&Rect::from_xywh(0.0, 0.0, 64.0, 8.0), &paint);
+
+        // A horizontal red ramp sampled at pixel centers.
+        for y in 0..8 {
+            for x in 0..64 {
+                let pixel = buffer.get_pixel(x, y).unwrap();
+                let expected = (x as f32 + 0.5) / 64.0 * 255.0;
+                assert!(
+                    (f32::from(pixel.red()) - expected).abs() <= 1.0,
+                    "({x}, {y})"
+                );
+                assert_eq!((pixel.green(), pixel.blue(), pixel.alpha()), (0, 0, 255));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_with_tiled_image_shader() {
+        use alloc::sync::Arc;
+        use skia_rs_core::Color4f;
+        use skia_rs_paint::{ImageShader, SamplingOptions, TileMode};
+
+        let black = Color4f::new(0.0, 0.0, 0.0, 1.0);
+        let white = Color4f::new(1.0, 1.0, 1.0, 1.0);
+        let checker = ImageShader::from_pixels(
+            2,
+            2,
+            &[black, white, white, black],
+            TileMode::Repeat,
+            TileMode::Repeat,
+            SamplingOptions::NEAREST,
+        )
+        .unwrap()
+        .with_local_matrix(Matrix::translate(4.0, 4.0));
+
+        let mut buffer = PixelBuffer::new(16, 16);
+        let mut paint = Paint::new();
+        paint.set_shader(Some(Arc::new(checker)));
+
+        // Rect and path fills both tile the 2x2 image four times each way.
+        for path_fill in [false, true] {
+            buffer.clear(Color::TRANSPARENT);
+            let rect = Rect::from_xywh(4.0, 4.0, 8.0, 8.0);
+            let mut rasterizer = Rasterizer::new(&mut buffer);
+            if path_fill {
+                let mut builder = skia_rs_path::PathBuilder::new();
+                builder.add_rect(&rect);
+                rasterizer.fill_path(&builder.build(), &paint);
+            } else {
+                rasterizer.fill_rect(&rect, &paint);
+            }
+
+            for y in 0..16 {
+                for x in 0..16 {
+                    let pixel = buffer.get_pixel(x, y).unwrap();
+                    if !(4..12).contains(&x) || !(4..12).contains(&y) {
+                        assert_eq!(pixel.alpha(), 0, "({x}, {y}) outside the fill");
+                        continue;
+                    }
+                    let expected = if (x + y) % 2 == 0 { 0 } else { 255 };
+                    assert_eq!(pixel.red(), expected, "({x}, {y}), path_fill = {path_fill}");
+                    assert_eq!(pixel.alpha(), 255);
+                }
+            }
+        }
+    }
+
+    fn blurred_rect_alpha(style: skia_rs_paint::BlurStyle, clip: Option<Rect>) -> PixelBuffer {
+        use skia_rs_paint::MaskFilter;
+
+        let mut buffer = PixelBuffer::new(40, 40);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::BLACK);
+        paint.set_mask_filter(<dyn MaskFilter>::blur(style, 2.0));
+        let mut rasterizer = Rasterizer::new(&mut buffer);
+        if let Some(clip) = clip {
+            rasterizer.set_clip(clip);
+        }
+        rasterizer.draw_rect(&Rect::from_xywh(10.0, 10.0, 20.0, 20.0), &paint);
+        buffer
+    }
+
+    #[test]
+    fn test_blur_mask_filter_spreads_coverage() {
+        use skia_rs_paint::BlurStyle;
+
+        let alpha = |buffer: &PixelBuffer, x, y| buffer.get_pixel(x, y).unwrap().alpha();
+
+        let normal = blurred_rect_alpha(BlurStyle::Normal, None);
+        // Coverage reaches a few pixels past the rect's edge at x = 30 ...
+        assert!(alpha(&normal, 32, 20) > 0);
+        assert!(alpha(&normal, 33, 20) > 0);
+        // ... falls off smoothly across the edge ...
+        assert!(alpha(&normal, 31, 20) < alpha(&normal, 29, 20));
+        assert!(alpha(&normal, 29, 20) < 255);
+        // ... stays solid in the middle and is gone beyond 3 sigma.
+        assert_eq!(alpha(&normal, 20, 20), 255);
+        assert_eq!(alpha(&normal, 37, 20), 0);
+
+        // Solid keeps the original shape opaque; inner and outer split the
+        // blur at the shape's edge.
+        let solid = blurred_rect_alpha(BlurStyle::Solid, None);
+        assert_eq!(alpha(&solid, 29, 20), 255);
+        assert_eq!(alpha(&solid, 32, 20), alpha(&normal, 32, 20));
+        let outer = blurred_rect_alpha(BlurStyle::Outer, None);
+        assert_eq!(alpha(&outer, 20, 20), 0);
+        assert!(alpha(&outer, 32, 20) > 0);
+        let inner = blurred_rect_alpha(BlurStyle::Inner, None);
+        assert_eq!(alpha(&inner, 32, 20), 0);
+        assert!(alpha(&inner, 29, 20) > 0);
+
+        // The blur is clipped after it is computed.
+        let clipped = blurred_rect_alpha(
+            BlurStyle::Normal,
+            Some(Rect::from_xywh(31.0, 0.0, 9.0, 40.0)),
+        );
+        assert_eq!(alpha(&clipped, 29, 20), 0);
+        assert_eq!(alpha(&clipped, 32, 20), alpha(&normal, 32, 20));
+    }
+
+    #[test]
+    fn test_blur_mask_filter_extreme_sigma() {
+        use skia_rs_paint::{BlurMaskFilter, BlurStyle};
+
+        let draw = |sigma: Scalar| {
+            let mut buffer = PixelBuffer::new(40, 40);
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_argb(255, 0, 0, 0));
+            paint.set_mask_filter(Some(alloc::sync::Arc::new(BlurMaskFilter::new(
+                BlurStyle::Normal,
+                sigma,
+            ))));
+            let mut rasterizer = Rasterizer::new(&mut buffer);
+            rasterizer.draw_rect(&Rect::from_xywh(10.0, 10.0, 20.0, 20.0), &paint);
+            buffer.get_pixel(20, 20).unwrap().alpha()
+        };
+
+        // Huge sigmas are clamped rather than overflowing the mask bounds,
+        // spreading the rect too thin to see.
+        assert_eq!(draw(1e30), 0);
+        assert_eq!(draw(Scalar::MAX), 0);
+        // Sigmas that can't blur draw the shape unblurred.
+        assert_eq!(draw(Scalar::INFINITY), 255);
+        assert_eq!(draw(Scalar::NAN), 255);
+        assert_eq!(draw(-1.0), 255);
+    }
+
+    fn stroke_alpha(
+        path: &Path,
+        width: Scalar,
+        cap: skia_rs_paint::StrokeCap,
+    ) -> impl Fn(usize, usize) -> u8 {
+        let mut buffer = PixelBuffer::new(64, 64);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 0, 0, 0));
+        paint.set_style(Style::Stroke);
+        paint.set_stroke_width(width);
+        paint.set_stroke_cap(cap);
+        Rasterizer::new(&mut buffer).draw_path(path, &paint);
+        move |x, y| buffer.pixels[(y * 64 + x) * 4 + 3]
+    }
+
+    #[test]
+    fn test_stroke_path_honors_width() {
+        use skia_rs_paint::StrokeCap;
+        use skia_rs_path::PathBuilder;
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(10.0, 32.0).line_to(54.0, 32.0);
+        let line = builder.build();
+
+        // Width 16 covers rows 24..40: 8px above and below the centerline.
+        let alpha = stroke_alpha(&line, 16.0, StrokeCap::Butt);
+        for x in [10, 32, 53] {
+            assert_eq!(alpha(x, 24), 255);
+            assert_eq!(alpha(x, 31), 255);
+            assert_eq!(alpha(x, 39), 255);
+            assert_eq!(alpha(x, 23), 0);
+            assert_eq!(alpha(x, 40), 0);
+        }
+        // Butt caps stop at the endpoints; square caps extend by half the width.
+        assert_eq!(alpha(9, 32), 0);
+        assert_eq!(alpha(54, 32), 0);
+        let square = stroke_alpha(&line, 16.0, StrokeCap::Square);
+        assert_eq!(square(2, 32), 255);
+        assert_eq!(square(61, 32), 255);
+        assert_eq!(square(1, 32), 0);
+    }
+
+    #[test]
+    fn test_hairline_ignores_matrix_scale() {
+        use skia_rs_path::PathBuilder;
+
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(2.0, 4.0)
+            .line_to(18.0, 4.0)
+            .move_to(4.0, 6.0)
+            .line_to(4.0, 18.0);
+        let path = builder.build();
+
+        let mut buffer = PixelBuffer::new(64, 64);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 0, 0, 0));
+        paint.set_style(Style::Stroke);
+        paint.set_stroke_width(0.0);
+        let mut rasterizer = Rasterizer::new(&mut buffer);
+        rasterizer.set_matrix(&Matrix::scale(3.0, 3.0));
+        rasterizer.draw_path(&path, &paint);
+        let alpha = |x: usize, y: usize| buffer.pixels[(y * 64 + x) * 4 + 3] as u32;
+
+        // The horizontal line lands on device row 12 and covers about one
+        // pixel's worth of each column, not three.
+        let column: Vec<u32> = (0..64).map(|y| alpha(30, y)).collect();
+        let sum: u32 = column.iter().sum();
+        assert!((230..=280).contains(&sum), "column coverage {sum}");
+        assert!(column.iter().filter(|&&a| a > 0).count() <= 2);
+        assert!(alpha(30, 12) > 0);
+
+        let row: Vec<u32> = (0..64).map(|x| alpha(x, 36)).collect();
+        let sum: u32 = row.iter().sum();
+        assert!((230..=280).contains(&sum), "row coverage {sum}");
+        assert!(row.iter().filter(|&&a| a > 0).count() <= 2);
+    }
+
+    #[test]
+    fn test_draw_path_filled_and_stroked() {
+        use skia_rs_path::PathBuilder;
+
+        let mut builder = PathBuilder::new();
+        builder.add_circle(32.0, 32.0, 20.0);
+        let circle = builder.build();
+
+        let mut buffer = PixelBuffer::new(64, 64);
+        let mut fill = Paint::new();
+        fill.set_color32(Color::from_rgb(255, 0, 0));
+        fill.set_anti_alias(true);
+        let mut stroke = fill.clone();
+        stroke.set_color32(Color::from_rgb(0, 0, 255));
+        stroke.set_stroke_width(4.0);
+        Rasterizer::new(&mut buffer).draw_path_filled_and_stroked(&circle, &fill, &stroke);
+        let pixel = |x: usize| {
+            let i = (32 * 64 + x) * 4;
+            [
+                buffer.pixels[i],
+                buffer.pixels[i + 1],
+                buffer.pixels[i + 2],
+                buffer.pixels[i + 3],
+            ]
+        };
+
+        // Left to right along the center row: outside, stroke band from 10
+        // to 14, red interior.
+        assert_eq!(pixel(8)[3], 0);
+        assert_eq!(pixel(12), [0, 0, 255, 255]);
+        assert_eq!(pixel(32), [255, 0, 0, 255]);
+        assert_eq!(pixel(51), [0, 0, 255, 255]);
+        // Where stroke meets fill, pixels mix the two colors and stay opaque.
+        for x in 12..=52 {
+            let [r, g, b, a] = pixel(x);
+            assert_eq!(a, 255, "pixel {x} is translucent");
+            assert_eq!(g, 0);
+            assert!(r as u32 + b as u32 >= 254, "pixel {x} is darkened");
+        }
+    }
+
+    #[test]
+    fn test_stroke_path_applies_dash_effect() {
+        use alloc::sync::Arc;
+        use skia_rs_path::{DashEffect, PathBuilder};
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(0.0, 32.0).line_to(100.0, 32.0);
+        let line = builder.build();
+
+        let mut buffer = PixelBuffer::new(100, 64);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 0, 0, 0));
+        paint.set_style(Style::Stroke);
+        paint.set_stroke_width(4.0);
+        paint.set_path_effect(Some(Arc::new(
+            DashEffect::new(vec![10.0, 10.0], 0.0).unwrap(),
+        )));
+        Rasterizer::new(&mut buffer).draw_path(&line, &paint);
+
+        // Count the runs of covered pixels along the centerline.
+        let covered: Vec<bool> = (0..100)
+            .map(|x| buffer.pixels[(32 * 100 + x) * 4 + 3] > 0)
+            .collect();
+        let runs = covered
+            .iter()
+            .enumerate()
+            .filter(|&(x, &on)| on && (x == 0 || !covered[x - 1]))
+            .count();
+        assert_eq!(runs, 5);
+        assert!(covered[..10].iter().all(|&on| on));
+        assert!(covered[10..20].iter().all(|&on| !on));
+    }
+
+    #[test]
+    fn test_stroke_path_zero_length_round_cap_dot() {
+        use skia_rs_paint::StrokeCap;
+        use skia_rs_path::PathBuilder;
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(32.0, 32.0).line_to(32.0, 32.0);
+        let dot = builder.build();
+
+        assert_eq!(stroke_alpha(&dot, 10.0, StrokeCap::Butt)(32, 32), 0);
+        let round = stroke_alpha(&dot, 10.0, StrokeCap::Round);
+        assert_eq!(round(32, 32), 255);
+        assert_eq!(round(28, 32), 255);
+        assert_eq!(round(32, 35), 255);
+        assert_eq!(round(27, 27), 0);
+        assert_eq!(round(32, 38), 0);
+    }
 }
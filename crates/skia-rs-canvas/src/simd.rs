@@ -38,16 +38,30 @@ impl SimdCapabilities {
         }
     }
 
+    /// Capabilities with every instruction set disabled, forcing the scalar
+    /// paths.
+    pub const fn scalar() -> Self {
+        Self {
+            sse42: false,
+            avx2: false,
+            neon: false,
+        }
+    }
+
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     fn has_sse42() -> bool {
         #[cfg(target_feature = "sse4.2")]
         {
             true
         }
-        #[cfg(not(target_feature = "sse4.2"))]
+        #[cfg(all(not(target_feature = "sse4.2"), feature = "std"))]
         {
             is_x86_feature_detected!("sse4.2")
         }
+        #[cfg(all(not(target_feature = "sse4.2"), not(feature = "std")))]
+        {
+            false
+        }
     }
 
     #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
@@ -61,10 +75,14 @@ impl SimdCapabilities {
         {
             true
         }
-        #[cfg(not(target_feature = "avx2"))]
+        #[cfg(all(not(target_feature = "avx2"), feature = "std"))]
         {
             is_x86_feature_detected!("avx2")
         }
+        #[cfg(all(not(target_feature = "avx2"), not(feature = "std")))]
+        {
+            false
+        }
     }
 
     #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
@@ -108,12 +126,32 @@ impl SimdCapabilities {
 }
 
 /// Global SIMD capabilities, lazily initialized.
+#[cfg(feature = "std")]
 static SIMD_CAPS: std::sync::OnceLock<SimdCapabilities> = std::sync::OnceLock::new();
 
+/// Without `std` there is no runtime detection, so only the instruction sets
+/// enabled at compile time are used.
+#[cfg(not(feature = "std"))]
+static SIMD_CAPS: SimdCapabilities = SimdCapabilities {
+    sse42: cfg!(target_feature = "sse4.2"),
+    avx2: cfg!(target_feature = "avx2"),
+    neon: cfg!(any(
+        target_arch = "aarch64",
+        all(target_arch = "arm", target_feature = "neon")
+    )),
+};
+
 /// Get the detected SIMD capabilities.
 #[inline]
 pub fn simd_capabilities() -> &'static SimdCapabilities {
-    SIMD_CAPS.get_or_init(SimdCapabilities::detect)
+    #[cfg(feature = "std")]
+    {
+        SIMD_CAPS.get_or_init(SimdCapabilities::detect)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        &SIMD_CAPS
+    }
 }
 
 // ============================================================================
@@ -123,46 +161,74 @@ pub fn simd_capabilities() -> &'static SimdCapabilities {
 /// Fill a span of pixels with a solid color (SIMD-optimized).
 ///
 /// This is the hot path for solid color fills - optimized for both
-/// opaque and semi-transparent colors.
+/// opaque and semi-transparent colors. `color` must be premultiplied;
+/// semi-transparent colors are blended with premultiplied SrcOver, so
+/// `dst` must either hold premultiplied pixels or be fully opaque.
 #[inline]
 pub fn fill_span_solid(dst: &mut [u8], color: Color) {
+    fill_span_solid_with(dst, color, simd_capabilities());
+}
+
+/// Fill a span of pixels with a solid color using only the instruction sets
+/// enabled in `caps`.
+///
+/// Every instruction set produces the same bytes as the scalar path, so
+/// passing [`SimdCapabilities::scalar`] is useful for testing and
+/// benchmarking. Capabilities the CPU doesn't actually have are ignored.
+pub fn fill_span_solid_with(dst: &mut [u8], color: Color, caps: &SimdCapabilities) {
     let len = dst.len() / 4;
     if len == 0 {
         return;
     }
 
-    // For opaque colors, use simple memset-style fill
-    if color.alpha() == 255 {
-        fill_span_opaque(dst, color);
+    // For transparent source, nothing to do
+    if color.alpha() == 0 {
         return;
     }
 
-    // For transparent source, nothing to do
-    if color.alpha() == 0 {
+    // Never trust more than the hardware offers.
+    let detected = simd_capabilities();
+    let caps = SimdCapabilities {
+        sse42: caps.sse42 && detected.sse42,
+        avx2: caps.avx2 && detected.avx2,
+        neon: caps.neon && detected.neon,
+    };
+
+    // For opaque colors, use simple memset-style fill
+    if color.alpha() == 255 {
+        fill_span_opaque(dst, color, &caps);
         return;
     }
 
-    let caps = simd_capabilities();
+    // Clamp to a valid premultiplied color so the 16-bit SIMD lanes can't
+    // overflow; this is a no-op for correct input.
+    let a = color.alpha();
+    let color = Color::from_argb(
+        a,
+        color.red().min(a),
+        color.green().min(a),
+        color.blue().min(a),
+    );
 
     // Use SIMD for semi-transparent blending
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     {
         if caps.avx2 && len >= 8 {
-            // SAFETY: We've verified AVX2 support and have enough data
+            // SAFETY: AVX2 support was checked above.
             unsafe { fill_span_blend_avx2(dst, color) };
             return;
         }
         if caps.sse42 && len >= 4 {
-            // SAFETY: We've verified SSE4.1 support and have enough data
-            unsafe { fill_span_blend_sse41(dst, color) };
+            // SAFETY: SSE4.2 support was checked above.
+            unsafe { fill_span_blend_sse42(dst, color) };
             return;
         }
     }
 
     #[cfg(target_arch = "aarch64")]
     {
-        if caps.neon && len >= 4 {
-            // SAFETY: NEON is always available on AArch64
+        if caps.neon && len >= 8 {
+            // SAFETY: NEON support was checked above.
             unsafe { fill_span_blend_neon(dst, color) };
             return;
         }
@@ -174,8 +240,39 @@ pub fn fill_span_solid(dst: &mut [u8], color: Color) {
 
 /// Fill span with opaque color (no blending needed).
 #[inline]
-fn fill_span_opaque(dst: &mut [u8], color: Color) {
+fn fill_span_opaque(dst: &mut [u8], color: Color, caps: &SimdCapabilities) {
     let pattern = [color.red(), color.green(), color.blue(), color.alpha()];
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if caps.avx2 && dst.len() >= 32 {
+            // SAFETY: AVX2 support was checked by the caller.
+            unsafe { fill_span_opaque_avx2(dst, pattern) };
+            return;
+        }
+        if caps.sse42 && dst.len() >= 16 {
+            // SAFETY: SSE4.2 support was checked by the caller.
+            unsafe { fill_span_opaque_sse42(dst, pattern) };
+            return;
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        if caps.neon && dst.len() >= 16 {
+            // SAFETY: NEON support was checked by the caller.
+            unsafe { fill_span_opaque_neon(dst, pattern) };
+            return;
+        }
+    }
+
+    let _ = caps;
+    fill_span_opaque_scalar(dst, pattern);
+}
+
+/// Scalar fallback for opaque fills.
+#[inline]
+fn fill_span_opaque_scalar(dst: &mut [u8], pattern: [u8; 4]) {
     for chunk in dst.chunks_exact_mut(4) {
         chunk.copy_from_slice(&pattern);
     }
@@ -204,94 +301,84 @@ fn fill_span_blend_scalar(dst: &mut [u8], src: Color) {
     }
 }
 
+/// `src * 255` for each channel of a premultiplied color, as four
+/// little-endian 16-bit lanes in RGBA order.
+///
+/// The SIMD blenders compute `(src * 255 + dst * inv_sa) / 255` in 16-bit
+/// lanes. For premultiplied input the sum is at most `255 * 255`, and
+/// `(x + 1 + (x >> 8)) >> 8` equals `x / 255` over that whole range, so the
+/// result matches [`fill_span_blend_scalar`] bit for bit.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+#[inline]
+fn scaled_src_lanes(src: Color) -> u64 {
+    let scale = |c: u8| u64::from(c) * 255;
+    scale(src.red())
+        | (scale(src.green()) << 16)
+        | (scale(src.blue()) << 32)
+        | (scale(src.alpha()) << 48)
+}
+
 // ============================================================================
-// x86/x86_64 SSE4.1 Implementation
+// x86/x86_64 SSE4.2 Implementation
 // ============================================================================
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-#[target_feature(enable = "sse4.1")]
-unsafe fn fill_span_blend_sse41(dst: &mut [u8], src: Color) {
+#[target_feature(enable = "sse4.2")]
+unsafe fn fill_span_opaque_sse42(dst: &mut [u8], pattern: [u8; 4]) {
     #[cfg(target_arch = "x86")]
-    use std::arch::x86::*;
+    use core::arch::x86::*;
     #[cfg(target_arch = "x86_64")]
-    use std::arch::x86_64::*;
+    use core::arch::x86_64::*;
 
-    let len = dst.len() / 4;
-    let chunks = len / 4;
-    let remainder_start = chunks * 16;
+    let chunks = dst.len() / 16;
+    let ptr = dst.as_mut_ptr();
+
+    // SAFETY: every store writes 16 bytes inside `dst`.
+    unsafe {
+        let fill = _mm_set1_epi32(i32::from_le_bytes(pattern));
+        for i in 0..chunks {
+            _mm_storeu_si128(ptr.add(i * 16) as *mut __m128i, fill);
+        }
+    }
 
-    let sa = src.alpha() as i16;
-    let inv_sa = (255 - src.alpha()) as i16;
+    fill_span_opaque_scalar(&mut dst[chunks * 16..], pattern);
+}
 
-    // Broadcast source color components to 16-bit vectors
-    let src_r = unsafe { _mm_set1_epi16(src.red() as i16) };
-    let src_g = unsafe { _mm_set1_epi16(src.green() as i16) };
-    let src_b = unsafe { _mm_set1_epi16(src.blue() as i16) };
-    let src_a = unsafe { _mm_set1_epi16(sa) };
-    let inv_alpha = unsafe { _mm_set1_epi16(inv_sa) };
-    let zero = unsafe { _mm_setzero_si128() };
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse4.2")]
+unsafe fn fill_span_blend_sse42(dst: &mut [u8], src: Color) {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
 
+    let chunks = dst.len() / 16;
     let ptr = dst.as_mut_ptr();
 
-    for i in 0..chunks {
-        let offset = i * 16;
-        let dst_ptr = unsafe { ptr.add(offset) };
-
-        // Load 4 pixels (16 bytes)
-        let dst_pixels = unsafe { _mm_loadu_si128(dst_ptr as *const __m128i) };
-
-        // Unpack bytes to 16-bit for arithmetic
-        let dst_lo = unsafe { _mm_unpacklo_epi8(dst_pixels, zero) }; // First 2 pixels
-        let dst_hi = unsafe { _mm_unpackhi_epi8(dst_pixels, zero) }; // Last 2 pixels
-
-        // Process first 2 pixels (dst_lo contains RGBA RGBA in 16-bit)
-        // Extract channels - they're interleaved as R0 G0 B0 A0 R1 G1 B1 A1
-        let blend_16 = |dst_chan: __m128i, src_chan: __m128i| -> __m128i {
-            // result = (src * 255 + dst * inv_alpha) >> 8
-            let s_scaled = unsafe { _mm_mullo_epi16(src_chan, _mm_set1_epi16(255)) };
-            let d_scaled = unsafe { _mm_mullo_epi16(dst_chan, inv_alpha) };
-            let sum = unsafe { _mm_add_epi16(s_scaled, d_scaled) };
-            unsafe { _mm_srli_epi16(sum, 8) }
+    // SAFETY: every load and store touches 16 bytes inside `dst`.
+    unsafe {
+        let src_scaled = _mm_set1_epi64x(scaled_src_lanes(src) as i64);
+        let inv_alpha = _mm_set1_epi16(i16::from(255 - src.alpha()));
+        let one = _mm_set1_epi16(1);
+        let zero = _mm_setzero_si128();
+
+        // Two pixels of 16-bit RGBA lanes: (src * 255 + dst * inv_sa) / 255
+        let blend = |d: __m128i| -> __m128i {
+            let sum = _mm_add_epi16(src_scaled, _mm_mullo_epi16(d, inv_alpha));
+            let biased = _mm_add_epi16(sum, _mm_add_epi16(one, _mm_srli_epi16(sum, 8)));
+            _mm_srli_epi16(biased, 8)
         };
 
-        // For simplicity, blend all channels identically
-        // (The interleaved layout makes per-channel extraction complex)
-        let blended_lo = blend_16(dst_lo, unsafe {
-            _mm_set_epi16(
-                sa,
-                src.blue() as i16,
-                src.green() as i16,
-                src.red() as i16,
-                sa,
-                src.blue() as i16,
-                src.green() as i16,
-                src.red() as i16,
-            )
-        });
-        let blended_hi = blend_16(dst_hi, unsafe {
-            _mm_set_epi16(
-                sa,
-                src.blue() as i16,
-                src.green() as i16,
-                src.red() as i16,
-                sa,
-                src.blue() as i16,
-                src.green() as i16,
-                src.red() as i16,
-            )
-        });
-
-        // Pack back to bytes
-        let result = unsafe { _mm_packus_epi16(blended_lo, blended_hi) };
-
-        // Store
-        unsafe { _mm_storeu_si128(dst_ptr as *mut __m128i, result) };
-    }
-
-    // Handle remainder with scalar code
-    if remainder_start < dst.len() {
-        fill_span_blend_scalar(&mut dst[remainder_start..], src);
+        for i in 0..chunks {
+            let dst_ptr = ptr.add(i * 16) as *mut __m128i;
+            let pixels = _mm_loadu_si128(dst_ptr);
+            let lo = blend(_mm_unpacklo_epi8(pixels, zero));
+            let hi = blend(_mm_unpackhi_epi8(pixels, zero));
+            _mm_storeu_si128(dst_ptr, _mm_packus_epi16(lo, hi));
+        }
     }
+
+    fill_span_blend_scalar(&mut dst[chunks * 16..], src);
 }
 
 // ============================================================================
@@ -300,74 +387,62 @@ unsafe fn fill_span_blend_sse41(dst: &mut [u8], src: Color) {
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 #[target_feature(enable = "avx2")]
-unsafe fn fill_span_blend_avx2(dst: &mut [u8], src: Color) {
+unsafe fn fill_span_opaque_avx2(dst: &mut [u8], pattern: [u8; 4]) {
     #[cfg(target_arch = "x86")]
-    use std::arch::x86::*;
+    use core::arch::x86::*;
     #[cfg(target_arch = "x86_64")]
-    use std::arch::x86_64::*;
+    use core::arch::x86_64::*;
 
-    let len = dst.len() / 4;
-    let chunks = len / 8;
-    let remainder_start = chunks * 32;
+    let chunks = dst.len() / 32;
+    let ptr = dst.as_mut_ptr();
 
-    let inv_alpha = (255 - src.alpha()) as i32;
+    // SAFETY: every store writes 32 bytes inside `dst`.
+    unsafe {
+        let fill = _mm256_set1_epi32(i32::from_le_bytes(pattern));
+        for i in 0..chunks {
+            _mm256_storeu_si256(ptr.add(i * 32) as *mut __m256i, fill);
+        }
+    }
 
-    let ptr = dst.as_mut_ptr();
+    fill_span_opaque_scalar(&mut dst[chunks * 32..], pattern);
+}
 
-    for i in 0..chunks {
-        let offset = i * 32;
-        let dst_ptr = unsafe { ptr.add(offset) };
-
-        // Load 8 pixels (32 bytes)
-        let dst_pixels = unsafe { _mm256_loadu_si256(dst_ptr as *const __m256i) };
-
-        // Extract channels using masks
-        let mask_r = unsafe { _mm256_set1_epi32(0x000000FF_u32 as i32) };
-        let mask_g = unsafe { _mm256_set1_epi32(0x0000FF00_u32 as i32) };
-        let mask_b = unsafe { _mm256_set1_epi32(0x00FF0000_u32 as i32) };
-        let mask_a = unsafe { _mm256_set1_epi32(0xFF000000_u32 as i32) };
-
-        let dst_r = unsafe { _mm256_and_si256(dst_pixels, mask_r) };
-        let dst_g = unsafe { _mm256_srli_epi32(_mm256_and_si256(dst_pixels, mask_g), 8) };
-        let dst_b = unsafe { _mm256_srli_epi32(_mm256_and_si256(dst_pixels, mask_b), 16) };
-        let dst_a = unsafe { _mm256_srli_epi32(_mm256_and_si256(dst_pixels, mask_a), 24) };
-
-        let src_r = unsafe { _mm256_set1_epi32(src.red() as i32) };
-        let src_g = unsafe { _mm256_set1_epi32(src.green() as i32) };
-        let src_b = unsafe { _mm256_set1_epi32(src.blue() as i32) };
-        let src_a = unsafe { _mm256_set1_epi32(src.alpha() as i32) };
-
-        // Blend: result = (src * 255 + dst * inv_alpha) / 256
-        let blend = |s: __m256i, d: __m256i| -> __m256i {
-            let s_scaled = unsafe { _mm256_mullo_epi32(s, _mm256_set1_epi32(255)) };
-            let d_scaled = unsafe { _mm256_mullo_epi32(d, _mm256_set1_epi32(inv_alpha)) };
-            let sum = unsafe { _mm256_add_epi32(s_scaled, d_scaled) };
-            unsafe { _mm256_srli_epi32(sum, 8) }
-        };
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn fill_span_blend_avx2(dst: &mut [u8], src: Color) {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
 
-        let result_r = blend(src_r, dst_r);
-        let result_g = blend(src_g, dst_g);
-        let result_b = blend(src_b, dst_b);
-        let result_a = blend(src_a, dst_a);
-
-        // Recombine channels
-        let rg = unsafe { _mm256_or_si256(result_r, _mm256_slli_epi32(result_g, 8)) };
-        let ba = unsafe {
-            _mm256_or_si256(
-                _mm256_slli_epi32(result_b, 16),
-                _mm256_slli_epi32(result_a, 24),
-            )
+    let chunks = dst.len() / 32;
+    let ptr = dst.as_mut_ptr();
+
+    // SAFETY: every load and store touches 32 bytes inside `dst`.
+    unsafe {
+        let src_scaled = _mm256_set1_epi64x(scaled_src_lanes(src) as i64);
+        let inv_alpha = _mm256_set1_epi16(i16::from(255 - src.alpha()));
+        let one = _mm256_set1_epi16(1);
+        let zero = _mm256_setzero_si256();
+
+        // Same math as the SSE path. Unpack and pack both work within
+        // 128-bit lanes, so pixel order survives the round trip.
+        let blend = |d: __m256i| -> __m256i {
+            let sum = _mm256_add_epi16(src_scaled, _mm256_mullo_epi16(d, inv_alpha));
+            let biased = _mm256_add_epi16(sum, _mm256_add_epi16(one, _mm256_srli_epi16(sum, 8)));
+            _mm256_srli_epi16(biased, 8)
         };
-        let result = unsafe { _mm256_or_si256(rg, ba) };
 
-        // Store result
-        unsafe { _mm256_storeu_si256(dst_ptr as *mut __m256i, result) };
+        for i in 0..chunks {
+            let dst_ptr = ptr.add(i * 32) as *mut __m256i;
+            let pixels = _mm256_loadu_si256(dst_ptr);
+            let lo = blend(_mm256_unpacklo_epi8(pixels, zero));
+            let hi = blend(_mm256_unpackhi_epi8(pixels, zero));
+            _mm256_storeu_si256(dst_ptr, _mm256_packus_epi16(lo, hi));
+        }
     }
 
-    // Handle remainder
-    if remainder_start < dst.len() {
-        fill_span_blend_scalar(&mut dst[remainder_start..], src);
-    }
+    fill_span_blend_scalar(&mut dst[chunks * 32..], src);
 }
 
 // ============================================================================
@@ -376,58 +451,63 @@ unsafe fn fill_span_blend_avx2(dst: &mut [u8], src: Color) {
 
 #[cfg(target_arch = "aarch64")]
 #[target_feature(enable = "neon")]
-unsafe fn fill_span_blend_neon(dst: &mut [u8], src: Color) {
-    use std::arch::aarch64::*;
+unsafe fn fill_span_opaque_neon(dst: &mut [u8], pattern: [u8; 4]) {
+    use core::arch::aarch64::*;
 
-    let len = dst.len() / 4;
-    let chunks = len / 4;
-    let remainder_start = chunks * 16;
+    let chunks = dst.len() / 16;
+    let ptr = dst.as_mut_ptr();
 
-    // Prepare source color vectors
-    let src_r = unsafe { vdupq_n_u16(src.red() as u16) };
-    let src_g = unsafe { vdupq_n_u16(src.green() as u16) };
-    let src_b = unsafe { vdupq_n_u16(src.blue() as u16) };
-    let src_a = unsafe { vdupq_n_u16(src.alpha() as u16) };
-    let inv_alpha = unsafe { vdupq_n_u16((255 - src.alpha()) as u16) };
+    // SAFETY: every store writes 16 bytes inside `dst`.
+    unsafe {
+        let fill = vreinterpretq_u8_u32(vdupq_n_u32(u32::from_le_bytes(pattern)));
+        for i in 0..chunks {
+            vst1q_u8(ptr.add(i * 16), fill);
+        }
+    }
 
-    let ptr = dst.as_mut_ptr();
+    fill_span_opaque_scalar(&mut dst[chunks * 16..], pattern);
+}
 
-    for i in 0..chunks {
-        let offset = i * 16;
-        let dst_ptr = unsafe { ptr.add(offset) };
-
-        // Load 4 pixels, deinterleaved into separate R, G, B, A channels
-        let dst_rgba = unsafe { vld4q_u8(dst_ptr) };
-
-        // Widen to 16-bit for arithmetic
-        let dst_r_lo = unsafe { vmovl_u8(vget_low_u8(dst_rgba.0)) };
-        let dst_g_lo = unsafe { vmovl_u8(vget_low_u8(dst_rgba.1)) };
-        let dst_b_lo = unsafe { vmovl_u8(vget_low_u8(dst_rgba.2)) };
-        let dst_a_lo = unsafe { vmovl_u8(vget_low_u8(dst_rgba.3)) };
-
-        // SrcOver blend: result = (src * 255 + dst * inv_alpha) / 256
-        let blend = |s: uint16x8_t, d: uint16x8_t| -> uint8x8_t {
-            let s_scaled = unsafe { vmulq_n_u16(s, 255) };
-            let d_scaled = unsafe { vmulq_u16(d, inv_alpha) };
-            let sum = unsafe { vaddq_u16(s_scaled, d_scaled) };
-            let result = unsafe { vshrq_n_u16(sum, 8) };
-            unsafe { vmovn_u16(result) }
-        };
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn fill_span_blend_neon(dst: &mut [u8], src: Color) {
+    use core::arch::aarch64::*;
 
-        let result_r = blend(src_r, dst_r_lo);
-        let result_g = blend(src_g, dst_g_lo);
-        let result_b = blend(src_b, dst_b_lo);
-        let result_a = blend(src_a, dst_a_lo);
+    let chunks = dst.len() / 32;
+    let ptr = dst.as_mut_ptr();
+    let lanes = scaled_src_lanes(src);
+
+    // SAFETY: every load and store touches 32 bytes inside `dst`.
+    unsafe {
+        let src_r = vdupq_n_u16(lanes as u16);
+        let src_g = vdupq_n_u16((lanes >> 16) as u16);
+        let src_b = vdupq_n_u16((lanes >> 32) as u16);
+        let src_a = vdupq_n_u16((lanes >> 48) as u16);
+        let inv_alpha = vdupq_n_u16(u16::from(255 - src.alpha()));
+        let one = vdupq_n_u16(1);
+
+        // Eight pixels of one channel: (src * 255 + dst * inv_sa) / 255
+        let blend = |s: uint16x8_t, d: uint8x8_t| -> uint8x8_t {
+            let sum = vmlaq_u16(s, vmovl_u8(d), inv_alpha);
+            let biased = vaddq_u16(sum, vaddq_u16(one, vshrq_n_u16(sum, 8)));
+            vshrn_n_u16(biased, 8)
+        };
 
-        // Interleave and store
-        let result = uint8x8x4_t(result_r, result_g, result_b, result_a);
-        unsafe { vst4_u8(dst_ptr, result) };
+        for i in 0..chunks {
+            let dst_ptr = ptr.add(i * 32);
+            // Load 8 pixels, deinterleaved into separate R, G, B, A channels
+            let pixels = vld4_u8(dst_ptr);
+            let result = uint8x8x4_t(
+                blend(src_r, pixels.0),
+                blend(src_g, pixels.1),
+                blend(src_b, pixels.2),
+                blend(src_a, pixels.3),
+            );
+            vst4_u8(dst_ptr, result);
+        }
     }
 
-    // Handle remainder
-    if remainder_start < dst.len() {
-        fill_span_blend_scalar(&mut dst[remainder_start..], src);
-    }
+    fill_span_blend_scalar(&mut dst[chunks * 32..], src);
 }
 
 // ============================================================================
@@ -436,7 +516,8 @@ unsafe fn fill_span_blend_neon(dst: &mut [u8], src: Color) {
 
 /// Blend multiple source pixels onto destination pixels (SIMD-optimized).
 ///
-/// Both `src` and `dst` must have the same length (multiple of 4 bytes).
+/// Both `src` and `dst` must have the same length (multiple of 4 bytes)
+/// and hold premultiplied RGBA.
 #[inline]
 pub fn blend_pixels_src_over(dst: &mut [u8], src: &[u8]) {
     debug_assert_eq!(dst.len(), src.len());
@@ -565,6 +646,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fill_span_simd_matches_scalar() {
+        let detected = *simd_capabilities();
+        let variants = [
+            detected,
+            SimdCapabilities {
+                avx2: false,
+                ..detected
+            },
+        ];
+        let colors = [
+            Color::from_argb(128, 100, 20, 128),
+            Color::from_argb(1, 1, 0, 1),
+            Color::from_argb(254, 200, 254, 0),
+            Color::from_argb(255, 10, 20, 30),
+        ];
+
+        for num_pixels in 0..70 {
+            let background: Vec<u8> = (0..num_pixels * 4).map(|i| (i * 37 + 11) as u8).collect();
+            for color in colors {
+                let mut expected = background.clone();
+                fill_span_solid_with(&mut expected, color, &SimdCapabilities::scalar());
+
+                for caps in &variants {
+                    let mut actual = background.clone();
+                    fill_span_solid_with(&mut actual, color, caps);
+                    assert_eq!(
+                        actual, expected,
+                        "{num_pixels} pixels of {color:?} with {caps:?}"
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_blend_pixels_src_over() {
         let mut dst = vec![100u8; 16];
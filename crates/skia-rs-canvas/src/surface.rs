@@ -1,12 +1,24 @@
 //! Surface backing store for canvas.
 
-use crate::Canvas;
-use crate::raster::PixelBuffer;
 #[cfg(feature = "codec")]
-use skia_rs_codec::Image;
+use crate::RSXform;
+use crate::clip::ClipMask;
+use crate::raster::{PixelBuffer, Rasterizer};
+use crate::{ClipOp, Picture, SaveLayerRec};
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+#[cfg(feature = "codec")]
+use skia_rs_codec::{CodecError, CodecResult, EncoderQuality, Image, ImageFormat};
+#[cfg(feature = "codec")]
+use skia_rs_core::AlphaType;
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 use skia_rs_core::pixel::{ImageInfo, SurfaceProps};
-use skia_rs_core::{AlphaType, Color, ColorType, IRect, Matrix, Point, Rect, Region, Scalar};
-use skia_rs_paint::{BlendMode, Paint};
+use skia_rs_core::{
+    Color, ColorType, IPoint, IRect, Matrix, Point, RRect, Rect, Region, RegionOp, Scalar,
+};
+use skia_rs_paint::{BlendMode, ColorFilterRef, FilterImage, ImageFilter, ImageFilterRef, Paint};
+#[cfg(feature = "codec")]
+use skia_rs_paint::{ImageShader, SamplingOptions, Shader, TileMode};
 use skia_rs_path::Path;
 
 /// A surface is a backing store for a canvas.
@@ -14,7 +26,9 @@ pub struct Surface {
     info: ImageInfo,
     #[allow(dead_code)]
     props: SurfaceProps,
-    buffer: PixelBuffer,
+    /// Shared with snapshots until the next write, which copies it if any
+    /// snapshot is still alive.
+    buffer: Arc<PixelBuffer>,
 }
 
 impl Surface {
@@ -24,12 +38,18 @@ impl Surface {
             return None;
         }
 
-        let buffer = PixelBuffer::new(info.width(), info.height());
+        // 16-bit surfaces store packed pixels; everything else renders as RGBA8888.
+        let buffer = match info.color_type {
+            ColorType::Rgb565 | ColorType::Argb4444 => {
+                PixelBuffer::with_color_type(info.width(), info.height(), info.color_type)?
+            }
+            _ => PixelBuffer::new(info.width(), info.height()),
+        };
 
         Some(Self {
             info: info.clone(),
             props: props.copied().unwrap_or_default(),
-            buffer,
+            buffer: Arc::new(buffer),
         })
     }
 
@@ -59,14 +79,38 @@ impl Surface {
         self.info.height()
     }
 
-    /// Get a canvas for drawing (without pixel access).
-    pub fn canvas(&self) -> Canvas {
-        Canvas::new(self.info.width(), self.info.height())
+    /// Fill `rect`, or the whole surface if `None`, with a solid color.
+    ///
+    /// The pixels are overwritten rather than blended, which makes this
+    /// cheaper than drawing a rect when clearing part of a frame.
+    pub fn erase(&mut self, color: Color, rect: Option<&IRect>) {
+        match rect {
+            Some(rect) => self.buffer_mut().erase(color, rect),
+            None => self.buffer_mut().clear(color),
+        }
+    }
+
+    /// Get a canvas that draws into this surface.
+    ///
+    /// The canvas borrows the surface, so drop it before reading pixels
+    /// back. Same as [`raster_canvas`](Self::raster_canvas).
+    pub fn canvas(&mut self) -> RasterCanvas<'_> {
+        self.raster_canvas()
     }
 
     /// Get a raster canvas that can actually draw pixels.
     pub fn raster_canvas(&mut self) -> RasterCanvas<'_> {
-        RasterCanvas::new(&mut self.buffer)
+        RasterCanvas::new(self.buffer_mut())
+    }
+
+    /// Get a raster canvas whose draws and clears only touch `region`.
+    ///
+    /// Useful for repainting just the dirty parts of a frame. See
+    /// [`RasterCanvas::clip_region`].
+    pub fn with_clip_region(&mut self, region: &Region) -> RasterCanvas<'_> {
+        let mut canvas = self.raster_canvas();
+        canvas.clip_region(region);
+        canvas
     }
 
     /// Get access to the pixel data.
@@ -76,7 +120,7 @@ impl Surface {
 
     /// Get mutable access to the pixel data.
     pub fn pixels_mut(&mut self) -> &mut [u8] {
-        &mut self.buffer.pixels
+        &mut self.buffer_mut().pixels
     }
 
     /// Get the row bytes.
@@ -91,26 +135,73 @@ impl Surface {
 
     /// Get mutable pixel buffer.
     pub fn pixel_buffer_mut(&mut self) -> &mut PixelBuffer {
-        &mut self.buffer
+        self.buffer_mut()
+    }
+
+    /// The pixel buffer for writing, copied first if a snapshot shares it.
+    fn buffer_mut(&mut self) -> &mut PixelBuffer {
+        Arc::make_mut(&mut self.buffer)
+    }
+
+    /// Capture the surface's current pixels as an immutable image.
+    ///
+    /// No pixels are copied here: the image shares the surface's buffer,
+    /// and the surface copies it on its next write (through a canvas,
+    /// [`erase`](Self::erase), [`pixels_mut`](Self::pixels_mut), ...) if the
+    /// image is still alive. Later drawing never shows up in the image.
+    ///
+    /// Returns `None` if the surface's buffer can't back an image.
+    #[cfg(feature = "codec")]
+    pub fn snapshot(&self) -> Option<Image> {
+        // Describe the buffer as it's actually laid out: surfaces render
+        // most color types (F16, BGRA, ...) into unpremultiplied RGBA8888.
+        let codec_info = skia_rs_codec::ImageInfo::new(
+            self.buffer.width,
+            self.buffer.height,
+            self.buffer.color_type,
+            self.snapshot_alpha_type(),
+        );
+
+        let pixels: skia_rs_codec::SharedPixels = self.buffer.clone();
+        Image::from_raster_data_shared(codec_info, pixels, self.buffer.stride)
     }
 
     /// Create a snapshot of the surface as an immutable image.
     ///
-    /// The returned image shares pixel data with the surface when possible.
+    /// Same as [`snapshot`](Self::snapshot); kept for Skia naming
+    /// compatibility.
     #[cfg(feature = "codec")]
     pub fn make_image_snapshot(&self) -> Option<Image> {
-        let pixels = self.buffer.pixels.clone();
-        let row_bytes = self.buffer.stride;
+        self.snapshot()
+    }
 
-        // Convert core ImageInfo to codec ImageInfo
-        let codec_info = skia_rs_codec::ImageInfo::new(
-            self.info.width(),
-            self.info.height(),
-            self.info.color_type,
-            self.info.alpha_type,
-        );
+    /// Alpha type of the pixel buffer, which holds unpremultiplied colors
+    /// whatever the surface's own alpha type.
+    #[cfg(feature = "codec")]
+    fn snapshot_alpha_type(&self) -> AlphaType {
+        if self.info.is_opaque() {
+            AlphaType::Opaque
+        } else {
+            AlphaType::Unpremul
+        }
+    }
 
-        Image::from_raster_data_owned(codec_info, pixels, row_bytes)
+    /// Encode the surface's current pixels in the given format.
+    #[cfg(feature = "codec")]
+    pub fn encode(
+        &self,
+        format: ImageFormat,
+        quality: Option<EncoderQuality>,
+    ) -> CodecResult<Vec<u8>> {
+        self.make_image_snapshot()
+            .ok_or_else(|| CodecError::EncodingError("Failed to snapshot surface".into()))?
+            .encode(format, quality)
+    }
+
+    /// Encode the surface's current pixels as PNG.
+    #[cfg(feature = "codec")]
+    pub fn encode_to_png(&self) -> CodecResult<Vec<u8>> {
+        self.encode(ImageFormat::Png, None)
     }
 
     /// Create a snapshot of a subset of the surface.
@@ -146,8 +237,12 @@ impl Surface {
             }
         }
 
-        let info =
-            skia_rs_codec::ImageInfo::new(width, height, ColorType::Rgba8888, AlphaType::Premul);
+        let info = skia_rs_codec::ImageInfo::new(
+            width,
+            height,
+            ColorType::Rgba8888,
+            self.snapshot_alpha_type(),
+        );
         Image::from_raster_data_owned(info, pixels, row_bytes)
     }
 }
@@ -239,12 +334,107 @@ pub trait GpuSurface: Send + Sync {
     fn make_image_snapshot(&self) -> Option<Image>;
 }
 
+/// Draw statistics gathered by a [`RasterCanvas`].
+///
+/// Each public draw call counts once, however many primitives it is built
+/// from; a nine-patch is one image and a region is one rect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawStats {
+    /// Rectangles and regions drawn.
+    pub rects: usize,
+    /// Paths and arcs drawn.
+    pub paths: usize,
+    /// Images drawn.
+    pub images: usize,
+    /// Strings and text blobs drawn.
+    pub text: usize,
+    /// Other draws: clears, colors, points, lines, ovals, circles, rounded
+    /// rectangles and vertices.
+    pub other: usize,
+    /// Draws skipped because their paint draws nothing.
+    pub skipped: usize,
+    /// Pixel writes issued. Overlapping draws count every write, so this
+    /// exceeds the canvas area when there is overdraw.
+    pub pixels_touched: u64,
+}
+
+impl DrawStats {
+    /// Total number of draws issued, including skipped ones.
+    #[inline]
+    pub fn total_draws(&self) -> usize {
+        self.rects + self.paths + self.images + self.text + self.other + self.skipped
+    }
+}
+
+/// An offscreen layer started by [`RasterCanvas::save_layer`].
+struct Layer {
+    /// Pixels drawn since the layer was saved.
+    buffer: PixelBuffer,
+    /// Device position of the buffer's top-left pixel.
+    origin: IPoint,
+    /// Opacity the layer is composited with.
+    alpha: Scalar,
+    /// Blend mode the layer is composited with.
+    blend_mode: BlendMode,
+    /// Color filter applied to the layer's pixels as it is composited.
+    color_filter: Option<ColorFilterRef>,
+    /// Image filter applied to the layer's pixels before the color filter.
+    image_filter: Option<ImageFilterRef>,
+    /// Save count whose restore composites the layer.
+    save_count: usize,
+}
+
+/// Run a layer's pixels through an image filter.
+///
+/// Returns the filtered pixels and the device position of their top-left
+/// corner, or `None` if the filter has no CPU implementation.
+fn filter_layer(filter: &dyn ImageFilter, layer: &Layer) -> Option<(PixelBuffer, IPoint)> {
+    let buffer = &layer.buffer;
+    let pixels = (0..buffer.height)
+        .flat_map(|y| (0..buffer.width).map(move |x| (x, y)))
+        .map(|(x, y)| buffer.get_pixel(x, y).unwrap().to_color4f().premul())
+        .collect();
+    let src = FilterImage::from_pixels(layer.origin, buffer.width, buffer.height, pixels)?;
+    let out = filter.filter_image(&src)?;
+
+    let mut filtered = PixelBuffer::new(out.width(), out.height());
+    for (i, pixel) in out.pixels().iter().enumerate() {
+        let (x, y) = (i as i32 % out.width(), i as i32 / out.width());
+        filtered.set_pixel(x, y, pixel.unpremul().to_color());
+    }
+    Some((filtered, out.origin()))
+}
+
+/// Mix two unpremultiplied colors in premultiplied space, taking
+/// `coverage / 255` of `src`.
+fn mix_by_coverage(dst: Color, src: Color, coverage: u8) -> Color {
+    let (dst, src) = (dst.premultiply(), src.premultiply());
+    let cov = u32::from(coverage);
+    let mix = |d: u8, s: u8| ((u32::from(s) * cov + u32::from(d) * (255 - cov) + 127) / 255) as u8;
+    Color::from_argb(
+        mix(dst.alpha(), src.alpha()),
+        mix(dst.red(), src.red()),
+        mix(dst.green(), src.green()),
+        mix(dst.blue(), src.blue()),
+    )
+    .unpremultiply()
+}
+
 /// A canvas that draws directly to a pixel buffer.
 pub struct RasterCanvas<'a> {
     buffer: &'a mut PixelBuffer,
     matrix_stack: Vec<Matrix>,
     clip_stack: Vec<Rect>,
+    clip_regions: Vec<Option<Region>>,
+    clip_masks: Vec<Option<ClipMask>>,
+    layers: Vec<Layer>,
     save_count: usize,
+    stats: DrawStats,
+    /// The last image drawn and a shader over all of its pixels, so drawing
+    /// parts of the same image again doesn't convert them again. Holding the
+    /// image keeps its unique ID from being reused.
+    #[cfg(feature = "codec")]
+    image_shader: Option<(Image, ImageShader)>,
 }
 
 impl<'a> RasterCanvas<'a> {
@@ -255,7 +445,13 @@ impl<'a> RasterCanvas<'a> {
             buffer,
             matrix_stack: vec![Matrix::IDENTITY],
             clip_stack: vec![clip],
+            clip_regions: vec![None],
+            clip_masks: vec![None],
+            layers: Vec::new(),
             save_count: 1,
+            stats: DrawStats::default(),
+            #[cfg(feature = "codec")]
+            image_shader: None,
         }
     }
 
@@ -271,6 +467,12 @@ impl<'a> RasterCanvas<'a> {
         self.buffer.height
     }
 
+    /// Get the current save count.
+    #[inline]
+    pub fn save_count(&self) -> usize {
+        self.save_count
+    }
+
     /// Get the current transformation matrix.
     #[inline]
     pub fn total_matrix(&self) -> &Matrix {
@@ -283,25 +485,227 @@ impl<'a> RasterCanvas<'a> {
         self.clip_stack.last().copied().unwrap_or(Rect::EMPTY)
     }
 
+    /// Get the number of draws skipped because their paint draws nothing.
+    ///
+    /// See [`Paint::nothing_to_draw`].
+    #[inline]
+    pub fn skipped_draws(&self) -> usize {
+        self.stats.skipped
+    }
+
+    /// Get the draw statistics gathered since creation or the last
+    /// [`reset_draw_stats`](Self::reset_draw_stats).
+    #[inline]
+    pub fn draw_stats(&self) -> DrawStats {
+        self.stats
+    }
+
+    /// Reset the draw statistics to zero.
+    pub fn reset_draw_stats(&mut self) {
+        self.stats = DrawStats::default();
+    }
+
+    /// Check whether a draw with `paint` can be skipped, counting it if so.
+    fn skip_draw(&mut self, paint: &Paint) -> bool {
+        let skip = paint.nothing_to_draw();
+        if skip {
+            self.stats.skipped += 1;
+        }
+        skip
+    }
+
+    /// The buffer draws currently land in, either the innermost layer or
+    /// the canvas itself, and the device position of its top-left pixel.
+    fn target(&mut self) -> (&mut PixelBuffer, IPoint) {
+        match self.layers.last_mut() {
+            Some(layer) => (&mut layer.buffer, layer.origin),
+            None => (&mut *self.buffer, IPoint::new(0, 0)),
+        }
+    }
+
+    /// Run `draw` on a rasterizer set up with the current matrix and clip,
+    /// counting the pixels it writes.
+    fn rasterize(&mut self, draw: impl FnOnce(&mut Rasterizer<'_>)) {
+        let matrix = *self.total_matrix();
+        let clip = self.clip_bounds();
+        let region = self.clip_regions.last().cloned().flatten();
+        let mask = self.clip_masks.last().cloned().flatten();
+
+        // Layers are drawn in their own pixel space.
+        let (buffer, origin) = self.target();
+        let (dx, dy) = (-origin.x as Scalar, -origin.y as Scalar);
+
+        let mut rasterizer = Rasterizer::new(buffer);
+        rasterizer.set_matrix(&Matrix::translate(dx, dy).concat(&matrix));
+        rasterizer.set_clip(clip.offset(dx, dy));
+        if let Some(mut region) = region {
+            region.translate(-origin.x, -origin.y);
+            rasterizer.clip_region(&region);
+        }
+        if let Some(mut mask) = mask {
+            mask.offset(-origin.x, -origin.y);
+            rasterizer.clip_mask(mask);
+        }
+        draw(&mut rasterizer);
+        let written = rasterizer.pixels_written();
+        self.stats.pixels_touched += written;
+    }
+
+    /// Blend a pixel at device position `(x, y)` into the current layer,
+    /// counting the write.
+    ///
+    /// Pixels outside the clip region are skipped and the color's alpha is
+    /// scaled by the clip mask coverage, if there is a mask; the caller is
+    /// responsible for the clip bounds.
+    #[inline]
+    fn blend_pixel(&mut self, x: i32, y: i32, color: Color, blend_mode: BlendMode) {
+        if let Some(Some(region)) = self.clip_regions.last() {
+            if !region.contains(x, y) {
+                return;
+            }
+        }
+        let coverage = match self.clip_masks.last() {
+            Some(Some(mask)) => mask.get_coverage_device(x, y),
+            _ => 255,
+        };
+        let color = match coverage {
+            0 => return,
+            255 => color,
+            _ => {
+                let alpha = (u32::from(color.alpha()) * u32::from(coverage) / 255) as u8;
+                Color::from_argb(alpha, color.red(), color.green(), color.blue())
+            }
+        };
+
+        self.blend_pixel_unclipped(x, y, color, blend_mode);
+    }
+
+    /// Blend a pixel at device position `(x, y)` into the current layer
+    /// without consulting the clip mask, counting the write.
+    #[inline]
+    fn blend_pixel_unclipped(&mut self, x: i32, y: i32, color: Color, blend_mode: BlendMode) {
+        let (buffer, origin) = self.target();
+        let (x, y) = (x - origin.x, y - origin.y);
+        if buffer.contains(x, y) {
+            buffer.blend_pixel(x, y, color, blend_mode);
+            self.stats.pixels_touched += 1;
+        }
+    }
+
     /// Save the current state.
     pub fn save(&mut self) -> usize {
         let matrix = *self.matrix_stack.last().unwrap();
         let clip = *self.clip_stack.last().unwrap();
+        let region = self.clip_regions.last().unwrap().clone();
+        let mask = self.clip_masks.last().unwrap().clone();
         self.matrix_stack.push(matrix);
         self.clip_stack.push(clip);
+        self.clip_regions.push(region);
+        self.clip_masks.push(mask);
         self.save_count += 1;
         self.save_count
     }
 
+    /// Save the current state and redirect drawing into an offscreen layer.
+    ///
+    /// The layer covers `rec.bounds` mapped by the current matrix, or the
+    /// clip if there are no bounds, and starts out transparent. The matching
+    /// [`restore`](Self::restore) composites it back with the image filter,
+    /// alpha, blend mode and color filter of `rec.paint`, so overlapping
+    /// draws inside the layer fade or blend as a group instead of one by
+    /// one. Image filter output may extend past the layer bounds, up to the
+    /// clip.
+    pub fn save_layer(&mut self, rec: &SaveLayerRec<'_>) -> usize {
+        let save_count = self.save();
+
+        let mut bounds = self.clip_bounds();
+        if let Some(rect) = rec.bounds {
+            let rect = self.total_matrix().map_rect(rect);
+            bounds = bounds.intersect(&rect).unwrap_or(Rect::EMPTY);
+        }
+        let bounds = if bounds.is_empty() {
+            IRect::empty()
+        } else {
+            bounds.round_out()
+        };
+        if let Some(clip) = self.clip_stack.last_mut() {
+            *clip = clip.intersect(&bounds.to_rect()).unwrap_or(Rect::EMPTY);
+        }
+
+        self.layers.push(Layer {
+            buffer: PixelBuffer::new(bounds.width(), bounds.height()),
+            origin: IPoint::new(bounds.left, bounds.top),
+            alpha: rec.paint.map_or(1.0, Paint::alpha),
+            blend_mode: rec.paint.map_or(BlendMode::SrcOver, Paint::blend_mode),
+            color_filter: rec.paint.and_then(|paint| paint.color_filter().cloned()),
+            image_filter: rec.paint.and_then(|paint| paint.image_filter().cloned()),
+            save_count,
+        });
+        save_count
+    }
+
     /// Restore to the previous state.
+    ///
+    /// Restoring a [`save_layer`](Self::save_layer) composites the layer
+    /// onto the buffer below it.
     pub fn restore(&mut self) {
         if self.save_count > 1 {
             self.matrix_stack.pop();
             self.clip_stack.pop();
+            self.clip_regions.pop();
+            self.clip_masks.pop();
+            if self
+                .layers
+                .last()
+                .is_some_and(|layer| layer.save_count == self.save_count)
+            {
+                let layer = self.layers.pop().unwrap();
+                self.composite_layer(&layer);
+            }
             self.save_count -= 1;
         }
     }
 
+    /// Blend a finished layer onto the buffer below it.
+    fn composite_layer(&mut self, layer: &Layer) {
+        let filtered = layer
+            .image_filter
+            .as_ref()
+            .and_then(|filter| filter_layer(filter.as_ref(), layer));
+        let (buffer, origin) = match &filtered {
+            Some((buffer, origin)) => (buffer, *origin),
+            None => (&layer.buffer, layer.origin),
+        };
+        // Filter output can spill past the layer, but not past the clip.
+        // Unfiltered layer pixels already went through the clip mask while
+        // they were drawn, so masking them again would square the coverage
+        // of anti-aliased clip edges.
+        let clip = self.clip_bounds().round_out();
+        for y in 0..buffer.height {
+            for x in 0..buffer.width {
+                let (dx, dy) = (origin.x + x, origin.y + y);
+                if dx < clip.left || dx >= clip.right || dy < clip.top || dy >= clip.bottom {
+                    continue;
+                }
+                let mut color = buffer.get_pixel(x, y).unwrap();
+                if let Some(filter) = &layer.color_filter {
+                    color = filter.filter_color(color.to_color4f()).to_color();
+                }
+                let alpha = (color.alpha() as Scalar * layer.alpha).round() as u8;
+                // Transparent pixels only matter to modes that can erase.
+                if alpha == 0 && layer.blend_mode == BlendMode::SrcOver {
+                    continue;
+                }
+                let color = Color::from_argb(alpha, color.red(), color.green(), color.blue());
+                if filtered.is_some() {
+                    self.blend_pixel(dx, dy, color, layer.blend_mode);
+                } else {
+                    self.blend_pixel_unclipped(dx, dy, color, layer.blend_mode);
+                }
+            }
+        }
+    }
+
     /// Restore to a specific save count.
     pub fn restore_to_count(&mut self, count: usize) {
         while self.save_count > count {
@@ -323,11 +727,17 @@ impl<'a> RasterCanvas<'a> {
 
     /// Rotate the canvas (angle in degrees).
     pub fn rotate(&mut self, degrees: Scalar) {
-        let radians = degrees * std::f32::consts::PI / 180.0;
+        let radians = degrees * core::f32::consts::PI / 180.0;
         let matrix = Matrix::rotate(radians);
         self.concat(&matrix);
     }
 
+    /// Skew the canvas.
+    pub fn skew(&mut self, sx: Scalar, sy: Scalar) {
+        let matrix = Matrix::skew(sx, sy);
+        self.concat(&matrix);
+    }
+
     /// Concatenate a matrix.
     pub fn concat(&mut self, matrix: &Matrix) {
         if let Some(current) = self.matrix_stack.last_mut() {
@@ -354,130 +764,253 @@ impl<'a> RasterCanvas<'a> {
         }
     }
 
-    /// Clear the canvas with a color.
-    pub fn clear(&mut self, color: Color) {
-        self.buffer.clear(color);
+    /// Clip to a region.
+    ///
+    /// The region is in device pixels and is not affected by the current
+    /// matrix. The result is intersected with the existing clip, and like
+    /// every clip it is undone by [`restore`](Self::restore).
+    pub fn clip_region(&mut self, region: &Region) {
+        let bounds = region.bounds().to_rect();
+        if let Some(current) = self.clip_stack.last_mut() {
+            *current = current.intersect(&bounds).unwrap_or(Rect::EMPTY);
+        }
+        // A single rectangle is fully described by the clip bounds.
+        if region.is_rect() || region.is_empty() {
+            return;
+        }
+        if let Some(current) = self.clip_regions.last_mut() {
+            match current {
+                Some(current) => {
+                    current.op_region(region, RegionOp::Intersect);
+                }
+                None => *current = Some(region.clone()),
+            }
+        }
     }
 
-    /// Draw a color over the entire canvas.
-    pub fn draw_color(&mut self, color: Color, blend_mode: BlendMode) {
-        let matrix = *self.total_matrix();
+    /// Clip to a path.
+    ///
+    /// The path is mapped by the current matrix and turned into a coverage
+    /// mask, with soft edges if `anti_alias` is set. [`ClipOp::Intersect`]
+    /// keeps drawing inside the path and [`ClipOp::Difference`] keeps it
+    /// outside; either way the result is intersected with the existing clip.
+    ///
+    /// Rasterized draws multiply their coverage by the mask, so drawing after
+    /// an anti-aliased clip gets smooth edges.
+    pub fn clip_path(&mut self, path: &Path, op: ClipOp, anti_alias: bool) {
+        let path = path.transformed(self.total_matrix());
+        let device = IRect::new(0, 0, self.buffer.width, self.buffer.height);
+        let mask = if anti_alias {
+            ClipMask::from_path_aa(&path, &device)
+        } else {
+            ClipMask::from_path(&path, &device)
+        };
+
+        let mask = match op {
+            ClipOp::Intersect => {
+                let bounds = mask.bounds().to_rect();
+                if let Some(current) = self.clip_stack.last_mut() {
+                    *current = current.intersect(&bounds).unwrap_or(Rect::EMPTY);
+                }
+                mask
+            }
+            ClipOp::Difference => mask.inverted(&device),
+        };
+
+        if let Some(current) = self.clip_masks.last_mut() {
+            match current {
+                Some(current) => current.intersect(&mask),
+                None => *current = Some(mask),
+            }
+        }
+    }
+
+    /// Clear the canvas, or the current layer, with a color.
+    ///
+    /// Pixels are replaced rather than blended, but only inside the current
+    /// clip; partially covered pixels of an anti-aliased clip are mixed
+    /// with the new color by their coverage.
+    pub fn clear(&mut self, color: Color) {
+        self.stats.other += 1;
         let clip = self.clip_bounds();
-        let width = self.width();
-        let height = self.height();
+        let region = self.clip_regions.last().cloned().flatten();
+        let mask = self.clip_masks.last().cloned().flatten();
+
+        let (buffer, origin) = self.target();
+        let local = clip
+            .offset(-origin.x as Scalar, -origin.y as Scalar)
+            .round_out()
+            .intersect(&IRect::new(0, 0, buffer.width, buffer.height));
+        let Some(local) = local else {
+            return;
+        };
 
-        let mut rasterizer = crate::raster::Rasterizer::new(self.buffer);
-        rasterizer.set_matrix(&matrix);
-        rasterizer.set_clip(clip);
+        let mut pixels = 0u64;
+        match (mask, region) {
+            (None, Some(region)) => {
+                for rect in region.iter() {
+                    let rect = IRect::new(
+                        rect.left - origin.x,
+                        rect.top - origin.y,
+                        rect.right - origin.x,
+                        rect.bottom - origin.y,
+                    );
+                    if let Some(rect) = rect.intersect(&local) {
+                        buffer.erase(color, &rect);
+                        pixels += rect.width() as u64 * rect.height() as u64;
+                    }
+                }
+            }
+            (None, None) => {
+                if local == IRect::new(0, 0, buffer.width, buffer.height) {
+                    buffer.clear(color);
+                } else {
+                    buffer.erase(color, &local);
+                }
+                pixels = local.width() as u64 * local.height() as u64;
+            }
+            (Some(mask), region) => {
+                for y in local.top..local.bottom {
+                    for x in local.left..local.right {
+                        let (dx, dy) = (x + origin.x, y + origin.y);
+                        if region.as_ref().is_some_and(|r| !r.contains(dx, dy)) {
+                            continue;
+                        }
+                        let coverage = mask.get_coverage_device(dx, dy);
+                        let cleared = match coverage {
+                            0 => continue,
+                            255 => color,
+                            _ => {
+                                let dst = buffer.get_pixel(x, y).unwrap_or(Color::TRANSPARENT);
+                                mix_by_coverage(dst, color, coverage)
+                            }
+                        };
+                        buffer.set_pixel(x, y, cleared);
+                        pixels += 1;
+                    }
+                }
+            }
+        }
+        self.stats.pixels_touched += pixels;
+    }
 
+    /// Draw a color over the entire canvas.
+    pub fn draw_color(&mut self, color: Color, blend_mode: BlendMode) {
         let mut paint = Paint::new();
         paint.set_color32(color);
         paint.set_blend_mode(blend_mode);
+        if self.skip_draw(&paint) {
+            return;
+        }
+        self.stats.other += 1;
 
-        let rect = Rect::from_xywh(0.0, 0.0, width as Scalar, height as Scalar);
-        rasterizer.fill_rect(&rect, &paint);
+        let rect = Rect::from_xywh(0.0, 0.0, self.width() as Scalar, self.height() as Scalar);
+        self.rasterize(|rasterizer| rasterizer.fill_rect(&rect, &paint));
     }
 
     /// Draw a point.
     pub fn draw_point(&mut self, point: Point, paint: &Paint) {
-        let matrix = *self.total_matrix();
-        let clip = self.clip_bounds();
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.other += 1;
 
-        let mut rasterizer = crate::raster::Rasterizer::new(self.buffer);
-        rasterizer.set_matrix(&matrix);
-        rasterizer.set_clip(clip);
-        rasterizer.draw_point(point, paint);
+        self.rasterize(|rasterizer| rasterizer.draw_point(point, paint));
     }
 
     /// Draw a line.
     pub fn draw_line(&mut self, p0: Point, p1: Point, paint: &Paint) {
-        let matrix = *self.total_matrix();
-        let clip = self.clip_bounds();
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.other += 1;
 
-        let mut rasterizer = crate::raster::Rasterizer::new(self.buffer);
-        rasterizer.set_matrix(&matrix);
-        rasterizer.set_clip(clip);
-        rasterizer.draw_line(p0, p1, paint);
+        self.rasterize(|rasterizer| rasterizer.draw_line(p0, p1, paint));
     }
 
     /// Draw a rectangle.
     pub fn draw_rect(&mut self, rect: &Rect, paint: &Paint) {
-        let matrix = *self.total_matrix();
-        let clip = self.clip_bounds();
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.rects += 1;
 
-        let mut rasterizer = crate::raster::Rasterizer::new(self.buffer);
-        rasterizer.set_matrix(&matrix);
-        rasterizer.set_clip(clip);
-        rasterizer.draw_rect(rect, paint);
+        self.rasterize(|rasterizer| rasterizer.draw_rect(rect, paint));
     }
 
     /// Draw an oval.
     pub fn draw_oval(&mut self, rect: &Rect, paint: &Paint) {
-        let matrix = *self.total_matrix();
-        let clip = self.clip_bounds();
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.other += 1;
 
-        let mut rasterizer = crate::raster::Rasterizer::new(self.buffer);
-        rasterizer.set_matrix(&matrix);
-        rasterizer.set_clip(clip);
-        rasterizer.draw_oval(rect, paint);
+        self.rasterize(|rasterizer| rasterizer.draw_oval(rect, paint));
     }
 
     /// Draw a circle.
     pub fn draw_circle(&mut self, center: Point, radius: Scalar, paint: &Paint) {
-        let matrix = *self.total_matrix();
-        let clip = self.clip_bounds();
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.other += 1;
 
-        let mut rasterizer = crate::raster::Rasterizer::new(self.buffer);
-        rasterizer.set_matrix(&matrix);
-        rasterizer.set_clip(clip);
-        rasterizer.draw_circle(center, radius, paint);
+        self.rasterize(|rasterizer| rasterizer.draw_circle(center, radius, paint));
     }
 
     /// Draw a rounded rectangle.
     pub fn draw_round_rect(&mut self, rect: &Rect, rx: Scalar, ry: Scalar, paint: &Paint) {
-        // Approximate with path
-        use skia_rs_path::PathBuilder;
-
-        let mut builder = PathBuilder::new();
-
-        // Start at top-left after corner
-        builder.move_to(rect.left + rx, rect.top);
-
-        // Top edge
-        builder.line_to(rect.right - rx, rect.top);
-        // Top-right corner
-        builder.quad_to(rect.right, rect.top, rect.right, rect.top + ry);
-
-        // Right edge
-        builder.line_to(rect.right, rect.bottom - ry);
-        // Bottom-right corner
-        builder.quad_to(rect.right, rect.bottom, rect.right - rx, rect.bottom);
-
-        // Bottom edge
-        builder.line_to(rect.left + rx, rect.bottom);
-        // Bottom-left corner
-        builder.quad_to(rect.left, rect.bottom, rect.left, rect.bottom - ry);
-
-        // Left edge
-        builder.line_to(rect.left, rect.top + ry);
-        // Top-left corner
-        builder.quad_to(rect.left, rect.top, rect.left + rx, rect.top);
+        self.draw_rrect(&RRect::from_rect_xy(*rect, rx, ry), paint);
+    }
 
-        builder.close();
-        let path = builder.build();
+    /// Draw a rounded rectangle with per-corner radii.
+    ///
+    /// Corners are anti-aliased when the paint is, and shader fills are
+    /// supported.
+    pub fn draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.other += 1;
 
-        self.draw_path(&path, paint);
+        self.rasterize(|rasterizer| rasterizer.draw_rrect(rrect, paint));
     }
 
     /// Draw a path.
     pub fn draw_path(&mut self, path: &Path, paint: &Paint) {
-        let matrix = *self.total_matrix();
-        let clip = self.clip_bounds();
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.paths += 1;
+
+        self.rasterize(|rasterizer| rasterizer.draw_path(path, paint));
+    }
 
-        let mut rasterizer = crate::raster::Rasterizer::new(self.buffer);
-        rasterizer.set_matrix(&matrix);
-        rasterizer.set_clip(clip);
-        rasterizer.draw_path(path, paint);
+    /// Fill a path with one paint and stroke it with another in a single
+    /// draw.
+    ///
+    /// The stroke is composited over the fill without a seam between them.
+    /// The paints' own styles are ignored, even when only one of them draws.
+    pub fn draw_path_filled_and_stroked(&mut self, path: &Path, fill: &Paint, stroke: &Paint) {
+        match (fill.nothing_to_draw(), stroke.nothing_to_draw()) {
+            (true, true) => self.stats.skipped += 1,
+            (false, true) => {
+                let mut fill = fill.clone();
+                fill.set_style(skia_rs_paint::Style::Fill);
+                self.draw_path(path, &fill);
+            }
+            (true, false) => {
+                let mut stroke = stroke.clone();
+                stroke.set_style(skia_rs_paint::Style::Stroke);
+                self.draw_path(path, &stroke);
+            }
+            (false, false) => {
+                self.stats.paths += 1;
+                self.rasterize(|rasterizer| {
+                    rasterizer.draw_path_filled_and_stroked(path, fill, stroke)
+                });
+            }
+        }
     }
 
     /// Draw an arc.
@@ -534,100 +1067,199 @@ impl<'a> RasterCanvas<'a> {
     /// Draw an image at the specified position.
     #[cfg(feature = "codec")]
     pub fn draw_image(&mut self, image: &Image, left: Scalar, top: Scalar, paint: Option<&Paint>) {
-        let src_rect = IRect::new(0, 0, image.width(), image.height());
         let dst_rect =
             Rect::from_xywh(left, top, image.width() as Scalar, image.height() as Scalar);
-        self.draw_image_rect(image, Some(&src_rect), &dst_rect, paint);
+        self.draw_image_rect(image, None, &dst_rect, SamplingOptions::NEAREST, paint);
     }
 
-    /// Draw an image with source and destination rectangles.
+    /// Draw the `src` part of an image, or all of it, scaled into `dst`.
+    ///
+    /// `dst` is transformed by the current matrix and clipped. `sampling`
+    /// selects nearest or bilinear filtering; bilinear samples clamp to the
+    /// edge of `src` rather than reading neighboring pixels. The paint's
+    /// color filter, alpha and blend mode apply.
     #[cfg(feature = "codec")]
     pub fn draw_image_rect(
         &mut self,
         image: &Image,
-        src: Option<&IRect>,
+        src: Option<&Rect>,
         dst: &Rect,
+        sampling: SamplingOptions,
         paint: Option<&Paint>,
     ) {
-        let src_rect = src
-            .cloned()
-            .unwrap_or_else(|| IRect::new(0, 0, image.width(), image.height()));
+        if paint.is_some_and(|paint| self.skip_draw(paint)) {
+            return;
+        }
+        self.stats.images += 1;
 
-        // Apply current transformation to destination
-        let matrix = *self.total_matrix();
-        let transformed_dst = matrix.map_rect(dst);
+        let bounds = Rect::from_xywh(0.0, 0.0, image.width() as Scalar, image.height() as Scalar);
+        self.blit_image_rect(image, src.unwrap_or(&bounds), dst, sampling, paint);
+    }
 
-        // Get the clip bounds
-        let clip = self.clip_bounds();
+    /// A clamped shader over all of `image`, reused while the same image is
+    /// drawn with the same sampling.
+    #[cfg(feature = "codec")]
+    fn image_shader(&mut self, image: &Image, sampling: SamplingOptions) -> Option<ImageShader> {
+        if let Some((cached, shader)) = &self.image_shader {
+            if cached.unique_id() == image.unique_id() && shader.sampling() == sampling {
+                return Some(shader.clone());
+            }
+        }
+        let bounds = IRect::new(0, 0, image.width(), image.height());
+        let shader =
+            ImageShader::from_image(image, &bounds, TileMode::Clamp, TileMode::Clamp, sampling)?;
+        self.image_shader = Some((image.clone(), shader.clone()));
+        Some(shader)
+    }
+
+    /// Draw `src` of an image into `dst` without counting a draw.
+    #[cfg(feature = "codec")]
+    fn blit_image_rect(
+        &mut self,
+        image: &Image,
+        src: &Rect,
+        dst: &Rect,
+        sampling: SamplingOptions,
+        paint: Option<&Paint>,
+    ) {
+        if src.is_empty() || dst.is_empty() {
+            return;
+        }
+        let image_bounds = IRect::new(0, 0, image.width(), image.height());
+        let Some(subset) = src.round_out().intersect(&image_bounds) else {
+            return;
+        };
+        let Some(shader) = self.image_shader(image, sampling) else {
+            return;
+        };
 
-        // Calculate intersection with clip
-        let visible_dst = match transformed_dst.intersect(&clip) {
-            Some(r) => r,
-            None => return, // Completely clipped
+        let matrix = *self.total_matrix();
+        let Some(inverse) = matrix.invert() else {
+            return;
+        };
+        let Some(visible) = matrix.map_rect(dst).intersect(&self.clip_bounds()) else {
+            return;
         };
 
-        // Calculate scale factors
-        let scale_x = (src_rect.width() as Scalar) / dst.width();
-        let scale_y = (src_rect.height() as Scalar) / dst.height();
-
-        // Blend mode from paint
-        let blend_mode = paint
-            .map(|p| p.blend_mode())
-            .unwrap_or(skia_rs_paint::BlendMode::SrcOver);
-        let alpha = paint.map(|p| p.alpha()).unwrap_or(1.0);
-
-        // Iterate over destination pixels
-        let dst_x_start = visible_dst.left.floor() as i32;
-        let dst_x_end = visible_dst.right.ceil() as i32;
-        let dst_y_start = visible_dst.top.floor() as i32;
-        let dst_y_end = visible_dst.bottom.ceil() as i32;
-
-        for dst_y in dst_y_start..dst_y_end {
-            for dst_x in dst_x_start..dst_x_end {
-                // Calculate source coordinates
-                let rel_x = (dst_x as Scalar - transformed_dst.left) * scale_x;
-                let rel_y = (dst_y as Scalar - transformed_dst.top) * scale_y;
-
-                let src_x = (src_rect.left as Scalar + rel_x) as i32;
-                let src_y = (src_rect.top as Scalar + rel_y) as i32;
-
-                // Bounds check
-                if src_x < 0 || src_x >= image.width() || src_y < 0 || src_y >= image.height() {
+        let blend_mode = paint.map_or(BlendMode::SrcOver, Paint::blend_mode);
+        let alpha = paint.map_or(1.0, Paint::alpha);
+        let color_filter = paint.and_then(Paint::color_filter);
+        let scale_x = src.width() / dst.width();
+        let scale_y = src.height() / dst.height();
+
+        // Keeping samples half a pixel inside the subset clamps both filters
+        // to its edge pixels, as if the subset were the whole image.
+        let (min_u, max_u) = (subset.left as Scalar + 0.5, subset.right as Scalar - 0.5);
+        let (min_v, max_v) = (subset.top as Scalar + 0.5, subset.bottom as Scalar - 0.5);
+
+        // Map each device pixel center back into `dst`, then into the image.
+        let visible = visible.round_out();
+        for y in visible.top..visible.bottom {
+            for x in visible.left..visible.right {
+                let p = inverse.map_point(Point::new(x as Scalar + 0.5, y as Scalar + 0.5));
+                if !dst.contains(p) {
                     continue;
                 }
-
-                // Get source pixel
-                if let Some(src_color) = image.read_pixel(src_x, src_y) {
-                    let mut color = Color::from_argb(
-                        (src_color.a * alpha * 255.0) as u8,
-                        (src_color.r * 255.0) as u8,
-                        (src_color.g * 255.0) as u8,
-                        (src_color.b * 255.0) as u8,
-                    );
-
-                    // Apply alpha
-                    if alpha < 1.0 {
-                        let a = (color.alpha() as f32 * alpha) as u8;
-                        color = Color::from_argb(a, color.red(), color.green(), color.blue());
-                    }
-
-                    self.buffer.blend_pixel(dst_x, dst_y, color, blend_mode);
+                let u = (src.left + (p.x - dst.left) * scale_x).clamp(min_u, max_u);
+                let v = (src.top + (p.y - dst.top) * scale_y).clamp(min_v, max_v);
+                let mut color = shader.sample(u, v);
+                if let Some(filter) = color_filter {
+                    color = filter.filter_color(color);
                 }
+                color.a *= alpha;
+                self.blend_pixel(x, y, color.to_color(), blend_mode);
             }
         }
     }
 
-    /// Draw an image with nine-patch stretching.
+    /// Draw sprites from an atlas image in one call.
+    ///
+    /// Sprite `i` is the `tex_rects[i]` part of `atlas`, with its top-left
+    /// corner placed by `xforms[i]` and rotated and scaled about that corner.
+    /// If `colors` is given, each sprite's color is blended with its pixels
+    /// using `mode`, the sprite being the source and the color the
+    /// destination, as in Skia. `paint` supplies the alpha
+    /// and the blend mode onto the canvas, and its color filter applies to
+    /// each tinted sprite. Sprites are sampled with nearest filtering, so
+    /// neighbors in the atlas never bleed in.
     #[cfg(feature = "codec")]
-    pub fn draw_image_nine(
+    pub fn draw_atlas(
         &mut self,
-        image: &Image,
-        center: &IRect,
-        dst: &Rect,
+        atlas: &Image,
+        xforms: &[RSXform],
+        tex_rects: &[Rect],
+        colors: Option<&[Color]>,
+        mode: BlendMode,
         paint: Option<&Paint>,
     ) {
-        let img_w = image.width();
-        let img_h = image.height();
+        if paint.is_some_and(|paint| self.skip_draw(paint)) {
+            return;
+        }
+        self.stats.images += 1;
+
+        let Some(shader) = self.image_shader(atlas, SamplingOptions::NEAREST) else {
+            return;
+        };
+
+        let count = xforms.len().min(tex_rects.len());
+        let colors = colors.filter(|colors| colors.len() >= count);
+        let blend_mode = paint.map_or(BlendMode::SrcOver, Paint::blend_mode);
+        let alpha = paint.map_or(1.0, Paint::alpha);
+        let color_filter = paint.and_then(Paint::color_filter);
+        let clip = self.clip_bounds();
+
+        for (i, (xform, tex)) in xforms.iter().zip(tex_rects).enumerate() {
+            if tex.is_empty() {
+                continue;
+            }
+            let matrix = self.total_matrix().concat(&xform.to_matrix());
+            let Some(inverse) = matrix.invert() else {
+                continue;
+            };
+            let sprite = Rect::from_xywh(0.0, 0.0, tex.width(), tex.height());
+            let Some(visible) = matrix.map_rect(&sprite).intersect(&clip) else {
+                continue;
+            };
+            let tint = colors.map(|colors| colors[i].to_color4f());
+
+            // Map each device pixel center back into the sprite.
+            let visible = visible.round_out();
+            for y in visible.top..visible.bottom {
+                for x in visible.left..visible.right {
+                    let p = inverse.map_point(Point::new(x as Scalar + 0.5, y as Scalar + 0.5));
+                    if !sprite.contains(p) {
+                        continue;
+                    }
+                    let mut color = shader.sample(tex.left + p.x, tex.top + p.y);
+                    if let Some(tint) = tint {
+                        color = mode.blend(color, tint);
+                    }
+                    if let Some(filter) = color_filter {
+                        color = filter.filter_color(color);
+                    }
+                    color.a *= alpha;
+                    self.blend_pixel(x, y, color.to_color(), blend_mode);
+                }
+            }
+        }
+    }
+
+    /// Draw an image with nine-patch stretching.
+    #[cfg(feature = "codec")]
+    pub fn draw_image_nine(
+        &mut self,
+        image: &Image,
+        center: &IRect,
+        dst: &Rect,
+        paint: Option<&Paint>,
+    ) {
+        if paint.is_some_and(|paint| self.skip_draw(paint)) {
+            return;
+        }
+        self.stats.images += 1;
+
+        let img_w = image.width();
+        let img_h = image.height();
 
         // Calculate the nine regions
         let left_w = center.left as Scalar;
@@ -637,242 +1269,162 @@ impl<'a> RasterCanvas<'a> {
 
         let center_w = dst.width() - left_w - right_w;
         let center_h = dst.height() - top_h - bottom_h;
+        let mut blit = |src: IRect, dst: Rect| {
+            self.blit_image_rect(image, &src.to_rect(), &dst, SamplingOptions::NEAREST, paint);
+        };
 
         // Top-left corner
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(0, 0, center.left, center.top)),
-            &Rect::from_xywh(dst.left, dst.top, left_w, top_h),
-            paint,
+        blit(
+            IRect::new(0, 0, center.left, center.top),
+            Rect::from_xywh(dst.left, dst.top, left_w, top_h),
         );
 
         // Top edge (stretched)
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(center.left, 0, center.right, center.top)),
-            &Rect::from_xywh(dst.left + left_w, dst.top, center_w, top_h),
-            paint,
+        blit(
+            IRect::new(center.left, 0, center.right, center.top),
+            Rect::from_xywh(dst.left + left_w, dst.top, center_w, top_h),
         );
 
         // Top-right corner
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(center.right, 0, img_w, center.top)),
-            &Rect::from_xywh(dst.right - right_w, dst.top, right_w, top_h),
-            paint,
+        blit(
+            IRect::new(center.right, 0, img_w, center.top),
+            Rect::from_xywh(dst.right - right_w, dst.top, right_w, top_h),
         );
 
         // Left edge (stretched)
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(0, center.top, center.left, center.bottom)),
-            &Rect::from_xywh(dst.left, dst.top + top_h, left_w, center_h),
-            paint,
+        blit(
+            IRect::new(0, center.top, center.left, center.bottom),
+            Rect::from_xywh(dst.left, dst.top + top_h, left_w, center_h),
         );
 
         // Center (stretched both ways)
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(
-                center.left,
-                center.top,
-                center.right,
-                center.bottom,
-            )),
-            &Rect::from_xywh(dst.left + left_w, dst.top + top_h, center_w, center_h),
-            paint,
+        blit(
+            IRect::new(center.left, center.top, center.right, center.bottom),
+            Rect::from_xywh(dst.left + left_w, dst.top + top_h, center_w, center_h),
         );
 
         // Right edge (stretched)
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(center.right, center.top, img_w, center.bottom)),
-            &Rect::from_xywh(dst.right - right_w, dst.top + top_h, right_w, center_h),
-            paint,
+        blit(
+            IRect::new(center.right, center.top, img_w, center.bottom),
+            Rect::from_xywh(dst.right - right_w, dst.top + top_h, right_w, center_h),
         );
 
         // Bottom-left corner
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(0, center.bottom, center.left, img_h)),
-            &Rect::from_xywh(dst.left, dst.bottom - bottom_h, left_w, bottom_h),
-            paint,
+        blit(
+            IRect::new(0, center.bottom, center.left, img_h),
+            Rect::from_xywh(dst.left, dst.bottom - bottom_h, left_w, bottom_h),
         );
 
         // Bottom edge (stretched)
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(center.left, center.bottom, center.right, img_h)),
-            &Rect::from_xywh(dst.left + left_w, dst.bottom - bottom_h, center_w, bottom_h),
-            paint,
+        blit(
+            IRect::new(center.left, center.bottom, center.right, img_h),
+            Rect::from_xywh(dst.left + left_w, dst.bottom - bottom_h, center_w, bottom_h),
         );
 
         // Bottom-right corner
-        self.draw_image_rect(
-            image,
-            Some(&IRect::new(center.right, center.bottom, img_w, img_h)),
-            &Rect::from_xywh(
+        blit(
+            IRect::new(center.right, center.bottom, img_w, img_h),
+            Rect::from_xywh(
                 dst.right - right_w,
                 dst.bottom - bottom_h,
                 right_w,
                 bottom_h,
             ),
-            paint,
         );
     }
 
+    /// Draw a picture.
+    ///
+    /// The picture's commands are replayed with `matrix` concatenated onto
+    /// the current matrix. With a `paint` the picture is drawn into a layer
+    /// bounded by its cull rect, which is then composited with the paint's
+    /// alpha and blend mode. Saves left open by the picture are restored.
+    pub fn draw_picture(
+        &mut self,
+        picture: &Picture,
+        matrix: Option<&Matrix>,
+        paint: Option<&Paint>,
+    ) {
+        let save_count = self.save_count;
+        self.save();
+        if let Some(matrix) = matrix {
+            self.concat(matrix);
+        }
+        if let Some(paint) = paint {
+            let bounds = picture.cull_rect();
+            self.save_layer(&SaveLayerRec {
+                bounds: Some(&bounds),
+                paint: Some(paint),
+                ..Default::default()
+            });
+        }
+        picture.playback_raster(self);
+        self.restore_to_count(save_count);
+    }
+
     /// Draw a region.
     pub fn draw_region(&mut self, region: &Region, paint: &Paint) {
-        // Draw each rectangle in the region
-        for rect in region.iter() {
-            let rect_f = rect.to_rect();
-            self.draw_rect(&rect_f, paint);
+        if self.skip_draw(paint) {
+            return;
         }
+        self.stats.rects += 1;
+
+        // Draw each rectangle in the region
+        self.rasterize(|rasterizer| {
+            for rect in region.iter() {
+                rasterizer.draw_rect(&rect.to_rect(), paint);
+            }
+        });
     }
 
-    /// Draw vertices (triangles).
+    /// Draw a triangle mesh.
+    ///
+    /// Triangles are assembled from `positions` according to `mode`, in the
+    /// order given by `indices` if present. Per-vertex `colors` are
+    /// interpolated across each triangle, and a shader on `paint` is sampled
+    /// at the interpolated `tex_coords` (or positions). See
+    /// [`Rasterizer::draw_vertices`].
     pub fn draw_vertices(
         &mut self,
         mode: VertexMode,
         positions: &[Point],
         colors: Option<&[Color]>,
+        tex_coords: Option<&[Point]>,
+        indices: Option<&[u16]>,
         paint: &Paint,
     ) {
-        if positions.len() < 3 {
+        if self.skip_draw(paint) {
             return;
         }
+        self.stats.other += 1;
 
-        let matrix = *self.total_matrix();
-
-        match mode {
-            VertexMode::Triangles => {
-                // Draw triangles (every 3 vertices)
-                for chunk in positions.chunks(3) {
-                    if chunk.len() == 3 {
-                        self.draw_triangle(
-                            matrix.map_point(chunk[0]),
-                            matrix.map_point(chunk[1]),
-                            matrix.map_point(chunk[2]),
-                            colors.and_then(|c| c.first().copied()),
-                            paint,
-                        );
-                    }
-                }
-            }
-            VertexMode::TriangleStrip => {
-                // Triangle strip
-                for i in 0..positions.len().saturating_sub(2) {
-                    let (p0, p1, p2) = if i % 2 == 0 {
-                        (positions[i], positions[i + 1], positions[i + 2])
-                    } else {
-                        (positions[i + 1], positions[i], positions[i + 2])
-                    };
-                    self.draw_triangle(
-                        matrix.map_point(p0),
-                        matrix.map_point(p1),
-                        matrix.map_point(p2),
-                        colors.and_then(|c| c.get(i).copied()),
-                        paint,
-                    );
-                }
-            }
-            VertexMode::TriangleFan => {
-                // Triangle fan
-                let center = positions[0];
-                for i in 1..positions.len().saturating_sub(1) {
-                    self.draw_triangle(
-                        matrix.map_point(center),
-                        matrix.map_point(positions[i]),
-                        matrix.map_point(positions[i + 1]),
-                        colors.and_then(|c| c.get(i).copied()),
-                        paint,
-                    );
-                }
-            }
-        }
+        self.rasterize(|rasterizer| {
+            rasterizer.draw_vertices(mode, positions, colors, tex_coords, indices, paint);
+        });
     }
 
-    /// Draw a single filled triangle.
-    fn draw_triangle(
+    /// Draw text at the specified position.
+    ///
+    /// Same as [`RasterCanvas::draw_str`].
+    #[cfg(feature = "text")]
+    pub fn draw_string(
         &mut self,
-        p0: Point,
-        p1: Point,
-        p2: Point,
-        color: Option<Color>,
+        text: &str,
+        x: Scalar,
+        y: Scalar,
+        font: &skia_rs_text::Font,
         paint: &Paint,
     ) {
-        // Use scanline algorithm for triangle fill
-        let color = color.unwrap_or_else(|| paint.color32());
-        let blend_mode = paint.blend_mode();
-
-        // Sort vertices by y coordinate
-        let mut verts = [(p0.x, p0.y), (p1.x, p1.y), (p2.x, p2.y)];
-        verts.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-
-        let (x0, y0) = verts[0];
-        let (x1, y1) = verts[1];
-        let (x2, y2) = verts[2];
-
-        // Calculate edge slopes
-        let inv_slope_02 = if (y2 - y0).abs() > 0.001 {
-            (x2 - x0) / (y2 - y0)
-        } else {
-            0.0
-        };
-
-        let inv_slope_01 = if (y1 - y0).abs() > 0.001 {
-            (x1 - x0) / (y1 - y0)
-        } else {
-            0.0
-        };
-
-        let inv_slope_12 = if (y2 - y1).abs() > 0.001 {
-            (x2 - x1) / (y2 - y1)
-        } else {
-            0.0
-        };
-
-        // Scan from y0 to y1
-        let y_start = y0.ceil() as i32;
-        let y_mid = y1.ceil() as i32;
-        let y_end = y2.ceil() as i32;
-
-        for y in y_start..y_mid {
-            let t = (y as Scalar - y0) / (y1 - y0).max(0.001);
-            let x_left = x0 + (y as Scalar - y0) * inv_slope_02;
-            let x_right = x0 + (y as Scalar - y0) * inv_slope_01;
-
-            let (xa, xb) = if x_left < x_right {
-                (x_left, x_right)
-            } else {
-                (x_right, x_left)
-            };
-
-            for x in (xa.ceil() as i32)..(xb.floor() as i32) {
-                self.buffer.blend_pixel(x, y, color, blend_mode);
-            }
-        }
-
-        // Scan from y1 to y2
-        for y in y_mid..y_end {
-            let x_left = x0 + (y as Scalar - y0) * inv_slope_02;
-            let x_right = x1 + (y as Scalar - y1) * inv_slope_12;
-
-            let (xa, xb) = if x_left < x_right {
-                (x_left, x_right)
-            } else {
-                (x_right, x_left)
-            };
-
-            for x in (xa.ceil() as i32)..(xb.floor() as i32) {
-                self.buffer.blend_pixel(x, y, color, blend_mode);
-            }
-        }
+        self.draw_str(text, x, y, font, paint);
     }
 
-    /// Draw text at the specified position.
+    /// Shape text and draw it with its baseline starting at (x, y).
+    ///
+    /// Text that can't be shaped, because the font has no font data, is
+    /// laid out by [`TextBlob::from_text`](skia_rs_text::TextBlob::from_text)
+    /// instead.
     #[cfg(feature = "text")]
-    pub fn draw_string(
+    pub fn draw_str(
         &mut self,
         text: &str,
         x: Scalar,
@@ -880,42 +1432,25 @@ impl<'a> RasterCanvas<'a> {
         font: &skia_rs_text::Font,
         paint: &Paint,
     ) {
-        // Simple text rendering - just draw each character as a rectangle placeholder
-        // A real implementation would use glyph outlines from the font
-        let color = paint.color32();
-        let blend_mode = paint.blend_mode();
-        let matrix = *self.total_matrix();
-
-        let char_width = font.size() * 0.5;
-        let char_height = font.size();
-        let mut current_x = x;
-
-        for _ch in text.chars() {
-            // Transform position
-            let pos = matrix.map_point(Point::new(current_x, y - char_height * 0.8));
+        use skia_rs_text::{Shaper, TextBlob};
 
-            // Draw a simple rectangle for each character (placeholder)
-            let rect = Rect::from_xywh(
-                pos.x,
-                pos.y,
-                char_width * matrix.scale_x().abs(),
-                char_height * matrix.scale_y().abs(),
-            );
-
-            if let Some(clipped) = rect.intersect(&self.clip_bounds()) {
-                let r = clipped.round_out();
-                for py in r.top..r.bottom {
-                    for px in r.left..r.right {
-                        self.buffer.blend_pixel(px, py, color, blend_mode);
-                    }
-                }
-            }
-
-            current_x += char_width;
+        if self.skip_draw(paint) {
+            return;
         }
+        let blob = Shaper::new()
+            .shape_auto(text, font)
+            .map(|runs| TextBlob::from_shaped_runs(&runs, Point::zero()))
+            .unwrap_or_else(|| TextBlob::from_text(text, font, Point::zero()));
+        self.draw_text_blob(&blob, x, y, paint);
     }
 
-    /// Draw a text blob.
+    /// Draw a text blob with its origin at (x, y).
+    ///
+    /// Each glyph's outline is read from its run's font and all of them are
+    /// filled together as one path, so anti-aliasing, shaders and mask
+    /// filters apply to the text as a whole. The paint's style is ignored.
+    /// Fonts without font data have no outlines; their glyphs are drawn as
+    /// placeholder boxes.
     #[cfg(feature = "text")]
     pub fn draw_text_blob(
         &mut self,
@@ -924,12 +1459,19 @@ impl<'a> RasterCanvas<'a> {
         y: Scalar,
         paint: &Paint,
     ) {
-        let color = paint.color32();
-        let blend_mode = paint.blend_mode();
-        let matrix = *self.total_matrix();
+        if self.skip_draw(paint) {
+            return;
+        }
+        self.stats.text += 1;
 
+        // Color glyphs use the paint's color for foreground layers and its
+        // alpha for every layer
+        let foreground = paint.color32().with_alpha(255);
+        let mut color_layers = Vec::new();
+        let mut builder = skia_rs_path::PathBuilder::new();
         for run in blob.runs() {
             let font = &run.font;
+            let has_outlines = font.typeface().and_then(|t| t.font_data()).is_some();
             let char_width = font.size() * 0.5;
             let char_height = font.size();
 
@@ -943,30 +1485,51 @@ impl<'a> RasterCanvas<'a> {
                 } else {
                     Point::new(i as Scalar * char_width, 0.0)
                 };
-
-                let world_pos = matrix.map_point(Point::new(
-                    x + run.origin.x + pos.x,
-                    y + run.origin.y + pos.y - char_height * 0.8,
-                ));
-
-                // Draw glyph as rectangle (placeholder)
-                let rect = Rect::from_xywh(
-                    world_pos.x,
-                    world_pos.y,
-                    char_width * matrix.scale_x().abs(),
-                    char_height * matrix.scale_y().abs(),
-                );
-
-                if let Some(clipped) = rect.intersect(&self.clip_bounds()) {
-                    let r = clipped.round_out();
-                    for py in r.top..r.bottom {
-                        for px in r.left..r.right {
-                            self.buffer.blend_pixel(px, py, color, blend_mode);
-                        }
+                let origin_x = x + run.origin.x + pos.x;
+                let origin_y = y + run.origin.y + pos.y;
+
+                if has_outlines {
+                    let transform = Matrix::translate(origin_x, origin_y);
+                    if let Some(layers) = font.color_glyph_layers(glyph, foreground) {
+                        color_layers.extend(
+                            layers
+                                .into_iter()
+                                .map(|layer| (layer.path.transformed(&transform), layer.color)),
+                        );
+                    } else if let Some(outline) = font.get_glyph_path(glyph) {
+                        builder.add_path(&outline.transformed(&transform));
                     }
+                } else {
+                    builder.add_rect(&Rect::from_xywh(
+                        origin_x,
+                        origin_y - char_height * 0.8,
+                        char_width,
+                        char_height,
+                    ));
                 }
             }
         }
+
+        let path = builder.build();
+        let mut fill = paint.clone();
+        fill.set_style(skia_rs_paint::Style::Fill);
+        if !path.is_empty() {
+            self.rasterize(|rasterizer| rasterizer.draw_path(&path, &fill));
+        }
+
+        fill.set_shader(None);
+        for (layer, color) in color_layers {
+            fill.set_color32(color);
+            fill.set_alpha(fill.alpha() * paint.alpha());
+            self.rasterize(|rasterizer| rasterizer.draw_path(&layer, &fill));
+        }
+    }
+}
+
+impl Drop for RasterCanvas<'_> {
+    /// Composites any layers that were never restored.
+    fn drop(&mut self) {
+        self.restore_to_count(1);
     }
 }
 
@@ -1082,4 +1645,1092 @@ mod tests {
         let pixel = buffer.get_pixel(55, 55).unwrap();
         assert_eq!(pixel.red(), 255);
     }
+
+    #[test]
+    fn test_raster_canvas_skips_nothing_to_draw() {
+        let mut surface = Surface::new_raster_n32_premul(40, 40).unwrap();
+        surface
+            .raster_canvas()
+            .clear(Color::from_argb(255, 10, 20, 30));
+        let before = surface.pixel_buffer().pixels.clone();
+
+        let mut canvas = surface.raster_canvas();
+        let mut dst = Paint::new();
+        dst.set_color32(Color::from_argb(255, 255, 0, 0));
+        dst.set_blend_mode(BlendMode::Dst);
+        canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 40.0, 40.0), &dst);
+        canvas.draw_circle(Point::new(20.0, 20.0), 10.0, &dst);
+        canvas.draw_color(Color::from_argb(255, 0, 255, 0), BlendMode::Dst);
+
+        let mut transparent = Paint::new();
+        transparent.set_color32(Color::TRANSPARENT);
+        canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 40.0, 40.0), &transparent);
+        assert_eq!(canvas.skipped_draws(), 4);
+
+        // Src with a transparent color still clears, so it is drawn.
+        let mut clear = Paint::new();
+        clear.set_color32(Color::TRANSPARENT);
+        clear.set_blend_mode(BlendMode::Src);
+        canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 1.0, 1.0), &clear);
+        assert_eq!(canvas.skipped_draws(), 4);
+        drop(canvas);
+
+        let buffer = surface.pixel_buffer();
+        assert_eq!(buffer.pixels[4..], before[4..]);
+        assert_eq!(buffer.get_pixel(0, 0).unwrap().alpha(), 0);
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_draw_text_blob_blurs_run_once() {
+        use skia_rs_paint::{BlurStyle, MaskFilter};
+        use skia_rs_text::{Font, GlyphRun, TextBlob};
+
+        // Glyph boxes are 10x20; the second overlaps both neighbors and the
+        // last one abuts the third, so together they cover 20..60 unbroken.
+        let positions = [0.0, 5.0, 10.0, 20.0, 30.0].map(|x| Point::new(x, 0.0));
+        let run = GlyphRun::new(
+            Font::from_size(20.0),
+            vec![1; positions.len()],
+            positions.to_vec(),
+            Point::zero(),
+        );
+        let blob = TextBlob::from_runs(vec![run]);
+
+        let mut surface = Surface::new_raster_n32_premul(80, 60).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_argb(128, 0, 0, 0));
+            paint.set_mask_filter(<dyn MaskFilter>::blur(BlurStyle::Normal, 1.5));
+            canvas.draw_text_blob(&blob, 20.0, 40.0, &paint);
+        }
+
+        // Across the interior of the run the coverage is flat: no seams where
+        // glyphs meet and no doubling where they overlap.
+        let buffer = surface.pixel_buffer();
+        for x in 26..54 {
+            let alpha = buffer.get_pixel(x, 34).unwrap().alpha();
+            assert!(alpha.abs_diff(128) <= 1, "alpha {alpha} at x = {x}");
+        }
+        // The blur still spreads past the run's edges.
+        assert!(buffer.get_pixel(18, 34).unwrap().alpha() > 0);
+        assert!(buffer.get_pixel(61, 34).unwrap().alpha() > 0);
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_draw_str_renders_glyph_outlines() {
+        use alloc::sync::Arc;
+        use skia_rs_text::{Font, Shaper, Typeface};
+
+        let data = include_bytes!("../../skia-rs-text/testdata/Tuffy.ttf");
+        let typeface = Typeface::from_bytes(data.to_vec(), 0).unwrap();
+        let font = Font::new(Arc::new(typeface), 32.0);
+        let run = Shaper::new().shape_auto("Hi", &font).unwrap().remove(0);
+        let (advance, i_start) = (run.width, 10.0 + run.glyphs[0].x_advance);
+
+        let mut surface = Surface::new_raster_n32_premul(80, 60).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_rgb(0, 0, 255));
+            paint.set_anti_alias(true);
+            canvas.draw_str("Hi", 10.0, 40.0, &font, &paint);
+        }
+
+        // Ink lands between the pen start and the advance, above the baseline,
+        // and only in the paint's color.
+        let buffer = surface.pixel_buffer();
+        let mut inked = 0;
+        let mut edge = false;
+        for y in 0..60 {
+            for x in 0..80 {
+                let pixel = buffer.get_pixel(x, y).unwrap();
+                if pixel.alpha() == 0 {
+                    continue;
+                }
+                inked += 1;
+                edge |= pixel.alpha() < 255;
+                assert_eq!((pixel.red(), pixel.green(), pixel.blue()), (0, 0, 255));
+                assert!((10.0..=10.0 + advance).contains(&(x as Scalar)), "x = {x}");
+                assert!((8..40).contains(&y), "y = {y}");
+            }
+        }
+        assert!(inked > 100, "only {inked} pixels inked");
+        assert!(edge, "outlines are not anti-aliased");
+        // The dot of the i is separate from its stem.
+        let i_column = (0..60)
+            .map(|y| {
+                (0..80)
+                    .filter(|&x| x as Scalar >= i_start)
+                    .any(|x| buffer.get_pixel(x, y).unwrap().alpha() > 128)
+            })
+            .collect::<Vec<_>>();
+        let ink_runs = i_column.windows(2).filter(|w| !w[0] && w[1]).count();
+        assert_eq!(ink_runs, 2);
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_draw_text_blob_renders_color_layers() {
+        use alloc::sync::Arc;
+        use skia_rs_text::{Font, GlyphRun, TextBlob, Typeface};
+        use std::collections::HashSet;
+
+        // Glyph 168 of the COLR test font is a COLRv0 glyph with 8 layers
+        let data = include_bytes!("../../skia-rs-text/testdata/colr_1.ttf");
+        let typeface = Typeface::from_bytes(data.to_vec(), 0).unwrap();
+        let font = Font::new(Arc::new(typeface), 64.0);
+        assert!(font.is_color_glyph(168));
+        let run = GlyphRun::new(font, vec![168], vec![Point::zero()], Point::zero());
+        let blob = TextBlob::from_runs(vec![run]);
+
+        let mut surface = Surface::new_raster_n32_premul(96, 96).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_rgb(0, 0, 0));
+            canvas.draw_text_blob(&blob, 16.0, 80.0, &paint);
+        }
+
+        let buffer = surface.pixel_buffer();
+        let colors: HashSet<_> = (0..96)
+            .flat_map(|y| (0..96).map(move |x| (x, y)))
+            .map(|(x, y)| buffer.get_pixel(x, y).unwrap())
+            .filter(|pixel| pixel.alpha() == 255)
+            .map(|pixel| (pixel.red(), pixel.green(), pixel.blue()))
+            .collect();
+        assert!(colors.len() > 1, "colors: {colors:?}");
+        assert!(colors.iter().any(|&color| color != (0, 0, 0)));
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_draw_image_rect_bilinear_downscale() {
+        use skia_rs_codec::ImageInfo;
+
+        // Red rises by 16 per pixel in row-major order.
+        let info = ImageInfo::new(4, 4, ColorType::Rgba8888, AlphaType::Unpremul);
+        let pixels: Vec<u8> = (0..16u8).flat_map(|i| [i * 16, 0, 0, 255]).collect();
+        let image = Image::from_raster_data(&info, &pixels, 16).unwrap();
+
+        let draw = |sampling: SamplingOptions| {
+            let mut surface = Surface::new_raster_n32_premul(8, 8).unwrap();
+            {
+                let mut canvas = surface.raster_canvas();
+                canvas.translate(4.0, 4.0);
+                let dst = Rect::from_xywh(0.0, 0.0, 2.0, 2.0);
+                canvas.draw_image_rect(&image, None, &dst, sampling, None);
+            }
+            let buffer = surface.pixel_buffer();
+            assert_eq!(buffer.get_pixel(3, 4).unwrap().alpha(), 0);
+            assert_eq!(buffer.get_pixel(6, 4).unwrap().alpha(), 0);
+            [(4, 4), (5, 4), (4, 5), (5, 5)].map(|(x, y)| buffer.get_pixel(x, y).unwrap().red())
+        };
+
+        // Each destination pixel averages the 2x2 block beneath it.
+        let linear = draw(SamplingOptions::LINEAR);
+        for (actual, expected) in linear.iter().zip([40u8, 72, 168, 200]) {
+            assert!(actual.abs_diff(expected) <= 1, "{linear:?}");
+        }
+        // Nearest picks the block's lower-right pixel under the center.
+        assert_eq!(draw(SamplingOptions::NEAREST), [80, 112, 208, 240]);
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_draw_image_rect_subsets_share_shader() {
+        use skia_rs_codec::ImageInfo;
+
+        // Left half red, right half blue.
+        let info = ImageInfo::new(4, 1, ColorType::Rgba8888, AlphaType::Unpremul);
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let pixels = [red, red, blue, blue].concat();
+        let image = Image::from_raster_data(&info, &pixels, 16).unwrap();
+
+        let mut surface = Surface::new_raster_n32_premul(8, 4).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            let halves = [Rect::new(0.0, 0.0, 2.0, 1.0), Rect::new(2.0, 0.0, 4.0, 1.0)];
+            for (i, src) in halves.iter().enumerate() {
+                let dst = Rect::from_xywh(i as Scalar * 4.0, 0.0, 4.0, 4.0);
+                canvas.draw_image_rect(&image, Some(src), &dst, SamplingOptions::LINEAR, None);
+                let (cached, _) = canvas.image_shader.as_ref().unwrap();
+                assert_eq!(cached.unique_id(), image.unique_id());
+            }
+        }
+
+        // Bilinear filtering stays inside each half.
+        let buffer = surface.pixel_buffer();
+        for x in 0..8 {
+            let expected = if x < 4 {
+                Color::from_rgb(255, 0, 0)
+            } else {
+                Color::from_rgb(0, 0, 255)
+            };
+            assert_eq!(buffer.get_pixel(x, 2), Some(expected), "x = {x}");
+        }
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_surface_encode_png() {
+        let mut surface = Surface::new_raster_n32_premul(40, 30).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            canvas.clear(Color::WHITE);
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_argb(255, 0, 0, 255));
+            canvas.draw_circle(Point::new(20.0, 15.0), 10.0, &paint);
+        }
+
+        let encoded = surface.encode(ImageFormat::Png, None).unwrap();
+        assert_eq!(ImageFormat::from_magic(&encoded), ImageFormat::Png);
+        assert_eq!(surface.encode_to_png().unwrap(), encoded);
+
+        let decoded = skia_rs_codec::decode_image(&encoded).unwrap();
+        assert_eq!(decoded.dimensions(), (40, 30));
+        let center = decoded.read_pixel(20, 15).unwrap();
+        assert_eq!((center.r, center.b), (0.0, 1.0));
+        assert_eq!(decoded.read_pixel(0, 0).unwrap().r, 1.0);
+    }
+
+    #[test]
+    fn test_raster_canvas_draw_vertices_colors() {
+        let mut surface = Surface::new_raster_n32_premul(60, 60).unwrap();
+        let positions = [
+            Point::new(5.0, 5.0),
+            Point::new(55.0, 5.0),
+            Point::new(30.0, 55.0),
+        ];
+        let colors = [
+            Color::from_argb(255, 255, 0, 0),
+            Color::from_argb(255, 0, 255, 0),
+            Color::from_argb(255, 0, 0, 255),
+        ];
+        surface.raster_canvas().draw_vertices(
+            VertexMode::Triangles,
+            &positions,
+            Some(&colors),
+            None,
+            None,
+            &Paint::new(),
+        );
+
+        let buffer = surface.pixel_buffer();
+        // The centroid mixes the three corners about equally.
+        let centroid = buffer.get_pixel(30, 21).unwrap();
+        assert_eq!(centroid.alpha(), 255);
+        for channel in [centroid.red(), centroid.green(), centroid.blue()] {
+            assert!((70..=100).contains(&channel), "{centroid:?}");
+        }
+        // Near a corner its color dominates.
+        let near_red = buffer.get_pixel(8, 6).unwrap();
+        assert!(near_red.red() > 200 && near_red.green() < 40 && near_red.blue() < 40);
+        assert_eq!(buffer.get_pixel(5, 50).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn test_raster_canvas_draw_vertices_modes_share_edges() {
+        let quad = [
+            Point::new(10.0, 10.0),
+            Point::new(30.0, 10.0),
+            Point::new(30.0, 30.0),
+            Point::new(10.0, 30.0),
+        ];
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(128, 0, 0, 255));
+
+        let draw = |mode: VertexMode, positions: &[Point], indices: Option<&[u16]>| {
+            let mut surface = Surface::new_raster_n32_premul(40, 40).unwrap();
+            surface
+                .raster_canvas()
+                .draw_vertices(mode, positions, None, None, indices, &paint);
+            surface.pixels().to_vec()
+        };
+
+        let indexed = draw(VertexMode::Triangles, &quad, Some(&[0, 1, 2, 0, 2, 3]));
+        let fan = draw(VertexMode::TriangleFan, &quad, None);
+        let strip = draw(
+            VertexMode::TriangleStrip,
+            &[quad[0], quad[1], quad[3], quad[2]],
+            None,
+        );
+        assert_eq!(fan, indexed);
+        assert_eq!(strip, indexed);
+
+        // The diagonal is drawn once, so the quad has no darker seam.
+        for (i, px) in indexed.chunks_exact(4).enumerate() {
+            let (x, y) = (i % 40, i / 40);
+            let inside = (10..30).contains(&x) && (10..30).contains(&y);
+            assert_eq!(px[3], if inside { 128 } else { 0 }, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn test_raster_canvas_draw_vertices_tex_coords() {
+        use alloc::sync::Arc;
+        use skia_rs_core::Color4f;
+        use skia_rs_paint::{LinearGradient, TileMode};
+
+        let gradient = LinearGradient::new(
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            vec![
+                Color4f::new(1.0, 0.0, 0.0, 1.0),
+                Color4f::new(0.0, 0.0, 1.0, 1.0),
+            ],
+            None,
+            TileMode::Clamp,
+        );
+        let mut paint = Paint::new();
+        paint.set_shader(Some(Arc::new(gradient)));
+
+        // The texture is mirrored: the left edge samples the gradient's end.
+        let positions = [
+            Point::new(0.0, 0.0),
+            Point::new(40.0, 0.0),
+            Point::new(40.0, 10.0),
+            Point::new(0.0, 10.0),
+        ];
+        let tex_coords = [
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 10.0),
+            Point::new(10.0, 10.0),
+        ];
+        let mut surface = Surface::new_raster_n32_premul(40, 10).unwrap();
+        surface.raster_canvas().draw_vertices(
+            VertexMode::TriangleFan,
+            &positions,
+            None,
+            Some(&tex_coords),
+            None,
+            &paint,
+        );
+
+        let buffer = surface.pixel_buffer();
+        let left = buffer.get_pixel(0, 5).unwrap();
+        let right = buffer.get_pixel(39, 5).unwrap();
+        assert!(left.blue() > 240 && left.red() < 15, "{left:?}");
+        assert!(right.red() > 240 && right.blue() < 15, "{right:?}");
+        let middle = buffer.get_pixel(20, 5).unwrap();
+        assert!(middle.red().abs_diff(middle.blue()) < 20, "{middle:?}");
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_raster_canvas_draw_atlas() {
+        use crate::RSXform;
+        use skia_rs_codec::ImageInfo;
+
+        // A 2x1 atlas: a red texel and a blue one.
+        let info = ImageInfo::new(2, 1, ColorType::Rgba8888, AlphaType::Unpremul);
+        let atlas = Image::from_raster_data(&info, &[255, 0, 0, 255, 0, 0, 255, 255], 8).unwrap();
+        let tex_rects = [Rect::new(0.0, 0.0, 1.0, 1.0), Rect::new(1.0, 0.0, 2.0, 1.0)];
+        let xforms = [
+            // Scaled 4x with its corner at (2, 2).
+            RSXform::from_scale_translate(4.0, 2.0, 2.0),
+            // Scaled 4x and turned a quarter clockwise about (20, 2).
+            RSXform::from_radians(4.0, core::f32::consts::FRAC_PI_2, 20.0, 2.0, 0.0, 0.0),
+        ];
+
+        let draw = |colors: Option<&[Color]>, mode: BlendMode, paint: Option<&Paint>| {
+            let mut surface = Surface::new_raster_n32_premul(24, 10).unwrap();
+            surface
+                .raster_canvas()
+                .draw_atlas(&atlas, &xforms, &tex_rects, colors, mode, paint);
+            surface
+        };
+
+        let surface = draw(None, BlendMode::Modulate, None);
+        let buffer = surface.pixel_buffer();
+        let red = Color::from_argb(255, 255, 0, 0);
+        let blue = Color::from_argb(255, 0, 0, 255);
+        for y in 0..10 {
+            for x in 0..24 {
+                let expected = if (2..6).contains(&x) && (2..6).contains(&y) {
+                    red
+                } else if (16..20).contains(&x) && (2..6).contains(&y) {
+                    blue
+                } else {
+                    Color::TRANSPARENT
+                };
+                assert_eq!(buffer.get_pixel(x, y), Some(expected), "({x}, {y})");
+            }
+        }
+
+        // Colors tint each sprite through the blend mode.
+        let white = Color::from_argb(255, 255, 255, 255);
+        let green = Color::from_argb(255, 0, 255, 0);
+        let surface = draw(Some(&[white, green]), BlendMode::Modulate, None);
+        let buffer = surface.pixel_buffer();
+        assert_eq!(buffer.get_pixel(3, 3), Some(red));
+        assert_eq!(
+            buffer.get_pixel(17, 3),
+            Some(Color::from_argb(255, 0, 0, 0))
+        );
+        // The sprite is the source and the color the destination.
+        let surface = draw(Some(&[white, green]), BlendMode::Dst, None);
+        let buffer = surface.pixel_buffer();
+        assert_eq!(buffer.get_pixel(3, 3), Some(white));
+        assert_eq!(buffer.get_pixel(17, 3), Some(green));
+
+        // The paint's color filter applies to every sprite.
+        let swap_red_blue = <dyn skia_rs_paint::ColorFilter>::matrix([
+            0.0, 0.0, 1.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, 0.0, //
+            1.0, 0.0, 0.0, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ]);
+        let mut paint = Paint::new();
+        paint.set_color_filter(Some(swap_red_blue));
+        let surface = draw(None, BlendMode::Modulate, Some(&paint));
+        let buffer = surface.pixel_buffer();
+        assert_eq!(buffer.get_pixel(3, 3), Some(blue));
+        assert_eq!(buffer.get_pixel(17, 3), Some(red));
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_surface_snapshot_copy_on_write() {
+        let red = Color::from_argb(255, 255, 0, 0);
+        let mut surface = Surface::new_raster_n32_premul(8, 8).unwrap();
+        surface.erase(red, None);
+
+        let snapshot = surface.snapshot().unwrap();
+        assert_eq!(snapshot.dimensions(), (8, 8));
+        // Taking the snapshot copies nothing.
+        let shared = surface.pixels().as_ptr();
+        assert_eq!(snapshot.peek_pixels().unwrap().as_ptr(), shared);
+
+        surface.raster_canvas().clear(Color::TRANSPARENT);
+
+        // The write copied the buffer, leaving the snapshot untouched.
+        assert_ne!(surface.pixels().as_ptr(), shared);
+        assert!(surface.pixels().iter().all(|&b| b == 0));
+        assert!(
+            snapshot
+                .peek_pixels()
+                .unwrap()
+                .chunks_exact(4)
+                .all(|px| px == [255, 0, 0, 255])
+        );
+
+        // With no snapshot alive, writes happen in place.
+        drop(snapshot);
+        let owned = surface.pixels().as_ptr();
+        surface.erase(red, None);
+        assert_eq!(surface.pixels().as_ptr(), owned);
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_surface_snapshot_non_rgba8_info() {
+        let info = ImageInfo::new(4, 4, ColorType::RgbaF16, AlphaType::Premul).unwrap();
+        let mut surface = Surface::new_raster(&info, None).unwrap();
+        surface.erase(Color::from_argb(255, 0, 0, 255), None);
+
+        // The snapshot describes the RGBA8888 buffer, not the requested F16.
+        let snapshot = surface.snapshot().unwrap();
+        assert_eq!(snapshot.color_type(), ColorType::Rgba8888);
+        assert_eq!(snapshot.row_bytes(), 16);
+        assert!(
+            snapshot
+                .peek_pixels()
+                .unwrap()
+                .chunks_exact(4)
+                .all(|px| px == [0, 0, 255, 255])
+        );
+        assert!(surface.make_image_snapshot().is_some());
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_surface_snapshot_translucent_draw_back() {
+        let translucent = Color::from_argb(128, 100, 0, 0);
+        let mut surface = Surface::new_raster_n32_premul(4, 4).unwrap();
+        surface.erase(translucent, None);
+
+        // The buffer holds unpremultiplied colors, so the snapshot says so.
+        let snapshot = surface.snapshot().unwrap();
+        assert_eq!(snapshot.alpha_type(), AlphaType::Unpremul);
+
+        let mut target = Surface::new_raster_n32_premul(4, 4).unwrap();
+        target.raster_canvas().draw_image(&snapshot, 0.0, 0.0, None);
+        let drawn = target.pixel_buffer().get_pixel(1, 1).unwrap();
+        assert_eq!(drawn.alpha(), 128);
+        assert!(drawn.red().abs_diff(100) <= 1, "red {}", drawn.red());
+
+        // Encoders read it the same way.
+        let png = snapshot.encode(ImageFormat::Png, None).unwrap();
+        let decoded = Image::from_encoded(Arc::new(png)).unwrap();
+        assert_eq!(&decoded.peek_pixels().unwrap()[..4], &[100, 0, 0, 128]);
+
+        let opaque = ImageInfo::new(4, 4, ColorType::Rgba8888, AlphaType::Opaque).unwrap();
+        let opaque = Surface::new_raster(&opaque, None).unwrap();
+        assert_eq!(opaque.snapshot().unwrap().alpha_type(), AlphaType::Opaque);
+    }
+
+    #[test]
+    fn test_surface_erase_sub_rect() {
+        let mut surface = Surface::new_raster_n32_premul(20, 20).unwrap();
+        let background = Color::from_argb(128, 0, 0, 255);
+        surface.erase(background, None);
+
+        let red = Color::from_argb(255, 255, 0, 0);
+        surface.erase(red, Some(&IRect::new(5, 6, 12, 15)));
+
+        let buffer = surface.pixel_buffer();
+        for y in 0..20 {
+            for x in 0..20 {
+                let inside = (5..12).contains(&x) && (6..15).contains(&y);
+                let expected = if inside { red } else { background };
+                assert_eq!(buffer.get_pixel(x, y), Some(expected), "pixel ({x}, {y})");
+            }
+        }
+
+        // Rects reaching past the surface are clipped to it.
+        surface.erase(Color::TRANSPARENT, Some(&IRect::new(-5, -5, 2, 30)));
+        let buffer = surface.pixel_buffer();
+        assert_eq!(buffer.get_pixel(1, 19), Some(Color::TRANSPARENT));
+        assert_eq!(buffer.get_pixel(2, 19), Some(background));
+    }
+
+    #[test]
+    fn test_raster_canvas_save_layer_group_opacity() {
+        let mut surface = Surface::new_raster_n32_premul(50, 50).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            canvas.clear(Color::WHITE);
+
+            let mut layer_paint = Paint::new();
+            layer_paint.set_alpha(0.5);
+            let bounds = Rect::new(0.0, 0.0, 35.0, 35.0);
+            let rec = SaveLayerRec {
+                bounds: Some(&bounds),
+                paint: Some(&layer_paint),
+                ..Default::default()
+            };
+            assert_eq!(canvas.save_layer(&rec), 2);
+
+            let mut paint = Paint::new();
+            paint.set_color32(Color::BLACK);
+            canvas.draw_rect(&Rect::new(10.0, 10.0, 30.0, 30.0), &paint);
+            canvas.draw_rect(&Rect::new(20.0, 20.0, 40.0, 40.0), &paint);
+
+            // Nothing reaches the canvas until the layer is restored.
+            canvas.restore();
+        }
+
+        let buffer = surface.pixel_buffer();
+        let single = buffer.get_pixel(15, 15).unwrap();
+        let overlap = buffer.get_pixel(25, 25).unwrap();
+        assert!((single.red() as i32 - 127).abs() <= 2, "{single:?}");
+        assert_eq!(overlap, single);
+
+        // The layer bounds clip what was drawn into it.
+        assert_eq!(buffer.get_pixel(37, 37), Some(Color::WHITE));
+        assert_eq!(buffer.get_pixel(5, 5), Some(Color::WHITE));
+    }
+
+    #[test]
+    fn test_raster_canvas_save_layer_drop_shadow() {
+        use skia_rs_paint::ImageFilter;
+
+        let blue = Color::from_rgb(0, 0, 255);
+        let shadow = <dyn ImageFilter>::drop_shadow(6.0, 6.0, 2.0, blue);
+        for filter in [
+            shadow.clone(),
+            <dyn ImageFilter>::compose(shadow, <dyn ImageFilter>::blur(0.0, 0.0)),
+        ] {
+            let mut surface = Surface::new_raster_n32_premul(64, 64).unwrap();
+            {
+                let mut canvas = surface.raster_canvas();
+                let mut layer_paint = Paint::new();
+                layer_paint.set_image_filter(Some(filter));
+                canvas.save_layer(&SaveLayerRec {
+                    paint: Some(&layer_paint),
+                    ..Default::default()
+                });
+                let mut paint = Paint::new();
+                paint.set_color32(Color::WHITE);
+                canvas.draw_rect(&Rect::new(16.0, 16.0, 40.0, 40.0), &paint);
+                canvas.restore();
+            }
+
+            let buffer = surface.pixel_buffer();
+            assert_eq!(buffer.get_pixel(28, 28), Some(Color::WHITE));
+            // The shadow shows below and right of the rect, in its color.
+            let core = buffer.get_pixel(43, 43).unwrap();
+            assert!(core.alpha() > 200, "{core:?}");
+            assert_eq!((core.red(), core.green(), core.blue()), (0, 0, 255));
+            // Its edge is blurred into a partially transparent halo.
+            let halo = buffer.get_pixel(47, 30).unwrap();
+            assert!(halo.alpha() > 0 && halo.alpha() < 128, "{halo:?}");
+            // Nothing reaches above or left of the rect.
+            assert_eq!(buffer.get_pixel(12, 30).unwrap().alpha(), 0);
+            assert_eq!(buffer.get_pixel(30, 12).unwrap().alpha(), 0);
+        }
+    }
+
+    #[test]
+    fn test_raster_canvas_save_layer_luma_mask() {
+        let mut surface = Surface::new_raster_n32_premul(40, 20).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_rgb(255, 0, 0));
+
+            canvas.save_layer(&SaveLayerRec::default());
+            canvas.draw_rect(&Rect::new(0.0, 0.0, 40.0, 20.0), &paint);
+
+            // White keeps the content, black removes it.
+            let mut mask_paint = Paint::new();
+            mask_paint.set_blend_mode(BlendMode::DstIn);
+            mask_paint
+                .set_color_filter(Some(alloc::sync::Arc::new(skia_rs_paint::LumaColorFilter)));
+            canvas.save_layer(&SaveLayerRec {
+                paint: Some(&mask_paint),
+                ..Default::default()
+            });
+            paint.set_color32(Color::WHITE);
+            canvas.draw_rect(&Rect::new(0.0, 0.0, 20.0, 20.0), &paint);
+            paint.set_color32(Color::BLACK);
+            canvas.draw_rect(&Rect::new(20.0, 0.0, 40.0, 20.0), &paint);
+            canvas.restore();
+            canvas.restore();
+        }
+
+        let buffer = surface.pixel_buffer();
+        assert_eq!(buffer.get_pixel(10, 10), Some(Color::from_rgb(255, 0, 0)));
+        assert_eq!(buffer.get_pixel(30, 10).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn test_raster_canvas_draw_stats() {
+        let mut surface = Surface::new_raster_n32_premul(100, 100).unwrap();
+        let mut canvas = surface.raster_canvas();
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 255, 0, 0));
+
+        canvas.clear(Color::WHITE);
+        canvas.draw_rect(&Rect::from_xywh(10.0, 10.0, 10.0, 10.0), &paint);
+        // Half of this rect falls outside the canvas.
+        canvas.draw_rect(&Rect::from_xywh(95.0, 0.0, 10.0, 10.0), &paint);
+
+        let mut region = Region::from_rect(IRect::new(0, 50, 10, 60));
+        region.op_rect(IRect::new(20, 50, 30, 60), skia_rs_core::RegionOp::Union);
+        canvas.draw_region(&region, &paint);
+
+        let mut builder = skia_rs_path::PathBuilder::new();
+        builder.add_rect(&Rect::from_xywh(40.0, 40.0, 20.0, 20.0));
+        canvas.draw_path(&builder.build(), &paint);
+        canvas.draw_point(Point::new(80.0, 80.0), &paint);
+
+        let mut nothing = paint.clone();
+        nothing.set_blend_mode(BlendMode::Dst);
+        canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 100.0, 100.0), &nothing);
+
+        let stats = canvas.draw_stats();
+        assert_eq!(stats.rects, 3);
+        assert_eq!(stats.paths, 1);
+        assert_eq!(stats.images, 0);
+        assert_eq!(stats.text, 0);
+        assert_eq!(stats.other, 2);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(stats.total_draws(), 7);
+        assert_eq!(canvas.skipped_draws(), 1);
+
+        // Clear, two rects, two region rects, the path and the point.
+        assert_eq!(stats.pixels_touched, 10_000 + 100 + 50 + 200 + 400 + 1);
+
+        canvas.reset_draw_stats();
+        assert_eq!(canvas.draw_stats(), DrawStats::default());
+    }
+
+    #[test]
+    fn test_surface_with_clip_region() {
+        let white = Color::from_argb(255, 255, 255, 255);
+        let blue = Color::from_argb(255, 0, 0, 255);
+        let mut surface = Surface::new_raster_n32_premul(40, 40).unwrap();
+        surface.erase(white, None);
+
+        let region = Region::from_rect(IRect::new(12, 8, 22, 18));
+        {
+            let mut canvas = surface.with_clip_region(&region);
+            let mut paint = Paint::new();
+            paint.set_color32(blue);
+            canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 40.0, 40.0), &paint);
+        }
+
+        let buffer = surface.pixel_buffer();
+        let mut changed = 0;
+        for y in 0..40 {
+            for x in 0..40 {
+                let expected = if region.contains(x, y) { blue } else { white };
+                assert_eq!(buffer.get_pixel(x, y), Some(expected), "({x}, {y})");
+                changed += usize::from(expected == blue);
+            }
+        }
+        assert_eq!(changed, 100);
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_surface_with_clip_region_draw_image() {
+        use skia_rs_codec::ImageInfo;
+
+        let info = ImageInfo::new(20, 20, ColorType::Rgba8888, AlphaType::Unpremul);
+        let image = Image::from_raster_data(&info, &[255, 0, 0, 255].repeat(400), 80).unwrap();
+
+        let mut surface = Surface::new_raster_n32_premul(20, 20).unwrap();
+        let region = Region::from_rect(IRect::new(3, 4, 8, 9));
+        surface
+            .with_clip_region(&region)
+            .draw_image(&image, 0.0, 0.0, None);
+
+        let buffer = surface.pixel_buffer();
+        for y in 0..20 {
+            for x in 0..20 {
+                let alpha = buffer.get_pixel(x, y).unwrap().alpha();
+                assert_eq!(alpha, if region.contains(x, y) { 255 } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn test_raster_canvas_clip_region_clear() {
+        let black = Color::from_argb(255, 0, 0, 0);
+        let red = Color::from_argb(255, 255, 0, 0);
+        let mut surface = Surface::new_raster_n32_premul(30, 30).unwrap();
+        surface.erase(black, None);
+
+        let mut region = Region::from_rect(IRect::new(0, 0, 5, 5));
+        region.op_rect(IRect::new(20, 10, 30, 15), skia_rs_core::RegionOp::Union);
+        {
+            let mut canvas = surface.raster_canvas();
+            canvas.save();
+            canvas.clip_region(&region);
+            assert_eq!(canvas.clip_bounds(), Rect::new(0.0, 0.0, 30.0, 15.0));
+            canvas.clear(red);
+            assert_eq!(canvas.draw_stats().pixels_touched, 25 + 50);
+            canvas.restore();
+            canvas.clear(red);
+        }
+        assert!(
+            surface
+                .pixel_buffer()
+                .pixels
+                .chunks_exact(4)
+                .all(|px| px == [255, 0, 0, 255])
+        );
+
+        surface.erase(black, None);
+        {
+            let mut canvas = surface.with_clip_region(&region);
+            canvas.clear(red);
+        }
+        let buffer = surface.pixel_buffer();
+        assert_eq!(buffer.get_pixel(4, 4), Some(red));
+        assert_eq!(buffer.get_pixel(29, 14), Some(red));
+        assert_eq!(buffer.get_pixel(10, 2), Some(black));
+        assert_eq!(buffer.get_pixel(25, 15), Some(black));
+    }
+
+    #[test]
+    fn test_raster_canvas_filled_and_stroked_forces_styles() {
+        let mut builder = skia_rs_path::PathBuilder::new();
+        builder.add_rect(&Rect::new(4.0, 4.0, 28.0, 28.0));
+        let path = builder.build();
+
+        let mut visible = Paint::new();
+        visible.set_color32(Color::from_argb(255, 255, 0, 0));
+        visible.set_stroke_width(2.0);
+        let mut hidden = visible.clone();
+        hidden.set_alpha(0.0);
+
+        // Each paint draws in its role, whatever its own style says.
+        let mut fill = visible.clone();
+        fill.set_style(Style::Stroke);
+        let mut stroke = visible.clone();
+        stroke.set_style(Style::Fill);
+        for (fill, stroke, center) in [(&fill, &hidden, 255), (&hidden, &stroke, 0)] {
+            let mut surface = Surface::new_raster_n32_premul(32, 32).unwrap();
+            surface
+                .raster_canvas()
+                .draw_path_filled_and_stroked(&path, fill, stroke);
+            let buffer = surface.pixel_buffer();
+            assert_eq!(buffer.get_pixel(16, 16).unwrap().alpha(), center);
+            assert_eq!(buffer.get_pixel(4, 16).unwrap().alpha(), 255);
+        }
+    }
+
+    #[test]
+    fn test_raster_canvas_clip_region_draws() {
+        let red = Color::from_argb(255, 255, 0, 0);
+        let mut surface = Surface::new_raster_n32_premul(40, 40).unwrap();
+        let mut region = Region::from_rect(IRect::new(0, 0, 10, 10));
+        region.op_rect(IRect::new(20, 20, 40, 30), skia_rs_core::RegionOp::Union);
+        let mut paint = Paint::new();
+        paint.set_color32(red);
+        {
+            let mut canvas = surface.raster_canvas();
+            canvas.clip_region(&region);
+            // The region is kept as rectangles, not rasterized into a mask.
+            assert!(canvas.clip_masks.last().unwrap().is_none());
+
+            canvas.draw_rect(&Rect::new(0.0, 0.0, 40.0, 25.0), &paint);
+            // Through a layer, whose pixels start away from the device origin.
+            canvas.save_layer(&SaveLayerRec {
+                bounds: Some(&Rect::new(5.0, 25.0, 40.0, 40.0)),
+                ..Default::default()
+            });
+            canvas.draw_rect(&Rect::new(0.0, 25.0, 40.0, 40.0), &paint);
+            canvas.restore();
+        }
+        let buffer = surface.pixel_buffer();
+        for (x, y, inside) in [
+            (5, 5, true),
+            (15, 5, false),
+            (5, 15, false),
+            (30, 22, true),
+            (30, 27, true),
+            (30, 32, false),
+            (7, 27, false),
+        ] {
+            let alpha = buffer.get_pixel(x, y).unwrap().alpha();
+            assert_eq!(alpha == 255, inside, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn test_raster_canvas_draw_rrect_aa_gradient() {
+        use alloc::sync::Arc;
+        use skia_rs_core::Color4f;
+        use skia_rs_paint::{LinearGradient, TileMode};
+
+        let mut surface = Surface::new_raster_n32_premul(100, 60).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            canvas.clear(Color::TRANSPARENT);
+
+            let gradient = LinearGradient::new(
+                Point::new(10.0, 0.0),
+                Point::new(90.0, 0.0),
+                vec![
+                    Color4f::new(1.0, 0.0, 0.0, 1.0),
+                    Color4f::new(0.0, 0.0, 1.0, 1.0),
+                ],
+                None,
+                TileMode::Clamp,
+            );
+            let mut paint = Paint::new();
+            paint.set_anti_alias(true);
+            paint.set_shader(Some(Arc::new(gradient)));
+
+            let rrect = RRect::from_rect_radius(Rect::new(10.0, 10.0, 90.0, 50.0), 12.0);
+            canvas.draw_rrect(&rrect, &paint);
+        }
+
+        let buffer = surface.pixel_buffer();
+
+        // The shader varies across the shape.
+        let left = buffer.get_pixel(20, 30).unwrap();
+        let right = buffer.get_pixel(80, 30).unwrap();
+        assert_eq!(left.alpha(), 255);
+        assert_eq!(right.alpha(), 255);
+        assert!(left.red() > right.red());
+        assert!(left.blue() < right.blue());
+
+        // The very corner is outside the rounded shape.
+        assert_eq!(buffer.get_pixel(10, 10).unwrap().alpha(), 0);
+
+        // Pixels straddling the corner arc get partial coverage.
+        let partial = (10..22)
+            .flat_map(|y| (10..22).map(move |x| (x, y)))
+            .map(|(x, y)| buffer.get_pixel(x, y).unwrap().alpha())
+            .filter(|&a| a > 0 && a < 255)
+            .count();
+        assert!(
+            partial >= 6,
+            "expected smooth corner, got {partial} partial pixels"
+        );
+    }
+
+    #[test]
+    fn test_raster_canvas_clip_path_aa() {
+        let mut builder = skia_rs_path::PathBuilder::new();
+        builder.add_circle(20.0, 20.0, 12.0);
+        let circle = builder.build();
+
+        let mut builder = skia_rs_path::PathBuilder::new();
+        builder.add_rect(&Rect::from_xywh(0.0, 0.0, 40.0, 40.0));
+        let square = builder.build();
+
+        let mut paint = Paint::new();
+        paint.set_anti_alias(true);
+        paint.set_color32(Color::from_argb(255, 255, 0, 0));
+
+        let mut surface = Surface::new_raster_n32_premul(40, 40).unwrap();
+        for op in [ClipOp::Intersect, ClipOp::Difference] {
+            {
+                let mut canvas = surface.raster_canvas();
+                canvas.clear(Color::TRANSPARENT);
+                canvas.save();
+                canvas.clip_path(&circle, op, true);
+                canvas.draw_path(&square, &paint);
+                canvas.restore();
+            }
+
+            let buffer = surface.pixel_buffer();
+            let (center, corner) = match op {
+                ClipOp::Intersect => (255, 0),
+                ClipOp::Difference => (0, 255),
+            };
+            assert_eq!(buffer.get_pixel(20, 20).unwrap().alpha(), center);
+            assert_eq!(buffer.get_pixel(2, 2).unwrap().alpha(), corner);
+
+            // The circle passes through the middle of this pixel.
+            let edge = buffer.get_pixel(28, 28).unwrap().alpha();
+            assert!(edge > 64 && edge < 192, "{op:?} edge alpha {edge}");
+
+            let partial = (0..40)
+                .flat_map(|y| (0..40).map(move |x| (x, y)))
+                .map(|(x, y)| buffer.get_pixel(x, y).unwrap().alpha())
+                .filter(|&a| a > 0 && a < 255)
+                .count();
+            assert!(
+                partial >= 24,
+                "{op:?}: expected smooth clip edge, got {partial} partial pixels"
+            );
+        }
+    }
+
+    #[test]
+    fn test_raster_canvas_save_layer_clip_path_aa() {
+        let mut builder = skia_rs_path::PathBuilder::new();
+        builder.add_circle(20.0, 20.0, 12.0);
+        let circle = builder.build();
+
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_argb(255, 255, 0, 0));
+
+        let draw = |through_layer: bool| {
+            let mut surface = Surface::new_raster_n32_premul(40, 40).unwrap();
+            {
+                let mut canvas = surface.raster_canvas();
+                canvas.clear(Color::TRANSPARENT);
+                canvas.save();
+                canvas.clip_path(&circle, ClipOp::Intersect, true);
+                if through_layer {
+                    canvas.save_layer(&SaveLayerRec::default());
+                }
+                canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 40.0, 40.0), &paint);
+                if through_layer {
+                    canvas.restore();
+                }
+                canvas.restore();
+            }
+            surface.pixel_buffer().clone()
+        };
+
+        // The clip edge is applied once whether or not a layer is in between.
+        let direct = draw(false);
+        let layered = draw(true);
+        let edge = direct.get_pixel(28, 28).unwrap().alpha();
+        assert!(edge > 64 && edge < 192, "edge alpha {edge}");
+        for y in 0..40 {
+            for x in 0..40 {
+                assert_eq!(
+                    layered.get_pixel(x, y),
+                    direct.get_pixel(x, y),
+                    "({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rgb565_surface_gradient_dithered() {
+        use alloc::sync::Arc;
+        use skia_rs_core::Color4f;
+        use skia_rs_paint::{LinearGradient, TileMode};
+
+        let info = ImageInfo::new(64, 8, ColorType::Rgb565, AlphaType::Opaque).unwrap();
+        let mut surface = Surface::new_raster(&info, None).unwrap();
+        assert_eq!(surface.row_bytes(), 128);
+        assert_eq!(surface.pixels().len(), 64 * 8 * 2);
+        {
+            let mut canvas = surface.raster_canvas();
+            canvas.clear(Color::BLACK);
+
+            let gradient = LinearGradient::new(
+                Point::new(0.0, 0.0),
+                Point::new(64.0, 0.0),
+                vec![
+                    Color4f::new(0.0, 0.0, 0.0, 1.0),
+                    Color4f::new(1.0, 1.0, 1.0, 1.0),
+                ],
+                None,
+                TileMode::Clamp,
+            );
+            let mut paint = Paint::new();
+            paint.set_shader(Some(Arc::new(gradient)));
+            canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 64.0, 8.0), &paint);
+        }
+
+        let pixels = surface.pixels();
+        let packed = |x: usize, y: usize| {
+            let i = (y * 64 + x) * 2;
+            u16::from_le_bytes([pixels[i], pixels[i + 1]])
+        };
+        let red = |p: u16| p >> 11;
+
+        // Dark on the left, bright on the right, increasing across the row.
+        assert!(red(packed(0, 0)) <= 1);
+        assert!(red(packed(63, 0)) >= 30);
+        let row_sum = |x0: usize| {
+            (x0..x0 + 8)
+                .map(|x| u32::from(red(packed(x, 0))))
+                .sum::<u32>()
+        };
+        assert!(row_sum(0) < row_sum(28) && row_sum(28) < row_sum(56));
+
+        // Within a 4x4 tile of near-constant input, dithering mixes levels.
+        let tile: std::collections::HashSet<u16> = (0..4)
+            .flat_map(|y| (20..24).map(move |x| (x, y)))
+            .map(|(x, y)| red(packed(x, y)))
+            .collect();
+        assert!(tile.len() >= 2, "expected dithered levels, got {tile:?}");
+
+        // Reading back expands to 8-bit channels.
+        let white = surface.pixel_buffer().get_pixel(63, 0).unwrap();
+        assert_eq!(white.alpha(), 255);
+        assert!(white.red() >= 240 && white.green() >= 240);
+    }
+
+    #[test]
+    fn test_argb4444_surface_blend() {
+        let info = ImageInfo::new(4, 4, ColorType::Argb4444, AlphaType::Premul).unwrap();
+        let mut surface = Surface::new_raster(&info, None).unwrap();
+        {
+            let mut canvas = surface.raster_canvas();
+            canvas.clear(Color::WHITE);
+
+            let mut paint = Paint::new();
+            paint.set_color32(Color::from_argb(128, 0, 0, 255));
+            canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 4.0, 4.0), &paint);
+        }
+
+        // Fully representable white stays exact; the blend lands mid-way.
+        let pixels = surface.pixels();
+        let p = u16::from_le_bytes([pixels[0], pixels[1]]);
+        assert_eq!(p & 0xF, 0xF, "alpha nibble");
+        let (r, b) = (p >> 12, (p >> 4) & 0xF);
+        assert!((6..=9).contains(&r), "red nibble {r}");
+        assert_eq!(b, 0xF);
+    }
 }
@@ -3,6 +3,7 @@
 //! Codecs handle encoding and decoding of images in various formats.
 
 use crate::Image;
+use std::borrow::Cow;
 use std::io::{Read, Write};
 use thiserror::Error;
 
@@ -50,6 +51,10 @@ pub enum ImageFormat {
     Avif,
     /// Camera RAW format.
     Raw,
+    /// TIFF format.
+    Tiff,
+    /// QOI format (Quite OK Image).
+    Qoi,
     /// Unknown format.
     Unknown,
 }
@@ -81,6 +86,16 @@ impl ImageFormat {
             return Self::WebP;
         }
 
+        // TIFF: II*\0 (little-endian) or MM\0* (big-endian)
+        if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+            return Self::Tiff;
+        }
+
+        // QOI: qoif
+        if data.starts_with(b"qoif") {
+            return Self::Qoi;
+        }
+
         // BMP: BM
         if data.starts_with(b"BM") {
             return Self::Bmp;
@@ -127,6 +142,8 @@ impl ImageFormat {
             Self::Wbmp => "wbmp",
             Self::Avif => "avif",
             Self::Raw => "raw",
+            Self::Tiff => "tiff",
+            Self::Qoi => "qoi",
             Self::Unknown => "",
         }
     }
@@ -143,6 +160,8 @@ impl ImageFormat {
             Self::Wbmp => "image/vnd.wap.wbmp",
             Self::Avif => "image/avif",
             Self::Raw => "image/x-raw",
+            Self::Tiff => "image/tiff",
+            Self::Qoi => "image/qoi",
             Self::Unknown => "application/octet-stream",
         }
     }
@@ -183,6 +202,28 @@ pub trait ImageEncoder: Send + Sync {
     fn format(&self) -> ImageFormat;
 }
 
+/// An image's pixels with no padding between rows, as encoders write them.
+///
+/// Views from [`Image::subset`] keep their parent's row bytes, so their
+/// rows are copied out; tightly packed images are borrowed as is.
+fn packed_pixels(image: &Image) -> CodecResult<Cow<'_, [u8]>> {
+    let pixels = image
+        .peek_pixels()
+        .ok_or_else(|| CodecError::EncodingError("Cannot access pixels".into()))?;
+    let packed = image.width() as usize * image.color_type().bytes_per_pixel();
+    let row_bytes = image.row_bytes();
+    if row_bytes == packed {
+        return Ok(Cow::Borrowed(pixels));
+    }
+    Ok(Cow::Owned(
+        pixels
+            .chunks(row_bytes)
+            .flat_map(|row| &row[..packed])
+            .copied()
+            .collect(),
+    ))
+}
+
 /// Quality setting for lossy encoders.
 #[derive(Debug, Clone, Copy)]
 pub struct EncoderQuality(u8);
@@ -218,25 +259,77 @@ impl Default for EncoderQuality {
 // Simple PNG Codec (stub - would use png crate for real implementation)
 // =============================================================================
 
+/// Options for [`PngDecoder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PngDecodeOptions {
+    /// Keep 16-bit channels, decoding them to
+    /// [`ColorType::R16G16B16A16Unorm`](skia_rs_core::ColorType) instead of
+    /// truncating to 8-bit RGBA.
+    pub keep_16bit: bool,
+    /// Convert samples from the gamma in the file's `gAMA` chunk to sRGB.
+    ///
+    /// Ignored when the file has an `iCCP` or `sRGB` chunk, which take
+    /// precedence over `gAMA`.
+    pub apply_gamma: bool,
+}
+
 /// PNG decoder.
+///
+/// Images decode to unpremultiplied RGBA. Palette and low bit depth images
+/// are expanded to 8 bits per channel, and 16-bit images are kept at 16
+/// bits if [`PngDecodeOptions::keep_16bit`] is set. An embedded ICC profile
+/// is attached to the image and sets its color space.
 #[derive(Debug, Default)]
-pub struct PngDecoder;
+pub struct PngDecoder {
+    options: PngDecodeOptions,
+}
 
 impl PngDecoder {
     /// Create a new PNG decoder.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Create a PNG decoder with the given options.
+    pub fn with_options(options: PngDecodeOptions) -> Self {
+        Self { options }
+    }
+
+    /// Get the decode options.
+    pub fn options(&self) -> PngDecodeOptions {
+        self.options
     }
 }
 
 impl ImageDecoder for PngDecoder {
     #[cfg(feature = "png")]
     fn decode<R: Read>(&self, reader: R) -> CodecResult<Image> {
-        let decoder = png::Decoder::new(reader);
+        let mut decoder = png::Decoder::new(reader);
+        let mut transformations = png::Transformations::EXPAND;
+        if !self.options.keep_16bit {
+            transformations |= png::Transformations::STRIP_16;
+        }
+        decoder.set_transformations(transformations);
         let mut png_reader = decoder
             .read_info()
             .map_err(|e| CodecError::DecodingError(e.to_string()))?;
 
+        let png_info = png_reader.info();
+        let icc_profile = png_info
+            .icc_profile
+            .as_deref()
+            .and_then(skia_rs_core::IccProfile::from_bytes);
+        let gamma = match png_info.gama_chunk {
+            Some(gamma)
+                if self.options.apply_gamma
+                    && png_info.icc_profile.is_none()
+                    && png_info.srgb.is_none() =>
+            {
+                Some(gamma.into_value())
+            }
+            _ => None,
+        };
+
         let mut buf = vec![0; png_reader.output_buffer_size()];
         let info = png_reader
             .next_frame(&mut buf)
@@ -244,55 +337,53 @@ impl ImageDecoder for PngDecoder {
 
         let width = info.width as i32;
         let height = info.height as i32;
-
-        // Convert to RGBA if necessary
-        let pixels = match info.color_type {
-            png::ColorType::Rgba => buf[..info.buffer_size()].to_vec(),
-            png::ColorType::Rgb => {
-                let rgb = &buf[..info.buffer_size()];
-                let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
-                for chunk in rgb.chunks(3) {
-                    rgba.push(chunk[0]);
-                    rgba.push(chunk[1]);
-                    rgba.push(chunk[2]);
-                    rgba.push(255);
-                }
-                rgba
-            }
-            png::ColorType::GrayscaleAlpha => {
-                let ga = &buf[..info.buffer_size()];
-                let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
-                for chunk in ga.chunks(2) {
-                    rgba.push(chunk[0]);
-                    rgba.push(chunk[0]);
-                    rgba.push(chunk[0]);
-                    rgba.push(chunk[1]);
+        let data = &buf[..info.buffer_size()];
+        let channels = info.color_type.samples();
+
+        // Expand to RGBA, keeping 16-bit samples in native byte order
+        let (pixels, color_type) = if info.bit_depth == png::BitDepth::Sixteen {
+            let lut = gamma.map(|gamma| gamma_lut(gamma, u16::MAX));
+            let samples: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                .collect();
+            let mut rgba = Vec::with_capacity(width as usize * height as usize * 8);
+            for pixel in samples.chunks_exact(channels) {
+                let mut pixel = expand_to_rgba(pixel, u16::MAX);
+                if let Some(lut) = &lut {
+                    pixel[..3].iter_mut().for_each(|c| *c = lut[*c as usize]);
                 }
-                rgba
+                pixel
+                    .iter()
+                    .for_each(|c| rgba.extend_from_slice(&c.to_ne_bytes()));
             }
-            png::ColorType::Grayscale => {
-                let gray = &buf[..info.buffer_size()];
-                let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
-                for &g in gray {
-                    rgba.push(g);
-                    rgba.push(g);
-                    rgba.push(g);
-                    rgba.push(255);
+            (rgba, skia_rs_core::ColorType::R16G16B16A16Unorm)
+        } else {
+            let lut = gamma.map(|gamma| gamma_lut(gamma, u8::MAX));
+            let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+            for pixel in data.chunks_exact(channels) {
+                let mut pixel = expand_to_rgba(pixel, u8::MAX);
+                if let Some(lut) = &lut {
+                    pixel[..3].iter_mut().for_each(|c| *c = lut[*c as usize]);
                 }
-                rgba
+                rgba.extend_from_slice(&pixel);
             }
-            _ => return Err(CodecError::Unsupported("Unsupported PNG color type".into())),
+            (rgba, skia_rs_core::ColorType::Rgba8888)
         };
 
-        let info = crate::ImageInfo::new(
-            width,
-            height,
-            skia_rs_core::ColorType::Rgba8888,
-            skia_rs_core::AlphaType::Unpremul,
-        );
-
-        Image::from_raster_data_owned(info, pixels, width as usize * 4)
-            .ok_or_else(|| CodecError::DecodingError("Failed to create image".into()))
+        let mut info =
+            crate::ImageInfo::new(width, height, color_type, skia_rs_core::AlphaType::Unpremul);
+        info.color_space = icc_profile
+            .as_ref()
+            .map(|profile| profile.color_space().clone());
+        let row_bytes = width as usize * color_type.bytes_per_pixel();
+
+        let image = Image::from_raster_data_owned(info, pixels, row_bytes)
+            .ok_or_else(|| CodecError::DecodingError("Failed to create image".into()))?;
+        Ok(match icc_profile {
+            Some(profile) => image.with_icc_profile(profile),
+            None => image,
+        })
     }
 
     #[cfg(not(feature = "png"))]
@@ -307,6 +398,269 @@ impl ImageDecoder for PngDecoder {
     }
 }
 
+/// Incremental PNG decoder for images that arrive in pieces.
+///
+/// Feed bytes with [`push`](Self::push) as they arrive; after each push,
+/// [`latest_image`](Self::latest_image) holds every complete row decoded so
+/// far, with the rows still missing left transparent. Interlaced images
+/// only appear once they are complete. Errors, including truncated data,
+/// are reported by [`finish`](Self::finish) once the stream has ended.
+///
+/// The inflate state and decoded rows are kept between pushes, so each push
+/// only decodes the data it adds.
+///
+/// Images decode to 8-bit unpremultiplied RGBA, as [`PngDecoder`] does by
+/// default.
+#[derive(Default)]
+pub struct PngStreamDecoder {
+    /// Bytes before the first `IDAT` chunk, held until they are all present.
+    header: Vec<u8>,
+    /// Bytes pushed since the reader was created that it hasn't read yet.
+    #[cfg(feature = "png")]
+    source: PngStreamSource,
+    #[cfg(feature = "png")]
+    reader: Option<png::Reader<PngStreamSource>>,
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    rows: usize,
+    complete: bool,
+    error: Option<String>,
+}
+
+impl core::fmt::Debug for PngStreamDecoder {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PngStreamDecoder")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("rows", &self.rows)
+            .field("complete", &self.complete)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PngStreamDecoder {
+    /// Create a decoder with no data yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the next bytes of the stream and decode the rows they
+    /// complete.
+    pub fn push(&mut self, chunk: &[u8]) {
+        if chunk.is_empty() || self.complete || self.error.is_some() {
+            return;
+        }
+        #[cfg(feature = "png")]
+        if self.reader.is_some() {
+            self.source.0.lock().extend(chunk);
+            self.decode_available();
+            return;
+        }
+        self.header.extend_from_slice(chunk);
+        self.decode_available();
+    }
+
+    /// Get the image decoded so far, or `None` before the header and first
+    /// row have arrived.
+    pub fn latest_image(&self) -> Option<Image> {
+        if self.rows == 0 {
+            return None;
+        }
+        Image::from_raster_data_owned(self.image_info(), self.pixels.clone(), self.width * 4)
+    }
+
+    /// Get the number of rows decoded so far.
+    pub fn decoded_rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Check whether every row of the image has been decoded.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// End the stream, returning the complete image or the error that
+    /// stopped decoding.
+    pub fn finish(self) -> CodecResult<Image> {
+        if !self.complete {
+            return Err(CodecError::DecodingError(
+                self.error
+                    .unwrap_or_else(|| "PNG stream ended early".into()),
+            ));
+        }
+        let info = self.image_info();
+        Image::from_raster_data_owned(info, self.pixels, self.width * 4)
+            .ok_or_else(|| CodecError::DecodingError("Failed to create image".into()))
+    }
+
+    fn image_info(&self) -> crate::ImageInfo {
+        crate::ImageInfo::new(
+            self.width as i32,
+            self.height as i32,
+            skia_rs_core::ColorType::Rgba8888,
+            skia_rs_core::AlphaType::Unpremul,
+        )
+    }
+
+    /// Decode the rows completed by the data pushed so far.
+    ///
+    /// The header is parsed once everything up to the first `IDAT` chunk
+    /// has arrived. After that the reader stops with `UnexpectedEof` when
+    /// it runs out of data and picks up where it stopped on the next push.
+    #[cfg(feature = "png")]
+    fn decode_available(&mut self) {
+        if self.reader.is_none() {
+            if !png_image_data_reached(&self.header) {
+                return;
+            }
+            self.source
+                .0
+                .lock()
+                .extend(core::mem::take(&mut self.header));
+            let mut decoder = png::Decoder::new(self.source.clone());
+            decoder
+                .set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+            let reader = match decoder.read_info() {
+                Ok(reader) => reader,
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    return;
+                }
+            };
+            self.width = reader.info().width as usize;
+            self.height = reader.info().height as usize;
+            // Checked before allocating, as nothing but the header has
+            // been seen yet
+            if self.width as u64 * self.height as u64 > MAX_IMAGE_PIXELS {
+                self.error = Some("PNG dimensions too large".into());
+                return;
+            }
+            self.pixels = vec![0; self.width * self.height * 4];
+            self.reader = Some(reader);
+        }
+
+        let Some(reader) = self.reader.as_mut() else {
+            return;
+        };
+        let channels = reader.output_color_type().0.samples();
+        let row_bytes = self.width * 4;
+        let mut rgba = Vec::with_capacity(row_bytes);
+        loop {
+            match reader.next_interlaced_row() {
+                Ok(Some(row)) => {
+                    rgba.clear();
+                    for src in row.data().chunks_exact(channels) {
+                        rgba.extend_from_slice(&expand_to_rgba(src, u8::MAX));
+                    }
+                    match row.interlace() {
+                        png::InterlaceInfo::Adam7(pass) => {
+                            png::expand_interlaced_row(
+                                &mut self.pixels,
+                                row_bytes,
+                                &rgba,
+                                pass,
+                                32,
+                            );
+                        }
+                        png::InterlaceInfo::Null(_) => {
+                            let start = self.rows * row_bytes;
+                            self.pixels[start..start + row_bytes].copy_from_slice(&rgba);
+                            self.rows += 1;
+                        }
+                    }
+                }
+                Ok(None) => {
+                    self.rows = self.height;
+                    self.complete = true;
+                    break;
+                }
+                Err(png::DecodingError::IoError(e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    break;
+                }
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "png"))]
+    fn decode_available(&mut self) {
+        self.error = Some("PNG decoding requires the 'png' feature".into());
+    }
+}
+
+/// The bytes a [`PngStreamDecoder`]'s reader has yet to read.
+///
+/// Reads return nothing once the pushed data runs out, which the `png`
+/// reader reports as a recoverable `UnexpectedEof`.
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, Default)]
+struct PngStreamSource(std::sync::Arc<parking_lot::Mutex<std::collections::VecDeque<u8>>>);
+
+#[cfg(feature = "png")]
+impl Read for PngStreamSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.lock().read(buf)
+    }
+}
+
+/// Check whether `data` reaches the first `IDAT` chunk, so that reading the
+/// PNG header from it can't run out of input.
+///
+/// Data that doesn't start with the PNG signature counts as complete, so
+/// the decoder reports it.
+#[cfg(feature = "png")]
+fn png_image_data_reached(data: &[u8]) -> bool {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+    if data.len() >= SIGNATURE.len() && data[..SIGNATURE.len()] != SIGNATURE {
+        return true;
+    }
+    let mut offset = SIGNATURE.len();
+    while let Some(chunk) = offset.checked_add(8).and_then(|end| data.get(offset..end)) {
+        if &chunk[4..] == b"IDAT" {
+            return true;
+        }
+        let len = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as usize;
+        // Length, type and CRC fields around the chunk data
+        offset = offset.saturating_add(len).saturating_add(12);
+    }
+    false
+}
+
+/// Expand a gray, gray-alpha, RGB or RGBA pixel to RGBA.
+#[cfg(feature = "png")]
+fn expand_to_rgba<T: Copy>(pixel: &[T], opaque: T) -> [T; 4] {
+    match *pixel {
+        [g] => [g, g, g, opaque],
+        [g, a] => [g, g, g, a],
+        [r, g, b] => [r, g, b, opaque],
+        [r, g, b, a, ..] => [r, g, b, a],
+        [] => [opaque; 4],
+    }
+}
+
+/// Build a table converting samples encoded with a PNG `gAMA` value to sRGB.
+#[cfg(feature = "png")]
+fn gamma_lut<T>(gamma: f32, max: T) -> Vec<T>
+where
+    T: Copy + Into<f32> + TryFrom<u32>,
+{
+    let max_value: f32 = max.into();
+    (0..=max_value as u32)
+        .map(|sample| {
+            let linear = (sample as f32 / max_value).powf(1.0 / gamma);
+            let encoded = skia_rs_core::linear_to_srgb(linear) * max_value;
+            T::try_from(encoded.round().clamp(0.0, max_value) as u32).unwrap_or(max)
+        })
+        .collect()
+}
+
 /// PNG encoder.
 #[derive(Debug, Default)]
 pub struct PngEncoder;
@@ -329,9 +683,7 @@ impl ImageEncoder for PngEncoder {
             .write_header()
             .map_err(|e| CodecError::EncodingError(e.to_string()))?;
 
-        let pixels = image
-            .peek_pixels()
-            .ok_or_else(|| CodecError::EncodingError("Cannot access pixels".into()))?;
+        let pixels = packed_pixels(image)?;
 
         // Convert to RGBA if necessary based on color type
         let rgba_data = match image.color_type() {
@@ -376,14 +728,52 @@ impl ImageEncoder for PngEncoder {
 // JPEG Codec (stub)
 // =============================================================================
 
+/// Options for [`JpegDecoder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JpegDecodeOptions {
+    /// Rotate and flip the pixels to the orientation given by the EXIF
+    /// `Orientation` tag, so the image comes out the way it is displayed.
+    pub apply_orientation: bool,
+}
+
 /// JPEG decoder.
 #[derive(Debug, Default)]
-pub struct JpegDecoder;
+pub struct JpegDecoder {
+    options: JpegDecodeOptions,
+}
 
 impl JpegDecoder {
     /// Create a new JPEG decoder.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Create a JPEG decoder with the given options.
+    pub fn with_options(options: JpegDecodeOptions) -> Self {
+        Self { options }
+    }
+
+    /// Get the decode options.
+    pub fn options(&self) -> JpegDecodeOptions {
+        self.options
+    }
+
+    /// Read the EXIF `Orientation` tag (1-8) without decoding the pixels.
+    ///
+    /// Returns `None` if the image has no EXIF data or no valid orientation.
+    #[cfg(feature = "jpeg")]
+    pub fn read_orientation(&self, data: &[u8]) -> CodecResult<Option<u8>> {
+        let mut decoder = jpeg_decoder::Decoder::new(data);
+        decoder.read_info().map_err(jpeg_error)?;
+        Ok(decoder.exif_data().and_then(exif_orientation))
+    }
+
+    /// Read the EXIF `Orientation` tag (1-8) without decoding the pixels.
+    #[cfg(not(feature = "jpeg"))]
+    pub fn read_orientation(&self, _data: &[u8]) -> CodecResult<Option<u8>> {
+        Err(CodecError::Unsupported(
+            "JPEG decoding requires the 'jpeg' feature".into(),
+        ))
     }
 }
 
@@ -391,43 +781,48 @@ impl ImageDecoder for JpegDecoder {
     #[cfg(feature = "jpeg")]
     fn decode<R: Read>(&self, reader: R) -> CodecResult<Image> {
         let mut decoder = jpeg_decoder::Decoder::new(reader);
-        let pixels = decoder
-            .decode()
-            .map_err(|e| CodecError::DecodingError(e.to_string()))?;
+        let pixels = decoder.decode().map_err(jpeg_error)?;
         let info = decoder
             .info()
             .ok_or_else(|| CodecError::DecodingError("No image info".into()))?;
 
-        let width = info.width as i32;
-        let height = info.height as i32;
+        let mut width = info.width as i32;
+        let mut height = info.height as i32;
+
+        // Convert to RGBA. The decoder has already upsampled subsampled
+        // chroma, combined progressive scans and converted YCbCr to RGB and
+        // YCCK to CMYK.
+        let rgba: Vec<u8> = match info.pixel_format {
+            jpeg_decoder::PixelFormat::RGB24 => pixels
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            jpeg_decoder::PixelFormat::L8 => pixels.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            jpeg_decoder::PixelFormat::L16 => pixels
+                .chunks_exact(2)
+                .flat_map(|l| {
+                    let g = (u16::from_ne_bytes([l[0], l[1]]) >> 8) as u8;
+                    [g, g, g, 255]
+                })
+                .collect(),
+            jpeg_decoder::PixelFormat::CMYK32 => pixels
+                .chunks_exact(4)
+                .flat_map(|cmyk| {
+                    let white = 255 - u32::from(cmyk[3]);
+                    let channel = |ink: u8| ((255 - u32::from(ink)) * white / 255) as u8;
+                    [channel(cmyk[0]), channel(cmyk[1]), channel(cmyk[2]), 255]
+                })
+                .collect(),
+        };
 
-        // Convert to RGBA
-        let rgba = match info.pixel_format {
-            jpeg_decoder::PixelFormat::RGB24 => {
-                let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
-                for chunk in pixels.chunks(3) {
-                    rgba.push(chunk[0]);
-                    rgba.push(chunk[1]);
-                    rgba.push(chunk[2]);
-                    rgba.push(255);
-                }
-                rgba
-            }
-            jpeg_decoder::PixelFormat::L8 => {
-                let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
-                for &g in &pixels {
-                    rgba.push(g);
-                    rgba.push(g);
-                    rgba.push(g);
-                    rgba.push(255);
-                }
-                rgba
-            }
-            _ => {
-                return Err(CodecError::Unsupported(
-                    "Unsupported JPEG pixel format".into(),
-                ));
+        let orientation = decoder.exif_data().and_then(exif_orientation);
+        let rgba = match orientation {
+            Some(orientation) if self.options.apply_orientation => {
+                let (oriented, w, h) = orient_rgba(&rgba, width, height, orientation);
+                (width, height) = (w, h);
+                oriented
             }
+            _ => rgba,
         };
 
         let img_info = crate::ImageInfo::new(
@@ -453,6 +848,82 @@ impl ImageDecoder for JpegDecoder {
     }
 }
 
+/// Map a JPEG decoding error, keeping unsupported features distinct from
+/// corrupt data.
+#[cfg(feature = "jpeg")]
+fn jpeg_error(error: jpeg_decoder::Error) -> CodecError {
+    match error {
+        jpeg_decoder::Error::Unsupported(feature) => {
+            CodecError::Unsupported(format!("JPEG feature {feature:?}"))
+        }
+        error => CodecError::DecodingError(error.to_string()),
+    }
+}
+
+/// Read the `Orientation` tag from EXIF data starting at the TIFF header.
+#[cfg(feature = "jpeg")]
+fn exif_orientation(exif: &[u8]) -> Option<u8> {
+    let big_endian = match exif.get(..4)? {
+        b"MM\0*" => true,
+        b"II*\0" => false,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| -> Option<u16> {
+        let bytes = [*exif.get(offset)?, *exif.get(offset + 1)?];
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let u32_at = |offset: usize| -> Option<u32> {
+        let bytes: [u8; 4] = exif.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    // Orientation lives in IFD0 as a SHORT
+    let ifd = u32_at(4)? as usize;
+    let entries = u16_at(ifd)? as usize;
+    (0..entries)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|&entry| u16_at(entry) == Some(0x0112) && u16_at(entry + 2) == Some(3))
+        .and_then(|entry| u16_at(entry + 8))
+        .filter(|orientation| (1..=8).contains(orientation))
+        .map(|orientation| orientation as u8)
+}
+
+/// Rotate and flip RGBA pixels from EXIF `orientation` to the displayed
+/// orientation, returning the pixels and their new width and height.
+#[cfg(feature = "jpeg")]
+fn orient_rgba(pixels: &[u8], width: i32, height: i32, orientation: u8) -> (Vec<u8>, i32, i32) {
+    let (w, h) = (width as usize, height as usize);
+    // Orientations 5-8 swap width and height
+    let (dst_w, dst_h) = if orientation >= 5 { (h, w) } else { (w, h) };
+    let mut oriented = vec![0u8; pixels.len()];
+    for y in 0..dst_h {
+        for x in 0..dst_w {
+            let (src_x, src_y) = match orientation {
+                2 => (w - 1 - x, y),
+                3 => (w - 1 - x, h - 1 - y),
+                4 => (x, h - 1 - y),
+                5 => (y, x),
+                6 => (y, h - 1 - x),
+                7 => (w - 1 - y, h - 1 - x),
+                8 => (w - 1 - y, x),
+                _ => (x, y),
+            };
+            let src = (src_y * w + src_x) * 4;
+            let dst = (y * dst_w + x) * 4;
+            oriented[dst..dst + 4].copy_from_slice(&pixels[src..src + 4]);
+        }
+    }
+    (oriented, dst_w as i32, dst_h as i32)
+}
+
 /// JPEG encoder.
 #[derive(Debug)]
 pub struct JpegEncoder {
@@ -487,9 +958,7 @@ impl Default for JpegEncoder {
 impl ImageEncoder for JpegEncoder {
     #[cfg(feature = "jpeg")]
     fn encode<W: Write>(&self, image: &Image, mut writer: W) -> CodecResult<()> {
-        let pixels = image
-            .peek_pixels()
-            .ok_or_else(|| CodecError::EncodingError("Cannot access pixels".into()))?;
+        let pixels = packed_pixels(image)?;
 
         // Convert to RGB (JPEG doesn't support alpha)
         let rgb = match image.color_type() {
@@ -547,6 +1016,29 @@ impl ImageEncoder for JpegEncoder {
 // GIF Codec
 // =============================================================================
 
+/// How an animation frame is disposed of before the next frame is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DisposalMethod {
+    /// Leave the frame in place; also used when no disposal is specified.
+    #[default]
+    Keep,
+    /// Clear the frame's area to transparent.
+    Background,
+    /// Restore the frame's area to what was there before the frame.
+    Previous,
+}
+
+/// A frame of an animated image, composited onto the full canvas.
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    /// The canvas after drawing this frame, at the animation's full size.
+    pub image: Image,
+    /// How long the frame is shown, in milliseconds.
+    pub delay_ms: u32,
+    /// How the frame is disposed of before the next one is drawn.
+    pub disposal: DisposalMethod,
+}
+
 /// GIF decoder.
 #[derive(Debug, Default)]
 pub struct GifDecoder;
@@ -556,6 +1048,136 @@ impl GifDecoder {
     pub fn new() -> Self {
         Self
     }
+
+    /// Decode every frame of an animated GIF.
+    ///
+    /// Each frame is drawn at its offset onto a canvas the size of the
+    /// logical screen, over what the previous frames left behind after
+    /// their disposal. The canvas starts out transparent, and
+    /// [`DisposalMethod::Background`] clears to transparent rather than the
+    /// background color, as browsers do.
+    ///
+    /// Every frame holds a full canvas, so the logical screen and the total
+    /// of all frames are each limited to [`MAX_IMAGE_PIXELS`].
+    #[cfg(feature = "gif")]
+    pub fn decode_frames(&self, data: &[u8]) -> CodecResult<Vec<AnimationFrame>> {
+        self.decode_frames_within(data, MAX_IMAGE_PIXELS)
+    }
+
+    /// [`decode_frames`](Self::decode_frames), with every frame's canvas
+    /// counting towards `pixel_budget`.
+    #[cfg(feature = "gif")]
+    fn decode_frames_within(
+        &self,
+        data: &[u8],
+        pixel_budget: u64,
+    ) -> CodecResult<Vec<AnimationFrame>> {
+        let too_large = || CodecError::DecodingError("GIF animation too large".into());
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options
+            .read_info(data)
+            .map_err(|e| CodecError::DecodingError(e.to_string()))?;
+
+        let width = decoder.width() as usize;
+        let height = decoder.height() as usize;
+        // Checked before allocating, as only the header has been read
+        let canvas_pixels = width as u64 * height as u64;
+        if canvas_pixels > MAX_IMAGE_PIXELS.min(pixel_budget) {
+            return Err(too_large());
+        }
+        let row_bytes = width * 4;
+        let mut canvas = vec![0u8; height * row_bytes];
+        let mut frames = Vec::new();
+        let mut used = 0;
+
+        while let Some(frame) = decoder
+            .read_next_frame()
+            .map_err(|e| CodecError::DecodingError(e.to_string()))?
+        {
+            used += canvas_pixels;
+            if used > pixel_budget {
+                return Err(too_large());
+            }
+            let disposal = match frame.dispose {
+                gif::DisposalMethod::Background => DisposalMethod::Background,
+                gif::DisposalMethod::Previous => DisposalMethod::Previous,
+                gif::DisposalMethod::Any | gif::DisposalMethod::Keep => DisposalMethod::Keep,
+            };
+
+            // The frame's area, clipped to the logical screen
+            let left = (frame.left as usize).min(width);
+            let top = (frame.top as usize).min(height);
+            let right = (left + frame.width as usize).min(width);
+            let bottom = (top + frame.height as usize).min(height);
+            let frame_row_bytes = frame.width as usize * 4;
+
+            // Only the frame's area can change, so only it is saved
+            let previous: Option<Vec<u8>> = (disposal == DisposalMethod::Previous).then(|| {
+                (top..bottom)
+                    .flat_map(|y| &canvas[y * row_bytes + left * 4..y * row_bytes + right * 4])
+                    .copied()
+                    .collect()
+            });
+
+            // GIF pixels are either opaque or fully transparent
+            for y in top..bottom {
+                let src_row = &frame.buffer[(y - top) * frame_row_bytes..];
+                let dst_row = &mut canvas[y * row_bytes..];
+                for x in left..right {
+                    let src = &src_row[(x - left) * 4..(x - left) * 4 + 4];
+                    if src[3] != 0 {
+                        dst_row[x * 4..x * 4 + 4].copy_from_slice(src);
+                    }
+                }
+            }
+
+            let info = crate::ImageInfo::new(
+                width as i32,
+                height as i32,
+                skia_rs_core::ColorType::Rgba8888,
+                skia_rs_core::AlphaType::Unpremul,
+            );
+            let image = Image::from_raster_data(&info, &canvas, row_bytes)
+                .ok_or_else(|| CodecError::DecodingError("Failed to create image".into()))?;
+            frames.push(AnimationFrame {
+                image,
+                delay_ms: u32::from(frame.delay) * 10,
+                disposal,
+            });
+
+            match disposal {
+                DisposalMethod::Keep => {}
+                DisposalMethod::Background => {
+                    for y in top..bottom {
+                        canvas[y * row_bytes + left * 4..y * row_bytes + right * 4].fill(0);
+                    }
+                }
+                DisposalMethod::Previous => {
+                    if let Some(previous) = previous {
+                        let area_row_bytes = (right - left) * 4;
+                        for (y, saved) in (top..bottom).zip(previous.chunks(area_row_bytes)) {
+                            canvas[y * row_bytes + left * 4..y * row_bytes + right * 4]
+                                .copy_from_slice(saved);
+                        }
+                    }
+                }
+            }
+        }
+
+        if frames.is_empty() {
+            return Err(CodecError::DecodingError("No frames in GIF".into()));
+        }
+        Ok(frames)
+    }
+
+    /// Decode every frame of an animated GIF.
+    #[cfg(not(feature = "gif"))]
+    pub fn decode_frames(&self, _data: &[u8]) -> CodecResult<Vec<AnimationFrame>> {
+        Err(CodecError::Unsupported(
+            "GIF decoding requires the 'gif' feature".into(),
+        ))
+    }
 }
 
 impl ImageDecoder for GifDecoder {
@@ -698,9 +1320,7 @@ impl Default for WebpEncoder {
 impl ImageEncoder for WebpEncoder {
     #[cfg(feature = "webp")]
     fn encode<W: Write>(&self, image: &Image, mut writer: W) -> CodecResult<()> {
-        let pixels = image
-            .peek_pixels()
-            .ok_or_else(|| CodecError::EncodingError("Cannot access pixels".into()))?;
+        let pixels = packed_pixels(image)?;
         let width = image.width() as u32;
         let height = image.height() as u32;
 
@@ -787,9 +1407,7 @@ impl BmpEncoder {
 
 impl ImageEncoder for BmpEncoder {
     fn encode<W: Write>(&self, image: &Image, mut writer: W) -> CodecResult<()> {
-        let pixels = image
-            .peek_pixels()
-            .ok_or_else(|| CodecError::EncodingError("Cannot access pixels".into()))?;
+        let pixels = packed_pixels(image)?;
 
         let width = image.width() as u32;
         let height = image.height() as u32;
@@ -1369,9 +1987,7 @@ fn decode_wbmp(data: &[u8]) -> CodecResult<Image> {
 fn encode_wbmp<W: Write>(image: &Image, writer: &mut W) -> CodecResult<()> {
     let width = image.width() as u32;
     let height = image.height() as u32;
-    let pixels = image
-        .peek_pixels()
-        .ok_or_else(|| CodecError::EncodingError("Cannot access pixels".into()))?;
+    let pixels = packed_pixels(image)?;
 
     // Write type (0)
     write_wbmp_int(writer, 0)?;
@@ -1612,9 +2228,7 @@ impl ImageEncoder for AvifEncoder {
         let width = image.width() as usize;
         let height = image.height() as usize;
 
-        let pixels = image
-            .peek_pixels()
-            .ok_or_else(|| CodecError::EncodingError("Failed to access pixels".into()))?;
+        let pixels = packed_pixels(image)?;
 
         // Convert to RGBA pixels for ravif
         let rgba_pixels: Vec<RGBA8> = pixels
@@ -1896,56 +2510,1005 @@ fn get_raw_dimensions(_data: &[u8]) -> CodecResult<(i32, i32)> {
 }
 
 // =============================================================================
-// Utility Functions
+// TIFF Codec
 // =============================================================================
 
-/// Decode an image from bytes, auto-detecting the format.
-pub fn decode_image(data: &[u8]) -> CodecResult<Image> {
-    let format = ImageFormat::from_magic(data);
+/// Options for [`TiffDecoder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TiffDecodeOptions {
+    /// Zero-based index of the page (IFD) to decode in multi-page files.
+    pub page: usize,
+}
 
-    match format {
-        ImageFormat::Png => PngDecoder::new().decode_bytes(data),
-        ImageFormat::Jpeg => JpegDecoder::new().decode_bytes(data),
-        ImageFormat::Gif => GifDecoder::new().decode_bytes(data),
-        ImageFormat::WebP => WebpDecoder::new().decode_bytes(data),
-        ImageFormat::Bmp => BmpDecoder::new().decode_bytes(data),
-        ImageFormat::Ico => IcoDecoder::new().decode_bytes(data),
-        ImageFormat::Wbmp => WbmpDecoder::new().decode_bytes(data),
-        ImageFormat::Avif => AvifDecoder::new().decode_bytes(data),
-        ImageFormat::Raw => RawDecoder::new().decode_bytes(data),
-        _ => Err(CodecError::Unsupported(format!(
-            "Format {:?} not supported",
-            format
-        ))),
-    }
+/// TIFF decoder.
+///
+/// Decodes baseline TIFF strips that are uncompressed, PackBits or LZW
+/// compressed, with 8 or 16 bits per sample in grayscale, RGB or RGBA.
+/// Images are decoded to 8-bit RGBA.
+#[derive(Debug, Default)]
+pub struct TiffDecoder {
+    options: TiffDecodeOptions,
 }
 
-/// Get the image dimensions without fully decoding.
-pub fn get_image_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
-    let format = ImageFormat::from_magic(data);
+impl TiffDecoder {
+    /// Create a new TIFF decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    match format {
-        ImageFormat::Png => get_png_dimensions(data),
-        ImageFormat::Jpeg => get_jpeg_dimensions(data),
-        ImageFormat::Bmp => get_bmp_dimensions(data),
-        ImageFormat::Ico => get_ico_dimensions(data),
-        ImageFormat::Wbmp => get_wbmp_dimensions(data),
-        ImageFormat::Avif => get_avif_dimensions(data),
-        ImageFormat::Raw => get_raw_dimensions(data),
-        _ => Err(CodecError::Unsupported(format!(
-            "Format {:?} not supported",
-            format
-        ))),
+    /// Create a TIFF decoder with the given options.
+    pub fn with_options(options: TiffDecodeOptions) -> Self {
+        Self { options }
     }
-}
 
-fn get_png_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
-    // PNG IHDR chunk starts at byte 8, width at offset 16, height at offset 20
-    if data.len() < 24 {
-        return Err(CodecError::InvalidData("PNG too short".into()));
+    /// Get the decode options.
+    pub fn options(&self) -> &TiffDecodeOptions {
+        &self.options
     }
 
-    let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]) as i32;
+    /// Count the pages in a TIFF file.
+    #[cfg(feature = "tiff")]
+    pub fn page_count(data: &[u8]) -> CodecResult<usize> {
+        Ok(tiff_ifd_offsets(data)?.len())
+    }
+
+    /// Count the pages in a TIFF file.
+    #[cfg(not(feature = "tiff"))]
+    pub fn page_count(_data: &[u8]) -> CodecResult<usize> {
+        Err(CodecError::Unsupported(
+            "TIFF decoding requires the 'tiff' feature".into(),
+        ))
+    }
+}
+
+impl ImageDecoder for TiffDecoder {
+    #[cfg(feature = "tiff")]
+    fn decode<R: Read>(&self, mut reader: R) -> CodecResult<Image> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let page = tiff_page(&data, self.options.page)?;
+        decode_tiff_page(&data, &page)
+    }
+
+    #[cfg(not(feature = "tiff"))]
+    fn decode<R: Read>(&self, _reader: R) -> CodecResult<Image> {
+        Err(CodecError::Unsupported(
+            "TIFF decoding requires the 'tiff' feature".into(),
+        ))
+    }
+
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Tiff
+    }
+}
+
+/// Compression scheme used by [`TiffEncoder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TiffCompression {
+    /// No compression.
+    #[default]
+    None,
+    /// PackBits run-length encoding.
+    PackBits,
+    /// LZW compression.
+    Lzw,
+}
+
+impl TiffCompression {
+    /// The TIFF `Compression` tag value.
+    #[cfg(feature = "tiff")]
+    fn tag_value(self) -> u16 {
+        match self {
+            Self::None => 1,
+            Self::Lzw => 5,
+            Self::PackBits => 32773,
+        }
+    }
+}
+
+/// TIFF encoder.
+///
+/// Writes 8-bit RGB for opaque images and 8-bit RGBA otherwise, as a
+/// single strip per page.
+#[derive(Debug, Default)]
+pub struct TiffEncoder {
+    compression: TiffCompression,
+}
+
+impl TiffEncoder {
+    /// Create a new TIFF encoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the compression scheme.
+    pub fn with_compression(mut self, compression: TiffCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Encode several images as the pages of one TIFF file.
+    #[cfg(feature = "tiff")]
+    pub fn encode_pages<W: Write>(&self, images: &[Image], mut writer: W) -> CodecResult<()> {
+        writer.write_all(&encode_tiff(images, self.compression)?)?;
+        Ok(())
+    }
+
+    /// Encode several images as the pages of one TIFF file.
+    #[cfg(not(feature = "tiff"))]
+    pub fn encode_pages<W: Write>(&self, _images: &[Image], _writer: W) -> CodecResult<()> {
+        Err(CodecError::Unsupported(
+            "TIFF encoding requires the 'tiff' feature".into(),
+        ))
+    }
+}
+
+impl ImageEncoder for TiffEncoder {
+    fn encode<W: Write>(&self, image: &Image, writer: W) -> CodecResult<()> {
+        self.encode_pages(std::slice::from_ref(image), writer)
+    }
+
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Tiff
+    }
+}
+
+/// Reads TIFF values in the file's byte order.
+#[cfg(feature = "tiff")]
+#[derive(Clone, Copy)]
+struct TiffReader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+#[cfg(feature = "tiff")]
+impl<'a> TiffReader<'a> {
+    fn new(data: &'a [u8]) -> CodecResult<Self> {
+        let big_endian = match data.get(..4) {
+            Some(b"II*\0") => false,
+            Some(b"MM\0*") => true,
+            _ => return Err(CodecError::InvalidData("Not a TIFF file".into())),
+        };
+        Ok(Self { data, big_endian })
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> CodecResult<&'a [u8]> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| CodecError::InvalidData("TIFF data truncated".into()))
+    }
+
+    fn u16(&self, offset: usize) -> CodecResult<u16> {
+        let b = self.bytes(offset, 2)?;
+        Ok(if self.big_endian {
+            u16::from_be_bytes([b[0], b[1]])
+        } else {
+            u16::from_le_bytes([b[0], b[1]])
+        })
+    }
+
+    fn u32(&self, offset: usize) -> CodecResult<u32> {
+        let b = self.bytes(offset, 4)?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+        } else {
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        })
+    }
+}
+
+/// Find the offset of every IFD (page) in a TIFF file.
+#[cfg(feature = "tiff")]
+fn tiff_ifd_offsets(data: &[u8]) -> CodecResult<Vec<usize>> {
+    let reader = TiffReader::new(data)?;
+    let mut offsets = Vec::new();
+    let mut offset = reader.u32(4)? as usize;
+    while offset != 0 {
+        // Guard against IFD chains that loop back on themselves
+        if offsets.contains(&offset) {
+            return Err(CodecError::InvalidData("TIFF IFD chain loops".into()));
+        }
+        offsets.push(offset);
+        let count = reader.u16(offset)? as usize;
+        offset = reader.u32(offset + 2 + count * 12)? as usize;
+    }
+    Ok(offsets)
+}
+
+/// The tags of one TIFF page needed to decode it.
+#[cfg(feature = "tiff")]
+#[derive(Debug)]
+struct TiffPage {
+    width: u32,
+    height: u32,
+    bits_per_sample: u16,
+    samples_per_pixel: u16,
+    compression: u16,
+    photometric: u16,
+    predictor: u16,
+    planar: u16,
+    extra_sample: u16,
+    rows_per_strip: u32,
+    strip_offsets: Vec<u32>,
+    strip_byte_counts: Vec<u32>,
+}
+
+/// Parse the IFD of the given page.
+#[cfg(feature = "tiff")]
+fn tiff_page(data: &[u8], page: usize) -> CodecResult<TiffPage> {
+    let reader = TiffReader::new(data)?;
+    let offsets = tiff_ifd_offsets(data)?;
+    let offset = *offsets.get(page).ok_or_else(|| {
+        CodecError::InvalidData(format!(
+            "TIFF has {} pages, not {}",
+            offsets.len(),
+            page + 1
+        ))
+    })?;
+
+    let mut result = TiffPage {
+        width: 0,
+        height: 0,
+        bits_per_sample: 1,
+        samples_per_pixel: 1,
+        compression: 1,
+        photometric: u16::MAX,
+        predictor: 1,
+        planar: 1,
+        extra_sample: 0,
+        rows_per_strip: u32::MAX,
+        strip_offsets: Vec::new(),
+        strip_byte_counts: Vec::new(),
+    };
+
+    let count = reader.u16(offset)? as usize;
+    for i in 0..count {
+        let entry = offset + 2 + i * 12;
+        let tag = reader.u16(entry)?;
+        let kind = reader.u16(entry + 2)?;
+        let len = reader.u32(entry + 4)? as usize;
+        let size = match kind {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 | 11 => 4,
+            _ => continue,
+        };
+        // Values that don't fit in the entry are stored elsewhere
+        let values_at = if len * size <= 4 {
+            entry + 8
+        } else {
+            reader.u32(entry + 8)? as usize
+        };
+        let values = (0..len)
+            .map(|j| match size {
+                1 => reader.bytes(values_at + j, 1).map(|b| b[0] as u32),
+                2 => reader.u16(values_at + j * 2).map(u32::from),
+                _ => reader.u32(values_at + j * 4),
+            })
+            .collect::<CodecResult<Vec<u32>>>()?;
+        let first = values.first().copied().unwrap_or(0);
+
+        match tag {
+            256 => result.width = first,
+            257 => result.height = first,
+            258 => result.bits_per_sample = first as u16,
+            259 => result.compression = first as u16,
+            262 => result.photometric = first as u16,
+            273 => result.strip_offsets = values,
+            277 => result.samples_per_pixel = first as u16,
+            278 => result.rows_per_strip = first,
+            279 => result.strip_byte_counts = values,
+            284 => result.planar = first as u16,
+            317 => result.predictor = first as u16,
+            338 => result.extra_sample = first as u16,
+            _ => {}
+        }
+    }
+
+    if result.width == 0
+        || result.height == 0
+        || result.width > i32::MAX as u32
+        || result.height > i32::MAX as u32
+    {
+        return Err(CodecError::InvalidData("Invalid TIFF dimensions".into()));
+    }
+    if result.strip_offsets.is_empty()
+        || result.strip_offsets.len() != result.strip_byte_counts.len()
+    {
+        return Err(CodecError::InvalidData("Invalid TIFF strips".into()));
+    }
+    Ok(result)
+}
+
+/// The largest TIFF page, in pixels, that will be decoded.
+#[cfg(feature = "tiff")]
+const MAX_TIFF_PIXELS: u64 = MAX_IMAGE_PIXELS;
+
+/// Upper bound on how many bytes one compressed byte of a strip can expand
+/// to: PackBits turns two bytes into at most 128, and a 9-bit LZW code
+/// emits at most one 4096-byte table entry.
+#[cfg(feature = "tiff")]
+fn tiff_max_expansion(compression: u16) -> u64 {
+    match compression {
+        1 => 1,
+        32773 => 64,
+        _ => 4096,
+    }
+}
+
+/// Decode one parsed TIFF page to RGBA.
+#[cfg(feature = "tiff")]
+fn decode_tiff_page(data: &[u8], page: &TiffPage) -> CodecResult<Image> {
+    let reader = TiffReader::new(data)?;
+    let samples = page.samples_per_pixel as usize;
+    let (gray, has_alpha) = match (page.photometric, samples) {
+        (0 | 1, 1) => (true, false),
+        (0 | 1, 2) => (true, true),
+        (2, 3) => (false, false),
+        (2, 4) => (false, true),
+        _ => {
+            return Err(CodecError::Unsupported(format!(
+                "TIFF photometric interpretation {} with {} samples",
+                page.photometric, samples
+            )));
+        }
+    };
+    if page.bits_per_sample != 8 && page.bits_per_sample != 16 {
+        return Err(CodecError::Unsupported(format!(
+            "TIFF with {} bits per sample",
+            page.bits_per_sample
+        )));
+    }
+    if page.planar != 1 {
+        return Err(CodecError::Unsupported("Planar TIFF".into()));
+    }
+
+    let width = page.width as usize;
+    let height = page.height as usize;
+    let sample_bytes = page.bits_per_sample as usize / 8;
+    let too_large = || CodecError::InvalidData("TIFF dimensions too large".into());
+    if u64::from(page.width) * u64::from(page.height) > MAX_TIFF_PIXELS {
+        return Err(too_large());
+    }
+    let row_len = width
+        .checked_mul(samples * sample_bytes)
+        .ok_or_else(too_large)?;
+    let raw_len = row_len.checked_mul(height).ok_or_else(too_large)?;
+    let rows_per_strip = (page.rows_per_strip as usize).clamp(1, height);
+
+    // Reject headers whose strips can't hold the image before allocating it
+    let strip_bytes: u64 = page
+        .strip_offsets
+        .iter()
+        .zip(&page.strip_byte_counts)
+        .map(|(&offset, &len)| {
+            (len as usize).min(data.len().saturating_sub(offset as usize)) as u64
+        })
+        .sum();
+    if strip_bytes.saturating_mul(tiff_max_expansion(page.compression)) < raw_len as u64 {
+        return Err(CodecError::InvalidData("TIFF strips too short".into()));
+    }
+
+    // Decompress strips into one buffer of rows
+    let mut raw = Vec::with_capacity(raw_len);
+    for (&offset, &len) in page.strip_offsets.iter().zip(&page.strip_byte_counts) {
+        let strip = reader.bytes(offset as usize, len as usize)?;
+        let rows = rows_per_strip.min(height - raw.len() / row_len);
+        let mut decoded = match page.compression {
+            1 => strip.to_vec(),
+            5 => weezl::decode::Decoder::with_tiff_size_switch(weezl::BitOrder::Msb, 8)
+                .decode(strip)
+                .map_err(|e| CodecError::DecodingError(format!("TIFF LZW error: {}", e)))?,
+            32773 => unpack_bits(strip, rows * row_len)?,
+            other => {
+                return Err(CodecError::Unsupported(format!(
+                    "TIFF compression {}",
+                    other
+                )));
+            }
+        };
+        if decoded.len() < rows * row_len {
+            return Err(CodecError::InvalidData("TIFF strip too short".into()));
+        }
+        decoded.truncate(rows * row_len);
+        raw.extend_from_slice(&decoded);
+        if raw.len() == raw_len {
+            break;
+        }
+    }
+    if raw.len() < raw_len {
+        return Err(CodecError::InvalidData("TIFF has too few strips".into()));
+    }
+
+    // Read samples in file byte order
+    let mut values: Vec<u16> = if sample_bytes == 2 {
+        raw.chunks_exact(2)
+            .map(|b| {
+                if reader.big_endian {
+                    u16::from_be_bytes([b[0], b[1]])
+                } else {
+                    u16::from_le_bytes([b[0], b[1]])
+                }
+            })
+            .collect()
+    } else {
+        raw.iter().map(|&b| b as u16).collect()
+    };
+
+    // Undo horizontal differencing
+    match page.predictor {
+        1 => {}
+        2 => {
+            let mask = if sample_bytes == 2 { 0xFFFF } else { 0xFF };
+            for row in values.chunks_exact_mut(width * samples) {
+                for i in samples..row.len() {
+                    row[i] = row[i].wrapping_add(row[i - samples]) & mask;
+                }
+            }
+        }
+        other => {
+            return Err(CodecError::Unsupported(format!("TIFF predictor {}", other)));
+        }
+    }
+
+    let shift = if sample_bytes == 2 { 8 } else { 0 };
+    let to_u8 = |v: u16| (v >> shift) as u8;
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for pixel in values.chunks_exact(samples) {
+        let alpha = if has_alpha {
+            to_u8(pixel[samples - 1])
+        } else {
+            255
+        };
+        if gray {
+            let mut v = to_u8(pixel[0]);
+            if page.photometric == 0 {
+                v = 255 - v;
+            }
+            pixels.extend_from_slice(&[v, v, v, alpha]);
+        } else {
+            pixels.extend_from_slice(&[to_u8(pixel[0]), to_u8(pixel[1]), to_u8(pixel[2]), alpha]);
+        }
+    }
+
+    // ExtraSamples 1 means associated (premultiplied) alpha
+    let alpha_type = if !has_alpha {
+        skia_rs_core::AlphaType::Opaque
+    } else if page.extra_sample == 1 {
+        skia_rs_core::AlphaType::Premul
+    } else {
+        skia_rs_core::AlphaType::Unpremul
+    };
+    let info = crate::ImageInfo::new(
+        width as i32,
+        height as i32,
+        skia_rs_core::ColorType::Rgba8888,
+        alpha_type,
+    );
+    Image::from_raster_data_owned(info, pixels, width * 4)
+        .ok_or_else(|| CodecError::DecodingError("Failed to create image".into()))
+}
+
+/// Decompress PackBits data, stopping once `expected` bytes are produced.
+#[cfg(feature = "tiff")]
+fn unpack_bits(data: &[u8], expected: usize) -> CodecResult<Vec<u8>> {
+    let truncated = || CodecError::InvalidData("PackBits data truncated".into());
+    let mut out = Vec::with_capacity(expected);
+    let mut i = 0;
+    while out.len() < expected && i < data.len() {
+        let n = data[i] as i8;
+        i += 1;
+        match n {
+            0..=127 => {
+                let len = n as usize + 1;
+                out.extend_from_slice(data.get(i..i + len).ok_or_else(truncated)?);
+                i += len;
+            }
+            -127..=-1 => {
+                let byte = *data.get(i).ok_or_else(truncated)?;
+                out.resize(out.len() + (1 - n as isize) as usize, byte);
+                i += 1;
+            }
+            // -128 is a no-op
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// Compress data with PackBits.
+#[cfg(feature = "tiff")]
+fn pack_bits(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / 128 + 1);
+    let mut i = 0;
+    while i < data.len() {
+        let run = data[i..]
+            .iter()
+            .take(128)
+            .take_while(|&&b| b == data[i])
+            .count();
+        if run >= 2 {
+            out.push((1 - run as isize) as u8);
+            out.push(data[i]);
+            i += run;
+            continue;
+        }
+
+        // Literal bytes up to the next repeat
+        let start = i;
+        while i < data.len() && i - start < 128 && data.get(i + 1) != Some(&data[i]) {
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        out.extend_from_slice(&data[start..i]);
+    }
+    out
+}
+
+/// Write images as the pages of a little-endian TIFF file.
+#[cfg(feature = "tiff")]
+fn encode_tiff(images: &[Image], compression: TiffCompression) -> CodecResult<Vec<u8>> {
+    if images.is_empty() {
+        return Err(CodecError::EncodingError("No images to encode".into()));
+    }
+
+    let mut out = b"II*\0".to_vec();
+    let mut next_ifd_link = out.len();
+    out.extend_from_slice(&0u32.to_le_bytes());
+
+    for image in images {
+        let opaque = image.is_opaque();
+        let samples = if opaque { 3 } else { 4 };
+
+        let to_u8 = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+        let mut data = Vec::with_capacity(image.width() as usize * image.height() as usize * 4);
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                let color = image.read_pixel(x, y).ok_or_else(|| {
+                    CodecError::Unsupported("Unsupported color type for TIFF encoding".into())
+                })?;
+                data.extend_from_slice(&[to_u8(color.r), to_u8(color.g), to_u8(color.b)]);
+                if !opaque {
+                    data.push(to_u8(color.a));
+                }
+            }
+        }
+        let strip = match compression {
+            TiffCompression::None => data,
+            TiffCompression::PackBits => pack_bits(&data),
+            TiffCompression::Lzw => {
+                weezl::encode::Encoder::with_tiff_size_switch(weezl::BitOrder::Msb, 8)
+                    .encode(&data)
+                    .map_err(|e| CodecError::EncodingError(format!("TIFF LZW error: {}", e)))?
+            }
+        };
+
+        // Each page is laid out as its strip, the BitsPerSample array and
+        // then the IFD, keeping word alignment
+        let strip_offset = out.len() as u32;
+        out.extend_from_slice(&strip);
+        if out.len() % 2 == 1 {
+            out.push(0);
+        }
+        let bits_offset = out.len() as u32;
+        for _ in 0..samples {
+            out.extend_from_slice(&8u16.to_le_bytes());
+        }
+
+        // (tag, type, count, value); type 3 is SHORT and 4 is LONG
+        let mut entries: Vec<(u16, u16, u32, u32)> = vec![
+            (256, 4, 1, image.width() as u32),
+            (257, 4, 1, image.height() as u32),
+            (258, 3, samples, bits_offset),
+            (259, 3, 1, compression.tag_value() as u32),
+            (262, 3, 1, 2),
+            (273, 4, 1, strip_offset),
+            (277, 3, 1, samples),
+            (278, 4, 1, image.height() as u32),
+            (279, 4, 1, strip.len() as u32),
+            (284, 3, 1, 1),
+        ];
+        if !opaque {
+            let associated = image.alpha_type() == skia_rs_core::AlphaType::Premul;
+            entries.push((338, 3, 1, if associated { 1 } else { 2 }));
+        }
+
+        // Link the header or the previous page to this IFD
+        let ifd_offset = out.len() as u32;
+        out[next_ifd_link..next_ifd_link + 4].copy_from_slice(&ifd_offset.to_le_bytes());
+        out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, kind, count, value) in entries {
+            out.extend_from_slice(&tag.to_le_bytes());
+            out.extend_from_slice(&kind.to_le_bytes());
+            out.extend_from_slice(&count.to_le_bytes());
+            // SHORT values sit in the first two bytes of the field
+            if kind == 3 && count == 1 {
+                out.extend_from_slice(&(value as u16).to_le_bytes());
+                out.extend_from_slice(&[0, 0]);
+            } else {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        next_ifd_link = out.len();
+        out.extend_from_slice(&0u32.to_le_bytes());
+    }
+
+    Ok(out)
+}
+
+/// Get TIFF dimensions from the first page.
+#[cfg(feature = "tiff")]
+fn get_tiff_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
+    let page = tiff_page(data, 0)?;
+    Ok((page.width as i32, page.height as i32))
+}
+
+#[cfg(not(feature = "tiff"))]
+fn get_tiff_dimensions(_data: &[u8]) -> CodecResult<(i32, i32)> {
+    Err(CodecError::Unsupported(
+        "TIFF support requires the 'tiff' feature".into(),
+    ))
+}
+
+// =============================================================================
+// QOI Codec
+// =============================================================================
+
+// QOI op codes
+const QOI_OP_INDEX: u8 = 0x00;
+const QOI_OP_DIFF: u8 = 0x40;
+const QOI_OP_LUMA: u8 = 0x80;
+const QOI_OP_RUN: u8 = 0xC0;
+const QOI_OP_RGB: u8 = 0xFE;
+const QOI_OP_RGBA: u8 = 0xFF;
+const QOI_MASK: u8 = 0xC0;
+
+/// Marks the end of a QOI stream.
+const QOI_END: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// Position of a pixel in the QOI color index.
+fn qoi_hash([r, g, b, a]: [u8; 4]) -> usize {
+    (r as usize * 3 + g as usize * 5 + b as usize * 7 + a as usize * 11) % 64
+}
+
+/// QOI decoder.
+///
+/// QOI ("Quite OK Image") is a simple lossless format. Images are decoded
+/// to 8-bit RGBA; the header's colorspace flag becomes the image's color
+/// space (sRGB or linear sRGB).
+#[derive(Debug, Default)]
+pub struct QoiDecoder;
+
+impl QoiDecoder {
+    /// Create a new QOI decoder.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ImageDecoder for QoiDecoder {
+    fn decode<R: Read>(&self, mut reader: R) -> CodecResult<Image> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        decode_qoi(&data)
+    }
+
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Qoi
+    }
+}
+
+/// QOI encoder.
+///
+/// Writes RGB for opaque images and RGBA otherwise. Images with a linear
+/// color space set the header's linear colorspace flag.
+#[derive(Debug, Default)]
+pub struct QoiEncoder;
+
+impl QoiEncoder {
+    /// Create a new QOI encoder.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ImageEncoder for QoiEncoder {
+    fn encode<W: Write>(&self, image: &Image, mut writer: W) -> CodecResult<()> {
+        writer.write_all(&encode_qoi(image)?)?;
+        Ok(())
+    }
+
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Qoi
+    }
+}
+
+/// Decode a QOI image from bytes.
+fn decode_qoi(data: &[u8]) -> CodecResult<Image> {
+    let (width, height) = get_qoi_dimensions(data)?;
+    let channels = data[12];
+    let colorspace = data[13];
+    if channels != 3 && channels != 4 {
+        return Err(CodecError::InvalidData(format!(
+            "Invalid QOI channel count {}",
+            channels
+        )));
+    }
+
+    if width as u64 * height as u64 > MAX_IMAGE_PIXELS {
+        return Err(CodecError::InvalidData("QOI dimensions too large".into()));
+    }
+    let pixel_count = width as usize * height as usize;
+    // A single byte covers at most 62 pixels, so reject headers that
+    // promise far more pixels than the data can hold
+    if pixel_count / 62 > data.len() {
+        return Err(CodecError::InvalidData("QOI data truncated".into()));
+    }
+
+    let truncated = || CodecError::InvalidData("QOI data truncated".into());
+    let body = &data[14..];
+    let mut pos = 0;
+    let mut next = || {
+        let byte = body.get(pos).copied();
+        pos += 1;
+        byte.ok_or_else(truncated)
+    };
+
+    let mut pixels = Vec::with_capacity(pixel_count * 4);
+    let mut index = [[0u8; 4]; 64];
+    let mut px = [0, 0, 0, 255u8];
+    let mut run = 0;
+    for _ in 0..pixel_count {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let op = next()?;
+            match op {
+                QOI_OP_RGB => {
+                    px = [next()?, next()?, next()?, px[3]];
+                }
+                QOI_OP_RGBA => {
+                    px = [next()?, next()?, next()?, next()?];
+                }
+                _ => match op & QOI_MASK {
+                    QOI_OP_INDEX => px = index[op as usize],
+                    QOI_OP_DIFF => {
+                        px[0] = px[0].wrapping_add((op >> 4) & 3).wrapping_sub(2);
+                        px[1] = px[1].wrapping_add((op >> 2) & 3).wrapping_sub(2);
+                        px[2] = px[2].wrapping_add(op & 3).wrapping_sub(2);
+                    }
+                    QOI_OP_LUMA => {
+                        let second = next()?;
+                        let dg = (op & 0x3F).wrapping_sub(32);
+                        px[0] = px[0]
+                            .wrapping_add(dg)
+                            .wrapping_add(second >> 4)
+                            .wrapping_sub(8);
+                        px[1] = px[1].wrapping_add(dg);
+                        px[2] = px[2]
+                            .wrapping_add(dg)
+                            .wrapping_add(second & 0x0F)
+                            .wrapping_sub(8);
+                    }
+                    // QOI_OP_RUN; this pixel is the first of the run
+                    _ => run = op & 0x3F,
+                },
+            }
+            index[qoi_hash(px)] = px;
+        }
+        pixels.extend_from_slice(&px);
+    }
+
+    let alpha_type = if channels == 3 {
+        skia_rs_core::AlphaType::Opaque
+    } else {
+        skia_rs_core::AlphaType::Unpremul
+    };
+    let mut info =
+        crate::ImageInfo::new(width, height, skia_rs_core::ColorType::Rgba8888, alpha_type);
+    // Colorspace 0 is sRGB with linear alpha, 1 is all channels linear
+    info.color_space = Some(if colorspace == 1 {
+        skia_rs_core::ColorSpace::srgb_linear()
+    } else {
+        skia_rs_core::ColorSpace::srgb()
+    });
+
+    Image::from_raster_data_owned(info, pixels, width as usize * 4)
+        .ok_or_else(|| CodecError::DecodingError("Failed to create image".into()))
+}
+
+/// Encode an image to QOI bytes.
+fn encode_qoi(image: &Image) -> CodecResult<Vec<u8>> {
+    let opaque = image.is_opaque();
+    let premul = image.alpha_type() == skia_rs_core::AlphaType::Premul;
+    let linear = image.color_space().is_some_and(|cs| cs.is_linear());
+
+    let mut out = Vec::with_capacity(14 + image.width() as usize * image.height() as usize + 8);
+    out.extend_from_slice(b"qoif");
+    out.extend_from_slice(&(image.width() as u32).to_be_bytes());
+    out.extend_from_slice(&(image.height() as u32).to_be_bytes());
+    out.push(if opaque { 3 } else { 4 });
+    out.push(linear as u8);
+
+    let to_u8 = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+    let mut index = [[0u8; 4]; 64];
+    let mut prev = [0, 0, 0, 255u8];
+    let mut run = 0u8;
+    let pixel_count = image.width() as usize * image.height() as usize;
+    for i in 0..pixel_count {
+        let x = (i % image.width() as usize) as i32;
+        let y = (i / image.width() as usize) as i32;
+        let mut color = image.read_pixel(x, y).ok_or_else(|| {
+            CodecError::Unsupported("Unsupported color type for QOI encoding".into())
+        })?;
+        // QOI stores unpremultiplied colors
+        if premul && color.a > 0.0 {
+            color.r /= color.a;
+            color.g /= color.a;
+            color.b /= color.a;
+        }
+        let a = if opaque { 255 } else { to_u8(color.a) };
+        let px = [to_u8(color.r), to_u8(color.g), to_u8(color.b), a];
+
+        if px == prev {
+            run += 1;
+            if run == 62 || i + 1 == pixel_count {
+                out.push(QOI_OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            out.push(QOI_OP_RUN | (run - 1));
+            run = 0;
+        }
+
+        let hash = qoi_hash(px);
+        if index[hash] == px {
+            out.push(QOI_OP_INDEX | hash as u8);
+        } else {
+            index[hash] = px;
+            if px[3] == prev[3] {
+                let dr = px[0].wrapping_sub(prev[0]) as i8;
+                let dg = px[1].wrapping_sub(prev[1]) as i8;
+                let db = px[2].wrapping_sub(prev[2]) as i8;
+                let dr_dg = dr.wrapping_sub(dg);
+                let db_dg = db.wrapping_sub(dg);
+                if (-2..=1).contains(&dr) && (-2..=1).contains(&dg) && (-2..=1).contains(&db) {
+                    out.push(
+                        QOI_OP_DIFF
+                            | ((dr + 2) as u8) << 4
+                            | ((dg + 2) as u8) << 2
+                            | (db + 2) as u8,
+                    );
+                } else if (-32..=31).contains(&dg)
+                    && (-8..=7).contains(&dr_dg)
+                    && (-8..=7).contains(&db_dg)
+                {
+                    out.push(QOI_OP_LUMA | (dg + 32) as u8);
+                    out.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+                } else {
+                    out.extend_from_slice(&[QOI_OP_RGB, px[0], px[1], px[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[QOI_OP_RGBA, px[0], px[1], px[2], px[3]]);
+            }
+        }
+        prev = px;
+    }
+
+    out.extend_from_slice(&QOI_END);
+    Ok(out)
+}
+
+fn get_qoi_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
+    if data.len() < 14 || !data.starts_with(b"qoif") {
+        return Err(CodecError::InvalidData("QOI too short".into()));
+    }
+
+    let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+    let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+    if width == 0 || height == 0 || width > i32::MAX as u32 || height > i32::MAX as u32 {
+        return Err(CodecError::InvalidData("Invalid QOI dimensions".into()));
+    }
+
+    Ok((width as i32, height as i32))
+}
+
+// =============================================================================
+// Utility Functions
+// =============================================================================
+
+/// Decode an image from bytes, auto-detecting the format.
+pub fn decode_image(data: &[u8]) -> CodecResult<Image> {
+    let format = ImageFormat::from_magic(data);
+
+    match format {
+        ImageFormat::Png => PngDecoder::new().decode_bytes(data),
+        ImageFormat::Jpeg => JpegDecoder::new().decode_bytes(data),
+        ImageFormat::Gif => GifDecoder::new().decode_bytes(data),
+        ImageFormat::WebP => WebpDecoder::new().decode_bytes(data),
+        ImageFormat::Bmp => BmpDecoder::new().decode_bytes(data),
+        ImageFormat::Ico => IcoDecoder::new().decode_bytes(data),
+        ImageFormat::Wbmp => WbmpDecoder::new().decode_bytes(data),
+        ImageFormat::Avif => AvifDecoder::new().decode_bytes(data),
+        ImageFormat::Raw => RawDecoder::new().decode_bytes(data),
+        ImageFormat::Tiff => TiffDecoder::new().decode_bytes(data),
+        ImageFormat::Qoi => QoiDecoder::new().decode_bytes(data),
+        _ => Err(CodecError::Unsupported(format!(
+            "Format {:?} not supported",
+            format
+        ))),
+    }
+}
+
+/// Encode an image to bytes in the given format.
+///
+/// `quality` applies to lossy formats (JPEG, WebP, AVIF) and defaults to
+/// [`EncoderQuality::DEFAULT`]; lossless formats ignore it. Decode-only
+/// formats return [`CodecError::Unsupported`].
+pub fn encode_image(
+    image: &Image,
+    format: ImageFormat,
+    quality: Option<EncoderQuality>,
+) -> CodecResult<Vec<u8>> {
+    let quality = quality.unwrap_or_default();
+
+    match format {
+        ImageFormat::Png => PngEncoder::new().encode_bytes(image),
+        ImageFormat::Jpeg => JpegEncoder::with_quality(quality).encode_bytes(image),
+        ImageFormat::WebP => WebpEncoder::with_quality(quality).encode_bytes(image),
+        ImageFormat::Bmp => BmpEncoder::new().encode_bytes(image),
+        ImageFormat::Wbmp => WbmpEncoder::new().encode_bytes(image),
+        ImageFormat::Avif => AvifEncoder::new()
+            .with_quality(quality.value())
+            .encode_bytes(image),
+        ImageFormat::Tiff => TiffEncoder::new().encode_bytes(image),
+        ImageFormat::Qoi => QoiEncoder::new().encode_bytes(image),
+        _ => Err(CodecError::Unsupported(format!(
+            "Encoding {:?} not supported",
+            format
+        ))),
+    }
+}
+
+/// The largest image, in pixels, that will be decoded.
+///
+/// Headers can claim any size, so this bounds what decoding allocates
+/// before any image data has been checked.
+pub const MAX_IMAGE_PIXELS: u64 = 1 << 28;
+
+/// Get the image dimensions without fully decoding.
+pub fn get_image_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
+    let format = ImageFormat::from_magic(data);
+
+    match format {
+        ImageFormat::Png => get_png_dimensions(data),
+        ImageFormat::Jpeg => get_jpeg_dimensions(data),
+        ImageFormat::Gif => get_gif_dimensions(data),
+        ImageFormat::WebP => get_webp_dimensions(data),
+        ImageFormat::Bmp => get_bmp_dimensions(data),
+        ImageFormat::Ico => get_ico_dimensions(data),
+        ImageFormat::Wbmp => get_wbmp_dimensions(data),
+        ImageFormat::Avif => get_avif_dimensions(data),
+        ImageFormat::Raw => get_raw_dimensions(data),
+        ImageFormat::Tiff => get_tiff_dimensions(data),
+        ImageFormat::Qoi => get_qoi_dimensions(data),
+        _ => Err(CodecError::Unsupported(format!(
+            "Format {:?} not supported",
+            format
+        ))),
+    }
+}
+
+fn get_png_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
+    // PNG IHDR chunk starts at byte 8, width at offset 16, height at offset 20
+    if data.len() < 24 {
+        return Err(CodecError::InvalidData("PNG too short".into()));
+    }
+
+    let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]) as i32;
     let height = u32::from_be_bytes([data[20], data[21], data[22], data[23]]) as i32;
 
     Ok((width, height))
@@ -1983,6 +3546,47 @@ fn get_jpeg_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
     ))
 }
 
+fn get_gif_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
+    // Logical screen size follows the 6-byte signature
+    if data.len() < 10 {
+        return Err(CodecError::InvalidData("GIF too short".into()));
+    }
+
+    let width = u16::from_le_bytes([data[6], data[7]]) as i32;
+    let height = u16::from_le_bytes([data[8], data[9]]) as i32;
+
+    Ok((width, height))
+}
+
+fn get_webp_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
+    if data.len() < 30 {
+        return Err(CodecError::InvalidData("WebP too short".into()));
+    }
+
+    let u24 = |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], 0]) as i32;
+    match &data[12..16] {
+        // Lossy: 14-bit sizes after the frame start code
+        b"VP8 " if data[23..26] == [0x9D, 0x01, 0x2A] => {
+            let width = u16::from_le_bytes([data[26], data[27]]) & 0x3FFF;
+            let height = u16::from_le_bytes([data[28], data[29]]) & 0x3FFF;
+            Ok((width as i32, height as i32))
+        }
+        // Lossless: 14-bit sizes minus one, packed after the signature
+        b"VP8L" if data[20] == 0x2F => {
+            let bits = u32::from_le_bytes([data[21], data[22], data[23], data[24]]);
+            Ok((
+                (bits & 0x3FFF) as i32 + 1,
+                ((bits >> 14) & 0x3FFF) as i32 + 1,
+            ))
+        }
+        // Extended: 24-bit canvas sizes minus one
+        b"VP8X" => Ok((u24(24) + 1, u24(27) + 1)),
+        _ => Err(CodecError::InvalidData(
+            "Could not find WebP dimensions".into(),
+        )),
+    }
+}
+
 fn get_bmp_dimensions(data: &[u8]) -> CodecResult<(i32, i32)> {
     if data.len() < 26 {
         return Err(CodecError::InvalidData("BMP too short".into()));
@@ -2086,6 +3690,507 @@ mod tests {
         assert_eq!(ImageFormat::Ico.mime_type(), "image/x-icon");
     }
 
+    #[cfg(feature = "png")]
+    fn encode_png(info: png::Info<'static>, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut writer = png::Encoder::with_info(&mut out, info)
+            .unwrap()
+            .write_header()
+            .unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+        out
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_decode_16bit_gray() {
+        let samples: [u16; 4] = [0, 1, 0x1234, 0xFFFF];
+        let mut info = png::Info::with_size(4, 1);
+        info.color_type = png::ColorType::Grayscale;
+        info.bit_depth = png::BitDepth::Sixteen;
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+        let png_data = encode_png(info, &data);
+
+        let options = PngDecodeOptions {
+            keep_16bit: true,
+            ..Default::default()
+        };
+        let image = PngDecoder::with_options(options)
+            .decode_bytes(&png_data)
+            .unwrap();
+        assert_eq!(
+            image.color_type(),
+            skia_rs_core::ColorType::R16G16B16A16Unorm
+        );
+        assert_eq!(image.row_bytes(), 32);
+        let pixels = image.peek_pixels().unwrap();
+        for (pixel, &sample) in pixels.chunks_exact(8).zip(&samples) {
+            let channels: Vec<u16> = pixel
+                .chunks_exact(2)
+                .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+                .collect();
+            assert_eq!(channels, [sample, sample, sample, 0xFFFF]);
+        }
+        let gray = image.read_pixel(2, 0).unwrap();
+        assert!((gray.r - 0x1234 as f32 / 65535.0).abs() < 1e-6);
+
+        // By default samples are truncated to 8 bits
+        let image = PngDecoder::new().decode_bytes(&png_data).unwrap();
+        assert_eq!(image.color_type(), skia_rs_core::ColorType::Rgba8888);
+        assert_eq!(
+            &image.peek_pixels().unwrap()[8..12],
+            &[0x12, 0x12, 0x12, 0xFF]
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_decode_icc_profile() {
+        let mut profile = vec![0u8; 128];
+        profile[12..16].copy_from_slice(b"mntr");
+        profile[16..20].copy_from_slice(b"RGB ");
+        profile[20..24].copy_from_slice(b"XYZ ");
+        profile[36..40].copy_from_slice(b"acsp");
+
+        let mut info = png::Info::with_size(1, 1);
+        info.color_type = png::ColorType::Rgb;
+        info.icc_profile = Some(profile.clone().into());
+        let png_data = encode_png(info, &[10, 20, 30]);
+
+        let image = PngDecoder::new().decode_bytes(&png_data).unwrap();
+        let icc = image.icc_profile().unwrap();
+        assert_eq!(icc.raw_data(), Some(profile.as_slice()));
+        assert!(image.color_space().is_some());
+        assert_eq!(image.peek_pixels().unwrap(), &[10, 20, 30, 255]);
+
+        let image = PngDecoder::new()
+            .decode_bytes(&encode_png(png::Info::with_size(1, 1), &[7]))
+            .unwrap();
+        assert!(image.icc_profile().is_none());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_decode_apply_gamma() {
+        // Linear samples tagged with a gamma of 1.0
+        let mut info = png::Info::with_size(3, 1);
+        info.source_gamma = Some(png::ScaledFloat::new(1.0));
+        let png_data = encode_png(info, &[0, 128, 255]);
+
+        let gray = |options: PngDecodeOptions| {
+            let image = PngDecoder::with_options(options)
+                .decode_bytes(&png_data)
+                .unwrap();
+            let pixels = image.peek_pixels().unwrap().to_vec();
+            [pixels[0], pixels[4], pixels[8]]
+        };
+        assert_eq!(gray(PngDecodeOptions::default()), [0, 128, 255]);
+        let options = PngDecodeOptions {
+            apply_gamma: true,
+            ..Default::default()
+        };
+        let [black, mid, white] = gray(options);
+        assert_eq!((black, white), (0, 255));
+        assert!((186..=190).contains(&mid), "mid = {mid}");
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_stream_decoder() {
+        // Noisy pixels so the compressed rows spread across the file
+        let mut seed = 1u32;
+        let data: Vec<u8> = (0..32 * 32 * 3)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        let mut info = png::Info::with_size(32, 32);
+        info.color_type = png::ColorType::Rgb;
+        let png_data = encode_png(info, &data);
+
+        let mut stream = PngStreamDecoder::new();
+        let mut rows = Vec::new();
+        for chunk in png_data.chunks(png_data.len().div_ceil(3)) {
+            stream.push(chunk);
+            rows.push(stream.decoded_rows());
+        }
+        assert!(rows.windows(2).all(|w| w[0] <= w[1]), "rows: {rows:?}");
+        assert!(rows[0] < 32, "rows: {rows:?}");
+        assert_eq!(rows[2], 32);
+        assert!(stream.is_complete());
+
+        let image = stream.finish().unwrap();
+        let expected = PngDecoder::new().decode_bytes(&png_data).unwrap();
+        assert_eq!(image.peek_pixels(), expected.peek_pixels());
+    }
+
+    /// A 5x3 Adam7-interlaced RGBA PNG.
+    #[cfg(feature = "png")]
+    const INTERLACED_PNG: [u8; 126] = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x03, 0x08, 0x06, 0x00, 0x00, 0x01, 0x2c,
+        0x31, 0xf5, 0x6e, 0x00, 0x00, 0x00, 0x45, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0x60,
+        0x60, 0x60, 0x6f, 0x60, 0x38, 0x01, 0x22, 0x52, 0x40, 0x04, 0xc3, 0x16, 0xf6, 0x86, 0x94,
+        0x2d, 0xf5, 0x0d, 0x27, 0xb6, 0x7c, 0x6f, 0x60, 0x30, 0x62, 0x60, 0xff, 0x3f, 0x0d, 0x88,
+        0x19, 0x8c, 0xb6, 0x38, 0xff, 0x9f, 0xb6, 0x65, 0xf7, 0x7f, 0x06, 0x86, 0x28, 0xf6, 0xff,
+        0x46, 0x51, 0xaa, 0x0d, 0x29, 0x51, 0x40, 0x81, 0xa8, 0xc4, 0x86, 0x13, 0x51, 0xf5, 0xff,
+        0x01, 0xe6, 0xb6, 0x1a, 0x09, 0x8d, 0xcb, 0x68, 0x02, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+        0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_stream_decoder_byte_at_a_time() {
+        let mut info = png::Info::with_size(6, 4);
+        info.color_type = png::ColorType::GrayscaleAlpha;
+        let data: Vec<u8> = (0..6 * 4 * 2).map(|i| (i * 11) as u8).collect();
+        let png_data = encode_png(info, &data);
+
+        for (png_data, interlaced) in [(&png_data[..], false), (&INTERLACED_PNG[..], true)] {
+            let mut stream = PngStreamDecoder::new();
+            let mut rows = 0;
+            for &byte in png_data {
+                stream.push(&[byte]);
+                assert!(stream.decoded_rows() >= rows);
+                rows = stream.decoded_rows();
+                if interlaced && !stream.is_complete() {
+                    assert!(stream.latest_image().is_none());
+                }
+            }
+            assert!(stream.is_complete());
+
+            let image = stream.finish().unwrap();
+            let expected = PngDecoder::new().decode_bytes(png_data).unwrap();
+            assert_eq!(image.dimensions(), expected.dimensions());
+            assert_eq!(image.peek_pixels(), expected.peek_pixels());
+        }
+
+        // The last pixel of the interlaced image comes from the final pass
+        let image = PngDecoder::new().decode_bytes(&INTERLACED_PNG).unwrap();
+        assert_eq!(&image.peek_pixels().unwrap()[56..], &[200, 180, 247, 128]);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_stream_decoder_truncated() {
+        let mut info = png::Info::with_size(8, 8);
+        info.color_type = png::ColorType::Rgb;
+        let png_data = encode_png(info, &[200; 8 * 8 * 3]);
+
+        let mut stream = PngStreamDecoder::new();
+        stream.push(&png_data[..png_data.len() / 2]);
+        assert!(!stream.is_complete());
+        assert!(stream.finish().is_err());
+
+        let mut stream = PngStreamDecoder::new();
+        stream.push(b"not a png");
+        assert!(stream.latest_image().is_none());
+        assert!(stream.finish().is_err());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_stream_decoder_oversized_header() {
+        // Signature and IHDR for a 100000x100000 image, then the start of
+        // the first IDAT chunk
+        let mut header = Vec::new();
+        drop(
+            png::Encoder::new(&mut header, 100_000, 100_000)
+                .write_header()
+                .unwrap(),
+        );
+        let mut png_data = header[..33].to_vec();
+        png_data.extend_from_slice(&[0, 0, 0, 16]);
+        png_data.extend_from_slice(b"IDAT");
+
+        let mut stream = PngStreamDecoder::new();
+        stream.push(&png_data);
+        assert!(stream.pixels.is_empty());
+        assert!(stream.latest_image().is_none());
+        let err = stream.finish().unwrap_err();
+        assert!(err.to_string().contains("too large"), "{err}");
+    }
+
+    /// Build EXIF data holding only an `Orientation` tag.
+    #[cfg(feature = "jpeg")]
+    fn exif_with_orientation(orientation: u16, big_endian: bool) -> Vec<u8> {
+        let u16_bytes = |v: u16| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let u32_bytes = |v: u32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let mut exif = if big_endian {
+            b"MM\0*".to_vec()
+        } else {
+            b"II*\0".to_vec()
+        };
+        exif.extend(u32_bytes(8));
+        exif.extend(u16_bytes(1));
+        exif.extend(u16_bytes(0x0112));
+        exif.extend(u16_bytes(3));
+        exif.extend(u32_bytes(1));
+        exif.extend(u16_bytes(orientation));
+        exif.extend([0, 0]);
+        exif.extend(u32_bytes(0));
+        exif
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    fn test_exif_orientation() {
+        for orientation in 1..=8 {
+            assert_eq!(
+                exif_orientation(&exif_with_orientation(orientation, true)),
+                Some(orientation as u8)
+            );
+            assert_eq!(
+                exif_orientation(&exif_with_orientation(orientation, false)),
+                Some(orientation as u8)
+            );
+        }
+        assert_eq!(exif_orientation(&exif_with_orientation(9, true)), None);
+        assert_eq!(exif_orientation(b"II*\0"), None);
+
+        // Label each pixel of a 3x2 image by its index, then check where the
+        // top-left and top-right pixels end up
+        let pixels: Vec<u8> = (0..6u8).flat_map(|i| [i, 0, 0, 255]).collect();
+        let corners = |orientation| {
+            let (oriented, w, h) = orient_rgba(&pixels, 3, 2, orientation);
+            let at = |x: i32, y: i32| oriented[((y * w + x) * 4) as usize];
+            ((w, h), at(0, 0), at(w - 1, 0))
+        };
+        assert_eq!(corners(1), ((3, 2), 0, 2));
+        assert_eq!(corners(2), ((3, 2), 2, 0));
+        assert_eq!(corners(3), ((3, 2), 5, 3));
+        assert_eq!(corners(4), ((3, 2), 3, 5));
+        assert_eq!(corners(5), ((2, 3), 0, 3));
+        assert_eq!(corners(6), ((2, 3), 3, 0));
+        assert_eq!(corners(7), ((2, 3), 5, 2));
+        assert_eq!(corners(8), ((2, 3), 2, 5));
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    fn test_jpeg_decode_orientation_6() {
+        // 32x16 image: a red left half and a blue right half
+        let (width, height) = (32u16, 16u16);
+        let rgb: Vec<u8> = (0..height)
+            .flat_map(|_| (0..width).flat_map(|x| if x < 16 { [255, 0, 0] } else { [0, 0, 255] }))
+            .collect();
+        let mut jpeg = Vec::new();
+        let mut encoder = jpeg_encoder::Encoder::new(&mut jpeg, 95);
+        let mut exif = b"Exif\0\0".to_vec();
+        exif.extend(exif_with_orientation(6, false));
+        encoder.add_app_segment(1, &exif).unwrap();
+        encoder
+            .encode(&rgb, width, height, jpeg_encoder::ColorType::Rgb)
+            .unwrap();
+
+        assert_eq!(JpegDecoder::new().read_orientation(&jpeg).unwrap(), Some(6));
+        let image = JpegDecoder::new().decode_bytes(&jpeg).unwrap();
+        assert_eq!(image.dimensions(), (32, 16));
+
+        // Rotated 90° clockwise, the left half becomes the top half
+        let options = JpegDecodeOptions {
+            apply_orientation: true,
+        };
+        let image = JpegDecoder::with_options(options)
+            .decode_bytes(&jpeg)
+            .unwrap();
+        assert_eq!(image.dimensions(), (16, 32));
+        let top = image.read_pixel(8, 4).unwrap();
+        let bottom = image.read_pixel(8, 28).unwrap();
+        assert!(top.r > 0.9 && top.b < 0.1, "top = {top:?}");
+        assert!(bottom.b > 0.9 && bottom.r < 0.1, "bottom = {bottom:?}");
+    }
+
+    /// Encode a 32x24 JPEG, left half orange and right half teal.
+    #[cfg(feature = "jpeg")]
+    fn encode_test_jpeg(
+        configure: impl FnOnce(&mut jpeg_encoder::Encoder<&mut Vec<u8>>),
+    ) -> Vec<u8> {
+        let rgb: Vec<u8> = (0..24)
+            .flat_map(|_| {
+                (0..32).flat_map(|x| {
+                    if x < 16 {
+                        [240, 140, 20]
+                    } else {
+                        [20, 160, 160]
+                    }
+                })
+            })
+            .collect();
+        let mut jpeg = Vec::new();
+        let mut encoder = jpeg_encoder::Encoder::new(&mut jpeg, 90);
+        configure(&mut encoder);
+        encoder
+            .encode(&rgb, 32, 24, jpeg_encoder::ColorType::Rgb)
+            .unwrap();
+        jpeg
+    }
+
+    #[cfg(feature = "jpeg")]
+    fn assert_color_near(image: &Image, x: i32, y: i32, rgb: [u8; 3]) {
+        let pixel = image.read_pixel(x, y).unwrap();
+        let actual = [pixel.r, pixel.g, pixel.b].map(|c| (c * 255.0).round() as i32);
+        for (actual, expected) in actual.iter().zip(rgb) {
+            assert!(
+                (actual - expected as i32).abs() <= 12,
+                "{actual:?} vs {rgb:?} at ({x}, {y})"
+            );
+        }
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    fn test_jpeg_decode_subsampled_and_progressive() {
+        let subsampled = encode_test_jpeg(|encoder| {
+            encoder.set_sampling_factor(jpeg_encoder::SamplingFactor::R_4_2_0);
+        });
+        let progressive = encode_test_jpeg(|encoder| {
+            encoder.set_sampling_factor(jpeg_encoder::SamplingFactor::R_4_2_0);
+            encoder.set_progressive(true);
+        });
+
+        for jpeg in [subsampled, progressive] {
+            let image = JpegDecoder::new().decode_bytes(&jpeg).unwrap();
+            assert_eq!(image.dimensions(), (32, 24));
+            assert_color_near(&image, 8, 12, [240, 140, 20]);
+            assert_color_near(&image, 24, 12, [20, 160, 160]);
+        }
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    fn test_jpeg_decode_cmyk() {
+        // 50% cyan over 20% black
+        let cmyk = [128u8, 0, 0, 51].repeat(16 * 16);
+        for color_type in [
+            jpeg_encoder::ColorType::Cmyk,
+            jpeg_encoder::ColorType::CmykAsYcck,
+        ] {
+            let mut jpeg = Vec::new();
+            jpeg_encoder::Encoder::new(&mut jpeg, 95)
+                .encode(&cmyk, 16, 16, color_type)
+                .unwrap();
+
+            let image = JpegDecoder::new().decode_bytes(&jpeg).unwrap();
+            assert_eq!(image.dimensions(), (16, 16));
+            assert_color_near(&image, 8, 8, [101, 204, 204]);
+        }
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn test_gif_decode_frames() {
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let green = [0, 255, 0, 255];
+
+        // A red background, a blue square cleared after showing, then a
+        // green pixel
+        let mut data = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut data, 4, 4, &[]).unwrap();
+            let frames = [
+                (4, (0, 0), red, 10, gif::DisposalMethod::Keep),
+                (2, (1, 1), blue, 20, gif::DisposalMethod::Background),
+                (1, (0, 0), green, 5, gif::DisposalMethod::Keep),
+            ];
+            for (size, (left, top), color, delay, dispose) in frames {
+                let mut pixels = color.repeat(size as usize * size as usize);
+                let mut frame = gif::Frame::from_rgba(size, size, &mut pixels);
+                frame.left = left;
+                frame.top = top;
+                frame.delay = delay;
+                frame.dispose = dispose;
+                encoder.write_frame(&frame).unwrap();
+            }
+        }
+
+        let frames = GifDecoder::new().decode_frames(&data).unwrap();
+        assert_eq!(frames.len(), 3);
+        let delays: Vec<u32> = frames.iter().map(|f| f.delay_ms).collect();
+        assert_eq!(delays, [100, 200, 50]);
+        assert_eq!(frames[1].disposal, DisposalMethod::Background);
+
+        let pixel = |frame: usize, x: usize, y: usize| {
+            let image = &frames[frame].image;
+            assert_eq!(image.dimensions(), (4, 4));
+            let offset = y * image.row_bytes() + x * 4;
+            <[u8; 4]>::try_from(&image.peek_pixels().unwrap()[offset..offset + 4]).unwrap()
+        };
+        assert_ne!(frames[0].image.peek_pixels(), frames[1].image.peek_pixels());
+        assert_eq!(pixel(1, 0, 0), red);
+        assert_eq!(pixel(1, 2, 2), blue);
+        assert_eq!(pixel(2, 0, 0), green);
+        assert_eq!(pixel(2, 2, 2), [0, 0, 0, 0]);
+        assert_eq!(pixel(2, 3, 3), red);
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn test_gif_decode_frames_limits() {
+        let gif = |width: u16, height: u16, frames: &[(u16, gif::DisposalMethod, [u8; 4])]| {
+            let mut data = Vec::new();
+            let mut encoder = gif::Encoder::new(&mut data, width, height, &[]).unwrap();
+            for &(offset, dispose, color) in frames {
+                let mut pixels = color.repeat(4);
+                let mut frame = gif::Frame::from_rgba(2, 2, &mut pixels);
+                (frame.left, frame.top, frame.dispose) = (offset, offset, dispose);
+                encoder.write_frame(&frame).unwrap();
+            }
+            drop(encoder);
+            data
+        };
+        let (red, blue) = ([255, 0, 0, 255], [0, 0, 255, 255]);
+        let keep = gif::DisposalMethod::Keep;
+
+        // A huge logical screen is rejected before its canvas is allocated
+        let huge = gif(65535, 65535, &[(0, keep, red)]);
+        assert!(matches!(
+            GifDecoder::new().decode_frames(&huge),
+            Err(CodecError::DecodingError(_))
+        ));
+
+        // Every frame's full canvas counts towards the budget
+        let data = gif(
+            4,
+            4,
+            &[
+                (0, keep, red),
+                (1, gif::DisposalMethod::Previous, blue),
+                (2, keep, red),
+            ],
+        );
+        let decoder = GifDecoder::new();
+        assert!(decoder.decode_frames_within(&data, 47).is_err());
+        let frames = decoder.decode_frames_within(&data, 48).unwrap();
+
+        // Previous disposal restores just the area the frame covered
+        let pixel = |frame: usize, x: usize, y: usize| {
+            let offset = y * 16 + x * 4;
+            <[u8; 4]>::try_from(&frames[frame].image.peek_pixels().unwrap()[offset..offset + 4])
+                .unwrap()
+        };
+        assert_eq!(pixel(1, 1, 1), blue);
+        assert_eq!(pixel(2, 1, 1), red);
+        assert_eq!(pixel(2, 2, 2), red);
+        assert_eq!(pixel(2, 2, 1), [0, 0, 0, 0]);
+        assert_eq!(pixel(2, 3, 3), red);
+    }
+
     #[test]
     fn test_encoder_quality() {
         let q = EncoderQuality::new(75);
@@ -2142,4 +4247,291 @@ mod tests {
         let dims = get_bmp_dimensions(&bmp).unwrap();
         assert_eq!(dims, (100, 50));
     }
+
+    #[test]
+    fn test_tiff_format_detection() {
+        assert_eq!(
+            ImageFormat::from_magic(b"II*\0\x08\0\0\0"),
+            ImageFormat::Tiff
+        );
+        assert_eq!(
+            ImageFormat::from_magic(b"MM\0*\0\0\0\x08"),
+            ImageFormat::Tiff
+        );
+        assert_eq!(ImageFormat::Tiff.extension(), "tiff");
+        assert_eq!(ImageFormat::Tiff.mime_type(), "image/tiff");
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn test_tiff_round_trip() {
+        // Repeated and varying bytes to exercise every PackBits and LZW path
+        let (width, height) = (13, 7);
+        let pixels: Vec<u8> = (0..width * height)
+            .flat_map(|i| {
+                let v = if i % 5 < 3 { 200 } else { (i * 37 % 256) as u8 };
+                [v, (i % 256) as u8, 255 - v, 255]
+            })
+            .collect();
+        let info = crate::ImageInfo::new(
+            width as i32,
+            height as i32,
+            skia_rs_core::ColorType::Rgba8888,
+            skia_rs_core::AlphaType::Opaque,
+        );
+        let image = Image::from_raster_data_owned(info, pixels.clone(), width * 4).unwrap();
+
+        for compression in [
+            TiffCompression::None,
+            TiffCompression::PackBits,
+            TiffCompression::Lzw,
+        ] {
+            let encoded = TiffEncoder::new()
+                .with_compression(compression)
+                .encode_bytes(&image)
+                .unwrap();
+            assert_eq!(ImageFormat::from_magic(&encoded), ImageFormat::Tiff);
+            assert_eq!(get_image_dimensions(&encoded).unwrap(), (13, 7));
+
+            let decoded = decode_image(&encoded).unwrap();
+            assert_eq!(decoded.dimensions(), (13, 7));
+            assert_eq!(decoded.alpha_type(), skia_rs_core::AlphaType::Opaque);
+            assert_eq!(
+                decoded.peek_pixels().unwrap(),
+                &pixels[..],
+                "{compression:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn test_tiff_pages() {
+        let first = Image::from_color(4, 4, 0xFF_FF0000).unwrap();
+        let info = crate::ImageInfo::new(
+            2,
+            1,
+            skia_rs_core::ColorType::Rgba8888,
+            skia_rs_core::AlphaType::Unpremul,
+        );
+        let second =
+            Image::from_raster_data_owned(info, vec![0, 0, 255, 128, 0, 255, 0, 0], 8).unwrap();
+
+        let mut encoded = Vec::new();
+        TiffEncoder::new()
+            .with_compression(TiffCompression::Lzw)
+            .encode_pages(&[first, second], &mut encoded)
+            .unwrap();
+        assert_eq!(TiffDecoder::page_count(&encoded).unwrap(), 2);
+
+        let decoder = TiffDecoder::with_options(TiffDecodeOptions { page: 1 });
+        let page = decoder.decode_bytes(&encoded).unwrap();
+        assert_eq!(page.dimensions(), (2, 1));
+        assert_eq!(page.alpha_type(), skia_rs_core::AlphaType::Unpremul);
+        assert_eq!(page.peek_pixels().unwrap(), &[0, 0, 255, 128, 0, 255, 0, 0]);
+
+        let decoder = TiffDecoder::with_options(TiffDecodeOptions { page: 2 });
+        assert!(decoder.decode_bytes(&encoded).is_err());
+    }
+
+    /// Build a big-endian TIFF with one IFD of single-valued SHORT (3) or
+    /// LONG (4) entries.
+    #[cfg(feature = "tiff")]
+    fn big_endian_tiff(entries: &[(u16, u16, u32)]) -> Vec<u8> {
+        let mut tiff = b"MM\0*\0\0\0\x08".to_vec();
+        tiff.extend_from_slice(&(entries.len() as u16).to_be_bytes());
+        for &(tag, kind, value) in entries {
+            tiff.extend_from_slice(&tag.to_be_bytes());
+            tiff.extend_from_slice(&kind.to_be_bytes());
+            tiff.extend_from_slice(&1u32.to_be_bytes());
+            if kind == 3 {
+                tiff.extend_from_slice(&(value as u16).to_be_bytes());
+                tiff.extend_from_slice(&[0, 0]);
+            } else {
+                tiff.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+        tiff.extend_from_slice(&0u32.to_be_bytes());
+        tiff
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn test_tiff_big_endian_16bit_gray() {
+        // 2x1 big-endian 16-bit BlackIsZero grayscale
+        let mut tiff = big_endian_tiff(&[
+            (256, 3, 2),
+            (257, 3, 1),
+            (258, 3, 16),
+            (259, 3, 1),
+            (262, 3, 1),
+            (273, 4, 98),
+            (279, 4, 4),
+        ]);
+        assert_eq!(tiff.len(), 98);
+        tiff.extend_from_slice(&[0x12, 0x34, 0xFF, 0x00]);
+
+        let image = TiffDecoder::new().decode_bytes(&tiff).unwrap();
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(
+            image.peek_pixels().unwrap(),
+            &[0x12, 0x12, 0x12, 255, 0xFF, 0xFF, 0xFF, 255]
+        );
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn test_tiff_rejects_huge_dimensions() {
+        let header = |width: u32, height: u32, compression: u32| {
+            let mut tiff = big_endian_tiff(&[
+                (256, 4, width),
+                (257, 4, height),
+                (258, 3, 8),
+                (259, 3, compression),
+                (262, 3, 2),
+                (273, 4, 110),
+                (277, 3, 4),
+                (279, 4, 16),
+            ]);
+            assert_eq!(tiff.len(), 110);
+            tiff.extend_from_slice(&[0xAB; 16]);
+            tiff
+        };
+        let decode = |tiff: &[u8]| TiffDecoder::new().decode_bytes(tiff);
+
+        // Over the pixel limit
+        let huge = header(0x7FFF_FFFF, 0x7FFF_FFFF, 1);
+        assert!(matches!(decode(&huge), Err(CodecError::InvalidData(_))));
+        // Within the limit, but far more than the strips can hold
+        for compression in [1, 5, 32773] {
+            let tiff = header(16384, 16384, compression);
+            assert!(matches!(decode(&tiff), Err(CodecError::InvalidData(_))));
+        }
+        // The same header with matching dimensions decodes
+        let image = decode(&header(2, 2, 1)).unwrap();
+        assert_eq!(image.dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn test_qoi_round_trip() {
+        // Runs, small diffs, luma diffs, index hits and alpha changes
+        let (width, height) = (17, 9);
+        let pixels: Vec<u8> = (0..width * height)
+            .flat_map(|i| match i % 7 {
+                0..=2 => [10, 20, 30, 255],
+                3 => [11, 19, 31, 255],
+                4 => [40, 45, 38, 255],
+                5 => [(i * 31 % 256) as u8, (i * 7 % 256) as u8, 200, 255],
+                _ => [90, 80, 70, (i * 13 % 256) as u8],
+            })
+            .collect();
+        let info = crate::ImageInfo::new(
+            width as i32,
+            height as i32,
+            skia_rs_core::ColorType::Rgba8888,
+            skia_rs_core::AlphaType::Unpremul,
+        );
+        let image = Image::from_raster_data_owned(info, pixels.clone(), width * 4).unwrap();
+
+        let encoded = QoiEncoder::new().encode_bytes(&image).unwrap();
+        assert_eq!(ImageFormat::from_magic(&encoded), ImageFormat::Qoi);
+        assert_eq!(get_image_dimensions(&encoded).unwrap(), (17, 9));
+        assert_eq!(encoded[12], 4);
+        assert!(encoded.ends_with(&QOI_END));
+
+        let decoded = decode_image(&encoded).unwrap();
+        assert_eq!(decoded.dimensions(), (17, 9));
+        assert_eq!(decoded.alpha_type(), skia_rs_core::AlphaType::Unpremul);
+        assert_eq!(decoded.peek_pixels().unwrap(), &pixels[..]);
+        assert!(decoded.color_space().unwrap().is_srgb());
+    }
+
+    #[test]
+    fn test_qoi_rgb_linear() {
+        let mut info = crate::ImageInfo::new(
+            70,
+            1,
+            skia_rs_core::ColorType::Rgba8888,
+            skia_rs_core::AlphaType::Opaque,
+        );
+        info.color_space = Some(skia_rs_core::ColorSpace::srgb_linear());
+        // A run longer than a single QOI_OP_RUN can hold
+        let pixels = [1, 2, 3, 255].repeat(70);
+        let image = Image::from_raster_data_owned(info, pixels.clone(), 70 * 4).unwrap();
+
+        let encoded = QoiEncoder::new().encode_bytes(&image).unwrap();
+        assert_eq!(&encoded[12..14], &[3, 1]);
+
+        let decoded = QoiDecoder::new().decode_bytes(&encoded).unwrap();
+        assert_eq!(decoded.alpha_type(), skia_rs_core::AlphaType::Opaque);
+        assert!(decoded.color_space().unwrap().is_linear());
+        assert_eq!(decoded.peek_pixels().unwrap(), &pixels[..]);
+
+        assert!(decode_image(&encoded[..16]).is_err());
+    }
+
+    #[test]
+    fn test_qoi_dimensions_too_large() {
+        // Enough maximal runs to cover the header's claim, which still
+        // exceeds the pixel limit
+        let (width, height) = (1u32 << 14, (1u32 << 14) + 1);
+        let mut data = b"qoif".to_vec();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&[4, 0]);
+        let runs = (width as usize * height as usize).div_ceil(62);
+        data.resize(data.len() + runs, QOI_OP_RUN | 61);
+        data.extend_from_slice(&QOI_END);
+        assert!(matches!(
+            decode_image(&data),
+            Err(CodecError::InvalidData(message)) if message == "QOI dimensions too large"
+        ));
+    }
+
+    #[test]
+    fn test_encode_image() {
+        let info = crate::ImageInfo::new(
+            3,
+            2,
+            skia_rs_core::ColorType::Rgba8888,
+            skia_rs_core::AlphaType::Opaque,
+        );
+        let pixels: Vec<u8> = (0..6u8)
+            .flat_map(|i| [i * 40, 255 - i * 40, i * 20, 255])
+            .collect();
+        let image = Image::from_raster_data_owned(info, pixels.clone(), 12).unwrap();
+
+        for format in [ImageFormat::Png, ImageFormat::Bmp] {
+            let encoded = encode_image(&image, format, Some(EncoderQuality::LOW)).unwrap();
+            assert_eq!(ImageFormat::from_magic(&encoded), format);
+
+            let decoded = decode_image(&encoded).unwrap();
+            assert_eq!(decoded.dimensions(), (3, 2));
+            assert_eq!(decoded.peek_pixels().unwrap(), &pixels[..], "{format:?}");
+        }
+
+        for format in [ImageFormat::Gif, ImageFormat::Ico, ImageFormat::Unknown] {
+            assert!(matches!(
+                encode_image(&image, format, None),
+                Err(CodecError::Unsupported(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_gif_webp_dimensions() {
+        let gif = b"GIF89a\x2C\x01\x0A\x00\x00\x00\x00";
+        assert_eq!(get_image_dimensions(gif).unwrap(), (300, 10));
+
+        let image = Image::from_color(37, 21, 0xFF_336699).unwrap();
+        for encoder in [WebpEncoder::new(), WebpEncoder::lossless()] {
+            let encoded = encoder.encode_bytes(&image).unwrap();
+            assert_eq!(get_image_dimensions(&encoded).unwrap(), (37, 21));
+        }
+
+        let mut vp8x = b"RIFF\0\0\0\0WEBPVP8X".to_vec();
+        vp8x.extend_from_slice(&[10, 0, 0, 0, 0, 0, 0, 0]);
+        vp8x.extend_from_slice(&[0xFF, 0x03, 0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(get_image_dimensions(&vp8x).unwrap(), (1024, 2));
+    }
 }
@@ -0,0 +1,139 @@
+//! Pixel comparison of images, for golden tests and output validation.
+
+use crate::Image;
+use skia_rs_core::AlphaType;
+
+/// The per-channel difference between two images.
+///
+/// Channels are compared premultiplied, in 8-bit units and RGBA order, so
+/// pixels that are both fully transparent match whatever color they store.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DiffResult {
+    /// Largest absolute difference of each channel.
+    pub max_diff: [u8; 4],
+    /// Mean absolute difference of each channel over all pixels.
+    pub mean_diff: [f32; 4],
+    /// Number of pixels where any channel differs.
+    pub differing_pixels: usize,
+}
+
+impl DiffResult {
+    /// Largest absolute difference over all channels.
+    #[inline]
+    pub fn max_channel_diff(&self) -> u8 {
+        self.max_diff.into_iter().max().unwrap_or(0)
+    }
+
+    /// Check whether the images matched exactly.
+    #[inline]
+    pub fn is_identical(&self) -> bool {
+        self.differing_pixels == 0
+    }
+}
+
+/// Compare two images pixel by pixel.
+///
+/// Returns `None` if the images differ in size or either uses a color type
+/// whose pixels can't be read.
+pub fn image_diff(a: &Image, b: &Image) -> Option<DiffResult> {
+    if a.dimensions() != b.dimensions() {
+        return None;
+    }
+
+    let mut result = DiffResult::default();
+    let mut sums = [0u64; 4];
+    for y in 0..a.height() {
+        for x in 0..a.width() {
+            let pa = premultiplied_pixel(a, x, y)?;
+            let pb = premultiplied_pixel(b, x, y)?;
+            let mut differs = false;
+            for channel in 0..4 {
+                let diff = pa[channel].abs_diff(pb[channel]);
+                result.max_diff[channel] = result.max_diff[channel].max(diff);
+                sums[channel] += u64::from(diff);
+                differs |= diff != 0;
+            }
+            result.differing_pixels += usize::from(differs);
+        }
+    }
+
+    let count = (a.width() as u64 * a.height() as u64).max(1) as f32;
+    result.mean_diff = sums.map(|sum| sum as f32 / count);
+    Some(result)
+}
+
+/// Check whether two images match, allowing each channel to differ by up to
+/// `tolerance`.
+///
+/// Images of different sizes or unreadable color types never match.
+pub fn images_equal(a: &Image, b: &Image, tolerance: u8) -> bool {
+    image_diff(a, b).is_some_and(|diff| diff.max_channel_diff() <= tolerance)
+}
+
+/// Read a pixel as premultiplied 8-bit RGBA.
+fn premultiplied_pixel(image: &Image, x: i32, y: i32) -> Option<[u8; 4]> {
+    let color = image.read_pixel(x, y)?;
+    let scale = if image.alpha_type() == AlphaType::Unpremul {
+        color.a
+    } else {
+        1.0
+    };
+    let to_byte = |v: f32| (v * 255.0).round() as u8;
+    Some([
+        to_byte(color.r * scale),
+        to_byte(color.g * scale),
+        to_byte(color.b * scale),
+        to_byte(color.a),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ImageInfo;
+    use skia_rs_core::ColorType;
+
+    fn image_from_pixels(alpha_type: AlphaType, pixels: Vec<u8>) -> Image {
+        let info = ImageInfo::new(2, 2, ColorType::Rgba8888, alpha_type);
+        Image::from_raster_data_owned(info, pixels, 8).unwrap()
+    }
+
+    #[test]
+    fn test_image_diff_identical() {
+        let a = Image::from_color(4, 3, 0xFF_336699).unwrap();
+        let b = Image::from_color(4, 3, 0xFF_336699).unwrap();
+        let diff = image_diff(&a, &b).unwrap();
+        assert!(diff.is_identical());
+        assert_eq!(diff, DiffResult::default());
+        assert!(images_equal(&a, &b, 0));
+    }
+
+    #[test]
+    fn test_image_diff_one_pixel() {
+        let mut pixels = vec![255u8; 16];
+        let a = image_from_pixels(AlphaType::Premul, pixels.clone());
+        pixels[4..8].copy_from_slice(&[255, 245, 215, 255]);
+        let b = image_from_pixels(AlphaType::Premul, pixels);
+
+        let diff = image_diff(&a, &b).unwrap();
+        assert_eq!(diff.differing_pixels, 1);
+        assert_eq!(diff.max_diff, [0, 10, 40, 0]);
+        assert_eq!(diff.mean_diff, [0.0, 2.5, 10.0, 0.0]);
+        assert_eq!(diff.max_channel_diff(), 40);
+        assert!(!images_equal(&a, &b, 39));
+        assert!(images_equal(&a, &b, 40));
+
+        let small = Image::from_color(1, 2, 0xFFFFFFFF).unwrap();
+        assert!(image_diff(&a, &small).is_none());
+        assert!(!images_equal(&a, &small, 255));
+    }
+
+    #[test]
+    fn test_image_diff_ignores_color_under_zero_alpha() {
+        let mut pixels = vec![0u8; 16];
+        let a = image_from_pixels(AlphaType::Unpremul, pixels.clone());
+        pixels[0..4].copy_from_slice(&[200, 100, 50, 0]);
+        let b = image_from_pixels(AlphaType::Unpremul, pixels);
+        assert!(image_diff(&a, &b).unwrap().is_identical());
+    }
+}
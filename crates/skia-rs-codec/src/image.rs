@@ -2,8 +2,13 @@
 //!
 //! Images represent immutable pixel data that can be drawn to a canvas.
 
-use skia_rs_core::{AlphaType, ColorSpace, ColorType, Rect, Scalar};
-use std::sync::Arc;
+use crate::{CodecResult, EncoderQuality, ImageFormat};
+use skia_rs_core::{AlphaType, ColorSpace, ColorType, IRect, IccProfile, Rect, Scalar};
+use std::panic::RefUnwindSafe;
+use std::sync::{Arc, OnceLock};
+
+/// Pixel storage that several images, or an image and its owner, can share.
+pub type SharedPixels = Arc<dyn AsRef<[u8]> + Send + Sync + RefUnwindSafe>;
 
 /// Simplified image info for codec use (avoids Result-based construction).
 #[derive(Debug, Clone, PartialEq)]
@@ -110,13 +115,61 @@ impl ImageInfo {
 /// Corresponds to Skia's `SkImage`.
 #[derive(Clone)]
 pub struct Image {
-    inner: Arc<ImageData>,
+    inner: Arc<ImageSource>,
 }
 
+#[derive(Clone)]
+enum ImageSource {
+    /// Decoded pixels.
+    Raster(ImageData),
+    /// Encoded data, decoded on first pixel access.
+    Encoded(EncodedData),
+}
+
+#[derive(Clone)]
 struct ImageData {
     info: ImageInfo,
-    pixels: Vec<u8>,
+    /// Pixel storage, shared with subsets of this image and, for surface
+    /// snapshots, with the surface until it is next written.
+    pixels: SharedPixels,
+    /// Byte offset of the top-left pixel within `pixels`.
+    offset: usize,
     row_bytes: usize,
+    icc_profile: Option<IccProfile>,
+}
+
+#[derive(Clone)]
+struct EncodedData {
+    data: Arc<Vec<u8>>,
+    format: ImageFormat,
+    /// Dimensions read from the header.
+    width: i32,
+    height: i32,
+    /// The decoded pixels, and whether decoding succeeded.
+    decoded: OnceLock<(ImageData, bool)>,
+}
+
+impl EncodedData {
+    fn decode(&self) -> &(ImageData, bool) {
+        self.decoded
+            .get_or_init(|| match crate::decode_image(&self.data) {
+                Ok(image) => (image.data().clone(), true),
+                // Data that fails to decode becomes an empty image, which
+                // draws nothing; sizing it from the header would let a bogus
+                // header force a huge allocation.
+                Err(_) => {
+                    let info = ImageInfo::new(0, 0, ColorType::Rgba8888, AlphaType::Premul);
+                    let data = ImageData {
+                        pixels: Arc::new(Vec::new()),
+                        info,
+                        offset: 0,
+                        row_bytes: 0,
+                        icc_profile: None,
+                    };
+                    (data, false)
+                }
+            })
+    }
 }
 
 impl std::fmt::Debug for Image {
@@ -145,11 +198,13 @@ impl Image {
         }
 
         Some(Self {
-            inner: Arc::new(ImageData {
+            inner: Arc::new(ImageSource::Raster(ImageData {
                 info: info.clone(),
-                pixels: pixels[..expected_size].to_vec(),
+                pixels: Arc::new(pixels[..expected_size].to_vec()),
+                offset: 0,
                 row_bytes,
-            }),
+                icc_profile: None,
+            })),
         })
     }
 
@@ -169,11 +224,73 @@ impl Image {
         }
 
         Some(Self {
-            inner: Arc::new(ImageData {
+            inner: Arc::new(ImageSource::Raster(ImageData {
+                info,
+                pixels: Arc::new(pixels),
+                offset: 0,
+                row_bytes,
+                icc_profile: None,
+            })),
+        })
+    }
+
+    /// Create an image that shares existing pixel storage instead of copying
+    /// it.
+    ///
+    /// The storage stays alive as long as the image (or any subset of it)
+    /// does. Owners that want to keep writing must copy first, e.g. with
+    /// [`Arc::make_mut`].
+    pub fn from_raster_data_shared(
+        info: ImageInfo,
+        pixels: SharedPixels,
+        row_bytes: usize,
+    ) -> Option<Self> {
+        if info.is_empty() {
+            return None;
+        }
+
+        let expected_size = info.compute_byte_size(row_bytes);
+        if (*pixels).as_ref().len() < expected_size {
+            return None;
+        }
+
+        Some(Self {
+            inner: Arc::new(ImageSource::Raster(ImageData {
                 info,
                 pixels,
+                offset: 0,
                 row_bytes,
-            }),
+                icc_profile: None,
+            })),
+        })
+    }
+
+    /// Create an image from encoded data (PNG, JPEG, etc.).
+    ///
+    /// Only the header is read here, so the dimensions are available right
+    /// away. The data is decoded on first pixel access (`peek_pixels`,
+    /// drawing, ...) and the result is cached; data that fails to decode
+    /// leaves an empty image. Returns `None` if the format isn't recognized,
+    /// or its dimensions can't be read or exceed
+    /// [`MAX_IMAGE_PIXELS`](crate::MAX_IMAGE_PIXELS).
+    pub fn from_encoded(data: Arc<Vec<u8>>) -> Option<Self> {
+        let format = ImageFormat::from_magic(&data);
+        let (width, height) = crate::get_image_dimensions(&data).ok()?;
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+        if width as u64 * height as u64 > crate::MAX_IMAGE_PIXELS {
+            return None;
+        }
+
+        Some(Self {
+            inner: Arc::new(ImageSource::Encoded(EncodedData {
+                data,
+                format,
+                width,
+                height,
+                decoded: OnceLock::new(),
+            })),
         })
     }
 
@@ -206,22 +323,77 @@ impl Image {
         Self::from_raster_data_owned(info, pixels, row_bytes)
     }
 
+    /// The decoded image data, decoding encoded images if needed.
+    fn data(&self) -> &ImageData {
+        match &*self.inner {
+            ImageSource::Raster(data) => data,
+            ImageSource::Encoded(encoded) => &encoded.decode().0,
+        }
+    }
+
+    /// Like [`data`](Self::data), but `None` if decoding failed.
+    ///
+    /// Pixel readers call this before looking at the dimensions: an encoded
+    /// image reports its header's size until decoded, and the decoded size
+    /// may differ (EXIF rotation) or be `(0, 0)` after a failure.
+    fn decoded(&self) -> Option<&ImageData> {
+        match &*self.inner {
+            ImageSource::Raster(data) => Some(data),
+            ImageSource::Encoded(encoded) => {
+                let (data, ok) = encoded.decode();
+                ok.then_some(data)
+            }
+        }
+    }
+
     /// Get the image width.
     #[inline]
     pub fn width(&self) -> i32 {
-        self.inner.info.width()
+        self.dimensions().0
     }
 
     /// Get the image height.
     #[inline]
     pub fn height(&self) -> i32 {
-        self.inner.info.height()
+        self.dimensions().1
     }
 
     /// Get the image dimensions as (width, height).
+    ///
+    /// Encoded images report their header's dimensions until decoded, and
+    /// `(0, 0)` if decoding failed.
     #[inline]
     pub fn dimensions(&self) -> (i32, i32) {
-        (self.width(), self.height())
+        let info = match &*self.inner {
+            ImageSource::Raster(data) => &data.info,
+            ImageSource::Encoded(encoded) => match encoded.decoded.get() {
+                Some((data, _)) => &data.info,
+                None => return (encoded.width, encoded.height),
+            },
+        };
+        (info.width, info.height)
+    }
+
+    /// Returns true if the image was created from encoded data and is
+    /// decoded on demand.
+    pub fn is_lazy_generated(&self) -> bool {
+        matches!(&*self.inner, ImageSource::Encoded(_))
+    }
+
+    /// Get the encoded data the image was created from, if any.
+    pub fn ref_encoded_data(&self) -> Option<Arc<Vec<u8>>> {
+        match &*self.inner {
+            ImageSource::Encoded(encoded) => Some(Arc::clone(&encoded.data)),
+            ImageSource::Raster(_) => None,
+        }
+    }
+
+    /// Get the format of the encoded data the image was created from, if any.
+    pub fn encoded_format(&self) -> Option<ImageFormat> {
+        match &*self.inner {
+            ImageSource::Encoded(encoded) => Some(encoded.format),
+            ImageSource::Raster(_) => None,
+        }
     }
 
     /// Get the image bounds as a rectangle.
@@ -233,37 +405,52 @@ impl Image {
     /// Get the image info.
     #[inline]
     pub fn info(&self) -> &ImageInfo {
-        &self.inner.info
+        &self.data().info
     }
 
     /// Get the color type.
     #[inline]
     pub fn color_type(&self) -> ColorType {
-        self.inner.info.color_type()
+        self.data().info.color_type()
     }
 
     /// Get the alpha type.
     #[inline]
     pub fn alpha_type(&self) -> AlphaType {
-        self.inner.info.alpha_type()
+        self.data().info.alpha_type()
     }
 
     /// Get the color space.
     #[inline]
     pub fn color_space(&self) -> Option<&ColorSpace> {
-        self.inner.info.color_space()
+        self.data().info.color_space()
+    }
+
+    /// Get the ICC profile the image was decoded with, if any.
+    #[inline]
+    pub fn icc_profile(&self) -> Option<&IccProfile> {
+        self.data().icc_profile.as_ref()
+    }
+
+    /// Attach an ICC profile describing the image's colors.
+    pub fn with_icc_profile(self, profile: IccProfile) -> Self {
+        let mut data = self.data().clone();
+        data.icc_profile = Some(profile);
+        Self {
+            inner: Arc::new(ImageSource::Raster(data)),
+        }
     }
 
     /// Returns true if the image is opaque.
     #[inline]
     pub fn is_opaque(&self) -> bool {
-        self.inner.info.is_opaque()
+        self.data().info.is_opaque()
     }
 
     /// Get the row bytes (stride).
     #[inline]
     pub fn row_bytes(&self) -> usize {
-        self.inner.row_bytes
+        self.data().row_bytes
     }
 
     /// Get the unique ID for this image.
@@ -281,6 +468,10 @@ impl Image {
         src_x: i32,
         src_y: i32,
     ) -> bool {
+        if self.decoded().is_none() {
+            return false;
+        }
+
         // Validate bounds
         if src_x < 0 || src_y < 0 {
             return false;
@@ -301,7 +492,7 @@ impl Image {
         if dst_info.color_type() == self.color_type() && dst_info.alpha_type() == self.alpha_type()
         {
             let bytes_per_pixel = self.color_type().bytes_per_pixel();
-            let src_row_bytes = self.inner.row_bytes;
+            let src_row_bytes = self.data().row_bytes;
 
             for y in 0..dst_info.height() as usize {
                 let src_offset =
@@ -310,7 +501,7 @@ impl Image {
                 let copy_len = dst_info.width() as usize * bytes_per_pixel;
 
                 dst_pixels[dst_offset..dst_offset + copy_len]
-                    .copy_from_slice(&self.inner.pixels[src_offset..src_offset + copy_len]);
+                    .copy_from_slice(&self.pixels()[src_offset..src_offset + copy_len]);
             }
 
             return true;
@@ -322,47 +513,113 @@ impl Image {
 
     /// Read a single pixel at (x, y).
     pub fn read_pixel(&self, x: i32, y: i32) -> Option<skia_rs_core::Color4f> {
+        self.decoded()?;
         if x < 0 || x >= self.width() || y < 0 || y >= self.height() {
             return None;
         }
 
         let bytes_per_pixel = self.color_type().bytes_per_pixel();
-        let offset = (y as usize) * self.inner.row_bytes + (x as usize) * bytes_per_pixel;
+        let offset = (y as usize) * self.data().row_bytes + (x as usize) * bytes_per_pixel;
 
         match self.color_type() {
             ColorType::Rgba8888 => {
-                let r = self.inner.pixels[offset] as f32 / 255.0;
-                let g = self.inner.pixels[offset + 1] as f32 / 255.0;
-                let b = self.inner.pixels[offset + 2] as f32 / 255.0;
-                let a = self.inner.pixels[offset + 3] as f32 / 255.0;
+                let r = self.pixels()[offset] as f32 / 255.0;
+                let g = self.pixels()[offset + 1] as f32 / 255.0;
+                let b = self.pixels()[offset + 2] as f32 / 255.0;
+                let a = self.pixels()[offset + 3] as f32 / 255.0;
                 Some(skia_rs_core::Color4f::new(r, g, b, a))
             }
             ColorType::Bgra8888 => {
-                let b = self.inner.pixels[offset] as f32 / 255.0;
-                let g = self.inner.pixels[offset + 1] as f32 / 255.0;
-                let r = self.inner.pixels[offset + 2] as f32 / 255.0;
-                let a = self.inner.pixels[offset + 3] as f32 / 255.0;
+                let b = self.pixels()[offset] as f32 / 255.0;
+                let g = self.pixels()[offset + 1] as f32 / 255.0;
+                let r = self.pixels()[offset + 2] as f32 / 255.0;
+                let a = self.pixels()[offset + 3] as f32 / 255.0;
                 Some(skia_rs_core::Color4f::new(r, g, b, a))
             }
             ColorType::Alpha8 => {
-                let a = self.inner.pixels[offset] as f32 / 255.0;
+                let a = self.pixels()[offset] as f32 / 255.0;
                 Some(skia_rs_core::Color4f::new(0.0, 0.0, 0.0, a))
             }
             ColorType::Gray8 => {
-                let v = self.inner.pixels[offset] as f32 / 255.0;
+                let v = self.pixels()[offset] as f32 / 255.0;
                 Some(skia_rs_core::Color4f::new(v, v, v, 1.0))
             }
+            ColorType::R16G16B16A16Unorm => {
+                let channel = |i: usize| {
+                    let bytes = &self.pixels()[offset + i * 2..offset + i * 2 + 2];
+                    u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / 65535.0
+                };
+                Some(skia_rs_core::Color4f::new(
+                    channel(0),
+                    channel(1),
+                    channel(2),
+                    channel(3),
+                ))
+            }
             _ => None,
         }
     }
 
     /// Get direct access to the pixel data (if available).
+    ///
+    /// Decodes encoded images; returns `None` if that fails.
     pub fn peek_pixels(&self) -> Option<&[u8]> {
-        Some(&self.inner.pixels)
+        self.decoded()?;
+        Some(self.pixels())
+    }
+
+    /// The pixel bytes of this image, starting at its top-left pixel.
+    fn pixels(&self) -> &[u8] {
+        let data = self.data();
+        let len = data.info.compute_byte_size(data.row_bytes);
+        &(*data.pixels).as_ref()[data.offset..data.offset + len]
+    }
+
+    /// Encode this image in the given format.
+    ///
+    /// See [`encode_image`](crate::encode_image) for how `quality` is used.
+    pub fn encode(
+        &self,
+        format: ImageFormat,
+        quality: Option<EncoderQuality>,
+    ) -> CodecResult<Vec<u8>> {
+        crate::encode_image(self, format, quality)
+    }
+
+    /// Create a view of a region of this image.
+    ///
+    /// The rect is clipped to the image bounds; returns `None` if nothing is
+    /// left. The result shares this image's pixels rather than copying them,
+    /// keeping the same row bytes.
+    pub fn subset(&self, rect: &IRect) -> Option<Self> {
+        let data = self.decoded()?;
+        let bounds = IRect::from_xywh(0, 0, data.info.width, data.info.height);
+        let rect = rect.intersect(&bounds)?;
+
+        let offset = data.offset
+            + rect.top as usize * data.row_bytes
+            + rect.left as usize * data.info.bytes_per_pixel();
+        Some(Self {
+            inner: Arc::new(ImageSource::Raster(ImageData {
+                info: ImageInfo {
+                    width: rect.width(),
+                    height: rect.height(),
+                    ..data.info.clone()
+                },
+                pixels: Arc::clone(&data.pixels),
+                offset,
+                row_bytes: data.row_bytes,
+                icc_profile: data.icc_profile.clone(),
+            })),
+        })
     }
 
     /// Create a subset of this image.
+    ///
+    /// Returns `None` if the subset is out of bounds or the image fails to
+    /// decode.
     pub fn make_subset(&self, subset: &Rect) -> Option<Self> {
+        self.decoded()?;
         let x = subset.left as i32;
         let y = subset.top as i32;
         let w = subset.width() as i32;
@@ -382,19 +639,21 @@ impl Image {
 
         for row in 0..h as usize {
             let src_offset =
-                (y as usize + row) * self.inner.row_bytes + x as usize * bytes_per_pixel;
+                (y as usize + row) * self.data().row_bytes + x as usize * bytes_per_pixel;
             let dst_offset = row * new_row_bytes;
 
             new_pixels[dst_offset..dst_offset + new_row_bytes]
-                .copy_from_slice(&self.inner.pixels[src_offset..src_offset + new_row_bytes]);
+                .copy_from_slice(&self.pixels()[src_offset..src_offset + new_row_bytes]);
         }
 
         Self::from_raster_data_owned(new_info, new_pixels, new_row_bytes)
     }
 
     /// Create a scaled version of this image.
+    ///
+    /// Returns `None` for an empty size or an image that fails to decode.
     pub fn make_scaled(&self, width: i32, height: i32) -> Option<Self> {
-        if width <= 0 || height <= 0 {
+        if width <= 0 || height <= 0 || self.decoded().is_none() {
             return None;
         }
 
@@ -412,11 +671,11 @@ impl Image {
             for dst_x in 0..width as usize {
                 let src_x = ((dst_x as f32 * x_scale) as usize).min(self.width() as usize - 1);
 
-                let src_offset = src_y * self.inner.row_bytes + src_x * bytes_per_pixel;
+                let src_offset = src_y * self.data().row_bytes + src_x * bytes_per_pixel;
                 let dst_offset = dst_y * new_row_bytes + dst_x * bytes_per_pixel;
 
                 for i in 0..bytes_per_pixel {
-                    new_pixels[dst_offset + i] = self.inner.pixels[src_offset + i];
+                    new_pixels[dst_offset + i] = self.pixels()[src_offset + i];
                 }
             }
         }
@@ -424,6 +683,107 @@ impl Image {
         Self::from_raster_data_owned(new_info, new_pixels, new_row_bytes)
     }
 
+    /// Resample this image to a new size with the given filter.
+    ///
+    /// Filtering happens on premultiplied colors so transparent pixels
+    /// don't bleed their color into their neighbors: unpremultiplied images
+    /// are premultiplied first and unpremultiplied again afterwards. When
+    /// downscaling, the filter widens to cover every source pixel.
+    ///
+    /// Supports 8-bit RGBA, BGRA, RGBX, gray and alpha images; returns
+    /// `None` for other color types, an empty size or an image that fails
+    /// to decode.
+    pub fn scaled(&self, new_w: i32, new_h: i32, filter: ResizeFilter) -> Option<Self> {
+        if new_w <= 0 || new_h <= 0 || self.decoded().is_none() {
+            return None;
+        }
+        let channels = match self.color_type() {
+            ColorType::Rgba8888 | ColorType::Bgra8888 | ColorType::Rgb888x => 4,
+            ColorType::Gray8 | ColorType::Alpha8 => 1,
+            _ => return None,
+        };
+        if filter == ResizeFilter::Nearest {
+            return self.make_scaled(new_w, new_h);
+        }
+
+        let (src_w, src_h) = (self.width() as usize, self.height() as usize);
+        let (dst_w, dst_h) = (new_w as usize, new_h as usize);
+        let unpremul = channels == 4 && self.alpha_type() == AlphaType::Unpremul;
+
+        // Load as floats, premultiplying where needed
+        let mut src = Vec::with_capacity(src_w * src_h * channels);
+        for y in 0..src_h {
+            let row = &self.pixels()[y * self.data().row_bytes..][..src_w * channels];
+            for pixel in row.chunks_exact(channels) {
+                let alpha = if unpremul {
+                    pixel[3] as f32 / 255.0
+                } else {
+                    1.0
+                };
+                src.extend(
+                    pixel.iter().enumerate().map(
+                        |(i, &c)| {
+                            if i < 3 { c as f32 * alpha } else { c as f32 }
+                        },
+                    ),
+                );
+            }
+        }
+
+        // Filter rows, then columns
+        let x_weights = resize_weights(src_w, dst_w, filter);
+        let y_weights = resize_weights(src_h, dst_h, filter);
+        let mut rows = vec![0.0f32; dst_w * src_h * channels];
+        for y in 0..src_h {
+            for (x, (start, weights)) in x_weights.iter().enumerate() {
+                for (k, &weight) in weights.iter().enumerate() {
+                    let s = (y * src_w + start + k) * channels;
+                    let d = (y * dst_w + x) * channels;
+                    for c in 0..channels {
+                        rows[d + c] += src[s + c] * weight;
+                    }
+                }
+            }
+        }
+        let mut dst = vec![0.0f32; dst_w * dst_h * channels];
+        for (y, (start, weights)) in y_weights.iter().enumerate() {
+            for (k, &weight) in weights.iter().enumerate() {
+                let s = (start + k) * dst_w * channels;
+                let d = y * dst_w * channels;
+                for i in 0..dst_w * channels {
+                    dst[d + i] += rows[s + i] * weight;
+                }
+            }
+        }
+
+        // Ringing filters can overshoot; clamp colors to their alpha
+        let mut pixels = Vec::with_capacity(dst.len());
+        for pixel in dst.chunks_exact(channels) {
+            if channels == 1 {
+                pixels.push(pixel[0].round().clamp(0.0, 255.0) as u8);
+                continue;
+            }
+            let alpha = pixel[3].clamp(0.0, 255.0);
+            let (max, scale) = match (unpremul, self.alpha_type()) {
+                (true, _) if alpha > 0.0 => (255.0, 255.0 / alpha),
+                (true, _) => (255.0, 0.0),
+                (false, AlphaType::Premul) => (alpha, 1.0),
+                (false, _) => (255.0, 1.0),
+            };
+            for &c in &pixel[..3] {
+                pixels.push((c * scale).round().clamp(0.0, max.round()) as u8);
+            }
+            pixels.push(alpha.round() as u8);
+        }
+
+        let info = ImageInfo {
+            width: new_w,
+            height: new_h,
+            ..self.info().clone()
+        };
+        Self::from_raster_data_owned(info, pixels, dst_w * channels)
+    }
+
     /// Create a transformed version of this image.
     pub fn make_with_filter(&self) -> Option<Self> {
         // TODO: Implement matrix transformation
@@ -431,6 +791,90 @@ impl Image {
     }
 }
 
+/// Resampling filter for [`Image::scaled`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResizeFilter {
+    /// Nearest neighbor; keeps hard edges.
+    Nearest,
+    /// Bilinear (triangle) filter.
+    #[default]
+    Bilinear,
+    /// Bicubic Catmull-Rom filter; sharper than bilinear.
+    Bicubic,
+    /// Lanczos filter with three lobes; sharpest, with some ringing.
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    /// Radius of the filter kernel, in source pixels at 1:1 scale.
+    fn support(self) -> f32 {
+        match self {
+            Self::Nearest => 0.5,
+            Self::Bilinear => 1.0,
+            Self::Bicubic => 2.0,
+            Self::Lanczos3 => 3.0,
+        }
+    }
+
+    /// Evaluate the filter kernel at distance `x`.
+    fn kernel(self, x: f32) -> f32 {
+        let x = x.abs();
+        match self {
+            Self::Nearest => (x < 0.5) as u8 as f32,
+            Self::Bilinear => (1.0 - x).max(0.0),
+            Self::Bicubic => {
+                // Catmull-Rom (B = 0, C = 0.5)
+                if x < 1.0 {
+                    1.5 * x * x * x - 2.5 * x * x + 1.0
+                } else if x < 2.0 {
+                    -0.5 * x * x * x + 2.5 * x * x - 4.0 * x + 2.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Lanczos3 => {
+                let sinc = |x: f32| {
+                    if x == 0.0 {
+                        1.0
+                    } else {
+                        let px = std::f32::consts::PI * x;
+                        px.sin() / px
+                    }
+                };
+                if x < 3.0 {
+                    sinc(x) * sinc(x / 3.0)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Compute, for each destination pixel, the first source pixel it reads and
+/// the normalized weights of the source pixels from there on.
+fn resize_weights(src_len: usize, dst_len: usize, filter: ResizeFilter) -> Vec<(usize, Vec<f32>)> {
+    let scale = src_len as f32 / dst_len as f32;
+    let filter_scale = scale.max(1.0);
+    let support = filter.support() * filter_scale;
+
+    (0..dst_len)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * scale;
+            let start = (center - support).floor().max(0.0) as usize;
+            let end = ((center + support).ceil() as usize).min(src_len);
+            let mut weights: Vec<f32> = (start..end)
+                .map(|j| filter.kernel((j as f32 + 0.5 - center) / filter_scale))
+                .collect();
+            let total: f32 = weights.iter().sum();
+            if total != 0.0 {
+                weights.iter_mut().for_each(|w| *w /= total);
+            }
+            (start, weights)
+        })
+        .collect()
+}
+
 /// A reference to an image (shared ownership).
 pub type ImageRef = Arc<Image>;
 
@@ -463,6 +907,155 @@ mod tests {
         assert_eq!(subset.dimensions(), (50, 50));
     }
 
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_image_encode() {
+        let image = Image::from_color(6, 3, 0xFF_00FF00).unwrap();
+        let encoded = image.encode(ImageFormat::Png, None).unwrap();
+        let decoded = crate::decode_image(&encoded).unwrap();
+        assert_eq!(decoded.dimensions(), (6, 3));
+        assert_eq!(decoded.peek_pixels(), image.peek_pixels());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_image_from_encoded() {
+        let pixels: Vec<u8> = (0..12u8)
+            .flat_map(|i| [i * 20, 0, 255 - i * 20, 255])
+            .collect();
+        let info = ImageInfo::new(4, 3, ColorType::Rgba8888, AlphaType::Unpremul);
+        let source = Image::from_raster_data_owned(info, pixels.clone(), 16).unwrap();
+        let data = Arc::new(source.encode(ImageFormat::Png, None).unwrap());
+
+        let is_decoded = |image: &Image| match &*image.inner {
+            ImageSource::Encoded(encoded) => encoded.decoded.get().is_some(),
+            ImageSource::Raster(_) => true,
+        };
+
+        let image = Image::from_encoded(Arc::clone(&data)).unwrap();
+        assert!(image.is_lazy_generated());
+        assert_eq!(image.encoded_format(), Some(ImageFormat::Png));
+        assert_eq!(image.dimensions(), (4, 3));
+        assert!(!is_decoded(&image));
+
+        assert_eq!(image.peek_pixels().unwrap(), &pixels[..]);
+        assert!(is_decoded(&image));
+        assert_eq!(
+            image.read_pixel(3, 2).unwrap().b,
+            (255 - 11 * 20) as f32 / 255.0
+        );
+        assert!(Arc::ptr_eq(&image.ref_encoded_data().unwrap(), &data));
+
+        // Corrupt data still has dimensions but no pixels
+        let truncated = Arc::new(data[..40].to_vec());
+        let image = Image::from_encoded(truncated).unwrap();
+        assert_eq!(image.dimensions(), (4, 3));
+        assert!(image.peek_pixels().is_none());
+        assert_eq!(image.dimensions(), (0, 0));
+        assert!(image.read_pixel(0, 0).is_none());
+
+        assert!(Image::from_encoded(Arc::new(b"not an image".to_vec())).is_none());
+    }
+
+    #[test]
+    fn test_image_from_encoded_corrupt_data() {
+        let info = ImageInfo::new(8, 8, ColorType::Rgba8888, AlphaType::Unpremul);
+        let source = Image::from_raster_data_owned(info.clone(), vec![200; 256], 32).unwrap();
+        let mut data = source.encode(ImageFormat::Png, None).unwrap();
+        // Keep the header, scramble the compressed pixel data after it
+        for byte in &mut data[41..] {
+            *byte ^= 0x5a;
+        }
+        let corrupt = || Image::from_encoded(Arc::new(data.clone())).unwrap();
+
+        // Each reader sees the header's size first, then fails to decode
+        let image = corrupt();
+        assert_eq!(image.dimensions(), (8, 8));
+        assert!(image.make_scaled(4, 4).is_none());
+        let image = corrupt();
+        assert_eq!(image.width(), 8);
+        assert!(image.scaled(4, 4, ResizeFilter::Bilinear).is_none());
+        let image = corrupt();
+        assert_eq!(image.width(), 8);
+        assert!(
+            image
+                .make_subset(&Rect::from_xywh(0.0, 0.0, 2.0, 2.0))
+                .is_none()
+        );
+        let image = corrupt();
+        assert_eq!(image.width(), 8);
+        assert!(image.subset(&IRect::from_xywh(0, 0, 2, 2)).is_none());
+        let image = corrupt();
+        assert_eq!(image.width(), 8);
+        assert!(image.read_pixel(7, 7).is_none());
+        let image = corrupt();
+        assert_eq!(image.width(), 8);
+        let mut dst = [0u8; 16];
+        assert!(!image.read_pixels(&info, &mut dst, 8, 0, 0));
+        assert_eq!(image.dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn test_image_from_encoded_oversized_header() {
+        let png_header = |width: u32, height: u32| {
+            let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+            data.extend_from_slice(&width.to_be_bytes());
+            data.extend_from_slice(&height.to_be_bytes());
+            data.extend_from_slice(&[8, 6, 0, 0, 0]);
+            Arc::new(data)
+        };
+
+        // Over the pixel cap: rejected before anything is decoded
+        assert!(Image::from_encoded(png_header(100_000, 100_000)).is_none());
+
+        // Within the cap but truncated: decoding fails without allocating
+        // the gigabyte the header asks for
+        let image = Image::from_encoded(png_header(16_000, 16_000)).unwrap();
+        assert_eq!(image.dimensions(), (16_000, 16_000));
+        assert!(image.peek_pixels().is_none());
+        assert_eq!(image.dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn test_image_subset_view() {
+        // 4x4 gray ramp: pixel (x, y) has value y * 4 + x
+        let info = ImageInfo::new(4, 4, ColorType::Gray8, AlphaType::Opaque);
+        let image = Image::from_raster_data_owned(info, (0..16).collect(), 4).unwrap();
+
+        let quadrant = image.subset(&IRect::from_xywh(2, 2, 2, 2)).unwrap();
+        assert_eq!(quadrant.dimensions(), (2, 2));
+        assert_eq!(quadrant.row_bytes(), 4);
+        assert_eq!(quadrant.peek_pixels().unwrap(), &[10, 11, 12, 13, 14, 15]);
+        assert_eq!(quadrant.read_pixel(1, 1).unwrap().r, 15.0 / 255.0);
+
+        // Subsets of subsets, clipped to bounds
+        let corner = quadrant.subset(&IRect::from_xywh(1, 1, 5, 5)).unwrap();
+        assert_eq!(corner.dimensions(), (1, 1));
+        assert_eq!(corner.peek_pixels().unwrap(), &[15]);
+
+        assert!(image.subset(&IRect::from_xywh(4, 0, 2, 2)).is_none());
+        assert!(image.subset(&IRect::empty()).is_none());
+    }
+
+    #[test]
+    fn test_image_subset_encode_round_trip() {
+        // 3x2 view into a 4x4 RGBA image whose pixel (x, y) is [x, y, 7, 255]
+        let info = ImageInfo::new(4, 4, ColorType::Rgba8888, AlphaType::Opaque);
+        let pixels: Vec<u8> = (0..16u8).flat_map(|i| [i % 4, i / 4, 7, 255]).collect();
+        let image = Image::from_raster_data_owned(info, pixels, 16).unwrap();
+        let view = image.subset(&IRect::from_xywh(1, 2, 3, 2)).unwrap();
+        let expected: Vec<u8> = (2..4u8)
+            .flat_map(|y| (1..4u8).flat_map(move |x| [x, y, 7, 255]))
+            .collect();
+
+        for format in [ImageFormat::Png, ImageFormat::Bmp, ImageFormat::Qoi] {
+            let encoded = view.encode(format, None).unwrap();
+            let decoded = crate::decode_image(&encoded).unwrap();
+            assert_eq!(decoded.dimensions(), (3, 2), "{format:?}");
+            assert_eq!(decoded.peek_pixels().unwrap(), &expected[..], "{format:?}");
+        }
+    }
+
     #[test]
     fn test_image_scaled() {
         let image = Image::from_color(100, 100, 0xFF_FF0000).unwrap();
@@ -470,6 +1063,56 @@ mod tests {
         assert_eq!(scaled.dimensions(), (50, 50));
     }
 
+    /// An 8x8 RGBA checkerboard of 1-pixel black and white cells.
+    fn checkerboard() -> Image {
+        let pixels: Vec<u8> = (0..64)
+            .flat_map(|i| {
+                let v = if (i % 8 + i / 8) % 2 == 0 { 255 } else { 0 };
+                [v, v, v, 255]
+            })
+            .collect();
+        let info = ImageInfo::new(8, 8, ColorType::Rgba8888, AlphaType::Premul);
+        Image::from_raster_data_owned(info, pixels, 32).unwrap()
+    }
+
+    #[test]
+    fn test_image_scaled_filters() {
+        let image = checkerboard();
+        for filter in [
+            ResizeFilter::Bilinear,
+            ResizeFilter::Bicubic,
+            ResizeFilter::Lanczos3,
+        ] {
+            let scaled = image.scaled(4, 4, filter).unwrap();
+            assert_eq!(scaled.dimensions(), (4, 4));
+            let center = scaled.read_pixel(2, 2).unwrap();
+            assert!((center.r - 0.5).abs() < 0.05, "{filter:?}: {center:?}");
+            assert_eq!(center.a, 1.0);
+        }
+
+        // Nearest keeps pure black and white
+        let scaled = image.scaled(16, 16, ResizeFilter::Nearest).unwrap();
+        let pixels = scaled.peek_pixels().unwrap();
+        assert!(pixels.iter().all(|&c| c == 0 || c == 255));
+        assert_eq!(scaled.read_pixel(1, 0).unwrap().r, 1.0);
+        assert_eq!(scaled.read_pixel(2, 0).unwrap().r, 0.0);
+
+        assert!(image.scaled(0, 4, ResizeFilter::Bilinear).is_none());
+    }
+
+    #[test]
+    fn test_image_scaled_unpremul_alpha() {
+        // Opaque red next to transparent green: filtering must not let the
+        // hidden green into the result
+        let pixels = [255, 0, 0, 255, 0, 255, 0, 0].repeat(2);
+        let info = ImageInfo::new(2, 2, ColorType::Rgba8888, AlphaType::Unpremul);
+        let image = Image::from_raster_data_owned(info, pixels, 8).unwrap();
+
+        let scaled = image.scaled(1, 1, ResizeFilter::Bilinear).unwrap();
+        assert_eq!(scaled.alpha_type(), AlphaType::Unpremul);
+        assert_eq!(scaled.peek_pixels().unwrap(), &[255, 0, 0, 128]);
+    }
+
     #[test]
     fn test_image_bounds() {
         let image = Image::from_color(100, 200, 0xFF_000000).unwrap();
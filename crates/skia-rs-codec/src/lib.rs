@@ -4,6 +4,7 @@
 //! - Image type for immutable pixel data
 //! - GPU-backed images for efficient GPU rendering
 //! - Lazy/deferred images for memory efficiency
+//! - Pixel comparison of images for golden tests
 //! - ImageGenerator trait for custom image generation
 //! - Codec trait for format-specific encoders/decoders
 //! - PNG encode/decode
@@ -13,19 +14,23 @@
 //! - BMP encode/decode
 //! - ICO decode
 //! - WBMP encode/decode (Wireless Bitmap)
+//! - QOI encode/decode (Quite OK Image)
 //! - AVIF encode/decode (optional, requires `avif` feature)
 //! - Camera RAW decode (optional, requires `raw` feature)
+//! - TIFF encode/decode (optional, requires `tiff` feature)
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
 pub mod codec;
+pub mod diff;
 pub mod generator;
 pub mod gpu_image;
 pub mod image;
 pub mod lazy_image;
 
 pub use codec::*;
+pub use diff::*;
 pub use generator::*;
 pub use gpu_image::*;
 pub use image::*;
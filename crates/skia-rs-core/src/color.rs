@@ -2,7 +2,14 @@
 //!
 //! This module provides Skia-compatible color types.
 
+#[cfg(not(feature = "std"))]
+use crate::FloatMath;
 use crate::Scalar;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use bitflags::bitflags;
 use bytemuck::{Pod, Zeroable};
 
@@ -86,6 +93,57 @@ impl Color {
         Self((self.0 & 0x00FFFFFF) | ((a as u32) << 24))
     }
 
+    /// Returns this color with RGB multiplied by alpha.
+    ///
+    /// Components are rounded to nearest. A fully transparent color becomes
+    /// [`Color::TRANSPARENT`]. See [`unpremultiply`](Self::unpremultiply)
+    /// for the inverse.
+    #[inline]
+    pub fn premultiply(self) -> Self {
+        let a = self.alpha() as u32;
+        match a {
+            255 => self,
+            0 => Self::TRANSPARENT,
+            _ => {
+                let mul = |c: u8| ((c as u32 * a + 127) / 255) as u8;
+                Self::from_argb(
+                    self.alpha(),
+                    mul(self.red()),
+                    mul(self.green()),
+                    mul(self.blue()),
+                )
+            }
+        }
+    }
+
+    /// Returns this color with RGB divided by alpha.
+    ///
+    /// Treats `self` as premultiplied. Components are rounded to nearest and
+    /// clamped to 255. A color with zero alpha becomes
+    /// [`Color::TRANSPARENT`].
+    ///
+    /// For any premultiplied `p`, `p.unpremultiply().premultiply() == p`.
+    /// The other direction loses precision at low alpha, since only
+    /// `alpha + 1` levels per channel survive premultiplication, but the
+    /// error never exceeds one premultiplied step (`|Δ| * alpha <= 255`).
+    #[inline]
+    pub fn unpremultiply(self) -> Self {
+        let a = self.alpha() as u32;
+        match a {
+            255 => self,
+            0 => Self::TRANSPARENT,
+            _ => {
+                let div = |c: u8| ((c as u32 * 255 + a / 2) / a).min(255) as u8;
+                Self::from_argb(
+                    self.alpha(),
+                    div(self.red()),
+                    div(self.green()),
+                    div(self.blue()),
+                )
+            }
+        }
+    }
+
     /// Converts to Color4f.
     #[inline]
     pub fn to_color4f(&self) -> Color4f {
@@ -97,6 +155,38 @@ impl Color {
     pub const fn as_u32(&self) -> u32 {
         self.0
     }
+
+    /// Formats the color as `#RRGGBB`, or `#RRGGBBAA` when not opaque.
+    ///
+    /// Digits are uppercase. The result parses back with
+    /// [`from_hex_string`](Self::from_hex_string).
+    pub fn to_hex_string(&self) -> String {
+        if self.alpha() == 255 {
+            format!("#{:02X}{:02X}{:02X}", self.red(), self.green(), self.blue())
+        } else {
+            format!(
+                "#{:02X}{:02X}{:02X}{:02X}",
+                self.red(),
+                self.green(),
+                self.blue(),
+                self.alpha()
+            )
+        }
+    }
+
+    /// Parses a `#RRGGBB` or `#RRGGBBAA` hex string.
+    ///
+    /// The leading `#` is optional and digits are case-insensitive. Returns
+    /// `None` for any other length or non-hex input.
+    pub fn from_hex_string(s: &str) -> Option<Self> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if !matches!(hex.len(), 6 | 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let alpha = if hex.len() == 8 { byte(6)? } else { 255 };
+        Some(Self::from_argb(alpha, byte(0)?, byte(2)?, byte(4)?))
+    }
 }
 
 impl From<u32> for Color {
@@ -115,37 +205,18 @@ impl From<Color> for u32 {
 
 /// Premultiply a color (multiply RGB by alpha).
 ///
-/// Returns a color where R, G, B are scaled by alpha/255.
+/// Equivalent to [`Color::premultiply`].
 #[inline]
 pub fn premultiply_color(color: Color) -> Color {
-    let a = color.alpha() as u32;
-    if a == 255 {
-        return color;
-    }
-    if a == 0 {
-        return Color::TRANSPARENT;
-    }
-
-    let r = (color.red() as u32 * a / 255) as u8;
-    let g = (color.green() as u32 * a / 255) as u8;
-    let b = (color.blue() as u32 * a / 255) as u8;
-
-    Color::from_argb(color.alpha(), r, g, b)
+    color.premultiply()
 }
 
 /// Unpremultiply a color (divide RGB by alpha).
+///
+/// Equivalent to [`Color::unpremultiply`].
 #[inline]
 pub fn unpremultiply_color(color: Color) -> Color {
-    let a = color.alpha() as u32;
-    if a == 255 || a == 0 {
-        return color;
-    }
-
-    let r = ((color.red() as u32 * 255 + a / 2) / a).min(255) as u8;
-    let g = ((color.green() as u32 * 255 + a / 2) / a).min(255) as u8;
-    let b = ((color.blue() as u32 * 255 + a / 2) / a).min(255) as u8;
-
-    Color::from_argb(color.alpha(), r, g, b)
+    color.unpremultiply()
 }
 
 // Legacy function aliases for backwards compatibility
@@ -338,6 +409,43 @@ impl Color4f {
     pub fn as_array(&self) -> [Scalar; 4] {
         [self.r, self.g, self.b, self.a]
     }
+
+    /// Converts an unpremultiplied color from one color space to another.
+    ///
+    /// The color is linearized with the source transfer function, mapped
+    /// through CIE XYZ (D65) into the destination gamut, and re-encoded with
+    /// the destination transfer function. Alpha is left untouched.
+    ///
+    /// Out-of-gamut results have components outside [0, 1]; pass `clamp` to
+    /// clip them into range.
+    pub fn convert(&self, from: &ColorSpace, to: &ColorSpace, clamp: bool) -> Self {
+        let converted = if from == to {
+            *self
+        } else {
+            let decode = |v| from.transfer_fn.to_linear(v);
+            let xyz = from
+                .gamut
+                .to_xyz(decode(self.r), decode(self.g), decode(self.b));
+            let (r, g, b) = to.gamut.from_xyz(xyz.0, xyz.1, xyz.2);
+            Self {
+                r: to.transfer_fn.from_linear(r),
+                g: to.transfer_fn.from_linear(g),
+                b: to.transfer_fn.from_linear(b),
+                a: self.a,
+            }
+        };
+
+        if clamp {
+            Self {
+                r: converted.r.clamp(0.0, 1.0),
+                g: converted.g.clamp(0.0, 1.0),
+                b: converted.b.clamp(0.0, 1.0),
+                a: converted.a,
+            }
+        } else {
+            converted
+        }
+    }
 }
 
 impl From<Color> for Color4f {
@@ -563,6 +671,92 @@ impl ColorSpace {
     pub fn is_linear(&self) -> bool {
         matches!(self.transfer_fn, TransferFunction::Linear)
     }
+
+    /// Precomputes the conversion from this color space to `to`.
+    ///
+    /// Use the result instead of [`Color4f::convert`] when converting many
+    /// colors between the same pair of spaces.
+    pub fn transform_to(&self, to: &ColorSpace) -> ColorSpaceTransform {
+        let src = self.gamut.to_xyz_matrix();
+        let dst = to.gamut.from_xyz_matrix();
+        let mut gamut = [0.0; 9];
+        for row in 0..3 {
+            for col in 0..3 {
+                gamut[row * 3 + col] = (0..3).map(|k| dst[row * 3 + k] * src[k * 3 + col]).sum();
+            }
+        }
+
+        ColorSpaceTransform {
+            src_transfer: self.transfer_fn,
+            dst_transfer: to.transfer_fn,
+            gamut,
+            identity: self == to,
+        }
+    }
+}
+
+/// A conversion between two color spaces with its gamut matrix computed up
+/// front.
+///
+/// Created by [`ColorSpace::transform_to`]. Applying it gives the same result
+/// as [`Color4f::convert`] with the same pair of spaces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorSpaceTransform {
+    src_transfer: TransferFunction,
+    dst_transfer: TransferFunction,
+    /// Row-major matrix from source linear RGB to destination linear RGB.
+    gamut: [Scalar; 9],
+    identity: bool,
+}
+
+impl ColorSpaceTransform {
+    /// Returns true if the transform leaves colors unchanged.
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        self.identity
+    }
+
+    /// Converts an unpremultiplied color. Alpha is left untouched.
+    ///
+    /// Out-of-gamut results have components outside [0, 1]; pass `clamp` to
+    /// clip them into range.
+    pub fn apply(&self, color: &Color4f, clamp: bool) -> Color4f {
+        let converted = if self.identity {
+            *color
+        } else {
+            let decode = |v| self.src_transfer.to_linear(v);
+            let (r, g, b) = mul_3x3(
+                &self.gamut,
+                decode(color.r),
+                decode(color.g),
+                decode(color.b),
+            );
+            Color4f {
+                r: self.dst_transfer.from_linear(r),
+                g: self.dst_transfer.from_linear(g),
+                b: self.dst_transfer.from_linear(b),
+                a: color.a,
+            }
+        };
+
+        if clamp {
+            Color4f {
+                r: converted.r.clamp(0.0, 1.0),
+                g: converted.g.clamp(0.0, 1.0),
+                b: converted.b.clamp(0.0, 1.0),
+                a: converted.a,
+            }
+        } else {
+            converted
+        }
+    }
+
+    /// Converts a slice of unpremultiplied colors in place.
+    pub fn apply_in_place(&self, colors: &mut [Color4f], clamp: bool) {
+        for color in colors {
+            *color = self.apply(color, clamp);
+        }
+    }
 }
 
 /// Transfer function (gamma curve) for a color space.
@@ -597,6 +791,112 @@ pub enum TransferFunction {
     },
 }
 
+impl TransferFunction {
+    /// Decodes an encoded component to linear light.
+    ///
+    /// Negative values are mirrored, so extended-range colors survive.
+    pub fn to_linear(&self, v: Scalar) -> Scalar {
+        let x = v.abs();
+        let l = match *self {
+            Self::Linear => x,
+            Self::Srgb => srgb_to_linear(x),
+            Self::Rec2020 => {
+                if x < 0.081 {
+                    x / 4.5
+                } else {
+                    ((x + 0.099) / 1.099).powf(1.0 / 0.45)
+                }
+            }
+            Self::Pq => {
+                let p = x.powf(1.0 / PQ_M2);
+                ((p - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * p)).powf(1.0 / PQ_M1)
+            }
+            Self::Hlg => {
+                if x <= 0.5 {
+                    x * x / 3.0
+                } else {
+                    (((x - HLG_C) / HLG_A).exp() + HLG_B) / 12.0
+                }
+            }
+            Self::Parametric {
+                g,
+                a,
+                b,
+                c,
+                d,
+                e,
+                f,
+            } => {
+                if x < d {
+                    c * x + f
+                } else {
+                    (a * x + b).powf(g) + e
+                }
+            }
+        };
+        l.copysign(v)
+    }
+
+    /// Encodes a linear component with this transfer function.
+    ///
+    /// This is the inverse of [`TransferFunction::to_linear`].
+    pub fn from_linear(&self, l: Scalar) -> Scalar {
+        let x = l.abs();
+        let v = match *self {
+            Self::Linear => x,
+            Self::Srgb => linear_to_srgb(x),
+            Self::Rec2020 => {
+                if x < 0.018 {
+                    x * 4.5
+                } else {
+                    1.099 * x.powf(0.45) - 0.099
+                }
+            }
+            Self::Pq => {
+                let p = x.powf(PQ_M1);
+                ((PQ_C1 + PQ_C2 * p) / (1.0 + PQ_C3 * p)).powf(PQ_M2)
+            }
+            Self::Hlg => {
+                if x <= 1.0 / 12.0 {
+                    (3.0 * x).sqrt()
+                } else {
+                    HLG_A * (12.0 * x - HLG_B).ln() + HLG_C
+                }
+            }
+            Self::Parametric {
+                g,
+                a,
+                b,
+                c,
+                d,
+                e,
+                f,
+            } => {
+                if x < c * d + f {
+                    if c == 0.0 { 0.0 } else { (x - f) / c }
+                } else if a == 0.0 {
+                    0.0
+                } else {
+                    ((x - e).max(0.0).powf(1.0 / g) - b) / a
+                }
+            }
+        };
+        v.copysign(l)
+    }
+}
+
+// SMPTE ST 2084 (PQ) constants.
+const PQ_M1: Scalar = 2610.0 / 16384.0;
+const PQ_M2: Scalar = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: Scalar = 3424.0 / 4096.0;
+const PQ_C2: Scalar = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: Scalar = 2392.0 / 4096.0 * 32.0;
+
+// ARIB STD-B67 (HLG) constants.
+const HLG_A: Scalar = 0.178_832_77;
+const HLG_B: Scalar = 0.284_668_92;
+const HLG_C: Scalar = 0.559_910_7;
+
 /// Color gamut (primaries and white point).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ColorGamut {
@@ -614,6 +914,125 @@ pub enum ColorGamut {
     Custom,
 }
 
+impl ColorGamut {
+    /// Returns the row-major matrix mapping linear RGB in this gamut to XYZ (D65).
+    ///
+    /// [`ColorGamut::Custom`] carries no primaries and is treated as sRGB.
+    pub fn to_xyz_matrix(&self) -> [Scalar; 9] {
+        match self {
+            Self::Srgb | Self::Custom => [
+                0.412_456_4,
+                0.357_576_1,
+                0.180_437_5,
+                0.212_672_9,
+                0.715_152_2,
+                0.072_175,
+                0.019_333_9,
+                0.119_192,
+                0.950_304_1,
+            ],
+            Self::AdobeRgb => [
+                0.576_730_9,
+                0.185_554,
+                0.188_185_2,
+                0.297_376_9,
+                0.627_349_1,
+                0.075_274_1,
+                0.027_034_3,
+                0.070_687_2,
+                0.991_108_5,
+            ],
+            Self::DisplayP3 => [
+                0.486_570_9,
+                0.265_667_7,
+                0.198_217_3,
+                0.228_974_6,
+                0.691_738_5,
+                0.079_286_9,
+                0.0,
+                0.045_113_4,
+                1.043_944_4,
+            ],
+            Self::Rec2020 => [
+                0.636_958,
+                0.144_616_9,
+                0.168_881,
+                0.262_700_2,
+                0.677_998_1,
+                0.059_301_7,
+                0.0,
+                0.028_072_7,
+                1.060_985_1,
+            ],
+            Self::Xyz => [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Returns the row-major matrix mapping CIE XYZ (D65) to linear RGB in
+    /// this gamut.
+    pub fn from_xyz_matrix(&self) -> [Scalar; 9] {
+        match self {
+            Self::Srgb | Self::Custom => [
+                3.240_454_2,
+                -1.537_138_5,
+                -0.498_531_4,
+                -0.969_266,
+                1.876_010_8,
+                0.041_556,
+                0.055_643_4,
+                -0.204_025_9,
+                1.057_225_2,
+            ],
+            _ => invert_3x3(&self.to_xyz_matrix()),
+        }
+    }
+
+    /// Maps linear RGB in this gamut to CIE XYZ (D65).
+    pub fn to_xyz(&self, r: Scalar, g: Scalar, b: Scalar) -> (Scalar, Scalar, Scalar) {
+        match self {
+            Self::Srgb | Self::Custom => rgb_to_xyz(r, g, b),
+            _ => mul_3x3(&self.to_xyz_matrix(), r, g, b),
+        }
+    }
+
+    /// Maps CIE XYZ (D65) to linear RGB in this gamut.
+    pub fn from_xyz(&self, x: Scalar, y: Scalar, z: Scalar) -> (Scalar, Scalar, Scalar) {
+        match self {
+            Self::Srgb | Self::Custom => xyz_to_rgb(x, y, z),
+            Self::Xyz => (x, y, z),
+            _ => mul_3x3(&invert_3x3(&self.to_xyz_matrix()), x, y, z),
+        }
+    }
+}
+
+/// Multiplies a row-major 3x3 matrix by a column vector.
+#[inline]
+fn mul_3x3(m: &[Scalar; 9], x: Scalar, y: Scalar, z: Scalar) -> (Scalar, Scalar, Scalar) {
+    (
+        m[0] * x + m[1] * y + m[2] * z,
+        m[3] * x + m[4] * y + m[5] * z,
+        m[6] * x + m[7] * y + m[8] * z,
+    )
+}
+
+/// Inverts a non-singular row-major 3x3 matrix.
+fn invert_3x3(m: &[Scalar; 9]) -> [Scalar; 9] {
+    let det = m[0] * (m[4] * m[8] - m[5] * m[7]) - m[1] * (m[3] * m[8] - m[5] * m[6])
+        + m[2] * (m[3] * m[7] - m[4] * m[6]);
+    let inv_det = 1.0 / det;
+    [
+        (m[4] * m[8] - m[5] * m[7]) * inv_det,
+        (m[2] * m[7] - m[1] * m[8]) * inv_det,
+        (m[1] * m[5] - m[2] * m[4]) * inv_det,
+        (m[5] * m[6] - m[3] * m[8]) * inv_det,
+        (m[0] * m[8] - m[2] * m[6]) * inv_det,
+        (m[2] * m[3] - m[0] * m[5]) * inv_det,
+        (m[3] * m[7] - m[4] * m[6]) * inv_det,
+        (m[1] * m[6] - m[0] * m[7]) * inv_det,
+        (m[0] * m[4] - m[1] * m[3]) * inv_det,
+    ]
+}
+
 // =============================================================================
 // ICC Profile Support
 // =============================================================================
@@ -740,6 +1159,136 @@ impl IccProfile {
     pub fn is_srgb(&self) -> bool {
         self.embedded_color_space.is_srgb()
     }
+
+    /// Serialize the profile as ICC data.
+    ///
+    /// Returns the raw data the profile was parsed from, or builds an ICC v2
+    /// matrix/TRC display profile from the embedded color space. Returns
+    /// `None` for profiles without raw data that aren't RGB.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        if let Some(data) = &self.raw_data {
+            return Some(data.clone());
+        }
+        (self.color_space == IccColorSpace::Rgb).then(|| self.build_matrix_trc_profile())
+    }
+
+    /// Build an ICC v2 RGB display profile with primaries and tone curves.
+    fn build_matrix_trc_profile(&self) -> Vec<u8> {
+        // Bradford adaptation from D65 to the D50 profile connection space
+        const D65_TO_D50: [Scalar; 9] = [
+            1.047_811_2,
+            0.022_886_6,
+            -0.050_127,
+            0.029_542_4,
+            0.990_484_4,
+            -0.017_049_1,
+            -0.009_234_5,
+            0.015_043_6,
+            0.752_131_6,
+        ];
+        const D50: [Scalar; 3] = [0.964_2, 1.0, 0.824_9];
+        const D65: [Scalar; 3] = [0.950_5, 1.0, 1.089];
+        const CURVE_POINTS: usize = 1024;
+
+        let to_xyz = self.embedded_color_space.gamut.to_xyz_matrix();
+        let primary = |column: usize| -> [Scalar; 3] {
+            let xyz = [to_xyz[column], to_xyz[3 + column], to_xyz[6 + column]];
+            [0, 1, 2].map(|row| {
+                (0..3)
+                    .map(|k| D65_TO_D50[row * 3 + k] * xyz[k])
+                    .sum::<Scalar>()
+            })
+        };
+        let xyz_tag = |xyz: [Scalar; 3]| -> Vec<u8> {
+            let mut tag = b"XYZ \0\0\0\0".to_vec();
+            for v in xyz {
+                tag.extend_from_slice(&s15_fixed16(v).to_be_bytes());
+            }
+            tag
+        };
+
+        let mut desc = b"desc\0\0\0\0".to_vec();
+        desc.extend_from_slice(&(self.description.len() as u32 + 1).to_be_bytes());
+        desc.extend_from_slice(self.description.as_bytes());
+        desc.push(0);
+        // Empty Unicode and ScriptCode descriptions
+        desc.resize(desc.len() + 8 + 3 + 67, 0);
+
+        let mut cprt = b"text\0\0\0\0No copyright, use freely".to_vec();
+        cprt.push(0);
+
+        let mut trc = b"curv\0\0\0\0".to_vec();
+        let transfer = self.embedded_color_space.transfer_fn;
+        if transfer == TransferFunction::Linear {
+            trc.extend_from_slice(&0u32.to_be_bytes());
+        } else {
+            trc.extend_from_slice(&(CURVE_POINTS as u32).to_be_bytes());
+            for i in 0..CURVE_POINTS {
+                let linear = transfer.to_linear(i as Scalar / (CURVE_POINTS - 1) as Scalar);
+                let value = (linear.clamp(0.0, 1.0) * 65535.0).round() as u16;
+                trc.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+
+        // The three tone curves share one tag
+        let tags: [(&[u8; 4], usize); 9] = [
+            (b"desc", 0),
+            (b"cprt", 1),
+            (b"wtpt", 2),
+            (b"rXYZ", 3),
+            (b"gXYZ", 4),
+            (b"bXYZ", 5),
+            (b"rTRC", 6),
+            (b"gTRC", 6),
+            (b"bTRC", 6),
+        ];
+        let data = [
+            desc,
+            cprt,
+            xyz_tag(D65),
+            xyz_tag(primary(0)),
+            xyz_tag(primary(1)),
+            xyz_tag(primary(2)),
+            trc,
+        ];
+
+        let mut offsets = Vec::with_capacity(data.len());
+        let mut offset = 128 + 4 + tags.len() * 12;
+        for tag in &data {
+            offsets.push(offset);
+            offset += tag.len().next_multiple_of(4);
+        }
+
+        let mut profile = Vec::with_capacity(offset);
+        profile.extend_from_slice(&(offset as u32).to_be_bytes());
+        profile.extend_from_slice(&[0; 4]); // Preferred CMM
+        profile.extend_from_slice(&0x0210_0000u32.to_be_bytes());
+        profile.extend_from_slice(b"mntrRGB XYZ ");
+        profile.extend_from_slice(&[0; 12]); // Creation date
+        profile.extend_from_slice(b"acsp");
+        profile.resize(68, 0); // Platform, flags, device and rendering intent
+        for v in D50 {
+            profile.extend_from_slice(&s15_fixed16(v).to_be_bytes());
+        }
+        profile.resize(128, 0);
+
+        profile.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+        for (signature, index) in tags {
+            profile.extend_from_slice(signature);
+            profile.extend_from_slice(&(offsets[index] as u32).to_be_bytes());
+            profile.extend_from_slice(&(data[index].len() as u32).to_be_bytes());
+        }
+        for tag in &data {
+            profile.extend_from_slice(tag);
+            profile.resize(profile.len().next_multiple_of(4), 0);
+        }
+        profile
+    }
+}
+
+/// Convert a value to ICC s15Fixed16 format.
+fn s15_fixed16(v: Scalar) -> i32 {
+    (v * 65536.0).round() as i32
 }
 
 /// ICC profile class.
@@ -1151,6 +1700,95 @@ mod tests {
         assert_eq!(premul.a, 0.5);
     }
 
+    #[test]
+    fn test_convert_identity() {
+        let c = Color4f::new(0.123_456, 0.654_321, 0.5, 0.25);
+        let srgb = ColorSpace::srgb();
+        assert_eq!(c.convert(&srgb, &srgb, false), c);
+        let p3 = ColorSpace::display_p3();
+        assert_eq!(c.convert(&p3, &p3, false), c);
+    }
+
+    #[test]
+    fn test_convert_p3_red_to_srgb() {
+        let p3_red = Color4f::new(1.0, 0.0, 0.0, 0.5);
+        let srgb = ColorSpace::srgb();
+        let p3 = ColorSpace::display_p3();
+
+        // P3 red lies outside the sRGB gamut.
+        let out = p3_red.convert(&p3, &srgb, false);
+        assert!(out.r > 1.0);
+        assert!(out.g < 0.0 && out.b < 0.0);
+        assert_eq!(out.a, 0.5);
+
+        let clamped = p3_red.convert(&p3, &srgb, true);
+        assert_eq!(clamped, Color4f::new(1.0, 0.0, 0.0, 0.5));
+
+        // sRGB red is a less saturated red in P3.
+        let srgb_red = Color4f::from_rgb(1.0, 0.0, 0.0).convert(&srgb, &p3, false);
+        assert!((srgb_red.r - 0.9175).abs() < 0.001);
+        assert!((srgb_red.g - 0.2003).abs() < 0.001);
+        assert!((srgb_red.b - 0.1386).abs() < 0.001);
+
+        let back = srgb_red.convert(&p3, &srgb, false);
+        assert!((back.r - 1.0).abs() < 0.001);
+        assert!(back.g.abs() < 0.001 && back.b.abs() < 0.001);
+    }
+
+    #[test]
+    fn test_color_space_transform_matches_convert() {
+        let spaces = [
+            ColorSpace::srgb(),
+            ColorSpace::srgb_linear(),
+            ColorSpace::display_p3(),
+            ColorSpace {
+                transfer_fn: TransferFunction::Rec2020,
+                gamut: ColorGamut::Rec2020,
+            },
+        ];
+        let colors: Vec<Color4f> = (0..10_000u32)
+            .map(|i| {
+                let channel = |k: u32| Scalar::from(((i * k) % 1013) as u16) / 1012.0;
+                Color4f::new(channel(7), channel(31), channel(113), channel(3))
+            })
+            .collect();
+
+        for from in &spaces {
+            for to in &spaces {
+                let transform = from.transform_to(to);
+                assert_eq!(transform.is_identity(), from == to);
+                for clamp in [false, true] {
+                    let mut converted = colors.clone();
+                    transform.apply_in_place(&mut converted, clamp);
+                    for (color, fast) in colors.iter().zip(&converted) {
+                        let slow = color.convert(from, to, clamp);
+                        for (a, b) in fast.as_array().into_iter().zip(slow.as_array()) {
+                            assert!((a - b).abs() < 1e-4, "{from:?} -> {to:?}: {a} vs {b}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_transfer_function_roundtrip() {
+        let fns = [
+            TransferFunction::Linear,
+            TransferFunction::Srgb,
+            TransferFunction::Rec2020,
+            TransferFunction::Pq,
+            TransferFunction::Hlg,
+        ];
+        for tf in fns {
+            for i in 0..=20 {
+                let v = i as f32 / 20.0;
+                let back = tf.from_linear(tf.to_linear(v));
+                assert!((v - back).abs() < 0.001, "{tf:?} failed for {v}");
+            }
+        }
+    }
+
     #[test]
     fn test_color_type_bytes() {
         assert_eq!(ColorType::Rgba8888.bytes_per_pixel(), 4);
@@ -1166,6 +1804,83 @@ mod tests {
         assert!(premul.red() > 90 && premul.red() < 110);
     }
 
+    #[test]
+    fn test_color_hex_string_roundtrip() {
+        let opaque = Color::from_rgb(0x12, 0xAB, 0xEF);
+        assert_eq!(opaque.to_hex_string(), "#12ABEF");
+        assert_eq!(Color::from_hex_string("#12ABEF"), Some(opaque));
+
+        let translucent = Color::from_argb(0x80, 0xFF, 0x00, 0x7F);
+        assert_eq!(translucent.to_hex_string(), "#FF007F80");
+        assert_eq!(Color::from_hex_string("#FF007F80"), Some(translucent));
+
+        for c in [
+            Color::TRANSPARENT,
+            Color::WHITE,
+            Color::from_argb(1, 2, 3, 4),
+        ] {
+            assert_eq!(Color::from_hex_string(&c.to_hex_string()), Some(c));
+        }
+        assert_eq!(Color::from_hex_string("ff007f80"), Some(translucent));
+    }
+
+    #[test]
+    fn test_color_from_hex_string_invalid() {
+        for s in [
+            "",
+            "#",
+            "#FFF",
+            "#12345",
+            "#1234567",
+            "#123456789",
+            "#12G456",
+            "#12345é",
+        ] {
+            assert_eq!(Color::from_hex_string(s), None, "{s:?}");
+        }
+    }
+
+    #[test]
+    fn test_color_premultiply_transparent() {
+        assert_eq!(
+            Color::from_argb(0, 10, 20, 30).premultiply(),
+            Color::TRANSPARENT
+        );
+        assert_eq!(
+            Color::from_argb(0, 10, 20, 30).unpremultiply(),
+            Color::TRANSPARENT
+        );
+        assert_eq!(Color::RED.premultiply(), Color::RED);
+    }
+
+    #[test]
+    fn test_color_premultiply_roundtrip() {
+        for a in 1..=255u8 {
+            for v in 0..=255u8 {
+                let c = Color::from_argb(a, v, 255 - v, v / 2);
+                let back = c.premultiply().unpremultiply();
+                assert_eq!(back.alpha(), a);
+
+                // Within one premultiplied step of the original.
+                for (orig, got) in [
+                    (c.red(), back.red()),
+                    (c.green(), back.green()),
+                    (c.blue(), back.blue()),
+                ] {
+                    let err = (i32::from(orig) - i32::from(got)).unsigned_abs();
+                    assert!(err * u32::from(a) <= 255, "a={a} orig={orig} got={got}");
+                }
+                if a == 255 {
+                    assert_eq!(back, c);
+                }
+
+                // Premultiplied colors survive the reverse trip exactly.
+                let p = c.premultiply();
+                assert_eq!(p.unpremultiply().premultiply(), p);
+            }
+        }
+    }
+
     #[test]
     fn test_color_with_alpha() {
         let c = Color::RED;
@@ -1264,4 +1979,35 @@ mod tests {
         let gray = mixed.red();
         assert!(gray > 100 && gray < 200);
     }
+
+    #[test]
+    fn test_icc_profile_to_bytes() {
+        let data = IccProfile::srgb().to_bytes().unwrap();
+        assert_eq!(
+            u32::from_be_bytes(data[0..4].try_into().unwrap()) as usize,
+            data.len()
+        );
+        assert_eq!(data.len() % 4, 0);
+
+        let parsed = IccProfile::from_bytes(&data).unwrap();
+        assert_eq!(parsed.profile_class, IccProfileClass::Display);
+        assert_eq!(parsed.color_space, IccColorSpace::Rgb);
+        assert_eq!(parsed.pcs, IccPcs::Xyz);
+        assert_eq!(parsed.to_bytes().unwrap(), data);
+
+        // The red primary, adapted to D50
+        let tags = u32::from_be_bytes(data[128..132].try_into().unwrap()) as usize;
+        let entry = (0..tags)
+            .map(|i| 132 + i * 12)
+            .find(|&e| &data[e..e + 4] == b"rXYZ")
+            .unwrap();
+        let offset = u32::from_be_bytes(data[entry + 4..entry + 8].try_into().unwrap()) as usize;
+        assert_eq!(&data[offset..offset + 4], b"XYZ ");
+        let x = i32::from_be_bytes(data[offset + 8..offset + 12].try_into().unwrap());
+        assert!((x as f32 / 65536.0 - 0.4361).abs() < 0.001);
+
+        let mut cmyk = IccProfile::srgb();
+        cmyk.color_space = IccColorSpace::Cmyk;
+        assert!(cmyk.to_bytes().is_none());
+    }
 }
@@ -2,6 +2,8 @@
 //!
 //! This module provides Skia-compatible geometry types.
 
+#[cfg(not(feature = "std"))]
+use crate::FloatMath;
 use crate::Scalar;
 use bytemuck::{Pod, Zeroable};
 
@@ -190,7 +192,7 @@ impl From<IPoint> for Point {
 }
 
 // Operator implementations for Point
-impl std::ops::Add for Point {
+impl core::ops::Add for Point {
     type Output = Self;
     #[inline]
     fn add(self, rhs: Self) -> Self::Output {
@@ -201,7 +203,7 @@ impl std::ops::Add for Point {
     }
 }
 
-impl std::ops::AddAssign for Point {
+impl core::ops::AddAssign for Point {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
         self.x += rhs.x;
@@ -209,7 +211,7 @@ impl std::ops::AddAssign for Point {
     }
 }
 
-impl std::ops::Sub for Point {
+impl core::ops::Sub for Point {
     type Output = Self;
     #[inline]
     fn sub(self, rhs: Self) -> Self::Output {
@@ -220,7 +222,7 @@ impl std::ops::Sub for Point {
     }
 }
 
-impl std::ops::SubAssign for Point {
+impl core::ops::SubAssign for Point {
     #[inline]
     fn sub_assign(&mut self, rhs: Self) {
         self.x -= rhs.x;
@@ -228,7 +230,7 @@ impl std::ops::SubAssign for Point {
     }
 }
 
-impl std::ops::Mul<Scalar> for Point {
+impl core::ops::Mul<Scalar> for Point {
     type Output = Self;
     #[inline]
     fn mul(self, rhs: Scalar) -> Self::Output {
@@ -239,7 +241,7 @@ impl std::ops::Mul<Scalar> for Point {
     }
 }
 
-impl std::ops::MulAssign<Scalar> for Point {
+impl core::ops::MulAssign<Scalar> for Point {
     #[inline]
     fn mul_assign(&mut self, rhs: Scalar) {
         self.x *= rhs;
@@ -247,7 +249,7 @@ impl std::ops::MulAssign<Scalar> for Point {
     }
 }
 
-impl std::ops::Div<Scalar> for Point {
+impl core::ops::Div<Scalar> for Point {
     type Output = Self;
     #[inline]
     fn div(self, rhs: Scalar) -> Self::Output {
@@ -258,7 +260,7 @@ impl std::ops::Div<Scalar> for Point {
     }
 }
 
-impl std::ops::Neg for Point {
+impl core::ops::Neg for Point {
     type Output = Self;
     #[inline]
     fn neg(self) -> Self::Output {
@@ -306,6 +308,37 @@ impl Point3 {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
 
+    /// Returns the squared length of the vector.
+    #[inline]
+    pub fn length_squared(&self) -> Scalar {
+        self.dot(self)
+    }
+
+    /// Returns a normalized (unit length) vector, or zero if length is zero.
+    #[inline]
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+        if len > 0.0 {
+            Self {
+                x: self.x / len,
+                y: self.y / len,
+                z: self.z / len,
+            }
+        } else {
+            Self::zero()
+        }
+    }
+
+    /// Scales the vector by a factor.
+    #[inline]
+    pub fn scale(&self, factor: Scalar) -> Self {
+        Self {
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+
     /// Dot product with another 3D point/vector.
     #[inline]
     pub fn dot(&self, other: &Self) -> Scalar {
@@ -321,6 +354,86 @@ impl Point3 {
             z: self.x * other.y - self.y * other.x,
         }
     }
+
+    /// Returns the unit normal of the triangle `(a, b, c)`.
+    ///
+    /// The normal follows the right-hand rule for counter-clockwise winding,
+    /// and is zero if the triangle is degenerate.
+    #[inline]
+    pub fn normal(a: Self, b: Self, c: Self) -> Self {
+        (b - a).cross(&(c - a)).normalize()
+    }
+}
+
+impl core::ops::Add for Point3 {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl core::ops::AddAssign for Point3 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl core::ops::Sub for Point3 {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl core::ops::SubAssign for Point3 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl core::ops::Mul<Scalar> for Point3 {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Scalar) -> Self::Output {
+        self.scale(rhs)
+    }
+}
+
+impl core::ops::MulAssign<Scalar> for Point3 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Scalar) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+    }
+}
+
+impl core::ops::Neg for Point3 {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
 }
 
 // =============================================================================
@@ -1104,6 +1217,14 @@ impl Matrix {
             && self.values[Self::PERSP_2] == 1.0
     }
 
+    /// Returns true if the matrix has a perspective component.
+    #[inline]
+    pub fn has_perspective(&self) -> bool {
+        self.values[Self::PERSP_0] != 0.0
+            || self.values[Self::PERSP_1] != 0.0
+            || self.values[Self::PERSP_2] != 1.0
+    }
+
     /// Returns the translation component.
     #[inline]
     pub fn translation(&self) -> Point {
@@ -1230,6 +1351,87 @@ impl Matrix {
             ],
         })
     }
+
+    /// Decomposes an affine matrix into translation, rotation, scale, and skew.
+    ///
+    /// Uses the QR-style decomposition from CSS matrix interpolation, so that
+    /// `Matrix::from_components(&m.decompose()?)` reproduces `m`. A negative
+    /// determinant is folded into the X scale.
+    ///
+    /// Returns `None` if the matrix has perspective or is not invertible.
+    pub fn decompose(&self) -> Option<MatrixComponents> {
+        if self.has_perspective() {
+            return None;
+        }
+
+        let m = &self.values;
+        let det = m[Self::SCALE_X] * m[Self::SCALE_Y] - m[Self::SKEW_X] * m[Self::SKEW_Y];
+        if det.abs() < 1e-10 {
+            return None;
+        }
+
+        // Columns of the linear part are the images of the X and Y basis vectors.
+        let mut col0 = Point::new(m[Self::SCALE_X], m[Self::SKEW_Y]);
+        let mut col1 = Point::new(m[Self::SKEW_X], m[Self::SCALE_Y]);
+
+        let mut scale_x = col0.length();
+        col0 *= 1.0 / scale_x;
+        if det < 0.0 {
+            scale_x = -scale_x;
+            col0 = -col0;
+        }
+
+        let mut skew = col0.dot(&col1);
+        col1 -= col0 * skew;
+
+        let scale_y = col1.length();
+        skew /= scale_y;
+
+        Some(MatrixComponents {
+            translate: self.translation(),
+            rotation: col0.y.atan2(col0.x),
+            scale: (scale_x, scale_y),
+            skew,
+        })
+    }
+
+    /// Recomposes a matrix from components produced by [`Matrix::decompose`].
+    ///
+    /// The result is `translate * rotate * skew * scale`.
+    pub fn from_components(components: &MatrixComponents) -> Self {
+        let (sin, cos) = components.rotation.sin_cos();
+        let (sx, sy) = components.scale;
+        let k = components.skew;
+        Self {
+            values: [
+                cos * sx,
+                sy * (cos * k - sin),
+                components.translate.x,
+                sin * sx,
+                sy * (sin * k + cos),
+                components.translate.y,
+                0.0,
+                0.0,
+                1.0,
+            ],
+        }
+    }
+}
+
+/// The components of an affine [`Matrix`], as produced by [`Matrix::decompose`].
+///
+/// Components can be interpolated independently and recomposed with
+/// [`Matrix::from_components`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MatrixComponents {
+    /// Translation.
+    pub translate: Point,
+    /// Rotation in radians.
+    pub rotation: Scalar,
+    /// Scale along X and Y, applied before skew and rotation.
+    pub scale: (Scalar, Scalar),
+    /// Shear factor of Y into X, applied after scale.
+    pub skew: Scalar,
 }
 
 #[cfg(test)]
@@ -1245,6 +1447,30 @@ mod tests {
         assert!((p1.dot(&p2) - 11.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_point3_cross_direction() {
+        let x = Point3::new(1.0, 0.0, 0.0);
+        let y = Point3::new(0.0, 1.0, 0.0);
+        assert_eq!(x.cross(&y), Point3::new(0.0, 0.0, 1.0));
+        assert_eq!(y.cross(&x), Point3::new(0.0, 0.0, -1.0));
+
+        let n = Point3::normal(Point3::zero(), x * 2.0, y * 3.0);
+        assert_eq!(n, Point3::new(0.0, 0.0, 1.0));
+        assert_eq!(Point3::normal(x, x, y), Point3::zero());
+    }
+
+    #[test]
+    fn test_point3_normalize() {
+        let v = Point3::new(2.0, 3.0, 6.0);
+        assert!((v.length() - 7.0).abs() < 1e-6);
+        let n = v.normalize();
+        assert!((n.length() - 1.0).abs() < 1e-6);
+        assert!((n.x - 2.0 / 7.0).abs() < 1e-6);
+        assert_eq!(Point3::zero().normalize(), Point3::zero());
+        assert_eq!(v - v, Point3::zero());
+        assert_eq!(-v + v * 2.0, v);
+    }
+
     #[test]
     fn test_rect_intersection() {
         let r1 = Rect::new(0.0, 0.0, 10.0, 10.0);
@@ -1277,4 +1503,43 @@ mod tests {
         assert!((result.values[0] - 1.0).abs() < 1e-6);
         assert!((result.values[4] - 1.0).abs() < 1e-6);
     }
+
+    fn assert_matrix_near(a: &Matrix, b: &Matrix) {
+        for (x, y) in a.values.iter().zip(b.values.iter()) {
+            assert!((x - y).abs() < 1e-4, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_matrix_decompose_trs() {
+        let m = Matrix::translate(10.0, -5.0)
+            .concat(&Matrix::rotate(0.75))
+            .concat(&Matrix::scale(2.0, 3.0));
+        let c = m.decompose().unwrap();
+        assert!((c.translate.x - 10.0).abs() < 1e-5);
+        assert!((c.translate.y + 5.0).abs() < 1e-5);
+        assert!((c.rotation - 0.75).abs() < 1e-5);
+        assert!((c.scale.0 - 2.0).abs() < 1e-5);
+        assert!((c.scale.1 - 3.0).abs() < 1e-5);
+        assert!(c.skew.abs() < 1e-5);
+        assert_matrix_near(&Matrix::from_components(&c), &m);
+    }
+
+    #[test]
+    fn test_matrix_decompose_skew_and_flip() {
+        let m = Matrix::rotate(-1.2)
+            .concat(&Matrix::skew(0.3, 0.0))
+            .concat(&Matrix::scale(-1.5, 0.5));
+        let c = m.decompose().unwrap();
+        assert!(c.scale.0 < 0.0);
+        assert_matrix_near(&Matrix::from_components(&c), &m);
+    }
+
+    #[test]
+    fn test_matrix_decompose_degenerate() {
+        assert!(Matrix::scale(0.0, 1.0).decompose().is_none());
+        let mut persp = Matrix::identity();
+        persp.values[Matrix::PERSP_0] = 0.01;
+        assert!(persp.decompose().is_none());
+    }
 }
@@ -0,0 +1,390 @@
+//! Double-precision geometry for large coordinate spaces.
+//!
+//! [`Matrix64`] and [`Rect64`] mirror [`Matrix`] and [`Rect`] with `f64`
+//! components. Compose transforms for world coordinates (map tiles, CAD
+//! drawings) in double precision, then convert to the `f32` types once the
+//! result is close to device space.
+
+#[cfg(not(feature = "std"))]
+use crate::FloatMath;
+use crate::Scalar;
+use crate::geometry::{Matrix, Rect};
+
+/// A 3x3 transformation matrix with `f64` components.
+///
+/// The layout matches [`Matrix`], so its index constants such as
+/// [`Matrix::TRANS_X`] apply to [`Matrix64::values`] as well.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix64 {
+    /// Matrix values in row-major order.
+    pub values: [f64; 9],
+}
+
+impl Default for Matrix64 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix64 {
+    /// The identity matrix constant.
+    pub const IDENTITY: Self = Self {
+        values: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+    };
+
+    /// Creates the identity matrix.
+    #[inline]
+    pub const fn identity() -> Self {
+        Self::IDENTITY
+    }
+
+    /// Creates a translation matrix.
+    #[inline]
+    pub const fn translate(dx: f64, dy: f64) -> Self {
+        Self {
+            values: [1.0, 0.0, dx, 0.0, 1.0, dy, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Creates a scale matrix.
+    #[inline]
+    pub const fn scale(sx: f64, sy: f64) -> Self {
+        Self {
+            values: [sx, 0.0, 0.0, 0.0, sy, 0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Creates a rotation matrix (angle in radians).
+    #[inline]
+    pub fn rotate(radians: f64) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            values: [cos, -sin, 0.0, sin, cos, 0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Returns true if this is the identity matrix.
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Returns true if the matrix has a perspective component.
+    #[inline]
+    pub fn has_perspective(&self) -> bool {
+        self.values[Matrix::PERSP_0..] != [0.0, 0.0, 1.0]
+    }
+
+    /// Concatenates this matrix with another (self * other).
+    #[inline]
+    pub fn concat(&self, other: &Self) -> Self {
+        let a = &self.values;
+        let b = &other.values;
+        Self {
+            values: [
+                a[0] * b[0] + a[1] * b[3] + a[2] * b[6],
+                a[0] * b[1] + a[1] * b[4] + a[2] * b[7],
+                a[0] * b[2] + a[1] * b[5] + a[2] * b[8],
+                a[3] * b[0] + a[4] * b[3] + a[5] * b[6],
+                a[3] * b[1] + a[4] * b[4] + a[5] * b[7],
+                a[3] * b[2] + a[4] * b[5] + a[5] * b[8],
+                a[6] * b[0] + a[7] * b[3] + a[8] * b[6],
+                a[6] * b[1] + a[7] * b[4] + a[8] * b[7],
+                a[6] * b[2] + a[7] * b[5] + a[8] * b[8],
+            ],
+        }
+    }
+
+    /// Transforms the point `(x, y)` by this matrix.
+    #[inline]
+    pub fn map_xy(&self, x: f64, y: f64) -> (f64, f64) {
+        let m = &self.values;
+        let mx = m[0] * x + m[1] * y + m[2];
+        let my = m[3] * x + m[4] * y + m[5];
+
+        if self.has_perspective() {
+            let w = m[6] * x + m[7] * y + m[8];
+            (mx / w, my / w)
+        } else {
+            (mx, my)
+        }
+    }
+
+    /// Transforms a rectangle by this matrix (returns bounding box of transformed corners).
+    pub fn map_rect(&self, rect: &Rect64) -> Rect64 {
+        let corners = [
+            self.map_xy(rect.left, rect.top),
+            self.map_xy(rect.right, rect.top),
+            self.map_xy(rect.right, rect.bottom),
+            self.map_xy(rect.left, rect.bottom),
+        ];
+
+        let (mut min_x, mut min_y) = corners[0];
+        let (mut max_x, mut max_y) = corners[0];
+        for &(x, y) in &corners[1..] {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+
+        Rect64::new(min_x, min_y, max_x, max_y)
+    }
+
+    /// Computes the determinant.
+    #[inline]
+    pub fn determinant(&self) -> f64 {
+        let m = &self.values;
+        m[0] * (m[4] * m[8] - m[5] * m[7]) - m[1] * (m[3] * m[8] - m[5] * m[6])
+            + m[2] * (m[3] * m[7] - m[4] * m[6])
+    }
+
+    /// Computes the inverse matrix, or None if singular.
+    pub fn invert(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let m = &self.values;
+        let inv_det = 1.0 / det;
+
+        Some(Self {
+            values: [
+                (m[4] * m[8] - m[5] * m[7]) * inv_det,
+                (m[2] * m[7] - m[1] * m[8]) * inv_det,
+                (m[1] * m[5] - m[2] * m[4]) * inv_det,
+                (m[5] * m[6] - m[3] * m[8]) * inv_det,
+                (m[0] * m[8] - m[2] * m[6]) * inv_det,
+                (m[2] * m[3] - m[0] * m[5]) * inv_det,
+                (m[3] * m[7] - m[4] * m[6]) * inv_det,
+                (m[1] * m[6] - m[0] * m[7]) * inv_det,
+                (m[0] * m[4] - m[1] * m[3]) * inv_det,
+            ],
+        })
+    }
+
+    /// Converts to a single-precision [`Matrix`], rounding each component.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_matrix(&self) -> Matrix {
+        Matrix {
+            values: self.values.map(|v| v as Scalar),
+        }
+    }
+}
+
+impl From<Matrix> for Matrix64 {
+    fn from(matrix: Matrix) -> Self {
+        Self {
+            values: matrix.values.map(f64::from),
+        }
+    }
+}
+
+impl From<Matrix64> for Matrix {
+    fn from(matrix: Matrix64) -> Self {
+        matrix.to_matrix()
+    }
+}
+
+/// A rectangle with `f64` edges.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect64 {
+    /// Left edge.
+    pub left: f64,
+    /// Top edge.
+    pub top: f64,
+    /// Right edge.
+    pub right: f64,
+    /// Bottom edge.
+    pub bottom: f64,
+}
+
+impl Rect64 {
+    /// Empty rectangle constant.
+    pub const EMPTY: Self = Self::new(0.0, 0.0, 0.0, 0.0);
+
+    /// Creates a new rectangle from edges.
+    #[inline]
+    pub const fn new(left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// Creates a rectangle from origin and size.
+    #[inline]
+    pub const fn from_xywh(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self::new(x, y, x + width, y + height)
+    }
+
+    /// Returns the width.
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.right - self.left
+    }
+
+    /// Returns the height.
+    #[inline]
+    pub fn height(&self) -> f64 {
+        self.bottom - self.top
+    }
+
+    /// Returns the center point.
+    #[inline]
+    pub fn center(&self) -> (f64, f64) {
+        (
+            (self.left + self.right) * 0.5,
+            (self.top + self.bottom) * 0.5,
+        )
+    }
+
+    /// Returns true if the rectangle has zero or negative area.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.left >= self.right || self.top >= self.bottom
+    }
+
+    /// Returns true if the point (x, y) is inside the rectangle.
+    #[inline]
+    pub fn contains_xy(&self, x: f64, y: f64) -> bool {
+        x >= self.left && x < self.right && y >= self.top && y < self.bottom
+    }
+
+    /// Returns the intersection of two rectangles, or None if they don't intersect.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let left = self.left.max(other.left);
+        let top = self.top.max(other.top);
+        let right = self.right.min(other.right);
+        let bottom = self.bottom.min(other.bottom);
+
+        if left < right && top < bottom {
+            Some(Self::new(left, top, right, bottom))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the union (bounding box) of two rectangles.
+    pub fn union(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        Self::new(
+            self.left.min(other.left),
+            self.top.min(other.top),
+            self.right.max(other.right),
+            self.bottom.max(other.bottom),
+        )
+    }
+
+    /// Offsets the rectangle by (dx, dy).
+    #[inline]
+    pub fn offset(&self, dx: f64, dy: f64) -> Self {
+        Self::new(
+            self.left + dx,
+            self.top + dy,
+            self.right + dx,
+            self.bottom + dy,
+        )
+    }
+
+    /// Converts to a single-precision [`Rect`], rounding each edge.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_rect(&self) -> Rect {
+        Rect::new(
+            self.left as Scalar,
+            self.top as Scalar,
+            self.right as Scalar,
+            self.bottom as Scalar,
+        )
+    }
+}
+
+impl From<Rect> for Rect64 {
+    fn from(rect: Rect) -> Self {
+        Self::new(
+            f64::from(rect.left),
+            f64::from(rect.top),
+            f64::from(rect.right),
+            f64::from(rect.bottom),
+        )
+    }
+}
+
+impl From<Rect64> for Rect {
+    fn from(rect: Rect64) -> Self {
+        rect.to_rect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_matrix64_conversions() {
+        let m = Matrix::translate(10.0, 20.0).concat(&Matrix::scale(2.0, 3.0));
+        let m64 = Matrix64::from(m);
+        assert_eq!(m64.map_xy(1.0, 1.0), (12.0, 23.0));
+        assert_eq!(Matrix::from(m64), m);
+
+        let inverse = m64.invert().unwrap();
+        assert!(m64.concat(&inverse).is_identity());
+        assert!(Matrix64::scale(0.0, 1.0).invert().is_none());
+
+        let rect = Rect64::from_xywh(0.0, 0.0, 4.0, 4.0);
+        let mapped = m64.map_rect(&rect);
+        assert_eq!(mapped, Rect64::new(10.0, 20.0, 18.0, 32.0));
+        assert_eq!(Rect::from(mapped), m.map_rect(&Rect::from(rect)));
+    }
+
+    #[test]
+    fn test_rect64_ops() {
+        let a = Rect64::new(0.0, 0.0, 10.0, 10.0);
+        let b = Rect64::new(5.0, 5.0, 15.0, 15.0);
+        assert_eq!(a.intersect(&b), Some(Rect64::new(5.0, 5.0, 10.0, 10.0)));
+        assert_eq!(a.union(&b), Rect64::new(0.0, 0.0, 15.0, 15.0));
+        assert!(a.intersect(&a.offset(20.0, 0.0)).is_none());
+        assert_eq!(a.center(), (5.0, 5.0));
+        assert!(a.contains_xy(0.0, 9.5) && !a.contains_xy(10.0, 5.0));
+    }
+
+    #[test]
+    fn test_matrix64_composition_drifts_less_than_f32() {
+        // Spin a full turn in 1000 steps around a pivot far from the origin.
+        // The composite should be the identity, mapping the probe to itself.
+        const STEPS: u32 = 1000;
+        let (px, py) = (1.0e6_f64, 2.0e6_f64);
+        let (probe_x, probe_y) = (px + 250.0, py - 125.0);
+
+        let step64 = Matrix64::translate(px, py)
+            .concat(&Matrix64::rotate(core::f64::consts::TAU / f64::from(STEPS)))
+            .concat(&Matrix64::translate(-px, -py));
+        let step32 = step64.to_matrix();
+
+        let mut total64 = Matrix64::IDENTITY;
+        let mut total32 = Matrix::IDENTITY;
+        for _ in 0..STEPS {
+            total64 = step64.concat(&total64);
+            total32 = step32.concat(&total32);
+        }
+
+        let (x64, y64) = total64.map_xy(probe_x, probe_y);
+        let error64 = (x64 - probe_x).hypot(y64 - probe_y);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let p32 = total32.map_point(Point::new(probe_x as f32, probe_y as f32));
+        let error32 = (f64::from(p32.x) - probe_x).hypot(f64::from(p32.y) - probe_y);
+
+        assert!(error64 < 1e-3, "f64 drift {error64}");
+        assert!(error32 > 1.0, "f32 drift {error32}");
+    }
+}
@@ -3,7 +3,7 @@
 //! Core types for the skia-rs graphics library.
 //!
 //! This crate provides fundamental types used throughout skia-rs:
-//! - **Geometry**: Points, sizes, rectangles, matrices
+//! - **Geometry**: Points, sizes, rectangles, matrices, and `f64` variants
 //! - **Color**: Color types, color spaces, alpha handling
 //! - **Pixels**: Image info, pixel storage, format conversion
 //! - **Region**: Complex clip regions composed of rectangles
@@ -18,33 +18,51 @@
 //! - [`Color`] ↔ `SkColor`
 //! - [`ColorSpace`] ↔ `SkColorSpace`
 //! - [`Region`] ↔ `SkRegion`
+//!
+//! ## `no_std`
+//!
+//! Disable the default `std` feature and enable `libm` to build with only
+//! `alloc`; float math then goes through [`FloatMath`].
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
 #![allow(clippy::module_name_repetitions)]
 
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("skia-rs-core needs either the `std` or the `libm` feature");
+
+extern crate alloc;
+
 pub mod color;
 pub mod geometry;
+pub mod geometry64;
+pub mod math;
 pub mod matrix44;
 pub mod pixel;
 pub mod region;
 
 // Re-exports for convenience
 pub use color::{
-    AlphaType, Color, Color4f, ColorFilterFlags, ColorGamut, ColorSpace, ColorType, IccColorSpace,
-    IccPcs, IccProfile, IccProfileClass, TransferFunction, color_to_linear, color4f_linear_to_srgb,
-    color4f_srgb_to_linear, contrast_ratio, hsl_to_rgb, hsv_to_rgb, lab_to_rgb, linear_to_color,
-    linear_to_srgb, luminance, mix_colors, premultiply_color, rgb_to_hsl, rgb_to_hsv, rgb_to_lab,
-    rgb_to_xyz, srgb_to_linear, unpremultiply_color, xyz_to_rgb,
+    AlphaType, Color, Color4f, ColorFilterFlags, ColorGamut, ColorSpace, ColorSpaceTransform,
+    ColorType, IccColorSpace, IccPcs, IccProfile, IccProfileClass, TransferFunction,
+    color_to_linear, color4f_linear_to_srgb, color4f_srgb_to_linear, contrast_ratio, hsl_to_rgb,
+    hsv_to_rgb, lab_to_rgb, linear_to_color, linear_to_srgb, luminance, mix_colors,
+    premultiply_color, rgb_to_hsl, rgb_to_hsv, rgb_to_lab, rgb_to_xyz, srgb_to_linear,
+    unpremultiply_color, xyz_to_rgb,
+};
+pub use geometry::{
+    Corner, IPoint, IRect, ISize, Matrix, MatrixComponents, Point, Point3, RRect, Rect, Size,
 };
-pub use geometry::{Corner, IPoint, IRect, ISize, Matrix, Point, Point3, RRect, Rect, Size};
+pub use geometry64::{Matrix64, Rect64};
+pub use math::FloatMath;
 pub use matrix44::Matrix44;
 pub use pixel::{
     Bitmap, ImageInfo, PixelError, PixelGeometry, Pixmap, SurfaceProps, SurfacePropsFlags,
     convert_pixels, premultiply_in_place, swizzle_rb_in_place, unpremultiply_in_place,
 };
-pub use region::{Region, RegionOp};
+pub use region::{Region, RegionOp, RegionTiles};
 
 /// Scalar type used for all floating-point geometry.
 ///
@@ -89,6 +107,7 @@ pub mod prelude {
     pub use crate::geometry::{
         Corner, IPoint, IRect, ISize, Matrix, Point, Point3, RRect, Rect, Size,
     };
+    pub use crate::geometry64::{Matrix64, Rect64};
     pub use crate::matrix44::Matrix44;
     pub use crate::pixel::{Bitmap, ImageInfo, PixelGeometry, Pixmap, SurfaceProps};
     pub use crate::region::{Region, RegionOp};
@@ -0,0 +1,339 @@
+//! Floating-point math for `no_std` builds.
+//!
+//! With the `std` feature the standard library's inherent `f32`/`f64`
+//! methods are used. Without it those methods don't exist, so [`FloatMath`]
+//! provides the same names backed by `libm`; modules that need them import
+//! the trait only in `no_std` builds:
+//!
+//! ```ignore
+//! #[cfg(not(feature = "std"))]
+//! use skia_rs_core::FloatMath;
+//! ```
+
+/// The `f32`/`f64` math methods that live in `std` rather than `core`.
+///
+/// Method names and semantics match the inherent methods, so code written
+/// against `std` compiles unchanged once the trait is in scope.
+pub trait FloatMath: Sized {
+    /// Largest integer less than or equal to `self`.
+    fn floor(self) -> Self;
+    /// Smallest integer greater than or equal to `self`.
+    fn ceil(self) -> Self;
+    /// Nearest integer, rounding half-way cases away from zero.
+    fn round(self) -> Self;
+    /// Nearest integer, rounding half-way cases to even.
+    fn round_ties_even(self) -> Self;
+    /// Integer part of `self`.
+    fn trunc(self) -> Self;
+    /// Fractional part of `self`.
+    fn fract(self) -> Self;
+    /// Square root.
+    fn sqrt(self) -> Self;
+    /// Cube root.
+    fn cbrt(self) -> Self;
+    /// `self` raised to an integer power.
+    fn powi(self, n: i32) -> Self;
+    /// `self` raised to a floating-point power.
+    fn powf(self, n: Self) -> Self;
+    /// `e^self`.
+    fn exp(self) -> Self;
+    /// `2^self`.
+    fn exp2(self) -> Self;
+    /// Natural logarithm.
+    fn ln(self) -> Self;
+    /// Base-2 logarithm.
+    fn log2(self) -> Self;
+    /// Base-10 logarithm.
+    fn log10(self) -> Self;
+    /// Sine (radians).
+    fn sin(self) -> Self;
+    /// Cosine (radians).
+    fn cos(self) -> Self;
+    /// Tangent (radians).
+    fn tan(self) -> Self;
+    /// Arcsine, in radians.
+    fn asin(self) -> Self;
+    /// Arccosine, in radians.
+    fn acos(self) -> Self;
+    /// Arctangent, in radians.
+    fn atan(self) -> Self;
+    /// Four-quadrant arctangent of `self` (y) and `other` (x).
+    fn atan2(self, other: Self) -> Self;
+    /// Sine and cosine at once.
+    fn sin_cos(self) -> (Self, Self);
+    /// Hyperbolic sine.
+    fn sinh(self) -> Self;
+    /// Hyperbolic cosine.
+    fn cosh(self) -> Self;
+    /// Hyperbolic tangent.
+    fn tanh(self) -> Self;
+    /// Length of the hypotenuse with legs `self` and `other`.
+    fn hypot(self, other: Self) -> Self;
+    /// `self * a + b` with a single rounding.
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    /// Least non-negative remainder of `self / rhs`.
+    fn rem_euclid(self, rhs: Self) -> Self;
+    /// Euclidean division, the quotient matching [`rem_euclid`](Self::rem_euclid).
+    fn div_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_float_math {
+    ($ty:ty) => {
+        #[cfg(not(feature = "std"))]
+        impl FloatMath for $ty {
+            #[inline]
+            fn floor(self) -> Self {
+                libm::Libm::<$ty>::floor(self)
+            }
+            #[inline]
+            fn ceil(self) -> Self {
+                libm::Libm::<$ty>::ceil(self)
+            }
+            #[inline]
+            fn round(self) -> Self {
+                libm::Libm::<$ty>::round(self)
+            }
+            #[inline]
+            fn round_ties_even(self) -> Self {
+                libm::Libm::<$ty>::rint(self)
+            }
+            #[inline]
+            fn trunc(self) -> Self {
+                libm::Libm::<$ty>::trunc(self)
+            }
+            #[inline]
+            fn fract(self) -> Self {
+                self - libm::Libm::<$ty>::trunc(self)
+            }
+            #[inline]
+            fn sqrt(self) -> Self {
+                libm::Libm::<$ty>::sqrt(self)
+            }
+            #[inline]
+            fn cbrt(self) -> Self {
+                libm::Libm::<$ty>::cbrt(self)
+            }
+            #[inline]
+            fn powi(self, n: i32) -> Self {
+                libm::Libm::<$ty>::pow(self, n as $ty)
+            }
+            #[inline]
+            fn powf(self, n: Self) -> Self {
+                libm::Libm::<$ty>::pow(self, n)
+            }
+            #[inline]
+            fn exp(self) -> Self {
+                libm::Libm::<$ty>::exp(self)
+            }
+            #[inline]
+            fn exp2(self) -> Self {
+                libm::Libm::<$ty>::exp2(self)
+            }
+            #[inline]
+            fn ln(self) -> Self {
+                libm::Libm::<$ty>::log(self)
+            }
+            #[inline]
+            fn log2(self) -> Self {
+                libm::Libm::<$ty>::log2(self)
+            }
+            #[inline]
+            fn log10(self) -> Self {
+                libm::Libm::<$ty>::log10(self)
+            }
+            #[inline]
+            fn sin(self) -> Self {
+                libm::Libm::<$ty>::sin(self)
+            }
+            #[inline]
+            fn cos(self) -> Self {
+                libm::Libm::<$ty>::cos(self)
+            }
+            #[inline]
+            fn tan(self) -> Self {
+                libm::Libm::<$ty>::tan(self)
+            }
+            #[inline]
+            fn asin(self) -> Self {
+                libm::Libm::<$ty>::asin(self)
+            }
+            #[inline]
+            fn acos(self) -> Self {
+                libm::Libm::<$ty>::acos(self)
+            }
+            #[inline]
+            fn atan(self) -> Self {
+                libm::Libm::<$ty>::atan(self)
+            }
+            #[inline]
+            fn atan2(self, other: Self) -> Self {
+                libm::Libm::<$ty>::atan2(self, other)
+            }
+            #[inline]
+            fn sin_cos(self) -> (Self, Self) {
+                libm::Libm::<$ty>::sincos(self)
+            }
+            #[inline]
+            fn sinh(self) -> Self {
+                libm::Libm::<$ty>::sinh(self)
+            }
+            #[inline]
+            fn cosh(self) -> Self {
+                libm::Libm::<$ty>::cosh(self)
+            }
+            #[inline]
+            fn tanh(self) -> Self {
+                libm::Libm::<$ty>::tanh(self)
+            }
+            #[inline]
+            fn hypot(self, other: Self) -> Self {
+                libm::Libm::<$ty>::hypot(self, other)
+            }
+            #[inline]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                libm::Libm::<$ty>::fma(self, a, b)
+            }
+            #[inline]
+            fn rem_euclid(self, rhs: Self) -> Self {
+                let r = self % rhs;
+                if r < 0.0 { r + rhs.abs() } else { r }
+            }
+            #[inline]
+            fn div_euclid(self, rhs: Self) -> Self {
+                let q = libm::Libm::<$ty>::trunc(self / rhs);
+                if self % rhs < 0.0 {
+                    if rhs > 0.0 { q - 1.0 } else { q + 1.0 }
+                } else {
+                    q
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl FloatMath for $ty {
+            #[inline]
+            fn floor(self) -> Self {
+                <$ty>::floor(self)
+            }
+            #[inline]
+            fn ceil(self) -> Self {
+                <$ty>::ceil(self)
+            }
+            #[inline]
+            fn round(self) -> Self {
+                <$ty>::round(self)
+            }
+            #[inline]
+            fn round_ties_even(self) -> Self {
+                <$ty>::round_ties_even(self)
+            }
+            #[inline]
+            fn trunc(self) -> Self {
+                <$ty>::trunc(self)
+            }
+            #[inline]
+            fn fract(self) -> Self {
+                <$ty>::fract(self)
+            }
+            #[inline]
+            fn sqrt(self) -> Self {
+                <$ty>::sqrt(self)
+            }
+            #[inline]
+            fn cbrt(self) -> Self {
+                <$ty>::cbrt(self)
+            }
+            #[inline]
+            fn powi(self, n: i32) -> Self {
+                <$ty>::powi(self, n)
+            }
+            #[inline]
+            fn powf(self, n: Self) -> Self {
+                <$ty>::powf(self, n)
+            }
+            #[inline]
+            fn exp(self) -> Self {
+                <$ty>::exp(self)
+            }
+            #[inline]
+            fn exp2(self) -> Self {
+                <$ty>::exp2(self)
+            }
+            #[inline]
+            fn ln(self) -> Self {
+                <$ty>::ln(self)
+            }
+            #[inline]
+            fn log2(self) -> Self {
+                <$ty>::log2(self)
+            }
+            #[inline]
+            fn log10(self) -> Self {
+                <$ty>::log10(self)
+            }
+            #[inline]
+            fn sin(self) -> Self {
+                <$ty>::sin(self)
+            }
+            #[inline]
+            fn cos(self) -> Self {
+                <$ty>::cos(self)
+            }
+            #[inline]
+            fn tan(self) -> Self {
+                <$ty>::tan(self)
+            }
+            #[inline]
+            fn asin(self) -> Self {
+                <$ty>::asin(self)
+            }
+            #[inline]
+            fn acos(self) -> Self {
+                <$ty>::acos(self)
+            }
+            #[inline]
+            fn atan(self) -> Self {
+                <$ty>::atan(self)
+            }
+            #[inline]
+            fn atan2(self, other: Self) -> Self {
+                <$ty>::atan2(self, other)
+            }
+            #[inline]
+            fn sin_cos(self) -> (Self, Self) {
+                <$ty>::sin_cos(self)
+            }
+            #[inline]
+            fn sinh(self) -> Self {
+                <$ty>::sinh(self)
+            }
+            #[inline]
+            fn cosh(self) -> Self {
+                <$ty>::cosh(self)
+            }
+            #[inline]
+            fn tanh(self) -> Self {
+                <$ty>::tanh(self)
+            }
+            #[inline]
+            fn hypot(self, other: Self) -> Self {
+                <$ty>::hypot(self, other)
+            }
+            #[inline]
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                <$ty>::mul_add(self, a, b)
+            }
+            #[inline]
+            fn rem_euclid(self, rhs: Self) -> Self {
+                <$ty>::rem_euclid(self, rhs)
+            }
+            #[inline]
+            fn div_euclid(self, rhs: Self) -> Self {
+                <$ty>::div_euclid(self, rhs)
+            }
+        }
+    };
+}
+
+impl_float_math!(f32);
+impl_float_math!(f64);
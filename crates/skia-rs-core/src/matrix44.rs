@@ -3,6 +3,8 @@
 //! This module provides a 4x4 matrix type for 3D transformations,
 //! corresponding to Skia's `SkM44` / `SkMatrix44`.
 
+#[cfg(not(feature = "std"))]
+use crate::FloatMath;
 use crate::Scalar;
 use crate::geometry::{Matrix, Point, Point3};
 
@@ -514,7 +516,7 @@ impl Matrix44 {
     }
 }
 
-impl std::ops::Mul for Matrix44 {
+impl core::ops::Mul for Matrix44 {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
@@ -522,7 +524,7 @@ impl std::ops::Mul for Matrix44 {
     }
 }
 
-impl std::ops::MulAssign for Matrix44 {
+impl core::ops::MulAssign for Matrix44 {
     fn mul_assign(&mut self, rhs: Self) {
         *self = self.concat(&rhs);
     }
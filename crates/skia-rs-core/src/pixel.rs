@@ -1,7 +1,10 @@
 //! Pixel formats and image storage.
 
+#[cfg(not(feature = "std"))]
+use crate::FloatMath;
 use crate::color::{AlphaType, ColorSpace, ColorType};
 use crate::geometry::{IRect, ISize};
+use alloc::{vec, vec::Vec};
 use bitflags::bitflags;
 use thiserror::Error;
 
@@ -356,6 +359,34 @@ impl<'a> Pixmap<'a> {
         let offset = y as usize * self.row_bytes + x as usize * bpp;
         Some(&self.pixels[offset..offset + bpp])
     }
+
+    /// Copies a rectangle of pixels into `dst`, converting the format.
+    ///
+    /// The rectangle has the dimensions of `dst_info` and its top-left corner
+    /// at (`src_x`, `src_y`) in this pixmap. It is clipped to the pixmap
+    /// bounds; only the overlapping pixels are written, at the matching
+    /// offset in `dst`. Color types are converted with the same rules as
+    /// [`convert_pixels`], and premultiplied and unpremultiplied 32-bit
+    /// formats are converted between each other.
+    ///
+    /// Returns `false`, leaving `dst` untouched, if the rectangle does not
+    /// overlap the pixmap, `dst` is too small for `dst_row_bytes`, or the
+    /// conversion is not supported.
+    pub fn read_pixels(
+        &self,
+        dst_info: &ImageInfo,
+        dst: &mut [u8],
+        dst_row_bytes: usize,
+        src_x: i32,
+        src_y: i32,
+    ) -> bool {
+        transfer_pixels(
+            (&self.info, self.pixels, self.row_bytes),
+            (dst_info, dst, dst_row_bytes),
+            src_x,
+            src_y,
+        )
+    }
 }
 
 // =============================================================================
@@ -486,6 +517,30 @@ impl Bitmap {
         }
     }
 
+    /// Copies pixels from `src` into this bitmap, converting the format.
+    ///
+    /// The source has the dimensions of `src_info` and is placed with its
+    /// top-left corner at (`dst_x`, `dst_y`). This is the inverse of
+    /// [`Pixmap::read_pixels`] and follows the same clipping and
+    /// conversion rules.
+    pub fn write_pixels(
+        &mut self,
+        src_info: &ImageInfo,
+        src: &[u8],
+        src_row_bytes: usize,
+        dst_x: i32,
+        dst_y: i32,
+    ) -> bool {
+        transfer_pixels(
+            (src_info, src, src_row_bytes),
+            (&self.info, &mut self.pixels, self.row_bytes),
+            // `i32::MIN` saturates to a source offset that is still fully
+            // outside, so nothing is written.
+            dst_x.saturating_neg(),
+            dst_y.saturating_neg(),
+        )
+    }
+
     /// Fills the bitmap with zeros.
     #[inline]
     pub fn erase(&mut self) {
@@ -767,6 +822,76 @@ fn convert_row(
     Ok(())
 }
 
+/// Copies the `dst`-sized window at (`src_x`, `src_y`) of `src` into `dst`.
+///
+/// Shared by [`Pixmap::read_pixels`] and [`Bitmap::write_pixels`].
+fn transfer_pixels(
+    (src_info, src, src_row_bytes): (&ImageInfo, &[u8], usize),
+    (dst_info, dst, dst_row_bytes): (&ImageInfo, &mut [u8], usize),
+    src_x: i32,
+    src_y: i32,
+) -> bool {
+    if src_info.alpha_type == AlphaType::Unknown || dst_info.alpha_type == AlphaType::Unknown {
+        return false;
+    }
+    if dst_info.validate_row_bytes(dst_row_bytes).is_err()
+        || dst.len() < dst_info.compute_byte_size(dst_row_bytes)
+        || src_info.validate_row_bytes(src_row_bytes).is_err()
+        || src.len() < src_info.compute_byte_size(src_row_bytes)
+    {
+        return false;
+    }
+
+    // Clip the window against the source bounds.
+    let left = src_x.max(0);
+    let top = src_y.max(0);
+    let right = src_x.saturating_add(dst_info.width()).min(src_info.width());
+    let bottom = src_y
+        .saturating_add(dst_info.height())
+        .min(src_info.height());
+    if left >= right || top >= bottom {
+        return false;
+    }
+
+    // Premultiplication only changes 32-bit formats that carry alpha.
+    let has_alpha = |ct: ColorType| matches!(ct, ColorType::Rgba8888 | ColorType::Bgra8888);
+    let alpha_op: Option<fn(&mut [u8])> = match (src_info.alpha_type, dst_info.alpha_type) {
+        (AlphaType::Premul, AlphaType::Unpremul) => Some(unpremultiply_in_place),
+        (AlphaType::Unpremul, AlphaType::Premul) => Some(premultiply_in_place),
+        _ => None,
+    };
+    if alpha_op.is_some() && !(has_alpha(src_info.color_type) && has_alpha(dst_info.color_type)) {
+        return false;
+    }
+
+    let width = (right - left) as usize;
+    let src_bpp = src_info.bytes_per_pixel();
+    let dst_bpp = dst_info.bytes_per_pixel();
+    for y in top..bottom {
+        let src_start = y as usize * src_row_bytes + left as usize * src_bpp;
+        let dst_start = (y - src_y) as usize * dst_row_bytes + (left - src_x) as usize * dst_bpp;
+        let dst_row = &mut dst[dst_start..dst_start + width * dst_bpp];
+
+        // An unsupported pair fails on the first row, before anything is written.
+        if convert_row(
+            &src[src_start..],
+            src_info.color_type,
+            dst_row,
+            dst_info.color_type,
+            width,
+        )
+        .is_err()
+        {
+            return false;
+        }
+        if let Some(op) = alpha_op {
+            op(dst_row);
+        }
+    }
+
+    true
+}
+
 /// Swizzle RGBA to BGRA (or vice versa) in place.
 ///
 /// This is a fast path for the common case of converting between
@@ -881,4 +1006,77 @@ mod tests {
         // Should be close to original (with some precision loss)
         assert!(pixels[0] > 190 && pixels[0] < 210);
     }
+
+    #[test]
+    fn test_read_pixels_bgra_to_rgba_cropped() {
+        // 4x3 BGRA source where each pixel encodes its coordinates.
+        let src_info = ImageInfo::new_bgra8888(4, 3, AlphaType::Premul).unwrap();
+        let mut src = vec![0u8; 48];
+        for y in 0..3u8 {
+            for x in 0..4u8 {
+                let i = (usize::from(y) * 4 + usize::from(x)) * 4;
+                src[i..i + 4].copy_from_slice(&[x, y, 200, 255]); // B, G, R, A
+            }
+        }
+        let pixmap = Pixmap::new(src_info, &src, 16).unwrap();
+
+        let dst_info = ImageInfo::new_rgba8888(2, 2, AlphaType::Premul).unwrap();
+        let mut dst = [0u8; 16];
+        assert!(pixmap.read_pixels(&dst_info, &mut dst, 8, 1, 1));
+
+        assert_eq!(&dst[0..4], &[200, 1, 1, 255]); // source (1, 1)
+        assert_eq!(&dst[4..8], &[200, 1, 2, 255]); // source (2, 1)
+        assert_eq!(&dst[8..12], &[200, 2, 1, 255]); // source (1, 2)
+        assert_eq!(&dst[12..16], &[200, 2, 2, 255]); // source (2, 2)
+    }
+
+    #[test]
+    fn test_read_pixels_clips_to_source() {
+        let src_info = ImageInfo::new_bgra8888(2, 2, AlphaType::Premul).unwrap();
+        let src = [10u8, 20, 30, 255].repeat(4);
+        let pixmap = Pixmap::new(src_info, &src, 8).unwrap();
+
+        // Window hangs off the bottom-right; only (1, 1) overlaps.
+        let dst_info = ImageInfo::new_rgba8888(2, 2, AlphaType::Premul).unwrap();
+        let mut dst = [0u8; 16];
+        assert!(pixmap.read_pixels(&dst_info, &mut dst, 8, 1, 1));
+        assert_eq!(&dst[0..4], &[30, 20, 10, 255]);
+        assert_eq!(&dst[4..16], &[0u8; 12]);
+
+        // No overlap, short buffer, and unsupported conversions all fail.
+        assert!(!pixmap.read_pixels(&dst_info, &mut dst, 8, 2, 0));
+        assert!(!pixmap.read_pixels(&dst_info, &mut dst[..8], 8, 0, 0));
+        let f32_info = dst_info.with_color_type(ColorType::RgbaF32);
+        assert!(!pixmap.read_pixels(&f32_info, &mut [0u8; 64], 32, 0, 0));
+    }
+
+    #[test]
+    fn test_read_pixels_unpremultiplies() {
+        let src_info = ImageInfo::new_rgba8888(1, 1, AlphaType::Premul).unwrap();
+        let src = [64u8, 32, 0, 128];
+        let pixmap = Pixmap::new(src_info, &src, 4).unwrap();
+
+        let dst_info = ImageInfo::new_bgra8888(1, 1, AlphaType::Unpremul).unwrap();
+        let mut dst = [0u8; 4];
+        assert!(pixmap.read_pixels(&dst_info, &mut dst, 4, 0, 0));
+        assert_eq!(dst, [0, 63, 127, 128]);
+    }
+
+    #[test]
+    fn test_write_pixels_offset() {
+        let info = ImageInfo::new_rgba8888(3, 3, AlphaType::Premul).unwrap();
+        let mut bitmap = Bitmap::allocate(info).unwrap();
+
+        let src_info = ImageInfo::new_bgra8888(2, 1, AlphaType::Premul).unwrap();
+        let src = [1u8, 2, 3, 255, 4, 5, 6, 255];
+        assert!(bitmap.write_pixels(&src_info, &src, 8, 2, 1));
+
+        // Only the first source pixel lands inside the bitmap, at (2, 1).
+        let pixmap = bitmap.as_pixmap();
+        assert_eq!(pixmap.pixel_addr(2, 1).unwrap(), &[3, 2, 1, 255]);
+        assert_eq!(pixmap.pixel_addr(1, 1).unwrap(), &[0, 0, 0, 0]);
+        assert!(!bitmap.write_pixels(&src_info, &src, 8, 3, 0));
+        assert!(!bitmap.write_pixels(&src_info, &src, 8, i32::MIN, 0));
+        assert!(!bitmap.write_pixels(&src_info, &src, 8, 0, i32::MIN));
+    }
 }
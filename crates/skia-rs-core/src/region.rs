@@ -4,6 +4,7 @@
 //! complex clip areas composed of multiple rectangles.
 
 use crate::geometry::{IRect, Rect};
+use alloc::{vec, vec::Vec};
 
 /// Operation type for combining regions.
 ///
@@ -96,6 +97,10 @@ impl Region {
     }
 
     /// Returns an iterator over the rectangles in the region.
+    ///
+    /// Rectangles are yielded in scan order (top to bottom, then left to
+    /// right) and never overlap. Results of [`Region::op_region`] are
+    /// coalesced, so these are the region's rectangles in y-x band order.
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = IRect> + '_ {
         self.rects.iter().copied()
@@ -107,6 +112,45 @@ impl Region {
         &self.rects
     }
 
+    /// Returns an iterator over the region cut into `tile_width` x
+    /// `tile_height` tiles.
+    ///
+    /// Tiles form a grid anchored at the origin. Each yielded rectangle is
+    /// the intersection of one tile with one rectangle of the region; tiles
+    /// are visited row by row, left to right, and the pieces within a tile
+    /// follow [`Region::iter`] order. The pieces never overlap and together
+    /// cover exactly the region. Iteration does not allocate.
+    ///
+    /// Yields nothing if either tile dimension is not positive.
+    pub fn tiles(&self, tile_width: i32, tile_height: i32) -> RegionTiles<'_> {
+        let valid = tile_width > 0 && tile_height > 0 && !self.is_empty();
+        let (first_col, last_col, first_row, last_row) = if valid {
+            let b = self.bounds;
+            (
+                b.left.div_euclid(tile_width),
+                (b.right - 1).div_euclid(tile_width),
+                b.top.div_euclid(tile_height),
+                (b.bottom - 1).div_euclid(tile_height),
+            )
+        } else {
+            (0, -1, 0, -1)
+        };
+        let mut tiles = RegionTiles {
+            rects: &self.rects,
+            tile_width,
+            tile_height,
+            first_col,
+            last_col,
+            last_row,
+            col: first_col,
+            row: first_row,
+            row_start: 0,
+            index: 0,
+        };
+        tiles.start_row();
+        tiles
+    }
+
     /// Clear the region to empty.
     pub fn set_empty(&mut self) {
         self.rects.clear();
@@ -168,18 +212,9 @@ impl Region {
             return true;
         }
 
-        // For complex regions, we'd need a more sophisticated algorithm
-        // This is a simplified check that may have false negatives
-        for r in &self.rects {
-            if r.left <= rect.left
-                && r.top <= rect.top
-                && r.right >= rect.right
-                && r.bottom >= rect.bottom
-            {
-                return true;
-            }
-        }
-        false
+        // The rectangle may span several bands, so check that nothing is left
+        // once the region is subtracted from it.
+        combine(&Region::from_rect(*rect), self, |a, b| a && !b).is_empty()
     }
 
     /// Returns true if this region intersects with a rectangle.
@@ -232,113 +267,48 @@ impl Region {
     }
 
     /// Combine this region with a rectangle using the specified operation.
+    ///
+    /// The result is stored as a minimal set of non-overlapping rectangles.
     pub fn op_rect(&mut self, rect: IRect, op: RegionOp) -> bool {
         let other = Region::from_rect(rect);
         self.op_region(&other, op)
     }
 
     /// Combine this region with another region using the specified operation.
+    ///
+    /// The result is stored as a minimal set of non-overlapping rectangles.
     pub fn op_region(&mut self, other: &Region, op: RegionOp) -> bool {
-        match op {
-            RegionOp::Replace => self.set_region(other),
-            RegionOp::Intersect => self.intersect(other),
-            RegionOp::Union => self.union(other),
-            RegionOp::Xor => self.xor(other),
-            RegionOp::Difference => self.difference(other),
-            RegionOp::ReverseDifference => {
-                let mut temp = other.clone();
-                temp.difference(self);
-                *self = temp;
-                !self.is_empty()
-            }
-        }
-    }
-
-    /// Intersect this region with another.
-    fn intersect(&mut self, other: &Region) -> bool {
-        if self.is_empty() || other.is_empty() {
-            self.set_empty();
-            return false;
-        }
-
-        // Quick bounds check
-        if self.bounds.intersect(&other.bounds).is_none() {
-            self.set_empty();
-            return false;
-        }
-
-        let mut result_rects = Vec::new();
-        for r1 in &self.rects {
-            for r2 in &other.rects {
-                if let Some(intersection) = r1.intersect(r2) {
-                    result_rects.push(intersection);
-                }
-            }
-        }
-
-        self.rects = result_rects;
-        self.recompute_bounds();
-        !self.is_empty()
-    }
-
-    /// Union this region with another.
-    fn union(&mut self, other: &Region) -> bool {
-        if other.is_empty() {
-            return !self.is_empty();
-        }
-        if self.is_empty() {
-            return self.set_region(other);
-        }
-
-        // Simple implementation: just combine all rectangles
-        // A proper implementation would merge overlapping rectangles
-        self.rects.extend(other.rects.iter().cloned());
-        self.bounds = self.bounds.union(&other.bounds);
-        true
-    }
-
-    /// XOR this region with another.
-    fn xor(&mut self, other: &Region) -> bool {
-        // XOR = (A - B) + (B - A)
-        let mut a_minus_b = self.clone();
-        a_minus_b.difference(other);
-
-        let mut b_minus_a = other.clone();
-        b_minus_a.difference(self);
-
-        a_minus_b.union(&b_minus_a);
-        *self = a_minus_b;
+        let keep: fn(bool, bool) -> bool = match op {
+            RegionOp::Replace => return self.set_region(other),
+            RegionOp::Difference => |a, b| a && !b,
+            RegionOp::Intersect => |a, b| a && b,
+            RegionOp::Union => |a, b| a || b,
+            RegionOp::Xor => |a, b| a != b,
+            RegionOp::ReverseDifference => |a, b| !a && b,
+        };
+        *self = combine(self, other, keep);
         !self.is_empty()
     }
 
-    /// Subtract another region from this one.
-    fn difference(&mut self, other: &Region) -> bool {
-        if self.is_empty() || other.is_empty() {
-            return !self.is_empty();
-        }
-
-        // Quick bounds check
-        if self.bounds.intersect(&other.bounds).is_none() {
-            return !self.is_empty();
-        }
-
-        // For each rectangle in self, subtract all rectangles from other
-        let mut result_rects = Vec::new();
-        for rect in &self.rects {
-            let mut fragments = vec![*rect];
-            for other_rect in &other.rects {
-                let mut new_fragments = Vec::new();
-                for frag in fragments {
-                    new_fragments.extend(subtract_rect(&frag, other_rect));
-                }
-                fragments = new_fragments;
+    /// Returns the x spans covered by this region within the band `[top, bottom)`.
+    ///
+    /// The band must not straddle any rectangle edge.
+    fn spans_in_band(&self, top: i32, bottom: i32) -> Vec<(i32, i32)> {
+        let mut spans: Vec<(i32, i32)> = self
+            .rects
+            .iter()
+            .filter(|r| r.top <= top && r.bottom >= bottom)
+            .map(|r| (r.left, r.right))
+            .collect();
+        spans.sort_unstable();
+        let mut merged: Vec<(i32, i32)> = Vec::with_capacity(spans.len());
+        for (left, right) in spans {
+            match merged.last_mut() {
+                Some(last) if left <= last.1 => last.1 = last.1.max(right),
+                _ => merged.push((left, right)),
             }
-            result_rects.extend(fragments);
         }
-
-        self.rects = result_rects;
-        self.recompute_bounds();
-        !self.is_empty()
+        merged
     }
 
     /// Recompute the bounds from the rectangles.
@@ -355,51 +325,150 @@ impl Region {
     }
 }
 
-/// Subtract rect2 from rect1, returning the resulting fragments.
-fn subtract_rect(rect1: &IRect, rect2: &IRect) -> Vec<IRect> {
-    if rect1.intersect(rect2).is_none() {
-        return vec![*rect1];
-    }
+/// A horizontal band `(top, bottom, spans)` of a region under construction.
+type Band = (i32, i32, Vec<(i32, i32)>);
 
-    let mut result = Vec::new();
+/// Combines two regions band by band, keeping the areas where `keep` holds.
+///
+/// The output is in scanline order: rectangles are grouped into horizontal
+/// bands sorted top to bottom, spans within a band are sorted left to right
+/// and never touch, and vertically adjacent bands with identical spans are
+/// coalesced.
+fn combine(a: &Region, b: &Region, keep: fn(bool, bool) -> bool) -> Region {
+    let mut ys: Vec<i32> = a
+        .rects
+        .iter()
+        .chain(&b.rects)
+        .flat_map(|r| [r.top, r.bottom])
+        .collect();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let mut bands: Vec<Band> = Vec::new();
+    for band in ys.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+        let spans = combine_spans(
+            &a.spans_in_band(top, bottom),
+            &b.spans_in_band(top, bottom),
+            keep,
+        );
+        if spans.is_empty() {
+            continue;
+        }
+        match bands.last_mut() {
+            Some(last) if last.1 == top && last.2 == spans => last.1 = bottom,
+            _ => bands.push((top, bottom, spans)),
+        }
+    }
+
+    let mut result = Region {
+        rects: bands
+            .iter()
+            .flat_map(|(top, bottom, spans)| {
+                spans
+                    .iter()
+                    .map(move |&(left, right)| IRect::new(left, *top, right, *bottom))
+            })
+            .collect(),
+        bounds: IRect::empty(),
+    };
+    result.recompute_bounds();
+    result
+}
+
+/// Combines two sorted, disjoint span lists, keeping the parts where `keep` holds.
+fn combine_spans(
+    a: &[(i32, i32)],
+    b: &[(i32, i32)],
+    keep: fn(bool, bool) -> bool,
+) -> Vec<(i32, i32)> {
+    let mut xs: Vec<i32> = a.iter().chain(b).flat_map(|&(l, r)| [l, r]).collect();
+    xs.sort_unstable();
+    xs.dedup();
+
+    let covers = |spans: &[(i32, i32)], x: i32| spans.iter().any(|&(l, r)| l <= x && x < r);
 
-    // Top fragment
-    if rect1.top < rect2.top {
-        let top = IRect::new(rect1.left, rect1.top, rect1.right, rect2.top);
-        if !top.is_empty() {
-            result.push(top);
+    let mut result: Vec<(i32, i32)> = Vec::new();
+    for seg in xs.windows(2) {
+        let (left, right) = (seg[0], seg[1]);
+        if !keep(covers(a, left), covers(b, left)) {
+            continue;
+        }
+        match result.last_mut() {
+            Some(last) if last.1 == left => last.1 = right,
+            _ => result.push((left, right)),
         }
     }
+    result
+}
 
-    // Bottom fragment
-    if rect1.bottom > rect2.bottom {
-        let bottom = IRect::new(rect1.left, rect2.bottom, rect1.right, rect1.bottom);
-        if !bottom.is_empty() {
-            result.push(bottom);
-        }
+/// Iterator over a region cut into tiles, created by [`Region::tiles`].
+#[derive(Debug, Clone)]
+pub struct RegionTiles<'a> {
+    rects: &'a [IRect],
+    tile_width: i32,
+    tile_height: i32,
+    first_col: i32,
+    last_col: i32,
+    last_row: i32,
+    col: i32,
+    row: i32,
+    /// First rectangle that can reach the current tile row.
+    row_start: usize,
+    /// Next rectangle to test against the current tile.
+    index: usize,
+}
+
+impl RegionTiles<'_> {
+    /// The tile at the current grid position.
+    fn tile(&self) -> IRect {
+        IRect::from_xywh(
+            self.col * self.tile_width,
+            self.row * self.tile_height,
+            self.tile_width,
+            self.tile_height,
+        )
+    }
+
+    /// Skip rectangles that end above the current tile row.
+    ///
+    /// Rectangles are stored in scan order with non-decreasing bottoms, so
+    /// those are always a prefix.
+    fn start_row(&mut self) {
+        let top = self.row * self.tile_height;
+        self.row_start = self.rects.partition_point(|r| r.bottom <= top);
+        self.index = self.row_start;
     }
+}
+
+impl Iterator for RegionTiles<'_> {
+    type Item = IRect;
 
-    // Left fragment (in the middle band)
-    let middle_top = rect1.top.max(rect2.top);
-    let middle_bottom = rect1.bottom.min(rect2.bottom);
-    if middle_top < middle_bottom {
-        if rect1.left < rect2.left {
-            let left = IRect::new(rect1.left, middle_top, rect2.left, middle_bottom);
-            if !left.is_empty() {
-                result.push(left);
+    fn next(&mut self) -> Option<IRect> {
+        while self.row <= self.last_row {
+            let tile = self.tile();
+            while let Some(rect) = self.rects.get(self.index) {
+                if rect.top >= tile.bottom {
+                    break;
+                }
+                self.index += 1;
+                if let Some(piece) = rect.intersect(&tile) {
+                    return Some(piece);
+                }
             }
-        }
 
-        // Right fragment (in the middle band)
-        if rect1.right > rect2.right {
-            let right = IRect::new(rect2.right, middle_top, rect1.right, middle_bottom);
-            if !right.is_empty() {
-                result.push(right);
+            // Advance to the next tile, then the next row.
+            if self.col < self.last_col {
+                self.col += 1;
+                self.index = self.row_start;
+            } else {
+                self.col = self.first_col;
+                self.row += 1;
+                self.start_row();
             }
         }
+        None
     }
-
-    result
 }
 
 /// Iterator over the rectangles in a region.
@@ -453,6 +522,83 @@ mod tests {
         assert!(!region.is_complex());
     }
 
+    #[test]
+    fn test_iter_counts() {
+        let region = Region::new();
+        assert_eq!(region.iter().count(), 0);
+        assert_eq!(region.rect_count(), 0);
+
+        let rect = IRect::new(10, 20, 100, 200);
+        let region = Region::from_rect(rect);
+        assert_eq!(region.iter().collect::<Vec<_>>(), vec![rect]);
+        assert_eq!(region.rect_count(), 1);
+    }
+
+    #[test]
+    fn test_iter_scan_order() {
+        let mut region = Region::from_rect(IRect::new(0, 0, 30, 30));
+        region.op_rect(IRect::new(10, 10, 20, 20), RegionOp::Difference);
+        region.op_rect(IRect::new(0, 0, 30, 30), RegionOp::Intersect);
+        let rects: Vec<IRect> = region.iter().collect();
+        assert_eq!(
+            rects,
+            vec![
+                IRect::new(0, 0, 30, 10),
+                IRect::new(0, 10, 10, 20),
+                IRect::new(20, 10, 30, 20),
+                IRect::new(0, 20, 30, 30),
+            ]
+        );
+        assert_eq!(region.rect_count(), rects.len());
+    }
+
+    #[test]
+    fn test_tiles_cover_region() {
+        let mut region = Region::from_rect(IRect::new(10, 5, 70, 40));
+        region.op_rect(IRect::new(50, 30, 100, 90), RegionOp::Union);
+
+        let tiles: Vec<IRect> = region.tiles(32, 32).collect();
+
+        // Every piece lies in a single tile and inside the region.
+        for piece in &tiles {
+            assert!(!piece.is_empty());
+            assert_eq!(piece.left.div_euclid(32), (piece.right - 1).div_euclid(32));
+            assert_eq!(piece.top.div_euclid(32), (piece.bottom - 1).div_euclid(32));
+            assert!(region.contains_rect(piece), "{piece:?} outside region");
+        }
+
+        // Pieces are disjoint and their union is the region.
+        let mut rebuilt = Region::new();
+        let mut area = 0;
+        for piece in &tiles {
+            area += piece.width() * piece.height();
+            rebuilt.op_rect(*piece, RegionOp::Union);
+        }
+        let region_area: i32 = region.iter().map(|r| r.width() * r.height()).sum();
+        assert_eq!(area, region_area);
+        assert_eq!(rebuilt, region);
+
+        // Tiles are visited row-major.
+        let keys: Vec<(i32, i32)> = tiles
+            .iter()
+            .map(|r| (r.top.div_euclid(32), r.left.div_euclid(32)))
+            .collect();
+        assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(tiles.first(), Some(&IRect::new(10, 5, 32, 30)));
+    }
+
+    #[test]
+    fn test_tiles_negative_origin_and_degenerate() {
+        let region = Region::from_rect(IRect::new(-5, -5, 5, 5));
+        let tiles: Vec<IRect> = region.tiles(4, 8).collect();
+        assert_eq!(tiles.len(), 8);
+        assert_eq!(tiles[0], IRect::new(-5, -5, -4, 0));
+        assert_eq!(tiles[7], IRect::new(4, 0, 5, 5));
+
+        assert_eq!(region.tiles(0, 8).count(), 0);
+        assert_eq!(Region::new().tiles(16, 16).count(), 0);
+    }
+
     #[test]
     fn test_rect_region() {
         let rect = IRect::new(10, 20, 100, 200);
@@ -503,4 +649,64 @@ mod tests {
         // Should have 4 fragments around the hole
         assert_eq!(region.rect_count(), 4);
     }
+
+    #[test]
+    fn test_difference_l_shape() {
+        let mut region = Region::from_rect(IRect::new(0, 0, 100, 100));
+        region.op_rect(IRect::new(50, 50, 100, 100), RegionOp::Difference);
+        assert_eq!(region.rect_count(), 2);
+        assert_eq!(
+            region.rects(),
+            &[IRect::new(0, 0, 100, 50), IRect::new(0, 50, 50, 100)]
+        );
+        assert!(region.contains(75, 25));
+        assert!(region.contains(25, 75));
+        assert!(!region.contains(75, 75));
+        assert!(region.contains_rect(&IRect::new(10, 10, 40, 90)));
+        assert!(!region.contains_rect(&IRect::new(40, 40, 60, 60)));
+    }
+
+    #[test]
+    fn test_union_coalesces() {
+        let mut region = Region::from_rect(IRect::new(0, 0, 50, 100));
+        region.op_rect(IRect::new(50, 0, 100, 100), RegionOp::Union);
+        assert_eq!(region.rects(), &[IRect::new(0, 0, 100, 100)]);
+
+        region.op_rect(IRect::new(0, 100, 100, 150), RegionOp::Union);
+        assert_eq!(region.rects(), &[IRect::new(0, 0, 100, 150)]);
+
+        // Overlapping union yields non-overlapping rectangles.
+        let mut region = Region::from_rect(IRect::new(0, 0, 60, 60));
+        region.op_rect(IRect::new(40, 40, 100, 100), RegionOp::Union);
+        assert_eq!(region.rect_count(), 3);
+        let area: i64 = region
+            .iter()
+            .map(|r| i64::from(r.width()) * i64::from(r.height()))
+            .sum();
+        assert_eq!(area, 60 * 60 * 2 - 20 * 20);
+    }
+
+    #[test]
+    fn test_xor_and_reverse_difference() {
+        let a = IRect::new(0, 0, 60, 60);
+        let b = IRect::new(40, 40, 100, 100);
+
+        let mut xor = Region::from_rect(a);
+        xor.op_rect(b, RegionOp::Xor);
+        assert!(xor.contains(10, 10));
+        assert!(xor.contains(90, 90));
+        assert!(!xor.contains(50, 50));
+        assert_eq!(xor.bounds(), IRect::new(0, 0, 100, 100));
+
+        let mut rev = Region::from_rect(a);
+        rev.op_rect(b, RegionOp::ReverseDifference);
+        assert!(!rev.contains(10, 10));
+        assert!(!rev.contains(50, 50));
+        assert!(rev.contains(90, 90));
+        assert_eq!(rev.bounds(), b);
+
+        let mut region = Region::from_rect(a);
+        assert!(!region.op_rect(IRect::new(70, 70, 80, 80), RegionOp::Intersect));
+        assert!(region.is_empty());
+    }
 }
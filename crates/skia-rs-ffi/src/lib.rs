@@ -351,9 +351,8 @@ pub unsafe extern "C" fn sk_refcnt_is_unique(ptr: *const sk_refcnt_t) -> bool {
 
 // Re-export types for FFI
 use skia_rs_canvas::{PixelBuffer, RasterCanvas, Surface};
-use skia_rs_core::{
-    AlphaType, Color, ColorType, IPoint, IRect, ISize, ImageInfo, Matrix, Point, Rect, Scalar, Size,
-};
+use skia_rs_codec::{Image, ImageFormat};
+use skia_rs_core::{AlphaType, Color, ColorType, ImageInfo, Matrix, Pixmap, Point, Rect};
 use skia_rs_paint::{BlendMode, Paint, Style};
 use skia_rs_path::{FillType, Path, PathBuilder};
 
@@ -362,7 +361,7 @@ use skia_rs_path::{FillType, Path, PathBuilder};
 // =============================================================================
 
 // Note: Reference counted types are defined in their respective sections:
-// - sk_surface_t = RefCounted<Surface>
+// - sk_surface_t = RefCounted<SurfaceHandle>
 // - sk_paint_t = RefCounted<Paint>
 // - sk_path_t = RefCounted<Path>
 // - sk_pathbuilder_t = RefCounted<PathBuilder>
@@ -465,6 +464,32 @@ pub struct sk_imageinfo_t {
     pub alpha_type: u32,
 }
 
+impl sk_imageinfo_t {
+    /// Convert to an [`ImageInfo`], falling back to RGBA/premul for unknown
+    /// color and alpha type values.
+    fn to_image_info(self) -> Option<ImageInfo> {
+        let color_type = match self.color_type {
+            0 => ColorType::Unknown,
+            1 => ColorType::Alpha8,
+            2 => ColorType::Rgb565,
+            3 => ColorType::Argb4444,
+            4 => ColorType::Rgba8888,
+            5 => ColorType::Bgra8888,
+            _ => ColorType::Rgba8888,
+        };
+
+        let alpha_type = match self.alpha_type {
+            0 => AlphaType::Unknown,
+            1 => AlphaType::Opaque,
+            2 => AlphaType::Premul,
+            3 => AlphaType::Unpremul,
+            _ => AlphaType::Premul,
+        };
+
+        ImageInfo::new(self.width, self.height, color_type, alpha_type).ok()
+    }
+}
+
 /// C-compatible color (ARGB).
 pub type sk_color_t = u32;
 
@@ -517,8 +542,49 @@ impl From<sk_matrix_t> for Matrix {
 // Surface API (Reference Counted)
 // =============================================================================
 
+/// A surface together with the canvas state kept between draw calls.
+///
+/// Every draw call opens a fresh canvas on the surface, so the transform set
+/// with [`sk_surface_set_matrix`] lives here and is applied to each draw.
+pub struct SurfaceHandle {
+    surface: Surface,
+    /// Transform applied to subsequent draws.
+    matrix: Matrix,
+}
+
+impl SurfaceHandle {
+    /// Wrap a surface with an identity transform.
+    pub fn new(surface: Surface) -> Self {
+        Self {
+            surface,
+            matrix: Matrix::IDENTITY,
+        }
+    }
+
+    /// Open a canvas on the surface with the current transform.
+    pub fn canvas(&mut self) -> RasterCanvas<'_> {
+        let mut canvas = self.surface.raster_canvas();
+        canvas.set_matrix(&self.matrix);
+        canvas
+    }
+}
+
+impl std::ops::Deref for SurfaceHandle {
+    type Target = Surface;
+
+    fn deref(&self) -> &Surface {
+        &self.surface
+    }
+}
+
+impl std::ops::DerefMut for SurfaceHandle {
+    fn deref_mut(&mut self) -> &mut Surface {
+        &mut self.surface
+    }
+}
+
 /// Reference counted surface type.
-pub type sk_surface_t = RefCounted<Surface>;
+pub type sk_surface_t = RefCounted<SurfaceHandle>;
 
 /// Create a new raster surface.
 ///
@@ -526,7 +592,7 @@ pub type sk_surface_t = RefCounted<Surface>;
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn sk_surface_new_raster(width: i32, height: i32) -> *mut sk_surface_t {
     catch_panic(|| match Surface::new_raster_n32_premul(width, height) {
-        Some(surface) => RefCounted::new(surface),
+        Some(surface) => RefCounted::new(SurfaceHandle::new(surface)),
         None => ptr::null_mut(),
     })
 }
@@ -542,32 +608,12 @@ pub unsafe extern "C" fn sk_surface_new_raster_with_info(
         return ptr::null_mut();
     }
 
-    let info = &*info;
-    let color_type = match info.color_type {
-        0 => ColorType::Unknown,
-        1 => ColorType::Alpha8,
-        2 => ColorType::Rgb565,
-        3 => ColorType::Argb4444,
-        4 => ColorType::Rgba8888,
-        5 => ColorType::Bgra8888,
-        _ => ColorType::Rgba8888,
-    };
-
-    let alpha_type = match info.alpha_type {
-        0 => AlphaType::Unknown,
-        1 => AlphaType::Opaque,
-        2 => AlphaType::Premul,
-        3 => AlphaType::Unpremul,
-        _ => AlphaType::Premul,
-    };
-
-    let img_info = match ImageInfo::new(info.width, info.height, color_type, alpha_type) {
-        Ok(i) => i,
-        Err(_) => return ptr::null_mut(),
+    let Some(img_info) = (*info).to_image_info() else {
+        return ptr::null_mut();
     };
 
     match Surface::new_raster(&img_info, None) {
-        Some(surface) => RefCounted::new(surface),
+        Some(surface) => RefCounted::new(SurfaceHandle::new(surface)),
         None => ptr::null_mut(),
     }
 }
@@ -632,6 +678,59 @@ pub unsafe extern "C" fn sk_surface_peek_pixels(
     }
 }
 
+/// Copy a rectangle of surface pixels into a caller-provided buffer.
+///
+/// The rectangle has the dimensions of `info` and its top-left corner at
+/// (`src_x`, `src_y`) on the surface. Pixels are converted to the color and
+/// alpha type of `info`, including RGBA/BGRA swizzling and premultiplying or
+/// unpremultiplying. The rectangle is clipped to the surface; pixels outside
+/// it are left untouched in `dst`.
+///
+/// `dst` must be at least `dst_row_bytes * (info.height - 1)` plus one row of
+/// pixels long. Returns `false` if the rectangle misses the surface, the row
+/// bytes are too small, or the conversion is not supported.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_surface_read_pixels(
+    surface: *const sk_surface_t,
+    info: *const sk_imageinfo_t,
+    dst: *mut u8,
+    dst_row_bytes: usize,
+    src_x: i32,
+    src_y: i32,
+) -> bool {
+    if info.is_null() || dst.is_null() {
+        return false;
+    }
+
+    let (Some(s), Some(dst_info)) = (RefCounted::get_ref(surface), (*info).to_image_info()) else {
+        return false;
+    };
+
+    catch_panic(AssertUnwindSafe(|| {
+        if dst_info.validate_row_bytes(dst_row_bytes).is_err() {
+            return false;
+        }
+
+        // Describe the buffer as it's laid out rather than by the surface's
+        // info: it holds RGBA8888 (or packed 16-bit) unpremultiplied colors
+        // whatever color and alpha type the surface was created with.
+        let buffer = s.pixel_buffer();
+        let alpha_type = if s.info().is_opaque() {
+            AlphaType::Opaque
+        } else {
+            AlphaType::Unpremul
+        };
+        let Ok(src_info) =
+            ImageInfo::new(buffer.width, buffer.height, buffer.color_type, alpha_type)
+        else {
+            return false;
+        };
+        let dst = std::slice::from_raw_parts_mut(dst, dst_info.compute_byte_size(dst_row_bytes));
+        Pixmap::new(src_info, &buffer.pixels, buffer.stride)
+            .is_ok_and(|src| src.read_pixels(&dst_info, dst, dst_row_bytes, src_x, src_y))
+    }))
+}
+
 // =============================================================================
 // Paint API (Reference Counted)
 // =============================================================================
@@ -982,6 +1081,102 @@ pub unsafe extern "C" fn sk_pathbuilder_snapshot(
     RefCounted::get_ref(builder).map_or(ptr::null_mut(), |b| RefCounted::new(b.clone().build()))
 }
 
+// =============================================================================
+// Image API (Reference Counted)
+// =============================================================================
+
+/// Reference counted image type.
+pub type sk_image_t = RefCounted<Image>;
+
+/// Decode an image from encoded bytes (PNG, JPEG, GIF, WebP, ...).
+///
+/// Returns an image with refcount of 1, or null if the data can't be decoded.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_image_new_from_encoded(data: *const u8, len: usize) -> *mut sk_image_t {
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+
+    let bytes = std::slice::from_raw_parts(data, len);
+    catch_panic(|| match skia_rs_codec::decode_image(bytes) {
+        Ok(image) => RefCounted::new(image),
+        Err(_) => ptr::null_mut(),
+    })
+}
+
+/// Increment the reference count of an image.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_image_ref(image: *mut sk_image_t) {
+    RefCounted::ref_ptr(image);
+}
+
+/// Decrement the reference count of an image.
+///
+/// Frees the image when the count reaches 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_image_unref(image: *mut sk_image_t) {
+    catch_panic_void(AssertUnwindSafe(|| {
+        RefCounted::unref_ptr(image);
+    }));
+}
+
+/// Get the reference count of an image.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_image_get_refcnt(image: *const sk_image_t) -> u32 {
+    RefCounted::get_count(image)
+}
+
+/// Get the width of an image.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_image_get_width(image: *const sk_image_t) -> i32 {
+    RefCounted::get_ref(image).map_or(0, |i| i.width())
+}
+
+/// Get the height of an image.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_image_get_height(image: *const sk_image_t) -> i32 {
+    RefCounted::get_ref(image).map_or(0, |i| i.height())
+}
+
+/// Encode an image as PNG.
+///
+/// On success the encoded bytes are written to `out_data` and `out_len`; the
+/// buffer must be released with [`sk_data_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_image_encode_to_png(
+    image: *const sk_image_t,
+    out_data: *mut *mut u8,
+    out_len: *mut usize,
+) -> bool {
+    if out_data.is_null() || out_len.is_null() {
+        return false;
+    }
+
+    let Some(image) = RefCounted::get_ref(image) else {
+        return false;
+    };
+
+    match catch_panic(|| image.encode(ImageFormat::Png, None).ok()) {
+        Some(bytes) => {
+            let bytes = bytes.into_boxed_slice();
+            *out_len = bytes.len();
+            *out_data = Box::into_raw(bytes) as *mut u8;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Free a buffer returned by an encode function.
+///
+/// `len` must be the length reported alongside the buffer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_data_free(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+    }
+}
+
 // =============================================================================
 // Matrix API
 // =============================================================================
@@ -1084,14 +1279,52 @@ pub unsafe extern "C" fn sk_surface_draw_rect(
     rect: *const sk_rect_t,
     paint: *const sk_paint_t,
 ) {
-    if let (Some(s), Some(r), Some(p)) = (
-        RefCounted::get_mut(surface),
-        rect.as_ref(),
-        RefCounted::get_ref(paint),
-    ) {
-        let mut canvas = s.raster_canvas();
-        canvas.draw_rect(&Rect::from(*r), p);
-    }
+    catch_panic_void(AssertUnwindSafe(|| {
+        if let (Some(s), Some(r), Some(p)) = (
+            RefCounted::get_mut(surface),
+            rect.as_ref(),
+            RefCounted::get_ref(paint),
+        ) {
+            s.canvas().draw_rect(&Rect::from(*r), p);
+        }
+    }));
+}
+
+/// Draw an oval inscribed in a rect on a surface.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_surface_draw_oval(
+    surface: *mut sk_surface_t,
+    rect: *const sk_rect_t,
+    paint: *const sk_paint_t,
+) {
+    catch_panic_void(AssertUnwindSafe(|| {
+        if let (Some(s), Some(r), Some(p)) = (
+            RefCounted::get_mut(surface),
+            rect.as_ref(),
+            RefCounted::get_ref(paint),
+        ) {
+            s.canvas().draw_oval(&Rect::from(*r), p);
+        }
+    }));
+}
+
+/// Draw an image on a surface with its top-left corner at (x, y).
+///
+/// `paint` may be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_surface_draw_image(
+    surface: *mut sk_surface_t,
+    image: *const sk_image_t,
+    x: f32,
+    y: f32,
+    paint: *const sk_paint_t,
+) {
+    catch_panic_void(AssertUnwindSafe(|| {
+        if let (Some(s), Some(image)) = (RefCounted::get_mut(surface), RefCounted::get_ref(image)) {
+            s.canvas()
+                .draw_image(image, x, y, RefCounted::get_ref(paint));
+        }
+    }));
 }
 
 /// Draw a circle on a surface.
@@ -1103,27 +1336,32 @@ pub unsafe extern "C" fn sk_surface_draw_circle(
     radius: f32,
     paint: *const sk_paint_t,
 ) {
-    if let (Some(s), Some(p)) = (RefCounted::get_mut(surface), RefCounted::get_ref(paint)) {
-        let mut canvas = s.raster_canvas();
-        canvas.draw_circle(Point::new(cx, cy), radius, p);
-    }
+    catch_panic_void(AssertUnwindSafe(|| {
+        if let (Some(s), Some(p)) = (RefCounted::get_mut(surface), RefCounted::get_ref(paint)) {
+            s.canvas().draw_circle(Point::new(cx, cy), radius, p);
+        }
+    }));
 }
 
 /// Draw a path on a surface.
+///
+/// The path is filled or stroked according to the paint's style, stroke
+/// width, cap and join.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn sk_surface_draw_path(
     surface: *mut sk_surface_t,
     path: *const sk_path_t,
     paint: *const sk_paint_t,
 ) {
-    if let (Some(s), Some(path), Some(p)) = (
-        RefCounted::get_mut(surface),
-        RefCounted::get_ref(path),
-        RefCounted::get_ref(paint),
-    ) {
-        let mut canvas = s.raster_canvas();
-        canvas.draw_path(path, p);
-    }
+    catch_panic_void(AssertUnwindSafe(|| {
+        if let (Some(s), Some(path), Some(p)) = (
+            RefCounted::get_mut(surface),
+            RefCounted::get_ref(path),
+            RefCounted::get_ref(paint),
+        ) {
+            s.canvas().draw_path(path, p);
+        }
+    }));
 }
 
 /// Draw a line on a surface.
@@ -1136,9 +1374,41 @@ pub unsafe extern "C" fn sk_surface_draw_line(
     y1: f32,
     paint: *const sk_paint_t,
 ) {
-    if let (Some(s), Some(p)) = (RefCounted::get_mut(surface), RefCounted::get_ref(paint)) {
-        let mut canvas = s.raster_canvas();
-        canvas.draw_line(Point::new(x0, y0), Point::new(x1, y1), p);
+    catch_panic_void(AssertUnwindSafe(|| {
+        if let (Some(s), Some(p)) = (RefCounted::get_mut(surface), RefCounted::get_ref(paint)) {
+            s.canvas()
+                .draw_line(Point::new(x0, y0), Point::new(x1, y1), p);
+        }
+    }));
+}
+
+/// Set the transform applied to subsequent draws on a surface.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_surface_set_matrix(
+    surface: *mut sk_surface_t,
+    matrix: *const sk_matrix_t,
+) {
+    if let (Some(s), Some(m)) = (RefCounted::get_mut(surface), matrix.as_ref()) {
+        s.matrix = (*m).into();
+    }
+}
+
+/// Reset the transform applied to subsequent draws on a surface to identity.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_surface_reset_matrix(surface: *mut sk_surface_t) {
+    if let Some(s) = RefCounted::get_mut(surface) {
+        s.matrix = Matrix::IDENTITY;
+    }
+}
+
+/// Get the transform applied to subsequent draws on a surface.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sk_surface_get_matrix(
+    surface: *const sk_surface_t,
+    matrix: *mut sk_matrix_t,
+) {
+    if let (Some(s), Some(m)) = (RefCounted::get_ref(surface), matrix.as_mut()) {
+        *m = s.matrix.into();
     }
 }
 
@@ -1300,6 +1570,324 @@ mod tests {
         }
     }
 
+    unsafe fn alpha_at(surface: *const sk_surface_t, x: usize, y: usize) -> u8 {
+        let mut pixels = std::ptr::null();
+        let mut row_bytes = 0;
+        unsafe {
+            assert!(sk_surface_peek_pixels(surface, &mut pixels, &mut row_bytes));
+            *pixels.add(y * row_bytes + x * 4 + 3)
+        }
+    }
+
+    #[test]
+    fn test_draw_oval() {
+        unsafe {
+            let surface = sk_surface_new_raster(100, 100);
+            let paint = sk_paint_new();
+            sk_paint_set_color(paint, 0xFFFF0000);
+
+            let rect = sk_rect_t {
+                left: 10.0,
+                top: 30.0,
+                right: 90.0,
+                bottom: 70.0,
+            };
+            sk_surface_draw_oval(surface, &rect, paint);
+
+            assert_eq!(alpha_at(surface, 50, 50), 255);
+            assert_eq!(alpha_at(surface, 15, 50), 255);
+            // Inside the bounds but outside the ellipse.
+            assert_eq!(alpha_at(surface, 12, 32), 0);
+            assert_eq!(alpha_at(surface, 50, 20), 0);
+
+            sk_paint_delete(paint);
+            sk_surface_unref(surface);
+        }
+    }
+
+    #[test]
+    fn test_surface_matrix() {
+        unsafe {
+            let surface = sk_surface_new_raster(100, 100);
+            let paint = sk_paint_new();
+            sk_paint_set_color(paint, 0xFF00FF00);
+
+            let mut matrix = sk_matrix_t::default();
+            sk_matrix_set_translate(&mut matrix, 50.0, 50.0);
+            sk_surface_set_matrix(surface, &matrix);
+
+            let mut current = sk_matrix_t::default();
+            sk_surface_get_matrix(surface, &mut current);
+            assert_eq!(current.values, matrix.values);
+
+            let rect = sk_rect_t {
+                left: 0.0,
+                top: 0.0,
+                right: 10.0,
+                bottom: 10.0,
+            };
+            sk_surface_draw_rect(surface, &rect, paint);
+            assert_eq!(alpha_at(surface, 5, 5), 0);
+            assert_eq!(alpha_at(surface, 55, 55), 255);
+
+            sk_surface_reset_matrix(surface);
+            sk_surface_draw_rect(surface, &rect, paint);
+            assert_eq!(alpha_at(surface, 5, 5), 255);
+
+            sk_paint_delete(paint);
+            sk_surface_unref(surface);
+        }
+    }
+
+    #[test]
+    fn test_draw_path_stroke() {
+        unsafe {
+            let surface = sk_surface_new_raster(100, 100);
+            let paint = sk_paint_new();
+            sk_paint_set_color(paint, 0xFF0000FF);
+            sk_paint_set_style(paint, 1);
+            sk_paint_set_stroke_width(paint, 4.0);
+
+            let builder = sk_pathbuilder_new();
+            sk_pathbuilder_move_to(builder, 20.0, 20.0);
+            sk_pathbuilder_line_to(builder, 80.0, 20.0);
+            sk_pathbuilder_line_to(builder, 80.0, 80.0);
+            sk_pathbuilder_line_to(builder, 20.0, 80.0);
+            sk_pathbuilder_close(builder);
+            let path = sk_pathbuilder_detach(builder);
+
+            sk_surface_draw_path(surface, path, paint);
+
+            assert_eq!(alpha_at(surface, 50, 20), 255);
+            assert_eq!(alpha_at(surface, 80, 50), 255);
+            assert_eq!(alpha_at(surface, 50, 50), 0);
+            assert_eq!(alpha_at(surface, 50, 10), 0);
+
+            sk_path_delete(path);
+            sk_pathbuilder_delete(builder);
+            sk_paint_delete(paint);
+            sk_surface_unref(surface);
+        }
+    }
+
+    #[test]
+    fn test_read_pixels_bgra() {
+        unsafe {
+            let surface = sk_surface_new_raster(4, 4);
+            sk_surface_clear(surface, 0xFFFF0000); // Red
+
+            let info = sk_imageinfo_t {
+                width: 2,
+                height: 2,
+                color_type: 5, // BGRA_8888
+                alpha_type: 2, // Premul
+            };
+            let mut dst = [0u8; 16];
+            assert!(sk_surface_read_pixels(
+                surface,
+                &info,
+                dst.as_mut_ptr(),
+                8,
+                1,
+                1
+            ));
+            for pixel in dst.chunks(4) {
+                assert_eq!(pixel, [0, 0, 255, 255]);
+            }
+
+            // Partially outside: only the overlapping pixel is written.
+            let mut dst = [7u8; 16];
+            assert!(sk_surface_read_pixels(
+                surface,
+                &info,
+                dst.as_mut_ptr(),
+                8,
+                3,
+                3
+            ));
+            assert_eq!(&dst[..4], &[0, 0, 255, 255]);
+            assert_eq!(&dst[4..], &[7u8; 12]);
+
+            // Entirely outside, or rows too short.
+            assert!(!sk_surface_read_pixels(
+                surface,
+                &info,
+                dst.as_mut_ptr(),
+                8,
+                4,
+                0
+            ));
+            assert!(!sk_surface_read_pixels(
+                surface,
+                &info,
+                dst.as_mut_ptr(),
+                4,
+                0,
+                0
+            ));
+
+            sk_surface_unref(surface);
+        }
+    }
+
+    #[test]
+    fn test_read_pixels_from_bgra_surface() {
+        unsafe {
+            // The surface's info says BGRA, but it still renders into RGBA.
+            let bgra = sk_imageinfo_t {
+                width: 1,
+                height: 1,
+                color_type: 5, // BGRA_8888
+                alpha_type: 2, // Premul
+            };
+            let surface = sk_surface_new_raster_with_info(&bgra);
+            sk_surface_clear(surface, 0xFFFF0000); // Red
+
+            let mut dst = [0u8; 4];
+            assert!(sk_surface_read_pixels(
+                surface,
+                &bgra,
+                dst.as_mut_ptr(),
+                4,
+                0,
+                0
+            ));
+            assert_eq!(dst, [0, 0, 255, 255]);
+
+            let rgba = sk_imageinfo_t {
+                color_type: 4, // RGBA_8888
+                ..bgra
+            };
+            assert!(sk_surface_read_pixels(
+                surface,
+                &rgba,
+                dst.as_mut_ptr(),
+                4,
+                0,
+                0
+            ));
+            assert_eq!(dst, [255, 0, 0, 255]);
+
+            sk_surface_unref(surface);
+        }
+    }
+
+    #[test]
+    fn test_read_pixels_unpremul() {
+        unsafe {
+            let surface = sk_surface_new_raster(1, 1);
+            sk_surface_clear(surface, 0x80FF0000); // Half-transparent red
+
+            let info = sk_imageinfo_t {
+                width: 1,
+                height: 1,
+                color_type: 4, // RGBA_8888
+                alpha_type: 3, // Unpremul
+            };
+            let mut dst = [0u8; 4];
+            assert!(sk_surface_read_pixels(
+                surface,
+                &info,
+                dst.as_mut_ptr(),
+                4,
+                0,
+                0
+            ));
+            assert_eq!(dst, [255, 0, 0, 128]);
+
+            sk_surface_unref(surface);
+        }
+    }
+
+    #[test]
+    fn test_read_pixels_premul_translucent() {
+        unsafe {
+            let surface = sk_surface_new_raster(1, 1);
+            sk_surface_clear(surface, 0x80FF0000); // Half-transparent red
+
+            let info = sk_imageinfo_t {
+                width: 1,
+                height: 1,
+                color_type: 4, // RGBA_8888
+                alpha_type: 2, // Premul
+            };
+            let mut dst = [0u8; 4];
+            assert!(sk_surface_read_pixels(
+                surface,
+                &info,
+                dst.as_mut_ptr(),
+                4,
+                0,
+                0
+            ));
+            assert_eq!(dst, [128, 0, 0, 128]);
+
+            sk_surface_unref(surface);
+        }
+    }
+
+    /// A 2x2 RGBA PNG: red, green / blue, white.
+    const TEST_PNG: [u8; 75] = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02, 0x08, 0x06, 0x00, 0x00, 0x00, 0x72,
+        0xb6, 0x0d, 0x24, 0x00, 0x00, 0x00, 0x12, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0xf8,
+        0xcf, 0xc0, 0xf0, 0x1f, 0x0c, 0x81, 0x34, 0x18, 0x00, 0x00, 0x49, 0xc8, 0x09, 0xf7, 0x03,
+        0xd9, 0x64, 0xf1, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    #[test]
+    fn test_image_decode_encode() {
+        unsafe {
+            let image = sk_image_new_from_encoded(TEST_PNG.as_ptr(), TEST_PNG.len());
+            assert!(!image.is_null());
+            assert_eq!(sk_image_get_refcnt(image), 1);
+            assert_eq!(sk_image_get_width(image), 2);
+            assert_eq!(sk_image_get_height(image), 2);
+
+            let mut data = std::ptr::null_mut();
+            let mut len = 0;
+            assert!(sk_image_encode_to_png(image, &mut data, &mut len));
+            assert!(!data.is_null());
+
+            let encoded = std::slice::from_raw_parts(data, len);
+            assert_eq!(&encoded[..8], &TEST_PNG[..8]);
+            let decoded = sk_image_new_from_encoded(data, len);
+            assert_eq!(sk_image_get_width(decoded), 2);
+            assert_eq!(sk_image_get_height(decoded), 2);
+
+            sk_data_free(data, len);
+            sk_image_unref(decoded);
+            sk_image_unref(image);
+
+            let garbage = [0u8; 16];
+            assert!(sk_image_new_from_encoded(garbage.as_ptr(), garbage.len()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_draw_image() {
+        unsafe {
+            let surface = sk_surface_new_raster(8, 8);
+            let image = sk_image_new_from_encoded(TEST_PNG.as_ptr(), TEST_PNG.len());
+
+            sk_surface_draw_image(surface, image, 3.0, 4.0, std::ptr::null());
+
+            let mut pixels = std::ptr::null();
+            let mut row_bytes = 0;
+            assert!(sk_surface_peek_pixels(surface, &mut pixels, &mut row_bytes));
+            let pixel = |x: usize, y: usize| {
+                std::slice::from_raw_parts(pixels.add(y * row_bytes + x * 4), 4).to_vec()
+            };
+            assert_eq!(pixel(3, 4), [255, 0, 0, 255]);
+            assert_eq!(pixel(4, 4), [0, 255, 0, 255]);
+            assert_eq!(pixel(3, 5), [0, 0, 255, 255]);
+            assert_eq!(pixel(2, 4), [0, 0, 0, 0]);
+
+            sk_image_unref(image);
+            sk_surface_unref(surface);
+        }
+    }
+
     #[test]
     fn test_refcnt_utility() {
         unsafe {
@@ -38,6 +38,8 @@ pub enum GpuBackendType {
     Direct3D12,
     /// WebGPU backend.
     WebGPU,
+    /// CPU software rasterizer (headless).
+    Software,
 }
 
 /// Information about a GPU adapter.
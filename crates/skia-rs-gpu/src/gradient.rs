@@ -78,6 +78,106 @@ impl Default for GradientTextureConfig {
     }
 }
 
+/// Color space gradient colors are interpolated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GradientInterpolation {
+    /// Interpolate the sRGB-encoded components, as the raster backend does.
+    #[default]
+    Srgb,
+    /// Interpolate linear-light components and re-encode the result.
+    Linear,
+}
+
+/// Baked 1D gradient ramps for sampling in shaders.
+///
+/// Baking the ramp once lets a fragment shader look the color up with a
+/// single texture sample instead of searching the stops per fragment.
+pub struct GradientTexture;
+
+impl GradientTexture {
+    /// Bake a gradient ramp interpolated in sRGB space.
+    ///
+    /// See [`from_stops_with_interpolation`](Self::from_stops_with_interpolation).
+    pub fn from_stops(colors: &[Color4f], stops: &[f32], width: usize) -> Vec<u8> {
+        Self::from_stops_with_interpolation(colors, stops, width, GradientInterpolation::Srgb)
+    }
+
+    /// Bake a gradient ramp into `width` premultiplied RGBA8 texels.
+    ///
+    /// `colors` are sRGB-encoded and `stops` gives each color's position.
+    /// When `stops` is empty or its length differs from `colors`, the colors
+    /// are spaced evenly. Positions are clamped to `[0, 1]` and to be no
+    /// smaller than the previous one, so repeated positions make hard stops.
+    /// A NaN position takes the previous one. The first texel holds the color at `t = 0` and the last the color at
+    /// `t = 1`. Texels are sRGB-encoded and premultiplied after
+    /// interpolating.
+    pub fn from_stops_with_interpolation(
+        colors: &[Color4f],
+        stops: &[f32],
+        width: usize,
+        interpolation: GradientInterpolation,
+    ) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(width * 4);
+        if colors.is_empty() {
+            pixels.resize(width * 4, 0);
+            return pixels;
+        }
+
+        let decode = |c: Color4f| match interpolation {
+            GradientInterpolation::Srgb => c,
+            GradientInterpolation::Linear => Color4f::new(
+                srgb_to_linear(c.r),
+                srgb_to_linear(c.g),
+                srgb_to_linear(c.b),
+                c.a,
+            ),
+        };
+        let even = stops.len() != colors.len();
+        let mut previous = 0.0f32;
+        let ramp: Vec<GradientStop> = colors
+            .iter()
+            .enumerate()
+            .map(|(i, &color)| {
+                let position = if even {
+                    i as f32 / (colors.len() - 1).max(1) as f32
+                } else {
+                    stops[i]
+                };
+                // Unlike `clamp`, `max` and `min` pass over a NaN position
+                previous = position.max(previous).min(1.0);
+                GradientStop::new(previous, decode(color))
+            })
+            .collect();
+
+        for x in 0..width {
+            let t = if width > 1 {
+                x as f32 / (width - 1) as f32
+            } else {
+                0.0
+            };
+            let color = if t < ramp[0].position {
+                ramp[0].color
+            } else {
+                sample_gradient(&ramp, t)
+            };
+            let (r, g, b) = match interpolation {
+                GradientInterpolation::Srgb => (color.r, color.g, color.b),
+                GradientInterpolation::Linear => (
+                    linear_to_srgb(color.r),
+                    linear_to_srgb(color.g),
+                    linear_to_srgb(color.b),
+                ),
+            };
+            let a = color.a.clamp(0.0, 1.0);
+            for component in [r * a, g * a, b * a, a] {
+                pixels.push((component.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+
+        pixels
+    }
+}
+
 /// Generate a 1D gradient texture.
 pub fn generate_gradient_texture_1d(
     stops: &[GradientStop],
@@ -453,6 +553,57 @@ mod tests {
         assert!((linear - back).abs() < 0.001);
     }
 
+    #[test]
+    fn test_gradient_texture_from_stops() {
+        let colors = [
+            Color4f::new(1.0, 0.0, 0.0, 1.0),
+            Color4f::new(0.0, 0.0, 1.0, 0.5),
+        ];
+        let texels = GradientTexture::from_stops(&colors, &[0.0, 1.0], 3);
+        assert_eq!(texels.len(), 3 * 4);
+        assert_eq!(&texels[0..4], &[255, 0, 0, 255]);
+        assert_eq!(&texels[8..12], &[0, 0, 128, 128]);
+        // Unpremultiplied (0.5, 0, 0.5, 0.75), premultiplied.
+        assert_eq!(&texels[4..8], &[96, 0, 96, 191]);
+
+        let opaque = [
+            Color4f::from_rgb(1.0, 0.0, 0.0),
+            Color4f::from_rgb(0.0, 0.0, 1.0),
+        ];
+        let linear = GradientTexture::from_stops_with_interpolation(
+            &opaque,
+            &[],
+            3,
+            GradientInterpolation::Linear,
+        );
+        assert_eq!(&linear[0..4], &[255, 0, 0, 255]);
+        assert_eq!(&linear[4..8], &[188, 0, 188, 255]);
+        assert_eq!(&linear[8..12], &[0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_gradient_texture_hard_stops() {
+        let colors = [
+            Color4f::from_rgb(1.0, 0.0, 0.0),
+            Color4f::from_rgb(0.0, 1.0, 0.0),
+            Color4f::from_rgb(0.0, 0.0, 1.0),
+        ];
+        // The middle stop is clamped up to 0.5, making a hard edge there.
+        let texels = GradientTexture::from_stops(&colors, &[0.5, 0.25, 0.5], 5);
+        assert_eq!(&texels[0..4], &[255, 0, 0, 255]);
+        assert_eq!(&texels[4..8], &[255, 0, 0, 255]);
+        assert_eq!(&texels[8..12], &[0, 0, 255, 255]);
+        assert_eq!(&texels[16..20], &[0, 0, 255, 255]);
+
+        // NaN stops repeat the previous position instead of panicking.
+        let texels = GradientTexture::from_stops(&colors, &[0.5, f32::NAN, 0.5], 5);
+        assert_eq!(&texels[4..8], &[255, 0, 0, 255]);
+        assert_eq!(&texels[16..20], &[0, 0, 255, 255]);
+
+        assert_eq!(GradientTexture::from_stops(&[], &[], 2), vec![0; 8]);
+        assert!(GradientTexture::from_stops(&colors, &[], 0).is_empty());
+    }
+
     #[test]
     fn test_gradient_lut() {
         let stops = vec![
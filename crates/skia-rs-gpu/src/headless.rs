@@ -0,0 +1,638 @@
+//! Headless software GPU backend.
+//!
+//! [`HeadlessGpuContext`] executes recorded [`CommandBuffer`]s on the CPU and
+//! rasterizes their triangles into a [`PixelBuffer`]. It needs no device, so
+//! examples and tests can drive the tessellation → fill pipeline and compare
+//! the result deterministically against the raster backend.
+//!
+//! The backend has a single fixed-function pipeline: vertex buffers hold
+//! [`TessVertex`] data whose positions are device pixels, and every triangle
+//! is filled with the solid color of the bound [`HeadlessPipeline`]. Pixel
+//! centers are sampled without anti-aliasing, so triangles sharing an edge
+//! never cover the same pixel twice.
+
+use crate::command::{CommandBuffer, DrawCommand, IndexFormat};
+use crate::context::{
+    GpuAdapterInfo, GpuBackendType, GpuContext, GpuDeviceType, GpuError, GpuResult,
+};
+use crate::surface::GpuSurface;
+use crate::tessellation::{TessMesh, TessVertex};
+use crate::texture::TextureFormat;
+use parking_lot::Mutex;
+use skia_rs_canvas::{PixelBuffer, Rasterizer};
+use skia_rs_core::{Color, Rect};
+use skia_rs_paint::{BlendMode, Paint};
+use skia_rs_path::PathBuilder;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Size in bytes of one [`TessVertex`] in a vertex buffer.
+const VERTEX_STRIDE: usize = std::mem::size_of::<TessVertex>();
+
+impl dyn GpuContext {
+    /// Create a headless context backed by the software rasterizer.
+    pub fn new_headless() -> HeadlessGpuContext {
+        HeadlessGpuContext::new()
+    }
+}
+
+/// Pipeline state of the headless backend.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadlessPipeline {
+    /// Solid color every triangle is filled with.
+    pub color: Color,
+    /// Blend mode used to composite the color onto the surface.
+    pub blend_mode: BlendMode,
+}
+
+impl HeadlessPipeline {
+    /// Create a source-over pipeline filling with `color`.
+    pub fn solid(color: Color) -> Self {
+        Self {
+            color,
+            blend_mode: BlendMode::SrcOver,
+        }
+    }
+
+    /// Set the blend mode.
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+}
+
+/// GPU context that executes command buffers on the CPU.
+pub struct HeadlessGpuContext {
+    adapter_info: GpuAdapterInfo,
+    buffers: Mutex<HashMap<u64, Vec<u8>>>,
+    pipelines: Mutex<HashMap<u64, HeadlessPipeline>>,
+    next_id: AtomicU64,
+}
+
+impl std::fmt::Debug for HeadlessGpuContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HeadlessGpuContext")
+            .field("buffers", &self.buffers.lock().len())
+            .field("pipelines", &self.pipelines.lock().len())
+            .finish()
+    }
+}
+
+impl Default for HeadlessGpuContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadlessGpuContext {
+    /// Create a new headless context.
+    pub fn new() -> Self {
+        Self {
+            adapter_info: GpuAdapterInfo {
+                name: "skia-rs software rasterizer".to_string(),
+                vendor: "skia-rs".to_string(),
+                backend: GpuBackendType::Software,
+                device_type: GpuDeviceType::Cpu,
+            },
+            buffers: Mutex::new(HashMap::new()),
+            pipelines: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn allocate_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Create a buffer holding a copy of `data` and return its ID.
+    pub fn create_buffer(&self, data: &[u8]) -> u64 {
+        let id = self.allocate_id();
+        self.buffers.lock().insert(id, data.to_vec());
+        id
+    }
+
+    /// Create a vertex buffer from tessellated vertices.
+    pub fn create_vertex_buffer(&self, vertices: &[TessVertex]) -> u64 {
+        let mut data = Vec::with_capacity(vertices.len() * VERTEX_STRIDE);
+        for vertex in vertices {
+            for value in vertex.position.iter().chain(&vertex.uv) {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        self.create_buffer(&data)
+    }
+
+    /// Create a 32-bit index buffer.
+    pub fn create_index_buffer(&self, indices: &[u32]) -> u64 {
+        let data: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        self.create_buffer(&data)
+    }
+
+    /// Overwrite part of a buffer.
+    pub fn write_buffer(&self, buffer_id: u64, offset: u64, data: &[u8]) -> GpuResult<()> {
+        let mut buffers = self.buffers.lock();
+        let buffer = buffers
+            .get_mut(&buffer_id)
+            .ok_or_else(|| unknown("buffer", buffer_id))?;
+        let range = byte_range(offset, Some(data.len() as u64), buffer.len())?;
+        buffer[range].copy_from_slice(data);
+        Ok(())
+    }
+
+    /// Read back the contents of a buffer.
+    pub fn read_buffer(&self, buffer_id: u64) -> Option<Vec<u8>> {
+        self.buffers.lock().get(&buffer_id).cloned()
+    }
+
+    /// Release a buffer.
+    pub fn destroy_buffer(&self, buffer_id: u64) {
+        self.buffers.lock().remove(&buffer_id);
+    }
+
+    /// Create a pipeline and return its ID.
+    pub fn create_pipeline(&self, pipeline: HeadlessPipeline) -> u64 {
+        let id = self.allocate_id();
+        self.pipelines.lock().insert(id, pipeline);
+        id
+    }
+
+    /// Create an offscreen surface cleared to transparent black.
+    pub fn create_surface(&self, width: u32, height: u32) -> GpuResult<HeadlessSurface> {
+        if width == 0 || height == 0 || width > i32::MAX as u32 || height > i32::MAX as u32 {
+            return Err(GpuError::SurfaceCreation(format!(
+                "invalid surface size {width}x{height}"
+            )));
+        }
+        Ok(HeadlessSurface {
+            buffer: PixelBuffer::new(width as i32, height as i32),
+        })
+    }
+
+    /// Execute a command buffer against a surface.
+    ///
+    /// Commands run in order and complete before this returns. Viewport,
+    /// bind group, blend constant, stencil and debug commands have no effect;
+    /// texture copies are rejected since the backend has no textures.
+    pub fn submit(&self, surface: &mut HeadlessSurface, commands: &CommandBuffer) -> GpuResult<()> {
+        let mut buffers = self.buffers.lock();
+        let pipelines = self.pipelines.lock();
+        let mut state = PassState::new(surface);
+
+        for command in commands.commands() {
+            match command {
+                DrawCommand::Clear { color } => surface.buffer.clear(*color),
+                DrawCommand::SetPipeline { pipeline_id } => {
+                    let pipeline = pipelines
+                        .get(pipeline_id)
+                        .ok_or_else(|| unknown("pipeline", *pipeline_id))?;
+                    state.pipeline = Some(*pipeline);
+                }
+                DrawCommand::SetVertexBuffer {
+                    slot,
+                    buffer_id,
+                    offset,
+                    size,
+                } => {
+                    if *slot != 0 {
+                        return Err(GpuError::OperationFailed(format!(
+                            "vertex buffer slot {slot} is not supported"
+                        )));
+                    }
+                    let buffer = buffers
+                        .get(buffer_id)
+                        .ok_or_else(|| unknown("buffer", *buffer_id))?;
+                    let range = byte_range(*offset, *size, buffer.len())?;
+                    state.vertices = Some((*buffer_id, range));
+                }
+                DrawCommand::SetIndexBuffer {
+                    buffer_id,
+                    format,
+                    offset,
+                    size,
+                } => {
+                    let buffer = buffers
+                        .get(buffer_id)
+                        .ok_or_else(|| unknown("buffer", *buffer_id))?;
+                    let range = byte_range(*offset, *size, buffer.len())?;
+                    state.indices = Some((*buffer_id, range, *format));
+                }
+                DrawCommand::SetScissor { rect } => {
+                    state.clip = Rect::from_xywh(
+                        rect.x as f32,
+                        rect.y as f32,
+                        rect.width as f32,
+                        rect.height as f32,
+                    );
+                }
+                DrawCommand::Draw {
+                    vertex_count,
+                    instance_count,
+                    first_vertex,
+                    ..
+                } => {
+                    let indices: Vec<u32> =
+                        (*first_vertex..first_vertex.saturating_add(*vertex_count)).collect();
+                    for _ in 0..*instance_count {
+                        state.draw_triangles(&buffers, &indices, 0, &mut surface.buffer)?;
+                    }
+                }
+                DrawCommand::DrawIndexed {
+                    index_count,
+                    instance_count,
+                    first_index,
+                    base_vertex,
+                    ..
+                } => {
+                    let indices = state.read_indices(&buffers, *first_index, *index_count)?;
+                    for _ in 0..*instance_count {
+                        state.draw_triangles(
+                            &buffers,
+                            &indices,
+                            *base_vertex,
+                            &mut surface.buffer,
+                        )?;
+                    }
+                }
+                DrawCommand::CopyBufferToBuffer {
+                    src,
+                    src_offset,
+                    dst,
+                    dst_offset,
+                    size,
+                } => {
+                    let source = buffers.get(src).ok_or_else(|| unknown("buffer", *src))?;
+                    let data = source[byte_range(*src_offset, Some(*size), source.len())?].to_vec();
+                    let target = buffers
+                        .get_mut(dst)
+                        .ok_or_else(|| unknown("buffer", *dst))?;
+                    let range = byte_range(*dst_offset, Some(*size), target.len())?;
+                    target[range].copy_from_slice(&data);
+                }
+                DrawCommand::CopyBufferToTexture { .. }
+                | DrawCommand::CopyTextureToBuffer { .. }
+                | DrawCommand::CopyTextureToTexture { .. } => {
+                    return Err(GpuError::OperationFailed(
+                        "textures are not supported by the headless backend".to_string(),
+                    ));
+                }
+                DrawCommand::SetViewport { .. }
+                | DrawCommand::SetBindGroup { .. }
+                | DrawCommand::SetBlendConstant { .. }
+                | DrawCommand::SetStencilReference { .. }
+                | DrawCommand::PushDebugGroup { .. }
+                | DrawCommand::PopDebugGroup
+                | DrawCommand::InsertDebugMarker { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Upload a tessellated mesh and fill it with a solid color.
+    pub fn fill_mesh(
+        &self,
+        surface: &mut HeadlessSurface,
+        mesh: &TessMesh,
+        color: Color,
+    ) -> GpuResult<()> {
+        let vertex_buffer = self.create_vertex_buffer(&mesh.vertices);
+        let index_buffer = self.create_index_buffer(&mesh.indices);
+        let pipeline = self.create_pipeline(HeadlessPipeline::solid(color));
+
+        let mut commands = CommandBuffer::new();
+        commands.record(DrawCommand::SetPipeline {
+            pipeline_id: pipeline,
+        });
+        commands.set_vertex_buffer(0, vertex_buffer, 0, None);
+        commands.set_index_buffer(index_buffer, IndexFormat::Uint32, 0, None);
+        commands.draw_indexed(mesh.indices.len() as u32, 1);
+        let result = self.submit(surface, &commands);
+
+        self.destroy_buffer(vertex_buffer);
+        self.destroy_buffer(index_buffer);
+        self.pipelines.lock().remove(&pipeline);
+        result
+    }
+}
+
+impl GpuContext for HeadlessGpuContext {
+    fn backend_type(&self) -> GpuBackendType {
+        GpuBackendType::Software
+    }
+
+    fn adapter_info(&self) -> &GpuAdapterInfo {
+        &self.adapter_info
+    }
+
+    fn flush(&self) {
+        // Submissions execute synchronously.
+    }
+
+    fn submit_and_wait(&self) {
+        // Submissions execute synchronously.
+    }
+
+    fn is_valid(&self) -> bool {
+        true
+    }
+}
+
+/// Offscreen surface of a [`HeadlessGpuContext`].
+#[derive(Debug, Clone)]
+pub struct HeadlessSurface {
+    buffer: PixelBuffer,
+}
+
+impl HeadlessSurface {
+    /// The pixels rendered so far.
+    pub fn pixel_buffer(&self) -> &PixelBuffer {
+        &self.buffer
+    }
+
+    /// Consume the surface and return its pixels.
+    pub fn into_pixel_buffer(self) -> PixelBuffer {
+        self.buffer
+    }
+}
+
+impl GpuSurface for HeadlessSurface {
+    fn width(&self) -> u32 {
+        self.buffer.width as u32
+    }
+
+    fn height(&self) -> u32 {
+        self.buffer.height as u32
+    }
+
+    fn format(&self) -> TextureFormat {
+        TextureFormat::Rgba8Unorm
+    }
+
+    fn sample_count(&self) -> u32 {
+        1
+    }
+
+    fn clear(&mut self, color: Color) {
+        self.buffer.clear(color);
+    }
+
+    fn present(&mut self) {}
+
+    fn read_pixels(&self, dst: &mut [u8], dst_row_bytes: usize) -> bool {
+        let row_len = self.buffer.width as usize * 4;
+        let rows = self.buffer.height as usize;
+        if dst_row_bytes < row_len || dst.len() < dst_row_bytes * (rows - 1) + row_len {
+            return false;
+        }
+        for (y, row) in self
+            .buffer
+            .pixels
+            .chunks(self.buffer.stride)
+            .take(rows)
+            .enumerate()
+        {
+            let start = y * dst_row_bytes;
+            dst[start..start + row_len].copy_from_slice(&row[..row_len]);
+        }
+        true
+    }
+
+    fn flush(&mut self) {}
+}
+
+/// Bindings of the pass being executed.
+struct PassState {
+    pipeline: Option<HeadlessPipeline>,
+    vertices: Option<(u64, std::ops::Range<usize>)>,
+    indices: Option<(u64, std::ops::Range<usize>, IndexFormat)>,
+    clip: Rect,
+}
+
+impl PassState {
+    fn new(surface: &HeadlessSurface) -> Self {
+        Self {
+            pipeline: None,
+            vertices: None,
+            indices: None,
+            clip: Rect::from_xywh(
+                0.0,
+                0.0,
+                surface.buffer.width as f32,
+                surface.buffer.height as f32,
+            ),
+        }
+    }
+
+    fn read_indices(
+        &self,
+        buffers: &HashMap<u64, Vec<u8>>,
+        first_index: u32,
+        index_count: u32,
+    ) -> GpuResult<Vec<u32>> {
+        let (buffer_id, range, format) = self
+            .indices
+            .clone()
+            .ok_or_else(|| GpuError::OperationFailed("no index buffer bound".to_string()))?;
+        let data = buffers
+            .get(&buffer_id)
+            .ok_or_else(|| unknown("buffer", buffer_id))?
+            .get(range)
+            .unwrap_or_default();
+        let size = match format {
+            IndexFormat::Uint16 => 2,
+            IndexFormat::Uint32 => 4,
+        };
+        let start = first_index as usize * size;
+        let end = start + index_count as usize * size;
+        let bytes = data.get(start..end).ok_or_else(|| {
+            GpuError::OperationFailed("draw reads past the end of the index buffer".to_string())
+        })?;
+        Ok(bytes
+            .chunks_exact(size)
+            .map(|b| match format {
+                IndexFormat::Uint16 => u16::from_le_bytes([b[0], b[1]]) as u32,
+                IndexFormat::Uint32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            })
+            .collect())
+    }
+
+    fn draw_triangles(
+        &self,
+        buffers: &HashMap<u64, Vec<u8>>,
+        indices: &[u32],
+        base_vertex: i32,
+        target: &mut PixelBuffer,
+    ) -> GpuResult<()> {
+        let pipeline = self
+            .pipeline
+            .ok_or_else(|| GpuError::OperationFailed("no pipeline bound".to_string()))?;
+        let (buffer_id, range) = self
+            .vertices
+            .clone()
+            .ok_or_else(|| GpuError::OperationFailed("no vertex buffer bound".to_string()))?;
+        let data = buffers
+            .get(&buffer_id)
+            .ok_or_else(|| unknown("buffer", buffer_id))?
+            .get(range)
+            .unwrap_or_default();
+        let vertex = |index: u32| -> GpuResult<(f32, f32)> {
+            let bytes = usize::try_from(i64::from(index) + i64::from(base_vertex))
+                .ok()
+                .and_then(|i| i.checked_mul(VERTEX_STRIDE))
+                .and_then(|start| data.get(start..start + 8))
+                .ok_or_else(|| {
+                    GpuError::OperationFailed(
+                        "draw reads past the end of the vertex buffer".to_string(),
+                    )
+                })?;
+            Ok((
+                f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+                f32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            ))
+        };
+
+        let mut paint = Paint::new();
+        paint
+            .set_color32(pipeline.color)
+            .set_blend_mode(pipeline.blend_mode)
+            .set_anti_alias(false);
+        let mut rasterizer = Rasterizer::new(target);
+        rasterizer.set_clip(self.clip);
+
+        for triangle in indices.chunks_exact(3) {
+            let (x0, y0) = vertex(triangle[0])?;
+            let (x1, y1) = vertex(triangle[1])?;
+            let (x2, y2) = vertex(triangle[2])?;
+            let mut builder = PathBuilder::new();
+            builder
+                .move_to(x0, y0)
+                .line_to(x1, y1)
+                .line_to(x2, y2)
+                .close();
+            rasterizer.draw_path(&builder.build(), &paint);
+        }
+        Ok(())
+    }
+}
+
+fn unknown(kind: &str, id: u64) -> GpuError {
+    GpuError::OperationFailed(format!("unknown {kind} {id}"))
+}
+
+/// Resolve an `offset`/`size` pair against a buffer of `len` bytes.
+fn byte_range(offset: u64, size: Option<u64>, len: usize) -> GpuResult<std::ops::Range<usize>> {
+    let start = offset as usize;
+    let end = match size {
+        Some(size) => start.checked_add(size as usize),
+        None => Some(len),
+    };
+    match end {
+        Some(end) if start <= end && end <= len => Ok(start..end),
+        _ => Err(GpuError::OperationFailed(format!(
+            "range {offset}..{} is outside a buffer of {len} bytes",
+            end.unwrap_or(usize::MAX)
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tessellation::PathTessellator;
+
+    fn triangle_path() -> skia_rs_path::Path {
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(4.5, 3.0)
+            .line_to(27.0, 9.25)
+            .line_to(11.0, 28.5)
+            .close();
+        builder.build()
+    }
+
+    #[test]
+    fn test_headless_triangle_matches_raster_fill() {
+        let context = <dyn GpuContext>::new_headless();
+        assert_eq!(context.backend_type(), GpuBackendType::Software);
+        assert_eq!(context.adapter_info().device_type, GpuDeviceType::Cpu);
+
+        let color = Color::from_argb(255, 200, 40, 90);
+        let vertices = [
+            TessVertex::new(4.5, 3.0, 0.0, 0.0),
+            TessVertex::new(27.0, 9.25, 0.0, 0.0),
+            TessVertex::new(11.0, 28.5, 0.0, 0.0),
+        ];
+        let vertex_buffer = context.create_vertex_buffer(&vertices);
+        let pipeline = context.create_pipeline(HeadlessPipeline::solid(color));
+
+        let mut surface = context.create_surface(32, 32).unwrap();
+        let mut commands = CommandBuffer::new();
+        commands.clear(Color::WHITE);
+        commands.record(DrawCommand::SetPipeline {
+            pipeline_id: pipeline,
+        });
+        commands.set_vertex_buffer(0, vertex_buffer, 0, None);
+        commands.draw(3, 1);
+        context.submit(&mut surface, &commands).unwrap();
+
+        let mut expected = PixelBuffer::new(32, 32);
+        expected.clear(Color::WHITE);
+        let mut paint = Paint::new();
+        paint.set_color32(color).set_anti_alias(false);
+        Rasterizer::new(&mut expected).draw_path(&triangle_path(), &paint);
+
+        let actual = surface.pixel_buffer();
+        assert_eq!(actual.pixels, expected.pixels);
+        assert_eq!(actual.get_pixel(12, 12), Some(color));
+
+        let mut read = vec![0; 32 * 32 * 4];
+        assert!(surface.read_pixels(&mut read, 32 * 4));
+        assert_eq!(read, expected.pixels);
+    }
+
+    #[test]
+    fn test_headless_mesh_covers_shared_edges_once() {
+        let context = HeadlessGpuContext::new();
+        let mut builder = PathBuilder::new();
+        builder.add_rect(&Rect::from_xywh(2.0, 2.0, 12.0, 12.0));
+        let mesh = PathTessellator::new().tessellate_fill(&builder.build());
+        assert!(mesh.triangle_count() >= 2);
+
+        let mut surface = context.create_surface(16, 16).unwrap();
+        let color = Color::from_argb(128, 0, 0, 255);
+        context.fill_mesh(&mut surface, &mesh, color).unwrap();
+
+        // Translucent triangles that overlapped would blend twice along the
+        // diagonal; every covered pixel must carry the color exactly once.
+        let buffer = surface.pixel_buffer();
+        for y in 2..14 {
+            for x in 2..14 {
+                let alpha = buffer.get_pixel(x, y).unwrap().alpha();
+                assert!((127..=128).contains(&alpha), "pixel {x},{y} alpha {alpha}");
+            }
+        }
+        assert_eq!(buffer.get_pixel(0, 0), Some(Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn test_headless_submit_errors() {
+        let context = HeadlessGpuContext::new();
+        let mut surface = context.create_surface(4, 4).unwrap();
+        assert!(context.create_surface(0, 4).is_err());
+
+        let mut commands = CommandBuffer::new();
+        commands.draw(3, 1);
+        assert!(context.submit(&mut surface, &commands).is_err());
+
+        let mut commands = CommandBuffer::new();
+        commands.set_vertex_buffer(0, 99, 0, None);
+        assert!(context.submit(&mut surface, &commands).is_err());
+
+        let buffer = context.create_buffer(&[0; 8]);
+        assert!(context.write_buffer(buffer, 4, &[1; 4]).is_ok());
+        assert!(context.write_buffer(buffer, 6, &[1; 4]).is_err());
+        assert_eq!(
+            context.read_buffer(buffer).unwrap(),
+            [0, 0, 0, 0, 1, 1, 1, 1]
+        );
+    }
+}
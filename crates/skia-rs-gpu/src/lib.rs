@@ -19,6 +19,7 @@
 //! - **Image Tiling**: Tile modes for image rendering
 //! - **MSAA Support**: Multi-sample anti-aliasing
 //! - **SDF Rendering**: Signed distance field for resolution-independent shapes
+//! - **Headless Backend**: Software execution of command buffers without a device
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
@@ -29,6 +30,7 @@ pub mod context;
 pub mod debug;
 pub mod glyph_cache;
 pub mod gradient;
+pub mod headless;
 pub mod msaa;
 pub mod pipeline;
 pub mod sdf;
@@ -56,6 +58,7 @@ pub use command::*;
 pub use context::*;
 pub use glyph_cache::*;
 pub use gradient::*;
+pub use headless::*;
 pub use msaa::*;
 pub use pipeline::*;
 pub use sdf::*;
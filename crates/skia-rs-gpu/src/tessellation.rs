@@ -2,9 +2,16 @@
 //!
 //! This module provides algorithms for converting vector paths into triangle meshes
 //! suitable for GPU rendering.
+//!
+//! Fills are flattened to line segments within a tolerance and split into
+//! horizontal bands at every vertex and edge crossing. Inside each band the
+//! edges no longer cross, so the spans the fill rule selects are trapezoids,
+//! each emitted as at most two triangles. This handles concave, overlapping
+//! and self-intersecting contours under both the winding and even-odd rules.
 
 use skia_rs_core::{Point, Rect, Scalar};
-use skia_rs_path::{Path, PathBuilder, PathElement};
+use skia_rs_path::{FillType, Path, PathElement, StrokeParams, stroke_to_fill};
+use std::collections::HashMap;
 
 /// A vertex in a tessellated mesh.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -143,6 +150,17 @@ impl TessQuality {
         tolerance: 0.1,
         max_subdivisions: 15,
     };
+
+    /// Quality flattening curves to within `tolerance`.
+    ///
+    /// The subdivision cap is high enough that the tolerance, not the cap,
+    /// decides how finely curves are flattened.
+    pub fn with_tolerance(tolerance: Scalar) -> Self {
+        Self {
+            tolerance: tolerance.max(0.001),
+            max_subdivisions: 256,
+        }
+    }
 }
 
 /// Path tessellator.
@@ -170,8 +188,13 @@ impl PathTessellator {
     }
 
     /// Tessellate a path for filling.
+    ///
+    /// Open contours are closed implicitly. The path's fill type selects the
+    /// covered regions; inverse fill types are tessellated as their
+    /// non-inverse counterparts since a mesh cannot cover the unbounded
+    /// outside.
     pub fn tessellate_fill(&mut self, path: &Path) -> TessMesh {
-        let mut mesh = TessMesh::new();
+        let mut contours = Vec::new();
         self.contour_points.clear();
 
         let mut current_point = Point::zero();
@@ -180,7 +203,7 @@ impl PathTessellator {
         for element in path.iter() {
             match element {
                 PathElement::Move(p) => {
-                    self.flush_contour(&mut mesh);
+                    self.flush_contour(&mut contours);
                     current_point = p;
                     contour_start = p;
                     self.contour_points.push(p);
@@ -202,18 +225,17 @@ impl PathTessellator {
                     current_point = end;
                 }
                 PathElement::Close => {
-                    if current_point != contour_start {
-                        self.contour_points.push(contour_start);
-                    }
-                    self.flush_contour(&mut mesh);
+                    self.flush_contour(&mut contours);
                     current_point = contour_start;
                 }
             }
         }
 
         // Flush any remaining contour
-        self.flush_contour(&mut mesh);
+        self.flush_contour(&mut contours);
 
+        let mut mesh = TessMesh::new();
+        triangulate_fill(&contours, path.fill_type(), &mut mesh);
         mesh
     }
 
@@ -285,8 +307,10 @@ impl PathTessellator {
             return;
         }
 
-        // Subdivide the conic for better approximation
-        let steps = (self.quality.max_subdivisions as usize).max(8);
+        // Subdivide the conic; its control point pulls the curve in by w / (1 + w)
+        let d = Self::point_to_line_distance(p1, p0, p2) * 2.0 * w / (1.0 + w);
+        let steps = ((d / self.quality.tolerance).sqrt().ceil() as u32)
+            .clamp(1, self.quality.max_subdivisions.max(1));
         for i in 1..=steps {
             let t = i as Scalar / steps as Scalar;
             let p = Self::eval_conic(p0, p1, p2, w, t);
@@ -369,34 +393,12 @@ impl PathTessellator {
         num / len_sq.sqrt()
     }
 
-    /// Flush current contour for fill tessellation using ear clipping.
-    fn flush_contour(&mut self, mesh: &mut TessMesh) {
-        if self.contour_points.len() < 3 {
-            self.contour_points.clear();
-            return;
+    /// Move the current contour into `contours` for fill tessellation.
+    fn flush_contour(&mut self, contours: &mut Vec<Vec<Point>>) {
+        let points = std::mem::take(&mut self.contour_points);
+        if points.len() >= 3 {
+            contours.push(points);
         }
-
-        // Simple ear clipping triangulation
-        let vertices: Vec<TessVertex> = self
-            .contour_points
-            .iter()
-            .map(|p| TessVertex::from_point(*p))
-            .collect();
-
-        let base_idx = mesh.vertices.len() as TessIndex;
-        mesh.vertices.extend(vertices);
-
-        // Triangulate using fan (works for convex, approximation for concave)
-        let n = self.contour_points.len();
-        for i in 1..(n - 1) {
-            mesh.add_triangle(
-                base_idx,
-                base_idx + i as TessIndex,
-                base_idx + (i + 1) as TessIndex,
-            );
-        }
-
-        self.contour_points.clear();
     }
 
     /// Flush current contour for stroke tessellation.
@@ -492,6 +494,145 @@ impl Default for PathTessellator {
     }
 }
 
+/// Tessellate a path's fill into an indexed triangle mesh.
+///
+/// Curves are flattened to within `tolerance` and the path's fill rule
+/// decides which regions are covered. No two triangles overlap, so the mesh
+/// can be drawn with blending in a single pass.
+pub fn tessellate_fill(path: &Path, tolerance: Scalar) -> TessMesh {
+    PathTessellator::with_quality(TessQuality::with_tolerance(tolerance)).tessellate_fill(path)
+}
+
+/// Tessellate a stroked path into an indexed triangle mesh.
+///
+/// The stroke is outlined with its caps, joins and dashes by
+/// [`stroke_to_fill`] and the outline is filled as by [`tessellate_fill`].
+pub fn tessellate_stroke(path: &Path, params: &StrokeParams<'_>, tolerance: Scalar) -> TessMesh {
+    match stroke_to_fill(path, params) {
+        Some(outline) => tessellate_fill(&outline, tolerance),
+        None => TessMesh::new(),
+    }
+}
+
+/// A flattened contour edge, oriented from top to bottom.
+struct SweepEdge {
+    top: Point,
+    bottom: Point,
+    /// +1 if the contour runs downwards along the edge, -1 otherwise.
+    winding: i32,
+}
+
+impl SweepEdge {
+    /// The edge's x coordinate at `y`, exact at its endpoints.
+    fn x_at(&self, y: Scalar) -> Scalar {
+        if y <= self.top.y {
+            self.top.x
+        } else if y >= self.bottom.y {
+            self.bottom.x
+        } else {
+            let t = (y - self.top.y) / (self.bottom.y - self.top.y);
+            self.top.x + (self.bottom.x - self.top.x) * t
+        }
+    }
+}
+
+/// Triangulate closed contours under `fill_type`, appending to `mesh`.
+fn triangulate_fill(contours: &[Vec<Point>], fill_type: FillType, mesh: &mut TessMesh) {
+    let mut edges = Vec::new();
+    for contour in contours {
+        for (i, &a) in contour.iter().enumerate() {
+            let b = contour[(i + 1) % contour.len()];
+            if a.y == b.y || !(a.is_finite() && b.is_finite()) {
+                continue;
+            }
+            edges.push(if a.y < b.y {
+                SweepEdge {
+                    top: a,
+                    bottom: b,
+                    winding: 1,
+                }
+            } else {
+                SweepEdge {
+                    top: b,
+                    bottom: a,
+                    winding: -1,
+                }
+            });
+        }
+    }
+    if edges.is_empty() {
+        return;
+    }
+    edges.sort_by(|a, b| a.top.y.total_cmp(&b.top.y));
+
+    // Band boundaries: every endpoint and every crossing of two edges.
+    let mut ys: Vec<Scalar> = edges.iter().flat_map(|e| [e.top.y, e.bottom.y]).collect();
+    for (i, a) in edges.iter().enumerate() {
+        for b in &edges[i + 1..] {
+            if b.top.y >= a.bottom.y {
+                break;
+            }
+            let y0 = b.top.y;
+            let y1 = a.bottom.y.min(b.bottom.y);
+            let d0 = a.x_at(y0) - b.x_at(y0);
+            let d1 = a.x_at(y1) - b.x_at(y1);
+            if (d0 < 0.0 && d1 > 0.0) || (d0 > 0.0 && d1 < 0.0) {
+                ys.push(y0 + (y1 - y0) * d0 / (d0 - d1));
+            }
+        }
+    }
+    ys.sort_by(Scalar::total_cmp);
+    ys.dedup();
+
+    let even_odd = matches!(fill_type, FillType::EvenOdd | FillType::InverseEvenOdd);
+    let mut indices: HashMap<(u32, u32), TessIndex> = HashMap::new();
+    let mut vertex = |mesh: &mut TessMesh, x: Scalar, y: Scalar| {
+        *indices
+            .entry((x.to_bits(), y.to_bits()))
+            .or_insert_with(|| mesh.add_vertex(TessVertex::new(x, y, 0.0, 0.0)))
+    };
+
+    let mut next_edge = 0;
+    let mut active: Vec<&SweepEdge> = Vec::new();
+    let mut crossings: Vec<(Scalar, Scalar, i32)> = Vec::new();
+    for band in ys.windows(2) {
+        let (y0, y1) = (band[0], band[1]);
+        while next_edge < edges.len() && edges[next_edge].top.y <= y0 {
+            active.push(&edges[next_edge]);
+            next_edge += 1;
+        }
+        active.retain(|e| e.bottom.y > y0);
+
+        crossings.clear();
+        crossings.extend(active.iter().map(|e| (e.x_at(y0), e.x_at(y1), e.winding)));
+        crossings.sort_by(|a, b| (a.0 + a.1).total_cmp(&(b.0 + b.1)));
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].2;
+            let inside = if even_odd {
+                winding & 1 != 0
+            } else {
+                winding != 0
+            };
+            if !inside {
+                continue;
+            }
+            let ((left_top, left_bottom, _), (right_top, right_bottom, _)) = (pair[0], pair[1]);
+            let a = vertex(mesh, left_top, y0);
+            let c = vertex(mesh, right_bottom, y1);
+            if right_top > left_top {
+                let b = vertex(mesh, right_top, y0);
+                mesh.add_triangle(a, b, c);
+            }
+            if right_bottom > left_bottom {
+                let d = vertex(mesh, left_bottom, y1);
+                mesh.add_triangle(a, c, d);
+            }
+        }
+    }
+}
+
 /// Tessellate a rectangle.
 pub fn tessellate_rect(rect: Rect) -> TessMesh {
     let mut mesh = TessMesh::with_capacity(4, 6);
@@ -610,6 +751,7 @@ pub fn tessellate_circle(center: Point, radius: Scalar, quality: TessQuality) ->
 #[cfg(test)]
 mod tests {
     use super::*;
+    use skia_rs_path::PathBuilder;
 
     #[test]
     fn test_tess_vertex() {
@@ -692,6 +834,90 @@ mod tests {
         assert!(mesh.vertices.len() >= 6);
     }
 
+    fn mesh_area(mesh: &TessMesh) -> f32 {
+        mesh.indices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[t[i] as usize].position);
+                ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_tessellate_fill_square() {
+        let mut builder = PathBuilder::new();
+        builder.add_rect(&Rect::from_xywh(10.0, 20.0, 40.0, 40.0));
+        let mesh = tessellate_fill(&builder.build(), 0.25);
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_eq!(mesh.vertices.len(), 4);
+        assert!((mesh_area(&mesh) - 1600.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_tessellate_fill_tolerance() {
+        let mut builder = PathBuilder::new();
+        builder.add_circle(50.0, 50.0, 40.0);
+        let path = builder.build();
+        let coarse = tessellate_fill(&path, 1.0);
+        let fine = tessellate_fill(&path, 0.05);
+        assert!(fine.vertices.len() > coarse.vertices.len());
+
+        let circle = std::f32::consts::PI * 40.0 * 40.0;
+        assert!((mesh_area(&fine) - circle).abs() < (mesh_area(&coarse) - circle).abs());
+        assert!((mesh_area(&fine) - circle).abs() / circle < 0.01);
+    }
+
+    #[test]
+    fn test_tessellate_fill_rules() {
+        // Two same-direction squares, the inner one inside the outer.
+        let mut builder = PathBuilder::new();
+        builder
+            .add_rect(&Rect::from_xywh(0.0, 0.0, 30.0, 30.0))
+            .add_rect(&Rect::from_xywh(10.0, 10.0, 10.0, 10.0));
+        let winding = builder.build();
+        assert!((mesh_area(&tessellate_fill(&winding, 0.25)) - 900.0).abs() < 1e-3);
+
+        let mut even_odd = winding.clone();
+        even_odd.set_fill_type(FillType::EvenOdd);
+        assert!((mesh_area(&tessellate_fill(&even_odd, 0.25)) - 800.0).abs() < 1e-3);
+
+        // A concave arrow and a self-intersecting bow tie.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(20.0, 10.0)
+            .line_to(0.0, 20.0)
+            .line_to(5.0, 10.0)
+            .close();
+        assert!((mesh_area(&tessellate_fill(&builder.build(), 0.25)) - 150.0).abs() < 1e-3);
+
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(20.0, 20.0)
+            .line_to(20.0, 0.0)
+            .line_to(0.0, 20.0)
+            .close();
+        assert!((mesh_area(&tessellate_fill(&builder.build(), 0.25)) - 200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_tessellate_stroke_params() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(10.0, 10.0).line_to(50.0, 10.0);
+        let path = builder.build();
+
+        let mesh = tessellate_stroke(&path, &StrokeParams::new(4.0), 0.25);
+        assert!((mesh_area(&mesh) - 160.0).abs() < 1e-2);
+
+        let square = StrokeParams::new(4.0).with_cap(skia_rs_path::StrokeCap::Square);
+        let mesh = tessellate_stroke(&path, &square, 0.25);
+        assert!((mesh_area(&mesh) - 176.0).abs() < 1e-2);
+
+        assert!(tessellate_stroke(&path, &StrokeParams::new(0.0), 0.25).is_empty());
+    }
+
     #[test]
     fn test_quality_presets() {
         assert!(TessQuality::LOW.tolerance > TessQuality::HIGH.tolerance);
@@ -29,6 +29,9 @@
 //!
 //! // Get pixel data
 //! const pixels = surface.getPixels(); // Buffer
+//!
+//! // Or encode it
+//! const png = surface.encode('png'); // Buffer
 //! ```
 
 #![deny(clippy::all)]
@@ -37,8 +40,9 @@ use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
 use skia_rs_canvas::Surface as RsSurface;
-use skia_rs_core::{Color, Matrix as RsMatrix, Point as RsPoint, Rect as RsRect};
-use skia_rs_paint::{Paint as RsPaint, Style as RsStyle};
+use skia_rs_codec::{EncoderQuality, Image as RsImage, ImageFormat};
+use skia_rs_core::{Color, Color4f, Matrix as RsMatrix, Point as RsPoint, Rect as RsRect};
+use skia_rs_paint::{Paint as RsPaint, ShaderRef, Style as RsStyle, TileMode, shaders};
 use skia_rs_path::{Path as RsPath, PathBuilder as RsPathBuilder};
 
 // =============================================================================
@@ -230,6 +234,12 @@ pub struct Matrix {
     inner: RsMatrix,
 }
 
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[napi]
 impl Matrix {
     /// Create an identity matrix.
@@ -311,6 +321,12 @@ pub struct Paint {
     inner: RsPaint,
 }
 
+impl Default for Paint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[napi]
 impl Paint {
     /// Create a new paint with default settings.
@@ -388,14 +404,100 @@ impl Paint {
     /// Get alpha (0-255).
     #[napi]
     pub fn get_alpha(&self) -> u32 {
-        self.inner.alpha() as u32
+        (self.inner.alpha() * 255.0).round() as u32
     }
 
     /// Set alpha (0-255).
     #[napi]
     pub fn set_alpha(&mut self, alpha: u32) {
-        self.inner.set_alpha(alpha as u8);
+        self.inner.set_alpha(alpha.min(255) as f32 / 255.0);
+    }
+
+    /// Fill with a linear gradient from (x0, y0) to (x1, y1).
+    ///
+    /// `colors` are ARGB integers; `stops` (0-1, one per color) default to
+    /// even spacing. `tileMode` is "clamp" (default), "repeat", "mirror" or
+    /// "decal".
+    #[napi]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_linear_gradient(
+        &mut self,
+        x0: f64,
+        y0: f64,
+        x1: f64,
+        y1: f64,
+        colors: Vec<u32>,
+        stops: Option<Vec<f64>>,
+        tile_mode: Option<String>,
+    ) -> Result<()> {
+        let tile_mode = parse_tile_mode(tile_mode.as_deref())?;
+        let (colors, stops) = gradient_stops(&colors, stops.as_deref());
+        let shader = shaders::linear_gradient(
+            RsPoint::new(x0 as f32, y0 as f32),
+            RsPoint::new(x1 as f32, y1 as f32),
+            &colors,
+            stops.as_deref(),
+            tile_mode,
+        );
+        self.set_gradient(shader)
+    }
+
+    /// Fill with a radial gradient around (cx, cy).
+    ///
+    /// Arguments after `radius` are as for `setLinearGradient`.
+    #[napi]
+    pub fn set_radial_gradient(
+        &mut self,
+        cx: f64,
+        cy: f64,
+        radius: f64,
+        colors: Vec<u32>,
+        stops: Option<Vec<f64>>,
+        tile_mode: Option<String>,
+    ) -> Result<()> {
+        let tile_mode = parse_tile_mode(tile_mode.as_deref())?;
+        let (colors, stops) = gradient_stops(&colors, stops.as_deref());
+        let shader = shaders::radial_gradient(
+            RsPoint::new(cx as f32, cy as f32),
+            radius as f32,
+            &colors,
+            stops.as_deref(),
+            tile_mode,
+        );
+        self.set_gradient(shader)
     }
+
+    /// Remove any gradient so fills use the solid color again.
+    #[napi]
+    pub fn clear_shader(&mut self) {
+        self.inner.set_shader(None);
+    }
+
+    fn set_gradient(&mut self, shader: Option<ShaderRef>) -> Result<()> {
+        let shader = shader.ok_or_else(|| {
+            Error::from_reason("Invalid gradient: colors must be non-empty and stops must match")
+        })?;
+        self.inner.set_shader(Some(shader));
+        Ok(())
+    }
+}
+
+/// Parse a tile mode name, defaulting to clamp.
+fn parse_tile_mode(tile_mode: Option<&str>) -> Result<TileMode> {
+    match tile_mode.map(str::to_ascii_lowercase).as_deref() {
+        None | Some("clamp") => Ok(TileMode::Clamp),
+        Some("repeat") => Ok(TileMode::Repeat),
+        Some("mirror") => Ok(TileMode::Mirror),
+        Some("decal") => Ok(TileMode::Decal),
+        Some(other) => Err(Error::from_reason(format!("Unknown tile mode: {other}"))),
+    }
+}
+
+/// Convert ARGB colors and stops to the types the gradient shaders take.
+fn gradient_stops(colors: &[u32], stops: Option<&[f64]>) -> (Vec<Color4f>, Option<Vec<f32>>) {
+    let colors = colors.iter().map(|&c| Color(c).to_color4f()).collect();
+    let stops = stops.map(|s| s.iter().map(|&p| p as f32).collect());
+    (colors, stops)
 }
 
 // =============================================================================
@@ -408,6 +510,12 @@ pub struct PathBuilder {
     inner: RsPathBuilder,
 }
 
+impl Default for PathBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[napi]
 impl PathBuilder {
     /// Create a new path builder.
@@ -441,22 +549,9 @@ impl PathBuilder {
 
     /// Cubic bezier curve.
     #[napi]
-    pub fn cubic_to(
-        &mut self,
-        c1x: f64,
-        c1y: f64,
-        c2x: f64,
-        c2y: f64,
-        x: f64,
-        y: f64,
-    ) -> &Self {
+    pub fn cubic_to(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64) -> &Self {
         self.inner.cubic_to(
-            c1x as f32,
-            c1y as f32,
-            c2x as f32,
-            c2y as f32,
-            x as f32,
-            y as f32,
+            c1x as f32, c1y as f32, c2x as f32, c2y as f32, x as f32, y as f32,
         );
         self
     }
@@ -543,6 +638,12 @@ pub struct Path {
     inner: RsPath,
 }
 
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[napi]
 impl Path {
     /// Create an empty path.
@@ -573,6 +674,54 @@ impl Path {
     }
 }
 
+// =============================================================================
+// Image
+// =============================================================================
+
+/// An immutable decoded image.
+#[napi]
+pub struct Image {
+    inner: RsImage,
+}
+
+#[napi]
+impl Image {
+    /// Decode an image from encoded bytes (PNG, JPEG, GIF, WebP, ...).
+    #[napi(factory)]
+    pub fn decode(buffer: Buffer) -> Result<Self> {
+        skia_rs_codec::decode_image(&buffer)
+            .map(|inner| Self { inner })
+            .map_err(|e| Error::from_reason(format!("Failed to decode image: {e}")))
+    }
+
+    /// Width in pixels.
+    #[napi(getter)]
+    pub fn width(&self) -> i32 {
+        self.inner.width()
+    }
+
+    /// Height in pixels.
+    #[napi(getter)]
+    pub fn height(&self) -> i32 {
+        self.inner.height()
+    }
+}
+
+/// Parse an encoder format name such as `"png"` or `"jpeg"`.
+fn parse_image_format(format: &str) -> Result<ImageFormat> {
+    match format.to_ascii_lowercase().as_str() {
+        "png" => Ok(ImageFormat::Png),
+        "jpeg" | "jpg" => Ok(ImageFormat::Jpeg),
+        "webp" => Ok(ImageFormat::WebP),
+        "bmp" => Ok(ImageFormat::Bmp),
+        "tiff" | "tif" => Ok(ImageFormat::Tiff),
+        "qoi" => Ok(ImageFormat::Qoi),
+        _ => Err(Error::from_reason(format!(
+            "Unsupported image format: {format}"
+        ))),
+    }
+}
+
 // =============================================================================
 // Surface
 // =============================================================================
@@ -626,7 +775,11 @@ impl Surface {
     #[napi]
     pub fn draw_circle(&mut self, cx: f64, cy: f64, radius: f64, paint: &Paint) {
         let mut canvas = self.inner.raster_canvas();
-        canvas.draw_circle(RsPoint::new(cx as f32, cy as f32), radius as f32, &paint.inner);
+        canvas.draw_circle(
+            RsPoint::new(cx as f32, cy as f32),
+            radius as f32,
+            &paint.inner,
+        );
     }
 
     /// Draw an oval inscribed in a rectangle.
@@ -664,6 +817,27 @@ impl Surface {
         canvas.draw_point(RsPoint::new(x as f32, y as f32), &paint.inner);
     }
 
+    /// Draw an image with its top-left corner at (x, y).
+    #[napi]
+    pub fn draw_image(&mut self, image: &Image, x: f64, y: f64) {
+        let mut canvas = self.inner.raster_canvas();
+        canvas.draw_image(&image.inner, x as f32, y as f32, None);
+    }
+
+    /// Encode the surface as an image file.
+    ///
+    /// `format` is one of "png", "jpeg", "webp", "bmp", "tiff" or "qoi".
+    /// `quality` (0-100) applies to lossy formats only.
+    #[napi]
+    pub fn encode(&self, format: String, quality: Option<u32>) -> Result<Buffer> {
+        let format = parse_image_format(&format)?;
+        let quality = quality.map(|q| EncoderQuality::new(q.min(100) as u8));
+        self.inner
+            .encode(format, quality)
+            .map(Buffer::from)
+            .map_err(|e| Error::from_reason(format!("Failed to encode surface: {e}")))
+    }
+
     /// Get pixel data as Buffer (RGBA).
     #[napi]
     pub fn get_pixels(&self) -> Buffer {
@@ -0,0 +1,129 @@
+#!/usr/bin/env node
+/**
+ * Smoke tests for the skia-rs Node.js bindings.
+ *
+ * Run with `npm test` after `npm run build:debug`.
+ */
+
+const assert = require('assert');
+const skia = require('./index.js');
+
+const tests = [];
+
+function test(name, fn) {
+    tests.push({ name, fn });
+}
+
+test('encoded PNG round-trips through Image.decode', () => {
+    const surface = new skia.Surface(16, 16);
+    surface.clear(skia.WHITE);
+
+    const paint = new skia.Paint();
+    paint.setColor(skia.RED);
+    surface.drawRect(4, 4, 12, 12, paint);
+
+    const png = surface.encode('png');
+    assert.ok(Buffer.isBuffer(png));
+    assert.deepStrictEqual([...png.subarray(0, 4)], [0x89, 0x50, 0x4e, 0x47]);
+
+    const image = skia.Image.decode(png);
+    assert.strictEqual(image.width, 16);
+    assert.strictEqual(image.height, 16);
+
+    const copy = new skia.Surface(16, 16);
+    copy.drawImage(image, 0, 0);
+    assert.ok(copy.getPixels().equals(surface.getPixels()));
+});
+
+test('drawImage places the image at the given offset', () => {
+    const source = new skia.Surface(2, 2);
+    source.clear(skia.BLUE);
+    const image = skia.Image.decode(source.encode('png'));
+
+    const surface = new skia.Surface(8, 8);
+    surface.drawImage(image, 3, 3);
+
+    const pixels = surface.getPixels();
+    const rowBytes = surface.getRowBytes();
+    const at = (x, y) => [...pixels.subarray(y * rowBytes + x * 4, y * rowBytes + x * 4 + 4)];
+    assert.deepStrictEqual(at(3, 3), [0, 0, 255, 255]);
+    assert.deepStrictEqual(at(4, 4), [0, 0, 255, 255]);
+    assert.deepStrictEqual(at(2, 3), [0, 0, 0, 0]);
+});
+
+test('linear gradient varies across x', () => {
+    const surface = new skia.Surface(64, 8);
+    const paint = new skia.Paint();
+    paint.setLinearGradient(0, 0, 64, 0, [skia.RED, skia.BLUE]);
+    surface.drawRect(0, 0, 64, 8, paint);
+
+    const pixels = surface.getPixels();
+    const red = (x) => pixels[4 * x];
+    const blue = (x) => pixels[4 * x + 2];
+    assert.ok(red(0) > 240 && blue(0) < 15);
+    assert.ok(red(63) < 15 && blue(63) > 240);
+    for (let x = 1; x < 64; x++) {
+        assert.ok(red(x) <= red(x - 1));
+        assert.ok(blue(x) >= blue(x - 1));
+    }
+    assert.strictEqual(pixels[4 * 32 + 3], 255);
+});
+
+test('radial gradient and invalid gradients', () => {
+    const surface = new skia.Surface(32, 32);
+    const paint = new skia.Paint();
+    paint.setRadialGradient(16, 16, 16, [skia.WHITE, skia.BLACK], [0, 1], 'clamp');
+    surface.drawRect(0, 0, 32, 32, paint);
+
+    const pixels = surface.getPixels();
+    const rowBytes = surface.getRowBytes();
+    assert.ok(pixels[16 * rowBytes + 16 * 4] > pixels[16 * rowBytes + 28 * 4]);
+
+    assert.throws(() => paint.setLinearGradient(0, 0, 1, 0, []));
+    assert.throws(() => paint.setLinearGradient(0, 0, 1, 0, [skia.RED], [0, 1]));
+    assert.throws(() => paint.setLinearGradient(0, 0, 1, 0, [skia.RED], null, 'spiral'));
+});
+
+test('gradient tile modes and clearShader', () => {
+    const surface = new skia.Surface(32, 4);
+    const paint = new skia.Paint();
+    const red = (x) => surface.getPixels()[4 * x];
+
+    // Past the end point, clamp holds the last color and repeat wraps
+    paint.setLinearGradient(0, 0, 16, 0, [skia.RED, skia.BLUE], null, 'clamp');
+    surface.drawRect(0, 0, 32, 4, paint);
+    assert.ok(red(24) < 15);
+    paint.setLinearGradient(0, 0, 16, 0, [skia.RED, skia.BLUE], null, 'Repeat');
+    surface.drawRect(0, 0, 32, 4, paint);
+    assert.ok(red(16) > 240);
+
+    paint.clearShader();
+    paint.setColor(skia.GREEN);
+    surface.drawRect(0, 0, 32, 4, paint);
+    assert.deepStrictEqual([...surface.getPixels().subarray(0, 4)], [0, 255, 0, 255]);
+
+    paint.setAlpha(128);
+    assert.strictEqual(paint.getAlpha(), 128);
+    paint.setAlpha(1000);
+    assert.strictEqual(paint.getAlpha(), 255);
+});
+
+test('encode and decode reject bad input', () => {
+    const surface = new skia.Surface(4, 4);
+    assert.throws(() => surface.encode('gif'));
+    assert.throws(() => skia.Image.decode(Buffer.from('not an image')));
+});
+
+let failed = 0;
+for (const { name, fn } of tests) {
+    try {
+        fn();
+        console.log(`ok - ${name}`);
+    } catch (e) {
+        failed++;
+        console.log(`not ok - ${name}`);
+        console.log(e);
+    }
+}
+
+process.exit(failed === 0 ? 0 : 1);
@@ -0,0 +1,66 @@
+//! `no_std` build check for skia-rs.
+//!
+//! This crate is `#![no_std]` and depends on the rasterization crates with
+//! default features off and `libm` on, so building it fails if anything on the
+//! software rendering path reaches for `std`. Build it on its own so workspace
+//! feature unification doesn't turn `std` back on:
+//!
+//! ```sh
+//! cargo build -p skia-rs-nostd-check
+//! cargo build -p skia-rs-nostd-check --target thumbv7em-none-eabihf
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(test)]
+extern crate std;
+
+use skia_rs_canvas::{PixelBuffer, Rasterizer};
+use skia_rs_core::Color;
+use skia_rs_paint::Paint;
+use skia_rs_path::PathBuilder;
+
+/// Fill an anti-aliased triangle into a heap-allocated `width` x `height`
+/// buffer and return it.
+pub fn fill_triangle(width: i32, height: i32, color: Color) -> PixelBuffer {
+    let (w, h) = (width as f32, height as f32);
+    let mut builder = PathBuilder::new();
+    builder
+        .move_to(w * 0.5, h * 0.1)
+        .line_to(w * 0.9, h * 0.9)
+        .line_to(w * 0.1, h * 0.9)
+        .close();
+    let path = builder.build();
+
+    let mut paint = Paint::new();
+    paint.set_color32(color);
+    paint.set_anti_alias(true);
+
+    let mut buffer = PixelBuffer::new(width, height);
+    Rasterizer::new(&mut buffer).fill_path_aa(&path, &paint);
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_triangle() {
+        let red = Color::from_argb(255, 255, 0, 0);
+        let buffer = fill_triangle(32, 32, red);
+
+        assert_eq!(buffer.get_pixel(16, 20), Some(red));
+        assert_eq!(buffer.get_pixel(2, 2).unwrap().alpha(), 0);
+        assert_eq!(buffer.get_pixel(29, 2).unwrap().alpha(), 0);
+        // The slanted edges leave partially covered pixels behind.
+        assert!(
+            buffer
+                .pixels
+                .chunks_exact(4)
+                .any(|px| px[3] > 0 && px[3] < 255)
+        );
+    }
+}
@@ -1,5 +1,9 @@
 //! Blend modes for compositing.
 
+use skia_rs_core::Color4f;
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
+
 /// Porter-Duff and other blend modes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[repr(u8)]
@@ -161,4 +165,225 @@ impl BlendMode {
         let v = *self as u8;
         v >= (BlendMode::Hue as u8) && v <= (BlendMode::Luminosity as u8)
     }
+
+    /// Blend `src` over `dst` with this mode.
+    ///
+    /// Both inputs and the result are unpremultiplied, with components in
+    /// `0..=1`.
+    pub fn blend(&self, src: Color4f, dst: Color4f) -> Color4f {
+        let (sa, sr, sg, sb) = (src.a, src.r, src.g, src.b);
+        let (da, dr, dg, db) = (dst.a, dst.r, dst.g, dst.b);
+
+        let (ra, rr, rg, rb) = match self {
+            BlendMode::Clear => (0.0, 0.0, 0.0, 0.0),
+            BlendMode::Src => (sa, sr, sg, sb),
+            BlendMode::Dst => (da, dr, dg, db),
+            BlendMode::SrcOver => {
+                let a = sa + da * (1.0 - sa);
+                if a > 0.0 {
+                    let r = (sr * sa + dr * da * (1.0 - sa)) / a;
+                    let g = (sg * sa + dg * da * (1.0 - sa)) / a;
+                    let b = (sb * sa + db * da * (1.0 - sa)) / a;
+                    (a, r, g, b)
+                } else {
+                    (0.0, 0.0, 0.0, 0.0)
+                }
+            }
+            BlendMode::DstOver => {
+                let a = da + sa * (1.0 - da);
+                if a > 0.0 {
+                    let r = (dr * da + sr * sa * (1.0 - da)) / a;
+                    let g = (dg * da + sg * sa * (1.0 - da)) / a;
+                    let b = (db * da + sb * sa * (1.0 - da)) / a;
+                    (a, r, g, b)
+                } else {
+                    (0.0, 0.0, 0.0, 0.0)
+                }
+            }
+            BlendMode::SrcIn => {
+                let a = sa * da;
+                (a, sr, sg, sb)
+            }
+            BlendMode::DstIn => {
+                let a = da * sa;
+                (a, dr, dg, db)
+            }
+            BlendMode::SrcOut => {
+                let a = sa * (1.0 - da);
+                (a, sr, sg, sb)
+            }
+            BlendMode::DstOut => {
+                let a = da * (1.0 - sa);
+                (a, dr, dg, db)
+            }
+            BlendMode::SrcATop => {
+                let a = da;
+                let r = sr * da + dr * (1.0 - sa);
+                let g = sg * da + dg * (1.0 - sa);
+                let b = sb * da + db * (1.0 - sa);
+                (a, r, g, b)
+            }
+            BlendMode::DstATop => {
+                let a = sa;
+                let r = dr * sa + sr * (1.0 - da);
+                let g = dg * sa + sg * (1.0 - da);
+                let b = db * sa + sb * (1.0 - da);
+                (a, r, g, b)
+            }
+            BlendMode::Xor => {
+                let a = sa + da - 2.0 * sa * da;
+                let r = sr * (1.0 - da) + dr * (1.0 - sa);
+                let g = sg * (1.0 - da) + dg * (1.0 - sa);
+                let b = sb * (1.0 - da) + db * (1.0 - sa);
+                (a, r, g, b)
+            }
+            BlendMode::Plus => {
+                let a = (sa + da).min(1.0);
+                let r = (sr + dr).min(1.0);
+                let g = (sg + dg).min(1.0);
+                let b = (sb + db).min(1.0);
+                (a, r, g, b)
+            }
+            BlendMode::Modulate => (sa * da, sr * dr, sg * dg, sb * db),
+            BlendMode::Screen
+            | BlendMode::Overlay
+            | BlendMode::Darken
+            | BlendMode::Lighten
+            | BlendMode::ColorDodge
+            | BlendMode::ColorBurn
+            | BlendMode::HardLight
+            | BlendMode::SoftLight
+            | BlendMode::Difference
+            | BlendMode::Exclusion
+            | BlendMode::Multiply
+            | BlendMode::Hue
+            | BlendMode::Saturation
+            | BlendMode::Color
+            | BlendMode::Luminosity => {
+                let (br, bg, bb) = blend_function(*self, [sr, sg, sb], [dr, dg, db]);
+                // W3C compositing: the blend result shows where both overlap,
+                // each input alone shows where the other is transparent.
+                // Written as `1 - (1 - sa)(1 - da)` so an opaque input stays
+                // exactly opaque.
+                let a = 1.0 - (1.0 - sa) * (1.0 - da);
+                if a > 0.0 {
+                    let mix = |s: f32, d: f32, b: f32| {
+                        (sa * (1.0 - da) * s + da * (1.0 - sa) * d + sa * da * b) / a
+                    };
+                    (a, mix(sr, dr, br), mix(sg, dg, bg), mix(sb, db, bb))
+                } else {
+                    (0.0, 0.0, 0.0, 0.0)
+                }
+            }
+        };
+
+        Color4f::new(rr, rg, rb, ra)
+    }
+}
+
+/// The blend function `B(Cs, Cd)` of a separable or non-separable mode.
+///
+/// Inputs and outputs are unpremultiplied RGB in `0..=1`. Alpha is handled
+/// by the caller.
+fn blend_function(mode: BlendMode, s: [f32; 3], d: [f32; 3]) -> (f32, f32, f32) {
+    let rgb = match mode {
+        BlendMode::Hue => set_lum(set_sat(s, sat(d)), lum(d)),
+        BlendMode::Saturation => set_lum(set_sat(d, sat(s)), lum(d)),
+        BlendMode::Color => set_lum(s, lum(d)),
+        BlendMode::Luminosity => set_lum(d, lum(s)),
+        _ => [0, 1, 2].map(|i| blend_channel(mode, s[i], d[i])),
+    };
+    (rgb[0], rgb[1], rgb[2])
+}
+
+/// Per-channel blend function of a separable mode.
+fn blend_channel(mode: BlendMode, s: f32, d: f32) -> f32 {
+    match mode {
+        BlendMode::Multiply => s * d,
+        BlendMode::Screen => s + d - s * d,
+        BlendMode::Overlay => blend_channel(BlendMode::HardLight, d, s),
+        BlendMode::Darken => s.min(d),
+        BlendMode::Lighten => s.max(d),
+        BlendMode::ColorDodge => {
+            if d <= 0.0 {
+                0.0
+            } else if s >= 1.0 {
+                1.0
+            } else {
+                (d / (1.0 - s)).min(1.0)
+            }
+        }
+        BlendMode::ColorBurn => {
+            if d >= 1.0 {
+                1.0
+            } else if s <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - d) / s).min(1.0)
+            }
+        }
+        BlendMode::HardLight => {
+            if s <= 0.5 {
+                2.0 * s * d
+            } else {
+                let s = 2.0 * s - 1.0;
+                s + d - s * d
+            }
+        }
+        BlendMode::SoftLight => {
+            if s <= 0.5 {
+                d - (1.0 - 2.0 * s) * d * (1.0 - d)
+            } else {
+                let dd = if d <= 0.25 {
+                    ((16.0 * d - 12.0) * d + 4.0) * d
+                } else {
+                    d.sqrt()
+                };
+                d + (2.0 * s - 1.0) * (dd - d)
+            }
+        }
+        BlendMode::Difference => (s - d).abs(),
+        BlendMode::Exclusion => s + d - 2.0 * s * d,
+        _ => s,
+    }
+}
+
+/// Luminosity of an RGB color, as defined for the non-separable modes.
+fn lum(c: [f32; 3]) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+/// Shifts `c` to luminosity `l`, clipping back into gamut.
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let delta = l - lum(c);
+    let c = c.map(|v| v + delta);
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    c.map(|v| {
+        let mut v = v;
+        if n < 0.0 {
+            v = l + (v - l) * l / (l - n);
+        }
+        if x > 1.0 {
+            v = l + (v - l) * (1.0 - l) / (x - l);
+        }
+        v
+    })
+}
+
+/// Saturation of an RGB color (max minus min channel).
+fn sat(c: [f32; 3]) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+/// Rescales `c` to saturation `s`, preserving its channel ordering.
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    let n = c[0].min(c[1]).min(c[2]);
+    let range = sat(c);
+    if range > 0.0 {
+        c.map(|v| (v - n) * s / range)
+    } else {
+        [0.0; 3]
+    }
 }
@@ -1,14 +1,43 @@
 //! Color, mask, and image filters.
 
-use skia_rs_core::{Color4f, Rect, Scalar};
-use std::sync::Arc;
+use crate::blend::BlendMode;
+use crate::shader::TileMode;
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
+use skia_rs_core::{Color, Color4f, IPoint, Rect, Scalar};
 
 /// A color filter that transforms colors.
-pub trait ColorFilter: Send + Sync + std::fmt::Debug {
+pub trait ColorFilter: Send + Sync + core::fmt::Debug {
     /// Filter a color.
     fn filter_color(&self, color: Color4f) -> Color4f;
 }
 
+impl dyn ColorFilter {
+    /// Create a 4x5 color matrix filter, like Skia's `SkColorFilters::Matrix`.
+    ///
+    /// Each row computes one of red, green, blue and alpha from the
+    /// unpremultiplied input `[r, g, b, a, 1]`, so the last column is a
+    /// translation in the `0..=1` range.
+    ///
+    /// Called as `<dyn ColorFilter>::matrix(..)`.
+    pub fn matrix(matrix: [Scalar; 20]) -> ColorFilterRef {
+        Arc::new(ColorMatrixFilter::new(matrix))
+    }
+
+    /// Create a filter that blends `color` onto every input color with
+    /// `mode`, like Skia's `SkColorFilters::Blend`.
+    ///
+    /// The filter's color is the source and the input color the
+    /// destination, so `SrcOver` with a translucent color tints toward it.
+    ///
+    /// Called as `<dyn ColorFilter>::blend(..)`.
+    pub fn blend(color: Color, mode: BlendMode) -> ColorFilterRef {
+        Arc::new(BlendColorFilter::new(color.to_color4f(), mode))
+    }
+}
+
 /// A matrix color filter.
 #[derive(Debug, Clone)]
 pub struct ColorMatrixFilter {
@@ -75,6 +104,51 @@ impl ColorFilter for ColorMatrixFilter {
     }
 }
 
+/// A color filter that blends a constant color onto its input.
+#[derive(Debug, Clone, Copy)]
+pub struct BlendColorFilter {
+    color: Color4f,
+    mode: BlendMode,
+}
+
+impl BlendColorFilter {
+    /// Create a new blend color filter.
+    pub fn new(color: Color4f, mode: BlendMode) -> Self {
+        Self { color, mode }
+    }
+
+    /// Get the blended color.
+    pub fn color(&self) -> Color4f {
+        self.color
+    }
+
+    /// Get the blend mode.
+    pub fn mode(&self) -> BlendMode {
+        self.mode
+    }
+}
+
+impl ColorFilter for BlendColorFilter {
+    fn filter_color(&self, color: Color4f) -> Color4f {
+        self.mode.blend(self.color, color)
+    }
+}
+
+/// A color filter that turns luminance into alpha, like Skia's
+/// `SkLumaColorFilter`.
+///
+/// The result is black, with the Rec. 709 luminance of the input times its
+/// alpha as alpha. Compositing a layer through it gives a luma mask.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LumaColorFilter;
+
+impl ColorFilter for LumaColorFilter {
+    fn filter_color(&self, color: Color4f) -> Color4f {
+        let luma = 0.2126 * color.r + 0.7152 * color.g + 0.0722 * color.b;
+        Color4f::new(0.0, 0.0, 0.0, (luma * color.a).clamp(0.0, 1.0))
+    }
+}
+
 /// Blur style for mask filters.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[repr(u8)]
@@ -91,9 +165,33 @@ pub enum BlurStyle {
 }
 
 /// A mask filter (blur, emboss, etc.).
-pub trait MaskFilter: Send + Sync + std::fmt::Debug {
+pub trait MaskFilter: Send + Sync + core::fmt::Debug {
     /// Get the blur radius if this is a blur filter.
     fn blur_radius(&self) -> Option<Scalar>;
+
+    /// Get the blur style and sigma if this is a Gaussian blur.
+    ///
+    /// Corresponds to Skia's `SkMaskFilterBase::asABlur`.
+    fn as_blur(&self) -> Option<(BlurStyle, Scalar)> {
+        None
+    }
+}
+
+impl dyn MaskFilter {
+    /// Create a Gaussian blur mask filter.
+    ///
+    /// The shape's coverage is blurred with standard deviation `sigma`, in
+    /// device pixels, and `style` selects which part of the result is kept.
+    /// Returns `None` unless `sigma` is finite and positive.
+    ///
+    /// Called as `<dyn MaskFilter>::blur(..)`.
+    pub fn blur(style: BlurStyle, sigma: Scalar) -> Option<MaskFilterRef> {
+        if sigma.is_finite() && sigma > 0.0 {
+            Some(Arc::new(BlurMaskFilter::new(style, sigma)))
+        } else {
+            None
+        }
+    }
 }
 
 /// A blur mask filter.
@@ -124,12 +222,253 @@ impl MaskFilter for BlurMaskFilter {
     fn blur_radius(&self) -> Option<Scalar> {
         Some(self.sigma)
     }
+
+    fn as_blur(&self) -> Option<(BlurStyle, Scalar)> {
+        Some((self.style, self.sigma))
+    }
 }
 
 /// An image filter.
-pub trait ImageFilter: Send + Sync + std::fmt::Debug {
+pub trait ImageFilter: Send + Sync + core::fmt::Debug {
     /// Get the bounds that this filter affects.
     fn filter_bounds(&self, src: &Rect) -> Rect;
+
+    /// Filter an image on the CPU.
+    ///
+    /// Returns `None` if the filter has no CPU implementation, in which
+    /// case callers use the unfiltered image.
+    fn filter_image(&self, src: &FilterImage) -> Option<FilterImage> {
+        let _ = src;
+        None
+    }
+}
+
+impl dyn ImageFilter {
+    /// Create a Gaussian blur image filter, like Skia's
+    /// `SkImageFilters::Blur`.
+    ///
+    /// The sigmas are in device pixels, and an axis whose sigma is not
+    /// positive is left unblurred. Pixels outside the input are treated as
+    /// transparent, so the result grows by `3 * sigma` on each side.
+    ///
+    /// Called as `<dyn ImageFilter>::blur(..)`.
+    pub fn blur(sigma_x: Scalar, sigma_y: Scalar) -> ImageFilterRef {
+        Arc::new(BlurImageFilter::new(sigma_x, sigma_y, TileMode::Decal))
+    }
+
+    /// Create a drop shadow image filter, like Skia's
+    /// `SkImageFilters::DropShadow`.
+    ///
+    /// The input's alpha, tinted with `color`, is blurred with `sigma` and
+    /// offset by `(dx, dy)` device pixels, then the input is drawn over it.
+    ///
+    /// Called as `<dyn ImageFilter>::drop_shadow(..)`.
+    pub fn drop_shadow(dx: Scalar, dy: Scalar, sigma: Scalar, color: Color) -> ImageFilterRef {
+        Arc::new(DropShadowImageFilter::new(
+            dx,
+            dy,
+            sigma,
+            sigma,
+            color.to_color4f(),
+            false,
+        ))
+    }
+
+    /// Chain two image filters, applying `inner` first and `outer` to its
+    /// result, like Skia's `SkImageFilters::Compose`.
+    ///
+    /// Called as `<dyn ImageFilter>::compose(..)`.
+    pub fn compose(outer: ImageFilterRef, inner: ImageFilterRef) -> ImageFilterRef {
+        Arc::new(ComposeImageFilter::new(outer, inner))
+    }
+}
+
+/// Premultiplied pixels passed through image filters on the CPU.
+///
+/// The image knows the device position of its top-left pixel, so filters
+/// that grow or move their input, like blurs and offsets, return an image
+/// with different bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterImage {
+    origin: IPoint,
+    width: i32,
+    height: i32,
+    pixels: Vec<Color4f>,
+}
+
+impl FilterImage {
+    /// Create a transparent image.
+    pub fn new(origin: IPoint, width: i32, height: i32) -> Self {
+        let (width, height) = (width.max(0), height.max(0));
+        Self {
+            origin,
+            width,
+            height,
+            pixels: vec![Color4f::transparent(); width as usize * height as usize],
+        }
+    }
+
+    /// Create an image from premultiplied pixels in row-major order.
+    ///
+    /// Returns `None` if `pixels` does not hold `width * height` colors.
+    pub fn from_pixels(
+        origin: IPoint,
+        width: i32,
+        height: i32,
+        pixels: Vec<Color4f>,
+    ) -> Option<Self> {
+        if width < 0 || height < 0 || pixels.len() != width as usize * height as usize {
+            return None;
+        }
+        Some(Self {
+            origin,
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Get the device position of the top-left pixel.
+    pub fn origin(&self) -> IPoint {
+        self.origin
+    }
+
+    /// Get the width in pixels.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Get the height in pixels.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Get the premultiplied pixels in row-major order.
+    pub fn pixels(&self) -> &[Color4f] {
+        &self.pixels
+    }
+
+    /// Get the pixel at device position `(x, y)`, transparent outside the
+    /// image.
+    pub fn get(&self, x: i32, y: i32) -> Color4f {
+        let (x, y) = (x - self.origin.x, y - self.origin.y);
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return Color4f::transparent();
+        }
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Apply `f` to every pixel.
+    fn map(&self, f: impl Fn(Color4f) -> Color4f) -> Self {
+        Self {
+            pixels: self.pixels.iter().map(|&p| f(p)).collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Move the image by whole device pixels.
+    fn offset(mut self, dx: i32, dy: i32) -> Self {
+        self.origin = IPoint::new(self.origin.x + dx, self.origin.y + dy);
+        self
+    }
+
+    /// Draw `self` over `below` with `SrcOver`, in the union of both bounds.
+    fn over(&self, below: &FilterImage) -> Self {
+        let left = self.origin.x.min(below.origin.x);
+        let top = self.origin.y.min(below.origin.y);
+        let right = (self.origin.x + self.width).max(below.origin.x + below.width);
+        let bottom = (self.origin.y + self.height).max(below.origin.y + below.height);
+        let mut out = FilterImage::new(IPoint::new(left, top), right - left, bottom - top);
+        for y in top..bottom {
+            for x in left..right {
+                let (s, d) = (self.get(x, y), below.get(x, y));
+                let inv = 1.0 - s.a;
+                out.pixels[((y - top) * out.width + x - left) as usize] = Color4f::new(
+                    s.r + d.r * inv,
+                    s.g + d.g * inv,
+                    s.b + d.b * inv,
+                    s.a + d.a * inv,
+                );
+            }
+        }
+        out
+    }
+
+    /// Blur with a separable Gaussian, growing the image by the kernel
+    /// radius on each side. Pixels outside the image are transparent.
+    fn blur(&self, sigma_x: Scalar, sigma_y: Scalar) -> Self {
+        let pass = |src: &FilterImage, sigma: Scalar, horizontal: bool| {
+            let kernel = gaussian_kernel(sigma);
+            let radius = (kernel.len() / 2) as i32;
+            let (dx, dy) = if horizontal { (radius, 0) } else { (0, radius) };
+            let mut out = FilterImage::new(
+                IPoint::new(src.origin.x - dx, src.origin.y - dy),
+                src.width + 2 * dx,
+                src.height + 2 * dy,
+            );
+            for y in 0..out.height {
+                for x in 0..out.width {
+                    let (px, py) = (out.origin.x + x, out.origin.y + y);
+                    let mut sum = [0.0; 4];
+                    for (k, weight) in kernel.iter().enumerate() {
+                        let d = k as i32 - radius;
+                        let c = if horizontal {
+                            src.get(px + d, py)
+                        } else {
+                            src.get(px, py + d)
+                        };
+                        sum[0] += c.r * weight;
+                        sum[1] += c.g * weight;
+                        sum[2] += c.b * weight;
+                        sum[3] += c.a * weight;
+                    }
+                    out.pixels[(y * out.width + x) as usize] =
+                        Color4f::new(sum[0], sum[1], sum[2], sum[3]);
+                }
+            }
+            out
+        };
+        pass(&pass(self, sigma_x, true), sigma_y, false)
+    }
+}
+
+/// Largest blur sigma honored, as in Skia's `SkBlurMask`. Wider blurs look
+/// the same at any practical size but would need huge kernels and images.
+const MAX_BLUR_SIGMA: Scalar = 532.0;
+
+/// The sigma a blur actually uses: clamped to [`MAX_BLUR_SIGMA`], or zero
+/// (no blur) unless it is finite and positive.
+fn blur_sigma(sigma: Scalar) -> Scalar {
+    if sigma.is_finite() && sigma > 0.0 {
+        sigma.min(MAX_BLUR_SIGMA)
+    } else {
+        0.0
+    }
+}
+
+/// A normalized Gaussian kernel reaching `3 * sigma` each way, or the
+/// identity kernel if `sigma` is not positive. See [`blur_sigma`].
+fn gaussian_kernel(sigma: Scalar) -> Vec<f32> {
+    let sigma = blur_sigma(sigma);
+    if sigma == 0.0 {
+        return vec![1.0];
+    }
+    let radius = (3.0 * sigma).ceil() as i32;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|d| (-(d * d) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|k| *k /= sum);
+    kernel
+}
+
+/// Run an optional input filter, passing the source through if there is
+/// none.
+fn filter_input(input: &Option<ImageFilterRef>, src: &FilterImage) -> Option<FilterImage> {
+    match input {
+        Some(filter) => filter.filter_image(src),
+        None => Some(src.clone()),
+    }
 }
 
 /// A blur image filter.
@@ -154,10 +493,15 @@ impl BlurImageFilter {
 impl ImageFilter for BlurImageFilter {
     fn filter_bounds(&self, src: &Rect) -> Rect {
         // Blur expands bounds by ~3 sigma
-        let dx = self.sigma_x * 3.0;
-        let dy = self.sigma_y * 3.0;
+        let dx = blur_sigma(self.sigma_x) * 3.0;
+        let dy = blur_sigma(self.sigma_y) * 3.0;
         Rect::new(src.left - dx, src.top - dy, src.right + dx, src.bottom + dy)
     }
+
+    /// Blurs with transparent edges whatever the tile mode.
+    fn filter_image(&self, src: &FilterImage) -> Option<FilterImage> {
+        Some(src.blur(self.sigma_x, self.sigma_y))
+    }
 }
 
 /// A drop shadow image filter.
@@ -194,8 +538,8 @@ impl DropShadowImageFilter {
 
 impl ImageFilter for DropShadowImageFilter {
     fn filter_bounds(&self, src: &Rect) -> Rect {
-        let blur_dx = self.sigma_x * 3.0;
-        let blur_dy = self.sigma_y * 3.0;
+        let blur_dx = blur_sigma(self.sigma_x) * 3.0;
+        let blur_dy = blur_sigma(self.sigma_y) * 3.0;
 
         if self.shadow_only {
             Rect::new(
@@ -213,6 +557,20 @@ impl ImageFilter for DropShadowImageFilter {
             )
         }
     }
+
+    /// The offset is rounded to whole device pixels.
+    fn filter_image(&self, src: &FilterImage) -> Option<FilterImage> {
+        let color = self.color.premul();
+        let shadow = src
+            .map(|p| Color4f::new(color.r * p.a, color.g * p.a, color.b * p.a, color.a * p.a))
+            .offset(self.dx.round() as i32, self.dy.round() as i32)
+            .blur(self.sigma_x, self.sigma_y);
+        if self.shadow_only {
+            Some(shadow)
+        } else {
+            Some(src.over(&shadow))
+        }
+    }
 }
 
 // =============================================================================
@@ -398,6 +756,11 @@ impl ImageFilter for ColorFilterImageFilter {
         // Color filters don't change bounds
         *src
     }
+
+    fn filter_image(&self, src: &FilterImage) -> Option<FilterImage> {
+        let input = filter_input(&self.input, src)?;
+        Some(input.map(|p| self.color_filter.filter_color(p.unpremul()).premul()))
+    }
 }
 
 /// A displacement map image filter.
@@ -566,6 +929,10 @@ impl ImageFilter for ComposeImageFilter {
         let inner_bounds = self.inner.filter_bounds(src);
         self.outer.filter_bounds(&inner_bounds)
     }
+
+    fn filter_image(&self, src: &FilterImage) -> Option<FilterImage> {
+        self.outer.filter_image(&self.inner.filter_image(src)?)
+    }
 }
 
 /// A merge image filter that combines multiple inputs.
@@ -622,6 +989,12 @@ impl ImageFilter for OffsetImageFilter {
             src.bottom + self.dy,
         )
     }
+
+    /// The offset is rounded to whole device pixels.
+    fn filter_image(&self, src: &FilterImage) -> Option<FilterImage> {
+        let input = filter_input(&self.input, src)?;
+        Some(input.offset(self.dx.round() as i32, self.dy.round() as i32))
+    }
 }
 
 /// A matrix convolution image filter.
@@ -808,3 +1181,36 @@ pub type ColorFilterRef = Arc<dyn ColorFilter + Send + Sync>;
 pub type MaskFilterRef = Arc<dyn MaskFilter + Send + Sync>;
 /// Boxed image filter type.
 pub type ImageFilterRef = Arc<dyn ImageFilter + Send + Sync>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blur_image_filter_large_sigma() {
+        let pixels = vec![Color4f::new(1.0, 1.0, 1.0, 1.0); 4];
+        let src = FilterImage::from_pixels(IPoint::new(10, 0), 4, 1, pixels).unwrap();
+
+        // Huge sigmas are clamped, so the image grows by 3 * 532 each way.
+        for sigma in [1e30, Scalar::MAX] {
+            let blurred = <dyn ImageFilter>::blur(sigma, 0.0)
+                .filter_image(&src)
+                .unwrap();
+            assert_eq!(blurred.origin(), IPoint::new(10 - 1596, 0));
+            assert_eq!((blurred.width(), blurred.height()), (4 + 2 * 1596, 1));
+            let total: f32 = blurred.pixels().iter().map(|p| p.a).sum();
+            assert!((total - 4.0).abs() < 0.01, "{total}");
+        }
+        let bounds = <dyn ImageFilter>::blur(1e30, Scalar::NAN)
+            .filter_bounds(&Rect::new(0.0, 0.0, 4.0, 1.0));
+        assert_eq!(bounds, Rect::new(-1596.0, 0.0, 1600.0, 1.0));
+
+        // Sigmas that can't blur leave the image as is.
+        for sigma in [Scalar::INFINITY, Scalar::NAN, -1.0] {
+            let same = <dyn ImageFilter>::blur(sigma, sigma)
+                .filter_image(&src)
+                .unwrap();
+            assert_eq!(same, src);
+        }
+    }
+}
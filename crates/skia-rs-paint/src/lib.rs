@@ -8,19 +8,24 @@
 //! - Image filters
 //! - Runtime effects (SkSL custom shaders)
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+extern crate alloc;
+
 pub mod blend;
 pub mod filter;
 pub mod paint;
 pub mod runtime_effect;
 pub mod shader;
 pub mod sksl;
+mod sksl_eval;
+mod sksl_wgsl;
 
 pub use blend::*;
 pub use filter::*;
 pub use paint::*;
 pub use runtime_effect::*;
 pub use shader::*;
-pub use sksl::{SkslProgram, SkslType};
+pub use sksl::{SkslError, SkslProgram, SkslType};
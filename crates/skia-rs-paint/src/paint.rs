@@ -1,8 +1,11 @@
 //! Paint structure for drawing configuration.
 
 use crate::blend::BlendMode;
+use crate::filter::{ColorFilterRef, ImageFilterRef, MaskFilterRef};
 use crate::shader::ShaderRef;
+use alloc::vec::Vec;
 use skia_rs_core::{Color, Color4f, Scalar};
+use skia_rs_path::PathEffectRef;
 
 /// Paint style (fill, stroke, or both).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -50,6 +53,14 @@ pub struct Paint {
     color: Color4f,
     /// Shader for complex fills (gradients, images, etc.).
     shader: Option<ShaderRef>,
+    /// Path effect applied to geometry before stroking (dashes, etc.).
+    path_effect: Option<PathEffectRef>,
+    /// Mask filter applied to the shape's coverage (blur, etc.).
+    mask_filter: Option<MaskFilterRef>,
+    /// Color filter applied to the source colors.
+    color_filter: Option<ColorFilterRef>,
+    /// Image filter applied to layers saved with this paint.
+    image_filter: Option<ImageFilterRef>,
     /// Blend mode.
     blend_mode: BlendMode,
     /// Style (fill/stroke).
@@ -73,6 +84,10 @@ impl Default for Paint {
         Self {
             color: Color4f::new(0.0, 0.0, 0.0, 1.0),
             shader: None,
+            path_effect: None,
+            mask_filter: None,
+            color_filter: None,
+            image_filter: None,
             blend_mode: BlendMode::SrcOver,
             style: Style::Fill,
             stroke_width: 1.0,
@@ -239,6 +254,70 @@ impl Paint {
         self.shader.is_some()
     }
 
+    /// Get the path effect.
+    #[inline]
+    pub fn path_effect(&self) -> Option<&PathEffectRef> {
+        self.path_effect.as_ref()
+    }
+
+    /// Set the path effect.
+    #[inline]
+    pub fn set_path_effect(&mut self, path_effect: Option<PathEffectRef>) -> &mut Self {
+        self.path_effect = path_effect;
+        self
+    }
+
+    /// Get the mask filter.
+    #[inline]
+    pub fn mask_filter(&self) -> Option<&MaskFilterRef> {
+        self.mask_filter.as_ref()
+    }
+
+    /// Set the mask filter.
+    ///
+    /// The raster backend honors blur mask filters, created with
+    /// `<dyn MaskFilter>::blur`, on rects, circles, ovals, round rects,
+    /// paths and text.
+    #[inline]
+    pub fn set_mask_filter(&mut self, mask_filter: Option<MaskFilterRef>) -> &mut Self {
+        self.mask_filter = mask_filter;
+        self
+    }
+
+    /// Get the color filter.
+    #[inline]
+    pub fn color_filter(&self) -> Option<&ColorFilterRef> {
+        self.color_filter.as_ref()
+    }
+
+    /// Set the color filter.
+    ///
+    /// The raster backend applies it to the paint's color and shader samples
+    /// before blending, and to the layer's pixels when the paint composites
+    /// a layer saved with `RasterCanvas::save_layer`.
+    #[inline]
+    pub fn set_color_filter(&mut self, color_filter: Option<ColorFilterRef>) -> &mut Self {
+        self.color_filter = color_filter;
+        self
+    }
+
+    /// Get the image filter.
+    #[inline]
+    pub fn image_filter(&self) -> Option<&ImageFilterRef> {
+        self.image_filter.as_ref()
+    }
+
+    /// Set the image filter.
+    ///
+    /// The raster backend applies it to the layer's pixels, before the color
+    /// filter, when the paint composites a layer saved with
+    /// `RasterCanvas::save_layer`.
+    #[inline]
+    pub fn set_image_filter(&mut self, image_filter: Option<ImageFilterRef>) -> &mut Self {
+        self.image_filter = image_filter;
+        self
+    }
+
     /// Check if anti-aliasing is enabled.
     #[inline]
     pub fn is_anti_alias(&self) -> bool {
@@ -265,6 +344,31 @@ impl Paint {
         self
     }
 
+    /// Check whether drawing with this paint can never change the destination.
+    ///
+    /// True for [`BlendMode::Dst`], and for a fully transparent color under a
+    /// blend mode that leaves the destination untouched by a transparent
+    /// source, unless a shader supplies the color or the color filter turns
+    /// it into a visible one. Zero-width strokes are hairlines and still draw.
+    pub fn nothing_to_draw(&self) -> bool {
+        match self.blend_mode {
+            BlendMode::Dst => true,
+            BlendMode::SrcOver
+            | BlendMode::SrcATop
+            | BlendMode::DstOut
+            | BlendMode::DstOver
+            | BlendMode::Plus => {
+                self.color.a <= 0.0
+                    && self.shader.is_none()
+                    && self
+                        .color_filter
+                        .as_ref()
+                        .is_none_or(|filter| filter.filter_color(self.color).a <= 0.0)
+            }
+            _ => false,
+        }
+    }
+
     /// Alias for is_anti_alias (Skia compatibility).
     #[inline]
     pub fn anti_alias(&self) -> bool {
@@ -381,7 +485,11 @@ impl Paint {
 
         Some(Self {
             color,
-            shader: None, // Shaders are not serialized
+            shader: None,       // Shaders are not serialized
+            path_effect: None,  // Nor are path effects
+            mask_filter: None,  // Or mask filters
+            color_filter: None, // Or color filters
+            image_filter: None, // Or image filters
             blend_mode,
             style,
             stroke_width,
@@ -430,6 +538,65 @@ mod tests {
         assert_eq!(deserialized.is_dither(), paint.is_dither());
     }
 
+    #[test]
+    fn test_nothing_to_draw() {
+        let mut paint = Paint::new();
+        assert!(!paint.nothing_to_draw());
+
+        paint.set_blend_mode(BlendMode::Dst);
+        assert!(paint.nothing_to_draw());
+
+        paint.set_blend_mode(BlendMode::SrcOver).set_alpha(0.0);
+        assert!(paint.nothing_to_draw());
+
+        // A transparent source still clears under Src, and a shader
+        // provides its own color.
+        paint.set_blend_mode(BlendMode::Src);
+        assert!(!paint.nothing_to_draw());
+        paint.set_blend_mode(BlendMode::SrcOver);
+        paint.set_shader(Some(alloc::sync::Arc::new(crate::ColorShader::new(
+            Color4f::new(1.0, 0.0, 0.0, 1.0),
+        ))));
+        assert!(!paint.nothing_to_draw());
+
+        // A color filter can turn the transparent color into a visible one.
+        paint.set_shader(None);
+        paint.set_color_filter(Some(<dyn crate::ColorFilter>::blend(
+            Color::from_rgb(0, 0, 255),
+            BlendMode::Src,
+        )));
+        assert!(!paint.nothing_to_draw());
+        let mut matrix = [0.0; 20];
+        matrix[19] = 1.0;
+        paint.set_color_filter(Some(<dyn crate::ColorFilter>::matrix(matrix)));
+        assert!(!paint.nothing_to_draw());
+        // One that keeps it transparent still draws nothing.
+        paint.set_color_filter(Some(<dyn crate::ColorFilter>::blend(
+            Color::from_rgb(0, 0, 255),
+            BlendMode::Dst,
+        )));
+        assert!(paint.nothing_to_draw());
+
+        // Hairlines draw.
+        let mut hairline = Paint::new();
+        hairline.set_style(Style::Stroke).set_stroke_width(0.0);
+        assert!(!hairline.nothing_to_draw());
+    }
+
+    #[test]
+    fn test_mask_filter_blur() {
+        use crate::filter::{BlurStyle, MaskFilter};
+
+        assert!(<dyn MaskFilter>::blur(BlurStyle::Normal, 0.0).is_none());
+        assert!(<dyn MaskFilter>::blur(BlurStyle::Normal, Scalar::NAN).is_none());
+
+        let mut paint = Paint::new();
+        assert!(paint.mask_filter().is_none());
+        paint.set_mask_filter(<dyn MaskFilter>::blur(BlurStyle::Outer, 2.5));
+        let blur = paint.mask_filter().and_then(|filter| filter.as_blur());
+        assert_eq!(blur, Some((BlurStyle::Outer, 2.5)));
+    }
+
     #[test]
     fn test_paint_deserialize_invalid() {
         // Too short
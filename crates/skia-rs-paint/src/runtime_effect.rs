@@ -2,11 +2,23 @@
 //!
 //! This module provides Skia's runtime effects system, allowing custom
 //! shaders written in SkSL to be compiled and used at runtime.
+//!
+//! On the raster backend, runtime shaders and color filters are evaluated on
+//! the CPU by interpreting the parsed program once per sample.
 
 use crate::shader::{Shader, ShaderKind};
 use crate::sksl::{Expr, FnDecl, Parser, SkslProgram, SkslType, Stmt};
+use crate::sksl_eval::{self, Value};
+use alloc::sync::Arc;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 use skia_rs_core::{Color4f, Matrix, Scalar};
-use std::sync::Arc;
 
 /// Error type for runtime effect operations.
 #[derive(Debug, Clone)]
@@ -23,8 +35,8 @@ pub enum RuntimeEffectError {
     InvalidChildCount { expected: usize, got: usize },
 }
 
-impl std::fmt::Display for RuntimeEffectError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for RuntimeEffectError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             RuntimeEffectError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             RuntimeEffectError::CompileError(msg) => write!(f, "Compile error: {}", msg),
@@ -37,7 +49,7 @@ impl std::fmt::Display for RuntimeEffectError {
     }
 }
 
-impl std::error::Error for RuntimeEffectError {}
+impl core::error::Error for RuntimeEffectError {}
 
 /// Uniform metadata.
 #[derive(Debug, Clone)]
@@ -794,6 +806,7 @@ impl RuntimeEffect {
             effect: Arc::clone(self),
             uniforms: uniforms.clone(),
             children: children.to_vec(),
+            globals: self.uniform_values(uniforms),
         })
     }
 
@@ -805,8 +818,57 @@ impl RuntimeEffect {
         Ok(RuntimeColorFilter {
             effect: Arc::clone(self),
             uniforms: uniforms.clone(),
+            globals: self.uniform_values(uniforms),
         })
     }
+
+    /// Decode uniform data into the named values the CPU evaluator binds.
+    fn uniform_values(&self, data: &UniformData) -> Vec<(String, Value)> {
+        let decode = |ty: UniformType, offset: usize| {
+            let slots: Vec<f32> = (0..ty.slot_count())
+                .map(|i| {
+                    if ty.is_float() {
+                        data.get_float(offset + i * 4)
+                    } else {
+                        data.get_int(offset + i * 4) as f32
+                    }
+                })
+                .collect();
+            let mut m = [0.0; 16];
+            m[..slots.len()].copy_from_slice(&slots);
+            match ty {
+                UniformType::Int => Value::Int(slots[0] as i32),
+                UniformType::Float2x2 => Value::Mat(m, 2),
+                UniformType::Float3x3 => Value::Mat(m, 3),
+                UniformType::Float4x4 => Value::Mat(m, 4),
+                _ => Value::vector(&slots),
+            }
+        };
+
+        self.program
+            .uniforms
+            .iter()
+            .zip(&self.uniforms)
+            .filter(|(decl, _)| {
+                !matches!(
+                    decl.ty,
+                    SkslType::Shader | SkslType::ColorFilter | SkslType::Blender
+                )
+            })
+            .map(|(decl, uniform)| {
+                let stride = uniform.ty.size_bytes();
+                let value = match decl.array_size {
+                    Some(count) => Value::Array(
+                        (0..count)
+                            .map(|i| decode(uniform.ty, uniform.offset + i * stride))
+                            .collect(),
+                    ),
+                    None => decode(uniform.ty, uniform.offset),
+                };
+                (uniform.name.clone(), value)
+            })
+            .collect()
+    }
 }
 
 /// Effect kind.
@@ -836,6 +898,32 @@ impl UniformData {
         Self::new(effect.uniform_size())
     }
 
+    /// Create from tightly packed float values, in uniform declaration order.
+    ///
+    /// Each uniform consumes as many values as it has slots; int uniforms
+    /// are converted from their float value. Missing values are left zero.
+    pub fn from_floats(effect: &RuntimeEffect, values: &[f32]) -> Self {
+        let mut data = Self::from_effect(effect);
+        let mut values = values.iter().copied();
+        for uniform in effect.uniforms() {
+            for slot in 0..uniform.ty.slot_count() * uniform.count {
+                let Some(value) = values.next() else {
+                    return data;
+                };
+                let element = slot / uniform.ty.slot_count();
+                let offset = uniform.offset
+                    + element * uniform.ty.size_bytes()
+                    + slot % uniform.ty.slot_count() * 4;
+                if uniform.ty.is_float() {
+                    data.set_float(offset, value);
+                } else {
+                    data.set_int(offset, value as i32);
+                }
+            }
+        }
+        data
+    }
+
     /// Set a float uniform.
     pub fn set_float(&mut self, offset: usize, value: f32) {
         if offset + 4 <= self.data.len() {
@@ -885,6 +973,15 @@ impl UniformData {
         }
     }
 
+    /// Get an int uniform.
+    pub fn get_int(&self, offset: usize) -> i32 {
+        if offset + 4 <= self.data.len() {
+            i32::from_le_bytes(self.data[offset..offset + 4].try_into().unwrap())
+        } else {
+            0
+        }
+    }
+
     /// Get the raw data.
     pub fn data(&self) -> &[u8] {
         &self.data
@@ -897,6 +994,7 @@ pub struct RuntimeShader {
     effect: Arc<RuntimeEffect>,
     uniforms: UniformData,
     children: Vec<Arc<dyn Shader>>,
+    globals: Vec<(String, Value)>,
 }
 
 impl RuntimeShader {
@@ -929,10 +1027,16 @@ impl Shader for RuntimeShader {
         ShaderKind::Color // Closest match for runtime shader
     }
 
-    fn sample(&self, _x: Scalar, _y: Scalar) -> Color4f {
-        // Software fallback - would need interpreter
-        // For now, return magenta to indicate runtime shader
-        Color4f::new(1.0, 0.0, 1.0, 1.0)
+    /// Evaluate `main(float2 coords)` at `(x, y)`.
+    ///
+    /// SkSL shaders return premultiplied colors. Samples that discard, or
+    /// that the evaluator cannot run, are transparent.
+    fn sample(&self, x: Scalar, y: Scalar) -> Color4f {
+        let args = vec![Value::vector(&[x, y])];
+        match sksl_eval::call_main(&self.effect.program, &self.globals, args) {
+            Ok(value) => premul_result(&value).unwrap_or_else(Color4f::transparent),
+            Err(_) => Color4f::transparent(),
+        }
     }
 }
 
@@ -941,6 +1045,7 @@ impl Shader for RuntimeShader {
 pub struct RuntimeColorFilter {
     effect: Arc<RuntimeEffect>,
     uniforms: UniformData,
+    globals: Vec<(String, Value)>,
 }
 
 impl RuntimeColorFilter {
@@ -954,13 +1059,27 @@ impl RuntimeColorFilter {
         &self.uniforms
     }
 
-    /// Filter a color.
+    /// Filter a color by evaluating `main(half4 color)`.
+    ///
+    /// The color is premultiplied on the way in and unpremultiplied on the
+    /// way out. If the effect cannot be evaluated the color is unchanged.
     pub fn filter_color(&self, color: Color4f) -> Color4f {
-        // Software fallback - would need interpreter
-        color
+        let premul = color.premul();
+        let args = vec![Value::vector(&[premul.r, premul.g, premul.b, premul.a])];
+        sksl_eval::call_main(&self.effect.program, &self.globals, args)
+            .ok()
+            .and_then(|value| premul_result(&value))
+            .unwrap_or(color)
     }
 }
 
+/// Convert a premultiplied `half4` result into an unpremultiplied color,
+/// clamped to the unit range.
+fn premul_result(value: &Value) -> Option<Color4f> {
+    let [r, g, b, a] = value.as_vec4()?.map(|c| c.clamp(0.0, 1.0));
+    Some(Color4f::new(r.min(a), g.min(a), b.min(a), a).unpremul())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1033,4 +1152,42 @@ mod tests {
         let shader = effect.make_shader(&data, &[]).unwrap();
         assert!(shader.effect().uniforms().len() == 2);
     }
+
+    #[test]
+    fn test_shader_evaluates_program() {
+        let effect = Arc::new(RuntimeEffect::make_for_shader(SIMPLE_SHADER).unwrap());
+        // `time` then `resolution`, packed as consecutive floats.
+        let data = UniformData::from_floats(&effect, &[0.5, 100.0, 50.0]);
+        let resolution = effect.find_uniform("resolution").unwrap();
+        assert_eq!(data.get_float(resolution.offset + 4), 50.0);
+
+        let shader = effect.make_shader(&data, &[]).unwrap();
+        let color = shader.sample(25.0, 40.0);
+        assert!((color.r - 0.25).abs() < 1e-6);
+        assert!((color.g - 0.8).abs() < 1e-6);
+        assert!((color.b - 0.5f32.sin()).abs() < 1e-6);
+        assert_eq!(color.a, 1.0);
+    }
+
+    #[test]
+    fn test_color_filter_evaluates_program() {
+        let effect = Arc::new(
+            RuntimeEffect::make_for_color_filter(
+                r#"
+                uniform half amount;
+                half4 main(half4 color) {
+                    return half4(color.rgb * amount, color.a);
+                }
+                "#,
+            )
+            .unwrap(),
+        );
+        let filter = effect
+            .make_color_filter(&UniformData::from_floats(&effect, &[0.5]))
+            .unwrap();
+        let out = filter.filter_color(Color4f::new(1.0, 0.5, 0.0, 0.5));
+        assert!((out.r - 0.5).abs() < 1e-6);
+        assert!((out.g - 0.25).abs() < 1e-6);
+        assert_eq!(out.a, 0.5);
+    }
 }
@@ -9,8 +9,11 @@
 //! - Image shaders
 //! - Blend shaders
 
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 use skia_rs_core::{Color4f, Matrix, Point, Rect, Scalar};
-use std::sync::Arc;
 
 // =============================================================================
 // Helper Functions for Gradient Sampling
@@ -51,6 +54,11 @@ fn interpolate_gradient_color(
 
     // If no explicit positions, use uniform distribution
     if let Some(pos) = positions {
+        // Before the first stop the first color extends
+        if t <= pos[0] {
+            return colors[0];
+        }
+
         // Find the segment containing t
         for i in 0..pos.len() - 1 {
             if t >= pos[i] && t <= pos[i + 1] {
@@ -109,7 +117,7 @@ impl GradientFlags {
 /// A shader that generates colors for drawing.
 ///
 /// Corresponds to Skia's `SkShader`.
-pub trait Shader: Send + Sync + std::fmt::Debug {
+pub trait Shader: Send + Sync + core::fmt::Debug {
     /// Get the local matrix.
     fn local_matrix(&self) -> Option<&Matrix>;
 
@@ -130,6 +138,20 @@ pub trait Shader: Send + Sync + std::fmt::Debug {
     }
 }
 
+/// Validate gradient stops against their colors, clamping them into `[0, 1]`.
+fn gradient_positions(colors: &[Color4f], stops: Option<&[Scalar]>) -> Option<Option<Vec<Scalar>>> {
+    if colors.is_empty() {
+        return None;
+    }
+    let Some(stops) = stops else {
+        return Some(None);
+    };
+    if stops.len() != colors.len() || !stops.is_sorted() {
+        return None;
+    }
+    Some(Some(stops.iter().map(|&t| t.clamp(0.0, 1.0)).collect()))
+}
+
 /// Kind of shader (for debugging/inspection).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ShaderKind {
@@ -533,18 +555,21 @@ impl Shader for SweepGradient {
         let dx = x - self.center.x;
         let dy = y - self.center.y;
 
-        // atan2 returns [-PI, PI], convert to [0, 360]
-        let mut angle = dy.atan2(dx).to_degrees();
-        if angle < 0.0 {
-            angle += 360.0;
-        }
+        // Measure the angle from the start in the sweep direction, so the
+        // sweep may begin anywhere on the circle.
+        let angle = dy.atan2(dx).to_degrees();
+        let sweep = self.end_angle - self.start_angle;
+        let turned = if sweep < 0.0 {
+            self.start_angle - angle
+        } else {
+            angle - self.start_angle
+        };
 
         // Map angle to t value
-        let sweep = self.end_angle - self.start_angle;
         let mut t = if sweep.abs() < 1e-10 {
             0.0
         } else {
-            (angle - self.start_angle) / sweep
+            turned.rem_euclid(360.0) / sweep.abs()
         };
 
         // Apply tile mode
@@ -680,6 +705,9 @@ pub struct ImageShader {
     sampling: SamplingOptions,
     /// Local matrix.
     local_matrix: Option<Matrix>,
+    /// Premultiplied RGBA8 pixels in row-major order, if the shader has
+    /// image data. Texels are converted to floats as they are sampled.
+    pixels: Option<Arc<[[u8; 4]]>>,
 }
 
 /// Sampling options for image shaders.
@@ -749,9 +777,117 @@ impl ImageShader {
             tile_mode_y,
             sampling,
             local_matrix: None,
+            pixels: None,
         }
     }
 
+    /// Create an image shader that samples the given pixels.
+    ///
+    /// `pixels` holds `width * height` unpremultiplied colors in row-major
+    /// order, with the image placed at the origin of the shader's local
+    /// space. Returns `None` if the size is empty or does not match `pixels`.
+    pub fn from_pixels(
+        width: i32,
+        height: i32,
+        pixels: &[Color4f],
+        tile_mode_x: TileMode,
+        tile_mode_y: TileMode,
+        sampling: SamplingOptions,
+    ) -> Option<Self> {
+        if width <= 0 || height <= 0 || pixels.len() != width as usize * height as usize {
+            return None;
+        }
+        let pixels = pixels
+            .iter()
+            .map(|color| {
+                let color = color.premul().to_color();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        Self::from_premul_rgba8(width, height, pixels, tile_mode_x, tile_mode_y, sampling)
+    }
+
+    /// Create an image shader from premultiplied RGBA8 texels.
+    fn from_premul_rgba8(
+        width: i32,
+        height: i32,
+        pixels: Arc<[[u8; 4]]>,
+        tile_mode_x: TileMode,
+        tile_mode_y: TileMode,
+        sampling: SamplingOptions,
+    ) -> Option<Self> {
+        if width <= 0 || height <= 0 || pixels.len() != width as usize * height as usize {
+            return None;
+        }
+        let bounds = Rect::from_xywh(0.0, 0.0, width as Scalar, height as Scalar);
+        Some(Self {
+            pixels: Some(pixels),
+            ..Self::new(bounds, tile_mode_x, tile_mode_y, sampling)
+        })
+    }
+
+    /// Create an image shader that samples `subset` of a decoded image.
+    ///
+    /// The subset is placed at the origin of the shader's local space.
+    /// Returns `None` if the subset is empty or reaches outside the image, or
+    /// if the image's color type can't be read.
+    #[cfg(feature = "codec")]
+    pub fn from_image(
+        image: &skia_rs_codec::Image,
+        subset: &skia_rs_core::IRect,
+        tile_mode_x: TileMode,
+        tile_mode_y: TileMode,
+        sampling: SamplingOptions,
+    ) -> Option<Self> {
+        if subset.left < 0
+            || subset.top < 0
+            || subset.right > image.width()
+            || subset.bottom > image.height()
+        {
+            return None;
+        }
+        let premul = image.alpha_type() == skia_rs_core::AlphaType::Premul;
+        let pixels = match (image.color_type(), image.peek_pixels()) {
+            // RGBA8 rows are copied as they are, premultiplying if needed.
+            (skia_rs_core::ColorType::Rgba8888, Some(bytes)) => {
+                let row_bytes = image.row_bytes();
+                (subset.top..subset.bottom)
+                    .flat_map(|y| {
+                        let row = y as usize * row_bytes;
+                        let start = row + subset.left as usize * 4;
+                        let end = row + subset.right as usize * 4;
+                        bytes[start..end].chunks_exact(4)
+                    })
+                    .map(|texel| {
+                        let [r, g, b, a] = [texel[0], texel[1], texel[2], texel[3]];
+                        if premul {
+                            [r, g, b, a]
+                        } else {
+                            let c = skia_rs_core::Color::from_argb(a, r, g, b).premultiply();
+                            [c.red(), c.green(), c.blue(), a]
+                        }
+                    })
+                    .collect()
+            }
+            _ => (subset.top..subset.bottom)
+                .flat_map(|y| (subset.left..subset.right).map(move |x| (x, y)))
+                .map(|(x, y)| {
+                    let color = image.read_pixel(x, y)?;
+                    let color = if premul { color } else { color.premul() }.to_color();
+                    Some([color.red(), color.green(), color.blue(), color.alpha()])
+                })
+                .collect::<Option<_>>()?,
+        };
+        Self::from_premul_rgba8(
+            subset.width(),
+            subset.height(),
+            pixels,
+            tile_mode_x,
+            tile_mode_y,
+            sampling,
+        )
+    }
+
     /// Create an image shader with the same tile mode for both axes.
     pub fn with_tile_mode(bounds: Rect, tile_mode: TileMode, sampling: SamplingOptions) -> Self {
         Self::new(bounds, tile_mode, tile_mode, sampling)
@@ -786,6 +922,38 @@ impl ImageShader {
     pub fn sampling(&self) -> SamplingOptions {
         self.sampling
     }
+
+    /// Fetch the premultiplied pixel at image coordinates `(x, y)`, tiled.
+    fn texel(&self, pixels: &[[u8; 4]], x: i32, y: i32) -> Color4f {
+        let width = self.bounds.width() as i32;
+        let height = self.bounds.height() as i32;
+        match (
+            tile_index(x, width, self.tile_mode_x),
+            tile_index(y, height, self.tile_mode_y),
+        ) {
+            (Some(x), Some(y)) => {
+                let [r, g, b, a] = pixels[(y * width + x) as usize].map(|c| c as Scalar / 255.0);
+                Color4f::new(r, g, b, a)
+            }
+            _ => Color4f::transparent(),
+        }
+    }
+}
+
+/// Wrap a pixel index into `0..len` according to the tile mode.
+///
+/// Returns `None` for indices outside the image under [`TileMode::Decal`].
+#[inline]
+fn tile_index(i: i32, len: i32, mode: TileMode) -> Option<i32> {
+    match mode {
+        TileMode::Clamp => Some(i.clamp(0, len - 1)),
+        TileMode::Repeat => Some(i.rem_euclid(len)),
+        TileMode::Mirror => {
+            let i = i.rem_euclid(2 * len);
+            Some(if i < len { i } else { 2 * len - 1 - i })
+        }
+        TileMode::Decal => (0..len).contains(&i).then_some(i),
+    }
 }
 
 impl Shader for ImageShader {
@@ -794,14 +962,46 @@ impl Shader for ImageShader {
     }
 
     fn is_opaque(&self) -> bool {
-        // Image shaders are generally not assumed to be opaque
-        // without analyzing the actual image data
-        false
+        // Decal leaves transparent pixels outside the image.
+        self.tile_mode_x != TileMode::Decal
+            && self.tile_mode_y != TileMode::Decal
+            && self
+                .pixels
+                .as_ref()
+                .is_some_and(|pixels| pixels.iter().all(|texel| texel[3] == 255))
     }
 
     fn shader_kind(&self) -> ShaderKind {
         ShaderKind::Image
     }
+
+    fn sample(&self, x: Scalar, y: Scalar) -> Color4f {
+        let Some(pixels) = self.pixels.as_deref() else {
+            return Color4f::transparent();
+        };
+        let x = x - self.bounds.left;
+        let y = y - self.bounds.top;
+
+        // Filter premultiplied colors so transparent pixels don't bleed
+        // their color into neighbours.
+        let color = match self.sampling.filter {
+            FilterMode::Nearest => self.texel(pixels, x.floor() as i32, y.floor() as i32),
+            FilterMode::Linear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i32, y0 as i32);
+                let top = self
+                    .texel(pixels, x0, y0)
+                    .lerp(&self.texel(pixels, x0 + 1, y0), tx);
+                let bottom = self
+                    .texel(pixels, x0, y0 + 1)
+                    .lerp(&self.texel(pixels, x0 + 1, y0 + 1), tx);
+                top.lerp(&bottom, ty)
+            }
+        };
+        color.unpremul()
+    }
 }
 
 /// Blend shader that combines two shaders.
@@ -1099,70 +1299,120 @@ pub mod shaders {
         Arc::new(ColorShader::new(color))
     }
 
-    /// Create a linear gradient shader.
+    /// Create a linear gradient from `start` to `end`.
+    ///
+    /// `positions` places each color along the gradient in `[0, 1]`; it must
+    /// be as long as `colors` and non-decreasing. Without positions the
+    /// colors are spaced evenly. Returns `None` if `colors` is empty or the
+    /// positions are invalid.
     pub fn linear_gradient(
         start: Point,
         end: Point,
-        colors: Vec<Color4f>,
-        positions: Option<Vec<Scalar>>,
+        colors: &[Color4f],
+        positions: Option<&[Scalar]>,
         tile_mode: TileMode,
-    ) -> ShaderRef {
-        Arc::new(LinearGradient::new(
-            start, end, colors, positions, tile_mode,
-        ))
+    ) -> Option<ShaderRef> {
+        let positions = gradient_positions(colors, positions)?;
+        Some(Arc::new(LinearGradient::new(
+            start,
+            end,
+            colors.to_vec(),
+            positions,
+            tile_mode,
+        )))
     }
 
-    /// Create a radial gradient shader.
+    /// Create a radial gradient around `center`.
+    ///
+    /// Colors run from the center out to `radius`; `positions` follow the
+    /// same rules as for [`linear_gradient`].
     pub fn radial_gradient(
         center: Point,
         radius: Scalar,
-        colors: Vec<Color4f>,
-        positions: Option<Vec<Scalar>>,
+        colors: &[Color4f],
+        positions: Option<&[Scalar]>,
         tile_mode: TileMode,
-    ) -> ShaderRef {
-        Arc::new(RadialGradient::new(
-            center, radius, colors, positions, tile_mode,
-        ))
+    ) -> Option<ShaderRef> {
+        let positions = gradient_positions(colors, positions)?;
+        Some(Arc::new(RadialGradient::new(
+            center,
+            radius,
+            colors.to_vec(),
+            positions,
+            tile_mode,
+        )))
     }
 
-    /// Create a sweep gradient shader.
+    /// Create a sweep (angular) gradient around `center`.
+    ///
+    /// Angles are in degrees, with 0 pointing right and increasing clockwise.
+    /// Colors run from `start_angle` to `end_angle`, which defaults to a full
+    /// turn; `tile_mode` decides what the rest of the circle shows. Returns
+    /// `None` if `end_angle` is not past `start_angle`, or under the same
+    /// conditions as [`linear_gradient`].
     pub fn sweep_gradient(
         center: Point,
         start_angle: Scalar,
-        end_angle: Scalar,
-        colors: Vec<Color4f>,
-        positions: Option<Vec<Scalar>>,
+        end_angle: Option<Scalar>,
+        colors: &[Color4f],
+        positions: Option<&[Scalar]>,
         tile_mode: TileMode,
-    ) -> ShaderRef {
-        Arc::new(SweepGradient::new(
+    ) -> Option<ShaderRef> {
+        let end_angle = end_angle.unwrap_or(start_angle + 360.0);
+        if start_angle.partial_cmp(&end_angle) != Some(core::cmp::Ordering::Less) {
+            return None;
+        }
+        let positions = gradient_positions(colors, positions)?;
+        Some(Arc::new(SweepGradient::new(
             center,
             start_angle,
             end_angle,
-            colors,
+            colors.to_vec(),
             positions,
             tile_mode,
-        ))
+        )))
     }
 
     /// Create a two-point conical gradient shader.
+    ///
+    /// Returns `None` under the same conditions as [`linear_gradient`].
     pub fn two_point_conical_gradient(
         start_center: Point,
         start_radius: Scalar,
         end_center: Point,
         end_radius: Scalar,
-        colors: Vec<Color4f>,
-        positions: Option<Vec<Scalar>>,
+        colors: &[Color4f],
+        positions: Option<&[Scalar]>,
         tile_mode: TileMode,
-    ) -> ShaderRef {
-        Arc::new(TwoPointConicalGradient::new(
+    ) -> Option<ShaderRef> {
+        let positions = gradient_positions(colors, positions)?;
+        Some(Arc::new(TwoPointConicalGradient::new(
             start_center,
             start_radius,
             end_center,
             end_radius,
-            colors,
+            colors.to_vec(),
             positions,
             tile_mode,
-        ))
+        )))
+    }
+
+    /// Create a shader that fills with an image.
+    ///
+    /// The image sits at the origin of the space given by `local_matrix`,
+    /// and the tile modes decide what lies beyond its edges. Returns `None`
+    /// if the image is empty or its color type can't be read.
+    #[cfg(feature = "codec")]
+    pub fn image(
+        image: &skia_rs_codec::Image,
+        tile_x: TileMode,
+        tile_y: TileMode,
+        sampling: SamplingOptions,
+        local_matrix: Matrix,
+    ) -> Option<ShaderRef> {
+        let bounds = skia_rs_core::IRect::new(0, 0, image.width(), image.height());
+        let shader = ImageShader::from_image(image, &bounds, tile_x, tile_y, sampling)?;
+        Some(Arc::new(shader.with_local_matrix(local_matrix)))
     }
 
     /// Create a blend shader.
@@ -1268,10 +1518,296 @@ mod tests {
         let linear = shaders::linear_gradient(
             Point::new(0.0, 0.0),
             Point::new(100.0, 0.0),
-            vec![Color4f::new(1.0, 0.0, 0.0, 1.0)],
+            &[Color4f::new(1.0, 0.0, 0.0, 1.0)],
             None,
             TileMode::Clamp,
-        );
+        )
+        .unwrap();
         assert_eq!(linear.shader_kind(), ShaderKind::LinearGradient);
     }
+
+    fn assert_color_near(actual: Color4f, expected: Color4f) {
+        let diff = (actual.r - expected.r)
+            .abs()
+            .max((actual.g - expected.g).abs())
+            .max((actual.b - expected.b).abs())
+            .max((actual.a - expected.a).abs());
+        assert!(diff < 1e-3, "{actual:?} vs {expected:?}");
+    }
+
+    const BLACK: Color4f = Color4f::new(0.0, 0.0, 0.0, 1.0);
+    const WHITE: Color4f = Color4f::new(1.0, 1.0, 1.0, 1.0);
+    const GRAY: Color4f = Color4f::new(0.5, 0.5, 0.5, 1.0);
+
+    #[test]
+    fn test_linear_gradient_constructor_midpoint() {
+        let shader = shaders::linear_gradient(
+            Point::new(10.0, 0.0),
+            Point::new(110.0, 0.0),
+            &[BLACK, WHITE],
+            None,
+            TileMode::Clamp,
+        )
+        .unwrap();
+        assert_color_near(shader.sample(60.0, 37.0), GRAY);
+        assert_color_near(shader.sample(0.0, 0.0), BLACK);
+        assert_color_near(shader.sample(200.0, 0.0), WHITE);
+
+        // Stops move the midpoint; before the first stop is the first color.
+        let shader = shaders::linear_gradient(
+            Point::new(0.0, 0.0),
+            Point::new(100.0, 0.0),
+            &[BLACK, WHITE],
+            Some(&[0.2, 0.6]),
+            TileMode::Clamp,
+        )
+        .unwrap();
+        assert_color_near(shader.sample(40.0, 0.0), GRAY);
+        assert_color_near(shader.sample(10.0, 0.0), BLACK);
+        assert_color_near(shader.sample(80.0, 0.0), WHITE);
+    }
+
+    #[test]
+    fn test_radial_gradient_constructor_midpoint() {
+        let shader = shaders::radial_gradient(
+            Point::new(50.0, 50.0),
+            40.0,
+            &[BLACK, WHITE],
+            Some(&[0.0, 1.0]),
+            TileMode::Clamp,
+        )
+        .unwrap();
+        assert_color_near(shader.sample(50.0, 50.0), BLACK);
+        assert_color_near(shader.sample(50.0, 70.0), GRAY);
+        assert_color_near(shader.sample(70.0, 50.0), GRAY);
+        assert_color_near(shader.sample(0.0, 0.0), WHITE);
+    }
+
+    #[test]
+    fn test_gradient_tile_modes() {
+        let sample = |tile_mode, x| {
+            shaders::linear_gradient(
+                Point::new(0.0, 0.0),
+                Point::new(100.0, 0.0),
+                &[BLACK, WHITE],
+                None,
+                tile_mode,
+            )
+            .unwrap()
+            .sample(x, 0.0)
+        };
+        let quarter = Color4f::new(0.25, 0.25, 0.25, 1.0);
+        let three_quarters = Color4f::new(0.75, 0.75, 0.75, 1.0);
+
+        assert_color_near(sample(TileMode::Clamp, 125.0), WHITE);
+        assert_color_near(sample(TileMode::Repeat, 125.0), quarter);
+        assert_color_near(sample(TileMode::Mirror, 125.0), three_quarters);
+        assert_color_near(sample(TileMode::Repeat, -25.0), three_quarters);
+        assert_color_near(sample(TileMode::Mirror, -25.0), quarter);
+    }
+
+    #[test]
+    fn test_sweep_gradient_constructor_wheel() {
+        const RED: Color4f = Color4f::new(1.0, 0.0, 0.0, 1.0);
+        const GREEN: Color4f = Color4f::new(0.0, 1.0, 0.0, 1.0);
+        const BLUE: Color4f = Color4f::new(0.0, 0.0, 1.0, 1.0);
+        let wheel = [RED, GREEN, BLUE, WHITE];
+        let stops = [0.0, 0.25, 0.5, 0.75];
+        let center = Point::new(50.0, 50.0);
+
+        let shader =
+            shaders::sweep_gradient(center, 0.0, None, &wheel, Some(&stops), TileMode::Clamp)
+                .unwrap();
+        assert_color_near(shader.sample(90.0, 50.0), RED);
+        assert_color_near(shader.sample(50.0, 90.0), GREEN);
+        assert_color_near(shader.sample(10.0, 50.0), BLUE);
+        assert_color_near(shader.sample(50.0, 10.0), WHITE);
+
+        // Starting at 90 degrees turns the wheel a quarter clockwise.
+        let shader =
+            shaders::sweep_gradient(center, 90.0, None, &wheel, Some(&stops), TileMode::Clamp)
+                .unwrap();
+        assert_color_near(shader.sample(50.0, 90.0), RED);
+        assert_color_near(shader.sample(10.0, 50.0), GREEN);
+        assert_color_near(shader.sample(90.0, 50.0), WHITE);
+    }
+
+    #[test]
+    fn test_sweep_gradient_constructor_arc() {
+        let center = Point::new(0.0, 0.0);
+        let arc = |tile_mode| {
+            shaders::sweep_gradient(center, 0.0, Some(90.0), &[BLACK, WHITE], None, tile_mode)
+                .unwrap()
+        };
+
+        // 45 degrees is halfway along the arc; 135 is half an arc past it.
+        assert_color_near(arc(TileMode::Clamp).sample(10.0, 10.0), GRAY);
+        assert_color_near(arc(TileMode::Clamp).sample(-10.0, 10.0), WHITE);
+        assert_color_near(arc(TileMode::Repeat).sample(-10.0, 10.0), GRAY);
+        assert_color_near(arc(TileMode::Mirror).sample(-10.0, 10.0), GRAY);
+
+        let empty = shaders::sweep_gradient(
+            center,
+            90.0,
+            Some(90.0),
+            &[BLACK, WHITE],
+            None,
+            TileMode::Clamp,
+        );
+        assert!(empty.is_none());
+    }
+
+    /// A 2x2 checkerboard with black at the origin.
+    const CHECKER: [Color4f; 4] = [BLACK, WHITE, WHITE, BLACK];
+
+    #[test]
+    fn test_image_shader_tiles_checkerboard() {
+        let shader = ImageShader::from_pixels(
+            2,
+            2,
+            &CHECKER,
+            TileMode::Repeat,
+            TileMode::Repeat,
+            SamplingOptions::NEAREST,
+        )
+        .unwrap();
+        for y in 0..8 {
+            for x in 0..8 {
+                let expected = if (x + y) % 2 == 0 { BLACK } else { WHITE };
+                assert_color_near(shader.sample(x as f32 + 0.5, y as f32 + 0.5), expected);
+            }
+        }
+
+        let sample = |tile_mode, x: Scalar| {
+            ImageShader::from_pixels(
+                2,
+                2,
+                &CHECKER,
+                tile_mode,
+                tile_mode,
+                SamplingOptions::NEAREST,
+            )
+            .unwrap()
+            .sample(x, 0.5)
+        };
+        assert_color_near(sample(TileMode::Clamp, 5.5), WHITE);
+        assert_color_near(sample(TileMode::Mirror, 2.5), WHITE);
+        assert_color_near(sample(TileMode::Mirror, -0.5), BLACK);
+        assert_color_near(sample(TileMode::Decal, 2.5), Color4f::transparent());
+
+        assert!(
+            ImageShader::from_pixels(
+                2,
+                2,
+                &CHECKER[..3],
+                TileMode::Repeat,
+                TileMode::Repeat,
+                SamplingOptions::NEAREST
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_image_shader_bilinear_premultiplied() {
+        let shader = ImageShader::from_pixels(
+            2,
+            1,
+            &[BLACK, WHITE],
+            TileMode::Clamp,
+            TileMode::Clamp,
+            SamplingOptions::LINEAR,
+        )
+        .unwrap();
+        assert_color_near(shader.sample(1.0, 0.5), GRAY);
+        assert_color_near(shader.sample(0.5, 0.5), BLACK);
+
+        // A transparent neighbour fades alpha without tinting the color.
+        let blue = Color4f::new(0.0, 0.0, 1.0, 1.0);
+        let shader = ImageShader::from_pixels(
+            2,
+            1,
+            &[Color4f::new(1.0, 0.0, 0.0, 0.0), blue],
+            TileMode::Clamp,
+            TileMode::Clamp,
+            SamplingOptions::LINEAR,
+        )
+        .unwrap();
+        assert_color_near(shader.sample(1.0, 0.5), Color4f::new(0.0, 0.0, 1.0, 0.5));
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn test_image_shader_constructor_from_image() {
+        use skia_rs_codec::{Image, ImageInfo};
+        use skia_rs_core::{AlphaType, ColorType};
+
+        let info = ImageInfo::new(2, 2, ColorType::Rgba8888, AlphaType::Unpremul);
+        let pixels: Vec<u8> = CHECKER
+            .iter()
+            .flat_map(|c| {
+                let c = c.to_color();
+                [c.red(), c.green(), c.blue(), c.alpha()]
+            })
+            .collect();
+        let image = Image::from_raster_data(&info, &pixels, 8).unwrap();
+
+        let shader = shaders::image(
+            &image,
+            TileMode::Repeat,
+            TileMode::Repeat,
+            SamplingOptions::NEAREST,
+            Matrix::scale(2.0, 2.0),
+        )
+        .unwrap();
+        assert_eq!(shader.local_matrix(), Some(&Matrix::scale(2.0, 2.0)));
+        assert!(shader.is_opaque());
+        // Sampling happens in image space; the local matrix is applied by the
+        // rasterizer.
+        assert_color_near(shader.sample(0.5, 0.5), BLACK);
+        assert_color_near(shader.sample(3.5, 0.5), WHITE);
+
+        // Texels are kept premultiplied in 8 bits whatever the image's
+        // alpha type, and come back unpremultiplied.
+        let half_red = [
+            (AlphaType::Unpremul, [255, 0, 0, 128]),
+            (AlphaType::Premul, [128, 0, 0, 128]),
+        ];
+        for (alpha_type, texel) in half_red {
+            let info = ImageInfo::new(1, 1, ColorType::Rgba8888, alpha_type);
+            let image = Image::from_raster_data(&info, &texel, 4).unwrap();
+            let bounds = skia_rs_core::IRect::new(0, 0, 1, 1);
+            let shader = ImageShader::from_image(
+                &image,
+                &bounds,
+                TileMode::Clamp,
+                TileMode::Clamp,
+                SamplingOptions::LINEAR,
+            )
+            .unwrap();
+            assert_eq!(shader.pixels.as_deref(), Some(&[[128, 0, 0, 128]][..]));
+            assert!(!shader.is_opaque());
+            assert_color_near(
+                shader.sample(0.5, 0.5),
+                Color4f::new(1.0, 0.0, 0.0, 128.0 / 255.0),
+            );
+        }
+    }
+
+    #[test]
+    fn test_gradient_constructor_validation() {
+        let center = Point::new(0.0, 0.0);
+        assert!(shaders::radial_gradient(center, 1.0, &[], None, TileMode::Clamp).is_none());
+        let mismatched =
+            shaders::radial_gradient(center, 1.0, &[BLACK, WHITE], Some(&[0.0]), TileMode::Clamp);
+        assert!(mismatched.is_none());
+        let decreasing = shaders::linear_gradient(
+            center,
+            Point::new(1.0, 0.0),
+            &[BLACK, WHITE],
+            Some(&[0.8, 0.2]),
+            TileMode::Clamp,
+        );
+        assert!(decreasing.is_none());
+    }
 }
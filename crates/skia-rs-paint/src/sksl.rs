@@ -6,6 +6,15 @@
 //! - Parser for building an AST
 //! - Type system for SkSL types
 //! - Compilation to target languages (GLSL, SPIR-V, MSL, WGSL)
+//!
+//! WGSL generation lives in [`SkslProgram::to_wgsl`].
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 /// SkSL token types.
 #[derive(Debug, Clone, PartialEq)]
@@ -182,7 +191,7 @@ pub enum Token {
 /// SkSL lexer.
 pub struct Lexer<'a> {
     source: &'a str,
-    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    chars: core::iter::Peekable<core::str::CharIndices<'a>>,
     current_pos: usize,
 }
 
@@ -929,6 +938,39 @@ pub struct SkslProgram {
     pub children: Vec<UniformDecl>,
 }
 
+impl SkslProgram {
+    /// Parse a complete SkSL program.
+    pub fn parse(source: &str) -> Result<Self, SkslError> {
+        Parser::new(source)
+            .parse_program()
+            .map_err(SkslError::Parse)
+    }
+}
+
+/// Error type for SkSL parsing and code generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkslError {
+    /// The source failed to parse.
+    Parse(String),
+    /// The program is malformed, e.g. it has no entry point or names an
+    /// unknown variable or function.
+    Invalid(String),
+    /// The program uses a construct the target language cannot express.
+    Unsupported(String),
+}
+
+impl core::fmt::Display for SkslError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SkslError::Parse(msg) => write!(f, "Parse error: {}", msg),
+            SkslError::Invalid(msg) => write!(f, "Invalid program: {}", msg),
+            SkslError::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
+        }
+    }
+}
+
+impl core::error::Error for SkslError {}
+
 /// SkSL parser.
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
@@ -969,7 +1011,7 @@ impl<'a> Parser<'a> {
     }
 
     fn advance(&mut self) -> Token {
-        let current = std::mem::replace(
+        let current = core::mem::replace(
             &mut self.current,
             self.peeked
                 .take()
@@ -986,7 +1028,7 @@ impl<'a> Parser<'a> {
     }
 
     fn check(&self, expected: &Token) -> bool {
-        std::mem::discriminant(&self.current) == std::mem::discriminant(expected)
+        core::mem::discriminant(&self.current) == core::mem::discriminant(expected)
     }
 
     fn expect(&mut self, expected: &Token) -> Result<Token, String> {
@@ -0,0 +1,1152 @@
+//! Tree-walking evaluation of parsed SkSL programs.
+//!
+//! The raster backend has no shader compiler, so runtime effects are run
+//! directly over the AST: every sample calls the program's `main` once with
+//! the uniform values bound as read-only globals. Scalars, vectors and
+//! matrices are fixed-size values, so ordinary math does not allocate.
+
+use crate::sksl::{BinaryOp, Expr, FnDecl, ParamQualifier, SkslProgram, SkslType, Stmt, UnaryOp};
+use alloc::{format, string::String, vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
+
+/// Upper bound on the iterations of a single loop, so that a runaway shader
+/// cannot hang the rasterizer.
+const MAX_LOOP_ITERATIONS: usize = 1 << 16;
+
+/// Upper bound on nested user function calls.
+const MAX_CALL_DEPTH: usize = 64;
+
+/// A value produced while evaluating SkSL.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    /// The result of a `void` function.
+    Void,
+    /// A boolean scalar.
+    Bool(bool),
+    /// An integer scalar.
+    Int(i32),
+    /// A float scalar.
+    Float(f32),
+    /// A float vector with 2 to 4 components.
+    Vec([f32; 4], usize),
+    /// A boolean vector with 2 to 4 components.
+    BVec([bool; 4], usize),
+    /// A square column-major matrix of dimension 2 to 4.
+    Mat([f32; 16], usize),
+    /// A fixed-size array.
+    Array(Vec<Value>),
+}
+
+/// Why evaluation stopped early.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Fault {
+    /// The program executed `discard`.
+    Discard,
+    /// The program cannot be evaluated.
+    Error(String),
+}
+
+type EvalResult<T> = Result<T, Fault>;
+
+fn fault<T>(msg: impl Into<String>) -> EvalResult<T> {
+    Err(Fault::Error(msg.into()))
+}
+
+/// Shape of a numeric value, used for component-wise operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Scalar,
+    Vector(usize),
+    Matrix(usize),
+}
+
+impl Shape {
+    fn len(self) -> usize {
+        match self {
+            Shape::Scalar => 1,
+            Shape::Vector(n) => n,
+            Shape::Matrix(n) => n * n,
+        }
+    }
+}
+
+impl Value {
+    /// Build a float scalar or vector from components.
+    pub(crate) fn vector(components: &[f32]) -> Self {
+        match components {
+            [x] => Value::Float(*x),
+            _ => {
+                let mut v = [0.0; 4];
+                v[..components.len()].copy_from_slice(components);
+                Value::Vec(v, components.len())
+            }
+        }
+    }
+
+    /// The four components of a `vec4`/`half4` result.
+    pub(crate) fn as_vec4(&self) -> Option<[f32; 4]> {
+        match self {
+            Value::Vec(v, 4) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// The shape and components of a numeric value. Integers and booleans
+    /// are promoted to float.
+    fn numeric(&self) -> Option<(Shape, [f32; 16])> {
+        let mut data = [0.0; 16];
+        let shape = match self {
+            Value::Float(f) => {
+                data[0] = *f;
+                Shape::Scalar
+            }
+            Value::Int(i) => {
+                data[0] = *i as f32;
+                Shape::Scalar
+            }
+            Value::Bool(b) => {
+                data[0] = f32::from(u8::from(*b));
+                Shape::Scalar
+            }
+            Value::Vec(v, n) => {
+                data[..*n].copy_from_slice(&v[..*n]);
+                Shape::Vector(*n)
+            }
+            Value::BVec(v, n) => {
+                for (d, b) in data.iter_mut().zip(&v[..*n]) {
+                    *d = f32::from(u8::from(*b));
+                }
+                Shape::Vector(*n)
+            }
+            Value::Mat(m, n) => {
+                data = *m;
+                Shape::Matrix(*n)
+            }
+            Value::Void | Value::Array(_) => return None,
+        };
+        Some((shape, data))
+    }
+
+    fn from_shape(shape: Shape, data: &[f32; 16]) -> Self {
+        match shape {
+            Shape::Scalar => Value::Float(data[0]),
+            Shape::Vector(n) => Value::vector(&data[..n]),
+            Shape::Matrix(n) => Value::Mat(*data, n),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Void => "void",
+            Value::Bool(_) => "bool",
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Vec(..) => "vector",
+            Value::BVec(..) => "bool vector",
+            Value::Mat(..) => "matrix",
+            Value::Array(_) => "array",
+        }
+    }
+
+    fn as_bool(&self) -> EvalResult<bool> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            other => fault(format!("expected bool, got {}", other.type_name())),
+        }
+    }
+
+    fn as_scalar(&self) -> EvalResult<f32> {
+        match self {
+            Value::Float(f) => Ok(*f),
+            Value::Int(i) => Ok(*i as f32),
+            other => fault(format!("expected a scalar, got {}", other.type_name())),
+        }
+    }
+
+    fn as_index(&self) -> EvalResult<usize> {
+        match self {
+            Value::Int(i) if *i >= 0 => Ok(*i as usize),
+            Value::Float(f) if *f >= 0.0 => Ok(*f as usize),
+            _ => fault("array index must be a non-negative integer"),
+        }
+    }
+
+    /// The components of a scalar or vector, for geometric built-ins.
+    fn components(&self) -> EvalResult<([f32; 4], usize)> {
+        match self {
+            Value::Vec(v, n) => Ok((*v, *n)),
+            other => Ok(([other.as_scalar()?, 0.0, 0.0, 0.0], 1)),
+        }
+    }
+}
+
+/// The zero value of a declared type.
+fn zero(ty: &SkslType) -> EvalResult<Value> {
+    Ok(match ty {
+        SkslType::Bool => Value::Bool(false),
+        SkslType::Int => Value::Int(0),
+        SkslType::Float | SkslType::Half => Value::Float(0.0),
+        SkslType::Mat2 => Value::Mat([0.0; 16], 2),
+        SkslType::Mat3 => Value::Mat([0.0; 16], 3),
+        SkslType::Mat4 => Value::Mat([0.0; 16], 4),
+        SkslType::Array(elem, len) => Value::Array(vec![zero(elem)?; *len]),
+        ty => match ty.vector_size() {
+            Some(n) => Value::Vec([0.0; 4], n),
+            None => return fault(format!("unsupported type {ty:?}")),
+        },
+    })
+}
+
+/// Apply the implicit int/float conversion of a declaration or return.
+fn coerce(value: Value, ty: &SkslType) -> Value {
+    match (ty, value) {
+        (SkslType::Float | SkslType::Half, Value::Int(i)) => Value::Float(i as f32),
+        (SkslType::Int, Value::Float(f)) => Value::Int(f as i32),
+        (_, value) => value,
+    }
+}
+
+/// Keep a variable's scalar type stable across assignments.
+fn conform(value: Value, current: &Value) -> Value {
+    match (current, value) {
+        (Value::Float(_), Value::Int(i)) => Value::Float(i as f32),
+        (Value::Int(_), Value::Float(f)) => Value::Int(f as i32),
+        (_, value) => value,
+    }
+}
+
+/// Evaluate the entry point `main` of `program`.
+///
+/// `globals` holds the uniform values by name. Returns [`Fault::Discard`] if
+/// the program discards and [`Fault::Error`] if it uses something the
+/// evaluator does not support.
+pub(crate) fn call_main(
+    program: &SkslProgram,
+    globals: &[(String, Value)],
+    args: Vec<Value>,
+) -> Result<Value, Fault> {
+    let main = program
+        .functions
+        .iter()
+        .find(|f| f.name == "main" && f.params.len() == args.len())
+        .ok_or_else(|| Fault::Error("no main function with a matching signature".into()))?;
+    let mut evaluator = Evaluator {
+        program,
+        globals,
+        locals: Vec::new(),
+        frame: 0,
+        depth: 0,
+    };
+    evaluator.invoke(main, args).map(|(value, _)| value)
+}
+
+/// Control flow out of a statement.
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+struct Evaluator<'a> {
+    program: &'a SkslProgram,
+    globals: &'a [(String, Value)],
+    /// Local variables of every active call, innermost last.
+    locals: Vec<(&'a str, Value)>,
+    /// Start of the current call's locals.
+    frame: usize,
+    depth: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Run `func` with already-evaluated arguments, returning its result and
+    /// the final values of its parameters.
+    fn invoke(&mut self, func: &'a FnDecl, args: Vec<Value>) -> EvalResult<(Value, Vec<Value>)> {
+        if self.depth >= MAX_CALL_DEPTH {
+            return fault("call depth limit exceeded");
+        }
+        let base = self.locals.len();
+        let saved_frame = core::mem::replace(&mut self.frame, base);
+        for (param, arg) in func.params.iter().zip(args) {
+            self.locals
+                .push((param.name.as_str(), coerce(arg, &param.ty)));
+        }
+
+        self.depth += 1;
+        let flow = self.exec(&func.body);
+        self.depth -= 1;
+
+        let params = self.locals[base..base + func.params.len()]
+            .iter()
+            .map(|(_, v)| v.clone())
+            .collect();
+        self.locals.truncate(base);
+        self.frame = saved_frame;
+
+        let result = match flow? {
+            Flow::Return(value) => coerce(value, &func.return_type),
+            _ if func.return_type == SkslType::Void => Value::Void,
+            _ => return fault(format!("function '{}' did not return a value", func.name)),
+        };
+        Ok((result, params))
+    }
+
+    fn lookup(&self, name: &str) -> EvalResult<Value> {
+        if let Some((_, value)) = self.locals[self.frame..]
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+        {
+            return Ok(value.clone());
+        }
+        match self.globals.iter().find(|(n, _)| n == name) {
+            Some((_, value)) => Ok(value.clone()),
+            None => fault(format!("unknown variable '{name}'")),
+        }
+    }
+
+    fn exec(&mut self, stmt: &'a Stmt) -> EvalResult<Flow> {
+        match stmt {
+            Stmt::Expr(expr) => {
+                self.eval(expr)?;
+                Ok(Flow::Next)
+            }
+            Stmt::VarDecl { ty, name, init } => {
+                let value = match init {
+                    Some(init) => coerce(self.eval(init)?, ty),
+                    None => zero(ty)?,
+                };
+                self.locals.push((name.as_str(), value));
+                Ok(Flow::Next)
+            }
+            Stmt::Block(stmts) => {
+                let mark = self.locals.len();
+                for stmt in stmts {
+                    let flow = self.exec(stmt)?;
+                    if !matches!(flow, Flow::Next) {
+                        self.locals.truncate(mark);
+                        return Ok(flow);
+                    }
+                }
+                self.locals.truncate(mark);
+                Ok(Flow::Next)
+            }
+            Stmt::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                if self.eval(cond)?.as_bool()? {
+                    self.exec(then_branch)
+                } else if let Some(else_branch) = else_branch {
+                    self.exec(else_branch)
+                } else {
+                    Ok(Flow::Next)
+                }
+            }
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+            } => {
+                let mark = self.locals.len();
+                if let Some(init) = init {
+                    self.exec(init)?;
+                }
+                let flow = self.run_loop(cond.as_ref(), update.as_ref(), body, true)?;
+                self.locals.truncate(mark);
+                Ok(flow)
+            }
+            Stmt::While { cond, body } => self.run_loop(Some(cond), None, body, true),
+            Stmt::DoWhile { body, cond } => self.run_loop(Some(cond), None, body, false),
+            Stmt::Return(expr) => Ok(Flow::Return(match expr {
+                Some(expr) => self.eval(expr)?,
+                None => Value::Void,
+            })),
+            Stmt::Break => Ok(Flow::Break),
+            Stmt::Continue => Ok(Flow::Continue),
+            Stmt::Discard => Err(Fault::Discard),
+        }
+    }
+
+    /// Run a loop body until `cond` fails. `test_first` is false for
+    /// do-while loops, whose first iteration is unconditional.
+    fn run_loop(
+        &mut self,
+        cond: Option<&'a Expr>,
+        update: Option<&'a Expr>,
+        body: &'a Stmt,
+        test_first: bool,
+    ) -> EvalResult<Flow> {
+        for iteration in 0..MAX_LOOP_ITERATIONS {
+            if test_first || iteration > 0 {
+                if let Some(cond) = cond {
+                    if !self.eval(cond)?.as_bool()? {
+                        return Ok(Flow::Next);
+                    }
+                }
+            }
+            match self.exec(body)? {
+                Flow::Break => return Ok(Flow::Next),
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Next | Flow::Continue => {}
+            }
+            if let Some(update) = update {
+                self.eval(update)?;
+            }
+        }
+        fault("loop iteration limit exceeded")
+    }
+
+    fn eval(&mut self, expr: &'a Expr) -> EvalResult<Value> {
+        match expr {
+            Expr::IntLit(i) => Ok(Value::Int(*i)),
+            Expr::FloatLit(f) => Ok(Value::Float(*f)),
+            Expr::BoolLit(b) => Ok(Value::Bool(*b)),
+            Expr::Var(name) => self.lookup(name),
+            Expr::Binary {
+                left,
+                op: BinaryOp::And,
+                right,
+            } => Ok(Value::Bool(
+                self.eval(left)?.as_bool()? && self.eval(right)?.as_bool()?,
+            )),
+            Expr::Binary {
+                left,
+                op: BinaryOp::Or,
+                right,
+            } => Ok(Value::Bool(
+                self.eval(left)?.as_bool()? || self.eval(right)?.as_bool()?,
+            )),
+            Expr::Binary { left, op, right } => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                binary(*op, &left, &right)
+            }
+            Expr::Unary { op, expr } => unary(*op, self.eval(expr)?),
+            Expr::Call { name, args } => self.call(name, args),
+            Expr::Constructor { ty, args } => {
+                let args = self.eval_args(args)?;
+                construct(ty, &args)
+            }
+            Expr::Field { expr, field } => swizzle(&self.eval(expr)?, field),
+            Expr::Index { expr, index } => {
+                let base = self.eval(expr)?;
+                let index = self.eval(index)?.as_index()?;
+                element(&base, index)
+            }
+            Expr::Ternary {
+                cond,
+                then_expr,
+                else_expr,
+            } => {
+                if self.eval(cond)?.as_bool()? {
+                    self.eval(then_expr)
+                } else {
+                    self.eval(else_expr)
+                }
+            }
+            Expr::Assign { target, value } => {
+                let value = self.eval(value)?;
+                self.assign(target, value)
+            }
+            Expr::CompoundAssign { target, op, value } => {
+                let value = self.eval(value)?;
+                let current = self.eval(target)?;
+                let result = binary(*op, &current, &value)?;
+                self.assign(target, result)
+            }
+            Expr::PostIncDec { expr, inc } => {
+                let current = self.eval(expr)?;
+                self.assign(expr, step_by_one(&current, *inc)?)?;
+                Ok(current)
+            }
+            Expr::PreIncDec { expr, inc } => {
+                let current = self.eval(expr)?;
+                self.assign(expr, step_by_one(&current, *inc)?)
+            }
+        }
+    }
+
+    fn eval_args(&mut self, args: &'a [Expr]) -> EvalResult<Vec<Value>> {
+        args.iter().map(|arg| self.eval(arg)).collect()
+    }
+
+    /// Store `value` into an assignable expression and return the stored
+    /// value.
+    fn assign(&mut self, target: &'a Expr, value: Value) -> EvalResult<Value> {
+        match target {
+            Expr::Var(name) => {
+                let frame = self.frame;
+                match self.locals[frame..]
+                    .iter_mut()
+                    .rev()
+                    .find(|(n, _)| n == name)
+                {
+                    Some((_, slot)) => {
+                        *slot = conform(value, slot);
+                        Ok(slot.clone())
+                    }
+                    None if self.globals.iter().any(|(n, _)| n == name) => {
+                        fault(format!("cannot assign to uniform '{name}'"))
+                    }
+                    None => fault(format!("unknown variable '{name}'")),
+                }
+            }
+            Expr::Field { expr, field } => {
+                let base = self.eval(expr)?;
+                let updated = set_swizzle(base, field, &value)?;
+                self.assign(expr, updated)?;
+                Ok(value)
+            }
+            Expr::Index { expr, index } => {
+                let index = self.eval(index)?.as_index()?;
+                let base = self.eval(expr)?;
+                let updated = set_element(base, index, value.clone())?;
+                self.assign(expr, updated)?;
+                Ok(value)
+            }
+            _ => fault("expression is not assignable"),
+        }
+    }
+
+    fn call(&mut self, name: &str, args: &'a [Expr]) -> EvalResult<Value> {
+        let program = self.program;
+        let Some(func) = program
+            .functions
+            .iter()
+            .find(|f| f.name == name && f.params.len() == args.len())
+        else {
+            let values = self.eval_args(args)?;
+            return builtin(name, &values)
+                .unwrap_or_else(|| fault(format!("unknown function '{name}'")));
+        };
+
+        let mut values = Vec::with_capacity(args.len());
+        for (param, arg) in func.params.iter().zip(args) {
+            values.push(match param.qualifier {
+                ParamQualifier::Out => zero(&param.ty)?,
+                _ => self.eval(arg)?,
+            });
+        }
+        let (result, params) = self.invoke(func, values)?;
+        for ((param, arg), value) in func.params.iter().zip(args).zip(params) {
+            if matches!(param.qualifier, ParamQualifier::Out | ParamQualifier::InOut) {
+                self.assign(arg, value)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn step_by_one(value: &Value, inc: bool) -> EvalResult<Value> {
+    let op = if inc { BinaryOp::Add } else { BinaryOp::Sub };
+    match value {
+        Value::Int(_) => binary(op, value, &Value::Int(1)),
+        _ => binary(op, value, &Value::Float(1.0)),
+    }
+}
+
+fn unary(op: UnaryOp, value: Value) -> EvalResult<Value> {
+    match (op, value) {
+        (UnaryOp::Neg, Value::Int(i)) => Ok(Value::Int(i.wrapping_neg())),
+        (UnaryOp::Neg, value) => map(&value, |x| -x),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnaryOp::BitNot, Value::Int(i)) => Ok(Value::Int(!i)),
+        (op, value) => fault(format!("cannot apply {op:?} to {}", value.type_name())),
+    }
+}
+
+fn binary(op: BinaryOp, a: &Value, b: &Value) -> EvalResult<Value> {
+    use BinaryOp::*;
+
+    match op {
+        Eq => Ok(Value::Bool(equal(a, b)?)),
+        NotEq => Ok(Value::Bool(!equal(a, b)?)),
+        Lt | LtEq | Gt | GtEq => {
+            let (x, y) = (a.as_scalar()?, b.as_scalar()?);
+            Ok(Value::Bool(match op {
+                Lt => x < y,
+                LtEq => x <= y,
+                Gt => x > y,
+                _ => x >= y,
+            }))
+        }
+        And | Or => Ok(Value::Bool(match op {
+            And => a.as_bool()? && b.as_bool()?,
+            _ => a.as_bool()? || b.as_bool()?,
+        })),
+        BitAnd | BitOr | BitXor | Shl | Shr => match (a, b) {
+            (Value::Int(x), Value::Int(y)) => Ok(Value::Int(match op {
+                BitAnd => x & y,
+                BitOr => x | y,
+                BitXor => x ^ y,
+                Shl => x.wrapping_shl(*y as u32),
+                _ => x.wrapping_shr(*y as u32),
+            })),
+            _ => fault("bitwise operators require int operands"),
+        },
+        Add | Sub | Mul | Div | Mod => arithmetic(op, a, b),
+    }
+}
+
+fn equal(a: &Value, b: &Value) -> EvalResult<bool> {
+    match (a, b) {
+        (Value::Bool(x), Value::Bool(y)) => Ok(x == y),
+        (Value::BVec(x, n), Value::BVec(y, m)) => Ok(n == m && x[..*n] == y[..*m]),
+        (Value::Array(x), Value::Array(y)) => {
+            if x.len() != y.len() {
+                return Ok(false);
+            }
+            for (x, y) in x.iter().zip(y) {
+                if !equal(x, y)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        _ => match (a.numeric(), b.numeric()) {
+            (Some((sa, da)), Some((sb, db))) if sa == sb => Ok(da == db),
+            _ => fault(format!(
+                "cannot compare {} with {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        },
+    }
+}
+
+fn arithmetic(op: BinaryOp, a: &Value, b: &Value) -> EvalResult<Value> {
+    match (op, a, b) {
+        (_, Value::Int(x), Value::Int(y)) => {
+            let (x, y) = (*x, *y);
+            let result = match op {
+                BinaryOp::Add => Some(x.wrapping_add(y)),
+                BinaryOp::Sub => Some(x.wrapping_sub(y)),
+                BinaryOp::Mul => Some(x.wrapping_mul(y)),
+                BinaryOp::Div => x.checked_div(y),
+                _ => x.checked_rem(y),
+            };
+            match result {
+                Some(value) => Ok(Value::Int(value)),
+                None => fault("integer division by zero"),
+            }
+        }
+        (BinaryOp::Mul, Value::Mat(m, n), Value::Mat(k, p)) if n == p => {
+            let n = *n;
+            let mut out = [0.0; 16];
+            for c in 0..n {
+                for r in 0..n {
+                    out[c * n + r] = (0..n).map(|i| m[i * n + r] * k[c * n + i]).sum();
+                }
+            }
+            Ok(Value::Mat(out, n))
+        }
+        (BinaryOp::Mul, Value::Mat(m, n), Value::Vec(v, p)) if n == p => {
+            let n = *n;
+            let mut out = [0.0; 4];
+            for (r, o) in out.iter_mut().enumerate().take(n) {
+                *o = (0..n).map(|c| m[c * n + r] * v[c]).sum();
+            }
+            Ok(Value::Vec(out, n))
+        }
+        (BinaryOp::Mul, Value::Vec(v, p), Value::Mat(m, n)) if n == p => {
+            let n = *n;
+            let mut out = [0.0; 4];
+            for (c, o) in out.iter_mut().enumerate().take(n) {
+                *o = (0..n).map(|r| v[r] * m[c * n + r]).sum();
+            }
+            Ok(Value::Vec(out, n))
+        }
+        _ => zip(&[a.clone(), b.clone()], |[x, y]| match op {
+            BinaryOp::Add => x + y,
+            BinaryOp::Sub => x - y,
+            BinaryOp::Mul => x * y,
+            BinaryOp::Div => x / y,
+            _ => x - y * (x / y).floor(),
+        }),
+    }
+}
+
+/// Apply `f` to every component of a numeric value.
+fn map(value: &Value, f: impl Fn(f32) -> f32) -> EvalResult<Value> {
+    zip(core::slice::from_ref(value), |[x]| f(x))
+}
+
+/// Apply `f` component-wise across `N` numeric values, broadcasting scalars
+/// to the shape of the other operands.
+fn zip<const N: usize>(args: &[Value], f: impl Fn([f32; N]) -> f32) -> EvalResult<Value> {
+    if args.len() != N {
+        return fault(format!("expected {N} arguments, got {}", args.len()));
+    }
+    let mut shape = Shape::Scalar;
+    let mut data = [(Shape::Scalar, [0.0; 16]); N];
+    for (slot, arg) in data.iter_mut().zip(args) {
+        *slot = arg
+            .numeric()
+            .ok_or_else(|| Fault::Error(format!("expected a number, got {}", arg.type_name())))?;
+        match (shape, slot.0) {
+            (_, Shape::Scalar) => {}
+            (Shape::Scalar, s) => shape = s,
+            (s, t) if s == t => {}
+            _ => return fault("operand shapes do not match"),
+        }
+    }
+    let mut out = [0.0; 16];
+    for (i, o) in out.iter_mut().enumerate().take(shape.len()) {
+        *o = f(core::array::from_fn(|k| match data[k].0 {
+            Shape::Scalar => data[k].1[0],
+            _ => data[k].1[i],
+        }));
+    }
+    Ok(Value::from_shape(shape, &out))
+}
+
+/// Apply `f` component-wise, producing a boolean vector.
+fn compare(args: &[Value], f: impl Fn(f32, f32) -> bool) -> EvalResult<Value> {
+    match args {
+        [Value::Vec(x, n), Value::Vec(y, m)] if n == m => {
+            let mut out = [false; 4];
+            for i in 0..*n {
+                out[i] = f(x[i], y[i]);
+            }
+            Ok(Value::BVec(out, *n))
+        }
+        _ => fault("relational built-ins require two vectors of the same size"),
+    }
+}
+
+fn dot(a: &Value, b: &Value) -> EvalResult<f32> {
+    let ((x, n), (y, m)) = (a.components()?, b.components()?);
+    if n != m {
+        return fault("vector sizes do not match");
+    }
+    Ok((0..n).map(|i| x[i] * y[i]).sum())
+}
+
+fn scale(value: &Value, factor: f32) -> EvalResult<Value> {
+    map(value, |x| x * factor)
+}
+
+fn sub(a: &Value, b: &Value) -> EvalResult<Value> {
+    arithmetic(BinaryOp::Sub, a, b)
+}
+
+/// Evaluate a built-in function, or `None` if `name` is not one.
+fn builtin(name: &str, args: &[Value]) -> Option<EvalResult<Value>> {
+    let all_int = !args.is_empty() && args.iter().all(|a| matches!(a, Value::Int(_)));
+    let to_int = |result: EvalResult<Value>| match result {
+        Ok(Value::Float(f)) if all_int => Ok(Value::Int(f as i32)),
+        other => other,
+    };
+
+    let result = match (name, args) {
+        ("abs", [x]) => to_int(map(x, f32::abs)),
+        ("sign", [x]) => to_int(map(x, |x| {
+            if x > 0.0 {
+                1.0
+            } else if x < 0.0 {
+                -1.0
+            } else {
+                0.0
+            }
+        })),
+        ("floor", [x]) => map(x, f32::floor),
+        ("ceil", [x]) => map(x, f32::ceil),
+        ("fract", [x]) => map(x, |x| x - x.floor()),
+        ("trunc", [x]) => map(x, f32::trunc),
+        ("round", [x]) => map(x, f32::round),
+        ("roundEven", [x]) => map(x, f32::round_ties_even),
+        ("sqrt", [x]) => map(x, f32::sqrt),
+        ("inversesqrt", [x]) => map(x, |x| 1.0 / x.sqrt()),
+        ("exp", [x]) => map(x, f32::exp),
+        ("exp2", [x]) => map(x, f32::exp2),
+        ("log", [x]) => map(x, f32::ln),
+        ("log2", [x]) => map(x, f32::log2),
+        ("sin", [x]) => map(x, f32::sin),
+        ("cos", [x]) => map(x, f32::cos),
+        ("tan", [x]) => map(x, f32::tan),
+        ("asin", [x]) => map(x, f32::asin),
+        ("acos", [x]) => map(x, f32::acos),
+        ("atan", [x]) => map(x, f32::atan),
+        ("atan", [y, x]) => zip(&[y.clone(), x.clone()], |[y, x]| y.atan2(x)),
+        ("sinh", [x]) => map(x, f32::sinh),
+        ("cosh", [x]) => map(x, f32::cosh),
+        ("tanh", [x]) => map(x, f32::tanh),
+        ("radians", [x]) => map(x, f32::to_radians),
+        ("degrees", [x]) => map(x, f32::to_degrees),
+        ("saturate", [x]) => map(x, |x| x.clamp(0.0, 1.0)),
+        ("pow", _) => zip(args, |[x, y]| x.powf(y)),
+        ("mod", _) => zip(args, |[x, y]| x - y * (x / y).floor()),
+        ("min", _) => to_int(zip(args, |[x, y]| x.min(y))),
+        ("max", _) => to_int(zip(args, |[x, y]| x.max(y))),
+        ("clamp", _) => to_int(zip(args, |[x, lo, hi]| x.max(lo).min(hi))),
+        ("step", _) => zip(args, |[edge, x]| if x < edge { 0.0 } else { 1.0 }),
+        ("smoothstep", _) => zip(args, |[e0, e1, x]| {
+            let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        }),
+        ("mix", [a, b, Value::Bool(t)]) => Ok(if *t { b.clone() } else { a.clone() }),
+        ("mix", [Value::Vec(a, n), Value::Vec(b, m), Value::BVec(t, k)]) if n == m && m == k => {
+            let mut out = *a;
+            for i in 0..*n {
+                if t[i] {
+                    out[i] = b[i];
+                }
+            }
+            Ok(Value::Vec(out, *n))
+        }
+        ("mix", _) => zip(args, |[a, b, t]| a + (b - a) * t),
+        ("length", [x]) => dot(x, x).map(|d| Value::Float(d.sqrt())),
+        ("distance", [a, b]) => sub(a, b)
+            .and_then(|d| dot(&d, &d))
+            .map(|d| Value::Float(d.sqrt())),
+        ("dot", [a, b]) => dot(a, b).map(Value::Float),
+        ("normalize", [x]) => dot(x, x).and_then(|d| scale(x, 1.0 / d.sqrt())),
+        ("cross", [Value::Vec(a, 3), Value::Vec(b, 3)]) => Ok(Value::vector(&[
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ])),
+        ("reflect", [i, n]) => dot(n, i)
+            .and_then(|d| scale(n, 2.0 * d))
+            .and_then(|s| sub(i, &s)),
+        ("refract", [i, n, eta]) => eta.as_scalar().and_then(|eta| {
+            let d = dot(n, i)?;
+            let k = 1.0 - eta * eta * (1.0 - d * d);
+            if k < 0.0 {
+                scale(i, 0.0)
+            } else {
+                sub(&scale(i, eta)?, &scale(n, eta * d + k.sqrt())?)
+            }
+        }),
+        ("faceforward", [n, i, nref]) => dot(nref, i).and_then(|d| {
+            if d < 0.0 {
+                Ok(n.clone())
+            } else {
+                scale(n, -1.0)
+            }
+        }),
+        ("lessThan", _) => compare(args, |x, y| x < y),
+        ("lessThanEqual", _) => compare(args, |x, y| x <= y),
+        ("greaterThan", _) => compare(args, |x, y| x > y),
+        ("greaterThanEqual", _) => compare(args, |x, y| x >= y),
+        ("equal", _) => compare(args, |x, y| x == y),
+        ("notEqual", _) => compare(args, |x, y| x != y),
+        ("any", [Value::BVec(v, n)]) => Ok(Value::Bool(v[..*n].iter().any(|b| *b))),
+        ("all", [Value::BVec(v, n)]) => Ok(Value::Bool(v[..*n].iter().all(|b| *b))),
+        ("not", [Value::BVec(v, n)]) => Ok(Value::BVec(v.map(|b| !b), *n)),
+        ("matrixCompMult", [Value::Mat(..), Value::Mat(..)]) => zip(args, |[x, y]| x * y),
+        ("transpose", [Value::Mat(m, n)]) => {
+            let n = *n;
+            let mut out = [0.0; 16];
+            for c in 0..n {
+                for r in 0..n {
+                    out[r * n + c] = m[c * n + r];
+                }
+            }
+            Ok(Value::Mat(out, n))
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Evaluate a type constructor such as `float4(uv, 0, 1)` or `float2x2(1)`.
+fn construct(ty: &SkslType, args: &[Value]) -> EvalResult<Value> {
+    let (components, len) = flatten(args)?;
+    let single_scalar = matches!(args, [a] if matches!(a.numeric(), Some((Shape::Scalar, _))));
+    match ty {
+        SkslType::Float | SkslType::Half if len >= 1 => Ok(Value::Float(components[0])),
+        SkslType::Int if len >= 1 => Ok(Value::Int(components[0] as i32)),
+        SkslType::Bool if len >= 1 => Ok(Value::Bool(components[0] != 0.0)),
+        SkslType::Mat2 | SkslType::Mat3 | SkslType::Mat4 => {
+            let n = match ty {
+                SkslType::Mat2 => 2,
+                SkslType::Mat3 => 3,
+                _ => 4,
+            };
+            let mut out = [0.0; 16];
+            match args {
+                [Value::Mat(m, k)] => {
+                    for c in 0..n {
+                        for r in 0..n {
+                            out[c * n + r] = if c < *k && r < *k {
+                                m[c * k + r]
+                            } else if c == r {
+                                1.0
+                            } else {
+                                0.0
+                            };
+                        }
+                    }
+                }
+                _ if single_scalar => {
+                    for i in 0..n {
+                        out[i * n + i] = components[0];
+                    }
+                }
+                _ if len == n * n => out[..len].copy_from_slice(&components[..len]),
+                _ => return fault(format!("wrong number of components for {ty:?}")),
+            }
+            Ok(Value::Mat(out, n))
+        }
+        _ => match ty.vector_size() {
+            Some(n) if single_scalar => Ok(Value::Vec([components[0]; 4], n)),
+            Some(n) if len >= n => Ok(Value::vector(&components[..n])),
+            _ => fault(format!("cannot construct {ty:?} from {len} components")),
+        },
+    }
+}
+
+/// Concatenate the components of constructor arguments.
+fn flatten(args: &[Value]) -> EvalResult<([f32; 16], usize)> {
+    let mut out = [0.0; 16];
+    let mut len = 0;
+    for arg in args {
+        let Some((shape, data)) = arg.numeric() else {
+            return fault(format!("cannot construct from {}", arg.type_name()));
+        };
+        let n = shape.len();
+        if len + n > out.len() {
+            return fault("too many constructor components");
+        }
+        out[len..len + n].copy_from_slice(&data[..n]);
+        len += n;
+    }
+    Ok((out, len))
+}
+
+/// Component indices named by a swizzle such as `xy`, `rgba` or `stp`.
+fn swizzle_indices(field: &str) -> EvalResult<([usize; 4], usize)> {
+    let mut indices = [0; 4];
+    if field.is_empty() || field.len() > 4 {
+        return fault(format!("invalid swizzle '{field}'"));
+    }
+    for (slot, ch) in indices.iter_mut().zip(field.chars()) {
+        *slot = match ch {
+            'x' | 'r' | 's' => 0,
+            'y' | 'g' | 't' => 1,
+            'z' | 'b' | 'p' => 2,
+            'w' | 'a' | 'q' => 3,
+            _ => return fault(format!("invalid swizzle '{field}'")),
+        };
+    }
+    Ok((indices, field.len()))
+}
+
+fn swizzle(base: &Value, field: &str) -> EvalResult<Value> {
+    let (indices, len) = swizzle_indices(field)?;
+    let width = match base {
+        Value::Vec(_, n) | Value::BVec(_, n) => *n,
+        Value::Float(_) | Value::Int(_) | Value::Bool(_) => 1,
+        other => return fault(format!("cannot swizzle {}", other.type_name())),
+    };
+    if indices[..len].iter().any(|&i| i >= width) {
+        return fault(format!("swizzle '{field}' out of range"));
+    }
+    Ok(match base {
+        Value::Vec(v, _) => Value::vector(&indices.map(|i| v[i])[..len]),
+        Value::BVec(v, _) if len == 1 => Value::Bool(v[indices[0]]),
+        Value::BVec(v, _) => {
+            let mut out = [false; 4];
+            for (o, &i) in out.iter_mut().zip(&indices[..len]) {
+                *o = v[i];
+            }
+            Value::BVec(out, len)
+        }
+        scalar if len == 1 => scalar.clone(),
+        Value::Bool(b) => Value::BVec([*b; 4], len),
+        scalar => Value::Vec([scalar.as_scalar()?; 4], len),
+    })
+}
+
+fn set_swizzle(base: Value, field: &str, value: &Value) -> EvalResult<Value> {
+    let (indices, len) = swizzle_indices(field)?;
+    match base {
+        Value::Vec(mut v, n) => {
+            let (components, count) = value.components()?;
+            if count != len || indices[..len].iter().any(|&i| i >= n) {
+                return fault(format!("cannot assign to swizzle '{field}'"));
+            }
+            for (k, &i) in indices[..len].iter().enumerate() {
+                v[i] = components[k];
+            }
+            Ok(Value::Vec(v, n))
+        }
+        scalar if len == 1 && indices[0] == 0 => Ok(conform(value.clone(), &scalar)),
+        other => fault(format!(
+            "cannot assign to a swizzle of {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn element(base: &Value, index: usize) -> EvalResult<Value> {
+    match base {
+        Value::Vec(v, n) if index < *n => Ok(Value::Float(v[index])),
+        Value::BVec(v, n) if index < *n => Ok(Value::Bool(v[index])),
+        Value::Mat(m, n) if index < *n => Ok(Value::vector(&m[index * n..(index + 1) * n])),
+        Value::Array(items) if index < items.len() => Ok(items[index].clone()),
+        _ => fault(format!("index {index} out of range")),
+    }
+}
+
+fn set_element(base: Value, index: usize, value: Value) -> EvalResult<Value> {
+    match base {
+        Value::Vec(mut v, n) if index < n => {
+            v[index] = value.as_scalar()?;
+            Ok(Value::Vec(v, n))
+        }
+        Value::Mat(mut m, n) if index < n => match value {
+            Value::Vec(column, k) if k == n => {
+                m[index * n..(index + 1) * n].copy_from_slice(&column[..n]);
+                Ok(Value::Mat(m, n))
+            }
+            _ => fault("matrix columns must be assigned a vector of the same size"),
+        },
+        Value::Array(mut items) if index < items.len() => {
+            items[index] = conform(value, &items[index]);
+            Ok(Value::Array(items))
+        }
+        _ => fault(format!("index {index} out of range")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str, args: Vec<Value>) -> Result<Value, Fault> {
+        let program = SkslProgram::parse(source).unwrap();
+        call_main(&program, &[], args)
+    }
+
+    fn run_vec4(source: &str) -> [f32; 4] {
+        run(source, vec![Value::vector(&[0.0, 0.0])])
+            .unwrap()
+            .as_vec4()
+            .unwrap()
+    }
+
+    fn assert_close(actual: [f32; 4], expected: [f32; 4]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_vector_math_and_swizzles() {
+        let color = run_vec4(
+            r#"
+            half4 main(float2 p) {
+                float3 v = float3(1, 2, 3);
+                v.zx = v.xz;
+                float2 w = v.yz * 2.0 + 1;
+                half4 c = half4(w, length(float2(3, 4)), dot(v, float3(1)));
+                c.a -= 1;
+                return c;
+            }
+            "#,
+        );
+        assert_close(color, [5.0, 3.0, 5.0, 5.0]);
+    }
+
+    #[test]
+    fn test_builtins() {
+        let color = run_vec4(
+            r#"
+            half4 main(float2 p) {
+                return half4(
+                    clamp(1.5, 0.0, 1.0) + fract(2.25),
+                    mix(float2(0, 10), float2(10, 20), 0.5).y,
+                    smoothstep(0.0, 1.0, 0.5) + step(0.5, 0.25),
+                    max(abs(-2), min(3, 4)));
+            }
+            "#,
+        );
+        assert_close(color, [1.25, 15.0, 0.5, 3.0]);
+    }
+
+    #[test]
+    fn test_matrices() {
+        let color = run_vec4(
+            r#"
+            half4 main(float2 p) {
+                float2x2 rot = float2x2(0, 1, -1, 0);
+                float2 v = rot * float2(1, 0);
+                float2x2 twice = rot * rot;
+                return half4(v, twice[0][0], (float2(1, 0) * rot).y);
+            }
+            "#,
+        );
+        assert_close(color, [0.0, 1.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_control_flow_and_functions() {
+        let color = run_vec4(
+            r#"
+            float sum(int n) {
+                float total = 0;
+                for (int i = 1; i <= n; i++) {
+                    if (i == 3) { continue; }
+                    total += float(i);
+                }
+                return total;
+            }
+
+            void bump(inout float x, out float y) {
+                x *= 2.0;
+                y = 7.0;
+            }
+
+            half4 main(float2 p) {
+                float a = 1.5;
+                float b;
+                bump(a, b);
+                int k = 0;
+                while (true) {
+                    k++;
+                    if (k >= 4) { break; }
+                }
+                return half4(sum(5), a, b, k > 3 ? 1.0 : 0.0);
+            }
+            "#,
+        );
+        assert_close(color, [12.0, 3.0, 7.0, 1.0]);
+    }
+
+    #[test]
+    fn test_uniforms_and_faults() {
+        let program = SkslProgram::parse(
+            r#"
+            uniform float2 size;
+            half4 main(float2 p) {
+                if (p.x < 0) { discard; }
+                return half4(p / size, 0, 1);
+            }
+            "#,
+        )
+        .unwrap();
+        let globals = [("size".to_string(), Value::vector(&[4.0, 8.0]))];
+        let result = call_main(&program, &globals, vec![Value::vector(&[2.0, 2.0])]).unwrap();
+        assert_close(result.as_vec4().unwrap(), [0.5, 0.25, 0.0, 1.0]);
+
+        let discarded = call_main(&program, &globals, vec![Value::vector(&[-1.0, 0.0])]);
+        assert_eq!(discarded, Err(Fault::Discard));
+
+        let unknown = run(
+            "half4 main(float2 p) { return half4(nope(p), 0, 1); }",
+            vec![Value::vector(&[0.0, 0.0])],
+        );
+        assert!(matches!(unknown, Err(Fault::Error(_))));
+        let runaway = run(
+            "half4 main(float2 p) { while (true) {} return half4(0); }",
+            vec![Value::vector(&[0.0, 0.0])],
+        );
+        assert!(matches!(runaway, Err(Fault::Error(_))));
+    }
+}
@@ -0,0 +1,1074 @@
+//! WGSL code generation for SkSL programs.
+//!
+//! The translation is source to source: types map to their WGSL spelling
+//! (half precision becomes `f32`), uniforms are gathered into one uniform
+//! block, and constructs WGSL lacks are rewritten — ternaries become
+//! `select`, do-while loops become `loop` with a `continuing` block, and
+//! writes through multi-component swizzles are split per component.
+
+use crate::sksl::{
+    BinaryOp, Expr, FnDecl, ParamQualifier, SkslError, SkslProgram, SkslType, Stmt, UnaryOp,
+    UniformDecl,
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+/// Binding of the uniform block in group 0.
+const UNIFORM_BINDING: u32 = 0;
+
+/// Names the generated code uses for itself.
+const UNIFORM_STRUCT: &str = "Uniforms";
+const UNIFORM_VAR: &str = "uniforms";
+const ENTRY_POINT: &str = "fs_main";
+
+/// WGSL keywords and reserved words that SkSL allows as identifiers.
+const WGSL_RESERVED: &[&str] = &[
+    "alias",
+    "array",
+    "as",
+    "asm",
+    "async",
+    "atomic",
+    "await",
+    "become",
+    "bf16",
+    "binding_array",
+    "bitcast",
+    "cast",
+    "catch",
+    "class",
+    "co_await",
+    "co_return",
+    "co_yield",
+    "coherent",
+    "column_major",
+    "common",
+    "compile",
+    "compile_fragment",
+    "concept",
+    "const_assert",
+    "const_cast",
+    "consteval",
+    "constexpr",
+    "constinit",
+    "continuing",
+    "crate",
+    "debugger",
+    "decltype",
+    "default",
+    "delete",
+    "demote",
+    "demote_to_helper",
+    "diagnostic",
+    "enable",
+    "enum",
+    "explicit",
+    "export",
+    "extends",
+    "extern",
+    "external",
+    "f16",
+    "f32",
+    "f64",
+    "fallthrough",
+    "filter",
+    "final",
+    "finally",
+    "fn",
+    "friend",
+    "from",
+    "fxgroup",
+    "get",
+    "goto",
+    "groupshared",
+    "highp",
+    "i16",
+    "i32",
+    "i64",
+    "i8",
+    "impl",
+    "implements",
+    "import",
+    "inline",
+    "instanceof",
+    "interface",
+    "layout",
+    "let",
+    "loop",
+    "lowp",
+    "macro",
+    "macro_rules",
+    "mat2x2",
+    "mat2x3",
+    "mat2x4",
+    "mat3x2",
+    "mat3x3",
+    "mat3x4",
+    "mat4x2",
+    "mat4x3",
+    "mat4x4",
+    "match",
+    "mediump",
+    "meta",
+    "mod",
+    "module",
+    "move",
+    "mut",
+    "mutable",
+    "namespace",
+    "new",
+    "nil",
+    "noexcept",
+    "noinline",
+    "nointerpolation",
+    "non_coherent",
+    "noncoherent",
+    "noperspective",
+    "null",
+    "nullptr",
+    "of",
+    "operator",
+    "override",
+    "package",
+    "packoffset",
+    "partition",
+    "pass",
+    "patch",
+    "pixelfragment",
+    "precise",
+    "precision",
+    "premerge",
+    "priv",
+    "protected",
+    "ptr",
+    "pub",
+    "public",
+    "quat",
+    "readonly",
+    "ref",
+    "regardless",
+    "register",
+    "reinterpret_cast",
+    "require",
+    "requires",
+    "resource",
+    "restrict",
+    "sampler",
+    "self",
+    "set",
+    "shared",
+    "signed",
+    "sizeof",
+    "smooth",
+    "snorm",
+    "static",
+    "static_assert",
+    "static_cast",
+    "std",
+    "subroutine",
+    "super",
+    "switch",
+    "target",
+    "template",
+    "this",
+    "thread_local",
+    "throw",
+    "trait",
+    "try",
+    "type",
+    "typedef",
+    "typeid",
+    "typename",
+    "typeof",
+    "u16",
+    "u32",
+    "u64",
+    "u8",
+    "union",
+    "unless",
+    "unorm",
+    "unsafe",
+    "unsized",
+    "use",
+    "using",
+    "var",
+    "varying",
+    "vec2",
+    "vec3",
+    "vec4",
+    "virtual",
+    "volatile",
+    "wgsl",
+    "where",
+    "with",
+    "writeonly",
+    "yield",
+    UNIFORM_STRUCT,
+    UNIFORM_VAR,
+    ENTRY_POINT,
+];
+
+/// SkSL intrinsics with no WGSL counterpart here.
+const UNSUPPORTED_FUNCTIONS: &[&str] = &[
+    "eval",
+    "sample",
+    "unpremul",
+    "toLinearSrgb",
+    "fromLinearSrgb",
+    "matrixCompMult",
+    "inverse",
+];
+
+impl SkslProgram {
+    /// Translate the program into a WGSL fragment shader.
+    ///
+    /// The program must define `half4 main(float2 coord)`, which becomes a
+    /// helper called from the `@fragment` entry point `fs_main` with the
+    /// fragment's position in pixels. Uniforms are members of a `Uniforms`
+    /// struct bound as `@group(0) @binding(0) var<uniform> uniforms` and laid
+    /// out by WGSL's uniform rules, so uniform arrays need 16-byte aligned
+    /// elements. Child shaders, `out` parameters and assignments nested in
+    /// expressions are not supported.
+    pub fn to_wgsl(&self) -> Result<String, SkslError> {
+        WgslWriter::new(self).write_program()
+    }
+}
+
+/// Emits WGSL for one program.
+struct WgslWriter<'a> {
+    program: &'a SkslProgram,
+    out: String,
+    indent: usize,
+    /// Local variables in scope, innermost last.
+    scopes: Vec<BTreeMap<String, SkslType>>,
+    uniforms: BTreeMap<&'a str, SkslType>,
+    functions: BTreeMap<&'a str, &'a FnDecl>,
+    temporaries: usize,
+}
+
+impl<'a> WgslWriter<'a> {
+    fn new(program: &'a SkslProgram) -> Self {
+        Self {
+            program,
+            out: String::new(),
+            indent: 0,
+            scopes: Vec::new(),
+            uniforms: BTreeMap::new(),
+            functions: program
+                .functions
+                .iter()
+                .map(|f| (f.name.as_str(), f))
+                .collect(),
+            temporaries: 0,
+        }
+    }
+
+    fn write_program(mut self) -> Result<String, SkslError> {
+        let main = self
+            .functions
+            .get("main")
+            .copied()
+            .ok_or_else(|| SkslError::Invalid("missing `main` function".to_string()))?;
+        let coords = matches!(main.params.as_slice(), [p]
+            if normalize(&p.ty) == SkslType::Vec2
+                && matches!(p.qualifier, ParamQualifier::None | ParamQualifier::In));
+        if normalize(&main.return_type) != SkslType::Vec4 || !coords {
+            return Err(SkslError::Unsupported(
+                "only the shader entry point `half4 main(float2)` is supported".to_string(),
+            ));
+        }
+
+        for decl in &self.program.structs {
+            writeln!(self.out, "struct {} {{", ident(&decl.name)).unwrap();
+            for field in &decl.fields {
+                let ty = wgsl_type(&field.ty)?;
+                writeln!(self.out, "    {}: {},", ident(&field.name), ty).unwrap();
+            }
+            self.out.push_str("}\n\n");
+        }
+
+        if !self.program.uniforms.is_empty() {
+            writeln!(self.out, "struct {UNIFORM_STRUCT} {{").unwrap();
+            for uniform in &self.program.uniforms {
+                let ty = uniform_type(uniform)?;
+                writeln!(
+                    self.out,
+                    "    {}: {},",
+                    ident(&uniform.name),
+                    wgsl_type(&ty)?
+                )
+                .unwrap();
+                self.uniforms.insert(&uniform.name, ty);
+            }
+            self.out.push_str("}\n\n");
+            writeln!(
+                self.out,
+                "@group(0) @binding({UNIFORM_BINDING}) var<uniform> {UNIFORM_VAR}: {UNIFORM_STRUCT};\n"
+            )
+            .unwrap();
+        }
+
+        for function in &self.program.functions {
+            self.write_function(function)?;
+            self.out.push('\n');
+        }
+
+        writeln!(
+            self.out,
+            "@fragment\nfn {ENTRY_POINT}(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {{\n    return {}(frag_coord.xy);\n}}",
+            ident("main")
+        )
+        .unwrap();
+        Ok(self.out)
+    }
+
+    fn write_function(&mut self, function: &FnDecl) -> Result<(), SkslError> {
+        let mut assigned = BTreeSet::new();
+        assigned_in_stmt(&function.body, &mut assigned);
+
+        let mut scope = BTreeMap::new();
+        let mut params = Vec::new();
+        for param in &function.params {
+            if matches!(param.qualifier, ParamQualifier::Out | ParamQualifier::InOut) {
+                return Err(SkslError::Unsupported(format!(
+                    "`out` parameter `{}` of `{}`",
+                    param.name, function.name
+                )));
+            }
+            let name = if assigned.contains(&param.name) {
+                format!("{}_in", param.name)
+            } else {
+                ident(&param.name)
+            };
+            params.push(format!("{}: {}", name, wgsl_type(&param.ty)?));
+            scope.insert(param.name.clone(), normalize(&param.ty));
+        }
+
+        write!(
+            self.out,
+            "fn {}({})",
+            ident(&function.name),
+            params.join(", ")
+        )
+        .unwrap();
+        if function.return_type != SkslType::Void {
+            write!(self.out, " -> {}", wgsl_type(&function.return_type)?).unwrap();
+        }
+        self.out.push_str(" {\n");
+
+        self.indent = 1;
+        self.scopes = vec![scope];
+        for param in &function.params {
+            if assigned.contains(&param.name) {
+                self.line(&format!(
+                    "var {}: {} = {}_in;",
+                    ident(&param.name),
+                    wgsl_type(&param.ty)?,
+                    param.name
+                ));
+            }
+        }
+        match &function.body {
+            Stmt::Block(stmts) => self.write_stmts(stmts)?,
+            stmt => self.write_stmt(stmt)?,
+        }
+        self.scopes.clear();
+        self.indent = 0;
+        self.out.push_str("}\n");
+        Ok(())
+    }
+
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.out.push_str("    ");
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn write_stmts(&mut self, stmts: &[Stmt]) -> Result<(), SkslError> {
+        stmts.iter().try_for_each(|stmt| self.write_stmt(stmt))
+    }
+
+    /// Write `stmt` as the braced body of a control-flow statement.
+    fn write_body(&mut self, stmt: &Stmt) -> Result<(), SkslError> {
+        self.indent += 1;
+        self.scopes.push(BTreeMap::new());
+        let result = match stmt {
+            Stmt::Block(stmts) => self.write_stmts(stmts),
+            stmt => self.write_stmt(stmt),
+        };
+        self.scopes.pop();
+        self.indent -= 1;
+        result
+    }
+
+    fn write_stmt(&mut self, stmt: &Stmt) -> Result<(), SkslError> {
+        match stmt {
+            Stmt::Expr(expr) => self.write_expr_stmt(expr)?,
+            Stmt::VarDecl { .. } => {
+                let text = self.simple_stmt(stmt)?;
+                self.line(&format!("{text};"));
+            }
+            Stmt::Block(_) => {
+                self.line("{");
+                self.write_body(stmt)?;
+                self.line("}");
+            }
+            Stmt::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                let cond = self.expr(cond)?;
+                self.line(&format!("if {cond} {{"));
+                self.write_body(then_branch)?;
+                let mut else_branch = else_branch.as_deref();
+                while let Some(branch) = else_branch {
+                    if let Stmt::If {
+                        cond,
+                        then_branch,
+                        else_branch: next,
+                    } = branch
+                    {
+                        let cond = self.expr(cond)?;
+                        self.line(&format!("}} else if {cond} {{"));
+                        self.write_body(then_branch)?;
+                        else_branch = next.as_deref();
+                    } else {
+                        self.line("} else {");
+                        self.write_body(branch)?;
+                        break;
+                    }
+                }
+                self.line("}");
+            }
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+            } => {
+                self.scopes.push(BTreeMap::new());
+                let init = match init {
+                    Some(init) => self.simple_stmt(init)?,
+                    None => String::new(),
+                };
+                let cond = match cond {
+                    Some(cond) => self.expr(cond)?,
+                    None => String::new(),
+                };
+                let update = match update {
+                    Some(update) => self.simple_stmt(&Stmt::Expr(update.clone()))?,
+                    None => String::new(),
+                };
+                self.line(&format!("for ({init}; {cond}; {update}) {{"));
+                let result = self.write_body(body);
+                self.scopes.pop();
+                result?;
+                self.line("}");
+            }
+            Stmt::While { cond, body } => {
+                let cond = self.expr(cond)?;
+                self.line(&format!("while {cond} {{"));
+                self.write_body(body)?;
+                self.line("}");
+            }
+            Stmt::DoWhile { body, cond } => {
+                self.line("loop {");
+                self.write_body(body)?;
+                let cond = self.expr(cond)?;
+                self.indent += 1;
+                self.line(&format!("continuing {{ break if !{cond}; }}"));
+                self.indent -= 1;
+                self.line("}");
+            }
+            Stmt::Return(Some(expr)) => {
+                let expr = self.expr(expr)?;
+                self.line(&format!("return {expr};"));
+            }
+            Stmt::Return(None) => self.line("return;"),
+            Stmt::Break => self.line("break;"),
+            Stmt::Continue => self.line("continue;"),
+            Stmt::Discard => self.line("discard;"),
+        }
+        Ok(())
+    }
+
+    fn write_expr_stmt(&mut self, expr: &Expr) -> Result<(), SkslError> {
+        let (target, op, value) = match expr {
+            Expr::Assign { target, value } => (target, None, value),
+            Expr::CompoundAssign { target, op, value } => (target, Some(*op), value),
+            _ => {
+                let text = self.simple_stmt(&Stmt::Expr(expr.clone()))?;
+                self.line(&format!("{text};"));
+                return Ok(());
+            }
+        };
+
+        // WGSL only assigns through single-component swizzles.
+        let Some((base, components)) = self.multi_swizzle(target) else {
+            let text = self.simple_stmt(&Stmt::Expr(expr.clone()))?;
+            self.line(&format!("{text};"));
+            return Ok(());
+        };
+        let rhs = self.expr(value)?;
+        let target = self.expr(target)?;
+        let rhs = match op {
+            Some(op) => format!("({target} {} {rhs})", op.glsl_str()),
+            None => rhs,
+        };
+        let temporary = format!("_t{}", self.temporaries);
+        self.temporaries += 1;
+        self.line("{");
+        self.indent += 1;
+        self.line(&format!("let {temporary} = {rhs};"));
+        for (component, source) in components.chars().zip("xyzw".chars()) {
+            self.line(&format!("{base}.{component} = {temporary}.{source};"));
+        }
+        self.indent -= 1;
+        self.line("}");
+        Ok(())
+    }
+
+    /// The WGSL base and components of a swizzle writing several components.
+    fn multi_swizzle(&mut self, target: &Expr) -> Option<(String, String)> {
+        let Expr::Field { expr, field } = target else {
+            return None;
+        };
+        let components = swizzle(field)?;
+        if components.len() < 2 || !self.infer(expr).is_some_and(|t| t.is_vector()) {
+            return None;
+        }
+        Some((self.expr(expr).ok()?, components))
+    }
+
+    /// Translate a statement that can appear in a `for` header, without
+    /// the trailing semicolon.
+    fn simple_stmt(&mut self, stmt: &Stmt) -> Result<String, SkslError> {
+        match stmt {
+            Stmt::VarDecl { ty, name, init } => {
+                let wgsl = wgsl_type(ty)?;
+                let text = match init {
+                    Some(init) => format!("var {}: {} = {}", ident(name), wgsl, self.expr(init)?),
+                    None => format!("var {}: {}", ident(name), wgsl),
+                };
+                self.declare(name, ty);
+                Ok(text)
+            }
+            Stmt::Expr(Expr::Assign { target, value }) => {
+                self.check_single_target(target)?;
+                Ok(format!("{} = {}", self.expr(target)?, self.expr(value)?))
+            }
+            Stmt::Expr(Expr::CompoundAssign { target, op, value }) => {
+                self.check_single_target(target)?;
+                Ok(format!(
+                    "{} {}= {}",
+                    self.expr(target)?,
+                    op.glsl_str(),
+                    self.expr(value)?
+                ))
+            }
+            Stmt::Expr(Expr::PostIncDec { expr, inc } | Expr::PreIncDec { expr, inc }) => Ok(
+                format!("{}{}", self.expr(expr)?, if *inc { "++" } else { "--" }),
+            ),
+            Stmt::Expr(expr @ Expr::Call { name, .. })
+                if self
+                    .functions
+                    .get(name.as_str())
+                    .is_some_and(|f| f.return_type == SkslType::Void) =>
+            {
+                self.expr(expr)
+            }
+            Stmt::Expr(expr) => Ok(format!("_ = {}", self.expr(expr)?)),
+            _ => Err(SkslError::Unsupported(
+                "compound statement in a `for` header".to_string(),
+            )),
+        }
+    }
+
+    fn check_single_target(&mut self, target: &Expr) -> Result<(), SkslError> {
+        match self.multi_swizzle(target) {
+            Some(_) => Err(SkslError::Unsupported(
+                "swizzled assignment in a `for` header".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn declare(&mut self, name: &str, ty: &SkslType) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), normalize(ty));
+        }
+    }
+
+    fn local(&self, name: &str) -> Option<&SkslType> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<String, SkslError> {
+        Ok(match expr {
+            Expr::IntLit(n) => n.to_string(),
+            Expr::FloatLit(n) => format!("{n:?}"),
+            Expr::BoolLit(b) => b.to_string(),
+            Expr::Var(name) => {
+                if self.local(name).is_some() {
+                    ident(name)
+                } else if self.uniforms.contains_key(name.as_str()) {
+                    format!("{UNIFORM_VAR}.{}", ident(name))
+                } else {
+                    return Err(SkslError::Invalid(format!("unknown identifier `{name}`")));
+                }
+            }
+            Expr::Binary { left, op, right } => {
+                let vectors = self.infer(left).is_some_and(|t| t.is_vector())
+                    && self.infer(right).is_some_and(|t| t.is_vector());
+                let (l, r) = (self.expr(left)?, self.expr(right)?);
+                match op {
+                    BinaryOp::Eq if vectors => format!("all({l} == {r})"),
+                    BinaryOp::NotEq if vectors => format!("any({l} != {r})"),
+                    BinaryOp::Shl | BinaryOp::Shr => {
+                        format!("({l} {} u32({r}))", op.glsl_str())
+                    }
+                    _ => format!("({l} {} {r})", op.glsl_str()),
+                }
+            }
+            Expr::Unary { op, expr } => {
+                let operand = self.expr(expr)?;
+                let op = match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Not => "!",
+                    UnaryOp::BitNot => "~",
+                };
+                format!("{op}{operand}")
+            }
+            Expr::Call { name, args } => self.call(name, args)?,
+            Expr::Constructor { ty, args } => {
+                let wgsl = wgsl_type(ty)?;
+                let scalar = match args.as_slice() {
+                    [arg] if ty.is_matrix() => self.infer(arg).is_some_and(|t| t.is_scalar()),
+                    _ => false,
+                };
+                let args = self.args(args)?;
+                if scalar {
+                    // A scalar builds a diagonal matrix.
+                    let n = match ty {
+                        SkslType::Mat2 => 2,
+                        SkslType::Mat3 => 3,
+                        _ => 4,
+                    };
+                    let columns: Vec<String> = (0..n * n)
+                        .map(|i| {
+                            if i % (n + 1) == 0 {
+                                format!("f32({})", args[0])
+                            } else {
+                                "0.0".to_string()
+                            }
+                        })
+                        .collect();
+                    format!("{wgsl}({})", columns.join(", "))
+                } else {
+                    format!("{wgsl}({})", args.join(", "))
+                }
+            }
+            Expr::Field { expr: base, field } => {
+                let base_type = self.infer(base);
+                let text = self.expr(base)?;
+                match (base_type, swizzle(field)) {
+                    (Some(SkslType::Struct(_)), _) | (_, None) => {
+                        format!("{text}.{}", ident(field))
+                    }
+                    (_, Some(components)) => format!("{text}.{components}"),
+                }
+            }
+            Expr::Index { expr, index } => format!("{}[{}]", self.expr(expr)?, self.expr(index)?),
+            Expr::Ternary {
+                cond,
+                then_expr,
+                else_expr,
+            } => format!(
+                "select({}, {}, {})",
+                self.expr(else_expr)?,
+                self.expr(then_expr)?,
+                self.expr(cond)?
+            ),
+            Expr::Assign { .. }
+            | Expr::CompoundAssign { .. }
+            | Expr::PostIncDec { .. }
+            | Expr::PreIncDec { .. } => {
+                return Err(SkslError::Unsupported(
+                    "assignment inside an expression".to_string(),
+                ));
+            }
+        })
+    }
+
+    fn args(&mut self, args: &[Expr]) -> Result<Vec<String>, SkslError> {
+        args.iter().map(|arg| self.expr(arg)).collect()
+    }
+
+    fn call(&mut self, name: &str, args: &[Expr]) -> Result<String, SkslError> {
+        if self.functions.contains_key(name) {
+            return Ok(format!("{}({})", ident(name), self.args(args)?.join(", ")));
+        }
+        let a = self.args(args)?;
+        let binary = |op: &str| match a.as_slice() {
+            [l, r] => Ok(format!("({l} {op} {r})")),
+            _ => Err(SkslError::Invalid(format!("`{name}` takes two arguments"))),
+        };
+        let renamed = match name {
+            "abs" | "sign" | "floor" | "ceil" | "fract" | "trunc" | "round" | "min" | "max"
+            | "clamp" | "mix" | "step" | "smoothstep" | "saturate" | "length" | "distance"
+            | "dot" | "cross" | "normalize" | "reflect" | "refract" | "faceforward" | "pow"
+            | "exp" | "exp2" | "log" | "log2" | "sqrt" | "sin" | "cos" | "tan" | "asin"
+            | "acos" | "sinh" | "cosh" | "tanh" | "asinh" | "acosh" | "atanh" | "radians"
+            | "degrees" | "transpose" | "determinant" | "any" | "all" | "fwidth" => name,
+            "inversesqrt" => "inverseSqrt",
+            "dFdx" => "dpdx",
+            "dFdy" => "dpdy",
+            "atan" if a.len() == 2 => "atan2",
+            "atan" => "atan",
+            "mod" => {
+                return match a.as_slice() {
+                    [x, y] => Ok(format!("({x} - {y} * floor({x} / {y}))")),
+                    _ => Err(SkslError::Invalid("`mod` takes two arguments".to_string())),
+                };
+            }
+            "equal" => return binary("=="),
+            "notEqual" => return binary("!="),
+            "lessThan" => return binary("<"),
+            "lessThanEqual" => return binary("<="),
+            "greaterThan" => return binary(">"),
+            "greaterThanEqual" => return binary(">="),
+            "not" => {
+                return match a.as_slice() {
+                    [v] => Ok(format!("!{v}")),
+                    _ => Err(SkslError::Invalid("`not` takes one argument".to_string())),
+                };
+            }
+            _ if UNSUPPORTED_FUNCTIONS.contains(&name) => {
+                return Err(SkslError::Unsupported(format!("function `{name}`")));
+            }
+            _ => return Err(SkslError::Invalid(format!("unknown function `{name}`"))),
+        };
+        Ok(format!("{renamed}({})", a.join(", ")))
+    }
+
+    /// Best-effort static type of an expression, with half types folded
+    /// into their float counterparts.
+    fn infer(&self, expr: &Expr) -> Option<SkslType> {
+        match expr {
+            Expr::IntLit(_) => Some(SkslType::Int),
+            Expr::FloatLit(_) => Some(SkslType::Float),
+            Expr::BoolLit(_) => Some(SkslType::Bool),
+            Expr::Var(name) => self
+                .local(name)
+                .or_else(|| self.uniforms.get(name.as_str()))
+                .map(normalize),
+            Expr::Binary { left, op, right } => match op {
+                BinaryOp::Eq
+                | BinaryOp::NotEq
+                | BinaryOp::Lt
+                | BinaryOp::LtEq
+                | BinaryOp::Gt
+                | BinaryOp::GtEq
+                | BinaryOp::And
+                | BinaryOp::Or => Some(SkslType::Bool),
+                _ => {
+                    let (l, r) = (self.infer(left)?, self.infer(right)?);
+                    // Scalars broadcast, and a vector times a matrix stays a vector.
+                    Some(if r.is_scalar() || l.is_vector() { l } else { r })
+                }
+            },
+            Expr::Unary { expr, .. } => self.infer(expr),
+            Expr::Call { name, args } => match (name.as_str(), args.as_slice()) {
+                (name, _) if self.functions.contains_key(name) => {
+                    Some(normalize(&self.functions[name].return_type))
+                }
+                ("length" | "distance" | "dot" | "determinant", _) => Some(SkslType::Float),
+                ("any" | "all", _) => Some(SkslType::Bool),
+                ("cross", _) => Some(SkslType::Vec3),
+                ("step", [_, x]) | ("smoothstep", [_, _, x]) => self.infer(x),
+                (_, [first, ..]) => self.infer(first),
+                _ => None,
+            },
+            Expr::Constructor { ty, .. } => Some(normalize(ty)),
+            Expr::Field { expr, field } => match self.infer(expr)? {
+                SkslType::Struct(name) => self
+                    .program
+                    .structs
+                    .iter()
+                    .find(|s| s.name == name)?
+                    .fields
+                    .iter()
+                    .find(|f| &f.name == field)
+                    .map(|f| normalize(&f.ty)),
+                _ => match swizzle(field)?.len() {
+                    1 => Some(SkslType::Float),
+                    2 => Some(SkslType::Vec2),
+                    3 => Some(SkslType::Vec3),
+                    _ => Some(SkslType::Vec4),
+                },
+            },
+            Expr::Index { expr, .. } => match self.infer(expr)? {
+                SkslType::Array(element, _) => Some(normalize(&element)),
+                SkslType::Mat2 => Some(SkslType::Vec2),
+                SkslType::Mat3 => Some(SkslType::Vec3),
+                SkslType::Mat4 => Some(SkslType::Vec4),
+                _ => Some(SkslType::Float),
+            },
+            Expr::Ternary { then_expr, .. } => self.infer(then_expr),
+            Expr::Assign { target, .. } | Expr::CompoundAssign { target, .. } => self.infer(target),
+            Expr::PostIncDec { expr, .. } | Expr::PreIncDec { expr, .. } => self.infer(expr),
+        }
+    }
+}
+
+/// Fold half precision types into their float counterparts.
+fn normalize(ty: &SkslType) -> SkslType {
+    match ty {
+        SkslType::Half => SkslType::Float,
+        SkslType::Half2 => SkslType::Vec2,
+        SkslType::Half3 => SkslType::Vec3,
+        SkslType::Half4 => SkslType::Vec4,
+        SkslType::Array(element, n) => SkslType::Array(Box::new(normalize(element)), *n),
+        ty => ty.clone(),
+    }
+}
+
+/// The WGSL spelling of a value type.
+fn wgsl_type(ty: &SkslType) -> Result<String, SkslError> {
+    Ok(match normalize(ty) {
+        SkslType::Array(element, n) => format!("array<{}, {n}>", wgsl_type(&element)?),
+        SkslType::Struct(name) => ident(&name),
+        SkslType::Sampler2D | SkslType::Shader | SkslType::ColorFilter | SkslType::Blender => {
+            return Err(SkslError::Unsupported(format!(
+                "child of type `{}`",
+                ty.glsl_name()
+            )));
+        }
+        ty => ty.wgsl_name().to_string(),
+    })
+}
+
+/// The type of a uniform, checked against WGSL's uniform layout rules.
+fn uniform_type(uniform: &UniformDecl) -> Result<SkslType, SkslError> {
+    let ty = normalize(&uniform.ty);
+    wgsl_type(&ty)?;
+    if ty == SkslType::Bool {
+        return Err(SkslError::Unsupported(format!(
+            "bool uniform `{}`",
+            uniform.name
+        )));
+    }
+    let Some(n) = uniform.array_size else {
+        return Ok(ty);
+    };
+    // Uniform array elements must be 16-byte aligned.
+    if ty.is_scalar() || ty == SkslType::Vec2 {
+        return Err(SkslError::Unsupported(format!(
+            "uniform array `{}` of `{}`; use 4-component elements",
+            uniform.name,
+            uniform.ty.glsl_name()
+        )));
+    }
+    Ok(SkslType::Array(Box::new(ty), n))
+}
+
+/// Map a swizzle to `xyzw` components, or `None` if `field` is not one.
+fn swizzle(field: &str) -> Option<String> {
+    if field.is_empty() || field.len() > 4 {
+        return None;
+    }
+    field
+        .chars()
+        .map(|c| match c {
+            'x' | 'r' | 's' => Some('x'),
+            'y' | 'g' | 't' => Some('y'),
+            'z' | 'b' | 'p' => Some('z'),
+            'w' | 'a' | 'q' => Some('w'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Escape identifiers that WGSL reserves.
+fn ident(name: &str) -> String {
+    if name.starts_with("__") {
+        format!("v{name}")
+    } else if WGSL_RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Collect the root names of variables `stmt` writes to.
+fn assigned_in_stmt(stmt: &Stmt, names: &mut BTreeSet<String>) {
+    match stmt {
+        Stmt::Expr(expr) => assigned_in_expr(expr, names),
+        Stmt::VarDecl { init, .. } => {
+            if let Some(init) = init {
+                assigned_in_expr(init, names);
+            }
+        }
+        Stmt::Block(stmts) => stmts.iter().for_each(|s| assigned_in_stmt(s, names)),
+        Stmt::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            assigned_in_stmt(then_branch, names);
+            if let Some(branch) = else_branch {
+                assigned_in_stmt(branch, names);
+            }
+        }
+        Stmt::For {
+            init, update, body, ..
+        } => {
+            if let Some(init) = init {
+                assigned_in_stmt(init, names);
+            }
+            if let Some(update) = update {
+                assigned_in_expr(update, names);
+            }
+            assigned_in_stmt(body, names);
+        }
+        Stmt::While { body, .. } | Stmt::DoWhile { body, .. } => assigned_in_stmt(body, names),
+        Stmt::Return(_) | Stmt::Break | Stmt::Continue | Stmt::Discard => {}
+    }
+}
+
+fn assigned_in_expr(expr: &Expr, names: &mut BTreeSet<String>) {
+    let mut target = match expr {
+        Expr::Assign { target, .. }
+        | Expr::CompoundAssign { target, .. }
+        | Expr::PostIncDec { expr: target, .. }
+        | Expr::PreIncDec { expr: target, .. } => target.as_ref(),
+        _ => return,
+    };
+    loop {
+        match target {
+            Expr::Field { expr, .. } | Expr::Index { expr, .. } => target = expr,
+            Expr::Var(name) => {
+                names.insert(name.clone());
+                return;
+            }
+            _ => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(wgsl: &str) {
+        let module = naga::front::wgsl::parse_str(wgsl)
+            .unwrap_or_else(|e| panic!("{}\n{wgsl}", e.emit_to_string(wgsl)));
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap_or_else(|e| panic!("{e:?}\n{wgsl}"));
+    }
+
+    #[test]
+    fn test_wgsl_solid_color() {
+        let program = SkslProgram::parse(
+            "uniform half4 color;
+             half4 main(float2 coord) { return color; }",
+        )
+        .unwrap();
+        let wgsl = program.to_wgsl().unwrap();
+        assert!(wgsl.contains("@group(0) @binding(0) var<uniform> uniforms: Uniforms;"));
+        assert!(wgsl.contains("color: vec4<f32>,"));
+        assert!(wgsl.contains("return uniforms.color;"));
+        assert!(wgsl.contains("@fragment"));
+        validate(&wgsl);
+    }
+
+    #[test]
+    fn test_wgsl_radial_gradient() {
+        let program = SkslProgram::parse(
+            "uniform float2 center;
+             uniform float radius;
+             uniform half4 inner;
+             uniform half4 outer;
+             half4 main(float2 coord) {
+                 float t = clamp(length(coord - center) / radius, 0.0, 1.0);
+                 return mix(inner, outer, t);
+             }",
+        )
+        .unwrap();
+        let wgsl = program.to_wgsl().unwrap();
+        assert!(wgsl.contains("var t: f32 = clamp("));
+        validate(&wgsl);
+    }
+
+    #[test]
+    fn test_wgsl_control_flow_and_swizzles() {
+        let program = SkslProgram::parse(
+            "uniform float4 tints[2];
+             float ring(float d, float width) {
+                 d = abs(d);
+                 return smoothstep(width, 0.0, d);
+             }
+             half4 main(float2 p) {
+                 half4 c = half4(0.0);
+                 for (int i = 0; i < 3; i++) {
+                     float r = 10.0 * float(i + 1);
+                     c.rgb += vec3(ring(length(p) - r, 2.0));
+                 }
+                 if (c.r > 1.0) {
+                     c.rgb = c.bgr;
+                 } else if (p == vec2(0.0)) {
+                     discard;
+                 } else {
+                     c = c.a > 0.5 ? tints[0] : tints[1] * mat4(2.0);
+                 }
+                 int n = 0;
+                 do { n += 1; } while (n < 4 && mod(float(n), 2.0) != 1.0);
+                 c.a = 1.0;
+                 return c;
+             }",
+        )
+        .unwrap();
+        let wgsl = program.to_wgsl().unwrap();
+        assert!(wgsl.contains("var d: f32 = d_in;"));
+        assert!(wgsl.contains("c.x = _t0.x;"));
+        assert!(wgsl.contains("all(p == vec2<f32>(0.0))"));
+        assert!(wgsl.contains("select("));
+        assert!(wgsl.contains("continuing { break if !"));
+        validate(&wgsl);
+    }
+
+    #[test]
+    fn test_wgsl_errors() {
+        let no_main = SkslProgram::parse("float f(float x) { return x; }").unwrap();
+        assert!(matches!(no_main.to_wgsl(), Err(SkslError::Invalid(_))));
+
+        let child = SkslProgram::parse(
+            "uniform shader image;
+             half4 main(float2 p) { return half4(1.0); }",
+        )
+        .unwrap();
+        assert!(matches!(child.to_wgsl(), Err(SkslError::Unsupported(_))));
+
+        let unknown = SkslProgram::parse("half4 main(float2 p) { return half4(q); }").unwrap();
+        assert!(matches!(unknown.to_wgsl(), Err(SkslError::Invalid(_))));
+
+        assert!(matches!(
+            SkslProgram::parse("half4 main("),
+            Err(SkslError::Parse(_))
+        ));
+    }
+}
@@ -1,7 +1,9 @@
 //! Path builder for constructing paths.
 
 use crate::{FillType, Path, Verb};
-use skia_rs_core::{Point, Rect, Scalar};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
+use skia_rs_core::{Point, RRect, Rect, Scalar};
 
 /// Builder for constructing paths.
 #[derive(Debug, Clone, Default)]
@@ -204,6 +206,96 @@ impl PathBuilder {
             .close()
     }
 
+    /// Add a rounded rectangle with independent corner radii.
+    ///
+    /// Radii are scaled down uniformly when adjacent corners would overlap,
+    /// matching Skia's `SkRRect` normalization. Corners with a zero radius
+    /// are drawn square.
+    pub fn add_rrect(&mut self, rrect: &RRect) -> &mut Self {
+        let rect = rrect.rect;
+        if rrect.is_rect() {
+            return self.add_rect(&rect);
+        }
+
+        let mut radii = rrect.radii.map(|r| {
+            if r.x > 0.0 && r.y > 0.0 {
+                r
+            } else {
+                Point::zero()
+            }
+        });
+
+        // Shrink all radii by the same factor until every side fits.
+        let width = rect.width();
+        let height = rect.height();
+        let mut scale: Scalar = 1.0;
+        for (sum, len) in [
+            (radii[0].x + radii[1].x, width),
+            (radii[3].x + radii[2].x, width),
+            (radii[0].y + radii[3].y, height),
+            (radii[1].y + radii[2].y, height),
+        ] {
+            if sum > len {
+                scale = scale.min(len / sum);
+            }
+        }
+        if scale < 1.0 {
+            for r in &mut radii {
+                *r = Point::new(r.x * scale, r.y * scale);
+            }
+        }
+
+        const KAPPA: Scalar = 0.5522847498;
+        let [tl, tr, br, bl] = radii;
+
+        self.move_to(rect.left + tl.x, rect.top)
+            .line_to(rect.right - tr.x, rect.top);
+        if tr.x > 0.0 {
+            self.cubic_to(
+                rect.right - tr.x * (1.0 - KAPPA),
+                rect.top,
+                rect.right,
+                rect.top + tr.y * (1.0 - KAPPA),
+                rect.right,
+                rect.top + tr.y,
+            );
+        }
+        self.line_to(rect.right, rect.bottom - br.y);
+        if br.x > 0.0 {
+            self.cubic_to(
+                rect.right,
+                rect.bottom - br.y * (1.0 - KAPPA),
+                rect.right - br.x * (1.0 - KAPPA),
+                rect.bottom,
+                rect.right - br.x,
+                rect.bottom,
+            );
+        }
+        self.line_to(rect.left + bl.x, rect.bottom);
+        if bl.x > 0.0 {
+            self.cubic_to(
+                rect.left + bl.x * (1.0 - KAPPA),
+                rect.bottom,
+                rect.left,
+                rect.bottom - bl.y * (1.0 - KAPPA),
+                rect.left,
+                rect.bottom - bl.y,
+            );
+        }
+        self.line_to(rect.left, rect.top + tl.y);
+        if tl.x > 0.0 {
+            self.cubic_to(
+                rect.left,
+                rect.top + tl.y * (1.0 - KAPPA),
+                rect.left + tl.x * (1.0 - KAPPA),
+                rect.top,
+                rect.left + tl.x,
+                rect.top,
+            );
+        }
+        self.close()
+    }
+
     /// Add an arc as a new contour.
     ///
     /// The arc is inscribed in the oval bounded by `oval`, starting at `start_angle`
@@ -236,7 +328,10 @@ impl PathBuilder {
 
     /// Arc to a point using radii and rotation.
     ///
-    /// This matches the SVG arc command semantics.
+    /// This matches the SVG arc command semantics: the arc is skipped when
+    /// it ends where it starts, becomes a line when either radius is zero,
+    /// and radii too small to reach the endpoint are scaled up. The arc is
+    /// emitted as conics of at most 90 degrees each.
     pub fn arc_to(
         &mut self,
         rx: Scalar,
@@ -260,8 +355,8 @@ impl PathBuilder {
             return self.line_to(x, y);
         }
 
-        // Convert to center parameterization and add cubics
-        self.svg_arc_to_cubics(
+        // Convert to center parameterization and add conics
+        self.svg_arc_to_conics(
             current.x,
             current.y,
             rx.abs(),
@@ -375,14 +470,27 @@ impl PathBuilder {
     }
 
     /// Get the current point (last point in the path).
+    ///
+    /// After [`close`](Self::close) this is the start of the closed contour,
+    /// where the next segment begins.
     pub fn current_point(&self) -> Point {
-        self.path.points.last().copied().unwrap_or(Point::zero())
+        match self.last_move {
+            Some(p) if self.path.verbs.last() == Some(&Verb::Close) => p,
+            _ => self.path.points.last().copied().unwrap_or(Point::zero()),
+        }
     }
 
-    /// Ensure we have an initial move command.
+    /// Ensure the next segment starts a contour, moving to the origin in an
+    /// empty path and to the start of the last contour after a close.
     fn ensure_move(&mut self) {
-        if self.last_move.is_none() {
-            self.move_to(0.0, 0.0);
+        match self.last_move {
+            None => {
+                self.move_to(0.0, 0.0);
+            }
+            Some(p) if self.path.verbs.last() == Some(&Verb::Close) => {
+                self.move_to(p.x, p.y);
+            }
+            Some(_) => {}
         }
     }
 
@@ -398,7 +506,7 @@ impl PathBuilder {
     ) {
         // Break arc into segments of at most 90 degrees
         let num_segments =
-            ((sweep_angle.abs() / (std::f32::consts::FRAC_PI_2)).ceil() as i32).max(1);
+            ((sweep_angle.abs() / (core::f32::consts::FRAC_PI_2)).ceil() as i32).max(1);
         let segment_angle = sweep_angle / num_segments as Scalar;
 
         let mut angle = start_angle;
@@ -440,8 +548,13 @@ impl PathBuilder {
         self.cubic_to(x1, y1, x2, y2, x3, y3);
     }
 
-    /// Convert SVG arc to cubic bezier segments.
-    fn svg_arc_to_cubics(
+    /// Convert an SVG arc to conic segments of at most 90 degrees each.
+    ///
+    /// Follows the endpoint-to-center conversion in the SVG implementation
+    /// notes (appendix B.2.4), including scaling up radii that are too small
+    /// to span the endpoints.
+    #[allow(clippy::too_many_arguments)]
+    fn svg_arc_to_conics(
         &mut self,
         x1: Scalar,
         y1: Scalar,
@@ -453,11 +566,9 @@ impl PathBuilder {
         x2: Scalar,
         y2: Scalar,
     ) {
-        // Based on W3C SVG arc implementation notes
-        let phi_rad = phi.to_radians();
-        let (sin_phi, cos_phi) = phi_rad.sin_cos();
+        let (sin_phi, cos_phi) = phi.to_radians().sin_cos();
 
-        // Step 1: Compute (x1', y1')
+        // Step 1: Compute (x1', y1'), the start point in the ellipse's frame
         let dx = (x1 - x2) / 2.0;
         let dy = (y1 - y2) / 2.0;
         let x1p = cos_phi * dx + sin_phi * dy;
@@ -500,13 +611,49 @@ impl PathBuilder {
         );
 
         if !sweep && dtheta > 0.0 {
-            dtheta -= std::f32::consts::TAU;
+            dtheta -= core::f32::consts::TAU;
         } else if sweep && dtheta < 0.0 {
-            dtheta += std::f32::consts::TAU;
+            dtheta += core::f32::consts::TAU;
         }
 
-        // Generate arc segments
-        self.add_arc_to_impl(cx, cy, rx, ry, theta1, dtheta);
+        if !(cx.is_finite() && cy.is_finite() && theta1.is_finite() && dtheta.is_finite()) {
+            self.line_to(x2, y2);
+            return;
+        }
+
+        // Map a point on the unit circle onto the rotated ellipse
+        let map = |ux: Scalar, uy: Scalar| {
+            let ex = ux * rx;
+            let ey = uy * ry;
+            (
+                cx + cos_phi * ex - sin_phi * ey,
+                cy + sin_phi * ex + cos_phi * ey,
+            )
+        };
+
+        // Each piece is an exact rational quadratic: its control point is
+        // where the end tangents meet, and its weight is cos(half-sweep).
+        // Conics survive the affine map to the ellipse unchanged.
+        let num_segments =
+            ((dtheta.abs() / core::f32::consts::FRAC_PI_2 - 0.001).ceil() as i32).max(1);
+        let segment_angle = dtheta / num_segments as Scalar;
+        let half = segment_angle / 2.0;
+        let weight = half.cos();
+
+        let mut angle = theta1;
+        for i in 0..num_segments {
+            let mid = angle + half;
+            let (ctrl_x, ctrl_y) = map(mid.cos() / weight, mid.sin() / weight);
+            let end_angle = angle + segment_angle;
+            // Land the last piece exactly on the requested endpoint
+            let (end_x, end_y) = if i == num_segments - 1 {
+                (x2, y2)
+            } else {
+                map(end_angle.cos(), end_angle.sin())
+            };
+            self.conic_to(ctrl_x, ctrl_y, end_x, end_y, weight);
+            angle = end_angle;
+        }
     }
 }
 
@@ -4,13 +4,16 @@
 //! to create dashed lines, rounded corners, jittery edges, and more.
 
 use crate::{Path, PathBuilder, PathElement};
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 use skia_rs_core::{Point, Scalar};
-use std::sync::Arc;
 
 /// A path effect that modifies how a path is stroked or filled.
 ///
 /// Corresponds to Skia's `SkPathEffect`.
-pub trait PathEffect: Send + Sync + std::fmt::Debug {
+pub trait PathEffect: Send + Sync + core::fmt::Debug {
     /// Apply the effect to a path.
     fn apply(&self, path: &Path) -> Option<Path>;
 
@@ -121,142 +124,54 @@ impl DashEffect {
 }
 
 impl PathEffect for DashEffect {
+    /// Dash each contour independently, restarting the pattern at `phase`.
+    ///
+    /// Contours are walked by arc length with [`PathMeasure`], so dashes
+    /// follow curves exactly and keep their lengths across segment joins.
     fn apply(&self, path: &Path) -> Option<Path> {
+        use crate::PathMeasure;
+
         if path.is_empty() {
             return None;
         }
 
-        let mut builder = PathBuilder::new();
-        let mut current_pos = Point::zero();
-        let mut contour_start = Point::zero();
-        #[allow(unused_assignments)]
-        let mut _distance_along_contour = 0.0_f32;
-
-        // Adjust phase to be within the interval sum
+        // Normalize the phase into the pattern and find where it lands.
         let mut phase = self.phase % self.interval_sum;
         if phase < 0.0 {
             phase += self.interval_sum;
         }
-
-        // Find starting interval index and offset
-        let (mut interval_idx, mut interval_offset) = {
-            let mut accumulated = 0.0;
-            let mut idx = 0;
-            while accumulated + self.intervals[idx] <= phase {
-                accumulated += self.intervals[idx];
-                idx = (idx + 1) % self.intervals.len();
+        // A zero-length dash at the phase is kept rather than skipped.
+        let mut first_interval = 0;
+        loop {
+            let interval = self.intervals[first_interval];
+            if phase < interval || (phase == interval && interval == 0.0) {
+                break;
             }
-            (idx, phase - accumulated)
-        };
-
-        let mut is_on = interval_idx % 2 == 0;
-        let mut remaining_in_interval = self.intervals[interval_idx] - interval_offset;
-
-        for element in path.iter() {
-            match element {
-                PathElement::Move(p) => {
-                    current_pos = p;
-                    contour_start = p;
-                    _distance_along_contour = 0.0;
-                    // Reset dash state for new contour
-                    let mut accumulated = 0.0;
-                    interval_idx = 0;
-                    while accumulated + self.intervals[interval_idx] <= phase {
-                        accumulated += self.intervals[interval_idx];
-                        interval_idx = (interval_idx + 1) % self.intervals.len();
-                    }
-                    is_on = interval_idx % 2 == 0;
-                    remaining_in_interval = self.intervals[interval_idx] - (phase - accumulated);
-                    if is_on {
-                        builder.move_to(p.x, p.y);
-                    }
-                }
-                PathElement::Line(end) => {
-                    let segment_length = current_pos.distance(&end);
-                    let mut t = 0.0;
-
-                    while t < 1.0 {
-                        let remaining_segment = segment_length * (1.0 - t);
-
-                        if remaining_in_interval >= remaining_segment {
-                            // Finish this segment
-                            if is_on {
-                                builder.line_to(end.x, end.y);
-                            }
-                            remaining_in_interval -= remaining_segment;
-                            t = 1.0;
-                        } else {
-                            // Partial segment
-                            let dt = remaining_in_interval / segment_length;
-                            let mid = Point::new(
-                                current_pos.x + (end.x - current_pos.x) * (t + dt),
-                                current_pos.y + (end.y - current_pos.y) * (t + dt),
-                            );
-
-                            if is_on {
-                                builder.line_to(mid.x, mid.y);
-                            }
-
-                            // Move to next interval
-                            interval_idx = (interval_idx + 1) % self.intervals.len();
-                            is_on = interval_idx % 2 == 0;
-                            remaining_in_interval = self.intervals[interval_idx];
-
-                            if is_on {
-                                builder.move_to(mid.x, mid.y);
-                            }
+            phase -= interval;
+            first_interval = (first_interval + 1) % self.intervals.len();
+        }
 
-                            t += dt;
-                        }
-                    }
+        let mut builder = PathBuilder::new();
+        let mut measure = PathMeasure::new(path, false);
+        loop {
+            let length = measure.length();
+            let dash_count = length / self.interval_sum * self.intervals.len() as Scalar;
+            if dash_count > MAX_DASH_COUNT {
+                return None;
+            }
 
-                    current_pos = end;
-                }
-                PathElement::Close => {
-                    // Handle closing line if needed
-                    if current_pos != contour_start {
-                        let segment_length = current_pos.distance(&contour_start);
-                        if segment_length > 0.0 && is_on {
-                            builder.line_to(contour_start.x, contour_start.y);
-                        }
-                    }
-                }
-                // For curves, we approximate with lines (simplified)
-                PathElement::Quad(ctrl, end) => {
-                    // Subdivide and treat as lines
-                    let steps = 8;
-                    for i in 1..=steps {
-                        let t = i as Scalar / steps as Scalar;
-                        let p = quadratic_point(current_pos, ctrl, end, t);
-                        if is_on {
-                            builder.line_to(p.x, p.y);
-                        }
-                    }
-                    current_pos = end;
-                }
-                PathElement::Conic(ctrl, end, _weight) => {
-                    // Approximate as quad
-                    let steps = 8;
-                    for i in 1..=steps {
-                        let t = i as Scalar / steps as Scalar;
-                        let p = quadratic_point(current_pos, ctrl, end, t);
-                        if is_on {
-                            builder.line_to(p.x, p.y);
-                        }
-                    }
-                    current_pos = end;
-                }
-                PathElement::Cubic(ctrl1, ctrl2, end) => {
-                    let steps = 12;
-                    for i in 1..=steps {
-                        let t = i as Scalar / steps as Scalar;
-                        let p = cubic_point(current_pos, ctrl1, ctrl2, end, t);
-                        if is_on {
-                            builder.line_to(p.x, p.y);
-                        }
-                    }
-                    current_pos = end;
+            let mut index = first_interval;
+            let mut distance = -phase;
+            while distance < length {
+                let end = distance + self.intervals[index];
+                if index % 2 == 0 {
+                    measure.append_segment(distance, end, true, &mut builder);
                 }
+                distance = end;
+                index = (index + 1) % self.intervals.len();
+            }
+            if !measure.next_contour() {
+                break;
             }
         }
 
@@ -268,6 +183,9 @@ impl PathEffect for DashEffect {
     }
 }
 
+/// Upper bound on the dashes a single contour may produce, as in Skia.
+const MAX_DASH_COUNT: Scalar = 1_000_000.0;
+
 // =============================================================================
 // Corner Effect
 // =============================================================================
@@ -804,20 +722,19 @@ impl PathEffect for Path1DEffect {
         }
 
         let mut builder = PathBuilder::new();
-        let measure = PathMeasure::new(src_path);
-        let length = measure.length();
-
-        if length <= 0.0 {
+        let mut measure = PathMeasure::new(src_path, false);
+        if measure.total_length() <= 0.0 {
             return None;
         }
 
-        let mut distance = self.phase;
-        while distance < length {
-            // Get position and tangent at this distance
-            let pos = measure.get_point_at(distance);
-            let tangent = measure.get_tangent_at(distance);
-
-            if let (Some(pos), Some(tangent)) = (pos, tangent) {
+        // Each contour is stamped from the phase onward
+        loop {
+            let length = measure.length();
+            let mut distance = self.phase;
+            while distance < length {
+                let Some((pos, tangent)) = measure.get_pos_tan(distance) else {
+                    break;
+                };
                 let transform = match self.style {
                     Path1DStyle::Translate => skia_rs_core::Matrix::translate(pos.x, pos.y),
                     Path1DStyle::Rotate | Path1DStyle::Morph => {
@@ -832,8 +749,11 @@ impl PathEffect for Path1DEffect {
                 // Transform and add the stamped path
                 let transformed = self.path.transformed(&transform);
                 builder.add_path(&transformed);
+                distance += self.advance;
+            }
+            if !measure.next_contour() {
+                break;
             }
-            distance += self.advance;
         }
 
         Some(builder.build())
@@ -1140,4 +1060,66 @@ mod tests {
         let composed = make_compose(dash, corner);
         assert_eq!(composed.effect_kind(), PathEffectKind::Compose);
     }
+
+    fn line(length: Scalar) -> Path {
+        let mut builder = PathBuilder::new();
+        builder.move_to(0.0, 0.0).line_to(length, 0.0);
+        builder.build()
+    }
+
+    #[test]
+    fn test_dash_apply_line() {
+        let dash = DashEffect::new(vec![10.0, 10.0], 0.0).unwrap();
+        let dashed = dash.apply(&line(100.0)).unwrap();
+        assert_eq!(dashed.contour_count(), 5);
+        let starts: Vec<Scalar> = dashed.points().iter().step_by(2).map(|p| p.x).collect();
+        assert_eq!(starts, [0.0, 20.0, 40.0, 60.0, 80.0]);
+
+        // A phase of 15 starts half-way through the first gap.
+        let dash = DashEffect::new(vec![10.0, 10.0], 15.0).unwrap();
+        let dashed = dash.apply(&line(100.0)).unwrap();
+        assert_eq!(dashed.contour_count(), 5);
+        assert_eq!(dashed.points()[0], Point::new(5.0, 0.0));
+        assert_eq!(dashed.last_point(), Some(Point::new(95.0, 0.0)));
+    }
+
+    #[test]
+    fn test_dash_apply_across_segments_and_contours() {
+        // Dashes run through corners and restart at each contour.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(15.0, 0.0)
+            .line_to(15.0, 15.0)
+            .move_to(0.0, 50.0)
+            .quad_to(20.0, 50.0, 40.0, 50.0);
+        let dash = DashEffect::new(vec![10.0, 10.0], 0.0).unwrap();
+        let dashed = dash.apply(&builder.build()).unwrap();
+
+        // [0,10] and [20,30] on the polyline, then [0,10] and [20,30] on the quad.
+        assert_eq!(dashed.contour_count(), 4);
+        let measure = crate::PathMeasure::new(&dashed, false);
+        for i in 0..4 {
+            assert!((measure.contour_length(i).unwrap() - 10.0).abs() < 1e-3);
+        }
+        let points = dashed.points();
+        assert_eq!(points[2], Point::new(15.0, 5.0));
+        assert_eq!(points[3], Point::new(15.0, 15.0));
+        assert_eq!(dashed.last_point(), Some(Point::new(30.0, 50.0)));
+    }
+
+    #[test]
+    fn test_dash_zero_length_on_interval() {
+        // Zero-length dashes stay as points, which round caps turn into dots.
+        let dash = DashEffect::new(vec![0.0, 10.0], 0.0).unwrap();
+        let dashed = dash.apply(&line(30.0)).unwrap();
+        assert_eq!(dashed.contour_count(), 3);
+        assert_eq!(dashed.points()[2], Point::new(10.0, 0.0));
+        assert_eq!(dashed.points()[3], Point::new(10.0, 0.0));
+
+        let round = crate::StrokeParams::new(4.0).with_cap(crate::StrokeCap::Round);
+        let bounds = crate::stroke_to_fill(&dashed, &round).unwrap().bounds();
+        assert!((bounds.left + 2.0).abs() < 0.01 && (bounds.right - 22.0).abs() < 0.01);
+        assert!((bounds.height() - 4.0).abs() < 0.01);
+    }
 }
@@ -8,9 +8,12 @@
 //! - SVG path parsing
 //! - Stroke-to-fill conversion
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+extern crate alloc;
+
 pub mod builder;
 pub mod effects;
 pub mod measure;
@@ -23,6 +26,8 @@ pub use builder::*;
 pub use effects::*;
 pub use measure::*;
 pub use ops::*;
-pub use path::{FillType, Path, PathConvexity, PathDirection, PathElement, PathIter, Verb};
-pub use path_utils::{StrokeCap, StrokeJoin, StrokeParams, stroke_to_fill};
+pub use path::{
+    ContourRef, Contours, FillType, Path, PathConvexity, PathDirection, PathElement, PathIter, Verb,
+};
+pub use path_utils::{StrokeCap, StrokeJoin, StrokeParams, conic_to_cubics, stroke_to_fill};
 pub use svg::{SvgPathError, parse_svg_path};
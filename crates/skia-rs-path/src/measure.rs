@@ -1,88 +1,789 @@
 //! Path measurement and traversal.
 
-use crate::Path;
+use crate::{Path, PathBuilder, PathElement};
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 use skia_rs_core::{Matrix, Point, Scalar};
 
 /// Measures the length of a path and allows querying points along it.
+///
+/// As with Skia's `SkPathMeasure`, queries apply to one contour at a time.
+/// A new measure is positioned on the first contour with a non-zero length,
+/// and [`next_contour`](Self::next_contour) advances to the next one.
+/// Curves are measured by integrating their arc length rather than by
+/// flattening them.
 #[derive(Debug)]
 pub struct PathMeasure {
-    path: Path,
-    contour_lengths: Vec<Scalar>,
-    total_length: Scalar,
+    contours: Vec<MeasuredContour>,
+    segments: Vec<MeasuredSegment>,
+    current: usize,
+}
+
+/// A contour of the measured path.
+#[derive(Debug, Clone)]
+struct MeasuredContour {
+    /// Indices of the contour's segments.
+    segments: Range<usize>,
+    length: Scalar,
+    closed: bool,
 }
 
 impl PathMeasure {
-    /// Create a new path measure.
-    pub fn new(path: &Path) -> Self {
+    /// Create a new path measure positioned on the first contour.
+    ///
+    /// With `force_closed`, open contours are measured as if they ended with
+    /// a close.
+    pub fn new(path: &Path, force_closed: bool) -> Self {
         let mut measure = Self {
-            path: path.clone(),
-            contour_lengths: Vec::new(),
-            total_length: 0.0,
+            contours: Vec::new(),
+            segments: Vec::new(),
+            current: 0,
         };
-        measure.compute_lengths();
+        measure.compute_lengths(path, force_closed);
         measure
     }
 
-    /// Get the total length of the path.
+    /// Get the length of the current contour.
+    ///
+    /// Returns zero once [`next_contour`](Self::next_contour) has moved past
+    /// the last contour.
     #[inline]
     pub fn length(&self) -> Scalar {
-        self.total_length
+        self.contour().map_or(0.0, |contour| contour.length)
+    }
+
+    /// Check whether the current contour is closed, or forced closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.contour().is_some_and(|contour| contour.closed)
+    }
+
+    /// Advance to the next contour.
+    ///
+    /// Returns `false` if there are no more contours.
+    pub fn next_contour(&mut self) -> bool {
+        if self.current < self.contours.len() {
+            self.current += 1;
+        }
+        self.current < self.contours.len()
+    }
+
+    /// Get the total length of all contours.
+    pub fn total_length(&self) -> Scalar {
+        self.contours.iter().map(|contour| contour.length).sum()
     }
 
     /// Get the number of contours.
     #[inline]
     pub fn contour_count(&self) -> usize {
-        self.contour_lengths.len()
+        self.contours.len()
     }
 
     /// Get the length of a specific contour.
     pub fn contour_length(&self, index: usize) -> Option<Scalar> {
-        self.contour_lengths.get(index).copied()
+        self.contours.get(index).map(|contour| contour.length)
     }
 
-    /// Get a point at a distance along the path.
-    pub fn get_point_at(&self, distance: Scalar) -> Option<Point> {
-        if distance < 0.0 || distance > self.total_length {
+    /// Get the position and unit tangent at a distance along the current
+    /// contour.
+    ///
+    /// The distance is clamped to the contour. Returns `None` once all
+    /// contours have been visited, or if the distance is NaN.
+    pub fn get_pos_tan(&self, distance: Scalar) -> Option<(Point, Point)> {
+        let contour = self.contour()?;
+        if distance.is_nan() {
             return None;
         }
-        // TODO: Implement point interpolation
-        let _ = distance;
-        None
+        let distance = distance.clamp(0.0, contour.length);
+
+        let segments = &self.segments[contour.segments.clone()];
+        let index = segments
+            .partition_point(|measured| measured.distance + measured.length < distance)
+            .min(segments.len() - 1);
+        let measured = &segments[index];
+        let t = measured.t_at(distance - measured.distance);
+        Some((measured.segment.point_at(t), measured.segment.tangent_at(t)))
     }
 
-    /// Get the tangent at a distance along the path.
-    pub fn get_tangent_at(&self, distance: Scalar) -> Option<Point> {
-        if distance < 0.0 || distance > self.total_length {
-            return None;
+    /// Get the transformation matrix at a distance along the current contour.
+    ///
+    /// The matrix rotates the x axis onto the tangent and then translates to
+    /// the position, for placing objects along the path.
+    pub fn get_matrix_at(&self, distance: Scalar) -> Option<Matrix> {
+        let (pos, tangent) = self.get_pos_tan(distance)?;
+        let rotation = Matrix::rotate(tangent.y.atan2(tangent.x));
+        Some(Matrix::translate(pos.x, pos.y).concat(&rotation))
+    }
+
+    /// Get the part of the current contour between the distances `start`
+    /// and `stop`.
+    ///
+    /// See [`append_segment`](Self::append_segment); the result is empty if
+    /// nothing lies between the distances.
+    pub fn get_segment(&self, start: Scalar, stop: Scalar, start_with_move: bool) -> Path {
+        let mut builder = PathBuilder::new();
+        self.append_segment(start, stop, start_with_move, &mut builder);
+        builder.build()
+    }
+
+    /// Append the part of the current contour between the distances `start`
+    /// and `stop` to `builder`.
+    ///
+    /// Distances are clamped to the contour. Curves are split exactly rather
+    /// than flattened. The segment begins with a move if `start_with_move` is
+    /// set, and otherwise with a line from the builder's last point. Equal
+    /// distances append a zero-length line, so stroke caps still draw a dot
+    /// there. Returns `false`, appending nothing, if no part of the contour
+    /// lies between the distances.
+    pub fn append_segment(
+        &self,
+        start: Scalar,
+        stop: Scalar,
+        start_with_move: bool,
+        builder: &mut PathBuilder,
+    ) -> bool {
+        let Some(contour) = self.contour() else {
+            return false;
+        };
+        let start = start.max(0.0);
+        let stop = stop.min(contour.length);
+        // Also rejects NaN distances
+        match start.partial_cmp(&stop) {
+            Some(core::cmp::Ordering::Less) => {}
+            Some(core::cmp::Ordering::Equal) => {
+                let Some((p, _)) = self.get_pos_tan(start) else {
+                    return false;
+                };
+                if start_with_move {
+                    builder.move_to(p.x, p.y);
+                } else {
+                    builder.line_to(p.x, p.y);
+                }
+                builder.line_to(p.x, p.y);
+                return true;
+            }
+            _ => return false,
+        }
+
+        let mut first = true;
+        for measured in &self.segments[contour.segments.clone()] {
+            let segment_end = measured.distance + measured.length;
+            if segment_end <= start || measured.distance >= stop {
+                continue;
+            }
+
+            let t0 = if start > measured.distance {
+                measured.t_at(start - measured.distance)
+            } else {
+                0.0
+            };
+            let t1 = if stop < segment_end {
+                measured.t_at(stop - measured.distance)
+            } else {
+                1.0
+            };
+            let piece = measured.segment.sub_segment(t0, t1);
+
+            if first {
+                let p = piece.start();
+                if start_with_move {
+                    builder.move_to(p.x, p.y);
+                } else {
+                    builder.line_to(p.x, p.y);
+                }
+                first = false;
+            }
+            piece.append_to(builder);
         }
-        // TODO: Implement tangent calculation
-        let _ = distance;
-        None
+        true
     }
 
-    /// Get the transformation matrix at a distance along the path.
-    pub fn get_matrix_at(&self, distance: Scalar) -> Option<Matrix> {
-        if distance < 0.0 || distance > self.total_length {
-            return None;
+    fn contour(&self) -> Option<&MeasuredContour> {
+        self.contours.get(self.current)
+    }
+
+    fn compute_lengths(&mut self, path: &Path, force_closed: bool) {
+        let mut first = 0;
+        let mut start = Point::zero();
+        let mut current = Point::zero();
+
+        for element in path.iter() {
+            let segment = match element {
+                PathElement::Move(p) => {
+                    self.finish_contour(first, Segment::Line(current, start), force_closed);
+                    first = self.segments.len();
+                    start = p;
+                    current = p;
+                    continue;
+                }
+                PathElement::Line(end) => Segment::Line(current, end),
+                PathElement::Quad(ctrl, end) => Segment::Quad(current, ctrl, end),
+                PathElement::Conic(ctrl, end, w) => Segment::Conic(current, ctrl, end, w),
+                PathElement::Cubic(c1, c2, end) => Segment::Cubic(current, c1, c2, end),
+                PathElement::Close => {
+                    self.push_segment(first, Segment::Line(current, start));
+                    self.finish_contour(first, Segment::Line(start, start), true);
+                    // Drawing after a close starts a new contour
+                    first = self.segments.len();
+                    current = start;
+                    continue;
+                }
+            };
+            self.push_segment(first, segment);
+            current = segment.end();
         }
-        // TODO: Implement matrix calculation
-        let _ = distance;
-        None
+        self.finish_contour(first, Segment::Line(current, start), force_closed);
     }
 
-    /// Get a segment of the path.
-    pub fn get_segment(&self, start: Scalar, end: Scalar) -> Option<Path> {
-        if start >= end || start < 0.0 || end > self.total_length {
-            return None;
+    /// Add a segment to the contour whose segments start at index `first`,
+    /// skipping it if it has no length.
+    fn push_segment(&mut self, first: usize, segment: Segment) {
+        let length = segment.length();
+        if length > 0.0 {
+            let distance = self.segments[first..]
+                .last()
+                .map_or(0.0, |last| last.distance + last.length);
+            self.segments.push(MeasuredSegment {
+                segment,
+                distance,
+                length,
+            });
+        }
+    }
+
+    /// Record the contour whose segments start at index `first`, adding the
+    /// `closing` line first if `closed` is set.
+    fn finish_contour(&mut self, first: usize, closing: Segment, closed: bool) {
+        if first == self.segments.len() {
+            return;
+        }
+        if closed {
+            self.push_segment(first, closing);
+        }
+        let last = &self.segments[self.segments.len() - 1];
+        self.contours.push(MeasuredContour {
+            segments: first..self.segments.len(),
+            length: last.distance + last.length,
+            closed,
+        });
+    }
+}
+
+/// A segment placed along the measured path.
+#[derive(Debug, Clone, Copy)]
+struct MeasuredSegment {
+    segment: Segment,
+    /// Distance along the contour at which the segment starts.
+    distance: Scalar,
+    /// Arc length of the segment.
+    length: Scalar,
+}
+
+impl MeasuredSegment {
+    /// Parameter at which the arc length from the segment start is `distance`.
+    ///
+    /// Lines invert exactly. Curves use Newton's method on the arc length,
+    /// falling back to bisection whenever a step leaves the bracketing interval.
+    fn t_at(&self, distance: Scalar) -> f64 {
+        let length = f64::from(self.length);
+        let target = f64::from(distance);
+        let mut t = (target / length).clamp(0.0, 1.0);
+        if let Segment::Line(..) = self.segment {
+            return t;
+        }
+
+        let (mut lo, mut hi) = (0.0, 1.0);
+        for _ in 0..MAX_INVERSION_STEPS {
+            let error = self.segment.length_between(0.0, t) - target;
+            if error.abs() <= LENGTH_TOLERANCE * length.max(1.0) {
+                break;
+            }
+            if error > 0.0 {
+                hi = t;
+            } else {
+                lo = t;
+            }
+            let speed = self.segment.speed(t);
+            let next = t - error / speed;
+            t = if speed > 0.0 && next > lo && next < hi {
+                next
+            } else {
+                0.5 * (lo + hi)
+            };
+        }
+        t
+    }
+}
+
+/// A single path segment with its start point resolved.
+#[derive(Debug, Clone, Copy)]
+enum Segment {
+    Line(Point, Point),
+    Quad(Point, Point, Point),
+    Conic(Point, Point, Point, Scalar),
+    Cubic(Point, Point, Point, Point),
+}
+
+/// Maximum recursion depth for adaptive length integration.
+const MAX_SUBDIVISIONS: u32 = 12;
+
+/// Relative tolerance at which adaptive integration stops subdividing.
+const LENGTH_TOLERANCE: f64 = 1e-7;
+
+/// Maximum number of steps when inverting arc length to a curve parameter.
+const MAX_INVERSION_STEPS: u32 = 24;
+
+/// Five-point Gauss-Legendre nodes on [-1, 1] and their weights.
+const GAUSS_LEGENDRE_5: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+impl Segment {
+    /// The end point of the segment.
+    fn end(&self) -> Point {
+        match *self {
+            Self::Line(_, end)
+            | Self::Quad(_, _, end)
+            | Self::Conic(_, _, end, _)
+            | Self::Cubic(_, _, _, end) => end,
+        }
+    }
+
+    /// The start point of the segment.
+    fn start(&self) -> Point {
+        match *self {
+            Self::Line(start, _)
+            | Self::Quad(start, _, _)
+            | Self::Conic(start, _, _, _)
+            | Self::Cubic(start, _, _, _) => start,
+        }
+    }
+
+    /// Append the segment to `builder`, which must already be at its start.
+    fn append_to(&self, builder: &mut PathBuilder) {
+        match *self {
+            Self::Line(_, p) => builder.line_to(p.x, p.y),
+            Self::Quad(_, c, p) => builder.quad_to(c.x, c.y, p.x, p.y),
+            Self::Conic(_, c, p, w) => builder.conic_to(c.x, c.y, p.x, p.y, w),
+            Self::Cubic(_, c1, c2, p) => builder.cubic_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y),
+        };
+    }
+
+    /// The part of the segment between the parameters `t0` and `t1`.
+    ///
+    /// The sub-curve's control points are blossoms of the original evaluated
+    /// at `t0` and `t1`. Conics are blossomed in homogeneous coordinates and
+    /// then brought back to standard form, with equal end weights.
+    fn sub_segment(&self, t0: f64, t1: f64) -> Self {
+        let h = |p: Point, w: f64| [f64::from(p.x) * w, f64::from(p.y) * w, w];
+        let project = |q: [f64; 3]| Point::new((q[0] / q[2]) as Scalar, (q[1] / q[2]) as Scalar);
+        match *self {
+            Self::Line(p0, p1) => {
+                let pts = [h(p0, 1.0), h(p1, 1.0)];
+                Self::Line(project(blossom(&pts, &[t0])), project(blossom(&pts, &[t1])))
+            }
+            Self::Quad(p0, p1, p2) => {
+                let pts = [h(p0, 1.0), h(p1, 1.0), h(p2, 1.0)];
+                Self::Quad(
+                    project(blossom(&pts, &[t0, t0])),
+                    project(blossom(&pts, &[t0, t1])),
+                    project(blossom(&pts, &[t1, t1])),
+                )
+            }
+            Self::Conic(p0, p1, p2, w) => {
+                let pts = [h(p0, 1.0), h(p1, f64::from(w)), h(p2, 1.0)];
+                let q0 = blossom(&pts, &[t0, t0]);
+                let q1 = blossom(&pts, &[t0, t1]);
+                let q2 = blossom(&pts, &[t1, t1]);
+                let weight = q1[2] / (q0[2] * q2[2]).sqrt();
+                Self::Conic(project(q0), project(q1), project(q2), weight as Scalar)
+            }
+            Self::Cubic(p0, p1, p2, p3) => {
+                let pts = [h(p0, 1.0), h(p1, 1.0), h(p2, 1.0), h(p3, 1.0)];
+                Self::Cubic(
+                    project(blossom(&pts, &[t0, t0, t0])),
+                    project(blossom(&pts, &[t0, t0, t1])),
+                    project(blossom(&pts, &[t0, t1, t1])),
+                    project(blossom(&pts, &[t1, t1, t1])),
+                )
+            }
+        }
+    }
+
+    /// Point on the segment at parameter `t`, computed in f64.
+    fn point_at(&self, t: f64) -> Point {
+        let v = |p: Point| (f64::from(p.x), f64::from(p.y));
+        let mt = 1.0 - t;
+        let combine = |terms: &[(f64, Point)]| {
+            terms.iter().fold((0.0, 0.0), |(x, y), &(w, p)| {
+                let p = v(p);
+                (x + w * p.0, y + w * p.1)
+            })
+        };
+        let (x, y) = match *self {
+            Self::Line(p0, p1) => combine(&[(mt, p0), (t, p1)]),
+            Self::Quad(p0, p1, p2) => combine(&[(mt * mt, p0), (2.0 * mt * t, p1), (t * t, p2)]),
+            Self::Cubic(p0, p1, p2, p3) => combine(&[
+                (mt * mt * mt, p0),
+                (3.0 * mt * mt * t, p1),
+                (3.0 * mt * t * t, p2),
+                (t * t * t, p3),
+            ]),
+            Self::Conic(p0, p1, p2, w) => {
+                let (b0, b1, b2) = (mt * mt, 2.0 * f64::from(w) * mt * t, t * t);
+                let (x, y) = combine(&[(b0, p0), (b1, p1), (b2, p2)]);
+                let den = b0 + b1 + b2;
+                (x / den, y / den)
+            }
+        };
+        Point::new(x as Scalar, y as Scalar)
+    }
+
+    /// Unit tangent at parameter `t`.
+    ///
+    /// Where the derivative vanishes, as at a cubic end whose control point
+    /// coincides with it, the direction is taken slightly inside the segment,
+    /// and failing that from the chord.
+    fn tangent_at(&self, t: f64) -> Point {
+        let inside = if t < 0.5 { t + 1e-3 } else { t - 1e-3 };
+        for (dx, dy) in [self.derivative(t), self.derivative(inside)] {
+            let len = dx.hypot(dy);
+            if len > 1e-9 {
+                return Point::new((dx / len) as Scalar, (dy / len) as Scalar);
+            }
+        }
+        let chord = self.end() - self.start();
+        if chord.length() > 0.0 {
+            chord.normalize()
+        } else {
+            Point::new(1.0, 0.0)
+        }
+    }
+
+    /// Arc length of the segment.
+    ///
+    /// Lines are measured exactly. Curves integrate the speed `|B'(t)|`
+    /// with adaptive Gauss-Legendre quadrature, splitting intervals until
+    /// both halves agree with the whole to within [`LENGTH_TOLERANCE`].
+    fn length(&self) -> Scalar {
+        if let Self::Line(p0, p1) = *self {
+            return p0.distance(&p1);
+        }
+        self.length_between(0.0, 1.0) as Scalar
+    }
+
+    /// Arc length between the parameters `a` and `b`.
+    fn length_between(&self, a: f64, b: f64) -> f64 {
+        let whole = self.gauss_legendre(a, b);
+        self.integrate(a, b, whole, MAX_SUBDIVISIONS)
+    }
+
+    fn integrate(&self, a: f64, b: f64, whole: f64, depth: u32) -> f64 {
+        let mid = 0.5 * (a + b);
+        let left = self.gauss_legendre(a, mid);
+        let right = self.gauss_legendre(mid, b);
+        let sum = left + right;
+        if depth == 0 || (sum - whole).abs() <= LENGTH_TOLERANCE * sum.max(1.0) {
+            return sum;
+        }
+        self.integrate(a, mid, left, depth - 1) + self.integrate(mid, b, right, depth - 1)
+    }
+
+    fn gauss_legendre(&self, a: f64, b: f64) -> f64 {
+        let half = 0.5 * (b - a);
+        let center = 0.5 * (a + b);
+        GAUSS_LEGENDRE_5
+            .iter()
+            .map(|&(x, w)| w * self.speed(center + half * x))
+            .sum::<f64>()
+            * half
+    }
+
+    /// Magnitude of the derivative at parameter `t`.
+    fn speed(&self, t: f64) -> f64 {
+        let (dx, dy) = self.derivative(t);
+        dx.hypot(dy)
+    }
+
+    /// Derivative of the segment at parameter `t`, computed in f64.
+    fn derivative(&self, t: f64) -> (f64, f64) {
+        let v = |p: Point| (f64::from(p.x), f64::from(p.y));
+        let mt = 1.0 - t;
+        match *self {
+            Self::Line(p0, p1) => {
+                let (p0, p1) = (v(p0), v(p1));
+                (p1.0 - p0.0, p1.1 - p0.1)
+            }
+            Self::Quad(p0, p1, p2) => {
+                let (p0, p1, p2) = (v(p0), v(p1), v(p2));
+                (
+                    2.0 * (mt * (p1.0 - p0.0) + t * (p2.0 - p1.0)),
+                    2.0 * (mt * (p1.1 - p0.1) + t * (p2.1 - p1.1)),
+                )
+            }
+            Self::Cubic(p0, p1, p2, p3) => {
+                let (p0, p1, p2, p3) = (v(p0), v(p1), v(p2), v(p3));
+                let (a, b, c) = (3.0 * mt * mt, 6.0 * mt * t, 3.0 * t * t);
+                (
+                    a * (p1.0 - p0.0) + b * (p2.0 - p1.0) + c * (p3.0 - p2.0),
+                    a * (p1.1 - p0.1) + b * (p2.1 - p1.1) + c * (p3.1 - p2.1),
+                )
+            }
+            Self::Conic(p0, p1, p2, w) => {
+                // P(t) = N(t) / D(t) with
+                //   N = (1-t)^2 P0 + 2w t(1-t) P1 + t^2 P2
+                //   D = (1-t)^2 + 2w t(1-t) + t^2
+                let (p0, p1, p2, w) = (v(p0), v(p1), v(p2), f64::from(w));
+                let (b0, b1, b2) = (mt * mt, 2.0 * w * t * mt, t * t);
+                let (d0, d1, d2) = (-2.0 * mt, 2.0 * w * (1.0 - 2.0 * t), 2.0 * t);
+                let den = b0 + b1 + b2;
+                let den_d = d0 + d1 + d2;
+                let axis = |c0: f64, c1: f64, c2: f64| {
+                    let num = b0 * c0 + b1 * c1 + b2 * c2;
+                    let num_d = d0 * c0 + d1 * c1 + d2 * c2;
+                    (num_d * den - num * den_d) / (den * den)
+                };
+                (axis(p0.0, p1.0, p2.0), axis(p0.1, p1.1, p2.1))
+            }
+        }
+    }
+}
+
+/// Evaluate the blossom of a Bézier curve in homogeneous coordinates.
+///
+/// Runs de Casteljau's algorithm with a different parameter at each level;
+/// `params` has one entry per degree.
+fn blossom<const N: usize>(points: &[[f64; 3]; N], params: &[f64]) -> [f64; 3] {
+    let mut pts = *points;
+    for (level, &t) in params.iter().enumerate() {
+        for i in 0..N - 1 - level {
+            let next = pts[i + 1];
+            for (c, n) in pts[i].iter_mut().zip(next) {
+                *c += (n - *c) * t;
+            }
         }
-        // TODO: Implement segment extraction
-        let _ = (start, end);
-        None
     }
+    pts[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PathBuilder;
+    use core::f32::consts::{FRAC_1_SQRT_2, PI};
+
+    #[test]
+    fn test_quarter_circle_conic_length() {
+        let r = 100.0;
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(r, 0.0)
+            .conic_to(r, r, 0.0, r, FRAC_1_SQRT_2);
+        let measure = PathMeasure::new(&builder.build(), false);
+
+        let expected = PI / 2.0 * r;
+        let error = (measure.length() - expected).abs() / expected;
+        assert!(error < 0.001, "length {} vs {}", measure.length(), expected);
+    }
+
+    #[test]
+    fn test_curve_lengths() {
+        // A straight cubic and quad measure as their chord.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .cubic_to(10.0, 0.0, 20.0, 0.0, 30.0, 0.0)
+            .quad_to(30.0, 20.0, 30.0, 40.0);
+        let measure = PathMeasure::new(&builder.build(), false);
+        assert!((measure.length() - 70.0).abs() < 1e-3);
+
+        // The four-cubic circle approximation is within 0.03% of 2πr.
+        let mut builder = PathBuilder::new();
+        builder.add_circle(0.0, 0.0, 50.0);
+        let measure = PathMeasure::new(&builder.build(), false);
+        let expected = 2.0 * PI * 50.0;
+        assert!((measure.length() - expected).abs() / expected < 3e-4);
+    }
+
+    #[test]
+    fn test_contour_lengths() {
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 10.0)
+            .close()
+            .move_to(50.0, 50.0)
+            .move_to(100.0, 0.0)
+            .line_to(100.0, 5.0);
+        let path = builder.build();
+        let mut measure = PathMeasure::new(&path, false);
+
+        assert_eq!(measure.contour_count(), 2);
+        let first = measure.contour_length(0).unwrap();
+        assert!((first - (20.0 + 200.0_f32.sqrt())).abs() < 1e-4);
+        assert_eq!(measure.contour_length(1), Some(5.0));
+        assert!((measure.total_length() - (first + 5.0)).abs() < 1e-4);
+
+        // Queries walk the contours one at a time.
+        assert_eq!(measure.length(), first);
+        assert!(measure.is_closed());
+        assert!(measure.next_contour());
+        assert_eq!(measure.length(), 5.0);
+        assert!(!measure.is_closed());
+        assert!(!measure.next_contour());
+        assert_eq!(measure.length(), 0.0);
+        assert!(measure.get_pos_tan(0.0).is_none());
+
+        // Forcing the open contour closed adds the way back.
+        let mut forced = PathMeasure::new(&path, true);
+        assert!(forced.next_contour());
+        assert_eq!(forced.length(), 10.0);
+        assert!(forced.is_closed());
+    }
+
+    #[test]
+    fn test_get_segment_line() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(0.0, 0.0).line_to(100.0, 0.0);
+        let measure = PathMeasure::new(&builder.build(), false);
+
+        let segment = measure.get_segment(25.0, 75.0, true);
+        assert_eq!(
+            segment.points(),
+            &[Point::new(25.0, 0.0), Point::new(75.0, 0.0)]
+        );
+        // Equal distances keep a zero-length line for stroke caps.
+        assert_eq!(
+            measure.get_segment(50.0, 50.0, true).points(),
+            &[Point::new(50.0, 0.0), Point::new(50.0, 0.0)]
+        );
+        assert!(measure.get_segment(60.0, 50.0, true).is_empty());
+        // Distances are clamped to the contour.
+        let whole = measure.get_segment(-10.0, 101.0, true);
+        assert_eq!(
+            whole.points(),
+            &[Point::new(0.0, 0.0), Point::new(100.0, 0.0)]
+        );
+
+        // Without a move, the segment joins the builder's current contour.
+        let mut builder = PathBuilder::new();
+        builder.move_to(0.0, 10.0);
+        assert!(measure.append_segment(10.0, 20.0, false, &mut builder));
+        let joined = builder.build();
+        assert_eq!(joined.contour_count(), 1);
+        assert_eq!(
+            joined.points(),
+            &[
+                Point::new(0.0, 10.0),
+                Point::new(10.0, 0.0),
+                Point::new(20.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_segment_curves() {
+        // Half of a quarter-circle conic ends at 45 degrees.
+        let r = 100.0;
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(r, 0.0)
+            .conic_to(r, r, 0.0, r, FRAC_1_SQRT_2);
+        let measure = PathMeasure::new(&builder.build(), false);
+        let half = measure.get_segment(0.0, measure.length() / 2.0, true);
+        let end = half.last_point().unwrap();
+        let expected = r * FRAC_1_SQRT_2;
+        assert!((end.x - expected).abs() < 1e-2 && (end.y - expected).abs() < 1e-2);
+        let sub = PathMeasure::new(&half, false);
+        assert!((sub.length() - measure.length() / 2.0).abs() < 1e-2);
+
+        // A cubic split mid-way keeps its shape: both halves sum to the whole.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .cubic_to(0.0, 60.0, 90.0, 60.0, 90.0, 0.0);
+        let measure = PathMeasure::new(&builder.build(), false);
+        let total = measure.length();
+        let first = measure.get_segment(0.0, 40.0, true);
+        let second = measure.get_segment(40.0, total, true);
+        assert!((PathMeasure::new(&first, false).length() - 40.0).abs() < 1e-2);
+        assert!((PathMeasure::new(&second, false).length() - (total - 40.0)).abs() < 1e-2);
+        assert_eq!(first.last_point(), second.points().first().copied());
+    }
+
+    #[test]
+    fn test_get_segment_per_contour() {
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .move_to(0.0, 20.0)
+            .line_to(10.0, 20.0);
+        let mut measure = PathMeasure::new(&builder.build(), false);
+
+        // Distances are measured from the start of the current contour.
+        let first = measure.get_segment(5.0, 15.0, true);
+        assert_eq!(
+            first.points(),
+            &[Point::new(5.0, 0.0), Point::new(10.0, 0.0)]
+        );
+        assert!(measure.next_contour());
+        let second = measure.get_segment(0.0, 5.0, true);
+        assert_eq!(
+            second.points(),
+            &[Point::new(0.0, 20.0), Point::new(5.0, 20.0)]
+        );
+    }
+
+    #[test]
+    fn test_get_pos_tan_line() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(0.0, 0.0).line_to(100.0, 0.0);
+        let measure = PathMeasure::new(&builder.build(), false);
+
+        assert_eq!(measure.length(), 100.0);
+        let (pos, tangent) = measure.get_pos_tan(50.0).unwrap();
+        assert_eq!(pos, Point::new(50.0, 0.0));
+        assert_eq!(tangent, Point::new(1.0, 0.0));
+        let (end, _) = measure.get_pos_tan(150.0).unwrap();
+        assert_eq!(end, Point::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn test_get_pos_tan_curves() {
+        // A quarter circle from (r, 0) to (0, r): at 45 degrees the tangent
+        // points down and to the left.
+        let r = 100.0;
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(r, 0.0)
+            .conic_to(r, r, 0.0, r, FRAC_1_SQRT_2);
+        let measure = PathMeasure::new(&builder.build(), false);
+        let (pos, tangent) = measure.get_pos_tan(measure.length() / 2.0).unwrap();
+        let expected = r * FRAC_1_SQRT_2;
+        assert!((pos.x - expected).abs() < 1e-2 && (pos.y - expected).abs() < 1e-2);
+        assert!((tangent.x + FRAC_1_SQRT_2).abs() < 1e-3);
+        assert!((tangent.y - FRAC_1_SQRT_2).abs() < 1e-3);
+
+        // The start control point coincides with the start, so the
+        // derivative vanishes there; the tangent still follows the curve.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .cubic_to(0.0, 0.0, 50.0, 50.0, 100.0, 50.0);
+        let measure = PathMeasure::new(&builder.build(), false);
+        let (_, tangent) = measure.get_pos_tan(0.0).unwrap();
+        assert!(tangent.x > 0.5 && tangent.y > 0.5);
 
-    fn compute_lengths(&mut self) {
-        // TODO: Implement length computation
-        // This requires flattening curves and summing segment lengths
-        self.total_length = 0.0;
+        let matrix = measure.get_matrix_at(measure.length()).unwrap();
+        let tip = matrix.map_point(Point::new(10.0, 0.0));
+        assert!((tip.x - 110.0).abs() < 1e-2 && (tip.y - 50.0).abs() < 1e-2);
     }
 }
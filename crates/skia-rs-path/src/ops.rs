@@ -1,10 +1,34 @@
 //! Path boolean operations (union, intersect, difference, xor).
 //!
-//! This module implements boolean operations on paths using a scanline-based
-//! algorithm inspired by the Bentley-Ottmann algorithm.
+//! Both operands are flattened to polygons and every edge is split wherever
+//! it crosses another edge. A split edge belongs to the result if the
+//! operation gives different answers on its two sides; the surviving edges
+//! are then chained back into closed contours. Edges of the same path are
+//! split against each other too, so self-intersecting inputs come out
+//! simplified.
 
-use crate::{Path, PathBuilder, PathElement, Verb};
-use skia_rs_core::{Point, Rect, Scalar};
+use crate::{FillType, Path, PathBuilder, PathElement, conic_to_cubics};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
+use skia_rs_core::{Point, Scalar};
+
+/// Tolerance used when flattening curves into line segments.
+const FLATTEN_TOLERANCE: Scalar = 0.1;
+
+/// Parametric tolerance for snapping crossings onto segment endpoints.
+const PARAM_EPSILON: f64 = 1e-9;
+
+/// Distance below which parallel segments are treated as coincident.
+const COINCIDENT_TOLERANCE: f64 = 1e-5;
+
+/// Distance within which a point counts as lying on a path's edge in
+/// [`winding_at`].
+const ON_EDGE_TOLERANCE: f64 = 1e-4;
+
+/// Largest distance from an edge at which its two sides are sampled.
+const SIDE_OFFSET: f64 = 1e-3;
 
 /// Operation type for path boolean operations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -22,6 +46,19 @@ pub enum PathOp {
     ReverseDifference,
 }
 
+impl PathOp {
+    /// Combine point membership in the two operands.
+    fn combine(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            PathOp::Difference => in_a && !in_b,
+            PathOp::Intersect => in_a && in_b,
+            PathOp::Union => in_a || in_b,
+            PathOp::Xor => in_a != in_b,
+            PathOp::ReverseDifference => in_b && !in_a,
+        }
+    }
+}
+
 /// Perform a boolean operation on two paths.
 ///
 /// # Arguments
@@ -32,138 +69,345 @@ pub enum PathOp {
 /// # Returns
 /// The resulting path, or None if the operation fails
 pub fn op(path1: &Path, path2: &Path, op: PathOp) -> Option<Path> {
-    PathOps::new(path1, path2, op).compute()
+    PathOps::apply(path1, path2, op)
 }
 
 /// Simplify a path by removing overlapping regions.
 pub fn simplify(path: &Path) -> Option<Path> {
-    // Simplification is union with self
-    let empty = Path::new();
-    op(path, &empty, PathOp::Union)
-}
-
-/// Internal path operations implementation.
-struct PathOps<'a> {
-    path1: &'a Path,
-    path2: &'a Path,
-    op: PathOp,
+    // Simplification is union with nothing
+    PathOps::apply(path, &Path::new(), PathOp::Union)
 }
 
-impl<'a> PathOps<'a> {
-    fn new(path1: &'a Path, path2: &'a Path, op: PathOp) -> Self {
-        Self { path1, path2, op }
-    }
-
-    fn compute(&self) -> Option<Path> {
-        // Handle empty paths
-        if self.path1.is_empty() && self.path2.is_empty() {
-            return Some(Path::new());
+/// Boolean operations on paths.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PathOps;
+
+impl PathOps {
+    /// Combine two paths with a boolean operation.
+    ///
+    /// Each operand is filled with its own fill type. Curves are flattened,
+    /// so the result is made of line segments; its contours never overlap.
+    /// If the result covers the plane outside its contours it gets an
+    /// inverse fill type.
+    ///
+    /// Returns `None` if either path has non-finite coordinates.
+    pub fn apply(a: &Path, b: &Path, op: PathOp) -> Option<Path> {
+        if !has_finite_points(a) || !has_finite_points(b) {
+            return None;
         }
 
-        if self.path1.is_empty() {
-            return match self.op {
-                PathOp::Union | PathOp::ReverseDifference | PathOp::Xor => Some(self.path2.clone()),
-                PathOp::Difference | PathOp::Intersect => Some(Path::new()),
+        let a = Operand::new(a);
+        let b = Operand::new(b);
+        // Every operand is either empty or full far away from its edges
+        let inverse = op.combine(a.fill_type.is_inverse(), b.fill_type.is_inverse());
+
+        let mut segments = a.segments();
+        segments.extend(b.segments());
+        let segments = split_segments(&segments);
+
+        let mut seen = BTreeSet::new();
+        let mut edges = Vec::new();
+        for segment in segments {
+            let (left, right) = segment.side_points();
+            let left_inside = op.combine(a.contains(left), b.contains(left)) != inverse;
+            let right_inside = op.combine(a.contains(right), b.contains(right)) != inverse;
+            if left_inside == right_inside {
+                continue;
+            }
+
+            // Orient the edge so the result lies on its left
+            let edge = if left_inside {
+                segment
+            } else {
+                segment.reversed()
             };
+            // Coincident edges of the two operands collapse into one
+            if seen.insert((PointKey::new(edge.p0), PointKey::new(edge.p1))) {
+                edges.push(edge);
+            }
         }
 
-        if self.path2.is_empty() {
-            return match self.op {
-                PathOp::Union | PathOp::Difference | PathOp::Xor => Some(self.path1.clone()),
-                PathOp::Intersect | PathOp::ReverseDifference => Some(Path::new()),
-            };
+        let fill_type = if inverse {
+            FillType::InverseWinding
+        } else {
+            FillType::Winding
+        };
+        Some(polygons_to_path(&chain_edges(&edges), fill_type))
+    }
+}
+
+fn has_finite_points(path: &Path) -> bool {
+    path.points()
+        .iter()
+        .all(|p| p.x.is_finite() && p.y.is_finite())
+}
+
+/// A flattened operand of a boolean operation.
+struct Operand {
+    polygons: Vec<Polygon>,
+    fill_type: FillType,
+}
+
+impl Operand {
+    fn new(path: &Path) -> Self {
+        Self {
+            polygons: path_to_polygons(path),
+            fill_type: path.fill_type(),
         }
+    }
 
-        // Check if bounding boxes intersect
-        let bounds1 = self.path1.bounds();
-        let bounds2 = self.path2.bounds();
-
-        if !bounds_intersect(&bounds1, &bounds2) {
-            return match self.op {
-                PathOp::Union => {
-                    // Combine both paths
-                    let mut builder = PathBuilder::new();
-                    self.add_path_to_builder(&mut builder, self.path1);
-                    self.add_path_to_builder(&mut builder, self.path2);
-                    Some(builder.build())
-                }
-                PathOp::Intersect => Some(Path::new()),
-                PathOp::Difference => Some(self.path1.clone()),
-                PathOp::ReverseDifference => Some(self.path2.clone()),
-                PathOp::Xor => {
-                    let mut builder = PathBuilder::new();
-                    self.add_path_to_builder(&mut builder, self.path1);
-                    self.add_path_to_builder(&mut builder, self.path2);
-                    Some(builder.build())
+    /// All polygon edges, including the closing ones.
+    fn segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        for poly in &self.polygons {
+            let n = poly.points.len();
+            for i in 0..n {
+                let segment = Segment::new(poly.points[i], poly.points[(i + 1) % n]);
+                if segment.p0 != segment.p1 {
+                    segments.push(segment);
                 }
-            };
+            }
         }
+        segments
+    }
 
-        // For complex cases, use polygon-based operations
-        self.compute_polygon_ops()
+    fn contains(&self, p: (f64, f64)) -> bool {
+        let winding: i32 = self.polygons.iter().map(|poly| poly.winding(p)).sum();
+        let inside = match self.fill_type {
+            FillType::Winding | FillType::InverseWinding => winding != 0,
+            FillType::EvenOdd | FillType::InverseEvenOdd => winding & 1 != 0,
+        };
+        inside != self.fill_type.is_inverse()
     }
+}
 
-    fn add_path_to_builder(&self, builder: &mut PathBuilder, path: &Path) {
-        for elem in path.iter() {
-            match elem {
-                PathElement::Move(p) => {
-                    builder.move_to(p.x, p.y);
-                }
-                PathElement::Line(p) => {
-                    builder.line_to(p.x, p.y);
-                }
-                PathElement::Quad(c, p) => {
-                    builder.quad_to(c.x, c.y, p.x, p.y);
-                }
-                PathElement::Conic(c, p, w) => {
-                    builder.conic_to(c.x, c.y, p.x, p.y, w);
-                }
-                PathElement::Cubic(c1, c2, p) => {
-                    builder.cubic_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y);
-                }
-                PathElement::Close => {
-                    builder.close();
-                }
+/// A directed line segment.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    p0: Point,
+    p1: Point,
+}
+
+impl Segment {
+    fn new(p0: Point, p1: Point) -> Self {
+        Self { p0, p1 }
+    }
+
+    fn reversed(self) -> Self {
+        Self::new(self.p1, self.p0)
+    }
+
+    fn bounds_overlap(&self, other: &Segment) -> bool {
+        self.p0.x.min(self.p1.x) <= other.p0.x.max(other.p1.x)
+            && other.p0.x.min(other.p1.x) <= self.p0.x.max(self.p1.x)
+            && self.p0.y.min(self.p1.y) <= other.p0.y.max(other.p1.y)
+            && other.p0.y.min(other.p1.y) <= self.p0.y.max(self.p1.y)
+    }
+
+    /// Points just to the left and right of the segment's midpoint.
+    fn side_points(&self) -> ((f64, f64), (f64, f64)) {
+        let (x0, y0) = (f64::from(self.p0.x), f64::from(self.p0.y));
+        let (dx, dy) = (f64::from(self.p1.x) - x0, f64::from(self.p1.y) - y0);
+        let len = dx.hypot(dy);
+        let offset = (len * 0.01).min(SIDE_OFFSET) / len;
+        let (mx, my) = (x0 + dx * 0.5, y0 + dy * 0.5);
+        // Left is the side where `is_left` is positive
+        let (nx, ny) = (-dy * offset, dx * offset);
+        ((mx + nx, my + ny), (mx - nx, my - ny))
+    }
+}
+
+/// Split segments at every point where they cross or touch each other.
+fn split_segments(segments: &[Segment]) -> Vec<Segment> {
+    let mut splits: Vec<Vec<(f64, Point)>> = vec![Vec::new(); segments.len()];
+    for i in 0..segments.len() {
+        for j in i + 1..segments.len() {
+            if segments[i].bounds_overlap(&segments[j]) {
+                let (head, tail) = splits.split_at_mut(j);
+                find_crossings(&segments[i], &segments[j], &mut head[i], &mut tail[0]);
             }
         }
     }
 
-    fn compute_polygon_ops(&self) -> Option<Path> {
-        // Convert paths to polygons (linearize curves)
-        let polys1 = path_to_polygons(self.path1);
-        let polys2 = path_to_polygons(self.path2);
+    let mut pieces = Vec::with_capacity(segments.len());
+    for (segment, mut points) in segments.iter().zip(splits) {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut start = segment.p0;
+        for (_, p) in points.into_iter().chain([(1.0, segment.p1)]) {
+            if p != start {
+                pieces.push(Segment::new(start, p));
+                start = p;
+            }
+        }
+    }
+    pieces
+}
 
-        // Perform the boolean operation
-        let result_polys = match self.op {
-            PathOp::Union => polygon_union(&polys1, &polys2),
-            PathOp::Intersect => polygon_intersect(&polys1, &polys2),
-            PathOp::Difference => polygon_difference(&polys1, &polys2),
-            PathOp::ReverseDifference => polygon_difference(&polys2, &polys1),
-            PathOp::Xor => polygon_xor(&polys1, &polys2),
+/// Record where `a` and `b` meet as split points on each of them.
+///
+/// Crossings close to an endpoint snap to that endpoint, so both segments
+/// are split at exactly the same point.
+fn find_crossings(
+    a: &Segment,
+    b: &Segment,
+    splits_a: &mut Vec<(f64, Point)>,
+    splits_b: &mut Vec<(f64, Point)>,
+) {
+    let (ax, ay) = (f64::from(a.p0.x), f64::from(a.p0.y));
+    let (bx, by) = (f64::from(b.p0.x), f64::from(b.p0.y));
+    let da = (f64::from(a.p1.x) - ax, f64::from(a.p1.y) - ay);
+    let db = (f64::from(b.p1.x) - bx, f64::from(b.p1.y) - by);
+    let cross = |u: (f64, f64), v: (f64, f64)| u.0 * v.1 - u.1 * v.0;
+    let interior = |t: f64| t > PARAM_EPSILON && t < 1.0 - PARAM_EPSILON;
+
+    let len_a = da.0.hypot(da.1);
+    let len_b = db.0.hypot(db.1);
+    let offset = (bx - ax, by - ay);
+    let denom = cross(da, db);
+
+    if denom.abs() <= PARAM_EPSILON * len_a * len_b {
+        // Parallel: only collinear overlaps matter, split at the endpoints
+        if cross(da, offset).abs() > COINCIDENT_TOLERANCE * len_a {
+            return;
+        }
+        let project = |p: Point, (x, y): (f64, f64), (dx, dy): (f64, f64), len: f64| {
+            ((f64::from(p.x) - x) * dx + (f64::from(p.y) - y) * dy) / (len * len)
         };
+        for p in [b.p0, b.p1] {
+            let t = project(p, (ax, ay), da, len_a);
+            if interior(t) {
+                splits_a.push((t, p));
+            }
+        }
+        for p in [a.p0, a.p1] {
+            let u = project(p, (bx, by), db, len_b);
+            if interior(u) {
+                splits_b.push((u, p));
+            }
+        }
+        return;
+    }
 
-        // Convert result back to path
-        Some(polygons_to_path(&result_polys))
+    let t = cross(offset, db) / denom;
+    let u = cross(offset, da) / denom;
+    let within = -PARAM_EPSILON..=1.0 + PARAM_EPSILON;
+    if !within.contains(&t) || !within.contains(&u) {
+        return;
+    }
+
+    let point = if t <= PARAM_EPSILON {
+        a.p0
+    } else if t >= 1.0 - PARAM_EPSILON {
+        a.p1
+    } else if u <= PARAM_EPSILON {
+        b.p0
+    } else if u >= 1.0 - PARAM_EPSILON {
+        b.p1
+    } else {
+        Point::new((ax + da.0 * t) as Scalar, (ay + da.1 * t) as Scalar)
+    };
+    if interior(t) {
+        splits_a.push((t, point));
+    }
+    if interior(u) {
+        splits_b.push((u, point));
     }
 }
 
-fn bounds_intersect(a: &Rect, b: &Rect) -> bool {
-    a.left < b.right && a.right > b.left && a.top < b.bottom && a.bottom > b.top
+/// Exact identity of a point, for joining edges end to start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct PointKey(u32, u32);
+
+impl PointKey {
+    fn new(p: Point) -> Self {
+        // Adding zero folds -0.0 into 0.0
+        Self((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits())
+    }
+}
+
+/// Join oriented edges into closed polygons.
+///
+/// Where several edges leave a vertex, the walk takes the sharpest left
+/// turn, which keeps regions that only touch at a point in separate
+/// polygons.
+fn chain_edges(edges: &[Segment]) -> Vec<Polygon> {
+    let mut outgoing: BTreeMap<PointKey, Vec<usize>> = BTreeMap::new();
+    for (i, edge) in edges.iter().enumerate() {
+        outgoing.entry(PointKey::new(edge.p0)).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut polygons = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+
+        let start = PointKey::new(edges[first].p0);
+        let mut poly = Polygon::new();
+        let mut current = first;
+        loop {
+            used[current] = true;
+            let edge = edges[current];
+            poly.add_point(edge.p0);
+
+            let end = PointKey::new(edge.p1);
+            if end == start {
+                break;
+            }
+            let dir = edge.p1 - edge.p0;
+            let turn = |i: &usize| {
+                let out = edges[*i].p1 - edges[*i].p0;
+                dir.cross(&out).atan2(dir.dot(&out))
+            };
+            let next = outgoing.get(&end).and_then(|candidates| {
+                candidates
+                    .iter()
+                    .filter(|i| !used[**i])
+                    .max_by(|a, b| turn(a).total_cmp(&turn(b)))
+            });
+            match next {
+                Some(&next) => current = next,
+                None => break,
+            }
+        }
+
+        remove_collinear_points(&mut poly.points);
+        if !poly.is_empty() && poly.signed_area() != 0.0 {
+            polygons.push(poly);
+        }
+    }
+    polygons
+}
+
+/// Drop vertices that don't change direction, including zero-length spikes.
+fn remove_collinear_points(points: &mut Vec<Point>) {
+    let mut i = 0;
+    while points.len() >= 3 && i < points.len() {
+        let n = points.len();
+        let a = points[(i + n - 1) % n];
+        let b = points[i];
+        let c = points[(i + 1) % n];
+        let ab = b - a;
+        let bc = c - b;
+        if ab.cross(&bc).abs() <= 1e-6 * ab.length() * bc.length() {
+            points.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
 }
 
 /// A simple polygon represented as a list of points.
 #[derive(Debug, Clone)]
 struct Polygon {
     points: Vec<Point>,
-    is_hole: bool,
 }
 
 impl Polygon {
     fn new() -> Self {
-        Self {
-            points: Vec::new(),
-            is_hole: false,
-        }
+        Self { points: Vec::new() }
     }
 
     fn add_point(&mut self, p: Point) {
@@ -174,26 +418,6 @@ impl Polygon {
         self.points.len() < 3
     }
 
-    fn bounds(&self) -> Rect {
-        if self.points.is_empty() {
-            return Rect::EMPTY;
-        }
-
-        let mut min_x = self.points[0].x;
-        let mut max_x = self.points[0].x;
-        let mut min_y = self.points[0].y;
-        let mut max_y = self.points[0].y;
-
-        for p in &self.points[1..] {
-            min_x = min_x.min(p.x);
-            max_x = max_x.max(p.x);
-            min_y = min_y.min(p.y);
-            max_y = max_y.max(p.y);
-        }
-
-        Rect::new(min_x, min_y, max_x, max_y)
-    }
-
     fn signed_area(&self) -> Scalar {
         if self.points.len() < 3 {
             return 0.0;
@@ -209,9 +433,15 @@ impl Polygon {
         area / 2.0
     }
 
+    #[cfg(test)]
     fn contains_point(&self, p: Point) -> bool {
+        self.winding((f64::from(p.x), f64::from(p.y))) != 0
+    }
+
+    /// Winding number of the polygon around a point.
+    fn winding(&self, (x, y): (f64, f64)) -> i32 {
         if self.points.len() < 3 {
-            return false;
+            return 0;
         }
 
         let mut winding = 0;
@@ -219,30 +449,56 @@ impl Polygon {
 
         for i in 0..n {
             let j = (i + 1) % n;
-            let p1 = self.points[i];
-            let p2 = self.points[j];
+            let (x1, y1) = (f64::from(self.points[i].x), f64::from(self.points[i].y));
+            let (x2, y2) = (f64::from(self.points[j].x), f64::from(self.points[j].y));
+            let left = (x2 - x1) * (y - y1) - (x - x1) * (y2 - y1);
 
-            if p1.y <= p.y {
-                if p2.y > p.y {
+            if y1 <= y {
+                if y2 > y && left > 0.0 {
                     // Upward crossing
-                    if is_left(p1, p2, p) > 0.0 {
-                        winding += 1;
-                    }
+                    winding += 1;
                 }
-            } else if p2.y <= p.y {
+            } else if y2 <= y && left < 0.0 {
                 // Downward crossing
-                if is_left(p1, p2, p) < 0.0 {
-                    winding -= 1;
-                }
+                winding -= 1;
             }
         }
 
-        winding != 0
+        winding
+    }
+
+    /// Whether a point lies on one of the polygon's edges, including the
+    /// closing edge.
+    fn on_edge(&self, (x, y): (f64, f64)) -> bool {
+        let n = self.points.len();
+        (0..n).any(|i| {
+            let (x1, y1) = (f64::from(self.points[i].x), f64::from(self.points[i].y));
+            let j = (i + 1) % n;
+            let (x2, y2) = (f64::from(self.points[j].x), f64::from(self.points[j].y));
+            let (dx, dy) = (x2 - x1, y2 - y1);
+            let len_sq = dx * dx + dy * dy;
+            let t = if len_sq > 0.0 {
+                (((x - x1) * dx + (y - y1) * dy) / len_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let (ex, ey) = (x1 + t * dx - x, y1 + t * dy - y);
+            ex * ex + ey * ey <= ON_EDGE_TOLERANCE * ON_EDGE_TOLERANCE
+        })
     }
 }
 
-fn is_left(p0: Point, p1: Point, p2: Point) -> Scalar {
-    (p1.x - p0.x) * (p2.y - p0.y) - (p2.x - p0.x) * (p1.y - p0.y)
+/// Winding number of a path's flattened contours around a point, or `None`
+/// if the point lies on an edge.
+///
+/// Every contour is treated as closed, as it is when filled.
+pub(crate) fn winding_at(path: &Path, point: Point) -> Option<i32> {
+    let p = (f64::from(point.x), f64::from(point.y));
+    let polygons = path_to_polygons(path);
+    if polygons.iter().any(|poly| poly.on_edge(p)) {
+        return None;
+    }
+    Some(polygons.iter().map(|poly| poly.winding(p)).sum())
 }
 
 /// Convert a path to a list of polygons.
@@ -253,6 +509,11 @@ fn path_to_polygons(path: &Path) -> Vec<Polygon> {
     let mut first_point = Point::new(0.0, 0.0);
 
     for elem in path.iter() {
+        // Drawing after a close starts a new contour at the old start point
+        if current_poly.points.is_empty() && !matches!(elem, PathElement::Move(_)) {
+            current_poly.add_point(current_point);
+        }
+
         match elem {
             PathElement::Move(p) => {
                 if !current_poly.is_empty() {
@@ -268,24 +529,36 @@ fn path_to_polygons(path: &Path) -> Vec<Polygon> {
                 current_point = p;
             }
             PathElement::Quad(c, p) => {
-                // Linearize quadratic bezier
-                linearize_quad(&mut current_poly, current_point, c, p, 0.5);
+                linearize_quad(&mut current_poly, current_point, c, p, FLATTEN_TOLERANCE);
                 current_point = p;
             }
             PathElement::Conic(c, p, w) => {
-                // Approximate conic as quadratic
-                linearize_quad(&mut current_poly, current_point, c, p, 0.5);
+                for [c1, c2, end] in conic_to_cubics(current_point, c, p, w) {
+                    linearize_cubic(
+                        &mut current_poly,
+                        current_point,
+                        c1,
+                        c2,
+                        end,
+                        FLATTEN_TOLERANCE,
+                    );
+                    current_point = end;
+                }
                 current_point = p;
             }
             PathElement::Cubic(c1, c2, p) => {
-                // Linearize cubic bezier
-                linearize_cubic(&mut current_poly, current_point, c1, c2, p, 0.5);
+                linearize_cubic(
+                    &mut current_poly,
+                    current_point,
+                    c1,
+                    c2,
+                    p,
+                    FLATTEN_TOLERANCE,
+                );
                 current_point = p;
             }
             PathElement::Close => {
                 if !current_poly.is_empty() {
-                    // Determine if this is a hole based on winding
-                    current_poly.is_hole = current_poly.signed_area() < 0.0;
                     polygons.push(current_poly);
                 }
                 current_poly = Polygon::new();
@@ -295,7 +568,6 @@ fn path_to_polygons(path: &Path) -> Vec<Polygon> {
     }
 
     if !current_poly.is_empty() {
-        current_poly.is_hole = current_poly.signed_area() < 0.0;
         polygons.push(current_poly);
     }
 
@@ -358,223 +630,10 @@ fn distance_to_line(p: Point, line_start: Point, line_end: Point) -> Scalar {
     cross.abs() / len_sq.sqrt()
 }
 
-/// Union of two polygon sets.
-fn polygon_union(polys1: &[Polygon], polys2: &[Polygon]) -> Vec<Polygon> {
-    let mut result = Vec::new();
-
-    // Simple implementation: add all polygons and merge overlapping ones
-    for poly in polys1 {
-        if !poly.is_empty() {
-            result.push(poly.clone());
-        }
-    }
-
-    for poly in polys2 {
-        if !poly.is_empty() {
-            // Check if this polygon is fully contained in any existing polygon
-            let mut fully_contained = false;
-            for existing in &result {
-                if polygon_contains_polygon(existing, poly) {
-                    fully_contained = true;
-                    break;
-                }
-            }
-
-            if !fully_contained {
-                result.push(poly.clone());
-            }
-        }
-    }
-
-    result
-}
-
-/// Intersection of two polygon sets.
-fn polygon_intersect(polys1: &[Polygon], polys2: &[Polygon]) -> Vec<Polygon> {
-    let mut result = Vec::new();
-
-    for poly1 in polys1 {
-        for poly2 in polys2 {
-            if let Some(intersection) = intersect_convex_polygons(poly1, poly2) {
-                if !intersection.is_empty() {
-                    result.push(intersection);
-                }
-            }
-        }
-    }
-
-    result
-}
-
-/// Difference of two polygon sets (polys1 - polys2).
-fn polygon_difference(polys1: &[Polygon], polys2: &[Polygon]) -> Vec<Polygon> {
-    let mut result = Vec::new();
-
-    for poly1 in polys1 {
-        if poly1.is_empty() {
-            continue;
-        }
-
-        let mut remaining = vec![poly1.clone()];
-
-        for poly2 in polys2 {
-            if poly2.is_empty() {
-                continue;
-            }
-
-            let mut new_remaining = Vec::new();
-            for rem in remaining {
-                // Check bounds overlap
-                let b1 = rem.bounds();
-                let b2 = poly2.bounds();
-
-                if !bounds_intersect(&b1, &b2) {
-                    new_remaining.push(rem);
-                } else {
-                    // Subtract poly2 from rem
-                    let subtracted = subtract_polygon(&rem, poly2);
-                    new_remaining.extend(subtracted);
-                }
-            }
-            remaining = new_remaining;
-        }
-
-        result.extend(remaining);
-    }
-
-    result
-}
-
-/// XOR of two polygon sets.
-fn polygon_xor(polys1: &[Polygon], polys2: &[Polygon]) -> Vec<Polygon> {
-    // XOR = (A - B) ∪ (B - A)
-    let a_minus_b = polygon_difference(polys1, polys2);
-    let b_minus_a = polygon_difference(polys2, polys1);
-
-    let mut result = a_minus_b;
-    result.extend(b_minus_a);
-    result
-}
-
-/// Check if polygon a fully contains polygon b.
-fn polygon_contains_polygon(a: &Polygon, b: &Polygon) -> bool {
-    if b.points.is_empty() {
-        return true;
-    }
-
-    // Check if all points of b are inside a
-    for p in &b.points {
-        if !a.contains_point(*p) {
-            return false;
-        }
-    }
-
-    true
-}
-
-/// Intersect two convex polygons using Sutherland-Hodgman algorithm.
-fn intersect_convex_polygons(subject: &Polygon, clip: &Polygon) -> Option<Polygon> {
-    if subject.is_empty() || clip.is_empty() {
-        return None;
-    }
-
-    let mut output = subject.points.clone();
-
-    let n = clip.points.len();
-    for i in 0..n {
-        if output.is_empty() {
-            break;
-        }
-
-        let j = (i + 1) % n;
-        let edge_start = clip.points[i];
-        let edge_end = clip.points[j];
-
-        let input = output;
-        output = Vec::new();
-
-        for k in 0..input.len() {
-            let current = input[k];
-            let next = input[(k + 1) % input.len()];
-
-            let current_inside = is_left(edge_start, edge_end, current) >= 0.0;
-            let next_inside = is_left(edge_start, edge_end, next) >= 0.0;
-
-            if current_inside {
-                output.push(current);
-
-                if !next_inside {
-                    if let Some(intersection) =
-                        line_intersection(current, next, edge_start, edge_end)
-                    {
-                        output.push(intersection);
-                    }
-                }
-            } else if next_inside {
-                if let Some(intersection) = line_intersection(current, next, edge_start, edge_end) {
-                    output.push(intersection);
-                }
-            }
-        }
-    }
-
-    if output.len() >= 3 {
-        let mut result = Polygon::new();
-        result.points = output;
-        Some(result)
-    } else {
-        None
-    }
-}
-
-/// Subtract one polygon from another.
-fn subtract_polygon(subject: &Polygon, clip: &Polygon) -> Vec<Polygon> {
-    // Simplified implementation: if clip contains subject, return empty
-    // Otherwise, return subject (proper implementation would clip)
-    if polygon_contains_polygon(clip, subject) {
-        return Vec::new();
-    }
-
-    // Check if there's any overlap
-    let bounds1 = subject.bounds();
-    let bounds2 = clip.bounds();
-
-    if !bounds_intersect(&bounds1, &bounds2) {
-        return vec![subject.clone()];
-    }
-
-    // For a proper implementation, we would:
-    // 1. Find all intersection points
-    // 2. Build a planar graph
-    // 3. Walk the graph to find result polygons
-    // For now, return subject if not fully contained
-    vec![subject.clone()]
-}
-
-/// Find intersection point of two line segments.
-fn line_intersection(p1: Point, p2: Point, p3: Point, p4: Point) -> Option<Point> {
-    let d1 = p2 - p1;
-    let d2 = p4 - p3;
-
-    let cross = d1.x * d2.y - d1.y * d2.x;
-
-    if cross.abs() < 1e-10 {
-        return None; // Lines are parallel
-    }
-
-    let d3 = p3 - p1;
-    let t = (d3.x * d2.y - d3.y * d2.x) / cross;
-
-    if t >= 0.0 && t <= 1.0 {
-        Some(p1 + d1 * t)
-    } else {
-        None
-    }
-}
-
 /// Convert polygons back to a path.
-fn polygons_to_path(polygons: &[Polygon]) -> Path {
+fn polygons_to_path(polygons: &[Polygon], fill_type: FillType) -> Path {
     let mut builder = PathBuilder::new();
+    builder.fill_type(fill_type);
 
     for poly in polygons {
         if poly.points.len() < 3 {
@@ -594,6 +653,21 @@ fn polygons_to_path(polygons: &[Polygon]) -> Path {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use skia_rs_core::Rect;
+
+    fn circle(cx: Scalar, cy: Scalar, radius: Scalar) -> Path {
+        let mut builder = PathBuilder::new();
+        builder.add_circle(cx, cy, radius);
+        builder.build()
+    }
+
+    fn area(path: &Path) -> Scalar {
+        path_to_polygons(path)
+            .iter()
+            .map(|poly| poly.signed_area())
+            .sum::<Scalar>()
+            .abs()
+    }
 
     #[test]
     fn test_empty_paths() {
@@ -646,4 +720,46 @@ mod tests {
         assert!(poly.contains_point(Point::new(5.0, 5.0)));
         assert!(!poly.contains_point(Point::new(15.0, 5.0)));
     }
+
+    #[test]
+    fn test_overlapping_circles() {
+        let a = circle(0.0, 0.0, 10.0);
+        let b = circle(10.0, 0.0, 10.0);
+
+        let union = PathOps::apply(&a, &b, PathOp::Union).unwrap();
+        assert_eq!(union.contour_count(), 1);
+        assert!(union.contains(Point::new(-8.0, 0.0)));
+        assert!(union.contains(Point::new(18.0, 0.0)));
+
+        // Lens between x = 0 and x = 10, about 123 units of area
+        let lens = PathOps::apply(&a, &b, PathOp::Intersect).unwrap();
+        assert_eq!(lens.contour_count(), 1);
+        assert!((area(&lens) - 122.8).abs() < 2.0);
+        let bounds = lens.bounds();
+        assert!(bounds.left > -0.1 && bounds.right < 10.1);
+
+        let difference = PathOps::apply(&a, &b, PathOp::Difference).unwrap();
+        assert!(difference.contains(Point::new(-5.0, 0.0)));
+        assert!(!difference.contains(Point::new(5.0, 0.0)));
+        let total = area(&difference) + area(&lens);
+        assert!((total - area(&a)).abs() < 1.0);
+
+        let xor = PathOps::apply(&a, &b, PathOp::Xor).unwrap();
+        assert!((area(&xor) - (area(&union) - area(&lens))).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_simplify_self_intersecting() {
+        // A bow tie: two triangles meeting at (5, 5)
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 10.0)
+            .line_to(10.0, 0.0)
+            .line_to(0.0, 10.0)
+            .close();
+        let result = simplify(&builder.build()).unwrap();
+        assert_eq!(result.contour_count(), 2);
+        assert!((area(&result) - 50.0).abs() < 1e-3);
+    }
 }
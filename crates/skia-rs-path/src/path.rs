@@ -1,5 +1,7 @@
 //! Path data structure and iteration.
 
+use alloc::string::String;
+use core::ops::Range;
 use skia_rs_core::{Point, Rect, Scalar};
 use smallvec::SmallVec;
 
@@ -213,6 +215,18 @@ impl Path {
         self.verbs.iter().filter(|v| **v == Verb::Move).count()
     }
 
+    /// Iterate over the contours of the path.
+    ///
+    /// Each contour starts at a `Move` verb and runs up to the next one.
+    pub fn contours(&self) -> Contours<'_> {
+        Contours {
+            path: self,
+            verb_index: 0,
+            point_index: 0,
+            weight_index: 0,
+        }
+    }
+
     /// Check if the path is closed.
     pub fn is_closed(&self) -> bool {
         self.verbs.last() == Some(&Verb::Close)
@@ -409,6 +423,47 @@ impl Path {
         result
     }
 
+    /// Resolve self-intersections into contours that don't cross.
+    ///
+    /// The result covers the same region as this path does under its fill
+    /// type, with curves flattened and every contour wound consistently, so
+    /// the winding and even-odd rules fill it alike. Overlapping duplicate
+    /// contours merge and contours that enclose no area are dropped. A path
+    /// with non-finite points is returned unchanged.
+    pub fn simplify(&self) -> Path {
+        crate::ops::simplify(self).unwrap_or_else(|| self.clone())
+    }
+
+    /// Serialize the path as an SVG path data string.
+    ///
+    /// Coordinates keep up to three decimal places. The fill type is not
+    /// part of SVG path data and is not written.
+    ///
+    /// # Example
+    /// ```
+    /// use skia_rs_path::{PathBuilder, parse_svg_path};
+    ///
+    /// let mut builder = PathBuilder::new();
+    /// builder.move_to(0.0, 0.0).line_to(10.5, 0.0).close();
+    /// let path = builder.build();
+    /// assert_eq!(path.to_svg_string(), "M0 0L10.5 0Z");
+    ///
+    /// let parsed = parse_svg_path(&path.to_svg_string()).unwrap();
+    /// assert_eq!(parsed.to_svg_string(), path.to_svg_string());
+    /// ```
+    pub fn to_svg_string(&self) -> String {
+        self.to_svg_string_with_precision(3)
+    }
+
+    /// Serialize the path as an SVG path data string, rounding coordinates
+    /// to `precision` decimal places.
+    ///
+    /// Conics have no SVG equivalent and are written as cubic
+    /// approximations.
+    pub fn to_svg_string_with_precision(&self, precision: usize) -> String {
+        crate::svg::write_svg_path(self, precision)
+    }
+
     /// Offset the path by (dx, dy).
     pub fn offset(&mut self, dx: Scalar, dy: Scalar) {
         for point in &mut self.points {
@@ -423,91 +478,30 @@ impl Path {
         }
     }
 
-    /// Check if a point is inside the path (using fill rule).
+    /// Check if a point is inside the path under its fill type.
+    ///
+    /// Every contour counts as closed, as it does when filled. Points on an
+    /// edge belong to the filled side of the boundary, so they are inside
+    /// for the normal fill types and outside for the inverse ones.
     pub fn contains(&self, point: Point) -> bool {
-        // Ray casting algorithm
-        if !self.bounds().contains(point) {
-            return false;
+        let inverse = self.fill_type.is_inverse();
+        let bounds = self.bounds();
+        if !(point.x >= bounds.left
+            && point.x <= bounds.right
+            && point.y >= bounds.top
+            && point.y <= bounds.bottom)
+        {
+            return inverse;
         }
 
-        let mut crossings = 0;
-        let mut current = Point::zero();
-
-        for element in self.iter() {
-            match element {
-                PathElement::Move(p) => current = p,
-                PathElement::Line(end) => {
-                    if ray_crosses_segment(point, current, end) {
-                        crossings += 1;
-                    }
-                    current = end;
-                }
-                PathElement::Quad(ctrl, end) => {
-                    // Approximate with lines
-                    for i in 1..=8 {
-                        let t = i as f32 / 8.0;
-                        let mt = 1.0 - t;
-                        let p = Point::new(
-                            mt * mt * current.x + 2.0 * mt * t * ctrl.x + t * t * end.x,
-                            mt * mt * current.y + 2.0 * mt * t * ctrl.y + t * t * end.y,
-                        );
-                        if ray_crosses_segment(point, current, p) {
-                            crossings += 1;
-                        }
-                        current = p;
-                    }
-                    current = end;
-                }
-                PathElement::Conic(ctrl, end, _w) => {
-                    // Approximate with lines
-                    for i in 1..=8 {
-                        let t = i as f32 / 8.0;
-                        let mt = 1.0 - t;
-                        let p = Point::new(
-                            mt * mt * current.x + 2.0 * mt * t * ctrl.x + t * t * end.x,
-                            mt * mt * current.y + 2.0 * mt * t * ctrl.y + t * t * end.y,
-                        );
-                        if ray_crosses_segment(point, current, p) {
-                            crossings += 1;
-                        }
-                        current = p;
-                    }
-                    current = end;
-                }
-                PathElement::Cubic(c1, c2, end) => {
-                    // Approximate with lines
-                    for i in 1..=12 {
-                        let t = i as f32 / 12.0;
-                        let mt = 1.0 - t;
-                        let mt2 = mt * mt;
-                        let t2 = t * t;
-                        let p = Point::new(
-                            mt2 * mt * current.x
-                                + 3.0 * mt2 * t * c1.x
-                                + 3.0 * mt * t2 * c2.x
-                                + t2 * t * end.x,
-                            mt2 * mt * current.y
-                                + 3.0 * mt2 * t * c1.y
-                                + 3.0 * mt * t2 * c2.y
-                                + t2 * t * end.y,
-                        );
-                        if ray_crosses_segment(point, current, p) {
-                            crossings += 1;
-                        }
-                        current = p;
-                    }
-                    current = end;
-                }
-                PathElement::Close => {}
-            }
-        }
-
-        match self.fill_type {
-            FillType::Winding => crossings != 0,
-            FillType::EvenOdd => crossings % 2 != 0,
-            FillType::InverseWinding => crossings == 0,
-            FillType::InverseEvenOdd => crossings % 2 == 0,
-        }
+        let inside = match crate::ops::winding_at(self, point) {
+            None => true,
+            Some(winding) => match self.fill_type {
+                FillType::Winding | FillType::InverseWinding => winding != 0,
+                FillType::EvenOdd | FillType::InverseEvenOdd => winding % 2 != 0,
+            },
+        };
+        inside != inverse
     }
 
     /// Compute tight bounds (considering curve control points).
@@ -549,23 +543,6 @@ impl Path {
     }
 }
 
-/// Check if a horizontal ray from point crosses the segment.
-fn ray_crosses_segment(point: Point, p0: Point, p1: Point) -> bool {
-    // Ensure p0 is below p1
-    let (p0, p1) = if p0.y <= p1.y { (p0, p1) } else { (p1, p0) };
-
-    // Check if point is in y-range of segment
-    if point.y < p0.y || point.y >= p1.y {
-        return false;
-    }
-
-    // Calculate x-coordinate of intersection
-    let t = (point.y - p0.y) / (p1.y - p0.y);
-    let x_intersect = p0.x + t * (p1.x - p0.x);
-
-    x_intersect > point.x
-}
-
 /// A path element from iteration.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PathElement {
@@ -640,3 +617,264 @@ impl<'a> Iterator for PathIter<'a> {
         Some(element)
     }
 }
+
+/// A single contour of a [`Path`], borrowed from it.
+#[derive(Debug, Clone)]
+pub struct ContourRef<'a> {
+    path: &'a Path,
+    verbs: Range<usize>,
+    points: Range<usize>,
+    weights: Range<usize>,
+}
+
+impl<'a> ContourRef<'a> {
+    /// Get the verbs of this contour, starting with its `Move`.
+    #[inline]
+    pub fn verbs(&self) -> &'a [Verb] {
+        &self.path.verbs[self.verbs.clone()]
+    }
+
+    /// Get the points of this contour.
+    #[inline]
+    pub fn points(&self) -> &'a [Point] {
+        &self.path.points[self.points.clone()]
+    }
+
+    /// Get the conic weights of this contour.
+    #[inline]
+    pub fn conic_weights(&self) -> &'a [Scalar] {
+        &self.path.conic_weights[self.weights.clone()]
+    }
+
+    /// Check if the contour ends with a `Close` verb.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.verbs().last() == Some(&Verb::Close)
+    }
+
+    /// Copy this contour into a path of its own, keeping the fill type.
+    pub fn to_path(&self) -> Path {
+        Path {
+            verbs: self.verbs().into(),
+            points: self.points().into(),
+            conic_weights: self.conic_weights().into(),
+            fill_type: self.path.fill_type,
+            bounds: None,
+            convexity: PathConvexity::Unknown,
+        }
+    }
+}
+
+/// Iterator over the contours of a path, created by [`Path::contours`].
+pub struct Contours<'a> {
+    path: &'a Path,
+    verb_index: usize,
+    point_index: usize,
+    weight_index: usize,
+}
+
+impl<'a> Iterator for Contours<'a> {
+    type Item = ContourRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let verbs = &self.path.verbs;
+        if self.verb_index >= verbs.len() {
+            return None;
+        }
+
+        let (verb_start, point_start, weight_start) =
+            (self.verb_index, self.point_index, self.weight_index);
+        loop {
+            let verb = verbs[self.verb_index];
+            self.verb_index += 1;
+            self.point_index += verb.point_count();
+            if verb == Verb::Conic {
+                self.weight_index += 1;
+            }
+            if verbs.get(self.verb_index).is_none_or(|v| *v == Verb::Move) {
+                break;
+            }
+        }
+
+        Some(ContourRef {
+            path: self.path,
+            verbs: verb_start..self.verb_index,
+            points: point_start..self.point_index,
+            weights: weight_start..self.weight_index,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PathBuilder;
+
+    #[test]
+    fn test_contours_open_and_closed() {
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 10.0)
+            .close()
+            .move_to(20.0, 0.0)
+            .quad_to(25.0, 5.0, 30.0, 0.0)
+            .conic_to(35.0, 5.0, 40.0, 0.0, 0.5);
+        let path = builder.build();
+
+        let contours: Vec<_> = path.contours().collect();
+        assert_eq!(contours.len(), path.contour_count());
+        assert_eq!(contours.len(), 2);
+
+        let (first, second) = (&contours[0], &contours[1]);
+        assert!(first.is_closed());
+        assert!(!second.is_closed());
+        assert_eq!(
+            first.verbs(),
+            [Verb::Move, Verb::Line, Verb::Line, Verb::Close]
+        );
+        assert_eq!(second.verbs(), [Verb::Move, Verb::Quad, Verb::Conic]);
+        assert_eq!(second.points().len(), 5);
+        assert_eq!(second.points()[0], Point::new(20.0, 0.0));
+        assert_eq!(second.conic_weights(), [0.5]);
+
+        let sub = second.to_path();
+        assert_eq!(sub.contour_count(), 1);
+        assert_eq!(sub.bounds(), Rect::new(20.0, 0.0, 40.0, 5.0));
+        assert_eq!(
+            sub.iter().collect::<Vec<_>>(),
+            path.iter().skip(4).collect::<Vec<_>>()
+        );
+
+        assert_eq!(Path::new().contours().count(), 0);
+
+        // Drawing on after a close starts a new contour at the closed one's start.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 10.0)
+            .close()
+            .r_line_to(0.0, 5.0);
+        let path = builder.build();
+        let contours: Vec<_> = path.contours().collect();
+        assert_eq!(contours.len(), 2);
+        assert!(contours[0].is_closed());
+        assert!(!contours[1].is_closed());
+        assert_eq!(contours[1].verbs(), [Verb::Move, Verb::Line]);
+        assert_eq!(
+            contours[1].points(),
+            [Point::new(0.0, 0.0), Point::new(0.0, 5.0)]
+        );
+    }
+
+    #[test]
+    fn test_contains_fill_types() {
+        let mut builder = PathBuilder::new();
+        builder.add_circle(50.0, 50.0, 20.0);
+        let mut circle = builder.build();
+        let center = Point::new(50.0, 50.0);
+        let far = Point::new(200.0, 50.0);
+
+        assert!(circle.contains(center));
+        assert!(!circle.contains(far));
+        circle.set_fill_type(FillType::InverseEvenOdd);
+        assert!(!circle.contains(center));
+        assert!(circle.contains(far));
+
+        // A square inside a square, wound the same way: the hole only
+        // exists under even-odd.
+        let mut builder = PathBuilder::new();
+        builder
+            .add_rect(&Rect::new(0.0, 0.0, 30.0, 30.0))
+            .add_rect(&Rect::new(10.0, 10.0, 20.0, 20.0));
+        let mut nested = builder.build();
+        let hole = Point::new(15.0, 15.0);
+        let ring = Point::new(5.0, 15.0);
+        for (fill_type, in_hole, in_ring) in [
+            (FillType::Winding, true, true),
+            (FillType::EvenOdd, false, true),
+            (FillType::InverseWinding, false, false),
+            (FillType::InverseEvenOdd, true, false),
+        ] {
+            nested.set_fill_type(fill_type);
+            assert_eq!(nested.contains(hole), in_hole, "{fill_type:?}");
+            assert_eq!(nested.contains(ring), in_ring, "{fill_type:?}");
+        }
+    }
+
+    #[test]
+    fn test_contains_edges() {
+        // An open triangle fills as if closed
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 10.0);
+        let mut triangle = builder.build();
+        assert!(triangle.contains(Point::new(8.0, 2.0)));
+        assert!(!triangle.contains(Point::new(2.0, 8.0)));
+
+        // Every edge, including the implicit closing one and the corners,
+        // belongs to the fill, and to the outside of an inverse fill
+        let on_edges = [
+            Point::new(5.0, 0.0),
+            Point::new(10.0, 5.0),
+            Point::new(5.0, 5.0),
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 10.0),
+        ];
+        for point in on_edges {
+            assert!(triangle.contains(point), "{point:?}");
+        }
+        triangle.set_fill_type(FillType::InverseWinding);
+        for point in on_edges {
+            assert!(!triangle.contains(point), "{point:?}");
+        }
+    }
+
+    #[test]
+    fn test_simplify_figure_eight() {
+        // Two lobes crossing at the origin, wound in opposite directions
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .cubic_to(20.0, -20.0, 20.0, 20.0, 0.0, 0.0)
+            .cubic_to(-20.0, 20.0, -20.0, -20.0, 0.0, 0.0)
+            .close();
+        let figure_eight = builder.build();
+
+        let simplified = figure_eight.simplify();
+        assert_eq!(simplified.contour_count(), 2);
+        let directions: Vec<_> = simplified
+            .contours()
+            .map(|contour| contour.to_path().direction())
+            .collect();
+        assert!(directions[0].is_some());
+        assert_eq!(directions[0], directions[1]);
+
+        for point in [Point::new(8.0, 0.0), Point::new(-8.0, 0.0)] {
+            assert!(simplified.contains(point));
+        }
+        assert!(!simplified.contains(Point::new(0.0, 10.0)));
+        assert!(!simplified.contains(Point::new(20.0, 0.0)));
+    }
+
+    #[test]
+    fn test_simplify_degenerate_input() {
+        assert!(Path::new().simplify().is_empty());
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(5.0, 5.0).close();
+        assert!(builder.build().simplify().is_empty());
+
+        // Duplicate squares merge into one
+        let square = Rect::from_xywh(0.0, 0.0, 10.0, 10.0);
+        let mut builder = PathBuilder::new();
+        builder.add_rect(&square).add_rect(&square);
+        let simplified = builder.build().simplify();
+        assert_eq!(simplified.contour_count(), 1);
+        assert_eq!(simplified.bounds(), square);
+    }
+}
@@ -3,7 +3,10 @@
 //! This module provides utility functions for path manipulation,
 //! including stroke-to-fill conversion.
 
-use crate::{Path, PathBuilder, PathElement};
+use crate::{DashEffect, FillType, Path, PathBuilder, PathEffect, PathElement};
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use skia_rs_core::FloatMath;
 use skia_rs_core::{Point, Scalar};
 
 /// Stroke cap style for stroke-to-fill conversion.
@@ -34,7 +37,7 @@ pub enum StrokeJoin {
 
 /// Parameters for stroke-to-fill conversion.
 #[derive(Debug, Clone)]
-pub struct StrokeParams {
+pub struct StrokeParams<'a> {
     /// Stroke width.
     pub width: Scalar,
     /// Stroke cap style.
@@ -43,20 +46,23 @@ pub struct StrokeParams {
     pub join: StrokeJoin,
     /// Miter limit (for miter joins).
     pub miter_limit: Scalar,
+    /// Dash intervals (on, off, ...) and phase, applied before outlining.
+    pub dash: Option<(&'a [Scalar], Scalar)>,
 }
 
-impl Default for StrokeParams {
+impl Default for StrokeParams<'_> {
     fn default() -> Self {
         Self {
             width: 1.0,
             cap: StrokeCap::Butt,
             join: StrokeJoin::Miter,
             miter_limit: 4.0,
+            dash: None,
         }
     }
 }
 
-impl StrokeParams {
+impl<'a> StrokeParams<'a> {
     /// Create new stroke parameters.
     pub fn new(width: Scalar) -> Self {
         Self {
@@ -82,6 +88,15 @@ impl StrokeParams {
         self.miter_limit = limit;
         self
     }
+
+    /// Dash the path before outlining it.
+    ///
+    /// `intervals` alternate between on and off lengths, as for
+    /// [`DashEffect`](crate::DashEffect), and `phase` offsets the pattern.
+    pub fn with_dash(mut self, intervals: &'a [Scalar], phase: Scalar) -> Self {
+        self.dash = Some((intervals, phase));
+        self
+    }
 }
 
 /// Convert a stroked path to a filled path.
@@ -89,322 +104,514 @@ impl StrokeParams {
 /// This creates an outline around the input path that, when filled,
 /// would produce the same visual result as stroking the original path.
 ///
+/// The outline is built as a union of pieces: one quad per segment plus
+/// join and cap geometry, all wound the same way. The result uses
+/// [`FillType::Winding`] so the overlapping pieces merge into one shape.
+/// Zero-length contours produce a dot for round and square caps.
+///
+/// With a dash pattern set, the path is dashed first and every dash is
+/// outlined as its own open contour. Caps and joins are already part of the
+/// returned outline, so filling it is all that is left to draw the stroke.
+/// An invalid dash pattern strokes the path undashed.
+///
 /// # Arguments
 /// * `path` - The input path to stroke.
-/// * `params` - Stroke parameters (width, cap, join, miter limit).
+/// * `params` - Stroke parameters (width, cap, join, miter limit, dash).
 ///
 /// # Returns
 /// The stroked path as a fillable outline, or `None` if the path is empty.
-pub fn stroke_to_fill(path: &Path, params: &StrokeParams) -> Option<Path> {
+pub fn stroke_to_fill(path: &Path, params: &StrokeParams<'_>) -> Option<Path> {
     if path.is_empty() || params.width <= 0.0 {
         return None;
     }
 
+    let dashed = params
+        .dash
+        .and_then(|(intervals, phase)| DashEffect::new(intervals.to_vec(), phase))
+        .and_then(|dash| dash.apply(path));
+    let path = dashed.as_ref().unwrap_or(path);
+    if path.is_empty() {
+        return None;
+    }
+
     let half_width = params.width / 2.0;
-    let mut builder = PathBuilder::new();
+    let mut builder = PathBuilder::with_fill_type(FillType::Winding);
+
+    for contour in collect_contours(path, half_width) {
+        stroke_contour(&mut builder, &contour, half_width, params);
+    }
 
-    // Collect path elements into contours
-    let mut contours: Vec<Vec<Point>> = Vec::new();
-    let mut current_contour: Vec<Point> = Vec::new();
-    let mut is_closed = false;
+    Some(builder.build())
+}
+
+/// A flattened contour of the input path.
+struct Contour {
+    points: Vec<Point>,
+    /// Whether each point ends one of the path's own segments, as opposed
+    /// to lying inside a flattened curve.
+    corners: Vec<bool>,
+    closed: bool,
+}
+
+/// Maximum distance, in path units, between flattened geometry and the true
+/// curve or arc.
+const FLATTEN_TOLERANCE: Scalar = 0.1;
+
+/// Most line segments a single curve, arc or circle is flattened into.
+const MAX_FLATTEN_STEPS: usize = 256;
+
+/// Maximum distance, in path units, between a conic and the cubics that
+/// replace it in [`conic_to_cubics`].
+const CONIC_TO_CUBIC_TOLERANCE: Scalar = 0.01;
+
+/// Deepest halving of a conic in [`conic_to_cubics`], giving at most 64 cubics.
+const CONIC_TO_CUBIC_MAX_DEPTH: u32 = 6;
+
+/// Convert the conic from `p0` through control point `c` to `p1` with weight
+/// `w` into cubic Béziers.
+///
+/// Each cubic is returned as its two control points and end point; the first
+/// starts at `p0` and each following one starts where the previous ended.
+/// The conic is halved until every cubic stays within 0.01 path units of it.
+/// A weight that is not positive describes a line, returned as a single
+/// straight cubic.
+pub fn conic_to_cubics(p0: Point, c: Point, p1: Point, w: Scalar) -> Vec<[Point; 3]> {
+    let mut cubics = Vec::new();
+    if w > 0.0 && w.is_finite() {
+        push_conic_cubics(&mut cubics, p0, c, p1, w, 0);
+    } else {
+        cubics.push([p0.lerp(p1, 1.0 / 3.0), p0.lerp(p1, 2.0 / 3.0), p1]);
+    }
+    cubics
+}
+
+fn push_conic_cubics(
+    cubics: &mut Vec<[Point; 3]>,
+    p0: Point,
+    c: Point,
+    p1: Point,
+    w: Scalar,
+    depth: u32,
+) {
+    // Pulling the controls 4w / 3(1 + w) of the way toward the conic's
+    // control point elevates a quad exactly (w = 1) and gives the usual
+    // circular arc approximation for w = cos(θ / 2).
+    let k = 4.0 * w / (3.0 * (1.0 + w));
+    let c1 = p0.lerp(c, k);
+    let c2 = p1.lerp(c, k);
+
+    let conic_mid = conic_point(p0, c, p1, w, 0.5);
+    let within_tolerance = [0.25, 0.5, 0.75].into_iter().all(|t| {
+        let q = cubic_point(p0, c1, c2, p1, t);
+        conic_distance(p0, c, p1, w, q, t) <= CONIC_TO_CUBIC_TOLERANCE
+    });
+    if within_tolerance || depth >= CONIC_TO_CUBIC_MAX_DEPTH {
+        cubics.push([c1, c2, p1]);
+        return;
+    }
+
+    // Split at t = 0.5; both halves share the weight sqrt((1 + w) / 2).
+    let half_w = ((1.0 + w) / 2.0).sqrt();
+    let left_c = (p0 + c * w) / (1.0 + w);
+    let right_c = (c * w + p1) / (1.0 + w);
+    push_conic_cubics(cubics, p0, left_c, conic_mid, half_w, depth + 1);
+    push_conic_cubics(cubics, conic_mid, right_c, p1, half_w, depth + 1);
+}
+
+/// Point at parameter `t` on the conic `p0, c, p1` with weight `w`.
+pub(crate) fn conic_point(p0: Point, c: Point, p1: Point, w: Scalar, t: Scalar) -> Point {
+    let mt = 1.0 - t;
+    let (a, b, d) = (mt * mt, 2.0 * w * mt * t, t * t);
+    (p0 * a + c * b + p1 * d) / (a + b + d)
+}
+
+fn cubic_point(p0: Point, c1: Point, c2: Point, p1: Point, t: Scalar) -> Point {
+    let mt = 1.0 - t;
+    p0 * (mt * mt * mt) + c1 * (3.0 * mt * mt * t) + c2 * (3.0 * mt * t * t) + p1 * (t * t * t)
+}
+
+/// Distance from `q` to the conic, searching conic parameters near `t`.
+///
+/// The cubic approximating a conic tracks its parameterization closely, so
+/// the nearest conic point lies within a quarter of the curve of `t`, where
+/// the distance is unimodal and a golden-section search finds it.
+fn conic_distance(p0: Point, c: Point, p1: Point, w: Scalar, q: Point, t: Scalar) -> Scalar {
+    const INV_PHI: Scalar = 0.618_034;
+    let distance = |s: Scalar| conic_point(p0, c, p1, w, s).distance(&q);
+    let (mut lo, mut hi) = ((t - 0.25).max(0.0), (t + 0.25).min(1.0));
+    for _ in 0..24 {
+        let a = hi - (hi - lo) * INV_PHI;
+        let b = lo + (hi - lo) * INV_PHI;
+        if distance(a) < distance(b) {
+            hi = b;
+        } else {
+            lo = a;
+        }
+    }
+    distance((lo + hi) / 2.0)
+}
+
+/// Split a path into flattened contours.
+///
+/// Curves are flattened finely enough that both the curve and its offset
+/// at `half_width` stay within [`FLATTEN_TOLERANCE`] of the polyline.
+///
+/// A verb following `Close` without a `Move` starts a new contour at the
+/// previous contour's start point.
+fn collect_contours(path: &Path, half_width: Scalar) -> Vec<Contour> {
+    let mut contours = Vec::new();
+    let mut current: Vec<Point> = Vec::new();
+    let mut corners: Vec<bool> = Vec::new();
+    let mut start = Point::zero();
 
     for element in path.iter() {
+        if current.is_empty() && !matches!(element, PathElement::Move(_)) {
+            current.push(start);
+            corners.push(true);
+        }
         match element {
             PathElement::Move(p) => {
-                if !current_contour.is_empty() {
-                    contours.push(std::mem::take(&mut current_contour));
+                if !current.is_empty() {
+                    contours.push(Contour {
+                        points: core::mem::take(&mut current),
+                        corners: core::mem::take(&mut corners),
+                        closed: false,
+                    });
                 }
-                current_contour.push(p);
-                is_closed = false;
-            }
-            PathElement::Line(p) => {
-                current_contour.push(p);
+                current.push(p);
+                start = p;
             }
+            PathElement::Line(p) => current.push(p),
             PathElement::Quad(ctrl, end) => {
-                // Flatten quadratic to lines
-                if let Some(&start) = current_contour.last() {
-                    flatten_quad(&mut current_contour, start, ctrl, end, 4);
-                }
-            }
-            PathElement::Cubic(ctrl1, ctrl2, end) => {
-                // Flatten cubic to lines
-                if let Some(&start) = current_contour.last() {
-                    flatten_cubic(&mut current_contour, start, ctrl1, ctrl2, end, 8);
-                }
+                let from = current[current.len() - 1];
+                let steps = curve_steps(
+                    quad_deviation(from, ctrl, end),
+                    &[from, ctrl, end],
+                    half_width,
+                );
+                flatten_quad(&mut current, from, ctrl, end, steps);
             }
             PathElement::Conic(ctrl, end, weight) => {
-                // Flatten conic to lines (approximate as quad)
-                if let Some(&start) = current_contour.last() {
-                    let mid_ctrl = Point::new(
-                        start.x * (1.0 - weight) / 2.0
-                            + ctrl.x * weight
-                            + end.x * (1.0 - weight) / 2.0,
-                        start.y * (1.0 - weight) / 2.0
-                            + ctrl.y * weight
-                            + end.y * (1.0 - weight) / 2.0,
-                    );
-                    flatten_quad(&mut current_contour, start, mid_ctrl, end, 4);
-                }
+                let from = current[current.len() - 1];
+                // Weights above one pull the conic toward its control point
+                // faster than the quad with the same control polygon.
+                let deviation = quad_deviation(from, ctrl, end) * weight.max(1.0);
+                let steps = curve_steps(deviation, &[from, ctrl, end], half_width);
+                flatten_conic(&mut current, from, ctrl, end, weight, steps);
+            }
+            PathElement::Cubic(ctrl1, ctrl2, end) => {
+                let from = current[current.len() - 1];
+                let deviation = 0.75
+                    * (from - ctrl1 * 2.0 + ctrl2)
+                        .length()
+                        .max((ctrl1 - ctrl2 * 2.0 + end).length());
+                let steps = curve_steps(deviation, &[from, ctrl1, ctrl2, end], half_width);
+                flatten_cubic(&mut current, from, ctrl1, ctrl2, end, steps);
             }
             PathElement::Close => {
-                is_closed = true;
+                contours.push(Contour {
+                    points: core::mem::take(&mut current),
+                    corners: core::mem::take(&mut corners),
+                    closed: true,
+                });
+                continue;
             }
         }
+        // Only the end of each verb is a join between the path's segments.
+        corners.resize(current.len(), false);
+        corners[current.len() - 1] = true;
     }
 
-    if !current_contour.is_empty() {
-        contours.push(current_contour);
+    if !current.is_empty() {
+        contours.push(Contour {
+            points: current,
+            corners,
+            closed: false,
+        });
     }
 
-    // Process each contour
-    for contour in &contours {
-        if contour.len() < 2 {
-            continue;
-        }
+    contours
+}
 
-        stroke_contour(&mut builder, contour, is_closed, half_width, params);
-    }
+/// Wang's bound on how far a quad strays from its chords, before dividing
+/// by the squared step count.
+fn quad_deviation(p0: Point, p1: Point, p2: Point) -> Scalar {
+    0.25 * (p0 - p1 * 2.0 + p2).length()
+}
 
-    Some(builder.build())
+/// Number of steps to flatten a curve whose flattening error is
+/// `deviation / steps²`, and whose control polygon `hull` bounds how far
+/// its tangent turns.
+///
+/// Each step also turns at most the angle whose arc at `half_width` stays
+/// within tolerance, so the offset outline is as smooth as the curve.
+fn curve_steps(deviation: Scalar, hull: &[Point], half_width: Scalar) -> usize {
+    let by_deviation = (deviation / FLATTEN_TOLERANCE).sqrt().ceil();
+
+    let turn: Scalar = hull
+        .windows(2)
+        .map(|leg| leg[1] - leg[0])
+        .filter(|leg| leg.length_squared() > 0.0)
+        .collect::<Vec<_>>()
+        .windows(2)
+        .map(|legs| legs[0].cross(&legs[1]).atan2(legs[0].dot(&legs[1])).abs())
+        .sum();
+    let by_turn = arc_step_angle(half_width).map_or(0.0, |step| (turn / step).ceil());
+
+    (by_deviation.max(by_turn) as usize).clamp(1, MAX_FLATTEN_STEPS)
+}
+
+/// Largest angle an arc of `radius` can span while its chord stays within
+/// [`FLATTEN_TOLERANCE`], or `None` if the whole circle fits the tolerance.
+fn arc_step_angle(radius: Scalar) -> Option<Scalar> {
+    (radius > FLATTEN_TOLERANCE).then(|| (1.0 - FLATTEN_TOLERANCE / radius).acos() * 2.0)
 }
 
 fn stroke_contour(
     builder: &mut PathBuilder,
-    points: &[Point],
-    is_closed: bool,
+    contour: &Contour,
     half_width: Scalar,
     params: &StrokeParams,
 ) {
-    if points.len() < 2 {
-        return;
-    }
-
-    let n = points.len();
-
-    // Compute normals for each segment
-    let mut normals: Vec<Point> = Vec::with_capacity(n - 1);
-    for i in 0..n - 1 {
-        let dx = points[i + 1].x - points[i].x;
-        let dy = points[i + 1].y - points[i].y;
-        let len = (dx * dx + dy * dy).sqrt();
-        if len > 0.0 {
-            normals.push(Point::new(-dy / len, dx / len));
+    // A lone move draws nothing; anything else with no extent is a dot.
+    let has_segments = contour.points.len() > 1 || contour.closed;
+
+    let mut points: Vec<Point> = Vec::with_capacity(contour.points.len());
+    let mut corners: Vec<bool> = Vec::with_capacity(contour.points.len());
+    for (&p, &corner) in contour.points.iter().zip(&contour.corners) {
+        if points.last() == Some(&p) {
+            *corners.last_mut().unwrap() |= corner;
         } else {
-            normals.push(Point::new(0.0, 1.0));
+            points.push(p);
+            corners.push(corner);
         }
     }
+    if contour.closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+        corners[0] |= corners.pop().unwrap();
+    }
 
-    if normals.is_empty() {
+    if points.len() < 2 {
+        if has_segments {
+            add_dot(builder, points[0], half_width, params.cap);
+        }
         return;
     }
 
-    // Build left side (offset by +half_width)
-    let mut left_side: Vec<Point> = Vec::with_capacity(n);
-    // Build right side (offset by -half_width)
-    let mut right_side: Vec<Point> = Vec::with_capacity(n);
-
-    // First point
-    let first_normal = normals[0];
-    left_side.push(Point::new(
-        points[0].x + first_normal.x * half_width,
-        points[0].y + first_normal.y * half_width,
-    ));
-    right_side.push(Point::new(
-        points[0].x - first_normal.x * half_width,
-        points[0].y - first_normal.y * half_width,
-    ));
-
-    // Interior points with join handling
-    for i in 1..n - 1 {
-        let n1 = normals[i - 1];
-        let n2 = normals[i];
-
-        // Average normal for the join
-        let avg = Point::new(n1.x + n2.x, n1.y + n2.y);
-        let avg_len = avg.length();
-
-        if avg_len > 0.001 {
-            let scale = half_width / avg_len;
-            let offset = Point::new(avg.x * scale, avg.y * scale);
-
-            match params.join {
-                StrokeJoin::Miter => {
-                    // Compute miter length
-                    let miter_len = 1.0 / (avg_len / 2.0);
-                    if miter_len <= params.miter_limit {
-                        left_side.push(Point::new(
-                            points[i].x + offset.x * miter_len,
-                            points[i].y + offset.y * miter_len,
-                        ));
-                        right_side.push(Point::new(
-                            points[i].x - offset.x * miter_len,
-                            points[i].y - offset.y * miter_len,
-                        ));
-                    } else {
-                        // Fallback to bevel
-                        left_side.push(Point::new(
-                            points[i].x + n1.x * half_width,
-                            points[i].y + n1.y * half_width,
-                        ));
-                        left_side.push(Point::new(
-                            points[i].x + n2.x * half_width,
-                            points[i].y + n2.y * half_width,
-                        ));
-                        right_side.push(Point::new(
-                            points[i].x - n1.x * half_width,
-                            points[i].y - n1.y * half_width,
-                        ));
-                        right_side.push(Point::new(
-                            points[i].x - n2.x * half_width,
-                            points[i].y - n2.y * half_width,
-                        ));
-                    }
-                }
-                StrokeJoin::Bevel => {
-                    left_side.push(Point::new(
-                        points[i].x + n1.x * half_width,
-                        points[i].y + n1.y * half_width,
-                    ));
-                    left_side.push(Point::new(
-                        points[i].x + n2.x * half_width,
-                        points[i].y + n2.y * half_width,
-                    ));
-                    right_side.push(Point::new(
-                        points[i].x - n1.x * half_width,
-                        points[i].y - n1.y * half_width,
-                    ));
-                    right_side.push(Point::new(
-                        points[i].x - n2.x * half_width,
-                        points[i].y - n2.y * half_width,
-                    ));
-                }
-                StrokeJoin::Round => {
-                    // Simplified: use multiple points to approximate round join
-                    left_side.push(Point::new(points[i].x + offset.x, points[i].y + offset.y));
-                    right_side.push(Point::new(points[i].x - offset.x, points[i].y - offset.y));
-                }
-            }
-        } else {
-            // Parallel segments, use normal offset
-            left_side.push(Point::new(
-                points[i].x + n1.x * half_width,
-                points[i].y + n1.y * half_width,
-            ));
-            right_side.push(Point::new(
-                points[i].x - n1.x * half_width,
-                points[i].y - n1.y * half_width,
-            ));
-        }
+    let n = points.len();
+    let segment_count = if contour.closed { n } else { n - 1 };
+    let directions: Vec<Point> = (0..segment_count)
+        .map(|i| unit(points[(i + 1) % n] - points[i]))
+        .collect();
+
+    for (i, &dir) in directions.iter().enumerate() {
+        let offset = normal(dir) * half_width;
+        let (p0, p1) = (points[i], points[(i + 1) % n]);
+        add_polygon(
+            builder,
+            &[p0 + offset, p1 + offset, p1 - offset, p0 - offset],
+        );
     }
 
-    // Last point
-    let last_normal = normals[normals.len() - 1];
-    left_side.push(Point::new(
-        points[n - 1].x + last_normal.x * half_width,
-        points[n - 1].y + last_normal.y * half_width,
-    ));
-    right_side.push(Point::new(
-        points[n - 1].x - last_normal.x * half_width,
-        points[n - 1].y - last_normal.y * half_width,
-    ));
-
-    // Build the outline path
-    if is_closed {
-        // For closed paths, connect left to right
-        if !left_side.is_empty() {
-            builder.move_to(left_side[0].x, left_side[0].y);
-            for p in &left_side[1..] {
-                builder.line_to(p.x, p.y);
-            }
-            builder.close();
-        }
-        if !right_side.is_empty() {
-            builder.move_to(right_side[0].x, right_side[0].y);
-            for p in &right_side[1..] {
-                builder.line_to(p.x, p.y);
-            }
-            builder.close();
+    if contour.closed {
+        for i in 0..n {
+            let incoming = directions[(i + n - 1) % n];
+            add_join(
+                builder,
+                points[i],
+                incoming,
+                directions[i],
+                half_width,
+                params,
+                corners[i],
+            );
         }
     } else {
-        // For open paths, create a single outline with caps
-        if !left_side.is_empty() {
-            builder.move_to(left_side[0].x, left_side[0].y);
-
-            // Add start cap
-            add_cap(builder, points[0], normals[0], half_width, params.cap, true);
-
-            // Left side (forward)
-            for p in &left_side {
-                builder.line_to(p.x, p.y);
-            }
-
-            // Add end cap
-            add_cap(
+        for i in 1..n - 1 {
+            add_join(
                 builder,
-                points[n - 1],
-                normals[normals.len() - 1],
+                points[i],
+                directions[i - 1],
+                directions[i],
                 half_width,
-                params.cap,
-                false,
+                params,
+                corners[i],
             );
+        }
+        add_cap(builder, points[0], -directions[0], half_width, params.cap);
+        add_cap(
+            builder,
+            points[n - 1],
+            directions[n - 2],
+            half_width,
+            params.cap,
+        );
+    }
+}
 
-            // Right side (reverse)
-            for p in right_side.iter().rev() {
-                builder.line_to(p.x, p.y);
-            }
+/// Add the outer wedge joining two segments that meet at `center`.
+///
+/// Only a `corner`, where two of the path's own segments meet, gets the
+/// requested join. Vertices inside a flattened curve turn by less than the
+/// flattening tolerance allows, so a bevel closes their gap.
+fn add_join(
+    builder: &mut PathBuilder,
+    center: Point,
+    incoming: Point,
+    outgoing: Point,
+    half_width: Scalar,
+    params: &StrokeParams,
+    corner: bool,
+) {
+    let cross = incoming.cross(&outgoing);
+    if cross.abs() <= Scalar::EPSILON && incoming.dot(&outgoing) > 0.0 {
+        return;
+    }
 
-            builder.close();
+    // The gap opens on the side away from the turn.
+    let side = if cross > 0.0 { -half_width } else { half_width };
+    let n1 = normal(incoming);
+    let n2 = normal(outgoing);
+    let outer1 = center + n1 * side;
+    let outer2 = center + n2 * side;
+
+    if corner && params.join == StrokeJoin::Round {
+        // Sweep from `outer1` to `outer2` the way the outer side turns; a
+        // full reversal sweeps around the front of the incoming segment.
+        let sweep = cross.atan2(incoming.dot(&outgoing)).abs();
+        let sweep = if cross > 0.0 { sweep } else { -sweep };
+        let steps = arc_step_angle(half_width)
+            .map_or(1, |step| (sweep.abs() / step).ceil() as usize)
+            .clamp(1, MAX_FLATTEN_STEPS);
+        let start = (n1.y * side).atan2(n1.x * side);
+        let mut wedge = Vec::with_capacity(steps + 2);
+        wedge.push(center);
+        wedge.push(outer1);
+        for i in 1..steps {
+            let angle = start + sweep * i as Scalar / steps as Scalar;
+            wedge.push(Point::new(
+                center.x + half_width * angle.cos(),
+                center.y + half_width * angle.sin(),
+            ));
         }
+        wedge.push(outer2);
+        add_polygon(builder, &wedge);
+        return;
     }
+
+    if corner && params.join == StrokeJoin::Miter {
+        // The miter length over the stroke width is 1 / cos(theta / 2),
+        // where |n1 + n2| = 2 cos(theta / 2).
+        let bisector = n1 + n2;
+        let len_sq = bisector.length_squared();
+        if len_sq > 0.0 && 2.0 / len_sq.sqrt() <= params.miter_limit {
+            let tip = center + bisector * (2.0 * side / len_sq);
+            add_polygon(builder, &[center, outer1, tip, outer2]);
+            return;
+        }
+    }
+
+    add_polygon(builder, &[center, outer1, outer2]);
 }
 
+/// Add the cap at an open contour's end; `dir` points away from the stroke.
 fn add_cap(
     builder: &mut PathBuilder,
     center: Point,
-    normal: Point,
+    dir: Point,
     half_width: Scalar,
     cap: StrokeCap,
-    is_start: bool,
 ) {
     match cap {
-        StrokeCap::Butt => {
-            // No extension
-        }
+        StrokeCap::Butt => {}
         StrokeCap::Square => {
-            // Extend by half_width in the direction perpendicular to normal
-            let dir = if is_start {
-                Point::new(-normal.y, normal.x)
-            } else {
-                Point::new(normal.y, -normal.x)
-            };
-            let ext = Point::new(dir.x * half_width, dir.y * half_width);
-            builder.line_to(
-                center.x + normal.x * half_width + ext.x,
-                center.y + normal.y * half_width + ext.y,
+            let offset = normal(dir) * half_width;
+            let ext = dir * half_width;
+            add_polygon(
+                builder,
+                &[
+                    center + offset,
+                    center + offset + ext,
+                    center - offset + ext,
+                    center - offset,
+                ],
             );
-            builder.line_to(
-                center.x - normal.x * half_width + ext.x,
-                center.y - normal.y * half_width + ext.y,
+        }
+        StrokeCap::Round => add_polygon(builder, &circle_points(center, half_width)),
+    }
+}
+
+/// Add the mark left by a zero-length contour.
+fn add_dot(builder: &mut PathBuilder, center: Point, half_width: Scalar, cap: StrokeCap) {
+    match cap {
+        StrokeCap::Butt => {}
+        StrokeCap::Square => {
+            let (l, t) = (center.x - half_width, center.y - half_width);
+            let (r, b) = (center.x + half_width, center.y + half_width);
+            add_polygon(
+                builder,
+                &[
+                    Point::new(l, t),
+                    Point::new(r, t),
+                    Point::new(r, b),
+                    Point::new(l, b),
+                ],
             );
         }
-        StrokeCap::Round => {
-            // Approximate semicircle with line segments
-            let steps = 8;
-            let start_angle = if is_start {
-                normal.y.atan2(normal.x)
-            } else {
-                (-normal.y).atan2(-normal.x)
-            };
-
-            for i in 0..=steps {
-                let t = i as Scalar / steps as Scalar;
-                let angle = start_angle + t * std::f32::consts::PI;
-                let x = center.x + angle.cos() * half_width;
-                let y = center.y + angle.sin() * half_width;
-                builder.line_to(x, y);
-            }
+        StrokeCap::Round => add_polygon(builder, &circle_points(center, half_width)),
+    }
+}
+
+/// Add a closed polygon, reversed if needed so every piece winds the same way.
+fn add_polygon(builder: &mut PathBuilder, points: &[Point]) {
+    let n = points.len();
+    let area: Scalar = (0..n).map(|i| points[i].cross(&points[(i + 1) % n])).sum();
+    if area == 0.0 {
+        return;
+    }
+
+    let at = |i: usize| {
+        if area > 0.0 {
+            points[i]
+        } else {
+            points[n - 1 - i]
         }
+    };
+    builder.move_to(at(0).x, at(0).y);
+    for i in 1..n {
+        builder.line_to(at(i).x, at(i).y);
     }
+    builder.close();
+}
+
+/// Flatten a circle to within [`FLATTEN_TOLERANCE`] of the true arc.
+///
+/// The step count is a multiple of four so the extremes land on the circle.
+fn circle_points(center: Point, radius: Scalar) -> Vec<Point> {
+    let steps = arc_step_angle(radius).map_or(8, |step_angle| {
+        ((core::f32::consts::TAU / step_angle).ceil() as usize)
+            .next_multiple_of(4)
+            .clamp(8, MAX_FLATTEN_STEPS)
+    });
+
+    (0..steps)
+        .map(|i| {
+            let angle = i as Scalar / steps as Scalar * core::f32::consts::TAU;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+fn unit(v: Point) -> Point {
+    let len = v.length();
+    Point::new(v.x / len, v.y / len)
+}
+
+fn normal(dir: Point) -> Point {
+    Point::new(-dir.y, dir.x)
 }
 
 fn flatten_quad(points: &mut Vec<Point>, p0: Point, p1: Point, p2: Point, steps: usize) {
@@ -417,6 +624,25 @@ fn flatten_quad(points: &mut Vec<Point>, p0: Point, p1: Point, p2: Point, steps:
     }
 }
 
+fn flatten_conic(
+    points: &mut Vec<Point>,
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    weight: Scalar,
+    steps: usize,
+) {
+    for i in 1..=steps {
+        let t = i as Scalar / steps as Scalar;
+        let mt = 1.0 - t;
+        let (a, b, c) = (mt * mt, 2.0 * weight * mt * t, t * t);
+        let denom = a + b + c;
+        let x = (a * p0.x + b * p1.x + c * p2.x) / denom;
+        let y = (a * p0.y + b * p1.y + c * p2.y) / denom;
+        points.push(Point::new(x, y));
+    }
+}
+
 fn flatten_cubic(
     points: &mut Vec<Point>,
     p0: Point,
@@ -439,6 +665,60 @@ fn flatten_cubic(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use skia_rs_core::Rect;
+
+    #[test]
+    fn test_conic_to_cubics_quarter_circle() {
+        let r = 100.0;
+        let cubics = conic_to_cubics(
+            Point::new(r, 0.0),
+            Point::new(r, r),
+            Point::new(0.0, r),
+            core::f32::consts::FRAC_1_SQRT_2,
+        );
+        assert!(!cubics.is_empty());
+
+        let mut start = Point::new(r, 0.0);
+        for &[c1, c2, end] in &cubics {
+            assert!((end.length() - r).abs() < 1e-3, "{end:?} is off the arc");
+            // Sample each cubic too; all of it should hug the circle.
+            for i in 1..8 {
+                let t = i as Scalar / 8.0;
+                let p = start
+                    .lerp(c1, t)
+                    .lerp(c1.lerp(c2, t), t)
+                    .lerp(c1.lerp(c2, t).lerp(c2.lerp(end, t), t), t);
+                assert!((p.length() - r).abs() < 0.02, "{p:?} at t = {t}");
+            }
+            start = end;
+        }
+        assert_eq!(start, Point::new(0.0, r));
+
+        // A parabola (w = 1) is a single exact cubic; a non-positive weight
+        // is a line.
+        assert_eq!(
+            conic_to_cubics(
+                Point::zero(),
+                Point::new(3.0, 3.0),
+                Point::new(6.0, 0.0),
+                1.0
+            ),
+            vec![[
+                Point::new(2.0, 2.0),
+                Point::new(4.0, 2.0),
+                Point::new(6.0, 0.0)
+            ]]
+        );
+        let line = conic_to_cubics(
+            Point::zero(),
+            Point::new(3.0, 3.0),
+            Point::new(6.0, 0.0),
+            0.0,
+        );
+        assert_eq!(line.len(), 1);
+        assert_eq!(line[0][2], Point::new(6.0, 0.0));
+        assert!(line[0][0].y.abs() < 1e-6 && line[0][1].y.abs() < 1e-6);
+    }
 
     #[test]
     fn test_stroke_to_fill_line() {
@@ -468,6 +748,195 @@ mod tests {
         assert!(!stroked.is_empty());
     }
 
+    fn line(x0: Scalar, y0: Scalar, x1: Scalar, y1: Scalar) -> Path {
+        let mut builder = PathBuilder::new();
+        builder.move_to(x0, y0).line_to(x1, y1);
+        builder.build()
+    }
+
+    #[test]
+    fn test_stroke_to_fill_caps() {
+        let path = line(0.0, 0.0, 100.0, 0.0);
+
+        let butt = stroke_to_fill(&path, &StrokeParams::new(10.0)).unwrap();
+        assert_eq!(butt.fill_type(), FillType::Winding);
+        assert_eq!(butt.bounds(), Rect::new(0.0, -5.0, 100.0, 5.0));
+
+        let square = StrokeParams::new(10.0).with_cap(StrokeCap::Square);
+        let square = stroke_to_fill(&path, &square).unwrap();
+        assert_eq!(square.bounds(), Rect::new(-5.0, -5.0, 105.0, 5.0));
+
+        let round = StrokeParams::new(10.0).with_cap(StrokeCap::Round);
+        let bounds = stroke_to_fill(&path, &round).unwrap().bounds();
+        assert!((bounds.left + 5.0).abs() < 0.01 && (bounds.right - 105.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_stroke_to_fill_zero_length_dot() {
+        let path = line(20.0, 20.0, 20.0, 20.0);
+
+        let butt = stroke_to_fill(&path, &StrokeParams::new(8.0)).unwrap();
+        assert!(butt.is_empty());
+
+        let round = StrokeParams::new(8.0).with_cap(StrokeCap::Round);
+        let bounds = stroke_to_fill(&path, &round).unwrap().bounds();
+        assert!((bounds.width() - 8.0).abs() < 0.01 && (bounds.height() - 8.0).abs() < 0.01);
+
+        let square = StrokeParams::new(8.0).with_cap(StrokeCap::Square);
+        let square = stroke_to_fill(&path, &square).unwrap();
+        assert_eq!(square.bounds(), Rect::new(16.0, 16.0, 24.0, 24.0));
+
+        // A lone move has nothing to cap.
+        let mut builder = PathBuilder::new();
+        builder.move_to(5.0, 5.0);
+        assert!(stroke_to_fill(&builder.build(), &round).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stroke_to_fill_miter_limit() {
+        // A 90 degree corner has a miter ratio of sqrt(2).
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(50.0, 0.0)
+            .line_to(50.0, 50.0);
+        let path = builder.build();
+
+        let miter = StrokeParams::new(10.0).with_miter_limit(1.5);
+        let bounds = stroke_to_fill(&path, &miter).unwrap().bounds();
+        assert_eq!((bounds.right, bounds.top), (55.0, -5.0));
+
+        // Over the limit the corner is beveled and never reaches (55, -5).
+        let beveled = StrokeParams::new(10.0).with_miter_limit(1.4);
+        let beveled = stroke_to_fill(&path, &beveled).unwrap();
+        assert_eq!(beveled.bounds(), bounds);
+        assert!(
+            !beveled
+                .points()
+                .iter()
+                .any(|p| *p == Point::new(55.0, -5.0))
+        );
+        let mitered = stroke_to_fill(&path, &miter).unwrap();
+        assert!(
+            mitered
+                .points()
+                .iter()
+                .any(|p| *p == Point::new(55.0, -5.0))
+        );
+    }
+
+    #[test]
+    fn test_stroke_to_fill_closed_contours() {
+        // Each closed contour gets its own joins, including at its start.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(20.0, 0.0)
+            .line_to(20.0, 20.0)
+            .line_to(0.0, 20.0)
+            .close()
+            .move_to(40.0, 0.0)
+            .line_to(60.0, 0.0);
+        let stroked = stroke_to_fill(&builder.build(), &StrokeParams::new(4.0)).unwrap();
+        assert!(
+            stroked
+                .points()
+                .iter()
+                .any(|p| *p == Point::new(-2.0, -2.0))
+        );
+        assert_eq!(stroked.bounds(), Rect::new(-2.0, -2.0, 60.0, 22.0));
+    }
+
+    #[test]
+    fn test_stroke_to_fill_dashed_circle() {
+        // A circumference of 120 fits the pattern six times over. The phase
+        // keeps dash ends off the joins between the circle's segments.
+        let radius = 60.0 / core::f32::consts::PI;
+        let mut builder = PathBuilder::new();
+        builder.add_circle(0.0, 0.0, radius);
+        let circle = builder.build();
+        let params = StrokeParams::new(4.0).with_dash(&[10.0, 10.0], 15.0);
+        let stroked = stroke_to_fill(&circle, &params).unwrap();
+
+        assert_eq!(stroked.simplify().contour_count(), 6);
+        let on_circle = |distance: Scalar| {
+            let angle = distance / radius;
+            Point::new(radius * angle.cos(), radius * angle.sin())
+        };
+        for dash in 0..6 {
+            let start = dash as Scalar * 20.0;
+            assert!(stroked.contains(on_circle(start + 10.0)), "dash {dash}");
+            assert!(!stroked.contains(on_circle(start)), "gap {dash}");
+        }
+    }
+
+    #[test]
+    fn test_stroke_to_fill_dashed_caps() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(0.0, 0.0).line_to(50.0, 0.0);
+        let line = builder.build();
+        let params = StrokeParams::new(4.0)
+            .with_cap(StrokeCap::Square)
+            .with_dash(&[10.0, 5.0], 0.0);
+        let stroked = stroke_to_fill(&line, &params).unwrap();
+
+        // Square caps stretch each dash by 2 on both ends, leaving 1 wide gaps.
+        assert_eq!(stroked.simplify().contour_count(), 4);
+        assert_eq!(stroked.bounds(), Rect::new(-2.0, -2.0, 52.0, 2.0));
+        assert!(stroked.contains(Point::new(11.5, 0.0)));
+        assert!(!stroked.contains(Point::new(12.5, 0.0)));
+        assert!(stroked.contains(Point::new(13.5, 0.0)));
+
+        // A pattern that can't dash leaves the stroke solid
+        let solid = StrokeParams::new(4.0).with_dash(&[0.0, 0.0], 0.0);
+        let stroked = stroke_to_fill(&line, &solid).unwrap();
+        assert!(stroked.contains(Point::new(12.5, 0.0)));
+    }
+
+    #[test]
+    fn test_stroke_to_fill_large_circle_tolerance() {
+        // Sixteen steps per quarter would cut 0.24 units off this circle.
+        let mut builder = PathBuilder::new();
+        builder.add_circle(0.0, 0.0, 200.0);
+        let params = StrokeParams::new(4.0).with_join(StrokeJoin::Round);
+        let stroked = stroke_to_fill(&builder.build(), &params).unwrap();
+
+        for step in 0..360 {
+            let angle = (step as Scalar + 0.5).to_radians();
+            let at = |radius: Scalar| Point::new(radius * angle.cos(), radius * angle.sin());
+            assert!(stroked.contains(at(201.85)), "outer edge at {step}");
+            assert!(stroked.contains(at(198.15)), "inner edge at {step}");
+            assert!(!stroked.contains(at(202.1)), "outside at {step}");
+        }
+    }
+
+    #[test]
+    fn test_stroke_to_fill_round_joins_at_segment_ends() {
+        // Within a curve, round joins add no more geometry than bevels.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .cubic_to(0.0, 100.0, 100.0, 100.0, 100.0, 0.0);
+        let curve = builder.build();
+        let round = StrokeParams::new(10.0).with_join(StrokeJoin::Round);
+        let bevel = StrokeParams::new(10.0).with_join(StrokeJoin::Bevel);
+        let round = stroke_to_fill(&curve, &round).unwrap();
+        let bevel = stroke_to_fill(&curve, &bevel).unwrap();
+        assert_eq!(round.points().len(), bevel.points().len());
+
+        // The corner between two lines gets a rounded wedge.
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(0.0, 0.0)
+            .line_to(50.0, 0.0)
+            .line_to(50.0, 50.0);
+        let params = StrokeParams::new(10.0).with_join(StrokeJoin::Round);
+        let stroked = stroke_to_fill(&builder.build(), &params).unwrap();
+        let diagonal = 5.0 * core::f32::consts::FRAC_1_SQRT_2 - 0.1;
+        assert!(stroked.contains(Point::new(50.0 + diagonal, -diagonal)));
+        assert!(!stroked.contains(Point::new(54.5, -4.5)));
+    }
+
     #[test]
     fn test_stroke_params() {
         let params = StrokeParams::new(2.0)
@@ -479,5 +948,6 @@ mod tests {
         assert_eq!(params.cap, StrokeCap::Round);
         assert_eq!(params.join, StrokeJoin::Bevel);
         assert_eq!(params.miter_limit, 10.0);
+        assert!(params.dash.is_none());
     }
 }
@@ -1,8 +1,13 @@
-//! SVG path data parsing.
+//! SVG path data parsing and serialization.
 //!
-//! Parses SVG path `d` attribute strings into a `Path`.
-
-use crate::{Path, PathBuilder};
+//! Parses SVG path `d` attribute strings into a `Path` and writes paths back
+//! out as path data.
+
+use crate::{Path, PathBuilder, PathElement, conic_to_cubics};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use skia_rs_core::Scalar;
 
 /// Parse an SVG path data string.
@@ -19,6 +24,75 @@ pub fn parse_svg_path(d: &str) -> Result<Path, SvgPathError> {
     parser.parse()
 }
 
+/// Write a path as an SVG path data string.
+///
+/// Coordinates are rounded to `precision` decimal places with trailing zeros
+/// dropped. SVG has no rational curves, so conics are written as the cubics
+/// from [`conic_to_cubics`].
+pub(crate) fn write_svg_path(path: &Path, precision: usize) -> String {
+    let mut data = String::new();
+    let mut current = skia_rs_core::Point::zero();
+    let mut contour_start = current;
+
+    let command = |data: &mut String, verb: char, coords: &[Scalar]| {
+        data.push(verb);
+        for (i, &value) in coords.iter().enumerate() {
+            if i > 0 {
+                data.push(' ');
+            }
+            write_scalar(data, value, precision);
+        }
+    };
+
+    for element in path.iter() {
+        match element {
+            PathElement::Move(p) => {
+                command(&mut data, 'M', &[p.x, p.y]);
+                current = p;
+                contour_start = p;
+            }
+            PathElement::Line(p) => {
+                command(&mut data, 'L', &[p.x, p.y]);
+                current = p;
+            }
+            PathElement::Quad(p1, p2) => {
+                command(&mut data, 'Q', &[p1.x, p1.y, p2.x, p2.y]);
+                current = p2;
+            }
+            PathElement::Conic(p1, p2, w) => {
+                for [c1, c2, p] in conic_to_cubics(current, p1, p2, w) {
+                    command(&mut data, 'C', &[c1.x, c1.y, c2.x, c2.y, p.x, p.y]);
+                }
+                current = p2;
+            }
+            PathElement::Cubic(p1, p2, p3) => {
+                command(&mut data, 'C', &[p1.x, p1.y, p2.x, p2.y, p3.x, p3.y]);
+                current = p3;
+            }
+            PathElement::Close => {
+                data.push('Z');
+                current = contour_start;
+            }
+        }
+    }
+
+    data
+}
+
+fn write_scalar(data: &mut String, value: Scalar, precision: usize) {
+    let formatted = format!("{:.prec$}", value, prec = precision);
+    // Remove trailing zeros and decimal point if unnecessary
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    match trimmed {
+        "" | "-0" => data.push('0'),
+        _ => data.push_str(trimmed),
+    }
+}
+
 /// Error type for SVG path parsing.
 #[derive(Debug, Clone, PartialEq)]
 pub enum SvgPathError {
@@ -34,8 +108,8 @@ pub enum SvgPathError {
     MissingMoveTo,
 }
 
-impl std::fmt::Display for SvgPathError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             SvgPathError::UnexpectedEnd => write!(f, "unexpected end of path data"),
             SvgPathError::InvalidNumber(s) => write!(f, "invalid number: {}", s),
@@ -46,7 +120,7 @@ impl std::fmt::Display for SvgPathError {
     }
 }
 
-impl std::error::Error for SvgPathError {}
+impl core::error::Error for SvgPathError {}
 
 struct SvgPathParser<'a> {
     input: &'a str,
@@ -438,6 +512,7 @@ impl<'a> SvgPathParser<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use skia_rs_core::Point;
 
     #[test]
     fn test_parse_simple_path() {
@@ -457,12 +532,115 @@ mod tests {
         assert_eq!(path.verb_count(), 2); // Move, Cubic
     }
 
+    fn assert_bounds(path: &Path, expected: [Scalar; 4]) {
+        let b = path.tight_bounds();
+        let actual = [b.left, b.top, b.right, b.bottom];
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!(
+                (a - e).abs() < 1e-3,
+                "bounds {actual:?}, expected {expected:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_parse_arc() {
         let path = parse_svg_path("M 0 0 A 50 50 0 0 1 100 0").unwrap();
         assert!(!path.is_empty());
     }
 
+    #[test]
+    fn test_parse_arc_semicircle() {
+        // Sweep flag 1 runs through increasing angles, which is upward in a
+        // y-down coordinate system.
+        let path = parse_svg_path("M0,0 A50,50 0 0 1 100,0").unwrap();
+        assert_bounds(&path, [0.0, -50.0, 100.0, 0.0]);
+        assert_eq!(path.last_point(), Some(Point::new(100.0, 0.0)));
+        let mid = path.iter().nth(1);
+        assert!(
+            matches!(mid, Some(PathElement::Conic(_, p, w)) if (p.x - 50.0).abs() < 1e-3
+                && (p.y + 50.0).abs() < 1e-3
+                && (w - core::f32::consts::FRAC_1_SQRT_2).abs() < 1e-5),
+            "{mid:?}"
+        );
+
+        let path = parse_svg_path("M0,0 A50,50 0 0 0 100,0").unwrap();
+        assert_bounds(&path, [0.0, 0.0, 100.0, 50.0]);
+    }
+
+    #[test]
+    fn test_parse_arc_flags() {
+        // Large arc on a radius that fits the chord twice over, with packed
+        // flags and a relative endpoint.
+        let path = parse_svg_path("M0,0 a100,100 0 1150,0").unwrap();
+        let b = path.tight_bounds();
+        assert!((b.top + 196.825).abs() < 1e-2, "{b:?}");
+        assert!(b.bottom.abs() < 1e-3, "{b:?}");
+
+        // A half ellipse rotated a quarter turn
+        let path = parse_svg_path("M0,0 A50,20 90 0 1 0,100").unwrap();
+        assert_bounds(&path, [0.0, 0.0, 20.0, 100.0]);
+    }
+
+    #[test]
+    fn test_parse_arc_degenerate() {
+        // Radii too small are scaled up to the semicircle
+        let path = parse_svg_path("M0,0 A10,10 0 0 1 100,0").unwrap();
+        assert_bounds(&path, [0.0, -50.0, 100.0, 0.0]);
+
+        // Zero radius is a straight line
+        let path = parse_svg_path("M0,0 A0,50 0 0 1 100,0").unwrap();
+        assert_eq!(path.verb_count(), 2);
+        assert_eq!(
+            path.iter().nth(1),
+            Some(PathElement::Line(Point::new(100.0, 0.0)))
+        );
+
+        // Equal endpoints draw nothing
+        let path = parse_svg_path("M10,10 A50,50 0 1 1 10,10").unwrap();
+        assert_eq!(path.verb_count(), 1);
+    }
+
+    #[test]
+    fn test_svg_string_round_trip() {
+        let mut builder = PathBuilder::new();
+        builder
+            .move_to(10.0, 20.0)
+            .line_to(110.5, 20.0)
+            .quad_to(150.0, 60.0, 110.5, 100.25)
+            .cubic_to(80.0, 140.0, 40.0, 90.0, 10.0, 100.0)
+            .close()
+            .move_to(-5.0, -5.0)
+            .line_to(-1.0, -5.0);
+        let path = builder.build();
+
+        let data = path.to_svg_string();
+        assert_eq!(
+            data,
+            "M10 20L110.5 20Q150 60 110.5 100.25C80 140 40 90 10 100ZM-5 -5L-1 -5"
+        );
+        let parsed = parse_svg_path(&data).unwrap();
+        assert_eq!(parsed.verbs(), path.verbs());
+        assert_eq!(parsed.bounds(), path.bounds());
+    }
+
+    #[test]
+    fn test_svg_string_precision_and_conics() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(0.0, 0.0).line_to(1.0 / 3.0, -0.0001);
+        let path = builder.build();
+        assert_eq!(path.to_svg_string_with_precision(2), "M0 0L0.33 0");
+        assert_eq!(path.to_svg_string_with_precision(0), "M0 0L0 0");
+
+        // Conics come back as cubics tracing the same curve
+        let circle = parse_svg_path("M10,50 A40,40 0 1 1 90,50 A40,40 0 1 1 10,50Z").unwrap();
+        assert!(circle.iter().any(|e| matches!(e, PathElement::Conic(..))));
+        let data = circle.to_svg_string();
+        assert!(!data.contains('A') && !data.contains('Q'), "{data}");
+        let parsed = parse_svg_path(&data).unwrap();
+        assert_bounds(&parsed, [10.0, 10.0, 90.0, 90.0]);
+    }
+
     #[test]
     fn test_parse_horizontal_vertical() {
         let path = parse_svg_path("M 0 0 H 100 V 100 H 0 Z").unwrap();
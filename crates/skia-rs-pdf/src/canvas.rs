@@ -1,8 +1,16 @@
 //! PDF canvas for drawing.
 
-use skia_rs_core::{Color, Matrix, Point, Rect, Scalar};
+use crate::font::{PdfFontManager, StandardFont};
+use crate::image::PdfImageManager;
+use skia_rs_codec::{Image, ImageFormat};
+use skia_rs_core::{AlphaType, Color, Matrix, Point, Rect, Scalar};
 use skia_rs_paint::{Paint, Style};
-use skia_rs_path::{Path, PathElement};
+use skia_rs_path::{Path, PathElement, conic_to_cubics};
+use skia_rs_text::{Font, Shaper};
+use std::collections::{HashMap, HashSet};
+
+/// Opaque images with more distinct colors than this are embedded as JPEG.
+const PHOTO_MIN_COLORS: usize = 256;
 
 /// A canvas that generates PDF content streams.
 pub struct PdfCanvas {
@@ -16,6 +24,24 @@ pub struct PdfCanvas {
     content: Vec<u8>,
     /// Graphics state stack.
     state_stack: Vec<GraphicsState>,
+    /// Images drawn on the page, including their soft masks.
+    images: PdfImageManager,
+    /// Image XObjects, named `/Im<index>` in the content stream.
+    xobjects: Vec<PdfImageXObject>,
+    /// Each drawn image and its XObject index, by the image's unique ID.
+    /// Holding the image keeps its ID from being reused by a later one.
+    image_xobjects: HashMap<usize, (Image, usize)>,
+    /// Fonts used on the page, named `/Font<index>` in the content stream.
+    fonts: PdfFontManager,
+}
+
+/// An image XObject drawn on a page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdfImageXObject {
+    /// Index of the image in the page's image manager.
+    pub image: usize,
+    /// Index of the image's soft mask, if it has transparency.
+    pub soft_mask: Option<usize>,
 }
 
 /// Graphics state.
@@ -48,6 +74,10 @@ impl PdfCanvas {
             object_id,
             content: Vec::new(),
             state_stack: vec![GraphicsState::default()],
+            images: PdfImageManager::new(),
+            xobjects: Vec::new(),
+            image_xobjects: HashMap::new(),
+            fonts: PdfFontManager::new(),
         };
 
         // Set up coordinate system (PDF has origin at bottom-left)
@@ -76,6 +106,31 @@ impl PdfCanvas {
         self.content
     }
 
+    /// Get the fonts available to the page.
+    pub fn fonts(&self) -> &PdfFontManager {
+        &self.fonts
+    }
+
+    /// Get the fonts available to the page, to register or embed more.
+    pub fn fonts_mut(&mut self) -> &mut PdfFontManager {
+        &mut self.fonts
+    }
+
+    /// Take the fonts registered so far.
+    pub fn take_fonts(&mut self) -> PdfFontManager {
+        self.fonts.update_subsets();
+        std::mem::take(&mut self.fonts)
+    }
+
+    /// Take the images drawn so far, along with the XObjects that use them.
+    pub fn take_images(&mut self) -> (PdfImageManager, Vec<PdfImageXObject>) {
+        self.image_xobjects.clear();
+        (
+            std::mem::take(&mut self.images),
+            std::mem::take(&mut self.xobjects),
+        )
+    }
+
     /// Write a PDF operation.
     fn write_op(&mut self, op: &str) {
         self.content.extend_from_slice(op.as_bytes());
@@ -259,20 +314,14 @@ impl PdfCanvas {
                     ));
                     current = end;
                 }
-                PathElement::Conic(ctrl, end, _w) => {
-                    // Approximate as quadratic
-                    let c1 = Point::new(
-                        current.x + 2.0 / 3.0 * (ctrl.x - current.x),
-                        current.y + 2.0 / 3.0 * (ctrl.y - current.y),
-                    );
-                    let c2 = Point::new(
-                        end.x + 2.0 / 3.0 * (ctrl.x - end.x),
-                        end.y + 2.0 / 3.0 * (ctrl.y - end.y),
-                    );
-                    self.write_op(&format!(
-                        "{} {} {} {} {} {} c\n",
-                        c1.x, c1.y, c2.x, c2.y, end.x, end.y
-                    ));
+                PathElement::Conic(ctrl, end, w) => {
+                    // PDF has no rational curves; emit a cubic chain instead
+                    for [c1, c2, p] in conic_to_cubics(current, ctrl, end, w) {
+                        self.write_op(&format!(
+                            "{} {} {} {} {} {} c\n",
+                            c1.x, c1.y, c2.x, c2.y, p.x, p.y
+                        ));
+                    }
                     current = end;
                 }
                 PathElement::Cubic(c1, c2, end) => {
@@ -291,23 +340,147 @@ impl PdfCanvas {
         self.stroke_or_fill(paint);
     }
 
-    /// Draw text (basic support).
-    pub fn draw_text(
+    /// Draw text with its baseline starting at (x, y).
+    ///
+    /// The text is shaped with the font's typeface, and the glyphs drawn are
+    /// recorded with [`fonts_mut`](Self::fonts_mut) so the embedded subset
+    /// holds them. Typefaces without TrueType outlines fall back to the
+    /// standard Helvetica font.
+    pub fn draw_text(&mut self, text: &str, x: Scalar, y: Scalar, font: &Font, paint: &Paint) {
+        if text.is_empty() {
+            return;
+        }
+
+        let embedded = font.typeface().and_then(|typeface| {
+            let glyphs: Vec<u16> = match Shaper::new().shape_auto(text, font) {
+                Some(runs) => runs
+                    .iter()
+                    .flat_map(|run| run.glyphs.iter().map(|glyph| glyph.glyph_id.0))
+                    .collect(),
+                None => typeface.chars_to_glyphs(text),
+            };
+            let index = self.fonts.use_glyphs(typeface, glyphs.iter().copied())?;
+            Some((index, encode_glyphs(&glyphs)))
+        });
+        let (index, encoded) = embedded.unwrap_or_else(|| {
+            let index = self.fonts.register_standard(StandardFont::Helvetica);
+            (index, self.fonts.fonts()[index].encode_text(text))
+        });
+
+        self.apply_paint(paint);
+        self.write_op("BT\n");
+        self.write_op(&format!("/Font{} {} Tf\n", index, font.size()));
+        // Flip the text matrix back so glyphs are upright
+        self.write_op("1 0 0 -1 0 0 Tm\n");
+        self.write_op(&format!("{} {} Td\n", x, -y));
+        self.write_op(&format!("{} Tj\n", encoded));
+        self.write_op("ET\n");
+    }
+
+    /// Draw text in a font registered with [`fonts_mut`](Self::fonts_mut),
+    /// with its baseline starting at (x, y).
+    ///
+    /// Does nothing if there is no font at `font`.
+    pub fn draw_text_with_font(
         &mut self,
         text: &str,
         x: Scalar,
         y: Scalar,
+        font: usize,
         font_size: Scalar,
         paint: &Paint,
     ) {
+        let Some(encoded) = self.fonts.get(font).map(|f| f.encode_text(text)) else {
+            return;
+        };
+
         self.apply_paint(paint);
         self.write_op("BT\n");
-        self.write_op(&format!("/F1 {} Tf\n", font_size));
-        self.write_op(&format!("{} {} Td\n", x, y));
-        self.write_op(&format!("({}) Tj\n", escape_pdf_string(text)));
+        self.write_op(&format!("/Font{} {} Tf\n", font, font_size));
+        // Flip the text matrix back so glyphs are upright
+        self.write_op(&format!("1 0 0 -1 {} {} Tm\n", x, y));
+        self.write_op(&format!("{} Tj\n", encoded));
         self.write_op("ET\n");
     }
 
+    /// Draw an image scaled to fill a rectangle.
+    ///
+    /// Opaque photographic images are embedded as JPEG (DCTDecode), reusing
+    /// the original data when the image was decoded from a JPEG; others are
+    /// embedded losslessly (FlateDecode), with alpha in a soft mask. Drawing
+    /// the same image again reuses its XObject.
+    pub fn draw_image(&mut self, image: &Image, dst: &Rect) {
+        let index = match self.image_xobjects.get(&image.unique_id()) {
+            Some(&(_, index)) => index,
+            None => match self.add_image(image) {
+                Some(xobject) => {
+                    let index = self.xobjects.len();
+                    self.xobjects.push(xobject);
+                    self.image_xobjects
+                        .insert(image.unique_id(), (image.clone(), index));
+                    index
+                }
+                None => return,
+            },
+        };
+
+        // Map the unit square onto dst, flipping it since image space is
+        // bottom-up
+        self.write_op("q\n");
+        self.write_op(&format!(
+            "{} 0 0 {} {} {} cm\n",
+            dst.width(),
+            -dst.height(),
+            dst.left,
+            dst.bottom
+        ));
+        self.write_op(&format!("/Im{} Do\n", index));
+        self.write_op("Q\n");
+    }
+
+    /// Register an image with the page's image manager.
+    fn add_image(&mut self, image: &Image) -> Option<PdfImageXObject> {
+        let (width, height) = (image.width() as u32, image.height() as u32);
+
+        if image.is_opaque() && image.encoded_format() == Some(ImageFormat::Jpeg) {
+            if let Some(data) = image.ref_encoded_data() {
+                let index = self.images.add_jpeg(width, height, data.to_vec());
+                return Some(PdfImageXObject {
+                    image: index,
+                    soft_mask: None,
+                });
+            }
+        }
+
+        let rgba = image_to_rgba(image)?;
+        if rgba.chunks(4).all(|pixel| pixel[3] == 255) {
+            let rgb: Vec<u8> = rgba
+                .chunks(4)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect();
+            if is_photo(&rgb) {
+                if let Ok(jpeg) = image.encode(ImageFormat::Jpeg, None) {
+                    let index = self.images.add_jpeg(width, height, jpeg);
+                    return Some(PdfImageXObject {
+                        image: index,
+                        soft_mask: None,
+                    });
+                }
+            }
+            let index = self.images.add_rgb(width, height, &rgb);
+            return Some(PdfImageXObject {
+                image: index,
+                soft_mask: None,
+            });
+        }
+
+        let (index, mask) = self.images.add_rgba(width, height, &rgba);
+        Some(PdfImageXObject {
+            image: index,
+            soft_mask: Some(mask),
+        })
+    }
+
     /// Apply paint settings.
     fn apply_paint(&mut self, paint: &Paint) {
         let color = paint.color32();
@@ -336,18 +509,48 @@ impl PdfCanvas {
     }
 }
 
-/// Escape special characters in a PDF string.
-fn escape_pdf_string(s: &str) -> String {
-    let mut result = String::new();
-    for c in s.chars() {
-        match c {
-            '(' => result.push_str("\\("),
-            ')' => result.push_str("\\)"),
-            '\\' => result.push_str("\\\\"),
-            _ => result.push(c),
+/// Read an image as unpremultiplied RGBA8 pixels.
+fn image_to_rgba(image: &Image) -> Option<Vec<u8>> {
+    let (width, height) = image.dimensions();
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+    let premul = image.alpha_type() == AlphaType::Premul;
+
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let color = image.read_pixel(x, y)?;
+            let scale = if premul && color.a > 0.0 {
+                1.0 / color.a
+            } else {
+                1.0
+            };
+            for c in [color.r * scale, color.g * scale, color.b * scale, color.a] {
+                rgba.push((c.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
         }
     }
-    result
+    Some(rgba)
+}
+
+/// Whether RGB pixels have enough distinct colors to compress better as JPEG.
+fn is_photo(rgb: &[u8]) -> bool {
+    let mut colors = HashSet::new();
+    rgb.chunks(3).any(|pixel| {
+        colors.insert([pixel[0], pixel[1], pixel[2]]);
+        colors.len() > PHOTO_MIN_COLORS
+    })
+}
+
+/// Encode glyph IDs as a PDF hex string of 2-byte CIDs.
+fn encode_glyphs(glyphs: &[u16]) -> String {
+    let mut encoded = String::from("<");
+    for glyph in glyphs {
+        encoded.push_str(&format!("{:04X}", glyph));
+    }
+    encoded.push('>');
+    encoded
 }
 
 #[cfg(test)]
@@ -368,6 +571,106 @@ mod tests {
         assert!(content.contains("f")); // Fill operator
     }
 
+    #[test]
+    fn test_pdf_canvas_draw_image_filters() {
+        use crate::image::PdfImageFilter;
+        use skia_rs_codec::ImageInfo;
+        use skia_rs_core::ColorType;
+        use std::sync::Arc;
+
+        let info = ImageInfo::new(32, 32, ColorType::Rgba8888, AlphaType::Opaque);
+        let pixels: Vec<u8> = (0..32 * 32)
+            .flat_map(|i| [(i % 32 * 8) as u8, (i / 32 * 8) as u8, 128, 255])
+            .collect();
+        let photo = Image::from_raster_data(&info, &pixels, 32 * 4).unwrap();
+        let flat = Image::from_color(4, 4, 0xFF00FF00).unwrap();
+        let jpeg = photo.encode(ImageFormat::Jpeg, None).unwrap();
+        let encoded = Image::from_encoded(Arc::new(jpeg.clone())).unwrap();
+
+        let mut canvas = PdfCanvas::new(100.0, 100.0, 1);
+        let dst = Rect::from_xywh(0.0, 0.0, 10.0, 10.0);
+        canvas.draw_image(&photo, &dst);
+        canvas.draw_image(&flat, &dst);
+        canvas.draw_image(&encoded, &dst);
+        canvas.draw_image(&flat, &dst);
+
+        let (images, xobjects) = canvas.take_images();
+        // Drawing an image again reuses its XObject
+        assert_eq!(xobjects.len(), 3);
+        assert_eq!(images.len(), 3);
+        assert!(xobjects.iter().all(|xobject| xobject.soft_mask.is_none()));
+        assert_eq!(images.get(0).unwrap().filter, PdfImageFilter::DCTDecode);
+        assert_eq!(images.get(1).unwrap().filter, PdfImageFilter::FlateDecode);
+        // JPEG data is embedded as is
+        assert_eq!(images.get(2).unwrap().data, jpeg);
+
+        let content = String::from_utf8(canvas.into_content()).unwrap();
+        assert_eq!(content.matches("/Im1 Do").count(), 2);
+    }
+
+    #[test]
+    fn test_pdf_canvas_draw_temporary_images() {
+        // Each image is dropped right after drawing, freeing its address
+        let mut canvas = PdfCanvas::new(100.0, 100.0, 1);
+        let dst = Rect::from_xywh(0.0, 0.0, 10.0, 10.0);
+        for color in [0xFFFF0000, 0xFF00FF00, 0xFF0000FF] {
+            canvas.draw_image(&Image::from_color(2, 2, color).unwrap(), &dst);
+        }
+
+        let (images, xobjects) = canvas.take_images();
+        assert_eq!(xobjects.len(), 3);
+        assert_eq!(images.len(), 3);
+        let content = String::from_utf8(canvas.into_content()).unwrap();
+        for index in 0..3 {
+            assert_eq!(content.matches(&format!("/Im{index} Do")).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_pdf_canvas_draw_text_records_glyphs() {
+        use skia_rs_text::Typeface;
+        use std::sync::Arc;
+
+        let data = include_bytes!("../../skia-rs-text/testdata/Tuffy.ttf");
+        let typeface = Typeface::from_bytes(data.to_vec(), 0).unwrap();
+        let (h, i, x) = (
+            typeface.char_to_glyph('H'),
+            typeface.char_to_glyph('i'),
+            typeface.char_to_glyph('x'),
+        );
+        let font = Font::new(Arc::new(typeface), 24.0);
+
+        let mut canvas = PdfCanvas::new(200.0, 100.0, 1);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_rgb(255, 0, 0));
+        canvas.draw_text("Hi", 10.0, 50.0, &font, &paint);
+        canvas.draw_text("x", 10.0, 80.0, &font, &paint);
+
+        let fonts = canvas.take_fonts();
+        assert_eq!(fonts.len(), 1);
+        let embedded = fonts.get(0).unwrap();
+        assert_eq!(embedded.used_glyphs, vec![h, i, x]);
+        // The subset was rebuilt with the glyphs of the second string
+        assert_eq!(embedded.glyph_map.get(&'x'), Some(&x));
+
+        let content = String::from_utf8(canvas.into_content()).unwrap();
+        assert!(content.contains("1.000 0.000 0.000 rg\n"));
+        assert!(content.contains(&format!(
+            "BT\n/Font0 24 Tf\n1 0 0 -1 0 0 Tm\n10 -50 Td\n<{:04X}{:04X}> Tj\nET\n",
+            h, i
+        )));
+    }
+
+    #[test]
+    fn test_pdf_canvas_draw_text_standard_fallback() {
+        let mut canvas = PdfCanvas::new(200.0, 100.0, 1);
+        canvas.draw_text("Hi", 10.0, 50.0, &Font::from_size(12.0), &Paint::new());
+
+        assert_eq!(canvas.fonts().get(0).unwrap().base_font, "Helvetica");
+        let content = String::from_utf8(canvas.into_content()).unwrap();
+        assert!(content.contains("/Font0 12 Tf\n1 0 0 -1 0 0 Tm\n10 -50 Td\n<4869> Tj\n"));
+    }
+
     #[test]
     fn test_pdf_canvas_save_restore() {
         let mut canvas = PdfCanvas::new(612.0, 792.0, 1);
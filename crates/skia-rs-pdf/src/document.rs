@@ -1,7 +1,10 @@
 //! PDF document structure.
 
-use crate::canvas::PdfCanvas;
-use skia_rs_core::{Rect, Scalar};
+use crate::canvas::{PdfCanvas, PdfImageXObject};
+use crate::font::{PdfFont, PdfFontManager};
+use crate::image::{PdfImageManager, compress_flate};
+use crate::pdfa::{OutputIntent, PRODUCER, XmpMetadata};
+use skia_rs_core::{IccProfile, Rect, Scalar};
 use std::io::Write;
 
 /// PDF document metadata.
@@ -24,11 +27,19 @@ pub struct PdfMetadata {
 }
 
 /// PDF document builder.
+///
+/// Pages are drawn one at a time: [`begin_page`](Self::begin_page) returns
+/// the canvas for a new page, and [`end_page`](Self::end_page) adds it to the
+/// document.
 pub struct PdfDocument {
     /// Document metadata.
     metadata: PdfMetadata,
     /// Pages in the document.
     pages: Vec<PdfPage>,
+    /// The page being drawn.
+    current_page: Option<PdfCanvas>,
+    /// Intended output device, with its ICC profile.
+    output_intent: Option<OutputIntent>,
     /// Next object ID.
     next_object_id: u32,
 }
@@ -43,6 +54,12 @@ pub struct PdfPage {
     pub content: Vec<u8>,
     /// Object ID.
     pub object_id: u32,
+    /// Images drawn on the page.
+    pub images: PdfImageManager,
+    /// Image XObjects referenced from the content stream.
+    pub xobjects: Vec<PdfImageXObject>,
+    /// Fonts referenced from the content stream.
+    pub fonts: PdfFontManager,
 }
 
 impl Default for PdfDocument {
@@ -57,11 +74,16 @@ impl PdfDocument {
         Self {
             metadata: PdfMetadata::default(),
             pages: Vec::new(),
+            current_page: None,
+            output_intent: None,
             next_object_id: 1,
         }
     }
 
     /// Set the document metadata.
+    ///
+    /// Metadata is written both as the document information dictionary and
+    /// as an XMP metadata stream.
     pub fn set_metadata(&mut self, metadata: PdfMetadata) {
         self.metadata = metadata;
     }
@@ -71,6 +93,14 @@ impl PdfDocument {
         &mut self.metadata
     }
 
+    /// Set the output intent, embedding the ICC profile of the intended
+    /// output device.
+    ///
+    /// PDF/A requires an output intent for documents using device colors.
+    pub fn set_output_intent(&mut self, icc: IccProfile) {
+        self.output_intent = Some(OutputIntent::from_icc_profile(&icc));
+    }
+
     /// Allocate a new object ID.
     fn alloc_object_id(&mut self) -> u32 {
         let id = self.next_object_id;
@@ -78,17 +108,32 @@ impl PdfDocument {
         id
     }
 
-    /// Begin a new page.
-    pub fn begin_page(&mut self, width: Scalar, height: Scalar) -> PdfCanvas {
+    /// Begin a new page of the given size in points, ending the current
+    /// page if there is one.
+    pub fn begin_page(&mut self, width: Scalar, height: Scalar) -> &mut PdfCanvas {
+        self.end_page();
         let object_id = self.alloc_object_id();
-        PdfCanvas::new(width, height, object_id)
+        self.current_page
+            .insert(PdfCanvas::new(width, height, object_id))
+    }
+
+    /// Get the canvas of the page being drawn, if any.
+    pub fn current_page(&mut self) -> Option<&mut PdfCanvas> {
+        self.current_page.as_mut()
     }
 
     /// End the current page and add it to the document.
-    pub fn end_page(&mut self, canvas: PdfCanvas) {
+    ///
+    /// Does nothing if no page is being drawn.
+    pub fn end_page(&mut self) {
+        let Some(mut canvas) = self.current_page.take() else {
+            return;
+        };
         let width = canvas.width();
         let height = canvas.height();
         let object_id = canvas.object_id();
+        let (images, xobjects) = canvas.take_images();
+        let fonts = canvas.take_fonts();
         let content = canvas.into_content();
 
         let page = PdfPage {
@@ -96,16 +141,21 @@ impl PdfDocument {
             height,
             content,
             object_id,
+            images,
+            xobjects,
+            fonts,
         };
         self.pages.push(page);
     }
 
-    /// Get the number of pages.
+    /// Get the number of finished pages.
     pub fn page_count(&self) -> usize {
         self.pages.len()
     }
 
     /// Write the PDF to a writer.
+    ///
+    /// Only finished pages are written; see [`finish`](Self::finish).
     pub fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
         // PDF header
         writer.write_all(b"%PDF-1.4\n")?;
@@ -114,13 +164,45 @@ impl PdfDocument {
         let mut object_offsets: Vec<(u32, u64)> = Vec::new();
         let mut offset = 15u64; // Header size
 
+        // Document-level objects follow the pages and their resources
+        let resource_count: u32 = self
+            .pages
+            .iter()
+            .map(|page| {
+                page.images.len() as u32
+                    + page
+                        .fonts
+                        .fonts()
+                        .iter()
+                        .map(PdfFont::object_count)
+                        .sum::<u32>()
+            })
+            .sum();
+        let mut next_document_id = 3 + self.pages.len() as u32 * 2 + resource_count;
+        let mut alloc_document_id = || {
+            next_document_id += 1;
+            next_document_id - 1
+        };
+        let info_id = self.has_metadata().then(&mut alloc_document_id);
+        let metadata_id = self.has_metadata().then(&mut alloc_document_id);
+        let output_intent_id = self.output_intent.as_ref().map(|_| alloc_document_id());
+        let icc_profile = self.output_intent.as_ref().and_then(|intent| {
+            let components = intent.components()?;
+            Some((intent.icc_profile.as_deref()?, components))
+        });
+        let icc_profile_id = icc_profile.map(|_| alloc_document_id());
+
         // Write catalog
         let catalog_id = 1u32;
         object_offsets.push((catalog_id, offset));
-        let catalog = format!(
-            "{} 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n",
-            catalog_id
-        );
+        let mut catalog_entries = "/Type /Catalog /Pages 2 0 R".to_string();
+        if let Some(id) = metadata_id {
+            catalog_entries.push_str(&format!(" /Metadata {} 0 R", id));
+        }
+        if let Some(id) = output_intent_id {
+            catalog_entries.push_str(&format!(" /OutputIntents [{} 0 R]", id));
+        }
+        let catalog = format!("{} 0 obj\n<< {} >>\nendobj\n", catalog_id, catalog_entries);
         writer.write_all(catalog.as_bytes())?;
         offset += catalog.len() as u64;
 
@@ -144,16 +226,48 @@ impl PdfDocument {
         writer.write_all(pages.as_bytes())?;
         offset += pages.len() as u64;
 
+        // Images and fonts are numbered after the pages and their content
+        // streams
+        let mut next_id = 3 + self.pages.len() as u32 * 2;
+
         // Write each page
         for (i, page) in self.pages.iter().enumerate() {
             let page_id = 3 + i as u32 * 2;
             let content_id = page_id + 1;
+            let first_image_id = next_id;
+            next_id += page.images.len() as u32;
+            let mut font_ids = Vec::with_capacity(page.fonts.len());
+            for font in page.fonts.fonts() {
+                font_ids.push(next_id);
+                next_id += font.object_count();
+            }
 
             // Page object
             object_offsets.push((page_id, offset));
+            let xobjects: Vec<String> = page
+                .xobjects
+                .iter()
+                .enumerate()
+                .map(|(n, xobject)| {
+                    format!("/Im{} {} 0 R", n, first_image_id + xobject.image as u32)
+                })
+                .collect();
+            let fonts: Vec<String> = font_ids
+                .iter()
+                .enumerate()
+                .map(|(n, id)| format!("/Font{} {} 0 R", n, id))
+                .collect();
+            let mut resources = "<<".to_string();
+            if !xobjects.is_empty() {
+                resources.push_str(&format!(" /XObject << {} >>", xobjects.join(" ")));
+            }
+            if !fonts.is_empty() {
+                resources.push_str(&format!(" /Font << {} >>", fonts.join(" ")));
+            }
+            resources.push_str(" >>");
             let page_obj = format!(
-                "{} 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R /Resources << >> >>\nendobj\n",
-                page_id, page.width, page.height, content_id
+                "{} 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R /Resources {} >>\nendobj\n",
+                page_id, page.width, page.height, content_id, resources
             );
             writer.write_all(page_obj.as_bytes())?;
             offset += page_obj.len() as u64;
@@ -169,19 +283,98 @@ impl PdfDocument {
             writer.write_all(&page.content)?;
             writer.write_all(b"\nendstream\nendobj\n")?;
             offset += content_header.len() as u64 + page.content.len() as u64 + 18;
+
+            // Image XObjects, pointing at their soft masks
+            for (n, image) in page.images.images().iter().enumerate() {
+                let image_id = first_image_id + n as u32;
+                let soft_mask = page
+                    .xobjects
+                    .iter()
+                    .find(|xobject| xobject.image == n)
+                    .and_then(|xobject| xobject.soft_mask)
+                    .map(|mask| first_image_id + mask as u32);
+
+                object_offsets.push((image_id, offset));
+                let xobject = image.to_pdf_xobject_with_soft_mask(image_id, soft_mask);
+                writer.write_all(&xobject)?;
+                offset += xobject.len() as u64;
+            }
+
+            // Fonts, with their descriptors and embedded data
+            for (font, &first_id) in page.fonts.fonts().iter().zip(&font_ids) {
+                for (id, object) in (first_id..).zip(font.to_pdf_objects(first_id)) {
+                    object_offsets.push((id, offset));
+                    writer.write_all(&object)?;
+                    offset += object.len() as u64;
+                }
+            }
         }
 
-        // Write info dictionary if metadata present
-        let info_id = if self.has_metadata() {
-            let id = self.next_object_id + self.pages.len() as u32 * 2;
+        // Write info dictionary and XMP metadata if metadata present
+        if let Some(id) = info_id {
             object_offsets.push((id, offset));
             let info = self.build_info_dict(id);
             writer.write_all(info.as_bytes())?;
             offset += info.len() as u64;
-            Some(id)
-        } else {
-            None
-        };
+        }
+        if let Some(id) = metadata_id {
+            let xmp = self.build_xmp_metadata().to_xmp();
+            object_offsets.push((id, offset));
+            let metadata = format!(
+                "{} 0 obj\n<< /Type /Metadata /Subtype /XML /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+                id,
+                xmp.len(),
+                xmp
+            );
+            writer.write_all(metadata.as_bytes())?;
+            offset += metadata.len() as u64;
+        }
+
+        // Write output intent, with the ICC profile of the output device
+        if let (Some(id), Some(intent)) = (output_intent_id, &self.output_intent) {
+            object_offsets.push((id, offset));
+            let mut entries = vec![
+                "/Type /OutputIntent".to_string(),
+                "/S /GTS_PDFA1".to_string(),
+                format!(
+                    "/OutputCondition ({})",
+                    escape_pdf_string(&intent.output_condition)
+                ),
+                format!(
+                    "/OutputConditionIdentifier ({})",
+                    escape_pdf_string(&intent.output_condition_identifier)
+                ),
+            ];
+            if let Some(registry_name) = &intent.registry_name {
+                entries.push(format!(
+                    "/RegistryName ({})",
+                    escape_pdf_string(registry_name)
+                ));
+            }
+            if let Some(info) = &intent.info {
+                entries.push(format!("/Info ({})", escape_pdf_string(info)));
+            }
+            if let Some(profile_id) = icc_profile_id {
+                entries.push(format!("/DestOutputProfile {} 0 R", profile_id));
+            }
+            let output_intent = format!("{} 0 obj\n<< {} >>\nendobj\n", id, entries.join(" "));
+            writer.write_all(output_intent.as_bytes())?;
+            offset += output_intent.len() as u64;
+        }
+        if let (Some(id), Some((profile, components))) = (icc_profile_id, icc_profile) {
+            let compressed = compress_flate(profile);
+            object_offsets.push((id, offset));
+            let header = format!(
+                "{} 0 obj\n<< /N {} /Length {} /Filter /FlateDecode >>\nstream\n",
+                id,
+                components,
+                compressed.len()
+            );
+            writer.write_all(header.as_bytes())?;
+            writer.write_all(&compressed)?;
+            writer.write_all(b"\nendstream\nendobj\n")?;
+            offset += header.len() as u64 + compressed.len() as u64 + 18;
+        }
 
         // Write xref table
         let xref_offset = offset;
@@ -219,7 +412,10 @@ impl PdfDocument {
         self.metadata.title.is_some()
             || self.metadata.author.is_some()
             || self.metadata.subject.is_some()
+            || self.metadata.keywords.is_some()
             || self.metadata.creator.is_some()
+            || self.metadata.creation_date.is_some()
+            || self.metadata.mod_date.is_some()
     }
 
     /// Build the info dictionary.
@@ -242,17 +438,97 @@ impl PdfDocument {
             entries.push(format!("/Keywords ({})", escape_pdf_string(keywords)));
         }
 
-        entries.push("/Producer (skia-rs)".to_string());
+        if let Some(creation_date) = &self.metadata.creation_date {
+            entries.push(format!(
+                "/CreationDate ({})",
+                escape_pdf_string(creation_date)
+            ));
+        }
+        if let Some(mod_date) = &self.metadata.mod_date {
+            entries.push(format!("/ModDate ({})", escape_pdf_string(mod_date)));
+        }
+
+        entries.push(format!("/Producer ({})", PRODUCER));
 
         format!("{} 0 obj\n<< {} >>\nendobj\n", id, entries.join(" "))
     }
 
+    /// Build XMP metadata matching the info dictionary.
+    fn build_xmp_metadata(&self) -> XmpMetadata {
+        let metadata = &self.metadata;
+        XmpMetadata {
+            title: metadata.title.clone(),
+            author: metadata.author.clone(),
+            subject: metadata.subject.clone(),
+            keywords: metadata
+                .keywords
+                .iter()
+                .flat_map(|keywords| keywords.split(','))
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(String::from)
+                .collect(),
+            creator: metadata.creator.clone(),
+            create_date: metadata
+                .creation_date
+                .as_deref()
+                .and_then(pdf_date_to_iso8601),
+            modify_date: metadata.mod_date.as_deref().and_then(pdf_date_to_iso8601),
+            ..XmpMetadata::default()
+        }
+    }
+
     /// Generate PDF bytes.
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buffer = Vec::new();
         self.write_to(&mut buffer).unwrap();
         buffer
     }
+
+    /// End the current page, if any, and generate the PDF bytes.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.end_page();
+        self.to_bytes()
+    }
+}
+
+/// Convert a PDF date (`D:YYYYMMDDHHmmSSOHH'mm'`) to ISO 8601.
+///
+/// Components after the year are optional. Returns `None` if the date
+/// can't be parsed.
+fn pdf_date_to_iso8601(date: &str) -> Option<String> {
+    let date = date.strip_prefix("D:").unwrap_or(date);
+    let digits = date.bytes().take_while(u8::is_ascii_digit).count();
+    if !(4..=14).contains(&digits) || digits % 2 != 0 {
+        return None;
+    }
+    let (fields, zone) = date.split_at(digits);
+    let field =
+        |start: usize, default: &'static str| fields.get(start..start + 2).unwrap_or(default);
+
+    let mut iso = format!(
+        "{}-{}-{}T{}:{}:{}",
+        &fields[..4],
+        field(4, "01"),
+        field(6, "01"),
+        field(8, "00"),
+        field(10, "00"),
+        field(12, "00")
+    );
+    match zone.as_bytes().first() {
+        None => {}
+        Some(b'Z') => iso.push('Z'),
+        Some(&sign @ (b'+' | b'-')) => {
+            let zone: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+            if zone.len() != 2 && zone.len() != 4 {
+                return None;
+            }
+            let minutes = zone.get(2..4).unwrap_or("00");
+            iso.push_str(&format!("{}{}:{}", sign as char, &zone[..2], minutes));
+        }
+        Some(_) => return None,
+    }
+    Some(iso)
 }
 
 /// Escape special characters in a PDF string.
@@ -283,25 +559,200 @@ mod tests {
     #[test]
     fn test_pdf_document_with_page() {
         let mut doc = PdfDocument::new();
-        let canvas = doc.begin_page(612.0, 792.0); // Letter size
-        doc.end_page(canvas);
+        doc.begin_page(612.0, 792.0); // Letter size
+        doc.end_page();
 
         let bytes = doc.to_bytes();
         assert!(bytes.starts_with(b"%PDF-1.4"));
         assert_eq!(doc.page_count(), 1);
     }
 
+    #[test]
+    fn test_pdf_multi_page_sizes() {
+        let mut doc = PdfDocument::new();
+        let sizes = [(612.0, 792.0), (595.0, 842.0), (300.5, 200.0)];
+        for (width, height) in sizes {
+            let canvas = doc.begin_page(width, height);
+            canvas.draw_rect(
+                &Rect::from_xywh(10.0, 10.0, 50.0, 50.0),
+                &Default::default(),
+            );
+        }
+        // The last page is ended by finish
+        assert_eq!(doc.page_count(), 2);
+
+        let bytes = doc.finish();
+        let content = String::from_utf8_lossy(&bytes);
+        assert!(content.contains("<< /Type /Catalog /Pages 2 0 R >>"));
+        assert!(content.contains("<< /Type /Pages /Kids [3 0 R 5 0 R 7 0 R] /Count 3 >>"));
+        for (id, media_box) in [
+            (3, "[0 0 612 792]"),
+            (5, "[0 0 595 842]"),
+            (7, "[0 0 300.5 200]"),
+        ] {
+            let page = format!(
+                "{} 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox {} /Contents {} 0 R",
+                id,
+                media_box,
+                id + 1
+            );
+            assert!(content.contains(&page), "missing {}", page);
+        }
+        assert!(content.contains("<< /Size 9 /Root 1 0 R >>"));
+    }
+
+    #[test]
+    fn test_pdf_embedded_truetype_font() {
+        use skia_rs_text::Typeface;
+        use std::collections::BTreeSet;
+
+        let data = include_bytes!("../../skia-rs-text/testdata/Tuffy.ttf");
+        let typeface = Typeface::from_bytes(data.to_vec(), 0).unwrap();
+        let glyphs: BTreeSet<u16> = typeface.chars_to_glyphs("Hi").into_iter().collect();
+        let (h, i) = (typeface.char_to_glyph('H'), typeface.char_to_glyph('i'));
+
+        let mut doc = PdfDocument::new();
+        let canvas = doc.begin_page(200.0, 100.0);
+        let font = canvas
+            .fonts_mut()
+            .embed_truetype(&typeface, &glyphs)
+            .unwrap();
+        canvas.draw_text_with_font("Hi", 10.0, 50.0, font, 24.0, &Default::default());
+
+        let bytes = doc.finish();
+        let content = String::from_utf8_lossy(&bytes);
+        // The font's objects follow the page and its content stream
+        assert!(content.contains("/Resources << /Font << /Font0 5 0 R >> >>"));
+        assert!(content.contains(&format!(
+            "/Font0 24 Tf\n1 0 0 -1 10 50 Tm\n<{:04X}{:04X}> Tj",
+            h, i
+        )));
+        for expected in [
+            "5 0 obj\n<<\n/Type /Font\n/Subtype /Type0\n",
+            "/DescendantFonts [6 0 R]\n/ToUnicode 9 0 R\n",
+            "6 0 obj\n<<\n/Type /Font\n/Subtype /CIDFontType2\n",
+            "/FontDescriptor 7 0 R\n/CIDToGIDMap /Identity\n/W [",
+            "7 0 obj\n<<\n/Type /FontDescriptor\n/FontName /",
+            "/FontFile2 8 0 R\n",
+            "8 0 obj\n<< /Length ",
+        ] {
+            assert!(content.contains(expected), "missing {:?}", expected);
+        }
+        assert!(content.contains(&format!("<{:04X}> <0048>", h)));
+        assert!(content.contains(&format!("<{:04X}> <0069>", i)));
+
+        // Only the used glyphs are embedded
+        let length1: usize = content
+            .split("/Length1 ")
+            .nth(1)
+            .and_then(|rest| rest.split(' ').next())
+            .and_then(|n| n.parse().ok())
+            .unwrap();
+        assert!(length1 < data.len() / 2);
+        assert!(content.contains("<< /Size 10 /Root 1 0 R >>"));
+    }
+
+    #[test]
+    fn test_pdf_image_with_soft_mask() {
+        use skia_rs_codec::{Image, ImageInfo};
+        use skia_rs_core::{AlphaType, ColorType};
+
+        let info = ImageInfo::new(2, 2, ColorType::Rgba8888, AlphaType::Unpremul);
+        let pixels = [
+            255, 0, 0, 255, 0, 255, 0, 128, //
+            0, 0, 255, 0, 255, 255, 255, 255,
+        ];
+        let image = Image::from_raster_data(&info, &pixels, 8).unwrap();
+
+        let mut doc = PdfDocument::new();
+        doc.metadata_mut().title = Some("Images".to_string());
+        let canvas = doc.begin_page(100.0, 100.0);
+        canvas.draw_image(&image, &Rect::from_xywh(10.0, 20.0, 40.0, 30.0));
+
+        let bytes = doc.finish();
+        let content = String::from_utf8_lossy(&bytes);
+        // The mask is object 5 and the color image object 6
+        assert!(content.contains("/Resources << /XObject << /Im0 6 0 R >> >>"));
+        assert!(content.contains("40 0 0 -30 10 50 cm\n/Im0 Do\n"));
+        assert!(content.contains("6 0 obj\n<<\n/Type /XObject\n/Subtype /Image\n"));
+        assert!(content.contains("/SMask 5 0 R\n"));
+        assert!(content.contains("7 0 obj\n<< /Title (Images)"));
+        assert!(content.contains("<< /Size 9 /Root 1 0 R /Info 7 0 R >>"));
+    }
+
     #[test]
     fn test_pdf_metadata() {
         let mut doc = PdfDocument::new();
         doc.metadata_mut().title = Some("Test Document".to_string());
         doc.metadata_mut().author = Some("Test Author".to_string());
 
-        let canvas = doc.begin_page(612.0, 792.0);
-        doc.end_page(canvas);
+        doc.begin_page(612.0, 792.0);
 
-        let bytes = doc.to_bytes();
+        let bytes = doc.finish();
         let content = String::from_utf8_lossy(&bytes);
         assert!(content.contains("/Title (Test Document)"));
     }
+
+    #[test]
+    fn test_pdf_metadata_xmp_and_output_intent() {
+        let mut doc = PdfDocument::new();
+        doc.set_metadata(PdfMetadata {
+            title: Some("Archive".to_string()),
+            author: Some("A & B".to_string()),
+            subject: Some("Records".to_string()),
+            keywords: Some("pdf, archive".to_string()),
+            creation_date: Some("D:20240102030405+01'00'".to_string()),
+            ..Default::default()
+        });
+        doc.set_output_intent(IccProfile::srgb());
+        doc.begin_page(612.0, 792.0);
+
+        let bytes = doc.finish();
+        let content = String::from_utf8_lossy(&bytes);
+        assert!(content.contains(
+            "1 0 obj\n<< /Type /Catalog /Pages 2 0 R /Metadata 6 0 R /OutputIntents [7 0 R] >>"
+        ));
+
+        // Info dictionary
+        assert!(content.contains(
+            "5 0 obj\n<< /Title (Archive) /Author (A & B) /Subject (Records) /Keywords (pdf, archive) /CreationDate (D:20240102030405+01'00') /Producer (skia-rs "
+        ));
+
+        // XMP metadata matching it
+        assert!(content.contains("6 0 obj\n<< /Type /Metadata /Subtype /XML /Length "));
+        for expected in [
+            r#"<rdf:li xml:lang="x-default">Archive</rdf:li>"#,
+            "<rdf:li>A &amp; B</rdf:li>",
+            "<xmp:CreateDate>2024-01-02T03:04:05+01:00</xmp:CreateDate>",
+            "<pdf:Keywords>pdf, archive</pdf:Keywords>",
+        ] {
+            assert!(content.contains(expected), "missing {expected}");
+        }
+
+        // Output intent with the embedded sRGB profile
+        assert!(content.contains(
+            "7 0 obj\n<< /Type /OutputIntent /S /GTS_PDFA1 /OutputCondition (sRGB IEC61966-2.1) "
+        ));
+        assert!(content.contains("/DestOutputProfile 8 0 R >>"));
+        assert!(content.contains("8 0 obj\n<< /N 3 /Length "));
+        assert!(content.contains("/Filter /FlateDecode >>\nstream\n"));
+        assert!(content.contains("<< /Size 9 /Root 1 0 R /Info 5 0 R >>"));
+    }
+
+    #[test]
+    fn test_pdf_date_to_iso8601() {
+        assert_eq!(
+            pdf_date_to_iso8601("D:20240102030405Z").as_deref(),
+            Some("2024-01-02T03:04:05Z")
+        );
+        assert_eq!(
+            pdf_date_to_iso8601("D:202401").as_deref(),
+            Some("2024-01-01T00:00:00")
+        );
+        assert_eq!(
+            pdf_date_to_iso8601("D:20240102-05'30").as_deref(),
+            Some("2024-01-02T00:00:00-05:30")
+        );
+        assert_eq!(pdf_date_to_iso8601("January 2024"), None);
+    }
 }
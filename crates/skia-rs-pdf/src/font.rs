@@ -3,11 +3,15 @@
 //! This module provides font embedding for PDF documents, including:
 //! - Type 1 standard fonts (14 built-in fonts)
 //! - TrueType font embedding
-//! - Font subsetting (basic)
+//! - Font subsetting (TrueType outlines, embedded as CID fonts)
 //! - Unicode mapping (ToUnicode CMap)
 
+use crate::image::compress_flate;
+use crate::subset::subset_truetype;
 use skia_rs_core::Scalar;
-use std::collections::HashMap;
+use skia_rs_text::Typeface;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
 
 /// Font type for PDF embedding.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -124,6 +128,8 @@ pub struct PdfFont {
     pub used_glyphs: Vec<u16>,
     /// ToUnicode CMap.
     pub to_unicode: Option<String>,
+    /// Glyph IDs of the characters covered by an embedded CID font.
+    pub glyph_map: HashMap<char, u16>,
 }
 
 impl PdfFont {
@@ -148,6 +154,7 @@ impl PdfFont {
             last_char: 255,
             used_glyphs: Vec::new(),
             to_unicode: None,
+            glyph_map: HashMap::new(),
         }
     }
 
@@ -175,9 +182,94 @@ impl PdfFont {
             last_char: 255,
             used_glyphs: Vec::new(),
             to_unicode: None,
+            glyph_map: HashMap::new(),
         }
     }
 
+    /// Create a CID font embedding a subset of a TrueType typeface.
+    ///
+    /// Text in the font is encoded as 2-byte glyph IDs (`Identity-H`). Only
+    /// `used_glyphs` are embedded, and only characters mapping to them can
+    /// be drawn or extracted. Returns `None` if the typeface has no
+    /// TrueType outlines.
+    pub fn truetype_subset(typeface: &Typeface, used_glyphs: &BTreeSet<u16>) -> Option<Self> {
+        let face = ttf_parser::Face::parse(typeface.font_data()?, typeface.face_index()).ok()?;
+
+        // Characters mapping to the used glyphs
+        let mut glyph_map = HashMap::new();
+        if let Some(cmap) = face.tables().cmap {
+            for subtable in cmap.subtables.into_iter().filter(|s| s.is_unicode()) {
+                subtable.codepoints(|code_point| {
+                    let glyph = subtable.glyph_index(code_point).map(|g| g.0);
+                    if let (Some(c), Some(glyph)) = (char::from_u32(code_point), glyph) {
+                        if used_glyphs.contains(&glyph) {
+                            glyph_map.entry(c).or_insert(glyph);
+                        }
+                    }
+                });
+            }
+        }
+        let mut chars: Vec<(char, u16)> = glyph_map.iter().map(|(&c, &g)| (c, g)).collect();
+        chars.sort_unstable();
+
+        let font_data = subset_truetype(&face, used_glyphs, &chars)?;
+
+        // Metrics in PDF glyph space (1000 units per em)
+        let scale = 1000.0 / Scalar::from(face.units_per_em());
+        let widths = used_glyphs
+            .iter()
+            .filter_map(|&glyph| {
+                let advance = face.glyph_hor_advance(ttf_parser::GlyphId(glyph))?;
+                Some((glyph, (Scalar::from(advance) * scale).round() as u16))
+            })
+            .collect();
+        let bbox = face.global_bounding_box();
+        let ascender = Scalar::from(face.ascender()) * scale;
+
+        // Extract each glyph as the lowest character mapping to it
+        let mut unicode = BTreeMap::new();
+        for &(c, glyph) in &chars {
+            unicode.entry(glyph).or_insert(c);
+        }
+
+        let mut flags = 4; // Symbolic
+        if face.is_monospaced() {
+            flags |= 1;
+        }
+        if face.is_italic() {
+            flags |= 64;
+        }
+
+        Some(Self {
+            font_type: PdfFontType::Type0,
+            base_font: format!("{}+{}", subset_tag(used_glyphs), font_name(typeface)),
+            object_id: None,
+            descriptor_id: None,
+            encoding: "Identity-H".to_string(),
+            font_data: Some(font_data),
+            flags,
+            italic_angle: face.italic_angle().unwrap_or(0.0),
+            ascender,
+            descender: Scalar::from(face.descender()) * scale,
+            cap_height: face
+                .capital_height()
+                .map_or(ascender, |height| Scalar::from(height) * scale),
+            stem_v: 80.0,
+            bbox: [
+                Scalar::from(bbox.x_min) * scale,
+                Scalar::from(bbox.y_min) * scale,
+                Scalar::from(bbox.x_max) * scale,
+                Scalar::from(bbox.y_max) * scale,
+            ],
+            widths,
+            first_char: 0,
+            last_char: 0,
+            used_glyphs: used_glyphs.iter().copied().collect(),
+            to_unicode: Some(to_unicode_cmap(unicode)),
+            glyph_map,
+        })
+    }
+
     /// Mark a glyph as used (for subsetting).
     pub fn use_glyph(&mut self, glyph_id: u16) {
         if !self.used_glyphs.contains(&glyph_id) {
@@ -234,7 +326,11 @@ impl PdfFont {
         let mut dict = format!("{} 0 obj\n<<\n", id);
         dict.push_str("/Type /FontDescriptor\n");
         dict.push_str(&format!("/FontName /{}\n", self.base_font.replace(' ', "")));
-        dict.push_str(&format!("/Flags {}\n", self.flags | 32)); // Non-symbolic
+        let flags = match self.font_type {
+            PdfFontType::Type0 => self.flags,
+            _ => self.flags | 32, // Non-symbolic
+        };
+        dict.push_str(&format!("/Flags {}\n", flags));
         dict.push_str(&format!(
             "/FontBBox [{} {} {} {}]\n",
             self.bbox[0] as i32, self.bbox[1] as i32, self.bbox[2] as i32, self.bbox[3] as i32
@@ -247,7 +343,7 @@ impl PdfFont {
 
         if let Some(file_id) = font_file_id {
             match self.font_type {
-                PdfFontType::TrueType => {
+                PdfFontType::TrueType | PdfFontType::Type0 => {
                     dict.push_str(&format!("/FontFile2 {} 0 R\n", file_id));
                 }
                 PdfFontType::OpenTypeCff => {
@@ -263,30 +359,180 @@ impl PdfFont {
 
     /// Generate ToUnicode CMap for proper text extraction.
     pub fn generate_to_unicode(&self) -> String {
-        let mut cmap = String::new();
-        cmap.push_str("/CIDInit /ProcSet findresource begin\n");
-        cmap.push_str("12 dict begin\n");
-        cmap.push_str("begincmap\n");
-        cmap.push_str("/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n");
-        cmap.push_str("/CMapName /Adobe-Identity-UCS def\n");
-        cmap.push_str("/CMapType 2 def\n");
-        cmap.push_str("1 begincodespacerange\n");
-        cmap.push_str("<0000> <FFFF>\n");
-        cmap.push_str("endcodespacerange\n");
-
         // Simple ASCII mapping
-        cmap.push_str("95 beginbfchar\n");
-        for i in 32..127 {
-            cmap.push_str(&format!("<{:04X}> <{:04X}>\n", i, i));
+        to_unicode_cmap((32..127u16).map(|i| (i, char::from(i as u8))))
+    }
+
+    /// Number of PDF objects written by [`to_pdf_objects`](Self::to_pdf_objects).
+    pub(crate) fn object_count(&self) -> u32 {
+        if self.is_embedded_cid_font() { 5 } else { 1 }
+    }
+
+    /// Generate the font's PDF objects, numbered from `first_id`.
+    ///
+    /// The first object is the font dictionary. Embedded CID fonts are
+    /// followed by their CIDFont, font descriptor, font file and ToUnicode
+    /// CMap.
+    pub(crate) fn to_pdf_objects(&self, first_id: u32) -> Vec<Vec<u8>> {
+        let (Some(font_data), true) = (&self.font_data, self.is_embedded_cid_font()) else {
+            return vec![self.to_pdf_dict(first_id).into_bytes()];
+        };
+        let cid_font_id = first_id + 1;
+        let descriptor_id = first_id + 2;
+        let font_file_id = first_id + 3;
+        let to_unicode_id = first_id + 4;
+
+        let type0 = format!(
+            "{} 0 obj\n<<\n/Type /Font\n/Subtype /Type0\n/BaseFont /{}\n/Encoding /Identity-H\n\
+             /DescendantFonts [{} 0 R]\n/ToUnicode {} 0 R\n>>\nendobj\n",
+            first_id, self.base_font, cid_font_id, to_unicode_id
+        );
+
+        let mut cid_font = format!(
+            "{} 0 obj\n<<\n/Type /Font\n/Subtype /CIDFontType2\n/BaseFont /{}\n\
+             /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >>\n\
+             /FontDescriptor {} 0 R\n/CIDToGIDMap /Identity\n/W [",
+            cid_font_id, self.base_font, descriptor_id
+        );
+        let widths: BTreeMap<u16, u16> = self.widths.iter().map(|(&g, &w)| (g, w)).collect();
+        let mut previous = None;
+        for (glyph, width) in widths {
+            // Consecutive glyphs share one run of widths
+            if previous.is_some_and(|p: u16| p + 1 == glyph) {
+                write!(cid_font, " {}", width).unwrap();
+            } else {
+                if previous.is_some() {
+                    cid_font.push(']');
+                }
+                write!(cid_font, " {} [{}", glyph, width).unwrap();
+            }
+            previous = Some(glyph);
+        }
+        if previous.is_some() {
+            cid_font.push(']');
+        }
+        cid_font.push_str(" ]\n>>\nendobj\n");
+
+        let descriptor = self.to_font_descriptor(descriptor_id, Some(font_file_id));
+
+        let compressed = compress_flate(font_data);
+        let mut font_file = format!(
+            "{} 0 obj\n<< /Length {} /Length1 {} /Filter /FlateDecode >>\nstream\n",
+            font_file_id,
+            compressed.len(),
+            font_data.len()
+        )
+        .into_bytes();
+        font_file.extend_from_slice(&compressed);
+        font_file.extend_from_slice(b"\nendstream\nendobj\n");
+
+        let cmap = self.to_unicode.clone().unwrap_or_default();
+        let to_unicode = format!(
+            "{} 0 obj\n<< /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+            to_unicode_id,
+            cmap.len(),
+            cmap
+        );
+
+        vec![
+            type0.into_bytes(),
+            cid_font.into_bytes(),
+            descriptor.into_bytes(),
+            font_file,
+            to_unicode.into_bytes(),
+        ]
+    }
+
+    /// Check if this is a CID font with embedded TrueType outlines.
+    fn is_embedded_cid_font(&self) -> bool {
+        self.font_type == PdfFontType::Type0 && self.font_data.is_some()
+    }
+
+    /// Encode text as a PDF hex string for this font.
+    ///
+    /// CID fonts use 2-byte glyph IDs, with `.notdef` for characters the
+    /// font doesn't cover; other fonts use 1-byte codes.
+    pub fn encode_text(&self, text: &str) -> String {
+        let mut encoded = String::from("<");
+        for c in text.chars() {
+            if self.font_type == PdfFontType::Type0 {
+                let glyph = self.glyph_map.get(&c).copied().unwrap_or(0);
+                write!(encoded, "{:04X}", glyph).unwrap();
+            } else {
+                let code = u8::try_from(c as u32).unwrap_or(b'?');
+                write!(encoded, "{:02X}", code).unwrap();
+            }
+        }
+        encoded.push('>');
+        encoded
+    }
+}
+
+/// Build a ToUnicode CMap from 2-byte codes to characters.
+fn to_unicode_cmap(mappings: impl IntoIterator<Item = (u16, char)>) -> String {
+    let mut cmap = String::new();
+    cmap.push_str("/CIDInit /ProcSet findresource begin\n");
+    cmap.push_str("12 dict begin\n");
+    cmap.push_str("begincmap\n");
+    cmap.push_str("/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n");
+    cmap.push_str("/CMapName /Adobe-Identity-UCS def\n");
+    cmap.push_str("/CMapType 2 def\n");
+    cmap.push_str("1 begincodespacerange\n");
+    cmap.push_str("<0000> <FFFF>\n");
+    cmap.push_str("endcodespacerange\n");
+
+    // A bfchar block holds at most 100 mappings
+    let mappings: Vec<(u16, char)> = mappings.into_iter().collect();
+    for block in mappings.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", block.len()));
+        for &(code, c) in block {
+            let mut utf16 = [0u16; 2];
+            let units: String = c
+                .encode_utf16(&mut utf16)
+                .iter()
+                .map(|unit| format!("{:04X}", unit))
+                .collect();
+            cmap.push_str(&format!("<{:04X}> <{}>\n", code, units));
         }
         cmap.push_str("endbfchar\n");
+    }
+
+    cmap.push_str("endcmap\n");
+    cmap.push_str("CMapName currentdict /CMap defineresource pop\n");
+    cmap.push_str("end\n");
+    cmap.push_str("end\n");
 
-        cmap.push_str("endcmap\n");
-        cmap.push_str("CMapName currentdict /CMap defineresource pop\n");
-        cmap.push_str("end\n");
-        cmap.push_str("end\n");
+    cmap
+}
+
+/// Tag naming a font subset, derived from its glyphs.
+fn subset_tag(glyphs: &BTreeSet<u16>) -> String {
+    // FNV-1a, spread over six uppercase letters
+    let mut hash = glyphs.iter().fold(0x811C_9DC5u32, |hash, glyph| {
+        glyph.to_be_bytes().iter().fold(hash, |hash, &byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        })
+    });
+    (0..6)
+        .map(|_| {
+            let letter = char::from(b'A' + (hash % 26) as u8);
+            hash /= 26;
+            letter
+        })
+        .collect()
+}
 
-        cmap
+/// PostScript-safe font name for a typeface.
+fn font_name(typeface: &Typeface) -> String {
+    let name: String = typeface
+        .family_name()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    if name.is_empty() {
+        "Font".to_string()
+    } else {
+        name
     }
 }
 
@@ -346,6 +592,12 @@ pub struct PdfFontManager {
     fonts: Vec<PdfFont>,
     /// Font name to index mapping.
     name_to_index: HashMap<String, usize>,
+    /// Typefaces of embedded subsets, by font index.
+    subset_typefaces: HashMap<usize, Typeface>,
+    /// Typeface unique ID to font index mapping.
+    typeface_to_index: HashMap<u32, usize>,
+    /// Subsets whose used glyphs changed since they were built.
+    stale_subsets: BTreeSet<usize>,
 }
 
 impl PdfFontManager {
@@ -379,6 +631,65 @@ impl PdfFontManager {
         idx
     }
 
+    /// Embed a subset of a TrueType typeface holding `used_glyphs`.
+    ///
+    /// Returns the font's index, for
+    /// [`PdfCanvas::draw_text_with_font`](crate::PdfCanvas::draw_text_with_font),
+    /// or `None` if the typeface has no TrueType outlines. See
+    /// [`PdfFont::truetype_subset`].
+    pub fn embed_truetype(
+        &mut self,
+        typeface: &Typeface,
+        used_glyphs: &BTreeSet<u16>,
+    ) -> Option<usize> {
+        let font = PdfFont::truetype_subset(typeface, used_glyphs)?;
+        let idx = self.fonts.len();
+        self.name_to_index.insert(font.base_font.clone(), idx);
+        self.fonts.push(font);
+        self.subset_typefaces.insert(idx, typeface.clone());
+        self.typeface_to_index.insert(typeface.unique_id(), idx);
+        Some(idx)
+    }
+
+    /// Record glyphs of a typeface as used, embedding it on first use.
+    ///
+    /// Returns the index of the typeface's embedded subset, or `None` if the
+    /// typeface has no TrueType outlines. The subset is rebuilt with the new
+    /// glyphs by [`update_subsets`](Self::update_subsets).
+    pub fn use_glyphs(
+        &mut self,
+        typeface: &Typeface,
+        glyphs: impl IntoIterator<Item = u16>,
+    ) -> Option<usize> {
+        let Some(&idx) = self.typeface_to_index.get(&typeface.unique_id()) else {
+            return self.embed_truetype(typeface, &glyphs.into_iter().collect());
+        };
+
+        let font = &mut self.fonts[idx];
+        let count = font.used_glyphs.len();
+        for glyph in glyphs {
+            font.use_glyph(glyph);
+        }
+        if font.used_glyphs.len() != count {
+            self.stale_subsets.insert(idx);
+        }
+        Some(idx)
+    }
+
+    /// Rebuild the subsets whose used glyphs changed since they were built.
+    pub fn update_subsets(&mut self) {
+        for idx in std::mem::take(&mut self.stale_subsets) {
+            let Some(typeface) = self.subset_typefaces.get(&idx) else {
+                continue;
+            };
+            let used_glyphs = self.fonts[idx].used_glyphs.iter().copied().collect();
+            if let Some(font) = PdfFont::truetype_subset(typeface, &used_glyphs) {
+                self.name_to_index.insert(font.base_font.clone(), idx);
+                self.fonts[idx] = font;
+            }
+        }
+    }
+
     /// Get font by index.
     pub fn get(&self, index: usize) -> Option<&PdfFont> {
         self.fonts.get(index)
@@ -211,6 +211,16 @@ impl PdfImage {
 
     /// Generate the image XObject PDF dictionary.
     pub fn to_pdf_xobject(&self, id: u32) -> Vec<u8> {
+        self.to_pdf_xobject_with_soft_mask(id, self.soft_mask_id)
+    }
+
+    /// Generate the image XObject PDF dictionary with the given soft mask
+    /// object ID.
+    pub(crate) fn to_pdf_xobject_with_soft_mask(
+        &self,
+        id: u32,
+        soft_mask_id: Option<u32>,
+    ) -> Vec<u8> {
         let mut output = Vec::new();
 
         // Object header
@@ -231,7 +241,7 @@ impl PdfImage {
             write!(output, "/Filter /{}\n", filter_name).unwrap();
         }
 
-        if let Some(mask_id) = self.soft_mask_id {
+        if let Some(mask_id) = soft_mask_id {
             write!(output, "/SMask {} 0 R\n", mask_id).unwrap();
         }
 
@@ -249,7 +259,7 @@ impl PdfImage {
 }
 
 /// Compress data using flate (zlib).
-fn compress_flate(data: &[u8]) -> Vec<u8> {
+pub(crate) fn compress_flate(data: &[u8]) -> Vec<u8> {
     use std::io::Write;
 
     let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
@@ -3,7 +3,7 @@
 //! This crate provides PDF output:
 //! - PDF document creation
 //! - Drawing to PDF canvas
-//! - Font embedding (Type 1, TrueType)
+//! - Font embedding (Type 1, TrueType, subset CID fonts)
 //! - Image embedding (JPEG, PNG)
 //! - Transparency (ExtGState, soft masks, transparency groups)
 //! - PDF/A compliance (ISO 19005)
@@ -17,6 +17,7 @@ pub mod font;
 pub mod image;
 pub mod pdfa;
 pub mod stream;
+mod subset;
 pub mod transparency;
 
 pub use canvas::*;
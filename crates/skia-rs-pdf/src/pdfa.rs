@@ -28,8 +28,12 @@
 //! }
 //! ```
 
+use skia_rs_core::{IccColorSpace, IccProfile};
 use std::collections::HashSet;
 
+/// Producer recorded in the document information and XMP metadata.
+pub(crate) const PRODUCER: &str = concat!("skia-rs ", env!("CARGO_PKG_VERSION"));
+
 // =============================================================================
 // PDF/A Conformance Levels
 // =============================================================================
@@ -349,8 +353,18 @@ impl XmpMetadata {
             r#"    <rdf:Description rdf:about="" xmlns:pdf="http://ns.adobe.com/pdf/1.3/">"#,
         );
         xmp.push('\n');
-        xmp.push_str(r#"      <pdf:Producer>skia-rs 0.1.0</pdf:Producer>"#);
+        xmp.push_str(&format!(
+            r#"      <pdf:Producer>{}</pdf:Producer>"#,
+            PRODUCER
+        ));
         xmp.push('\n');
+        if !self.keywords.is_empty() {
+            xmp.push_str(&format!(
+                r#"      <pdf:Keywords>{}</pdf:Keywords>"#,
+                escape_xml(&self.keywords.join(", "))
+            ));
+            xmp.push('\n');
+        }
         xmp.push_str(r#"    </rdf:Description>"#);
         xmp.push('\n');
 
@@ -449,7 +463,7 @@ impl OutputIntent {
             output_condition_identifier: "sRGB IEC61966-2.1".to_string(),
             registry_name: Some("http://www.color.org".to_string()),
             info: Some("sRGB IEC61966-2.1".to_string()),
-            icc_profile: None, // Would contain actual sRGB ICC profile
+            icc_profile: IccProfile::srgb().to_bytes(),
         }
     }
 
@@ -474,6 +488,31 @@ impl OutputIntent {
             icc_profile: Some(icc_profile),
         }
     }
+
+    /// Create output intent for an ICC profile.
+    ///
+    /// sRGB profiles without raw data use [`srgb`](Self::srgb); other
+    /// profiles are identified by their description.
+    pub fn from_icc_profile(icc: &IccProfile) -> Self {
+        if icc.raw_data().is_none() && icc.is_srgb() {
+            return Self::srgb();
+        }
+        Self {
+            icc_profile: icc.to_bytes(),
+            ..Self::custom(&icc.description, Vec::new())
+        }
+    }
+
+    /// Get the number of color components of the ICC profile, if any.
+    pub fn components(&self) -> Option<u8> {
+        let icc = IccProfile::from_bytes(self.icc_profile.as_deref()?)?;
+        match icc.color_space {
+            IccColorSpace::Gray => Some(1),
+            IccColorSpace::Rgb | IccColorSpace::Lab | IccColorSpace::Xyz => Some(3),
+            IccColorSpace::Cmyk => Some(4),
+            IccColorSpace::Unknown => None,
+        }
+    }
 }
 
 // =============================================================================
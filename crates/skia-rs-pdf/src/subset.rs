@@ -0,0 +1,298 @@
+//! TrueType font subsetting.
+//!
+//! Subsets keep the original glyph IDs so that a CID font can use an
+//! identity CID-to-GID map: glyphs that aren't used are emptied rather than
+//! removed, which leaves only the `loca` offsets and `hmtx` entries behind.
+
+use std::collections::BTreeSet;
+
+/// Tables copied unchanged into a subset.
+const COPIED_TABLES: [&[u8; 4]; 6] = [b"cvt ", b"fpgm", b"hhea", b"hmtx", b"maxp", b"prep"];
+
+/// Composite glyph flags.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// Subset a TrueType font to the given glyphs and the characters mapping
+/// to them.
+///
+/// Glyph 0 (`.notdef`) and the components of composite glyphs are always
+/// kept. The `cmap` table is rebuilt with only `chars`, which must map to
+/// kept glyphs. Returns `None` for fonts without `glyf` outlines.
+pub(crate) fn subset_truetype(
+    face: &ttf_parser::Face<'_>,
+    glyphs: &BTreeSet<u16>,
+    chars: &[(char, u16)],
+) -> Option<Vec<u8>> {
+    let raw = face.raw_face();
+    let table = |tag: &[u8; 4]| raw.table(ttf_parser::Tag::from_bytes(tag));
+
+    let head = table(b"head")?;
+    let glyf = table(b"glyf")?;
+    let loca = table(b"loca")?;
+    if head.len() < 54 {
+        return None;
+    }
+    let long_offsets = read_u16(head, 50)? != 0;
+    let num_glyphs = face.number_of_glyphs();
+
+    let glyph_range = |gid: u16| -> Option<(usize, usize)> {
+        let (start, end) = if long_offsets {
+            let i = gid as usize * 4;
+            (read_u32(loca, i)? as usize, read_u32(loca, i + 4)? as usize)
+        } else {
+            let i = gid as usize * 2;
+            (
+                read_u16(loca, i)? as usize * 2,
+                read_u16(loca, i + 2)? as usize * 2,
+            )
+        };
+        (start <= end && end <= glyf.len()).then_some((start, end))
+    };
+
+    // Add the components of composite glyphs
+    let mut kept: BTreeSet<u16> = glyphs.iter().copied().filter(|&g| g < num_glyphs).collect();
+    kept.insert(0);
+    let mut pending: Vec<u16> = kept.iter().copied().collect();
+    while let Some(gid) = pending.pop() {
+        let Some((start, end)) = glyph_range(gid) else {
+            continue;
+        };
+        for component in composite_components(&glyf[start..end]) {
+            if component < num_glyphs && kept.insert(component) {
+                pending.push(component);
+            }
+        }
+    }
+
+    // Rebuild glyf and loca, emptying the glyphs that aren't kept
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((num_glyphs as usize + 1) * 4);
+    for gid in 0..num_glyphs {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if kept.contains(&gid) {
+            if let Some((start, end)) = glyph_range(gid) {
+                new_glyf.extend_from_slice(&glyf[start..end]);
+                pad4(&mut new_glyf);
+            }
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    // Switch to long loca offsets and clear the checksum adjustment
+    let mut new_head = head.to_vec();
+    new_head[8..12].fill(0);
+    new_head[50..52].copy_from_slice(&1u16.to_be_bytes());
+
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = vec![
+        (*b"cmap", build_cmap(chars)),
+        (*b"glyf", new_glyf),
+        (*b"head", new_head),
+        (*b"loca", new_loca),
+    ];
+    for tag in COPIED_TABLES {
+        if let Some(data) = table(tag) {
+            tables.push((*tag, data.to_vec()));
+        }
+    }
+    tables.sort_by_key(|(tag, _)| *tag);
+
+    let mut font = write_sfnt(&tables);
+
+    // Checksum adjustment makes the whole font sum to a fixed value
+    let head_offset = table_offset(&font, b"head")?;
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    Some(font)
+}
+
+/// Get the glyph IDs referenced by a composite glyph.
+fn composite_components(glyph: &[u8]) -> Vec<u16> {
+    let mut components = Vec::new();
+    if read_u16(glyph, 0).is_none_or(|contours| (contours as i16) >= 0) {
+        return components;
+    }
+
+    let mut offset = 10;
+    while let (Some(flags), Some(gid)) = (read_u16(glyph, offset), read_u16(glyph, offset + 2)) {
+        components.push(gid);
+        offset += 4;
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            offset += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            offset += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            offset += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+/// Build a `cmap` table with a Windows Unicode BMP (format 4) subtable.
+fn build_cmap(chars: &[(char, u16)]) -> Vec<u8> {
+    let mut mappings: Vec<(u16, u16)> = chars
+        .iter()
+        .filter_map(|&(c, gid)| u16::try_from(c as u32).ok().map(|code| (code, gid)))
+        .filter(|&(code, _)| code != 0xFFFF)
+        .collect();
+    mappings.sort_unstable();
+    mappings.dedup_by_key(|(code, _)| *code);
+
+    // One segment per character, plus the required final segment
+    let seg_count = mappings.len() + 1;
+    let mut end_codes = Vec::with_capacity(seg_count);
+    let mut start_codes = Vec::with_capacity(seg_count);
+    let mut deltas = Vec::with_capacity(seg_count);
+    for &(code, gid) in &mappings {
+        end_codes.push(code);
+        start_codes.push(code);
+        deltas.push(gid.wrapping_sub(code));
+    }
+    end_codes.push(0xFFFF);
+    start_codes.push(0xFFFF);
+    deltas.push(1);
+
+    let entry_selector = (seg_count as u16).ilog2() as u16;
+    let search_range = 2 * (1u16 << entry_selector);
+    let length = 16 + seg_count * 8;
+
+    let mut subtable = Vec::with_capacity(length);
+    for value in [
+        4,
+        length as u16,
+        0,
+        seg_count as u16 * 2,
+        search_range,
+        entry_selector,
+        seg_count as u16 * 2 - search_range,
+    ] {
+        subtable.extend_from_slice(&value.to_be_bytes());
+    }
+    end_codes
+        .iter()
+        .for_each(|v| subtable.extend_from_slice(&v.to_be_bytes()));
+    subtable.extend_from_slice(&0u16.to_be_bytes()); // reservedPad
+    start_codes
+        .iter()
+        .chain(&deltas)
+        .for_each(|v| subtable.extend_from_slice(&v.to_be_bytes()));
+    // idRangeOffsets are all zero
+    subtable.resize(length, 0);
+
+    let mut cmap = Vec::with_capacity(12 + length);
+    for value in [0u16, 1, 3, 1] {
+        cmap.extend_from_slice(&value.to_be_bytes());
+    }
+    cmap.extend_from_slice(&12u32.to_be_bytes());
+    cmap.extend_from_slice(&subtable);
+    cmap
+}
+
+/// Write an sfnt file from tables sorted by tag.
+fn write_sfnt(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = num_tables.max(1).ilog2() as u16;
+    let search_range = 16 * (1u16 << entry_selector);
+
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    for value in [
+        num_tables,
+        search_range,
+        entry_selector,
+        num_tables * 16 - search_range,
+    ] {
+        font.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in tables {
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&checksum(data).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        font.extend_from_slice(data);
+        pad4(&mut font);
+    }
+    font
+}
+
+/// Find a table's offset in an sfnt file written by [`write_sfnt`].
+fn table_offset(font: &[u8], tag: &[u8; 4]) -> Option<usize> {
+    let num_tables = read_u16(font, 4)? as usize;
+    (0..num_tables)
+        .map(|i| 12 + i * 16)
+        .find(|&entry| font.get(entry..entry + 4) == Some(tag))
+        .and_then(|entry| read_u32(font, entry + 8))
+        .map(|offset| offset as usize)
+}
+
+/// Sum data as big-endian 32-bit words, zero-padding the last one.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn pad4(data: &mut Vec<u8>) {
+    data.resize(data.len().next_multiple_of(4), 0);
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TUFFY: &[u8] = include_bytes!("../../skia-rs-text/testdata/Tuffy.ttf");
+
+    #[test]
+    fn test_subset_truetype() {
+        let face = ttf_parser::Face::parse(TUFFY, 0).unwrap();
+        let h = face.glyph_index('H').unwrap().0;
+        let i = face.glyph_index('i').unwrap().0;
+        let glyphs = BTreeSet::from([h, i]);
+
+        let subset = subset_truetype(&face, &glyphs, &[('H', h), ('i', i)]).unwrap();
+        assert!(subset.len() < TUFFY.len() / 2);
+        assert_eq!(checksum(&subset), 0xB1B0_AFBA);
+
+        // Glyph IDs and outlines are kept, other characters are unmapped
+        let parsed = ttf_parser::Face::parse(&subset, 0).unwrap();
+        assert_eq!(parsed.number_of_glyphs(), face.number_of_glyphs());
+        assert_eq!(parsed.glyph_index('H'), Some(ttf_parser::GlyphId(h)));
+        assert_eq!(parsed.glyph_index('i'), Some(ttf_parser::GlyphId(i)));
+        assert_eq!(parsed.glyph_index('x'), None);
+        assert_eq!(
+            parsed.glyph_bounding_box(ttf_parser::GlyphId(h)),
+            face.glyph_bounding_box(ttf_parser::GlyphId(h))
+        );
+        let x = face.glyph_index('x').unwrap();
+        assert!(parsed.glyph_bounding_box(x).is_none());
+        assert_eq!(parsed.glyph_hor_advance(x), face.glyph_hor_advance(x));
+    }
+}
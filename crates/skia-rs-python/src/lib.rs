@@ -33,11 +33,14 @@
 
 use pyo3::prelude::*;
 use pyo3::exceptions::PyValueError;
+use pyo3::types::PyBytes;
 
-use skia_rs_canvas::Surface as RsSurface;
+use skia_rs_canvas::{RasterCanvas, Surface as RsSurface};
+#[cfg(feature = "codec")]
+use skia_rs_codec::{EncoderQuality, ImageFormat};
 use skia_rs_core::{Color, Matrix as RsMatrix, Point as RsPoint, Rect as RsRect};
 use skia_rs_paint::{Paint as RsPaint, Style as RsStyle};
-use skia_rs_path::{Path as RsPath, PathBuilder as RsPathBuilder};
+use skia_rs_path::{Path as RsPath, PathBuilder as RsPathBuilder, parse_svg_path};
 
 // =============================================================================
 // Point
@@ -484,6 +487,14 @@ impl PathBuilder {
         PyRef::from(self)
     }
 
+    /// Append the contours of an SVG path data string (the `d` attribute).
+    fn add_svg_path(&mut self, d: &str) -> PyResult<PyRef<'_, Self>> {
+        let path = parse_svg_path(d)
+            .map_err(|e| PyValueError::new_err(format!("Invalid SVG path: {e}")))?;
+        self.inner.add_path(&path);
+        Ok(PyRef::from(self))
+    }
+
     /// Build the path.
     fn build(&self) -> Path {
         Path {
@@ -549,9 +560,23 @@ impl Path {
 // =============================================================================
 
 /// A drawing surface backed by pixels.
+///
+/// Draws are transformed by a current matrix, which `translate`, `scale`,
+/// `rotate` and `concat` modify and `save`/`restore` push and pop.
 #[pyclass]
 pub struct Surface {
     inner: RsSurface,
+    matrix: RsMatrix,
+    save_stack: Vec<RsMatrix>,
+}
+
+impl Surface {
+    /// Open a canvas on the surface with the current matrix.
+    fn canvas(&mut self) -> RasterCanvas<'_> {
+        let mut canvas = self.inner.raster_canvas();
+        canvas.set_matrix(&self.matrix);
+        canvas
+    }
 }
 
 #[pymethods]
@@ -560,7 +585,11 @@ impl Surface {
     #[new]
     fn new(width: i32, height: i32) -> PyResult<Self> {
         RsSurface::new_raster_n32_premul(width, height)
-            .map(|s| Self { inner: s })
+            .map(|s| Self {
+                inner: s,
+                matrix: RsMatrix::IDENTITY,
+                save_stack: Vec::new(),
+            })
             .ok_or_else(|| PyValueError::new_err("Failed to create surface"))
     }
 
@@ -576,7 +605,66 @@ impl Surface {
         self.inner.height()
     }
 
+    /// Push the current matrix; returns the save count before the push.
+    fn save(&mut self) -> usize {
+        self.save_stack.push(self.matrix);
+        self.save_stack.len()
+    }
+
+    /// Pop the matrix pushed by the matching `save`.
+    ///
+    /// Does nothing if there is no matching `save`.
+    fn restore(&mut self) {
+        if let Some(matrix) = self.save_stack.pop() {
+            self.matrix = matrix;
+        }
+    }
+
+    /// Number of saved states, plus one for the initial state.
+    #[getter]
+    fn save_count(&self) -> usize {
+        self.save_stack.len() + 1
+    }
+
+    /// Translate subsequent draws.
+    fn translate(&mut self, dx: f32, dy: f32) {
+        self.matrix = self.matrix.concat(&RsMatrix::translate(dx, dy));
+    }
+
+    /// Scale subsequent draws.
+    fn scale(&mut self, sx: f32, sy: f32) {
+        self.matrix = self.matrix.concat(&RsMatrix::scale(sx, sy));
+    }
+
+    /// Rotate subsequent draws (degrees).
+    fn rotate(&mut self, degrees: f32) {
+        self.matrix = self.matrix.concat(&RsMatrix::rotate(degrees.to_radians()));
+    }
+
+    /// Pre-concatenate a matrix with the current matrix.
+    fn concat(&mut self, matrix: &Matrix) {
+        self.matrix = self.matrix.concat(&matrix.inner);
+    }
+
+    /// Replace the current matrix.
+    fn set_matrix(&mut self, matrix: &Matrix) {
+        self.matrix = matrix.inner;
+    }
+
+    /// Reset the current matrix to identity.
+    fn reset_matrix(&mut self) {
+        self.matrix = RsMatrix::IDENTITY;
+    }
+
+    /// The current matrix.
+    #[getter]
+    fn matrix(&self) -> Matrix {
+        Matrix { inner: self.matrix }
+    }
+
     /// Clear the surface with a color.
+    ///
+    /// Clearing ignores the current matrix.
     fn clear(&mut self, color: u32) {
         let mut canvas = self.inner.raster_canvas();
         canvas.clear(Color(color));
@@ -584,37 +672,37 @@ impl Surface {
 
     /// Draw a rectangle.
     fn draw_rect(&mut self, left: f32, top: f32, right: f32, bottom: f32, paint: &Paint) {
-        let mut canvas = self.inner.raster_canvas();
+        let mut canvas = self.canvas();
         canvas.draw_rect(&RsRect::new(left, top, right, bottom), &paint.inner);
     }
 
     /// Draw a circle.
     fn draw_circle(&mut self, cx: f32, cy: f32, radius: f32, paint: &Paint) {
-        let mut canvas = self.inner.raster_canvas();
+        let mut canvas = self.canvas();
         canvas.draw_circle(RsPoint::new(cx, cy), radius, &paint.inner);
     }
 
     /// Draw an oval inscribed in a rectangle.
     fn draw_oval(&mut self, left: f32, top: f32, right: f32, bottom: f32, paint: &Paint) {
-        let mut canvas = self.inner.raster_canvas();
+        let mut canvas = self.canvas();
         canvas.draw_oval(&RsRect::new(left, top, right, bottom), &paint.inner);
     }
 
     /// Draw a line.
     fn draw_line(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, paint: &Paint) {
-        let mut canvas = self.inner.raster_canvas();
+        let mut canvas = self.canvas();
         canvas.draw_line(RsPoint::new(x0, y0), RsPoint::new(x1, y1), &paint.inner);
     }
 
     /// Draw a path.
     fn draw_path(&mut self, path: &Path, paint: &Paint) {
-        let mut canvas = self.inner.raster_canvas();
+        let mut canvas = self.canvas();
         canvas.draw_path(&path.inner, &paint.inner);
     }
 
     /// Draw a point.
     fn draw_point(&mut self, x: f32, y: f32, paint: &Paint) {
-        let mut canvas = self.inner.raster_canvas();
+        let mut canvas = self.canvas();
         canvas.draw_point(RsPoint::new(x, y), &paint.inner);
     }
 
@@ -624,10 +712,59 @@ impl Surface {
     }
 
     /// Save to PNG file.
-    #[cfg(feature = "png")]
     fn save_png(&self, path: &str) -> PyResult<()> {
-        // Would need codec integration
-        Err(PyValueError::new_err("PNG saving not yet implemented"))
+        #[cfg(feature = "png")]
+        {
+            use pyo3::exceptions::PyIOError;
+            use skia_rs_codec::{ImageEncoder, PngEncoder};
+
+            let image = self
+                .inner
+                .make_image_snapshot()
+                .ok_or_else(|| PyValueError::new_err("Failed to snapshot surface"))?;
+            let data = PngEncoder::new()
+                .encode_bytes(&image)
+                .map_err(|e| PyValueError::new_err(format!("Failed to encode PNG: {e}")))?;
+            std::fs::write(path, data)
+                .map_err(|e| PyIOError::new_err(format!("Failed to write {path}: {e}")))
+        }
+        #[cfg(not(feature = "png"))]
+        {
+            let _ = path;
+            Err(PyValueError::new_err(
+                "PNG saving requires skia-rs to be built with the `png` feature",
+            ))
+        }
+    }
+
+    /// Encode to bytes in the given format.
+    ///
+    /// `format` is one of "png", "jpeg", "webp", "bmp", "tiff" or "qoi";
+    /// `quality` (0-100) applies to lossy formats only.
+    #[pyo3(signature = (format = "png", quality = None))]
+    fn encode_to_bytes<'py>(
+        &self,
+        py: Python<'py>,
+        format: &str,
+        quality: Option<u8>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        #[cfg(feature = "codec")]
+        {
+            let format = parse_image_format(format)?;
+            let quality = quality.map(|q| EncoderQuality::new(q.min(100)));
+            let data = self
+                .inner
+                .encode(format, quality)
+                .map_err(|e| PyValueError::new_err(format!("Failed to encode surface: {e}")))?;
+            Ok(PyBytes::new_bound(py, &data))
+        }
+        #[cfg(not(feature = "codec"))]
+        {
+            let _ = (py, format, quality);
+            Err(PyValueError::new_err(
+                "Encoding requires skia-rs to be built with the `codec` feature",
+            ))
+        }
     }
 
     fn __repr__(&self) -> String {
@@ -635,6 +772,22 @@ impl Surface {
     }
 }
 
+/// Parse an encoder format name such as `"png"` or `"jpeg"`.
+#[cfg(feature = "codec")]
+fn parse_image_format(format: &str) -> PyResult<ImageFormat> {
+    match format.to_ascii_lowercase().as_str() {
+        "png" => Ok(ImageFormat::Png),
+        "jpeg" | "jpg" => Ok(ImageFormat::Jpeg),
+        "webp" => Ok(ImageFormat::WebP),
+        "bmp" => Ok(ImageFormat::Bmp),
+        "tiff" | "tif" => Ok(ImageFormat::Tiff),
+        "qoi" => Ok(ImageFormat::Qoi),
+        _ => Err(PyValueError::new_err(format!(
+            "Unsupported image format: {format}"
+        ))),
+    }
+}
+
 // =============================================================================
 // Color utilities
 // =============================================================================
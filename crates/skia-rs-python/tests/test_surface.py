@@ -0,0 +1,112 @@
+"""Tests for the skia_rs Surface binding.
+
+Run with `maturin develop && pytest` from this directory.
+"""
+
+import io
+
+import pytest
+
+import skia_rs
+
+
+def red_surface(width=32, height=24):
+    surface = skia_rs.Surface(width, height)
+    surface.clear(skia_rs.Colors.RED)
+    return surface
+
+
+def test_save_png(tmp_path):
+    Image = pytest.importorskip("PIL.Image")
+
+    path = tmp_path / "red.png"
+    red_surface().save_png(str(path))
+
+    with Image.open(path) as img:
+        assert img.format == "PNG"
+        assert img.size == (32, 24)
+        assert img.convert("RGBA").getpixel((5, 5)) == (255, 0, 0, 255)
+
+
+def test_encode_to_bytes():
+    Image = pytest.importorskip("PIL.Image")
+
+    data = red_surface().encode_to_bytes()
+    assert isinstance(data, bytes)
+    assert data[:8] == b"\x89PNG\r\n\x1a\n"
+
+    with Image.open(io.BytesIO(data)) as img:
+        assert img.size == (32, 24)
+
+    jpeg = red_surface().encode_to_bytes("jpeg", quality=90)
+    assert jpeg[:2] == b"\xff\xd8"
+
+
+def test_encode_to_bytes_rejects_unknown_format():
+    with pytest.raises(ValueError):
+        red_surface().encode_to_bytes("gif")
+
+
+def alpha_at(surface, x, y):
+    return surface.pixels()[(y * surface.width + x) * 4 + 3]
+
+
+def fill_paint():
+    paint = skia_rs.Paint()
+    paint.color = skia_rs.Colors.BLUE
+    return paint
+
+
+def test_translate_moves_draws():
+    surface = skia_rs.Surface(100, 100)
+    surface.translate(50, 50)
+    surface.draw_rect(0, 0, 10, 10, fill_paint())
+
+    assert alpha_at(surface, 55, 55) == 255
+    assert alpha_at(surface, 5, 5) == 0
+
+
+def test_save_restore():
+    surface = skia_rs.Surface(100, 100)
+    paint = fill_paint()
+
+    assert surface.save() == 1
+    surface.scale(2, 2)
+    surface.draw_rect(10, 10, 20, 20, paint)
+    surface.restore()
+    assert surface.save_count == 1
+    surface.draw_rect(0, 0, 5, 5, paint)
+
+    assert alpha_at(surface, 35, 35) == 255
+    assert alpha_at(surface, 15, 15) == 0
+    assert alpha_at(surface, 2, 2) == 255
+
+
+def test_rotate_and_concat():
+    surface = skia_rs.Surface(100, 100)
+    surface.concat(skia_rs.Matrix.translate(50, 50))
+    surface.rotate(90)
+    # (x, y) maps to (50 - y, 50 + x).
+    surface.draw_rect(10, 0, 20, 10, fill_paint())
+
+    assert alpha_at(surface, 45, 65) == 255
+    assert alpha_at(surface, 65, 55) == 0
+
+    surface.reset_matrix()
+    point = surface.matrix.map_point(3, 4)
+    assert (point.x, point.y) == (3, 4)
+
+
+def test_add_svg_path():
+    builder = skia_rs.PathBuilder()
+    builder.add_svg_path("M 10 10 H 40 V 40 H 10 Z")
+    path = builder.build()
+    assert path.contains(25, 25)
+    assert not path.contains(50, 50)
+
+    surface = skia_rs.Surface(50, 50)
+    surface.draw_path(path, fill_paint())
+    assert alpha_at(surface, 25, 25) == 255
+
+    with pytest.raises(ValueError):
+        skia_rs.PathBuilder().add_svg_path("M 10 oops")
@@ -24,6 +24,7 @@ fn pdf_generator_main() {
     use skia_rs_paint::{Paint, Style};
     use skia_rs_path::PathBuilder;
     use skia_rs_pdf::{PdfDocument, PdfMetadata};
+    use skia_rs_text::Font;
     use std::fs::File;
     use std::io::BufWriter;
     println!("skia-rs PDF Generator Example");
@@ -52,7 +53,7 @@ fn pdf_generator_main() {
         page_width, page_height
     );
 
-    let mut canvas = doc.begin_page(page_width, page_height);
+    let canvas = doc.begin_page(page_width, page_height);
 
     // Draw a header rectangle
     {
@@ -69,7 +70,13 @@ fn pdf_generator_main() {
         let mut paint = Paint::new();
         paint.set_color32(Color::from_rgb(255, 255, 255));
 
-        canvas.draw_text("skia-rs PDF Example", 50.0, 50.0, 24.0, &paint);
+        canvas.draw_text(
+            "skia-rs PDF Example",
+            50.0,
+            50.0,
+            &Font::from_size(24.0),
+            &paint,
+        );
         println!("  Drew title text");
     }
 
@@ -78,7 +85,13 @@ fn pdf_generator_main() {
         let mut paint = Paint::new();
         paint.set_color32(Color::from_rgb(100, 100, 100));
 
-        canvas.draw_text("Pure Rust 2D Graphics Library", 50.0, 120.0, 14.0, &paint);
+        canvas.draw_text(
+            "Pure Rust 2D Graphics Library",
+            50.0,
+            120.0,
+            &Font::from_size(14.0),
+            &paint,
+        );
     }
 
     // Draw some shapes demonstration
@@ -86,7 +99,13 @@ fn pdf_generator_main() {
         // Draw section title
         let mut text_paint = Paint::new();
         text_paint.set_color32(Color::from_rgb(30, 30, 50));
-        canvas.draw_text("Shape Examples:", 50.0, 170.0, 16.0, &text_paint);
+        canvas.draw_text(
+            "Shape Examples:",
+            50.0,
+            170.0,
+            &Font::from_size(16.0),
+            &text_paint,
+        );
 
         // Draw a filled rectangle
         let mut fill_paint = Paint::new();
@@ -121,7 +140,13 @@ fn pdf_generator_main() {
 
     // Draw some lines
     {
-        canvas.draw_text("Line Examples:", 50.0, 330.0, 16.0, &Paint::new());
+        canvas.draw_text(
+            "Line Examples:",
+            50.0,
+            330.0,
+            &Font::from_size(16.0),
+            &Paint::new(),
+        );
 
         let mut line_paint = Paint::new();
         line_paint.set_color32(Color::from_rgb(241, 196, 15));
@@ -152,7 +177,13 @@ fn pdf_generator_main() {
 
     // Draw a path
     {
-        canvas.draw_text("Path Example:", 50.0, 450.0, 16.0, &Paint::new());
+        canvas.draw_text(
+            "Path Example:",
+            50.0,
+            450.0,
+            &Font::from_size(16.0),
+            &Paint::new(),
+        );
 
         let mut path_builder = PathBuilder::new();
         path_builder
@@ -191,19 +222,19 @@ fn pdf_generator_main() {
             "Generated with skia-rs - Pure Rust 2D Graphics",
             50.0,
             page_height - 20.0,
-            12.0,
+            &Font::from_size(12.0),
             &text_paint,
         );
         println!("  Drew footer");
     }
 
     // End the page
-    doc.end_page(canvas);
+    doc.end_page();
     println!("\nPage 1 complete!");
 
     // Add a second page
     println!("\nBeginning page 2...");
-    let mut canvas2 = doc.begin_page(page_width, page_height);
+    let canvas2 = doc.begin_page(page_width, page_height);
 
     // Draw some content on page 2
     {
@@ -214,7 +245,13 @@ fn pdf_generator_main() {
 
         let mut text_paint = Paint::new();
         text_paint.set_color32(Color::from_rgb(255, 255, 255));
-        canvas2.draw_text("Page 2 - Color Palette", 50.0, 50.0, 24.0, &text_paint);
+        canvas2.draw_text(
+            "Page 2 - Color Palette",
+            50.0,
+            50.0,
+            &Font::from_size(24.0),
+            &text_paint,
+        );
     }
 
     // Draw color swatches
@@ -238,14 +275,14 @@ fn pdf_generator_main() {
 
             let mut text_paint = Paint::new();
             text_paint.set_color32(Color::from_rgb(30, 30, 50));
-            canvas2.draw_text(name, 170.0, y + 30.0, 14.0, &text_paint);
+            canvas2.draw_text(name, 170.0, y + 30.0, &Font::from_size(14.0), &text_paint);
 
             y += 70.0;
         }
         println!("  Drew color palette");
     }
 
-    doc.end_page(canvas2);
+    doc.end_page();
     println!("Page 2 complete!");
 
     // Write PDF to file
@@ -3,6 +3,36 @@
 //! This crate provides a convenient, idiomatic Rust API that wraps
 //! the lower-level crates with ergonomic types and methods.
 //!
+//! # Quick Start
+//!
+//! ```rust
+//! use skia_rs_safe::prelude::*;
+//!
+//! // Create an 800x600 RGBA surface
+//! let mut surface = Surface::new_raster_n32_premul(800, 600).expect("failed to create surface");
+//!
+//! // Get a canvas that draws into the surface
+//! let mut canvas = surface.canvas();
+//!
+//! // Clear with a dark background
+//! canvas.clear(Color::from_rgb(18, 18, 26));
+//!
+//! // Create a paint with an orange color
+//! let mut paint = Paint::new();
+//! paint.set_anti_alias(true);
+//! paint.set_color(Color::from_rgb(255, 107, 53).into());
+//! paint.set_style(Style::Fill);
+//!
+//! // Draw a rounded rectangle
+//! let rect = Rect::from_xywh(100.0, 100.0, 600.0, 400.0);
+//! canvas.draw_round_rect(&rect, 20.0, 20.0, &paint);
+//! # drop(canvas);
+//! # let inside = (450 * 800 + 120) * 4;
+//! # assert_eq!(&surface.pixels()[inside..inside + 4], &[255, 107, 53, 255]);
+//! ```
+//!
+//! Drawing text with `RasterCanvas::draw_string` needs the `text` feature.
+//!
 //! # Features
 //!
 //! - `std` (default) - Enable standard library support
@@ -70,6 +100,8 @@ pub mod android;
 pub mod prelude {
     pub use skia_rs_canvas::{RasterCanvas, Surface};
     pub use skia_rs_core::{Color, Color4f, Matrix, Point, Rect, Scalar};
-    pub use skia_rs_paint::{Paint, Style};
-    pub use skia_rs_path::{Path, PathBuilder};
+    pub use skia_rs_paint::{Paint, Shader, ShaderRef, Style, TileMode, shaders};
+    pub use skia_rs_path::{
+        Path, PathBuilder, StrokeCap, StrokeJoin, StrokeParams, stroke_to_fill,
+    };
 }
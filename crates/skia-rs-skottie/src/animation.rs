@@ -3,11 +3,12 @@
 //! This module provides the main `Animation` type for loading and
 //! rendering Lottie animations.
 
+use crate::expression::ExpressionEnvironment;
 use crate::layers::{Layer, LayerContent, ShapeContent};
 use crate::model::{AssetModel, LottieModel};
-use crate::render::RenderContext;
+use crate::render::{Canvas, RenderContext};
 use crate::{Result, SkottieError};
-use skia_rs_core::{Matrix, Rect, Scalar};
+use skia_rs_core::{Matrix, Rect, Scalar, Size};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -34,6 +35,8 @@ pub struct Animation {
     assets: HashMap<String, Asset>,
     /// Current frame.
     current_frame: Scalar,
+    /// Expression environments of the main composition and its precomps.
+    expressions: Vec<Arc<ExpressionEnvironment>>,
 }
 
 /// Asset types.
@@ -96,20 +99,26 @@ impl Animation {
 
     /// Build an animation from a parsed Lottie model.
     fn from_model(model: LottieModel) -> Result<Self> {
-        // Parse layers
-        let layers: Vec<Layer> = model.layers.iter().map(Layer::from_lottie).collect();
+        // Parse layers, binding their expressions to the composition
+        let environment = ExpressionEnvironment::new(model.frame_rate, model.width, model.height);
+        let layers = parse_layers(&model.layers, &environment);
+        let mut expressions = vec![environment];
 
         // Parse assets
         let mut assets = HashMap::new();
         for asset in &model.assets {
             if !asset.layers.is_empty() {
                 // Precomp
+                let width = asset.width.unwrap_or(model.width);
+                let height = asset.height.unwrap_or(model.height);
+                let environment = ExpressionEnvironment::new(model.frame_rate, width, height);
                 let precomp = PrecompAsset {
                     id: asset.id.clone(),
-                    width: asset.width.unwrap_or(model.width),
-                    height: asset.height.unwrap_or(model.height),
-                    layers: asset.layers.iter().map(Layer::from_lottie).collect(),
+                    width,
+                    height,
+                    layers: parse_layers(&asset.layers, &environment),
                 };
+                expressions.push(environment);
                 assets.insert(asset.id.clone(), Asset::Precomp(precomp));
             } else if asset.filename.is_some() {
                 // Image
@@ -140,6 +149,7 @@ impl Animation {
             layers,
             assets,
             current_frame: model.in_point,
+            expressions,
         })
     }
 
@@ -163,6 +173,11 @@ impl Animation {
         self.height
     }
 
+    /// Get the composition size.
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
     /// Get the frame rate (fps).
     pub fn fps(&self) -> Scalar {
         self.frame_rate
@@ -260,9 +275,11 @@ impl Animation {
         }
     }
 
-    /// Render the animation at the current frame.
-    pub fn render(&self, ctx: &mut RenderContext) {
-        self.render_frame(ctx, self.current_frame);
+    /// Render the current frame to a canvas, scaling the composition to fit
+    /// `dst` and centering it.
+    pub fn render(&self, canvas: &mut dyn Canvas, dst: &Rect) {
+        let mut ctx = RenderContext::new(canvas);
+        self.render_to_rect(&mut ctx, dst);
     }
 
     /// Render a specific frame.
@@ -270,16 +287,12 @@ impl Animation {
         ctx.save();
 
         // Render layers in reverse order (bottom to top)
-        for layer in self.layers.iter().rev() {
-            if layer.is_visible_at(frame) {
-                ctx.render_layer(layer, frame, &self.assets);
-            }
-        }
+        ctx.render_layers(&self.layers, frame, &self.assets);
 
         ctx.restore();
     }
 
-    /// Render to a target rect (scales to fit).
+    /// Render the current frame to a target rect (scales to fit).
     pub fn render_to_rect(&self, ctx: &mut RenderContext, rect: &Rect) {
         let scale_x = rect.width() / self.width;
         let scale_y = rect.height() / self.height;
@@ -292,7 +305,7 @@ impl Animation {
         ctx.concat(&Matrix::translate(offset_x, offset_y));
         ctx.concat(&Matrix::scale(scale, scale));
 
-        self.render(ctx);
+        self.render_frame(ctx, self.current_frame);
 
         ctx.restore();
     }
@@ -341,10 +354,25 @@ impl Animation {
             total_shapes,
             total_masks,
             asset_count: self.assets.len(),
+            expression_count: self.expressions.iter().map(|e| e.expression_count()).sum(),
+            unsupported_expressions: self.expressions.iter().map(|e| e.unsupported_count()).sum(),
         }
     }
 }
 
+/// Parse a composition's layers and make them available to its expressions.
+fn parse_layers(
+    models: &[crate::model::LayerModel],
+    environment: &Arc<ExpressionEnvironment>,
+) -> Vec<Layer> {
+    let layers: Vec<Layer> = models
+        .iter()
+        .map(|model| Layer::from_lottie_with_environment(model, environment))
+        .collect();
+    environment.link_layers(&layers);
+    layers
+}
+
 fn count_shapes(shapes: &[crate::shapes::Shape]) -> usize {
     let mut count = 0;
     for shape in shapes {
@@ -393,6 +421,11 @@ pub struct AnimationStats {
     pub total_masks: usize,
     /// Asset count.
     pub asset_count: usize,
+    /// Properties driven by expressions.
+    pub expression_count: usize,
+    /// Expressions using unsupported features, whose properties keep their
+    /// keyframed values.
+    pub unsupported_expressions: usize,
 }
 
 /// Builder for loading animations with options.
@@ -509,6 +542,201 @@ mod tests {
         assert_eq!(stats.total_frames, 60);
     }
 
+    /// A 20x20 rect moving from x=20 to x=80 over frames 0-10, filled with
+    /// `fill`.
+    fn moving_rect(fill: &str) -> String {
+        format!(
+            r#"{{
+            "v": "5.5.7", "fr": 10, "ip": 0, "op": 10, "w": 100, "h": 100,
+            "layers": [{{
+                "ty": 4, "ip": 0, "op": 10, "st": 0, "ks": {{}},
+                "shapes": [
+                    {{
+                        "ty": "rc",
+                        "p": {{"a": 1, "k": [{{"t": 0, "s": [20, 50]}}, {{"t": 10, "s": [80, 50]}}]}},
+                        "s": {{"a": 0, "k": [20, 20]}},
+                        "r": {{"a": 0, "k": 0}}
+                    }},
+                    {fill}
+                ]
+            }}]
+        }}"#
+        )
+    }
+
+    #[test]
+    fn test_render_to_canvas() {
+        use skia_rs_canvas::{PixelBuffer, RasterCanvas};
+        use skia_rs_core::Color;
+
+        let fill = r#"{"ty": "fl", "c": {"a": 0, "k": [1, 0, 0, 1]}, "o": {"a": 0, "k": 100}}"#;
+        let mut anim = Animation::from_json(&moving_rect(fill)).unwrap();
+        assert_eq!(anim.size(), Size::new(100.0, 100.0));
+        assert_eq!(anim.duration(), 1.0);
+        anim.seek(0.5);
+
+        let red = Color::from_rgb(255, 0, 0);
+        let mut buffer = PixelBuffer::new(100, 100);
+        let mut canvas = RasterCanvas::new(&mut buffer);
+        anim.render(&mut canvas, &anim.bounds());
+        drop(canvas);
+        // The rect is halfway along, spanning x=40-60
+        assert_eq!(buffer.get_pixel(50, 50), Some(red));
+        assert_eq!(buffer.get_pixel(45, 45), Some(red));
+        assert_ne!(buffer.get_pixel(30, 50), Some(red));
+        assert_ne!(buffer.get_pixel(70, 50), Some(red));
+
+        // Scaled to fit a 50x50 destination at (50, 0)
+        let mut buffer = PixelBuffer::new(100, 100);
+        let mut canvas = RasterCanvas::new(&mut buffer);
+        anim.render(&mut canvas, &Rect::from_xywh(50.0, 0.0, 50.0, 50.0));
+        drop(canvas);
+        assert_eq!(buffer.get_pixel(75, 25), Some(red));
+        assert_ne!(buffer.get_pixel(50, 50), Some(red));
+    }
+
+    #[test]
+    fn test_render_gradient_fill() {
+        use skia_rs_canvas::{PixelBuffer, RasterCanvas};
+
+        // Red to blue across the rect at x=40-60
+        let fill = r#"{
+            "ty": "gf", "t": 1, "o": {"a": 0, "k": 100},
+            "s": {"a": 0, "k": [40, 50]}, "e": {"a": 0, "k": [60, 50]},
+            "g": {"p": 2, "k": {"a": 0, "k": [0, 1, 0, 0, 1, 0, 0, 1]}}
+        }"#;
+        let mut anim = Animation::from_json(&moving_rect(fill)).unwrap();
+        anim.seek_frame(5.0);
+
+        let mut buffer = PixelBuffer::new(100, 100);
+        let mut canvas = RasterCanvas::new(&mut buffer);
+        anim.render(&mut canvas, &anim.bounds());
+        drop(canvas);
+        let left = buffer.get_pixel(41, 50).unwrap();
+        let right = buffer.get_pixel(58, 50).unwrap();
+        assert!(left.red() > 200 && left.blue() < 50, "{left:?}");
+        assert!(right.blue() > 200 && right.red() < 50, "{right:?}");
+    }
+
+    /// A 100x100 red-to-blue gradient layer matted by a white circle of
+    /// radius 30 at the center, with matte type `tt`.
+    fn circle_matte(tt: i32) -> String {
+        format!(
+            r#"{{
+            "v": "5.5.7", "fr": 10, "ip": 0, "op": 10, "w": 100, "h": 100,
+            "layers": [
+                {{
+                    "ty": 4, "nm": "Circle", "ind": 1, "td": 1, "ip": 0, "op": 10, "st": 0, "ks": {{}},
+                    "shapes": [
+                        {{"ty": "el", "p": {{"a": 0, "k": [50, 50]}}, "s": {{"a": 0, "k": [60, 60]}}}},
+                        {{"ty": "fl", "c": {{"a": 0, "k": [1, 1, 1, 1]}}, "o": {{"a": 0, "k": 100}}}}
+                    ]
+                }},
+                {{
+                    "ty": 4, "nm": "Gradient", "ind": 2, "tt": {tt}, "ip": 0, "op": 10, "st": 0, "ks": {{}},
+                    "shapes": [
+                        {{"ty": "rc", "p": {{"a": 0, "k": [50, 50]}}, "s": {{"a": 0, "k": [100, 100]}}, "r": {{"a": 0, "k": 0}}}},
+                        {{
+                            "ty": "gf", "t": 1, "o": {{"a": 0, "k": 100}},
+                            "s": {{"a": 0, "k": [0, 50]}}, "e": {{"a": 0, "k": [100, 50]}},
+                            "g": {{"p": 2, "k": {{"a": 0, "k": [0, 1, 0, 0, 1, 0, 0, 1]}}}}
+                        }}
+                    ]
+                }}
+            ]
+        }}"#
+        )
+    }
+
+    #[test]
+    fn test_render_track_mattes() {
+        use skia_rs_canvas::{PixelBuffer, RasterCanvas};
+
+        let render = |tt: i32| {
+            let anim = Animation::from_json(&circle_matte(tt)).unwrap();
+            let mut buffer = PixelBuffer::new(100, 100);
+            let mut canvas = RasterCanvas::new(&mut buffer);
+            anim.render(&mut canvas, &anim.bounds());
+            drop(canvas);
+            buffer
+        };
+        let alpha = |buffer: &PixelBuffer, x: i32, y: i32| buffer.get_pixel(x, y).unwrap().alpha();
+
+        // Alpha matte: the gradient shows through the circle only, and the
+        // matte itself is not drawn.
+        let buffer = render(1);
+        let center = buffer.get_pixel(50, 50).unwrap();
+        assert_eq!(center.alpha(), 255);
+        assert!(center.red() > 100 && center.blue() > 100, "{center:?}");
+        assert_eq!(alpha(&buffer, 5, 5), 0);
+        assert_eq!(alpha(&buffer, 95, 50), 0);
+
+        // Coverage falls off across the circle's edge at x = 80.
+        let row: Vec<u8> = (70..90).map(|x| alpha(&buffer, x, 50)).collect();
+        assert!(row.windows(2).all(|w| w[0] >= w[1]), "{row:?}");
+        assert_eq!(row[0], 255);
+        assert_eq!(row[19], 0);
+        assert!(row.iter().any(|a| *a > 0 && *a < 255), "{row:?}");
+
+        // Inverted mattes keep the outside instead.
+        let buffer = render(2);
+        assert_eq!(alpha(&buffer, 50, 50), 0);
+        assert_eq!(alpha(&buffer, 5, 5), 255);
+
+        // A white matte has full luminance, so luma mattes match alpha ones.
+        let buffer = render(3);
+        assert_eq!(alpha(&buffer, 50, 50), 255);
+        assert_eq!(alpha(&buffer, 5, 5), 0);
+        let buffer = render(4);
+        assert_eq!(alpha(&buffer, 50, 50), 0);
+        assert_eq!(alpha(&buffer, 5, 5), 255);
+    }
+
+    #[test]
+    fn test_expression_properties() {
+        let json = r#"{
+            "v": "5.5.7", "fr": 30, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [
+                {
+                    "ty": 3, "nm": "Wiggler", "ind": 1, "ip": 0, "op": 60, "st": 0,
+                    "ks": {
+                        "o": {"a": 0, "k": 50, "x": "var $bm_rt;\n$bm_rt = wiggle(2, 30);"},
+                        "p": {"a": 0, "k": [30, 40]}
+                    }
+                },
+                {
+                    "ty": 3, "nm": "Follower", "ind": 2, "ip": 0, "op": 60, "st": 0,
+                    "ks": {
+                        "p": {"a": 0, "k": [0, 0], "x": "thisComp.layer('Wiggler').transform.position + [10, 0]"},
+                        "r": {"a": 0, "k": 90, "x": "loopOut('cycle')"}
+                    }
+                }
+            ]
+        }"#;
+        let anim = Animation::from_json(json).unwrap();
+        let wiggler = &anim.layers()[0];
+        let follower = &anim.layers()[1];
+
+        assert!(wiggler.transform.opacity.is_animated());
+        let opacities: Vec<Scalar> = [0.0, 10.0, 20.0, 30.0]
+            .iter()
+            .map(|frame| wiggler.opacity_at(*frame))
+            .collect();
+        assert!(
+            opacities.iter().all(|o| (0.2..=0.8).contains(o)),
+            "{opacities:?}"
+        );
+        assert!(opacities.windows(2).all(|w| w[0] != w[1]), "{opacities:?}");
+
+        assert_eq!(follower.transform.position_at(15.0), [40.0, 40.0]);
+        // Unsupported expressions keep the keyframed value
+        assert_eq!(follower.transform.rotation_at(15.0), 90.0_f32.to_radians());
+
+        let stats = anim.stats();
+        assert_eq!(stats.expression_count, 3);
+        assert_eq!(stats.unsupported_expressions, 1);
+    }
+
     #[test]
     fn test_builder() {
         let anim = AnimationBuilder::new().load(SIMPLE_ANIMATION).unwrap();
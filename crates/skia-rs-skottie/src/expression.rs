@@ -1,13 +1,21 @@
 //! Expression evaluation for Lottie animations.
 //!
 //! This module provides a subset of Lottie expression support:
-//! - Basic math operations
-//! - Property access
-//! - Time-based functions
-//! - Simple conditionals
+//! - Basic math operations, with vector arithmetic
+//! - `var` declarations, assignments and `if`/`else`
+//! - Time-based functions (`linear`, `ease`, `wiggle`, ...)
+//! - Property links via `thisComp.layer(...)`
+//!
+//! Expressions using anything else evaluate to [`Value::Null`], which leaves
+//! the property they drive at its keyframed value.
 
+use crate::layers::Layer;
+use crate::transform::Transform;
+use crate::{Result, SkottieError};
 use skia_rs_core::Scalar;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
 
 /// Expression value types.
 #[derive(Debug, Clone)]
@@ -52,6 +60,21 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Format the value as string concatenation does.
+    fn to_text(&self) -> String {
+        match self {
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::String(s) => s.clone(),
+            Value::Array(a) => a
+                .iter()
+                .map(Scalar::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+            Value::Null => "null".to_string(),
+        }
+    }
 }
 
 impl Default for Value {
@@ -77,6 +100,10 @@ pub struct ExpressionContext {
     pub variables: HashMap<String, Value>,
     /// Property cache.
     property_cache: HashMap<String, Value>,
+    /// Composition that property links resolve against.
+    environment: Option<Arc<ExpressionEnvironment>>,
+    /// Nesting depth of the property link being evaluated.
+    depth: u32,
 }
 
 impl ExpressionContext {
@@ -119,327 +146,1383 @@ impl ExpressionContext {
     }
 }
 
+/// Maximum nesting of property links before evaluation gives up.
+const MAX_LINK_DEPTH: u32 = 8;
+
+/// Maximum nesting of statements and expressions, so that deeply nested
+/// source can't overflow the stack while it is parsed or evaluated.
+const MAX_NESTING_DEPTH: usize = 256;
+
+/// Names every expression can refer to without declaring them.
+const GLOBALS: &[&str] = &[
+    "time", "frame", "value", "thisComp", "Math", "true", "false",
+];
+
+/// Functions callable without a receiver, besides the `Math` ones.
+const FUNCTIONS: &[&str] = &[
+    "linear",
+    "ease",
+    "easeIn",
+    "easeOut",
+    "easeInOut",
+    "clamp",
+    "random",
+    "wiggle",
+    "length",
+    "normalize",
+    "add",
+    "sub",
+    "mul",
+    "div",
+    "sum",
+    "$bm_sum",
+    "$bm_sub",
+    "$bm_mul",
+    "$bm_div",
+    "$bm_mod",
+    "$bm_neg",
+    "degreesToRadians",
+    "radiansToDegrees",
+    "framesToTime",
+    "timeToFrames",
+];
+
+/// `Math` functions, also callable unqualified.
+const MATH_FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "asin", "acos", "atan", "atan2", "abs", "floor", "ceil", "round", "sqrt",
+    "pow", "exp", "log", "min", "max",
+];
+
+/// Keywords of constructs the evaluator does not run.
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "function", "for", "while", "do", "switch", "return", "new", "try", "throw",
+];
+
+/// Punctuation, longest first so that `==` wins over `=`.
+const PUNCTUATION: &[&str] = &[
+    "===", "!==", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "*=", "/=", "+", "-", "*", "/",
+    "%", "<", ">", "!", "=", "(", ")", "[", "]", "{", "}", ",", ".", ";", "?", ":",
+];
+
+/// Binary operator precedence levels, loosest first.
+const BINARY_LEVELS: [&[&str]; 6] = [
+    &["||"],
+    &["&&"],
+    &["==", "!=", "===", "!=="],
+    &["<", "<=", ">", ">="],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
 /// A simple expression evaluator.
 #[derive(Debug)]
 pub struct ExpressionEvaluator {
     /// Expression source.
     source: String,
+    /// Parsed statements, or why the source could not be parsed.
+    program: std::result::Result<Vec<Stmt>, String>,
 }
 
 impl ExpressionEvaluator {
     /// Create a new evaluator.
     pub fn new(source: &str) -> Self {
+        let program = tokenize(source).and_then(|tokens| {
+            Parser {
+                tokens,
+                pos: 0,
+                depth: 0,
+            }
+            .program()
+        });
         Self {
             source: source.to_string(),
+            program,
         }
     }
 
+    /// Get the expression source.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Check that the expression only uses supported syntax, functions and
+    /// global names.
+    ///
+    /// Variables set on an [`ExpressionContext`] are not known here, so an
+    /// expression reading them fails the check but still evaluates.
+    pub fn check(&self) -> Result<()> {
+        let program = self.program.as_ref().map_err(|reason| {
+            SkottieError::UnsupportedFeature(format!("expression syntax: {reason}"))
+        })?;
+        check_program(program)
+            .map_err(|reason| SkottieError::UnsupportedFeature(format!("expression uses {reason}")))
+    }
+
     /// Evaluate the expression.
+    ///
+    /// Returns [`Value::Null`] if the expression uses an unsupported feature.
     pub fn evaluate(&self, ctx: &ExpressionContext) -> Value {
-        // Simple expression parser
-        let source = self.source.trim();
+        let Ok(program) = &self.program else {
+            return Value::Null;
+        };
+        let mut scope = Scope {
+            ctx,
+            locals: HashMap::new(),
+        };
+        scope.run(program).unwrap_or(Value::Null)
+    }
+}
+
+/// An expression attached to an animated property.
+#[derive(Debug, Clone)]
+pub struct PropertyExpression {
+    /// The evaluator.
+    evaluator: Arc<ExpressionEvaluator>,
+    /// Composition the expression runs against.
+    environment: Weak<ExpressionEnvironment>,
+}
+
+impl PropertyExpression {
+    /// Create a property expression that isn't bound to a composition.
+    ///
+    /// With no frame rate to run against, it leaves the property at its
+    /// keyframed value.
+    pub fn new(source: &str) -> Self {
+        Self::new_with_environment(source, &ExpressionEnvironment::detached())
+    }
 
-        // Check for simple built-in functions
-        if source == "time" {
-            return Value::Number(ctx.time);
+    /// Create a property expression bound to the composition `environment`
+    /// describes.
+    ///
+    /// The expression stops evaluating, leaving the property at its
+    /// keyframed value, once the composition is dropped.
+    pub fn new_with_environment(source: &str, environment: &Arc<ExpressionEnvironment>) -> Self {
+        let evaluator = ExpressionEvaluator::new(source);
+        environment.register(&evaluator);
+        Self {
+            evaluator: Arc::new(evaluator),
+            environment: Arc::downgrade(environment),
         }
-        if source == "frame" {
-            return Value::Number(ctx.frame);
+    }
+
+    /// Get the expression source.
+    pub fn source(&self) -> &str {
+        self.evaluator.source()
+    }
+
+    /// Check if the expression only uses supported features.
+    pub fn is_supported(&self) -> bool {
+        self.evaluator.check().is_ok()
+    }
+
+    /// Evaluate the expression at a frame, with `value` bound to the
+    /// property's keyframed value.
+    pub(crate) fn evaluate(&self, frame: Scalar, value: Value, depth: u32) -> Value {
+        let Some(environment) = self.environment.upgrade() else {
+            return Value::Null;
+        };
+        if environment.frame_rate <= 0.0 {
+            return Value::Null;
         }
 
-        // Check for math operations
-        if let Some(result) = self.evaluate_math(source, ctx) {
-            return result;
+        let mut ctx = ExpressionContext::new();
+        ctx.set_time(frame / environment.frame_rate, environment.frame_rate);
+        ctx.frame = frame;
+        ctx.set_size(environment.width, environment.height);
+        ctx.set_variable("value", value);
+        ctx.environment = Some(environment);
+        ctx.depth = depth;
+        self.evaluator.evaluate(&ctx)
+    }
+}
+
+/// Composition state shared by the expressions of one composition.
+///
+/// Layers and properties parsed for a composition take its environment,
+/// which their expressions read the frame rate, size and linked layers from.
+#[derive(Debug)]
+pub struct ExpressionEnvironment {
+    /// Frame rate.
+    frame_rate: Scalar,
+    /// Composition width.
+    width: Scalar,
+    /// Composition height.
+    height: Scalar,
+    /// Layers reachable through `thisComp.layer(...)`.
+    layers: OnceLock<Vec<LinkedLayer>>,
+    /// Expressions bound to the composition.
+    expression_count: AtomicUsize,
+    /// Bound expressions that use unsupported features.
+    unsupported_count: AtomicUsize,
+}
+
+/// A layer as seen by property links.
+#[derive(Debug)]
+struct LinkedLayer {
+    /// Layer name.
+    name: String,
+    /// Layer index.
+    index: i32,
+    /// Layer transform.
+    transform: Transform,
+}
+
+impl ExpressionEnvironment {
+    /// Create an environment for a composition.
+    pub fn new(frame_rate: Scalar, width: Scalar, height: Scalar) -> Arc<Self> {
+        Arc::new(Self {
+            frame_rate,
+            width,
+            height,
+            layers: OnceLock::new(),
+            expression_count: AtomicUsize::new(0),
+            unsupported_count: AtomicUsize::new(0),
+        })
+    }
+
+    /// An environment for expressions parsed outside any composition.
+    ///
+    /// Callers drop it once parsing is done, which leaves the expressions
+    /// bound to it unevaluated.
+    pub(crate) fn detached() -> Arc<Self> {
+        Self::new(0.0, 0.0, 0.0)
+    }
+
+    /// Make the composition's layers available to property links.
+    pub(crate) fn link_layers(&self, layers: &[Layer]) {
+        let linked = layers
+            .iter()
+            .map(|layer| LinkedLayer {
+                name: layer.name.clone(),
+                index: layer.index,
+                transform: layer.transform.clone(),
+            })
+            .collect();
+        let _ = self.layers.set(linked);
+    }
+
+    /// Number of expressions bound to the composition.
+    pub(crate) fn expression_count(&self) -> usize {
+        self.expression_count.load(Ordering::Relaxed)
+    }
+
+    /// Number of bound expressions that use unsupported features.
+    pub(crate) fn unsupported_count(&self) -> usize {
+        self.unsupported_count.load(Ordering::Relaxed)
+    }
+
+    /// Count an expression bound to the composition.
+    fn register(&self, evaluator: &ExpressionEvaluator) {
+        self.expression_count.fetch_add(1, Ordering::Relaxed);
+        if evaluator.check().is_err() {
+            self.unsupported_count.fetch_add(1, Ordering::Relaxed);
         }
+    }
+
+    /// Get the linked layers.
+    fn layers(&self) -> &[LinkedLayer] {
+        self.layers.get().map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Expression token.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Scalar),
+    Str(String),
+    Ident(String),
+    Punct(&'static str),
+}
+
+/// Expression statement.
+#[derive(Debug)]
+enum Stmt {
+    /// `var a = 1, b;`
+    Declare(Vec<(String, Option<Expr>)>),
+    /// `a = 1;` or a compound assignment such as `a += 1;`.
+    Assign(String, Option<BinaryOp>, Expr),
+    /// `if (condition) statement else statement`
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    /// `{ statements }`
+    Block(Vec<Stmt>),
+    /// Expression statement.
+    Expr(Expr),
+}
 
-        // Check for function calls
-        if let Some(result) = self.evaluate_function(source, ctx) {
-            return result;
+/// Expression syntax tree.
+#[derive(Debug)]
+enum Expr {
+    Number(Scalar),
+    Str(String),
+    Ident(String),
+    Array(Vec<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Member(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+}
+
+/// Unary operator.
+#[derive(Debug, Clone, Copy)]
+enum UnaryOp {
+    Neg,
+    Plus,
+    Not,
+}
+
+/// Binary operator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl BinaryOp {
+    fn from_punct(punct: &str) -> Self {
+        match punct {
+            "+" | "+=" => BinaryOp::Add,
+            "-" | "-=" => BinaryOp::Sub,
+            "*" | "*=" => BinaryOp::Mul,
+            "/" | "/=" => BinaryOp::Div,
+            "%" => BinaryOp::Rem,
+            "==" | "===" => BinaryOp::Eq,
+            "!=" | "!==" => BinaryOp::Ne,
+            "<" => BinaryOp::Lt,
+            "<=" => BinaryOp::Le,
+            ">" => BinaryOp::Gt,
+            _ => BinaryOp::Ge,
         }
+    }
+}
 
-        // Try to parse as number
-        if let Ok(n) = source.parse::<Scalar>() {
-            return Value::Number(n);
+/// Split expression source into tokens.
+fn tokenize(source: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+        } else if c.is_ascii_digit()
+            || (c == '.' && rest[1..].starts_with(|d: char| d.is_ascii_digit()))
+        {
+            let len = number_len(rest);
+            let number = rest[..len]
+                .parse()
+                .map_err(|_| format!("invalid number `{}`", &rest[..len]))?;
+            tokens.push(Token::Number(number));
+            rest = &rest[len..];
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..len].to_string()));
+            rest = &rest[len..];
+        } else if c == '"' || c == '\'' {
+            let mut text = String::new();
+            let mut chars = rest[1..].char_indices();
+            let mut len = None;
+            while let Some((i, ch)) = chars.next() {
+                match ch {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            text.push(match escaped {
+                                'n' => '\n',
+                                't' => '\t',
+                                other => other,
+                            });
+                        }
+                    }
+                    ch if ch == c => {
+                        len = Some(i + 2);
+                        break;
+                    }
+                    ch => text.push(ch),
+                }
+            }
+            let len = len.ok_or("unterminated string")?;
+            tokens.push(Token::Str(text));
+            rest = &rest[len..];
+        } else {
+            let punct = PUNCTUATION
+                .iter()
+                .find(|punct| rest.starts_with(**punct))
+                .ok_or_else(|| format!("unexpected character `{c}`"))?;
+            tokens.push(Token::Punct(punct));
+            rest = &rest[punct.len()..];
         }
+    }
+
+    Ok(tokens)
+}
 
-        // Try to parse as array
-        if source.starts_with('[') && source.ends_with(']') {
-            let inner = &source[1..source.len() - 1];
-            let values: Vec<Scalar> = inner
-                .split(',')
-                .filter_map(|s| s.trim().parse().ok())
-                .collect();
-            return Value::Array(values);
+/// Length of the number literal at the start of `s`.
+fn number_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits = |mut i: usize| {
+        while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+            i += 1;
+        }
+        i
+    };
+
+    let len = digits(0);
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let mut exponent = len + 1;
+        if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+            exponent += 1;
         }
+        if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+            return digits(exponent);
+        }
+    }
+    len
+}
+
+/// Recursive-descent parser over expression tokens.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Nesting depth of the node being parsed.
+    depth: usize,
+}
 
-        // Variable lookup
-        if let Some(value) = ctx.get_variable(source) {
-            return value.clone();
+impl Parser {
+    /// Go one level deeper into the syntax tree.
+    fn descend(&mut self) -> std::result::Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err("expression nested too deeply".to_string());
         }
+        Ok(())
+    }
 
-        Value::Null
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
     }
 
-    /// Evaluate math operations.
-    fn evaluate_math(&self, source: &str, ctx: &ExpressionContext) -> Option<Value> {
-        // Very simple math parsing
-        // Format: "a + b", "a - b", "a * b", "a / b"
-
-        for op in &[" + ", " - ", " * ", " / ", " % "] {
-            if let Some(pos) = source.find(op) {
-                let left = source[..pos].trim();
-                let right = source[pos + op.len()..].trim();
-
-                let left_val = ExpressionEvaluator::new(left).evaluate(ctx);
-                let right_val = ExpressionEvaluator::new(right).evaluate(ctx);
-
-                if let (Some(a), Some(b)) = (left_val.as_number(), right_val.as_number()) {
-                    let result = match *op {
-                        " + " => a + b,
-                        " - " => a - b,
-                        " * " => a * b,
-                        " / " => {
-                            if b != 0.0 {
-                                a / b
-                            } else {
-                                0.0
-                            }
-                        }
-                        " % " => {
-                            if b != 0.0 {
-                                a % b
-                            } else {
-                                0.0
-                            }
-                        }
-                        _ => return None,
-                    };
-                    return Some(Value::Number(result));
-                }
-            }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Punct(p)) if *p == punct);
+        if found {
+            self.pos += 1;
         }
+        found
+    }
 
-        None
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Ident(k)) if k == keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
     }
 
-    /// Evaluate function calls.
-    fn evaluate_function(&self, source: &str, ctx: &ExpressionContext) -> Option<Value> {
-        // Parse function call: name(args)
-        if let Some(paren_pos) = source.find('(') {
-            if source.ends_with(')') {
-                let name = &source[..paren_pos];
-                let args_str = &source[paren_pos + 1..source.len() - 1];
-                let args: Vec<&str> = args_str.split(',').map(|s| s.trim()).collect();
+    fn expect(&mut self, punct: &str) -> std::result::Result<(), String> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(format!("expected `{punct}`"))
+        }
+    }
 
-                return self.call_function(name, &args, ctx);
-            }
+    fn identifier(&mut self) -> std::result::Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            _ => Err("expected a name".to_string()),
         }
+    }
 
-        None
+    fn program(mut self) -> std::result::Result<Vec<Stmt>, String> {
+        let mut statements = Vec::new();
+        while self.peek().is_some() {
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
     }
 
-    /// Call a built-in function.
-    fn call_function(&self, name: &str, args: &[&str], ctx: &ExpressionContext) -> Option<Value> {
-        match name {
-            // Math functions
-            "Math.sin" | "sin" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.sin()))
-            }
-            "Math.cos" | "cos" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.cos()))
-            }
-            "Math.tan" | "tan" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.tan()))
-            }
-            "Math.abs" | "abs" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.abs()))
-            }
-            "Math.floor" | "floor" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.floor()))
-            }
-            "Math.ceil" | "ceil" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.ceil()))
-            }
-            "Math.round" | "round" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.round()))
-            }
-            "Math.sqrt" | "sqrt" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                Some(Value::Number(arg.as_number()?.sqrt()))
-            }
-            "Math.pow" | "pow" => {
-                if args.len() >= 2 {
-                    let base = ExpressionEvaluator::new(args[0]).evaluate(ctx);
-                    let exp = ExpressionEvaluator::new(args[1]).evaluate(ctx);
-                    Some(Value::Number(base.as_number()?.powf(exp.as_number()?)))
-                } else {
-                    None
+    fn statement(&mut self) -> std::result::Result<Stmt, String> {
+        let depth = self.depth;
+        self.descend()?;
+        let statement = self.statement_body();
+        self.depth = depth;
+        statement
+    }
+
+    fn statement_body(&mut self) -> std::result::Result<Stmt, String> {
+        if self.eat(";") {
+            return Ok(Stmt::Block(Vec::new()));
+        }
+        if self.eat("{") {
+            let mut body = Vec::new();
+            while !self.eat("}") {
+                if self.peek().is_none() {
+                    return Err("expected `}`".to_string());
                 }
+                body.push(self.statement()?);
             }
-            "Math.min" | "min" => {
-                if args.len() >= 2 {
-                    let a = ExpressionEvaluator::new(args[0]).evaluate(ctx);
-                    let b = ExpressionEvaluator::new(args[1]).evaluate(ctx);
-                    Some(Value::Number(a.as_number()?.min(b.as_number()?)))
+            return Ok(Stmt::Block(body));
+        }
+        if self.eat_keyword("var") || self.eat_keyword("let") || self.eat_keyword("const") {
+            let mut declarations = Vec::new();
+            loop {
+                let name = self.identifier()?;
+                let init = if self.eat("=") {
+                    Some(self.expression()?)
                 } else {
                     None
+                };
+                declarations.push((name, init));
+                if !self.eat(",") {
+                    break;
                 }
             }
-            "Math.max" | "max" => {
-                if args.len() >= 2 {
-                    let a = ExpressionEvaluator::new(args[0]).evaluate(ctx);
-                    let b = ExpressionEvaluator::new(args[1]).evaluate(ctx);
-                    Some(Value::Number(a.as_number()?.max(b.as_number()?)))
-                } else {
-                    None
+            self.eat(";");
+            return Ok(Stmt::Declare(declarations));
+        }
+        if self.eat_keyword("if") {
+            self.expect("(")?;
+            let condition = self.expression()?;
+            self.expect(")")?;
+            let then = Box::new(self.statement()?);
+            let otherwise = if self.eat_keyword("else") {
+                Some(Box::new(self.statement()?))
+            } else {
+                None
+            };
+            return Ok(Stmt::If(condition, then, otherwise));
+        }
+        if let (Some(Token::Ident(name)), Some(Token::Punct(op))) =
+            (self.peek(), self.tokens.get(self.pos + 1))
+        {
+            if matches!(*op, "=" | "+=" | "-=" | "*=" | "/=") {
+                let name = name.clone();
+                let op = (*op != "=").then(|| BinaryOp::from_punct(op));
+                self.pos += 2;
+                let value = self.expression()?;
+                self.eat(";");
+                return Ok(Stmt::Assign(name, op, value));
+            }
+        }
+
+        let expr = self.expression()?;
+        self.eat(";");
+        Ok(Stmt::Expr(expr))
+    }
+
+    fn expression(&mut self) -> std::result::Result<Expr, String> {
+        let depth = self.depth;
+        self.descend()?;
+        let expression = self.conditional();
+        self.depth = depth;
+        expression
+    }
+
+    fn conditional(&mut self) -> std::result::Result<Expr, String> {
+        let condition = self.binary(0)?;
+        if self.eat("?") {
+            let then = self.expression()?;
+            self.expect(":")?;
+            let otherwise = self.expression()?;
+            return Ok(Expr::Conditional(
+                Box::new(condition),
+                Box::new(then),
+                Box::new(otherwise),
+            ));
+        }
+        Ok(condition)
+    }
+
+    fn binary(&mut self, level: usize) -> std::result::Result<Expr, String> {
+        if level == BINARY_LEVELS.len() {
+            return self.unary();
+        }
+
+        // Each operator nests the operands before it one level deeper
+        let depth = self.depth;
+        let mut left = self.binary(level + 1)?;
+        while let Some(&Token::Punct(punct)) = self.peek() {
+            if !BINARY_LEVELS[level].contains(&punct) {
+                break;
+            }
+            self.pos += 1;
+            self.descend()?;
+            let right = Box::new(self.binary(level + 1)?);
+            let lhs = Box::new(left);
+            left = match punct {
+                "||" => Expr::Or(lhs, right),
+                "&&" => Expr::And(lhs, right),
+                _ => Expr::Binary(BinaryOp::from_punct(punct), lhs, right),
+            };
+        }
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> std::result::Result<Expr, String> {
+        let op = if self.eat("-") {
+            UnaryOp::Neg
+        } else if self.eat("+") {
+            UnaryOp::Plus
+        } else if self.eat("!") {
+            UnaryOp::Not
+        } else {
+            return self.postfix();
+        };
+        let depth = self.depth;
+        self.descend()?;
+        let operand = self.unary();
+        self.depth = depth;
+        Ok(Expr::Unary(op, Box::new(operand?)))
+    }
+
+    fn postfix(&mut self) -> std::result::Result<Expr, String> {
+        let depth = self.depth;
+        let mut expr = self.primary()?;
+        loop {
+            if matches!(self.peek(), Some(Token::Punct("." | "[" | "("))) {
+                self.descend()?;
+            }
+            if self.eat(".") {
+                expr = Expr::Member(Box::new(expr), self.identifier()?);
+            } else if self.eat("[") {
+                let index = self.expression()?;
+                self.expect("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else if self.eat("(") {
+                expr = Expr::Call(Box::new(expr), self.list(")")?);
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn list(&mut self, close: &str) -> std::result::Result<Vec<Expr>, String> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expression()?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn primary(&mut self) -> std::result::Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Str(s)) => Ok(Expr::Str(s)),
+            Some(Token::Ident(name)) if UNSUPPORTED_KEYWORDS.contains(&name.as_str()) => {
+                Err(format!("`{name}`"))
+            }
+            Some(Token::Ident(name)) => Ok(Expr::Ident(name)),
+            Some(Token::Punct("(")) => {
+                let expr = self.expression()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(Token::Punct("[")) => Ok(Expr::Array(self.list("]")?)),
+            Some(Token::Punct(punct)) => Err(format!("unexpected `{punct}`")),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+/// Check a parsed program for unknown names and functions.
+fn check_program(program: &[Stmt]) -> std::result::Result<(), String> {
+    fn declare<'a>(statement: &'a Stmt, names: &mut Vec<&'a str>) {
+        match statement {
+            Stmt::Declare(declarations) => {
+                names.extend(declarations.iter().map(|(name, _)| name.as_str()));
+            }
+            Stmt::Assign(name, ..) => names.push(name),
+            Stmt::If(_, then, otherwise) => {
+                declare(then, names);
+                if let Some(otherwise) = otherwise {
+                    declare(otherwise, names);
                 }
             }
+            Stmt::Block(body) => body.iter().for_each(|s| declare(s, names)),
+            Stmt::Expr(_) => {}
+        }
+    }
 
-            // Lottie-specific functions
-            "linear" => {
-                if args.len() >= 5 {
-                    let t = ExpressionEvaluator::new(args[0])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let t_min = ExpressionEvaluator::new(args[1])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let t_max = ExpressionEvaluator::new(args[2])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let v_min = ExpressionEvaluator::new(args[3])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let v_max = ExpressionEvaluator::new(args[4])
-                        .evaluate(ctx)
-                        .as_number()?;
-
-                    let normalized = (t - t_min) / (t_max - t_min);
-                    let clamped = normalized.clamp(0.0, 1.0);
-                    Some(Value::Number(v_min + clamped * (v_max - v_min)))
+    fn statement(s: &Stmt, names: &[&str]) -> std::result::Result<(), String> {
+        match s {
+            Stmt::Declare(declarations) => declarations
+                .iter()
+                .filter_map(|(_, init)| init.as_ref())
+                .try_for_each(|init| expr(init, names)),
+            Stmt::Assign(_, _, value) | Stmt::Expr(value) => expr(value, names),
+            Stmt::If(condition, then, otherwise) => {
+                expr(condition, names)?;
+                statement(then, names)?;
+                otherwise
+                    .as_ref()
+                    .map_or(Ok(()), |otherwise| statement(otherwise, names))
+            }
+            Stmt::Block(body) => body.iter().try_for_each(|s| statement(s, names)),
+        }
+    }
+
+    fn expr(e: &Expr, names: &[&str]) -> std::result::Result<(), String> {
+        match e {
+            Expr::Number(_) | Expr::Str(_) => Ok(()),
+            Expr::Ident(name) => {
+                if GLOBALS.contains(&name.as_str()) || names.contains(&name.as_str()) {
+                    Ok(())
                 } else {
-                    None
+                    Err(format!("unknown name `{name}`"))
                 }
             }
-            "ease" | "easeIn" | "easeOut" | "easeInOut" => {
-                if args.len() >= 5 {
-                    let t = ExpressionEvaluator::new(args[0])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let t_min = ExpressionEvaluator::new(args[1])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let t_max = ExpressionEvaluator::new(args[2])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let v_min = ExpressionEvaluator::new(args[3])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let v_max = ExpressionEvaluator::new(args[4])
-                        .evaluate(ctx)
-                        .as_number()?;
-
-                    let normalized = ((t - t_min) / (t_max - t_min)).clamp(0.0, 1.0);
-                    let eased = match name {
-                        "easeIn" => normalized * normalized,
-                        "easeOut" => 1.0 - (1.0 - normalized) * (1.0 - normalized),
-                        "easeInOut" => {
-                            if normalized < 0.5 {
-                                2.0 * normalized * normalized
-                            } else {
-                                1.0 - (-2.0 * normalized + 2.0).powi(2) / 2.0
+            Expr::Array(items) => items.iter().try_for_each(|item| expr(item, names)),
+            Expr::Unary(_, operand) | Expr::Member(operand, _) => expr(operand, names),
+            Expr::Binary(_, a, b) | Expr::And(a, b) | Expr::Or(a, b) | Expr::Index(a, b) => {
+                expr(a, names)?;
+                expr(b, names)
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                expr(condition, names)?;
+                expr(then, names)?;
+                expr(otherwise, names)
+            }
+            Expr::Call(callee, args) => {
+                match callee.as_ref() {
+                    Expr::Ident(name) => {
+                        if !FUNCTIONS.contains(&name.as_str())
+                            && !MATH_FUNCTIONS.contains(&name.as_str())
+                        {
+                            return Err(format!("`{name}()`"));
+                        }
+                    }
+                    Expr::Member(object, method) => {
+                        let known = match object.as_ref() {
+                            Expr::Ident(math) if math == "Math" => {
+                                MATH_FUNCTIONS.contains(&method.as_str())
                             }
+                            _ => method == "layer",
+                        };
+                        if !known {
+                            return Err(format!("`.{method}()`"));
                         }
-                        _ => normalized, // Default to linear
-                    };
-                    Some(Value::Number(v_min + eased * (v_max - v_min)))
-                } else {
-                    None
+                        expr(object, names)?;
+                    }
+                    _ => return Err("calls of computed functions".to_string()),
                 }
+                args.iter().try_for_each(|arg| expr(arg, names))
             }
-            "clamp" => {
-                if args.len() >= 3 {
-                    let value = ExpressionEvaluator::new(args[0])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let min = ExpressionEvaluator::new(args[1])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let max = ExpressionEvaluator::new(args[2])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    Some(Value::Number(value.clamp(min, max)))
-                } else {
-                    None
+        }
+    }
+
+    let mut names = Vec::new();
+    program.iter().for_each(|s| declare(s, &mut names));
+    program.iter().try_for_each(|s| statement(s, &names))
+}
+
+/// Runtime value, including the composition objects links walk through.
+#[derive(Debug, Clone)]
+enum Operand {
+    /// Plain value.
+    Value(Value),
+    /// The `Math` object.
+    Math,
+    /// `thisComp`.
+    Comp,
+    /// A layer, by position in the linked layers.
+    Layer(usize),
+    /// A layer's transform group.
+    Transform(usize),
+}
+
+/// Evaluation state of one program run.
+struct Scope<'a> {
+    ctx: &'a ExpressionContext,
+    locals: HashMap<String, Operand>,
+}
+
+impl Scope<'_> {
+    /// Run a program; its result is `$bm_rt` if set, else the last
+    /// expression statement.
+    fn run(&mut self, program: &[Stmt]) -> std::result::Result<Value, String> {
+        let mut result = Value::Null;
+        for statement in program {
+            if let Some(value) = self.statement(statement)? {
+                result = value;
+            }
+        }
+        match self.locals.remove("$bm_rt") {
+            Some(Operand::Value(Value::Null)) | None => Ok(result),
+            Some(Operand::Value(value)) => Ok(value),
+            Some(_) => Err("composition objects are not values".to_string()),
+        }
+    }
+
+    fn statement(&mut self, statement: &Stmt) -> std::result::Result<Option<Value>, String> {
+        match statement {
+            Stmt::Declare(declarations) => {
+                for (name, init) in declarations {
+                    let value = match init {
+                        Some(init) => self.eval(init)?,
+                        None => Operand::Value(Value::Null),
+                    };
+                    self.locals.insert(name.clone(), value);
                 }
+                Ok(None)
             }
-            "random" => {
-                // Simple pseudo-random based on time
-                let seed = if !args.is_empty() {
-                    ExpressionEvaluator::new(args[0])
-                        .evaluate(ctx)
-                        .as_number()
-                        .unwrap_or(0.0)
-                } else {
-                    ctx.time
+            Stmt::Assign(name, op, value) => {
+                let value = match op {
+                    Some(op) => {
+                        let current = self.to_value(self.lookup(name)?)?;
+                        Operand::Value(binary(*op, &current, &self.value(value)?)?)
+                    }
+                    None => self.eval(value)?,
                 };
-                Some(Value::Number(pseudo_random(seed)))
-            }
-            "wiggle" => {
-                if args.len() >= 2 {
-                    let freq = ExpressionEvaluator::new(args[0])
-                        .evaluate(ctx)
-                        .as_number()?;
-                    let amp = ExpressionEvaluator::new(args[1])
-                        .evaluate(ctx)
-                        .as_number()?;
-
-                    // Simple wiggle approximation
-                    let t = ctx.time * freq;
-                    let noise = (t.sin() * 0.5 + (t * 2.3).cos() * 0.3 + (t * 5.7).sin() * 0.2);
-                    Some(Value::Number(noise * amp))
+                self.locals.insert(name.clone(), value);
+                Ok(None)
+            }
+            Stmt::If(condition, then, otherwise) => {
+                if self.value(condition)?.as_bool() {
+                    self.statement(then)
+                } else if let Some(otherwise) = otherwise {
+                    self.statement(otherwise)
                 } else {
-                    None
+                    Ok(None)
+                }
+            }
+            Stmt::Block(body) => {
+                let mut result = None;
+                for statement in body {
+                    if let Some(value) = self.statement(statement)? {
+                        result = Some(value);
+                    }
+                }
+                Ok(result)
+            }
+            Stmt::Expr(expr) => self.value(expr).map(Some),
+        }
+    }
+
+    fn value(&mut self, expr: &Expr) -> std::result::Result<Value, String> {
+        let operand = self.eval(expr)?;
+        self.to_value(operand)
+    }
+
+    fn to_value(&self, operand: Operand) -> std::result::Result<Value, String> {
+        match operand {
+            Operand::Value(value) => Ok(value),
+            _ => Err("composition objects are not values".to_string()),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> std::result::Result<Operand, String> {
+        let value = match expr {
+            Expr::Number(n) => Value::Number(*n),
+            Expr::Str(s) => Value::String(s.clone()),
+            Expr::Ident(name) => return self.lookup(name),
+            Expr::Array(items) => {
+                let mut values = Vec::with_capacity(items.len());
+                for item in items {
+                    let value = self.value(item)?;
+                    values.push(value.as_number().ok_or("nested arrays")?);
+                }
+                Value::Array(values)
+            }
+            Expr::Unary(op, operand) => {
+                let operand = self.value(operand)?;
+                match (op, operand) {
+                    (UnaryOp::Not, operand) => Value::Bool(!operand.as_bool()),
+                    (UnaryOp::Neg, Value::Array(values)) => {
+                        Value::Array(values.iter().map(|v| -v).collect())
+                    }
+                    (op, operand) => {
+                        let n = operand.as_number().ok_or("non-numeric operand")?;
+                        Value::Number(if matches!(op, UnaryOp::Neg) { -n } else { n })
+                    }
+                }
+            }
+            Expr::Binary(op, a, b) => {
+                let a = self.value(a)?;
+                let b = self.value(b)?;
+                binary(*op, &a, &b)?
+            }
+            Expr::And(a, b) => {
+                let a = self.value(a)?;
+                if a.as_bool() { self.value(b)? } else { a }
+            }
+            Expr::Or(a, b) => {
+                let a = self.value(a)?;
+                if a.as_bool() { a } else { self.value(b)? }
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                if self.value(condition)?.as_bool() {
+                    return self.eval(then);
+                }
+                return self.eval(otherwise);
+            }
+            Expr::Member(object, name) => {
+                let object = self.eval(object)?;
+                return self.member(object, name);
+            }
+            Expr::Index(object, index) => {
+                let object = self.value(object)?;
+                let index = self.value(index)?.as_number().ok_or("non-numeric index")?;
+                match object {
+                    Value::Array(values) if index >= 0.0 => values
+                        .get(index as usize)
+                        .map_or(Value::Null, |v| Value::Number(*v)),
+                    _ => return Err("indexing a non-array".to_string()),
+                }
+            }
+            Expr::Call(callee, args) => return self.call(callee, args),
+        };
+        Ok(Operand::Value(value))
+    }
+
+    fn lookup(&self, name: &str) -> std::result::Result<Operand, String> {
+        if let Some(operand) = self.locals.get(name) {
+            return Ok(operand.clone());
+        }
+        if let Some(value) = self.ctx.get_variable(name) {
+            return Ok(Operand::Value(value.clone()));
+        }
+        let value = match name {
+            "time" => Value::Number(self.ctx.time),
+            "frame" => Value::Number(self.ctx.frame),
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "value" => Value::Null,
+            "Math" => return Ok(Operand::Math),
+            "thisComp" => return Ok(Operand::Comp),
+            _ => return Err(format!("unknown name `{name}`")),
+        };
+        Ok(Operand::Value(value))
+    }
+
+    fn member(&self, object: Operand, name: &str) -> std::result::Result<Operand, String> {
+        let value = match (object, name) {
+            (Operand::Math, "PI") => Value::Number(std::f32::consts::PI),
+            (Operand::Math, "E") => Value::Number(std::f32::consts::E),
+            (Operand::Comp, "width") => Value::Number(self.ctx.width),
+            (Operand::Comp, "height") => Value::Number(self.ctx.height),
+            (Operand::Comp, "frameDuration") if self.ctx.fps > 0.0 => {
+                Value::Number(1.0 / self.ctx.fps)
+            }
+            (Operand::Comp, "numLayers") => Value::Number(self.linked_layers()?.len() as Scalar),
+            (Operand::Layer(layer), "transform") => return Ok(Operand::Transform(layer)),
+            (Operand::Layer(layer), "name") => {
+                Value::String(self.linked_layers()?[layer].name.clone())
+            }
+            (Operand::Layer(layer), "index") => {
+                Value::Number(self.linked_layers()?[layer].index as Scalar)
+            }
+            (Operand::Layer(layer) | Operand::Transform(layer), property) => {
+                self.link(layer, property)?
+            }
+            (Operand::Value(Value::Array(values)), "length") => {
+                Value::Number(values.len() as Scalar)
+            }
+            (Operand::Value(Value::String(s)), "length") => {
+                Value::Number(s.chars().count() as Scalar)
+            }
+            _ => return Err(format!("unknown property `{name}`")),
+        };
+        Ok(Operand::Value(value))
+    }
+
+    fn linked_layers(&self) -> std::result::Result<&[LinkedLayer], String> {
+        self.ctx
+            .environment
+            .as_deref()
+            .map(ExpressionEnvironment::layers)
+            .ok_or_else(|| "`thisComp` outside a composition".to_string())
+    }
+
+    /// Resolve a linked layer's transform property at the current frame.
+    fn link(&self, layer: usize, property: &str) -> std::result::Result<Value, String> {
+        if self.ctx.depth >= MAX_LINK_DEPTH {
+            return Err("property links nested too deeply".to_string());
+        }
+        let transform = &self.linked_layers()?[layer].transform;
+        let (frame, depth) = (self.ctx.frame, self.ctx.depth + 1);
+
+        let value = match property {
+            "anchorPoint" => transform.anchor.evaluate(frame, depth),
+            "position" => match (&transform.position_x, &transform.position_y) {
+                (Some(x), Some(y)) => {
+                    let x = x.evaluate(frame, depth).as_scalar().unwrap_or(0.0);
+                    let y = y.evaluate(frame, depth).as_scalar().unwrap_or(0.0);
+                    return Ok(Value::Array(vec![x, y]));
+                }
+                _ => transform.position.evaluate(frame, depth),
+            },
+            "scale" => transform.scale.evaluate(frame, depth),
+            "rotation" => transform.rotation.evaluate(frame, depth),
+            "opacity" => transform.opacity.evaluate(frame, depth),
+            _ => return Err(format!("unknown property `{property}`")),
+        };
+        Ok(value.to_expression_value())
+    }
+
+    fn call(&mut self, callee: &Expr, args: &[Expr]) -> std::result::Result<Operand, String> {
+        let mut values = Vec::with_capacity(args.len());
+        let callee = match callee {
+            Expr::Member(object, method) => {
+                let object = self.eval(object)?;
+                for arg in args {
+                    values.push(self.value(arg)?);
+                }
+                match (object, method.as_str()) {
+                    (Operand::Comp, "layer") => return self.find_layer(values.first()),
+                    (Operand::Math, name) if MATH_FUNCTIONS.contains(&name) => name,
+                    _ => return Err(format!("`.{method}()`")),
                 }
             }
-            "loopOut" | "loopIn" => {
-                // Simplified loop - just return time
-                Some(Value::Number(ctx.time))
+            Expr::Ident(name) => {
+                for arg in args {
+                    values.push(self.value(arg)?);
+                }
+                name.as_str()
+            }
+            _ => return Err("calls of computed functions".to_string()),
+        };
+
+        if MATH_FUNCTIONS.contains(&callee) {
+            return math(callee, &values).map(Operand::Value);
+        }
+        self.function(callee, &values).map(Operand::Value)
+    }
+
+    fn find_layer(&self, key: Option<&Value>) -> std::result::Result<Operand, String> {
+        let layers = self.linked_layers()?;
+        let position = match key {
+            Some(Value::Number(index)) => layers.iter().position(|l| l.index as Scalar == *index),
+            Some(Value::String(name)) => layers.iter().position(|l| &l.name == name),
+            _ => return Err("`thisComp.layer()` takes a name or an index".to_string()),
+        };
+        position
+            .map(Operand::Layer)
+            .ok_or_else(|| format!("no layer {key:?}"))
+    }
+
+    /// Call a built-in function.
+    fn function(&self, name: &str, args: &[Value]) -> std::result::Result<Value, String> {
+        let arg = |i: usize| number_arg(args, i);
+        let pair = || -> std::result::Result<(&Value, &Value), String> {
+            match args {
+                [a, b, ..] => Ok((a, b)),
+                _ => Err(format!("`{name}()` takes two arguments")),
             }
+        };
 
-            // Vector functions
+        let value = match name {
+            "linear" | "ease" | "easeIn" | "easeOut" | "easeInOut" => interpolate(name, args)?,
+            "clamp" => Value::Number(arg(0)?.clamp(arg(1)?, arg(2)?)),
+            "random" => {
+                let r = pseudo_random(self.ctx.time);
+                Value::Number(match args.len() {
+                    0 => r,
+                    1 => r * arg(0)?,
+                    _ => arg(0)? + r * (arg(1)? - arg(0)?),
+                })
+            }
+            "wiggle" => self.wiggle(args)?,
             "length" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                if let Some(arr) = arg.as_array() {
-                    let sum: Scalar = arr.iter().map(|x| x * x).sum();
-                    Some(Value::Number(sum.sqrt()))
-                } else {
-                    arg.as_number().map(|n| Value::Number(n.abs()))
+                let (a, b) = match args {
+                    [a] => (a.clone(), Value::Number(0.0)),
+                    _ => {
+                        let (a, b) = pair()?;
+                        (a.clone(), b.clone())
+                    }
+                };
+                match binary(BinaryOp::Sub, &a, &b)? {
+                    Value::Array(values) => {
+                        Value::Number(values.iter().map(|x| x * x).sum::<Scalar>().sqrt())
+                    }
+                    other => Value::Number(other.as_number().unwrap_or(0.0).abs()),
                 }
             }
-            "normalize" => {
-                let arg = ExpressionEvaluator::new(args.first()?).evaluate(ctx);
-                if let Some(arr) = arg.as_array() {
-                    let len: Scalar = arr.iter().map(|x| x * x).sum::<Scalar>().sqrt();
+            "normalize" => match args.first() {
+                Some(Value::Array(values)) => {
+                    let len = values.iter().map(|x| x * x).sum::<Scalar>().sqrt();
                     if len > 0.0 {
-                        let normalized: Vec<Scalar> = arr.iter().map(|x| x / len).collect();
-                        Some(Value::Array(normalized))
+                        Value::Array(values.iter().map(|x| x / len).collect())
                     } else {
-                        Some(arg)
+                        Value::Array(values.clone())
                     }
-                } else {
-                    Some(Value::Number(1.0))
                 }
+                _ => Value::Number(1.0),
+            },
+            "add" | "sum" | "$bm_sum" => binary(BinaryOp::Add, pair()?.0, pair()?.1)?,
+            "sub" | "$bm_sub" => binary(BinaryOp::Sub, pair()?.0, pair()?.1)?,
+            "mul" | "$bm_mul" => binary(BinaryOp::Mul, pair()?.0, pair()?.1)?,
+            "div" | "$bm_div" => binary(BinaryOp::Div, pair()?.0, pair()?.1)?,
+            "$bm_mod" => binary(BinaryOp::Rem, pair()?.0, pair()?.1)?,
+            "$bm_neg" => binary(
+                BinaryOp::Mul,
+                args.first().ok_or("missing argument")?,
+                &Value::Number(-1.0),
+            )?,
+            "degreesToRadians" => Value::Number(arg(0)?.to_radians()),
+            "radiansToDegrees" => Value::Number(arg(0)?.to_degrees()),
+            "framesToTime" => {
+                let fps = args
+                    .get(1)
+                    .and_then(Value::as_number)
+                    .unwrap_or(self.ctx.fps);
+                Value::Number(if fps > 0.0 { arg(0)? / fps } else { 0.0 })
+            }
+            "timeToFrames" => {
+                let time = args
+                    .first()
+                    .and_then(Value::as_number)
+                    .unwrap_or(self.ctx.time);
+                let fps = args
+                    .get(1)
+                    .and_then(Value::as_number)
+                    .unwrap_or(self.ctx.fps);
+                Value::Number(time * fps)
             }
+            _ => return Err(format!("`{name}()`")),
+        };
+        Ok(value)
+    }
 
-            _ => None,
+    /// `wiggle(freq, amp, octaves = 1, amp_mult = 0.5, t = time)`: the
+    /// property value plus smooth noise in every dimension.
+    fn wiggle(&self, args: &[Value]) -> std::result::Result<Value, String> {
+        let frequency = number_arg(args, 0)?;
+        let amplitude = number_arg(args, 1)?;
+        let octaves = args
+            .get(2)
+            .and_then(Value::as_number)
+            .unwrap_or(1.0)
+            .clamp(1.0, 8.0) as i32;
+        let amplitude_multiplier = args.get(3).and_then(Value::as_number).unwrap_or(0.5);
+        let time = args
+            .get(4)
+            .and_then(Value::as_number)
+            .unwrap_or(self.ctx.time);
+
+        let offset = |dimension: usize| -> Scalar {
+            (0..octaves)
+                .map(|octave| {
+                    let t = time * frequency * (2.0 as Scalar).powi(octave)
+                        + dimension as Scalar * 17.31;
+                    let noise = t.sin() * 0.5 + (t * 2.3).cos() * 0.3 + (t * 5.7).sin() * 0.2;
+                    noise * amplitude * amplitude_multiplier.powi(octave)
+                })
+                .sum()
+        };
+
+        Ok(match self.to_value(self.lookup("value")?)? {
+            Value::Array(values) => Value::Array(
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(dimension, v)| v + offset(dimension))
+                    .collect(),
+            ),
+            value => Value::Number(value.as_number().unwrap_or(0.0) + offset(0)),
+        })
+    }
+}
+
+/// Get a numeric argument.
+fn number_arg(args: &[Value], i: usize) -> std::result::Result<Scalar, String> {
+    args.get(i)
+        .and_then(Value::as_number)
+        .ok_or_else(|| format!("argument {} must be a number", i + 1))
+}
+
+/// Call a `Math` function.
+fn math(name: &str, args: &[Value]) -> std::result::Result<Value, String> {
+    let arg = |i: usize| number_arg(args, i);
+    let n = match name {
+        "sin" => arg(0)?.sin(),
+        "cos" => arg(0)?.cos(),
+        "tan" => arg(0)?.tan(),
+        "asin" => arg(0)?.asin(),
+        "acos" => arg(0)?.acos(),
+        "atan" => arg(0)?.atan(),
+        "atan2" => arg(0)?.atan2(arg(1)?),
+        "abs" => arg(0)?.abs(),
+        "floor" => arg(0)?.floor(),
+        "ceil" => arg(0)?.ceil(),
+        // JavaScript rounds halves towards positive infinity.
+        "round" => (arg(0)? + 0.5).floor(),
+        "sqrt" => arg(0)?.sqrt(),
+        "pow" => arg(0)?.powf(arg(1)?),
+        "exp" => arg(0)?.exp(),
+        "log" => arg(0)?.ln(),
+        "min" | "max" => {
+            let mut result = arg(0)?;
+            for i in 1..args.len() {
+                result = if name == "min" {
+                    result.min(arg(i)?)
+                } else {
+                    result.max(arg(i)?)
+                };
+            }
+            result
+        }
+        _ => return Err(format!("`Math.{name}()`")),
+    };
+    Ok(Value::Number(n))
+}
+
+/// `linear`/`ease*(t, [tMin, tMax,] value1, value2)`.
+fn interpolate(name: &str, args: &[Value]) -> std::result::Result<Value, String> {
+    let t = number_arg(args, 0)?;
+    let (t_min, t_max, from, to) = match args {
+        [_, from, to] => (0.0, 1.0, from, to),
+        [_, _, _, from, to] => (number_arg(args, 1)?, number_arg(args, 2)?, from, to),
+        _ => return Err(format!("`{name}()` takes 3 or 5 arguments")),
+    };
+
+    let normalized = if t_max == t_min {
+        if t < t_min { 0.0 } else { 1.0 }
+    } else {
+        ((t - t_min) / (t_max - t_min)).clamp(0.0, 1.0)
+    };
+    let eased = match name {
+        "easeIn" => normalized * normalized,
+        "easeOut" => 1.0 - (1.0 - normalized) * (1.0 - normalized),
+        "easeInOut" => {
+            if normalized < 0.5 {
+                2.0 * normalized * normalized
+            } else {
+                1.0 - (-2.0 * normalized + 2.0).powi(2) / 2.0
+            }
+        }
+        _ => normalized,
+    };
+
+    match (from, to) {
+        (Value::Array(a), Value::Array(b)) => Ok(Value::Array(
+            (0..a.len().max(b.len()))
+                .map(|i| {
+                    let a = a.get(i).copied().unwrap_or(0.0);
+                    let b = b.get(i).copied().unwrap_or(0.0);
+                    a + (b - a) * eased
+                })
+                .collect(),
+        )),
+        _ => {
+            let a = from.as_number().ok_or("cannot interpolate these values")?;
+            let b = to.as_number().ok_or("cannot interpolate these values")?;
+            Ok(Value::Number(a + (b - a) * eased))
+        }
+    }
+}
+
+/// Apply a binary operator with the vector semantics of Lottie expressions.
+fn binary(op: BinaryOp, a: &Value, b: &Value) -> std::result::Result<Value, String> {
+    match op {
+        BinaryOp::Eq | BinaryOp::Ne => {
+            let equal = match (a, b) {
+                (Value::String(a), Value::String(b)) => a == b,
+                (Value::Array(a), Value::Array(b)) => a == b,
+                (Value::Null, Value::Null) => true,
+                _ => match (a.as_number(), b.as_number()) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => false,
+                },
+            };
+            return Ok(Value::Bool(equal == (op == BinaryOp::Eq)));
+        }
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            let (a, b) = a
+                .as_number()
+                .zip(b.as_number())
+                .ok_or("comparing non-numbers")?;
+            return Ok(Value::Bool(match op {
+                BinaryOp::Lt => a < b,
+                BinaryOp::Le => a <= b,
+                BinaryOp::Gt => a > b,
+                _ => a >= b,
+            }));
+        }
+        _ => {}
+    }
+
+    let scalar = |a: Scalar, b: Scalar| match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        _ if b == 0.0 => 0.0,
+        BinaryOp::Div => a / b,
+        _ => a % b,
+    };
+
+    match (a, b) {
+        (Value::String(_), _) | (_, Value::String(_)) if op == BinaryOp::Add => {
+            Ok(Value::String(format!("{}{}", a.to_text(), b.to_text())))
+        }
+        (Value::Array(a), Value::Array(b)) if matches!(op, BinaryOp::Add | BinaryOp::Sub) => {
+            Ok(Value::Array(
+                (0..a.len().max(b.len()))
+                    .map(|i| {
+                        scalar(
+                            a.get(i).copied().unwrap_or(0.0),
+                            b.get(i).copied().unwrap_or(0.0),
+                        )
+                    })
+                    .collect(),
+            ))
+        }
+        (Value::Array(values), other) => {
+            let n = other.as_number().ok_or("unsupported vector operation")?;
+            Ok(Value::Array(match op {
+                // Adding a number to a vector offsets its first component.
+                BinaryOp::Add | BinaryOp::Sub => values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| if i == 0 { scalar(*v, n) } else { *v })
+                    .collect(),
+                _ => values.iter().map(|v| scalar(*v, n)).collect(),
+            }))
+        }
+        (other, Value::Array(values)) if op != BinaryOp::Div && op != BinaryOp::Rem => {
+            let n = other.as_number().ok_or("unsupported vector operation")?;
+            Ok(Value::Array(match op {
+                BinaryOp::Add | BinaryOp::Sub => values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| if i == 0 { scalar(n, *v) } else { *v })
+                    .collect(),
+                _ => values.iter().map(|v| scalar(n, *v)).collect(),
+            }))
+        }
+        _ => {
+            let a = a.as_number().ok_or("non-numeric operand")?;
+            let b = b.as_number().ok_or("non-numeric operand")?;
+            Ok(Value::Number(scalar(a, b)))
         }
     }
 }
@@ -581,6 +1664,121 @@ mod tests {
         assert_eq!(result.as_array(), Some(&[1.0, 2.0, 3.0][..]));
     }
 
+    #[test]
+    fn test_precedence_and_vectors() {
+        let ctx = ExpressionContext::new();
+
+        let eval = ExpressionEvaluator::new("2 + 3 * 4 - (1 + 1) / 2");
+        assert_eq!(eval.evaluate(&ctx).as_number(), Some(13.0));
+
+        let eval = ExpressionEvaluator::new("[10, 20] + [1, 2] * 2");
+        assert_eq!(eval.evaluate(&ctx).as_array(), Some(&[12.0, 24.0][..]));
+
+        let eval = ExpressionEvaluator::new("linear(0.5, [0, 0], [10, 20])");
+        assert_eq!(eval.evaluate(&ctx).as_array(), Some(&[5.0, 10.0][..]));
+    }
+
+    #[test]
+    fn test_statements() {
+        let mut ctx = ExpressionContext::new();
+        ctx.set_time(2.0, 30.0);
+        ctx.set_variable("value", Value::Number(40.0));
+
+        let eval = ExpressionEvaluator::new(
+            "var $bm_rt;\nvar speed = 10; // per second\nif (time > 1) { $bm_rt = $bm_sum(value, time * speed); } else { $bm_rt = value; }",
+        );
+        assert!(eval.check().is_ok());
+        assert_eq!(eval.evaluate(&ctx).as_number(), Some(60.0));
+
+        let eval = ExpressionEvaluator::new("time < 1 ? 0 : Math.max(value, 100)");
+        assert_eq!(eval.evaluate(&ctx).as_number(), Some(100.0));
+    }
+
+    #[test]
+    fn test_wiggle_offsets_value() {
+        let mut ctx = ExpressionContext::new();
+        ctx.set_variable("value", Value::Array(vec![100.0, 100.0]));
+        let eval = ExpressionEvaluator::new("wiggle(3, 10)");
+
+        let mut samples = Vec::new();
+        for i in 0..4 {
+            ctx.set_time(i as Scalar * 0.25, 30.0);
+            let result = eval.evaluate(&ctx);
+            let values = result.as_array().unwrap();
+            assert!(values.iter().all(|v| (90.0..=110.0).contains(v)));
+            samples.push(values[0]);
+        }
+        assert!(samples.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn test_unsupported_expressions() {
+        let ctx = ExpressionContext::new();
+
+        for source in [
+            "loopOut('cycle')",
+            "thisLayer.opacity",
+            "for (var i = 0; i < 3; i++) {}",
+            "value +",
+        ] {
+            let eval = ExpressionEvaluator::new(source);
+            assert!(matches!(
+                eval.check(),
+                Err(SkottieError::UnsupportedFeature(_))
+            ));
+            assert!(matches!(eval.evaluate(&ctx), Value::Null));
+        }
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let ctx = ExpressionContext::new();
+
+        let depth = MAX_NESTING_DEPTH - 2;
+        let nested = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        let eval = ExpressionEvaluator::new(&nested);
+        assert_eq!(eval.evaluate(&ctx).as_number(), Some(1.0));
+
+        // Deep enough to overflow the stack without the limit
+        let n = 100_000;
+        for source in [
+            format!("{}1{}", "(".repeat(n), ")".repeat(n)),
+            format!("{}1", "-".repeat(n)),
+            format!("{}1", "1 + ".repeat(n)),
+            format!("{}{}", "{".repeat(n), "}".repeat(n)),
+            format!("Math{}", ".x".repeat(n)),
+        ] {
+            let eval = ExpressionEvaluator::new(&source);
+            assert!(eval.check().is_err());
+            assert!(matches!(eval.evaluate(&ctx), Value::Null));
+        }
+    }
+
+    #[test]
+    fn test_property_expression_environment() {
+        let environment = ExpressionEnvironment::new(30.0, 100.0, 100.0);
+        let expression = PropertyExpression::new_with_environment("time * 10", &environment);
+        assert_eq!(environment.expression_count(), 1);
+        assert_eq!(
+            expression.evaluate(60.0, Value::Null, 0).as_number(),
+            Some(20.0)
+        );
+
+        // Without its composition the expression leaves the value alone
+        drop(environment);
+        assert!(matches!(
+            expression.evaluate(60.0, Value::Null, 0),
+            Value::Null
+        ));
+
+        // Nor does one created without a composition
+        let unbound = PropertyExpression::new("time * 10");
+        assert!(matches!(
+            unbound.evaluate(60.0, Value::Null, 0),
+            Value::Null
+        ));
+    }
+
     #[test]
     fn test_expression_compiler() {
         let mut compiler = ExpressionCompiler::new();
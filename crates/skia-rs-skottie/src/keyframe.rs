@@ -6,8 +6,10 @@
 //! - Hold keyframes
 //! - Multi-dimensional values
 
+use crate::expression::{ExpressionEnvironment, PropertyExpression, Value};
 use crate::model::{AnimatedValue, KeyframeModel, TangentModel, TangentValue};
 use skia_rs_core::Scalar;
+use std::sync::Arc;
 
 /// Easing function for keyframe interpolation.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -162,6 +164,8 @@ pub enum KeyframeValue {
     Color([Scalar; 4]),
     /// Path data.
     Path(PathData),
+    /// Values longer than a color, such as gradient color stops.
+    Array(Vec<Scalar>),
 }
 
 impl KeyframeValue {
@@ -202,6 +206,58 @@ impl KeyframeValue {
         }
     }
 
+    /// Get the numeric components of any non-path value.
+    pub fn as_array(&self) -> Option<&[Scalar]> {
+        match self {
+            KeyframeValue::Scalar(v) => Some(std::slice::from_ref(v)),
+            KeyframeValue::Vec2(v) => Some(v),
+            KeyframeValue::Vec3(v) => Some(v),
+            KeyframeValue::Color(v) => Some(v),
+            KeyframeValue::Array(v) => Some(v),
+            KeyframeValue::Path(_) => None,
+        }
+    }
+
+    /// Convert to an expression value.
+    pub(crate) fn to_expression_value(&self) -> Value {
+        match self.as_array() {
+            Some([v]) => Value::Number(*v),
+            Some(values) => Value::Array(values.to_vec()),
+            None => Value::Null,
+        }
+    }
+
+    /// Take an expression result in place of this value, keeping this
+    /// value's shape.
+    ///
+    /// Missing vector components keep their current values; results that
+    /// cannot take this shape are rejected.
+    fn with_expression_value(&self, value: &Value) -> Option<KeyframeValue> {
+        fn resized<const N: usize>(current: &[Scalar; N], values: &[Scalar]) -> [Scalar; N] {
+            let mut resized = *current;
+            for (slot, v) in resized.iter_mut().zip(values) {
+                *slot = *v;
+            }
+            resized
+        }
+
+        let value = match (self, value) {
+            (KeyframeValue::Scalar(_), Value::Number(n)) => KeyframeValue::Scalar(*n),
+            (_, Value::Array(values)) => match self {
+                KeyframeValue::Vec2(v) => KeyframeValue::Vec2(resized(v, values)),
+                KeyframeValue::Vec3(v) => KeyframeValue::Vec3(resized(v, values)),
+                KeyframeValue::Color(v) => KeyframeValue::Color(resized(v, values)),
+                KeyframeValue::Array(_) => KeyframeValue::Array(values.clone()),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        value
+            .as_array()
+            .is_some_and(|values| values.iter().all(|v| v.is_finite()))
+            .then_some(value)
+    }
+
     /// Interpolate between two values.
     pub fn lerp(&self, other: &KeyframeValue, t: Scalar) -> KeyframeValue {
         match (self, other) {
@@ -223,6 +279,9 @@ impl KeyframeValue {
                 a[3] + (b[3] - a[3]) * t,
             ]),
             (KeyframeValue::Path(a), KeyframeValue::Path(b)) => KeyframeValue::Path(a.lerp(b, t)),
+            (KeyframeValue::Array(a), KeyframeValue::Array(b)) if a.len() == b.len() => {
+                KeyframeValue::Array(a.iter().zip(b).map(|(a, b)| a + (b - a) * t).collect())
+            }
             // Mismatched types - return first
             _ => self.clone(),
         }
@@ -310,6 +369,8 @@ impl Default for PathData {
 pub struct AnimatedProperty {
     /// Keyframes (sorted by time).
     pub keyframes: Vec<Keyframe>,
+    /// Expression computing the value from the keyframed one.
+    pub expression: Option<PropertyExpression>,
 }
 
 impl AnimatedProperty {
@@ -317,6 +378,7 @@ impl AnimatedProperty {
     pub fn new() -> Self {
         Self {
             keyframes: Vec::new(),
+            expression: None,
         }
     }
 
@@ -324,6 +386,7 @@ impl AnimatedProperty {
     pub fn static_value(value: KeyframeValue) -> Self {
         Self {
             keyframes: vec![Keyframe::new(0.0, value)],
+            expression: None,
         }
     }
 
@@ -336,11 +399,29 @@ impl AnimatedProperty {
 
     /// Check if this property is animated.
     pub fn is_animated(&self) -> bool {
-        self.keyframes.len() > 1
+        self.keyframes.len() > 1 || self.expression.is_some()
     }
 
     /// Get the value at a specific frame.
+    ///
+    /// A property with an expression evaluates it against the keyframed
+    /// value, and keeps the keyframed value if the expression is unsupported.
     pub fn value_at(&self, frame: Scalar) -> KeyframeValue {
+        self.evaluate(frame, 0)
+    }
+
+    /// Get the value at a frame, `depth` property links deep.
+    pub(crate) fn evaluate(&self, frame: Scalar, depth: u32) -> KeyframeValue {
+        let value = self.keyframe_value_at(frame);
+        let Some(expression) = &self.expression else {
+            return value;
+        };
+        let result = expression.evaluate(frame, value.to_expression_value(), depth);
+        value.with_expression_value(&result).unwrap_or(value)
+    }
+
+    /// Get the keyframed value at a specific frame.
+    fn keyframe_value_at(&self, frame: Scalar) -> KeyframeValue {
         if self.keyframes.is_empty() {
             return KeyframeValue::Scalar(0.0);
         }
@@ -385,7 +466,30 @@ impl AnimatedProperty {
     }
 
     /// Parse from Lottie animated value.
+    ///
+    /// An expression on the value isn't bound to any composition, so it
+    /// leaves the property at its keyframed value; use
+    /// [`from_lottie_with_environment`](Self::from_lottie_with_environment)
+    /// to evaluate it.
     pub fn from_lottie(value: &AnimatedValue) -> Self {
+        Self::from_lottie_with_environment(value, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie animated value, binding its expression, if any, to
+    /// `environment`.
+    pub fn from_lottie_with_environment(
+        value: &AnimatedValue,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
+        let mut prop = Self::from_lottie_keyframes(value);
+        prop.expression = value
+            .expression()
+            .map(|source| PropertyExpression::new_with_environment(source, environment));
+        prop
+    }
+
+    /// Parse the keyframes of a Lottie animated value.
+    fn from_lottie_keyframes(value: &AnimatedValue) -> Self {
         match value {
             AnimatedValue::Animated { keyframes, .. } => {
                 let mut prop = Self::new();
@@ -440,12 +544,8 @@ fn parse_keyframe_value(values: &[Scalar]) -> KeyframeValue {
         1 => KeyframeValue::Scalar(values[0]),
         2 => KeyframeValue::Vec2([values[0], values[1]]),
         3 => KeyframeValue::Vec3([values[0], values[1], values[2]]),
-        _ => KeyframeValue::Color([
-            values.get(0).copied().unwrap_or(0.0),
-            values.get(1).copied().unwrap_or(0.0),
-            values.get(2).copied().unwrap_or(0.0),
-            values.get(3).copied().unwrap_or(1.0),
-        ]),
+        4 => KeyframeValue::Color([values[0], values[1], values[2], values[3]]),
+        _ => KeyframeValue::Array(values.to_vec()),
     }
 }
 
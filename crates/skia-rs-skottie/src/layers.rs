@@ -8,6 +8,7 @@
 //! - Shape
 //! - Text
 
+use crate::expression::ExpressionEnvironment;
 use crate::keyframe::AnimatedProperty;
 use crate::mask::Mask;
 use crate::model::LayerModel;
@@ -15,6 +16,7 @@ use crate::shapes::{Shape, ShapeGroup};
 use crate::transform::Transform;
 use skia_rs_core::{Color, Scalar};
 use skia_rs_paint::BlendMode;
+use std::sync::Arc;
 
 /// Layer type enumeration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -111,6 +113,8 @@ pub struct Layer {
     pub matte_mode: Option<MatteMode>,
     /// Track matte layer index.
     pub matte_layer: Option<i32>,
+    /// Is a track matte, drawn only through the layer it mattes.
+    pub is_track_matte: bool,
     /// Time stretch factor.
     pub time_stretch: Scalar,
     /// Time remapping.
@@ -256,12 +260,20 @@ impl From<i32> for MatteMode {
 impl Layer {
     /// Parse from Lottie layer model.
     pub fn from_lottie(model: &LayerModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie layer model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &LayerModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         let layer_type = LayerType::from(model.layer_type);
 
         let transform = model
             .transform
             .as_ref()
-            .map(Transform::from_lottie)
+            .map(|transform| Transform::from_lottie_with_environment(transform, environment))
             .unwrap_or_default();
 
         let content = match layer_type {
@@ -285,8 +297,11 @@ impl Layer {
                 ref_id: model.ref_id.clone().unwrap_or_default(),
             }),
             LayerType::Shape => {
-                let shapes: Vec<Shape> =
-                    model.shapes.iter().filter_map(Shape::from_lottie).collect();
+                let shapes: Vec<Shape> = model
+                    .shapes
+                    .iter()
+                    .filter_map(|shape| Shape::from_lottie_with_environment(shape, environment))
+                    .collect();
                 LayerContent::Shape(ShapeContent { shapes })
             }
             LayerType::Text => {
@@ -331,7 +346,11 @@ impl Layer {
             _ => LayerContent::None,
         };
 
-        let masks = model.masks.iter().map(Mask::from_lottie).collect();
+        let masks = model
+            .masks
+            .iter()
+            .map(|mask| Mask::from_lottie_with_environment(mask, environment))
+            .collect();
 
         let blend_mode = match model.blend_mode {
             0 => BlendMode::SrcOver,
@@ -370,6 +389,7 @@ impl Layer {
             masks,
             matte_mode: model.track_matte_type.map(MatteMode::from),
             matte_layer: model.track_matte_layer,
+            is_track_matte: model.is_track_matte == Some(1),
             time_stretch: 1.0,
             time_remap: None,
         }
@@ -400,10 +420,25 @@ impl Layer {
         !self.masks.is_empty()
     }
 
-    /// Check if this is a matte layer.
+    /// Check if this layer is drawn through a track matte.
     pub fn is_matte_layer(&self) -> bool {
         self.matte_mode.is_some() && self.matte_mode != Some(MatteMode::None)
     }
+
+    /// Find this layer's track matte among its composition's `layers`,
+    /// where it sits at `position`.
+    ///
+    /// The matte is the layer named by [`matte_layer`](Self::matte_layer),
+    /// or else the layer above.
+    pub fn find_matte<'a>(&self, layers: &'a [Layer], position: usize) -> Option<&'a Layer> {
+        if !self.is_matte_layer() {
+            return None;
+        }
+        match self.matte_layer {
+            Some(index) => layers.iter().find(|layer| layer.index == index),
+            None => position.checked_sub(1).and_then(|above| layers.get(above)),
+        }
+    }
 }
 
 /// Parse a hex color string to Color.
@@ -465,6 +500,7 @@ mod tests {
             masks: Vec::new(),
             matte_mode: None,
             matte_layer: None,
+            is_track_matte: false,
             time_stretch: 1.0,
             time_remap: None,
         };
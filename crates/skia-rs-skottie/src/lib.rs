@@ -16,9 +16,9 @@
 //! ```ignore
 //! use skia_rs_skottie::Animation;
 //!
-//! let animation = Animation::from_json(json_string)?;
+//! let mut animation = Animation::from_json(json_string)?;
 //! animation.seek_frame(30.0);
-//! animation.render(&mut canvas);
+//! animation.render(&mut canvas, &animation.bounds());
 //! ```
 
 #![warn(missing_docs)]
@@ -5,10 +5,12 @@
 //! - Track mattes (Alpha, Luma)
 //! - Mask expansion and feathering
 
+use crate::expression::ExpressionEnvironment;
 use crate::keyframe::{AnimatedProperty, KeyframeValue, PathData};
 use crate::model::MaskModel;
 use skia_rs_core::Scalar;
 use skia_rs_path::{Path, PathBuilder};
+use std::sync::Arc;
 
 /// Mask mode (boolean operation).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -103,16 +105,24 @@ impl Mask {
 
     /// Parse from Lottie mask model.
     pub fn from_lottie(model: &MaskModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie mask model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &MaskModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: String::new(),
             mode: MaskMode::from(model.mode.as_str()),
-            path: AnimatedProperty::from_lottie(&model.path),
-            opacity: AnimatedProperty::from_lottie(&model.opacity),
+            path: AnimatedProperty::from_lottie_with_environment(&model.path, environment),
+            opacity: AnimatedProperty::from_lottie_with_environment(&model.opacity, environment),
             inverted: model.inverted,
             expansion: model
                 .expansion
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(0.0))),
         }
     }
@@ -123,9 +123,12 @@ pub struct LayerModel {
     /// Track matte type.
     #[serde(rename = "tt", default)]
     pub track_matte_type: Option<i32>,
-    /// Track matte layer.
-    #[serde(rename = "td", default)]
+    /// Track matte layer index (defaults to the layer above).
+    #[serde(rename = "tp", default)]
     pub track_matte_layer: Option<i32>,
+    /// Is a track matte of another layer.
+    #[serde(rename = "td", default)]
+    pub is_track_matte: Option<i32>,
     /// Effects.
     #[serde(rename = "ef", default)]
     pub effects: Vec<EffectModel>,
@@ -175,6 +178,9 @@ pub enum AnimatedValue {
         /// Keyframes.
         #[serde(rename = "k")]
         keyframes: Vec<KeyframeModel>,
+        /// Expression source.
+        #[serde(rename = "x", default)]
+        expression: Option<String>,
     },
     /// Static value (single keyframe).
     Static {
@@ -184,11 +190,25 @@ pub enum AnimatedValue {
         /// Value.
         #[serde(rename = "k")]
         value: serde_json::Value,
+        /// Expression source.
+        #[serde(rename = "x", default)]
+        expression: Option<String>,
     },
     /// Direct value.
     Direct(serde_json::Value),
 }
 
+impl AnimatedValue {
+    /// Get the expression source, if any.
+    pub fn expression(&self) -> Option<&str> {
+        match self {
+            AnimatedValue::Animated { expression, .. }
+            | AnimatedValue::Static { expression, .. } => expression.as_deref(),
+            AnimatedValue::Direct(_) => None,
+        }
+    }
+}
+
 impl Default for AnimatedValue {
     fn default() -> Self {
         AnimatedValue::Direct(serde_json::Value::Array(vec![serde_json::Value::Number(
@@ -304,7 +324,7 @@ pub struct ShapeModel {
     #[serde(rename = "sp", default)]
     pub gradient_start: Option<AnimatedValue>,
     /// Gradient end point.
-    #[serde(rename = "ep", default)]
+    #[serde(rename = "e", alias = "ep", default)]
     pub gradient_end: Option<AnimatedValue>,
     /// Gradient colors.
     #[serde(rename = "g", default)]
@@ -5,11 +5,15 @@
 
 use crate::animation::{Asset, PrecompAsset};
 use crate::layers::{Layer, LayerContent, MatteMode};
-use crate::shapes::{FillShape, GradientFillShape, Shape, ShapeGroup, StrokeShape, TrimPathShape};
-use skia_rs_core::{Color4f, Matrix, Rect, Scalar};
-use skia_rs_paint::{BlendMode, Paint, Style};
+use crate::shapes::{
+    FillShape, GradientFillShape, GradientStrokeShape, Shape, StrokeShape, TrimPathShape,
+};
+use skia_rs_canvas::{ClipOp, RasterCanvas, SaveLayerRec};
+use skia_rs_core::{Color4f, Matrix, Point, Rect, Scalar};
+use skia_rs_paint::{BlendMode, LumaColorFilter, Paint, ShaderRef, Style, TileMode, shaders};
 use skia_rs_path::Path;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Render context for drawing animations.
 pub struct RenderContext<'a> {
@@ -31,6 +35,16 @@ pub trait Canvas {
     fn save(&mut self);
     /// Restore the previous state.
     fn restore(&mut self);
+    /// Save the current state and draw into an offscreen layer, which the
+    /// matching `restore` composites with the alpha, blend mode and color
+    /// filter of `paint`.
+    ///
+    /// Canvases without offscreen layers just save, so track mattes have no
+    /// effect on them.
+    fn save_layer(&mut self, paint: Option<&Paint>) {
+        let _ = paint;
+        self.save();
+    }
     /// Apply a transform.
     fn concat(&mut self, matrix: &Matrix);
     /// Draw a path with a paint.
@@ -66,6 +80,13 @@ impl<'a> RenderContext<'a> {
         self.canvas.save();
     }
 
+    /// Save the current state and draw into an offscreen layer.
+    pub fn save_layer(&mut self, paint: Option<&Paint>) {
+        self.transform_stack.push(self.current_transform);
+        self.opacity_stack.push(self.current_opacity);
+        self.canvas.save_layer(paint);
+    }
+
     /// Restore the previous state.
     pub fn restore(&mut self) {
         if let Some(transform) = self.transform_stack.pop() {
@@ -113,6 +134,61 @@ impl<'a> RenderContext<'a> {
         self.canvas.clip_rect(rect);
     }
 
+    /// Render a composition's layers, bottom to top, drawing track mattes
+    /// through the layers they matte.
+    pub fn render_layers(
+        &mut self,
+        layers: &[Layer],
+        frame: Scalar,
+        assets: &HashMap<String, Asset>,
+    ) {
+        for (position, layer) in layers.iter().enumerate().rev() {
+            if layer.is_track_matte || !layer.is_visible_at(frame) {
+                continue;
+            }
+            match (layer.matte_mode, layer.find_matte(layers, position)) {
+                (Some(mode), Some(matte)) => {
+                    self.render_matted_layer(layer, matte, mode, frame, assets);
+                }
+                _ => self.render_layer(layer, frame, assets),
+            }
+        }
+    }
+
+    /// Render a layer masked by its track matte.
+    ///
+    /// The layer and the matte are drawn into offscreen layers, and the matte
+    /// is composited onto the layer with `DstIn` (or `DstOut` when inverted),
+    /// through a luma color filter for luma mattes.
+    fn render_matted_layer(
+        &mut self,
+        layer: &Layer,
+        matte: &Layer,
+        mode: MatteMode,
+        frame: Scalar,
+        assets: &HashMap<String, Asset>,
+    ) {
+        let (blend_mode, luma) = match mode {
+            MatteMode::None => return self.render_layer(layer, frame, assets),
+            MatteMode::Alpha => (BlendMode::DstIn, false),
+            MatteMode::AlphaInverted => (BlendMode::DstOut, false),
+            MatteMode::Luma => (BlendMode::DstIn, true),
+            MatteMode::LumaInverted => (BlendMode::DstOut, true),
+        };
+        let mut matte_paint = Paint::new();
+        matte_paint.set_blend_mode(blend_mode);
+        if luma {
+            matte_paint.set_color_filter(Some(Arc::new(LumaColorFilter)));
+        }
+
+        self.save_layer(None);
+        self.render_layer(layer, frame, assets);
+        self.save_layer(Some(&matte_paint));
+        self.render_layer(matte, frame, assets);
+        self.restore();
+        self.restore();
+    }
+
     /// Render a layer.
     pub fn render_layer(&mut self, layer: &Layer, frame: Scalar, assets: &HashMap<String, Asset>) {
         if !layer.is_visible_at(frame) || layer.hidden {
@@ -180,6 +256,7 @@ impl<'a> RenderContext<'a> {
         let mut fills: Vec<&FillShape> = Vec::new();
         let mut strokes: Vec<&StrokeShape> = Vec::new();
         let mut gradient_fills: Vec<&GradientFillShape> = Vec::new();
+        let mut gradient_strokes: Vec<&GradientStrokeShape> = Vec::new();
         let mut trim: Option<&TrimPathShape> = None;
 
         for shape in shapes {
@@ -227,6 +304,9 @@ impl<'a> RenderContext<'a> {
                 Shape::GradientFill(gf) => {
                     gradient_fills.push(gf);
                 }
+                Shape::GradientStroke(gs) => {
+                    gradient_strokes.push(gs);
+                }
                 Shape::TrimPath(tp) => {
                     trim = Some(tp);
                 }
@@ -269,12 +349,16 @@ impl<'a> RenderContext<'a> {
 
         // Draw gradient fills
         for gf in &gradient_fills {
-            // Simplified gradient - just use first color
+            let (start, end) = gf.points_at(frame);
+            let (colors, offsets) = gf.stops_at(frame);
+            let Some(shader) = self.gradient_shader(gf.gradient_type, start, end, colors, &offsets)
+            else {
+                continue;
+            };
+
             let mut paint = Paint::new();
             paint.set_style(Style::Fill);
-
-            let opacity = gf.opacity.value_at(frame).as_scalar().unwrap_or(100.0) / 100.0;
-            paint.set_color(Color4f::new(0.5, 0.5, 0.5, opacity * self.current_opacity));
+            paint.set_shader(Some(shader));
 
             for path in &final_paths {
                 self.draw_path(path, &paint);
@@ -301,6 +385,48 @@ impl<'a> RenderContext<'a> {
                 self.draw_path(path, &paint);
             }
         }
+
+        // Draw gradient strokes
+        for gs in &gradient_strokes {
+            let (start, end) = gs.points_at(frame);
+            let (colors, offsets) = gs.stops_at(frame);
+            let Some(shader) = self.gradient_shader(gs.gradient_type, start, end, colors, &offsets)
+            else {
+                continue;
+            };
+
+            let mut paint = Paint::new();
+            paint.set_style(Style::Stroke);
+            paint.set_shader(Some(shader));
+            paint.set_stroke_width(gs.width_at(frame));
+            paint.set_stroke_cap(gs.line_cap);
+            paint.set_stroke_join(gs.line_join);
+
+            for path in &final_paths {
+                self.draw_path(path, &paint);
+            }
+        }
+    }
+
+    /// Build a linear (type 1) or radial (type 2) gradient shader, with the
+    /// current opacity applied to its colors.
+    fn gradient_shader(
+        &self,
+        gradient_type: i32,
+        start: Point,
+        end: Point,
+        mut colors: Vec<Color4f>,
+        offsets: &[Scalar],
+    ) -> Option<ShaderRef> {
+        for color in &mut colors {
+            color.a *= self.current_opacity;
+        }
+        if gradient_type == 2 {
+            let radius = (end.x - start.x).hypot(end.y - start.y);
+            shaders::radial_gradient(start, radius, &colors, Some(offsets), TileMode::Clamp)
+        } else {
+            shaders::linear_gradient(start, end, &colors, Some(offsets), TileMode::Clamp)
+        }
     }
 
     /// Render a precomposition.
@@ -310,11 +436,7 @@ impl<'a> RenderContext<'a> {
         frame: Scalar,
         assets: &HashMap<String, Asset>,
     ) {
-        for layer in precomp.layers.iter().rev() {
-            if layer.is_visible_at(frame) {
-                self.render_layer(layer, frame, assets);
-            }
-        }
+        self.render_layers(&precomp.layers, frame, assets);
     }
 }
 
@@ -382,6 +504,54 @@ impl<'a> Canvas for SkiaCanvas<'a> {
     }
 }
 
+impl Canvas for RasterCanvas<'_> {
+    fn save(&mut self) {
+        RasterCanvas::save(self);
+    }
+
+    fn restore(&mut self) {
+        RasterCanvas::restore(self);
+    }
+
+    fn save_layer(&mut self, paint: Option<&Paint>) {
+        RasterCanvas::save_layer(
+            self,
+            &SaveLayerRec {
+                paint,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn concat(&mut self, matrix: &Matrix) {
+        RasterCanvas::concat(self, matrix);
+    }
+
+    fn draw_path(&mut self, path: &Path, paint: &Paint) {
+        RasterCanvas::draw_path(self, path, paint);
+    }
+
+    fn draw_rect(&mut self, rect: &Rect, paint: &Paint) {
+        RasterCanvas::draw_rect(self, rect, paint);
+    }
+
+    fn clip_path(&mut self, path: &Path) {
+        RasterCanvas::clip_path(self, path, ClipOp::Intersect, true);
+    }
+
+    fn clip_rect(&mut self, rect: &Rect) {
+        RasterCanvas::clip_rect(self, rect);
+    }
+
+    fn get_transform(&self) -> Matrix {
+        *self.total_matrix()
+    }
+
+    fn set_transform(&mut self, matrix: &Matrix) {
+        self.set_matrix(matrix);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -13,12 +13,14 @@
 //! - Merge paths
 //! - Repeater
 
+use crate::expression::ExpressionEnvironment;
 use crate::keyframe::{AnimatedProperty, KeyframeValue, PathData};
 use crate::model::ShapeModel;
 use crate::transform::Transform;
-use skia_rs_core::{Color4f, Scalar};
+use skia_rs_core::{Color4f, Point, Scalar};
 use skia_rs_paint::{StrokeCap, StrokeJoin};
 use skia_rs_path::{Path, PathBuilder};
+use std::sync::Arc;
 
 /// Shape element types.
 #[derive(Debug, Clone)]
@@ -56,25 +58,58 @@ pub enum Shape {
 impl Shape {
     /// Parse from Lottie shape model.
     pub fn from_lottie(model: &ShapeModel) -> Option<Self> {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie shape model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Option<Self> {
         if model.hidden {
             return None;
         }
 
         match model.shape_type.as_str() {
-            "gr" => Some(Shape::Group(ShapeGroup::from_lottie(model))),
-            "rc" => Some(Shape::Rectangle(RectangleShape::from_lottie(model))),
-            "el" => Some(Shape::Ellipse(EllipseShape::from_lottie(model))),
-            "sh" => Some(Shape::Path(PathShape::from_lottie(model))),
-            "sr" => Some(Shape::Polystar(PolystarShape::from_lottie(model))),
-            "fl" => Some(Shape::Fill(FillShape::from_lottie(model))),
-            "st" => Some(Shape::Stroke(StrokeShape::from_lottie(model))),
-            "gf" => Some(Shape::GradientFill(GradientFillShape::from_lottie(model))),
-            "gs" => Some(Shape::GradientStroke(GradientStrokeShape::from_lottie(
+            "gr" => Some(Shape::Group(ShapeGroup::from_lottie_with_environment(
+                model,
+                environment,
+            ))),
+            "rc" => Some(Shape::Rectangle(
+                RectangleShape::from_lottie_with_environment(model, environment),
+            )),
+            "el" => Some(Shape::Ellipse(EllipseShape::from_lottie_with_environment(
+                model,
+                environment,
+            ))),
+            "sh" => Some(Shape::Path(PathShape::from_lottie_with_environment(
+                model,
+                environment,
+            ))),
+            "sr" => Some(Shape::Polystar(
+                PolystarShape::from_lottie_with_environment(model, environment),
+            )),
+            "fl" => Some(Shape::Fill(FillShape::from_lottie_with_environment(
                 model,
+                environment,
             ))),
-            "tm" => Some(Shape::TrimPath(TrimPathShape::from_lottie(model))),
+            "st" => Some(Shape::Stroke(StrokeShape::from_lottie_with_environment(
+                model,
+                environment,
+            ))),
+            "gf" => Some(Shape::GradientFill(
+                GradientFillShape::from_lottie_with_environment(model, environment),
+            )),
+            "gs" => Some(Shape::GradientStroke(
+                GradientStrokeShape::from_lottie_with_environment(model, environment),
+            )),
+            "tm" => Some(Shape::TrimPath(
+                TrimPathShape::from_lottie_with_environment(model, environment),
+            )),
             "mm" => Some(Shape::MergePaths(MergePathsShape::from_lottie(model))),
-            "rd" => Some(Shape::RoundCorners(RoundCornersShape::from_lottie(model))),
+            "rd" => Some(Shape::RoundCorners(
+                RoundCornersShape::from_lottie_with_environment(model, environment),
+            )),
             "rp" => Some(Shape::Repeater(RepeaterShape::from_lottie(model))),
             "tr" => Some(Shape::Transform(ShapeTransform::from_lottie(model))),
             _ => None, // Unknown shape type
@@ -105,13 +140,21 @@ impl ShapeGroup {
 
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         let mut group = Self::new(&model.name);
 
         for item in &model.items {
             if item.shape_type == "tr" {
                 // Transform item - shape transforms use a simplified structure
                 group.transform = Some(Transform::default());
-            } else if let Some(shape) = Shape::from_lottie(item) {
+            } else if let Some(shape) = Shape::from_lottie_with_environment(item, environment) {
                 group.shapes.push(shape);
             }
         }
@@ -174,22 +217,30 @@ pub struct RectangleShape {
 impl RectangleShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             position: model
                 .position
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             size: model
                 .size
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             roundness: model
                 .roundness
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             direction: model.direction.unwrap_or(1),
         }
@@ -261,17 +312,25 @@ pub struct EllipseShape {
 impl EllipseShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             position: model
                 .position
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             size: model
                 .size
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             direction: model.direction.unwrap_or(1),
         }
@@ -326,12 +385,20 @@ pub struct PathShape {
 impl PathShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             path: model
                 .path
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             direction: model.direction.unwrap_or(1),
         }
@@ -441,27 +508,35 @@ pub struct PolystarShape {
 impl PolystarShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             position: model
                 .position
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             points: model
                 .points
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             outer_radius: model
                 .outer_radius
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             inner_radius: model
                 .inner_radius
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             outer_roundness: AnimatedProperty::default(),
             inner_roundness: AnimatedProperty::default(),
@@ -469,7 +544,7 @@ impl PolystarShape {
             rotation: model
                 .roundness
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             direction: model.direction.unwrap_or(1),
         }
@@ -546,17 +621,25 @@ pub struct FillShape {
 impl FillShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             color: model
                 .color
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             opacity: model
                 .opacity
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(100.0))),
             fill_rule: 1,
         }
@@ -600,6 +683,14 @@ pub struct StrokeShape {
 impl StrokeShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         let line_cap = match model.line_cap.unwrap_or(2) {
             1 => StrokeCap::Butt,
             2 => StrokeCap::Round,
@@ -619,17 +710,17 @@ impl StrokeShape {
             color: model
                 .color
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             opacity: model
                 .opacity
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(100.0))),
             width: model
                 .stroke_width
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(1.0))),
             line_cap,
             line_join,
@@ -678,23 +769,34 @@ pub struct GradientFillShape {
 impl GradientFillShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             gradient_type: model.gradient_type.unwrap_or(1),
+            // Lottie stores the start point under `s`, which the model
+            // shares with the size
             start_point: model
                 .gradient_start
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .or(model.size.as_ref())
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             end_point: model
                 .gradient_end
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             colors: model
                 .gradient_colors
                 .as_ref()
-                .map(|gc| AnimatedProperty::from_lottie(&gc.colors))
+                .map(|gc| AnimatedProperty::from_lottie_with_environment(&gc.colors, environment))
                 .unwrap_or_default(),
             color_count: model
                 .gradient_colors
@@ -704,10 +806,21 @@ impl GradientFillShape {
             opacity: model
                 .opacity
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(100.0))),
         }
     }
+
+    /// Get the gradient's start and end points at a specific frame.
+    pub fn points_at(&self, frame: Scalar) -> (Point, Point) {
+        gradient_points(&self.start_point, &self.end_point, frame)
+    }
+
+    /// Get the gradient's colors and their offsets at a specific frame.
+    pub fn stops_at(&self, frame: Scalar) -> (Vec<Color4f>, Vec<Scalar>) {
+        let opacity = self.opacity.value_at(frame).as_scalar().unwrap_or(100.0) / 100.0;
+        gradient_stops(&self.colors, self.color_count, opacity, frame)
+    }
 }
 
 /// Gradient stroke shape.
@@ -738,23 +851,34 @@ pub struct GradientStrokeShape {
 impl GradientStrokeShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             gradient_type: model.gradient_type.unwrap_or(1),
+            // Lottie stores the start point under `s`, which the model
+            // shares with the size
             start_point: model
                 .gradient_start
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .or(model.size.as_ref())
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             end_point: model
                 .gradient_end
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             colors: model
                 .gradient_colors
                 .as_ref()
-                .map(|gc| AnimatedProperty::from_lottie(&gc.colors))
+                .map(|gc| AnimatedProperty::from_lottie_with_environment(&gc.colors, environment))
                 .unwrap_or_default(),
             color_count: model
                 .gradient_colors
@@ -764,17 +888,95 @@ impl GradientStrokeShape {
             opacity: model
                 .opacity
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(100.0))),
             width: model
                 .stroke_width
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(1.0))),
             line_cap: StrokeCap::Round,
             line_join: StrokeJoin::Round,
         }
     }
+
+    /// Get the gradient's start and end points at a specific frame.
+    pub fn points_at(&self, frame: Scalar) -> (Point, Point) {
+        gradient_points(&self.start_point, &self.end_point, frame)
+    }
+
+    /// Get the gradient's colors and their offsets at a specific frame.
+    pub fn stops_at(&self, frame: Scalar) -> (Vec<Color4f>, Vec<Scalar>) {
+        let opacity = self.opacity.value_at(frame).as_scalar().unwrap_or(100.0) / 100.0;
+        gradient_stops(&self.colors, self.color_count, opacity, frame)
+    }
+
+    /// Get the stroke width at a specific frame.
+    pub fn width_at(&self, frame: Scalar) -> Scalar {
+        self.width.value_at(frame).as_scalar().unwrap_or(1.0)
+    }
+}
+
+/// Evaluate gradient start and end points.
+fn gradient_points(
+    start: &AnimatedProperty,
+    end: &AnimatedProperty,
+    frame: Scalar,
+) -> (Point, Point) {
+    let [x0, y0] = start.value_at(frame).as_vec2().unwrap_or([0.0, 0.0]);
+    let [x1, y1] = end.value_at(frame).as_vec2().unwrap_or([0.0, 0.0]);
+    (Point::new(x0, y0), Point::new(x1, y1))
+}
+
+/// Evaluate gradient colors and offsets.
+///
+/// Lottie stores `count` color stops as `[offset, r, g, b]`, optionally
+/// followed by opacity stops as `[offset, alpha]`; the opacity stops are
+/// sampled at each color stop.
+fn gradient_stops(
+    colors: &AnimatedProperty,
+    count: i32,
+    opacity: Scalar,
+    frame: Scalar,
+) -> (Vec<Color4f>, Vec<Scalar>) {
+    let value = colors.value_at(frame);
+    let values = value.as_array().unwrap_or_default();
+    let color_len = (count.max(0) as usize * 4).min(values.len() / 4 * 4);
+    let (color_values, alpha_values) = values.split_at(color_len);
+    let alphas: Vec<&[Scalar]> = alpha_values.chunks_exact(2).collect();
+
+    color_values
+        .chunks_exact(4)
+        .map(|stop| {
+            let alpha = alpha_at(&alphas, stop[0]);
+            (
+                Color4f::new(stop[1], stop[2], stop[3], alpha * opacity),
+                stop[0],
+            )
+        })
+        .unzip()
+}
+
+/// Interpolate `[offset, alpha]` opacity stops at an offset.
+fn alpha_at(stops: &[&[Scalar]], offset: Scalar) -> Scalar {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return 1.0;
+    };
+    if offset <= first[0] {
+        return first[1];
+    }
+    for pair in stops.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        if offset <= end[0] {
+            let t = if end[0] > start[0] {
+                (offset - start[0]) / (end[0] - start[0])
+            } else {
+                1.0
+            };
+            return start[1] + (end[1] - start[1]) * t;
+        }
+    }
+    last[1]
 }
 
 /// Trim paths shape.
@@ -795,22 +997,30 @@ pub struct TrimPathShape {
 impl TrimPathShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             start: model
                 .trim_start
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             end: model
                 .trim_end
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_else(|| AnimatedProperty::static_value(KeyframeValue::Scalar(100.0))),
             offset: model
                 .trim_offset
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
             mode: model.trim_mode.unwrap_or(1),
         }
@@ -856,12 +1066,20 @@ pub struct RoundCornersShape {
 impl RoundCornersShape {
     /// Parse from Lottie model.
     pub fn from_lottie(model: &ShapeModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &ShapeModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         Self {
             name: model.name.clone(),
             radius: model
                 .roundness
                 .as_ref()
-                .map(AnimatedProperty::from_lottie)
+                .map(|value| AnimatedProperty::from_lottie_with_environment(value, environment))
                 .unwrap_or_default(),
         }
     }
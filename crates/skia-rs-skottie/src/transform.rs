@@ -8,9 +8,11 @@
 //! - Opacity
 //! - Skew
 
+use crate::expression::ExpressionEnvironment;
 use crate::keyframe::{AnimatedProperty, KeyframeValue};
 use crate::model::TransformModel;
 use skia_rs_core::{Matrix, Scalar};
+use std::sync::Arc;
 
 /// Animated transform for a layer or shape.
 #[derive(Debug, Clone)]
@@ -59,42 +61,65 @@ impl Transform {
 
     /// Parse from Lottie transform model.
     pub fn from_lottie(model: &TransformModel) -> Self {
+        Self::from_lottie_with_environment(model, &ExpressionEnvironment::detached())
+    }
+
+    /// Parse from Lottie transform model, binding expressions to `environment`.
+    pub fn from_lottie_with_environment(
+        model: &TransformModel,
+        environment: &Arc<ExpressionEnvironment>,
+    ) -> Self {
         let mut transform = Self::new();
 
         if let Some(ref anchor) = model.anchor {
-            transform.anchor = AnimatedProperty::from_lottie(anchor);
+            transform.anchor = AnimatedProperty::from_lottie_with_environment(anchor, environment);
         }
 
         if let Some(ref position) = model.position {
-            transform.position = AnimatedProperty::from_lottie(position);
+            transform.position =
+                AnimatedProperty::from_lottie_with_environment(position, environment);
         }
 
         if let Some(ref px) = model.position_x {
-            transform.position_x = Some(AnimatedProperty::from_lottie(px));
+            transform.position_x = Some(AnimatedProperty::from_lottie_with_environment(
+                px,
+                environment,
+            ));
         }
 
         if let Some(ref py) = model.position_y {
-            transform.position_y = Some(AnimatedProperty::from_lottie(py));
+            transform.position_y = Some(AnimatedProperty::from_lottie_with_environment(
+                py,
+                environment,
+            ));
         }
 
         if let Some(ref scale) = model.scale {
-            transform.scale = AnimatedProperty::from_lottie(scale);
+            transform.scale = AnimatedProperty::from_lottie_with_environment(scale, environment);
         }
 
         if let Some(ref rotation) = model.rotation {
-            transform.rotation = AnimatedProperty::from_lottie(rotation);
+            transform.rotation =
+                AnimatedProperty::from_lottie_with_environment(rotation, environment);
         }
 
         if let Some(ref opacity) = model.opacity {
-            transform.opacity = AnimatedProperty::from_lottie(opacity);
+            transform.opacity =
+                AnimatedProperty::from_lottie_with_environment(opacity, environment);
         }
 
         if let Some(ref skew) = model.skew {
-            transform.skew = Some(AnimatedProperty::from_lottie(skew));
+            transform.skew = Some(AnimatedProperty::from_lottie_with_environment(
+                skew,
+                environment,
+            ));
         }
 
         if let Some(ref skew_axis) = model.skew_axis {
-            transform.skew_axis = Some(AnimatedProperty::from_lottie(skew_axis));
+            transform.skew_axis = Some(AnimatedProperty::from_lottie_with_environment(
+                skew_axis,
+                environment,
+            ));
         }
 
         transform
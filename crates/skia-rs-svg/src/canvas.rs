@@ -0,0 +1,418 @@
+//! Canvas that records draw calls as SVG.
+//!
+//! [`SvgCanvas`] mirrors the drawing methods of
+//! [`Canvas`](skia_rs_canvas::Canvas), writing each draw call as an SVG
+//! element instead of rasterizing it. Styles come from the paint's color,
+//! style and stroke settings; shaders, effects and blend modes are not
+//! exported.
+
+use crate::export::{
+    SvgExportOptions, escape_xml, export_transform_attr, format_color, format_scalar,
+};
+use skia_rs_core::{Color, Matrix, Point, RRect, Rect, Scalar};
+use skia_rs_paint::{BlendMode, Paint, StrokeCap, StrokeJoin, Style};
+use skia_rs_path::{FillType, Path, PathBuilder};
+use std::fmt::Write;
+
+/// A canvas that turns draw calls into SVG markup.
+#[derive(Debug, Clone)]
+pub struct SvgCanvas {
+    /// Width of the document.
+    width: Scalar,
+    /// Height of the document.
+    height: Scalar,
+    /// Formatting options.
+    options: SvgExportOptions,
+    /// Current transformation matrix stack.
+    matrix_stack: Vec<Matrix>,
+    /// The elements drawn so far.
+    body: String,
+}
+
+impl SvgCanvas {
+    /// Create a canvas for a document of the given size.
+    pub fn new(width: Scalar, height: Scalar) -> Self {
+        Self::with_options(width, height, SvgExportOptions::default())
+    }
+
+    /// Create a canvas with custom export options.
+    pub fn with_options(width: Scalar, height: Scalar, options: SvgExportOptions) -> Self {
+        Self {
+            width,
+            height,
+            options,
+            matrix_stack: vec![Matrix::IDENTITY],
+            body: String::new(),
+        }
+    }
+
+    /// Get the width.
+    #[inline]
+    pub fn width(&self) -> Scalar {
+        self.width
+    }
+
+    /// Get the height.
+    #[inline]
+    pub fn height(&self) -> Scalar {
+        self.height
+    }
+
+    /// Get the current save count.
+    #[inline]
+    pub fn save_count(&self) -> usize {
+        self.matrix_stack.len()
+    }
+
+    /// Get the current transformation matrix.
+    #[inline]
+    pub fn total_matrix(&self) -> &Matrix {
+        self.matrix_stack.last().unwrap()
+    }
+
+    /// Save the current state.
+    pub fn save(&mut self) -> usize {
+        let matrix = *self.total_matrix();
+        self.matrix_stack.push(matrix);
+        self.save_count()
+    }
+
+    /// Restore to the previous state.
+    pub fn restore(&mut self) {
+        if self.matrix_stack.len() > 1 {
+            self.matrix_stack.pop();
+        }
+    }
+
+    /// Restore to a specific save count.
+    pub fn restore_to_count(&mut self, count: usize) {
+        while self.save_count() > count.max(1) {
+            self.restore();
+        }
+    }
+
+    /// Translate the canvas.
+    pub fn translate(&mut self, dx: Scalar, dy: Scalar) {
+        self.concat(&Matrix::translate(dx, dy));
+    }
+
+    /// Scale the canvas.
+    pub fn scale(&mut self, sx: Scalar, sy: Scalar) {
+        self.concat(&Matrix::scale(sx, sy));
+    }
+
+    /// Rotate the canvas (angle in degrees).
+    pub fn rotate(&mut self, degrees: Scalar) {
+        self.concat(&Matrix::rotate(degrees.to_radians()));
+    }
+
+    /// Skew the canvas.
+    pub fn skew(&mut self, sx: Scalar, sy: Scalar) {
+        self.concat(&Matrix::skew(sx, sy));
+    }
+
+    /// Concatenate a matrix.
+    pub fn concat(&mut self, matrix: &Matrix) {
+        if let Some(current) = self.matrix_stack.last_mut() {
+            *current = current.concat(matrix);
+        }
+    }
+
+    /// Set the matrix.
+    pub fn set_matrix(&mut self, matrix: &Matrix) {
+        if let Some(current) = self.matrix_stack.last_mut() {
+            *current = *matrix;
+        }
+    }
+
+    /// Reset the matrix to identity.
+    pub fn reset_matrix(&mut self) {
+        self.set_matrix(&Matrix::IDENTITY);
+    }
+
+    // =========================================================================
+    // Drawing
+    // =========================================================================
+
+    /// Fill the whole document with a color.
+    pub fn clear(&mut self, color: Color) {
+        self.draw_color(color, BlendMode::Src);
+    }
+
+    /// Fill the whole document with a color.
+    pub fn draw_color(&mut self, color: Color, blend_mode: BlendMode) {
+        let mut paint = Paint::new();
+        paint.set_color32(color).set_blend_mode(blend_mode);
+
+        let matrix = *self.total_matrix();
+        self.reset_matrix();
+        self.draw_rect(&Rect::from_xywh(0.0, 0.0, self.width, self.height), &paint);
+        self.set_matrix(&matrix);
+    }
+
+    /// Draw a line segment as a `<line>` element.
+    pub fn draw_line(&mut self, p0: Point, p1: Point, paint: &Paint) {
+        if self.begin_element("line", paint) {
+            self.write_scalar_attr("x1", p0.x);
+            self.write_scalar_attr("y1", p0.y);
+            self.write_scalar_attr("x2", p1.x);
+            self.write_scalar_attr("y2", p1.y);
+            self.end_element(paint, FillType::Winding);
+        }
+    }
+
+    /// Draw a rectangle as a `<rect>` element.
+    pub fn draw_rect(&mut self, rect: &Rect, paint: &Paint) {
+        self.draw_round_rect(rect, 0.0, 0.0, paint);
+    }
+
+    /// Draw an oval as an `<ellipse>` element.
+    pub fn draw_oval(&mut self, rect: &Rect, paint: &Paint) {
+        if self.begin_element("ellipse", paint) {
+            let center = rect.center();
+            self.write_scalar_attr("cx", center.x);
+            self.write_scalar_attr("cy", center.y);
+            self.write_scalar_attr("rx", rect.width() / 2.0);
+            self.write_scalar_attr("ry", rect.height() / 2.0);
+            self.end_element(paint, FillType::Winding);
+        }
+    }
+
+    /// Draw a circle as a `<circle>` element.
+    pub fn draw_circle(&mut self, center: Point, radius: Scalar, paint: &Paint) {
+        if self.begin_element("circle", paint) {
+            self.write_scalar_attr("cx", center.x);
+            self.write_scalar_attr("cy", center.y);
+            self.write_scalar_attr("r", radius);
+            self.end_element(paint, FillType::Winding);
+        }
+    }
+
+    /// Draw a rounded rectangle as a `<rect>` element.
+    pub fn draw_round_rect(&mut self, rect: &Rect, rx: Scalar, ry: Scalar, paint: &Paint) {
+        if self.begin_element("rect", paint) {
+            self.write_scalar_attr("x", rect.left);
+            self.write_scalar_attr("y", rect.top);
+            self.write_scalar_attr("width", rect.width());
+            self.write_scalar_attr("height", rect.height());
+            if rx > 0.0 && ry > 0.0 {
+                self.write_scalar_attr("rx", rx);
+                self.write_scalar_attr("ry", ry);
+            }
+            self.end_element(paint, FillType::Winding);
+        }
+    }
+
+    /// Draw a rounded rectangle.
+    ///
+    /// Rectangles whose corners all share one radius become `<rect>`
+    /// elements, others become `<path>` elements.
+    pub fn draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {
+        if rrect.is_simple() {
+            let radius = rrect.radii[0];
+            self.draw_round_rect(&rrect.rect, radius.x, radius.y, paint);
+        } else {
+            let mut builder = PathBuilder::new();
+            builder.add_rrect(rrect);
+            self.draw_path(&builder.build(), paint);
+        }
+    }
+
+    /// Draw a path as a `<path>` element.
+    pub fn draw_path(&mut self, path: &Path, paint: &Paint) {
+        if self.begin_element("path", paint) {
+            let data = path.to_svg_string_with_precision(self.options.precision);
+            write!(self.body, " d=\"{}\"", data).unwrap();
+            self.end_element(paint, path.fill_type());
+        }
+    }
+
+    /// Draw text as a `<text>` element with its baseline starting at (x, y).
+    pub fn draw_text(
+        &mut self,
+        text: &str,
+        x: Scalar,
+        y: Scalar,
+        font_size: Scalar,
+        paint: &Paint,
+    ) {
+        if self.begin_element("text", paint) {
+            self.write_scalar_attr("x", x);
+            self.write_scalar_attr("y", y);
+            self.write_scalar_attr("font-size", font_size);
+            self.write_paint_attrs(paint, FillType::Winding);
+            self.write_transform();
+            write!(self.body, ">{}</text>", escape_xml(text)).unwrap();
+            self.end_line();
+        }
+    }
+
+    /// Finish recording and return the SVG document.
+    pub fn into_svg(self) -> String {
+        let mut output = String::new();
+        if self.options.xml_declaration {
+            output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        }
+
+        let width = format_scalar(self.width, self.options.precision);
+        let height = format_scalar(self.height, self.options.precision);
+        write!(
+            output,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+        )
+        .unwrap();
+        if self.options.pretty_print {
+            output.push('\n');
+        }
+        output.push_str(&self.body);
+        output.push_str("</svg>");
+        if self.options.pretty_print {
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Start an element, returning false if the paint draws nothing.
+    fn begin_element(&mut self, tag: &str, paint: &Paint) -> bool {
+        if paint.nothing_to_draw() {
+            return false;
+        }
+        if self.options.pretty_print {
+            self.body.push_str(&self.options.indent);
+        }
+        write!(self.body, "<{}", tag).unwrap();
+        true
+    }
+
+    /// Write the paint and transform attributes and close an empty element.
+    fn end_element(&mut self, paint: &Paint, fill_type: FillType) {
+        self.write_paint_attrs(paint, fill_type);
+        self.write_transform();
+        self.body.push_str("/>");
+        self.end_line();
+    }
+
+    fn end_line(&mut self) {
+        if self.options.pretty_print {
+            self.body.push('\n');
+        }
+    }
+
+    fn write_scalar_attr(&mut self, name: &str, value: Scalar) {
+        let value = format_scalar(value, self.options.precision);
+        write!(self.body, " {}=\"{}\"", name, value).unwrap();
+    }
+
+    fn write_transform(&mut self) {
+        let matrix = *self.total_matrix();
+        if !matrix.is_identity() {
+            export_transform_attr(&mut self.body, &matrix, &self.options);
+        }
+    }
+
+    fn write_paint_attrs(&mut self, paint: &Paint, fill_type: FillType) {
+        let color = paint.color32();
+        let (fill, stroke) = match paint.style() {
+            Style::Fill => (true, false),
+            Style::Stroke => (false, true),
+            Style::StrokeAndFill => (true, true),
+        };
+
+        if fill {
+            self.write_color_attrs("fill", color);
+            if matches!(fill_type, FillType::EvenOdd | FillType::InverseEvenOdd) {
+                self.body.push_str(" fill-rule=\"evenodd\"");
+            }
+        } else {
+            self.body.push_str(" fill=\"none\"");
+        }
+
+        if stroke {
+            self.write_color_attrs("stroke", color);
+            if paint.stroke_width() > 0.0 {
+                self.write_scalar_attr("stroke-width", paint.stroke_width());
+            } else {
+                // Hairlines are one pixel wide whatever the transform
+                self.body
+                    .push_str(" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"");
+            }
+            match paint.stroke_cap() {
+                StrokeCap::Butt => {}
+                StrokeCap::Round => self.body.push_str(" stroke-linecap=\"round\""),
+                StrokeCap::Square => self.body.push_str(" stroke-linecap=\"square\""),
+            }
+            match paint.stroke_join() {
+                StrokeJoin::Miter => {
+                    if paint.stroke_miter() != 4.0 {
+                        self.write_scalar_attr("stroke-miterlimit", paint.stroke_miter());
+                    }
+                }
+                StrokeJoin::Round => self.body.push_str(" stroke-linejoin=\"round\""),
+                StrokeJoin::Bevel => self.body.push_str(" stroke-linejoin=\"bevel\""),
+            }
+        }
+    }
+
+    /// Write a color as an opaque color plus a separate opacity.
+    fn write_color_attrs(&mut self, name: &str, color: Color) {
+        let opaque = Color::from_rgb(color.red(), color.green(), color.blue());
+        write!(self.body, " {}=\"{}\"", name, format_color(&opaque)).unwrap();
+        if color.alpha() < 255 {
+            let opacity = color.alpha() as Scalar / 255.0;
+            self.write_scalar_attr(&format!("{}-opacity", name), opacity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_canvas_circle() {
+        let mut canvas = SvgCanvas::new(100.0, 100.0);
+        let mut paint = Paint::new();
+        paint.set_color32(Color::from_rgb(255, 0, 0));
+        canvas.draw_circle(Point::new(50.0, 40.0), 25.0, &paint);
+        canvas.draw_text("a < b", 10.0, 90.0, 12.0, &paint);
+
+        let svg = canvas.into_svg();
+        assert!(svg.contains("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\""));
+        assert!(svg.contains("<circle cx=\"50\" cy=\"40\" r=\"25\" fill=\"#ff0000\"/>"));
+        assert!(
+            svg.contains(
+                "<text x=\"10\" y=\"90\" font-size=\"12\" fill=\"#ff0000\">a &lt; b</text>"
+            )
+        );
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_svg_canvas_stroke_and_transform() {
+        let mut canvas = SvgCanvas::with_options(20.0, 20.0, SvgExportOptions::minified());
+        let mut paint = Paint::new();
+        paint
+            .set_argb(128, 0, 0, 255)
+            .set_style(Style::Stroke)
+            .set_stroke_width(2.0)
+            .set_stroke_cap(StrokeCap::Round);
+
+        canvas.save();
+        canvas.translate(5.0, 5.0);
+        canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, 10.0, 10.0), &paint);
+        canvas.restore();
+        canvas.draw_line(Point::new(0.0, 0.0), Point::new(20.0, 20.0), &paint);
+
+        let svg = canvas.into_svg();
+        assert!(svg.contains(
+            "<rect x=\"0\" y=\"0\" width=\"10\" height=\"10\" fill=\"none\" stroke=\"#0000ff\" \
+             stroke-opacity=\"0.5\" stroke-width=\"2\" stroke-linecap=\"round\" \
+             transform=\"translate(5, 5)\"/>"
+        ));
+        // The transform was restored for the line
+        assert!(svg.contains(
+            "<line x1=\"0\" y1=\"0\" x2=\"20\" y2=\"20\" fill=\"none\" stroke=\"#0000ff\" \
+             stroke-opacity=\"0.5\" stroke-width=\"2\" stroke-linecap=\"round\"/>"
+        ));
+    }
+}
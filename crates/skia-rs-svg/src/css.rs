@@ -146,61 +146,66 @@ pub enum CssSelector {
 
 impl CssSelector {
     /// Parse a selector string.
+    ///
+    /// Combinators group to the left, so `svg g > rect` is parsed as
+    /// `(svg g) > rect`.
     pub fn parse(s: &str) -> Self {
-        let s = s.trim();
-
-        // Check for descendant/child selectors
-        if s.contains(" > ") {
-            let parts: Vec<&str> = s.splitn(2, " > ").collect();
-            if parts.len() == 2 {
-                return CssSelector::Child(
-                    Box::new(Self::parse(parts[0])),
-                    Box::new(Self::parse(parts[1])),
-                );
-            }
-        }
-
-        if s.contains(' ') {
-            let parts: Vec<&str> = s.splitn(2, ' ').collect();
-            if parts.len() == 2 && !parts[1].is_empty() {
-                return CssSelector::Descendant(
-                    Box::new(Self::parse(parts[0])),
-                    Box::new(Self::parse(parts[1])),
-                );
+        let spaced = s.replace('>', " > ");
+        let mut tokens = spaced.split_whitespace();
+        let mut selector = tokens
+            .next()
+            .map_or(CssSelector::Universal, parse_compound_selector);
+
+        let mut child = false;
+        for token in tokens {
+            if token == ">" {
+                child = true;
+                continue;
             }
+            let next = Box::new(parse_compound_selector(token));
+            selector = if child {
+                CssSelector::Child(Box::new(selector), next)
+            } else {
+                CssSelector::Descendant(Box::new(selector), next)
+            };
+            child = false;
         }
+        selector
+    }
+}
 
-        // Check for combined selectors (e.g., rect.classname#id)
-        let mut selectors = Vec::new();
-        let mut current = String::new();
-        let mut chars = s.chars().peekable();
-
-        while let Some(c) = chars.next() {
-            match c {
-                '.' | '#' => {
-                    if !current.is_empty() {
-                        selectors.push(parse_simple_selector(&current));
-                        current.clear();
-                    }
-                    current.push(c);
+/// Parse a compound selector such as `rect.classname#id`.
+fn parse_compound_selector(s: &str) -> CssSelector {
+    let mut selectors = Vec::new();
+    let mut current = String::new();
+
+    for c in s.chars() {
+        match c {
+            '.' | '#' => {
+                if !current.is_empty() {
+                    selectors.push(parse_simple_selector(&current));
+                    current.clear();
                 }
-                _ => current.push(c),
+                current.push(c);
             }
+            _ => current.push(c),
         }
+    }
 
-        if !current.is_empty() {
-            selectors.push(parse_simple_selector(&current));
-        }
+    if !current.is_empty() {
+        selectors.push(parse_simple_selector(&current));
+    }
 
-        if selectors.len() == 1 {
-            selectors.pop().unwrap()
-        } else if selectors.is_empty() {
-            CssSelector::Universal
-        } else {
-            CssSelector::And(selectors)
-        }
+    if selectors.len() == 1 {
+        selectors.pop().unwrap()
+    } else if selectors.is_empty() {
+        CssSelector::Universal
+    } else {
+        CssSelector::And(selectors)
     }
+}
 
+impl CssSelector {
     /// Calculate specificity (ID, class, element counts).
     pub fn specificity(&self) -> (u32, u32, u32) {
         match self {
@@ -239,13 +244,8 @@ impl CssSelector {
                 if !child_sel.matches(node, ancestors) {
                     return false;
                 }
-                // Check if any ancestor matches
-                for ancestor in ancestors {
-                    if ancestor_sel.matches(ancestor, &[]) {
-                        return true;
-                    }
-                }
-                false
+                // Check if any ancestor matches, given its own ancestors
+                (0..ancestors.len()).any(|i| ancestor_sel.matches(ancestors[i], &ancestors[..i]))
             }
             CssSelector::Child(parent_sel, child_sel) => {
                 if !child_sel.matches(node, ancestors) {
@@ -288,11 +288,14 @@ fn node_tag_name(node: &SvgNode) -> &str {
         SvgNodeKind::Polygon(_) => "polygon",
         SvgNodeKind::Path(_) => "path",
         SvgNodeKind::Text(_) => "text",
+        SvgNodeKind::TSpan(_) => "tspan",
         SvgNodeKind::Image(_) => "image",
         SvgNodeKind::Use(_) => "use",
         SvgNodeKind::Defs => "defs",
+        SvgNodeKind::Symbol(_) => "symbol",
         SvgNodeKind::LinearGradient(_) => "linearGradient",
         SvgNodeKind::RadialGradient(_) => "radialGradient",
+        SvgNodeKind::Pattern(_) => "pattern",
         SvgNodeKind::ClipPath(_) => "clipPath",
         SvgNodeKind::Unknown(name) => name,
     }
@@ -331,27 +334,52 @@ pub fn apply_stylesheet(dom: &mut SvgDom, stylesheet: &Stylesheet) {
     apply_stylesheet_to_node(&mut dom.root, stylesheet, &[]);
 }
 
+/// Where a declaration ranks in the cascade: `!important` first, then inline
+/// style over rules, then specificity, then source order.
+type CascadeRank = (bool, bool, (u32, u32, u32), usize);
+
 fn apply_stylesheet_to_node(node: &mut SvgNode, stylesheet: &Stylesheet, ancestors: &[&SvgNode]) {
-    // Collect matching rules with their specificity
-    let mut matching_rules: Vec<(&CssRule, (u32, u32, u32))> = stylesheet
+    let inline = node
+        .attributes
+        .get("style")
+        .map(|style| parse_inline_style(style))
+        .unwrap_or_default();
+
+    // Find the winning declaration of each property
+    let rule_declarations = stylesheet
         .rules
         .iter()
-        .filter(|rule| rule.selector.matches(node, ancestors))
-        .map(|rule| (rule, rule.selector.specificity()))
-        .collect();
-
-    // Sort by specificity (lower first, so later rules override)
-    matching_rules.sort_by_key(|(_, spec)| *spec);
-
-    // Apply declarations in order
-    for (rule, _) in matching_rules {
-        apply_declarations_to_node(node, &rule.declarations);
+        .enumerate()
+        .filter(|(_, rule)| rule.selector.matches(node, ancestors))
+        .flat_map(|(order, rule)| {
+            let specificity = rule.selector.specificity();
+            rule.declarations
+                .iter()
+                .map(move |(property, value)| (property, value, false, specificity, order))
+        });
+    let inline_declarations = inline
+        .iter()
+        .map(|(property, value)| (property, value, true, (0, 0, 0), 0));
+
+    let mut cascade: HashMap<&str, (CascadeRank, &str)> = HashMap::new();
+    for (property, value, is_inline, specificity, order) in
+        rule_declarations.chain(inline_declarations)
+    {
+        let (value, important) = split_important(value);
+        let rank = (important, is_inline, specificity, order);
+        if cascade
+            .get(property.as_str())
+            .is_none_or(|(best, _)| rank >= *best)
+        {
+            cascade.insert(property, (rank, value));
+        }
     }
 
-    // Also parse and apply inline style attribute if present
-    if let Some(style) = node.attributes.get("style").cloned() {
-        let inline_decls = parse_inline_style(&style);
-        apply_declarations_to_node(node, &inline_decls);
+    // Sorted so properties like fill are set before fill-opacity adjusts them
+    let mut declarations: Vec<_> = cascade.into_iter().collect();
+    declarations.sort_by_key(|(property, _)| *property);
+    for (property, (_, value)) in declarations {
+        apply_style_property(node, property, value);
     }
 
     // Recursively apply to children
@@ -366,9 +394,14 @@ fn apply_stylesheet_to_node(node: &mut SvgNode, stylesheet: &Stylesheet, ancesto
     }
 }
 
-fn apply_declarations_to_node(node: &mut SvgNode, declarations: &StyleDeclarations) {
-    for (property, value) in declarations {
-        apply_style_property(node, property, value);
+/// Split a declaration value from its `!important` flag.
+fn split_important(value: &str) -> (&str, bool) {
+    match value
+        .strip_suffix("important")
+        .and_then(|value| value.trim_end().strip_suffix('!'))
+    {
+        Some(value) => (value.trim_end(), true),
+        None => (value, false),
     }
 }
 
@@ -423,33 +456,46 @@ pub fn apply_style_property(node: &mut SvgNode, property: &str, value: &str) {
             node.transform = parse_css_transform(value);
         }
         "font-family" => {
-            if let crate::dom::SvgNodeKind::Text(ref mut text) = node.kind {
-                text.font_family = Some(value.trim_matches('"').trim_matches('\'').to_string());
+            let family = value.trim_matches('"').trim_matches('\'').to_string();
+            match node.kind {
+                crate::dom::SvgNodeKind::Text(ref mut text) => text.font_family = Some(family),
+                crate::dom::SvgNodeKind::TSpan(ref mut span) => span.font_family = Some(family),
+                _ => {}
             }
         }
-        "font-size" => {
-            if let crate::dom::SvgNodeKind::Text(ref mut text) = node.kind {
+        "font-size" => match node.kind {
+            crate::dom::SvgNodeKind::Text(ref mut text) => {
                 text.font_size = parse_css_length(value);
             }
-        }
+            crate::dom::SvgNodeKind::TSpan(ref mut span) => {
+                span.font_size = Some(parse_css_length(value));
+            }
+            _ => {}
+        },
         "font-weight" => {
-            if let crate::dom::SvgNodeKind::Text(ref mut text) = node.kind {
-                text.font_weight = match value {
-                    "normal" => 400,
-                    "bold" => 700,
-                    "lighter" => 300,
-                    "bolder" => 800,
-                    _ => value.parse().unwrap_or(400),
-                };
+            let weight = match value {
+                "normal" => 400,
+                "bold" => 700,
+                "lighter" => 300,
+                "bolder" => 800,
+                _ => value.parse().unwrap_or(400),
+            };
+            match node.kind {
+                crate::dom::SvgNodeKind::Text(ref mut text) => text.font_weight = weight,
+                crate::dom::SvgNodeKind::TSpan(ref mut span) => span.font_weight = Some(weight),
+                _ => {}
             }
         }
         "text-anchor" => {
-            if let crate::dom::SvgNodeKind::Text(ref mut text) = node.kind {
-                text.text_anchor = match value {
-                    "middle" => crate::dom::TextAnchor::Middle,
-                    "end" => crate::dom::TextAnchor::End,
-                    _ => crate::dom::TextAnchor::Start,
-                };
+            let anchor = match value {
+                "middle" => crate::dom::TextAnchor::Middle,
+                "end" => crate::dom::TextAnchor::End,
+                _ => crate::dom::TextAnchor::Start,
+            };
+            match node.kind {
+                crate::dom::SvgNodeKind::Text(ref mut text) => text.text_anchor = anchor,
+                crate::dom::SvgNodeKind::TSpan(ref mut span) => span.text_anchor = Some(anchor),
+                _ => {}
             }
         }
         "stroke-linecap" | "stroke-linejoin" | "stroke-dasharray" | "stroke-dashoffset" => {
@@ -753,6 +799,53 @@ mod tests {
         assert!(matches!(sel, CssSelector::Descendant(_, _)));
     }
 
+    #[test]
+    fn test_child_selector() {
+        let dom = crate::parser::parse_svg(
+            r#"<svg><g id="g"><a><rect id="deep"/></a><rect id="child"/></g></svg>"#,
+        )
+        .unwrap();
+        let svg = &dom.root.children[0];
+        let g = dom.root.find_by_id("g").unwrap();
+        let a = &g.children[0];
+
+        let sel = CssSelector::parse("svg g>rect");
+        assert!(matches!(sel, CssSelector::Child(_, _)));
+        assert_eq!(sel.specificity(), (0, 0, 3));
+        assert!(sel.matches(dom.root.find_by_id("child").unwrap(), &[&dom.root, svg, g]));
+        assert!(!sel.matches(
+            dom.root.find_by_id("deep").unwrap(),
+            &[&dom.root, svg, g, a]
+        ));
+        assert!(CssSelector::parse("svg rect").matches(
+            dom.root.find_by_id("deep").unwrap(),
+            &[&dom.root, svg, g, a]
+        ));
+    }
+
+    #[test]
+    fn test_cascade_specificity() {
+        let dom = crate::parser::parse_svg(
+            r#"<svg>
+                <style>
+                    #a { stroke-width: 4 }
+                    rect.thin { stroke-width: 1; opacity: 0.5 }
+                    .thin { stroke-width: 2; opacity: 0.25 }
+                    rect { opacity: 0.75 !important }
+                </style>
+                <rect id="a" class="thin"/>
+                <rect id="b" class="thin" style="stroke-width: 3"/>
+            </svg>"#,
+        )
+        .unwrap();
+
+        let a = dom.root.find_by_id("a").unwrap();
+        assert_eq!(a.stroke_width, 4.0);
+        assert_eq!(a.opacity, 0.75);
+        let b = dom.root.find_by_id("b").unwrap();
+        assert_eq!(b.stroke_width, 3.0);
+    }
+
     #[test]
     fn test_hsl_color() {
         let color = parse_css_color("hsl(0, 100%, 50%)").unwrap();
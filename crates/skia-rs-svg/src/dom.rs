@@ -1,6 +1,6 @@
 //! SVG DOM representation.
 
-use skia_rs_core::{Color, Matrix, Point, Rect, Scalar};
+use skia_rs_core::{Color, Matrix, Point, Rect, Scalar, Size};
 use skia_rs_paint::Paint;
 use skia_rs_path::Path;
 use std::collections::HashMap;
@@ -16,6 +16,25 @@ pub struct SvgDom {
     pub height: Scalar,
     /// View box.
     pub view_box: Option<Rect>,
+    /// How the view box is fitted to the viewport.
+    pub preserve_aspect_ratio: PreserveAspectRatio,
+    /// Size of the viewport the document is rendered into.
+    container_size: Option<Size>,
+    /// Fonts used to render text.
+    #[cfg(feature = "text")]
+    font_mgr: Option<SvgFontMgr>,
+}
+
+/// A shared font manager, wrapped so the DOM stays `Debug`.
+#[cfg(feature = "text")]
+#[derive(Clone)]
+struct SvgFontMgr(std::sync::Arc<dyn skia_rs_text::FontMgr>);
+
+#[cfg(feature = "text")]
+impl std::fmt::Debug for SvgFontMgr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FontMgr")
+    }
 }
 
 impl SvgDom {
@@ -34,6 +53,149 @@ impl SvgDom {
         self.view_box
             .unwrap_or_else(|| Rect::from_xywh(0.0, 0.0, self.width, self.height))
     }
+
+    /// Set the size of the viewport the document is rendered into.
+    ///
+    /// The view box is fitted to this size when rendering. If unset, the
+    /// canvas size is used.
+    pub fn set_container_size(&mut self, size: Size) {
+        self.container_size = Some(size);
+    }
+
+    /// Get the container size, if set.
+    pub fn container_size(&self) -> Option<Size> {
+        self.container_size
+    }
+
+    /// Set the font manager used to find the fonts of text elements.
+    ///
+    /// Families that aren't registered fall back to the manager's default
+    /// family. Without a font manager, text uses the default typeface.
+    #[cfg(feature = "text")]
+    pub fn set_font_mgr(&mut self, font_mgr: std::sync::Arc<dyn skia_rs_text::FontMgr>) {
+        self.font_mgr = Some(SvgFontMgr(font_mgr));
+    }
+
+    /// Get the font manager, if set.
+    #[cfg(feature = "text")]
+    pub fn font_mgr(&self) -> Option<&std::sync::Arc<dyn skia_rs_text::FontMgr>> {
+        self.font_mgr.as_ref().map(|mgr| &mgr.0)
+    }
+
+    /// Find the element an `href` such as `#icon` refers to.
+    pub fn resolve_href(&self, href: &str) -> Option<&SvgNode> {
+        self.root.find_by_id(href.strip_prefix('#').unwrap_or(href))
+    }
+
+    /// Instantiate a `<use>` element.
+    ///
+    /// Returns a group holding a copy of the referenced element, translated
+    /// by the use element's x/y. A referenced symbol becomes a group, with its
+    /// view box fitted to the use element's width and height if both are
+    /// given. The use element's own transform is not included: the instance
+    /// is meant to be drawn in its place, like a child. `<use>` elements
+    /// inside the copy are left unresolved. Returns `None` if `node` isn't a
+    /// use element or its reference can't be found.
+    pub fn resolve_use(&self, node: &SvgNode) -> Option<SvgNode> {
+        let SvgNodeKind::Use(svg_use) = &node.kind else {
+            return None;
+        };
+        let mut content = self.resolve_href(&svg_use.href)?.clone();
+
+        if let SvgNodeKind::Symbol(symbol) = &content.kind {
+            if let (Some(view_box), Some(width), Some(height)) =
+                (symbol.view_box, svg_use.width, svg_use.height)
+            {
+                let viewport = Rect::from_xywh(0.0, 0.0, width, height);
+                content.transform = content
+                    .transform
+                    .concat(&symbol.preserve_aspect_ratio.transform(&view_box, &viewport));
+            }
+            content.kind = SvgNodeKind::Group;
+        }
+
+        let mut instance = SvgNode::new(SvgNodeKind::Group);
+        instance.transform = Matrix::translate(svg_use.x, svg_use.y);
+        instance.add_child(content);
+        Some(instance)
+    }
+
+    /// Get the transform mapping the view box onto a viewport of `size`.
+    pub fn view_box_transform(&self, size: Size) -> Matrix {
+        self.preserve_aspect_ratio.transform(
+            &self.get_view_box(),
+            &Rect::from_xywh(0.0, 0.0, size.width, size.height),
+        )
+    }
+}
+
+/// Alignment of the view box along one axis (`preserveAspectRatio`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AspectAlign {
+    /// Align the view box's minimum edge with the viewport's.
+    Min,
+    /// Center the view box in the viewport.
+    #[default]
+    Mid,
+    /// Align the view box's maximum edge with the viewport's.
+    Max,
+}
+
+impl AspectAlign {
+    /// Offset of content of `length` placed in `space` with this alignment.
+    fn offset(self, space: Scalar, length: Scalar) -> Scalar {
+        match self {
+            Self::Min => 0.0,
+            Self::Mid => (space - length) / 2.0,
+            Self::Max => space - length,
+        }
+    }
+}
+
+/// The `preserveAspectRatio` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreserveAspectRatio {
+    /// Alignment along x and y, or `None` to stretch to the viewport.
+    pub align: Option<(AspectAlign, AspectAlign)>,
+    /// Scale to cover the viewport (`slice`) rather than fit inside it (`meet`).
+    pub slice: bool,
+}
+
+impl Default for PreserveAspectRatio {
+    fn default() -> Self {
+        Self {
+            align: Some((AspectAlign::Mid, AspectAlign::Mid)),
+            slice: false,
+        }
+    }
+}
+
+impl PreserveAspectRatio {
+    /// Get the transform mapping `view_box` onto `viewport`.
+    pub fn transform(&self, view_box: &Rect, viewport: &Rect) -> Matrix {
+        if view_box.width() <= 0.0 || view_box.height() <= 0.0 {
+            return Matrix::IDENTITY;
+        }
+        let mut sx = viewport.width() / view_box.width();
+        let mut sy = viewport.height() / view_box.height();
+
+        let (tx, ty) = match self.align {
+            None => (viewport.left, viewport.top),
+            Some((align_x, align_y)) => {
+                let scale = if self.slice { sx.max(sy) } else { sx.min(sy) };
+                sx = scale;
+                sy = scale;
+                (
+                    viewport.left + align_x.offset(viewport.width(), view_box.width() * scale),
+                    viewport.top + align_y.offset(viewport.height(), view_box.height() * scale),
+                )
+            }
+        };
+
+        Matrix::translate(tx, ty)
+            .concat(&Matrix::scale(sx, sy))
+            .concat(&Matrix::translate(-view_box.left, -view_box.top))
+    }
 }
 
 /// SVG node types.
@@ -59,16 +221,22 @@ pub enum SvgNodeKind {
     Path(Path),
     /// Text.
     Text(SvgText),
+    /// Text span, inside a text element or another span.
+    TSpan(SvgTSpan),
     /// Image.
     Image(SvgImage),
     /// Use (reference to another element).
-    Use(String),
+    Use(SvgUse),
     /// Definitions.
     Defs,
+    /// Symbol (content drawn only through `<use>`).
+    Symbol(SvgSymbol),
     /// Linear gradient.
     LinearGradient(SvgLinearGradient),
     /// Radial gradient.
     RadialGradient(SvgRadialGradient),
+    /// Pattern (tiled fill defined by its children).
+    Pattern(SvgPattern),
     /// Clip path.
     ClipPath(String),
     /// Unknown element.
@@ -244,7 +412,11 @@ pub struct SvgText {
     pub x: Scalar,
     /// Y position.
     pub y: Scalar,
-    /// Text content.
+    /// Horizontal shift from `x`.
+    pub dx: Scalar,
+    /// Vertical shift from `y`.
+    pub dy: Scalar,
+    /// Text content, up to the first span.
     pub content: String,
     /// Font family.
     pub font_family: Option<String>,
@@ -256,6 +428,34 @@ pub struct SvgText {
     pub text_anchor: TextAnchor,
 }
 
+/// SVG text span (`<tspan>`).
+///
+/// Unset positions continue from the end of the preceding text, and unset
+/// font properties are inherited from the parent.
+#[derive(Debug, Clone, Default)]
+pub struct SvgTSpan {
+    /// Absolute X position.
+    pub x: Option<Scalar>,
+    /// Absolute Y position.
+    pub y: Option<Scalar>,
+    /// Horizontal shift.
+    pub dx: Scalar,
+    /// Vertical shift.
+    pub dy: Scalar,
+    /// Text content, up to the first nested span.
+    pub content: String,
+    /// Text following the span, which belongs to the parent.
+    pub tail: String,
+    /// Font family.
+    pub font_family: Option<String>,
+    /// Font size.
+    pub font_size: Option<Scalar>,
+    /// Font weight.
+    pub font_weight: Option<u16>,
+    /// Text anchor.
+    pub text_anchor: Option<TextAnchor>,
+}
+
 /// Text anchor alignment.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum TextAnchor {
@@ -268,6 +468,30 @@ pub enum TextAnchor {
     End,
 }
 
+/// SVG use element.
+#[derive(Debug, Clone, Default)]
+pub struct SvgUse {
+    /// Referenced element (`#id`).
+    pub href: String,
+    /// X offset of the instance.
+    pub x: Scalar,
+    /// Y offset of the instance.
+    pub y: Scalar,
+    /// Viewport width for a referenced symbol.
+    pub width: Option<Scalar>,
+    /// Viewport height for a referenced symbol.
+    pub height: Option<Scalar>,
+}
+
+/// SVG symbol.
+#[derive(Debug, Clone, Default)]
+pub struct SvgSymbol {
+    /// View box, fitted to the viewport of the `<use>` that draws it.
+    pub view_box: Option<Rect>,
+    /// How the view box is fitted to the viewport.
+    pub preserve_aspect_ratio: PreserveAspectRatio,
+}
+
 /// SVG image.
 #[derive(Debug, Clone, Default)]
 pub struct SvgImage {
@@ -302,6 +526,8 @@ pub struct SvgLinearGradient {
     pub units: GradientUnits,
     /// Transform.
     pub transform: Matrix,
+    /// Gradient whose stops are used when this one has none (`href`).
+    pub href: Option<String>,
 }
 
 /// SVG radial gradient.
@@ -325,6 +551,32 @@ pub struct SvgRadialGradient {
     pub units: GradientUnits,
     /// Transform.
     pub transform: Matrix,
+    /// Gradient whose stops are used when this one has none (`href`).
+    pub href: Option<String>,
+}
+
+/// SVG pattern.
+///
+/// The pattern's children are drawn into a tile that repeats across the
+/// filled shape.
+#[derive(Debug, Clone, Default)]
+pub struct SvgPattern {
+    /// Tile X.
+    pub x: Scalar,
+    /// Tile Y.
+    pub y: Scalar,
+    /// Tile width.
+    pub width: Scalar,
+    /// Tile height.
+    pub height: Scalar,
+    /// Units of the tile rectangle (`patternUnits`).
+    pub units: GradientUnits,
+    /// Units of the pattern content (`patternContentUnits`).
+    pub content_units: GradientUnits,
+    /// Content view box, mapped onto the tile.
+    pub view_box: Option<Rect>,
+    /// Pattern transform (`patternTransform`).
+    pub transform: Matrix,
 }
 
 /// Gradient stop.
@@ -81,6 +81,8 @@ pub fn export_svg_with_options(dom: &SvgDom, options: &SvgExportOptions) -> Stri
         .unwrap();
     }
 
+    export_preserve_aspect_ratio(&mut output, &dom.preserve_aspect_ratio);
+
     output.push('>');
 
     if options.pretty_print {
@@ -267,6 +269,7 @@ fn export_node(output: &mut String, node: &SvgNode, options: &SvgExportOptions,
                 format_scalar(text.y, options.precision)
             )
             .unwrap();
+            export_text_shift(output, text.dx, text.dy, options);
 
             if let Some(ref family) = text.font_family {
                 write!(output, " font-family=\"{}\"", escape_xml(family)).unwrap();
@@ -292,9 +295,53 @@ fn export_node(output: &mut String, node: &SvgNode, options: &SvgExportOptions,
             export_common_attrs(output, node, options);
             output.push('>');
             output.push_str(&escape_xml(&text.content));
+            // No indentation inside text, where white space is content
+            for child in &node.children {
+                export_node(output, child, options, 0);
+            }
             output.push_str("</text>");
             output.push_str(newline);
         }
+        SvgNodeKind::TSpan(span) => {
+            output.push_str("<tspan");
+
+            if let Some(x) = span.x {
+                write!(output, " x=\"{}\"", format_scalar(x, options.precision)).unwrap();
+            }
+            if let Some(y) = span.y {
+                write!(output, " y=\"{}\"", format_scalar(y, options.precision)).unwrap();
+            }
+            export_text_shift(output, span.dx, span.dy, options);
+            if let Some(ref family) = span.font_family {
+                write!(output, " font-family=\"{}\"", escape_xml(family)).unwrap();
+            }
+            if let Some(size) = span.font_size {
+                write!(
+                    output,
+                    " font-size=\"{}\"",
+                    format_scalar(size, options.precision)
+                )
+                .unwrap();
+            }
+            if let Some(weight) = span.font_weight {
+                write!(output, " font-weight=\"{}\"", weight).unwrap();
+            }
+            match span.text_anchor {
+                Some(TextAnchor::Start) => output.push_str(" text-anchor=\"start\""),
+                Some(TextAnchor::Middle) => output.push_str(" text-anchor=\"middle\""),
+                Some(TextAnchor::End) => output.push_str(" text-anchor=\"end\""),
+                None => {}
+            }
+
+            export_common_attrs(output, node, options);
+            output.push('>');
+            output.push_str(&escape_xml(&span.content));
+            for child in &node.children {
+                export_node(output, child, options, 0);
+            }
+            output.push_str("</tspan>");
+            output.push_str(&escape_xml(&span.tail));
+        }
         SvgNodeKind::Image(image) => {
             output.push_str(&indent);
             output.push_str("<image");
@@ -315,14 +362,66 @@ fn export_node(output: &mut String, node: &SvgNode, options: &SvgExportOptions,
             output.push_str("/>");
             output.push_str(newline);
         }
-        SvgNodeKind::Use(href) => {
+        SvgNodeKind::Use(svg_use) => {
             output.push_str(&indent);
             output.push_str("<use");
-            write!(output, " href=\"{}\"", escape_xml(href)).unwrap();
+            write!(output, " href=\"{}\"", escape_xml(&svg_use.href)).unwrap();
+            if svg_use.x != 0.0 || svg_use.y != 0.0 {
+                write!(
+                    output,
+                    " x=\"{}\" y=\"{}\"",
+                    format_scalar(svg_use.x, options.precision),
+                    format_scalar(svg_use.y, options.precision)
+                )
+                .unwrap();
+            }
+            if let Some(width) = svg_use.width {
+                write!(
+                    output,
+                    " width=\"{}\"",
+                    format_scalar(width, options.precision)
+                )
+                .unwrap();
+            }
+            if let Some(height) = svg_use.height {
+                write!(
+                    output,
+                    " height=\"{}\"",
+                    format_scalar(height, options.precision)
+                )
+                .unwrap();
+            }
             export_common_attrs(output, node, options);
             output.push_str("/>");
             output.push_str(newline);
         }
+        SvgNodeKind::Symbol(symbol) => {
+            output.push_str(&indent);
+            output.push_str("<symbol");
+            if let Some(vb) = &symbol.view_box {
+                write!(
+                    output,
+                    " viewBox=\"{} {} {} {}\"",
+                    format_scalar(vb.left, options.precision),
+                    format_scalar(vb.top, options.precision),
+                    format_scalar(vb.width(), options.precision),
+                    format_scalar(vb.height(), options.precision)
+                )
+                .unwrap();
+            }
+            export_preserve_aspect_ratio(output, &symbol.preserve_aspect_ratio);
+            export_common_attrs(output, node, options);
+            output.push('>');
+            output.push_str(newline);
+
+            for child in &node.children {
+                export_node(output, child, options, depth + 1);
+            }
+
+            output.push_str(&indent);
+            output.push_str("</symbol>");
+            output.push_str(newline);
+        }
         SvgNodeKind::Defs => {
             output.push_str(&indent);
             output.push_str("<defs>");
@@ -355,6 +454,9 @@ fn export_node(output: &mut String, node: &SvgNode, options: &SvgExportOptions,
             .unwrap();
 
             export_gradient_attrs(output, &grad.spread, &grad.units);
+            if let Some(href) = &grad.href {
+                write!(output, " href=\"{}\"", escape_xml(href)).unwrap();
+            }
 
             if !grad.transform.is_identity() {
                 export_transform_attr(output, &grad.transform, options);
@@ -399,6 +501,9 @@ fn export_node(output: &mut String, node: &SvgNode, options: &SvgExportOptions,
             }
 
             export_gradient_attrs(output, &grad.spread, &grad.units);
+            if let Some(href) = &grad.href {
+                write!(output, " href=\"{}\"", escape_xml(href)).unwrap();
+            }
 
             if !grad.transform.is_identity() {
                 export_transform_attr(output, &grad.transform, options);
@@ -415,6 +520,56 @@ fn export_node(output: &mut String, node: &SvgNode, options: &SvgExportOptions,
             output.push_str("</radialGradient>");
             output.push_str(newline);
         }
+        SvgNodeKind::Pattern(pattern) => {
+            output.push_str(&indent);
+            output.push_str("<pattern");
+
+            if let Some(ref id) = node.id {
+                write!(output, " id=\"{}\"", escape_xml(id)).unwrap();
+            }
+
+            write!(
+                output,
+                " x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+                format_scalar(pattern.x, options.precision),
+                format_scalar(pattern.y, options.precision),
+                format_scalar(pattern.width, options.precision),
+                format_scalar(pattern.height, options.precision)
+            )
+            .unwrap();
+
+            if pattern.units == GradientUnits::UserSpaceOnUse {
+                output.push_str(" patternUnits=\"userSpaceOnUse\"");
+            }
+            if pattern.content_units == GradientUnits::ObjectBoundingBox {
+                output.push_str(" patternContentUnits=\"objectBoundingBox\"");
+            }
+            if let Some(vb) = &pattern.view_box {
+                write!(
+                    output,
+                    " viewBox=\"{} {} {} {}\"",
+                    format_scalar(vb.left, options.precision),
+                    format_scalar(vb.top, options.precision),
+                    format_scalar(vb.width(), options.precision),
+                    format_scalar(vb.height(), options.precision)
+                )
+                .unwrap();
+            }
+            if !pattern.transform.is_identity() {
+                export_matrix_attr(output, "patternTransform", &pattern.transform, options);
+            }
+
+            output.push('>');
+            output.push_str(newline);
+
+            for child in &node.children {
+                export_node(output, child, options, depth + 1);
+            }
+
+            output.push_str(&indent);
+            output.push_str("</pattern>");
+            output.push_str(newline);
+        }
         SvgNodeKind::ClipPath(id) => {
             output.push_str(&indent);
             write!(output, "<clipPath id=\"{}\">", escape_xml(id)).unwrap();
@@ -532,7 +687,20 @@ fn is_standard_attr(key: &str) -> bool {
     )
 }
 
-fn export_transform_attr(output: &mut String, matrix: &Matrix, options: &SvgExportOptions) {
+pub(crate) fn export_transform_attr(
+    output: &mut String,
+    matrix: &Matrix,
+    options: &SvgExportOptions,
+) {
+    export_matrix_attr(output, "transform", matrix, options);
+}
+
+fn export_matrix_attr(
+    output: &mut String,
+    name: &str,
+    matrix: &Matrix,
+    options: &SvgExportOptions,
+) {
     let v = &matrix.values;
 
     // Check for special cases
@@ -547,7 +715,8 @@ fn export_transform_attr(output: &mut String, matrix: &Matrix, options: &SvgExpo
     if is_translate && (v[2].abs() > 0.001 || v[5].abs() > 0.001) {
         write!(
             output,
-            " transform=\"translate({}, {})\"",
+            " {}=\"translate({}, {})\"",
+            name,
             format_scalar(v[2], options.precision),
             format_scalar(v[5], options.precision)
         )
@@ -556,14 +725,16 @@ fn export_transform_attr(output: &mut String, matrix: &Matrix, options: &SvgExpo
         if (v[0] - v[4]).abs() < 0.001 {
             write!(
                 output,
-                " transform=\"scale({})\"",
+                " {}=\"scale({})\"",
+                name,
                 format_scalar(v[0], options.precision)
             )
             .unwrap();
         } else {
             write!(
                 output,
-                " transform=\"scale({}, {})\"",
+                " {}=\"scale({}, {})\"",
+                name,
                 format_scalar(v[0], options.precision),
                 format_scalar(v[4], options.precision)
             )
@@ -573,7 +744,8 @@ fn export_transform_attr(output: &mut String, matrix: &Matrix, options: &SvgExpo
         // Use matrix form
         write!(
             output,
-            " transform=\"matrix({}, {}, {}, {}, {}, {})\"",
+            " {}=\"matrix({}, {}, {}, {}, {}, {})\"",
+            name,
             format_scalar(v[0], options.precision),
             format_scalar(v[3], options.precision),
             format_scalar(v[1], options.precision),
@@ -607,74 +779,36 @@ fn export_points_attr(
 }
 
 fn export_path_data(path: &skia_rs_path::Path, options: &SvgExportOptions) -> String {
-    use skia_rs_path::PathElement;
-
-    let mut data = String::new();
+    path.to_svg_string_with_precision(options.precision)
+}
 
-    for elem in path.iter() {
-        match elem {
-            PathElement::Move(p) => {
-                write!(
-                    data,
-                    "M{} {}",
-                    format_scalar(p.x, options.precision),
-                    format_scalar(p.y, options.precision)
-                )
-                .unwrap();
-            }
-            PathElement::Line(p) => {
-                write!(
-                    data,
-                    "L{} {}",
-                    format_scalar(p.x, options.precision),
-                    format_scalar(p.y, options.precision)
-                )
-                .unwrap();
-            }
-            PathElement::Quad(p1, p2) => {
-                write!(
-                    data,
-                    "Q{} {} {} {}",
-                    format_scalar(p1.x, options.precision),
-                    format_scalar(p1.y, options.precision),
-                    format_scalar(p2.x, options.precision),
-                    format_scalar(p2.y, options.precision)
-                )
-                .unwrap();
-            }
-            PathElement::Conic(p1, p2, _w) => {
-                // Conics aren't directly supported in SVG, approximate with quad
-                // For now, output as quad (lossy)
-                write!(
-                    data,
-                    "Q{} {} {} {}",
-                    format_scalar(p1.x, options.precision),
-                    format_scalar(p1.y, options.precision),
-                    format_scalar(p2.x, options.precision),
-                    format_scalar(p2.y, options.precision)
-                )
-                .unwrap();
-            }
-            PathElement::Cubic(p1, p2, p3) => {
-                write!(
-                    data,
-                    "C{} {} {} {} {} {}",
-                    format_scalar(p1.x, options.precision),
-                    format_scalar(p1.y, options.precision),
-                    format_scalar(p2.x, options.precision),
-                    format_scalar(p2.y, options.precision),
-                    format_scalar(p3.x, options.precision),
-                    format_scalar(p3.y, options.precision)
-                )
-                .unwrap();
-            }
-            PathElement::Close => {
-                data.push('Z');
-            }
-        }
+fn export_preserve_aspect_ratio(output: &mut String, par: &PreserveAspectRatio) {
+    if *par == PreserveAspectRatio::default() {
+        return;
+    }
+    let axis = |align| match align {
+        AspectAlign::Min => "Min",
+        AspectAlign::Mid => "Mid",
+        AspectAlign::Max => "Max",
+    };
+    output.push_str(" preserveAspectRatio=\"");
+    match par.align {
+        Some((x, y)) => write!(output, "x{}Y{}", axis(x), axis(y)).unwrap(),
+        None => output.push_str("none"),
+    }
+    if par.slice {
+        output.push_str(" slice");
     }
+    output.push('"');
+}
 
-    data
+fn export_text_shift(output: &mut String, dx: Scalar, dy: Scalar, options: &SvgExportOptions) {
+    if dx != 0.0 {
+        write!(output, " dx=\"{}\"", format_scalar(dx, options.precision)).unwrap();
+    }
+    if dy != 0.0 {
+        write!(output, " dy=\"{}\"", format_scalar(dy, options.precision)).unwrap();
+    }
 }
 
 fn export_gradient_attrs(output: &mut String, spread: &SpreadMethod, units: &GradientUnits) {
@@ -733,7 +867,7 @@ fn format_paint(paint: &SvgPaint) -> String {
     }
 }
 
-fn format_color(color: &Color) -> String {
+pub(crate) fn format_color(color: &Color) -> String {
     if color.alpha() == 255 {
         format!(
             "#{:02x}{:02x}{:02x}",
@@ -752,7 +886,7 @@ fn format_color(color: &Color) -> String {
     }
 }
 
-fn format_scalar(value: Scalar, precision: usize) -> String {
+pub(crate) fn format_scalar(value: Scalar, precision: usize) -> String {
     let formatted = format!("{:.prec$}", value, prec = precision);
     // Remove trailing zeros and decimal point if unnecessary
     let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
@@ -763,7 +897,7 @@ fn format_scalar(value: Scalar, precision: usize) -> String {
     }
 }
 
-fn escape_xml(s: &str) -> String {
+pub(crate) fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -826,6 +960,22 @@ mod tests {
         assert!(!svg.contains("<?xml"));
     }
 
+    #[test]
+    fn test_export_path_data_conic_as_cubics() {
+        let mut builder = skia_rs_path::PathBuilder::new();
+        builder.move_to(100.0, 0.0).conic_to(
+            100.0,
+            100.0,
+            0.0,
+            100.0,
+            std::f32::consts::FRAC_1_SQRT_2,
+        );
+        let data = export_path_data(&builder.build(), &SvgExportOptions::default());
+        assert!(data.starts_with("M100 0C"));
+        assert!(!data.contains('Q'));
+        assert!(data.ends_with(" 0 100"));
+    }
+
     #[test]
     fn test_format_scalar() {
         assert_eq!(format_scalar(10.0, 3), "10");
@@ -5,17 +5,19 @@
 //! - SVG rendering to canvas
 //! - SVG DOM manipulation
 //! - CSS styling support
-//! - SVG export
+//! - SVG export, including recording canvas draw calls as SVG
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+pub mod canvas;
 pub mod css;
 pub mod dom;
 pub mod export;
 pub mod parser;
 pub mod render;
 
+pub use canvas::SvgCanvas;
 pub use css::{CssRule, CssSelector, Stylesheet, apply_stylesheet, parse_inline_style};
 pub use dom::*;
 pub use export::{SvgExportOptions, export_svg, export_svg_with_options};
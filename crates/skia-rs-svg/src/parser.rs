@@ -29,11 +29,20 @@ pub fn parse_svg(svg: &str) -> Result<SvgDom, SvgError> {
     let mut current_tag = String::new();
     let mut attributes = HashMap::new();
     let mut node_stack: Vec<SvgNode> = vec![SvgNode::new(SvgNodeKind::Svg)];
+    let mut text = String::new();
 
     let mut chars = svg.chars().peekable();
 
     while let Some(c) = chars.next() {
-        if c == '<' {
+        if c != '<' {
+            text.push(c);
+        } else {
+            // Character data before this tag
+            if let Some(node) = node_stack.last_mut() {
+                add_text_content(node, &text);
+            }
+            text.clear();
+
             if chars.peek() == Some(&'/') {
                 // Closing tag
                 chars.next(); // Skip '/'
@@ -53,6 +62,19 @@ pub fn parse_svg(svg: &str) -> Result<SvgDom, SvgError> {
                         parent.add_child(node);
                     }
                 }
+            } else if chars.clone().take(8).eq("![CDATA[".chars()) {
+                // Character data, such as a style sheet
+                let mut cdata = String::new();
+                for ch in chars.by_ref().skip(8) {
+                    cdata.push(ch);
+                    if cdata.ends_with("]]>") {
+                        break;
+                    }
+                }
+                let cdata = cdata.strip_suffix("]]>").unwrap_or(&cdata);
+                if let Some(node) = node_stack.last_mut() {
+                    add_text_content(node, cdata);
+                }
             } else if chars.peek() == Some(&'!') {
                 // Comment or DOCTYPE, skip
                 while let Some(ch) = chars.next() {
@@ -135,8 +157,25 @@ pub fn parse_svg(svg: &str) -> Result<SvgDom, SvgError> {
                     chars.next();
                 }
 
+                // Stops belong to the enclosing gradient
+                if current_tag == "stop" {
+                    if let Some(parent) = node_stack.last_mut() {
+                        add_gradient_stop(parent, parse_stop(&attributes));
+                    }
+                }
+
                 // Create node
-                let node = create_node(&current_tag, &attributes, &mut dom)?;
+                let mut node = create_node(&current_tag, &attributes, &mut dom)?;
+
+                // Spans are painted like their parent unless they say otherwise
+                if let (SvgNodeKind::TSpan(_), Some(parent)) = (&node.kind, node_stack.last()) {
+                    if !attributes.contains_key("fill") {
+                        node.fill = parent.fill.clone();
+                    }
+                    if !attributes.contains_key("stroke") {
+                        node.stroke = parent.stroke.clone();
+                    }
+                }
 
                 if self_closing {
                     if let Some(parent) = node_stack.last_mut() {
@@ -158,6 +197,11 @@ pub fn parse_svg(svg: &str) -> Result<SvgDom, SvgError> {
     }
 
     dom.root = node_stack.pop().unwrap_or_default();
+    normalize_text(&mut dom.root);
+
+    // Style sheets and style attributes override presentation attributes
+    let stylesheet = crate::css::extract_stylesheets(&dom);
+    crate::css::apply_stylesheet(&mut dom, &stylesheet);
     Ok(dom)
 }
 
@@ -176,6 +220,10 @@ fn create_node(
                 dom.view_box = parse_viewbox(vb);
             }
 
+            if let Some(par) = attrs.get("preserveAspectRatio") {
+                dom.preserve_aspect_ratio = parse_preserve_aspect_ratio(par);
+            }
+
             SvgNode::new(SvgNodeKind::Svg)
         }
         "g" => SvgNode::new(SvgNodeKind::Group),
@@ -233,21 +281,35 @@ fn create_node(
             let text = SvgText {
                 x: parse_length(attrs.get("x").map(|s| s.as_str()).unwrap_or("0")),
                 y: parse_length(attrs.get("y").map(|s| s.as_str()).unwrap_or("0")),
+                dx: parse_length(attrs.get("dx").map(|s| s.as_str()).unwrap_or("0")),
+                dy: parse_length(attrs.get("dy").map(|s| s.as_str()).unwrap_or("0")),
                 content: String::new(), // Will be filled with text content
                 font_family: attrs.get("font-family").cloned(),
                 font_size: parse_length(attrs.get("font-size").map(|s| s.as_str()).unwrap_or("12")),
                 font_weight: attrs
                     .get("font-weight")
-                    .and_then(|w| w.parse().ok())
-                    .unwrap_or(400),
-                text_anchor: match attrs.get("text-anchor").map(|s| s.as_str()) {
-                    Some("middle") => TextAnchor::Middle,
-                    Some("end") => TextAnchor::End,
-                    _ => TextAnchor::Start,
-                },
+                    .map_or(400, |w| parse_font_weight(w)),
+                text_anchor: attrs
+                    .get("text-anchor")
+                    .map_or(TextAnchor::Start, |a| parse_text_anchor(a)),
             };
             SvgNode::new(SvgNodeKind::Text(text))
         }
+        "tspan" => {
+            let span = SvgTSpan {
+                x: attrs.get("x").map(|s| parse_length(s)),
+                y: attrs.get("y").map(|s| parse_length(s)),
+                dx: parse_length(attrs.get("dx").map(|s| s.as_str()).unwrap_or("0")),
+                dy: parse_length(attrs.get("dy").map(|s| s.as_str()).unwrap_or("0")),
+                content: String::new(),
+                tail: String::new(),
+                font_family: attrs.get("font-family").cloned(),
+                font_size: attrs.get("font-size").map(|s| parse_length(s)),
+                font_weight: attrs.get("font-weight").map(|w| parse_font_weight(w)),
+                text_anchor: attrs.get("text-anchor").map(|a| parse_text_anchor(a)),
+            };
+            SvgNode::new(SvgNodeKind::TSpan(span))
+        }
         "defs" => SvgNode::new(SvgNodeKind::Defs),
         "linearGradient" => {
             let gradient = SvgLinearGradient {
@@ -256,9 +318,10 @@ fn create_node(
                 x2: parse_length(attrs.get("x2").map(|s| s.as_str()).unwrap_or("100%")),
                 y2: parse_length(attrs.get("y2").map(|s| s.as_str()).unwrap_or("0")),
                 stops: Vec::new(),
-                spread: SpreadMethod::Pad,
-                units: GradientUnits::ObjectBoundingBox,
-                transform: Matrix::IDENTITY,
+                spread: parse_spread_method(attrs),
+                units: parse_gradient_units(attrs),
+                transform: parse_gradient_transform(attrs),
+                href: parse_href(attrs),
             };
             SvgNode::new(SvgNodeKind::LinearGradient(gradient))
         }
@@ -282,19 +345,52 @@ fn create_node(
                         .unwrap_or(&cy.to_string()),
                 ),
                 stops: Vec::new(),
-                spread: SpreadMethod::Pad,
-                units: GradientUnits::ObjectBoundingBox,
-                transform: Matrix::IDENTITY,
+                spread: parse_spread_method(attrs),
+                units: parse_gradient_units(attrs),
+                transform: parse_gradient_transform(attrs),
+                href: parse_href(attrs),
             };
             SvgNode::new(SvgNodeKind::RadialGradient(gradient))
         }
+        "pattern" => {
+            let units = |name: &str, default| match attrs.get(name).map(|s| s.as_str()) {
+                Some("userSpaceOnUse") => GradientUnits::UserSpaceOnUse,
+                Some("objectBoundingBox") => GradientUnits::ObjectBoundingBox,
+                _ => default,
+            };
+            let pattern = SvgPattern {
+                x: parse_length(attrs.get("x").map(|s| s.as_str()).unwrap_or("0")),
+                y: parse_length(attrs.get("y").map(|s| s.as_str()).unwrap_or("0")),
+                width: parse_length(attrs.get("width").map(|s| s.as_str()).unwrap_or("0")),
+                height: parse_length(attrs.get("height").map(|s| s.as_str()).unwrap_or("0")),
+                units: units("patternUnits", GradientUnits::ObjectBoundingBox),
+                content_units: units("patternContentUnits", GradientUnits::UserSpaceOnUse),
+                view_box: attrs.get("viewBox").and_then(|vb| parse_viewbox(vb)),
+                transform: attrs
+                    .get("patternTransform")
+                    .map_or(Matrix::IDENTITY, |t| parse_transform(t)),
+            };
+            SvgNode::new(SvgNodeKind::Pattern(pattern))
+        }
         "use" => {
-            let href = attrs
-                .get("href")
-                .or_else(|| attrs.get("xlink:href"))
-                .cloned()
-                .unwrap_or_default();
-            SvgNode::new(SvgNodeKind::Use(href))
+            let svg_use = SvgUse {
+                href: parse_href(attrs).unwrap_or_default(),
+                x: parse_length(attrs.get("x").map(|s| s.as_str()).unwrap_or("0")),
+                y: parse_length(attrs.get("y").map(|s| s.as_str()).unwrap_or("0")),
+                width: attrs.get("width").map(|s| parse_length(s)),
+                height: attrs.get("height").map(|s| parse_length(s)),
+            };
+            SvgNode::new(SvgNodeKind::Use(svg_use))
+        }
+        "symbol" => {
+            let symbol = SvgSymbol {
+                view_box: attrs.get("viewBox").and_then(|vb| parse_viewbox(vb)),
+                preserve_aspect_ratio: attrs
+                    .get("preserveAspectRatio")
+                    .map(|par| parse_preserve_aspect_ratio(par))
+                    .unwrap_or_default(),
+            };
+            SvgNode::new(SvgNodeKind::Symbol(symbol))
         }
         _ => SvgNode::new(SvgNodeKind::Unknown(tag.to_string())),
     };
@@ -302,6 +398,11 @@ fn create_node(
     // Parse common attributes
     node.id = attrs.get("id").cloned();
 
+    // Applied along with style sheets once the document is parsed
+    if let Some(style) = attrs.get("style") {
+        node.attributes.insert("style".to_string(), style.clone());
+    }
+
     if let Some(class) = attrs.get("class") {
         node.classes = class.split_whitespace().map(|s| s.to_string()).collect();
     }
@@ -339,6 +440,227 @@ fn create_node(
     Ok(node)
 }
 
+/// Parse a `font-weight` value.
+fn parse_font_weight(s: &str) -> u16 {
+    match s.trim() {
+        "normal" => 400,
+        "bold" => 700,
+        "lighter" => 300,
+        "bolder" => 800,
+        weight => weight.parse().unwrap_or(400),
+    }
+}
+
+/// Parse a `text-anchor` value.
+fn parse_text_anchor(s: &str) -> TextAnchor {
+    match s.trim() {
+        "middle" => TextAnchor::Middle,
+        "end" => TextAnchor::End,
+        _ => TextAnchor::Start,
+    }
+}
+
+/// Add character data to the node it appears in.
+///
+/// Text inside a text element after one of its spans is the span's tail.
+/// Style sheet text is kept for [`extract_stylesheets`](crate::css::extract_stylesheets).
+fn add_text_content(node: &mut SvgNode, raw: &str) {
+    if raw.is_empty() {
+        return;
+    }
+    let text = decode_entities(raw);
+
+    if let Some(SvgNode {
+        kind: SvgNodeKind::TSpan(span),
+        ..
+    }) = node.children.last_mut()
+    {
+        if matches!(node.kind, SvgNodeKind::Text(_) | SvgNodeKind::TSpan(_)) {
+            span.tail.push_str(&text);
+            return;
+        }
+    }
+    match &mut node.kind {
+        SvgNodeKind::Text(svg_text) => svg_text.content.push_str(&text),
+        SvgNodeKind::TSpan(span) => span.content.push_str(&text),
+        SvgNodeKind::Unknown(tag) if tag == "style" => node
+            .attributes
+            .entry("__text_content".to_string())
+            .or_default()
+            .push_str(&text),
+        _ => {}
+    }
+}
+
+/// Replace the predefined XML entities and character references.
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let decoded = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => entity.strip_prefix('#').and_then(|code| {
+                match code.strip_prefix('x') {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse().ok(),
+                }
+                .and_then(char::from_u32)
+            }),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Apply default SVG white space handling to every text element.
+///
+/// Runs of white space collapse to one space, also across spans, and leading
+/// and trailing white space is removed.
+fn normalize_text(node: &mut SvgNode) {
+    if let SvgNodeKind::Text(_) = node.kind {
+        let mut pieces = Vec::new();
+        collect_text_pieces(node, &mut pieces);
+
+        let mut after_space = true;
+        for piece in &mut pieces {
+            let mut collapsed = String::with_capacity(piece.len());
+            for c in piece.chars() {
+                if !c.is_whitespace() {
+                    collapsed.push(c);
+                    after_space = false;
+                } else if !after_space {
+                    collapsed.push(' ');
+                    after_space = true;
+                }
+            }
+            **piece = collapsed;
+        }
+        if let Some(last) = pieces.iter_mut().rev().find(|piece| !piece.is_empty()) {
+            if last.ends_with(' ') {
+                last.pop();
+            }
+        }
+        return;
+    }
+
+    for child in &mut node.children {
+        normalize_text(child);
+    }
+}
+
+/// The text strings of a text element or span, in document order.
+fn collect_text_pieces<'a>(node: &'a mut SvgNode, pieces: &mut Vec<&'a mut String>) {
+    let SvgNode { kind, children, .. } = node;
+    let tail = match kind {
+        SvgNodeKind::Text(text) => {
+            pieces.push(&mut text.content);
+            None
+        }
+        SvgNodeKind::TSpan(span) => {
+            pieces.push(&mut span.content);
+            Some(&mut span.tail)
+        }
+        _ => return,
+    };
+    for child in children {
+        collect_text_pieces(child, pieces);
+    }
+    if let Some(tail) = tail {
+        pieces.push(tail);
+    }
+}
+
+/// Get the `href` (or legacy `xlink:href`) attribute.
+fn parse_href(attrs: &HashMap<String, String>) -> Option<String> {
+    attrs
+        .get("href")
+        .or_else(|| attrs.get("xlink:href"))
+        .cloned()
+}
+
+/// Parse the `gradientUnits` attribute.
+fn parse_gradient_units(attrs: &HashMap<String, String>) -> GradientUnits {
+    match attrs.get("gradientUnits").map(|s| s.as_str()) {
+        Some("userSpaceOnUse") => GradientUnits::UserSpaceOnUse,
+        _ => GradientUnits::ObjectBoundingBox,
+    }
+}
+
+/// Parse the `spreadMethod` attribute.
+fn parse_spread_method(attrs: &HashMap<String, String>) -> SpreadMethod {
+    match attrs.get("spreadMethod").map(|s| s.as_str()) {
+        Some("reflect") => SpreadMethod::Reflect,
+        Some("repeat") => SpreadMethod::Repeat,
+        _ => SpreadMethod::Pad,
+    }
+}
+
+/// Parse the `gradientTransform` attribute.
+fn parse_gradient_transform(attrs: &HashMap<String, String>) -> Matrix {
+    attrs
+        .get("gradientTransform")
+        .map_or(Matrix::IDENTITY, |t| parse_transform(t))
+}
+
+/// Parse a `<stop>` element.
+///
+/// `stop-color` and `stop-opacity` may also be given in a `style` attribute,
+/// which takes precedence.
+fn parse_stop(attrs: &HashMap<String, String>) -> GradientStop {
+    let style = attrs
+        .get("style")
+        .map(|s| crate::css::parse_inline_style(s))
+        .unwrap_or_default();
+    let property = |name: &str| style.get(name).or_else(|| attrs.get(name));
+
+    let offset = attrs.get("offset").map_or(0.0, |s| {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<Scalar>().unwrap_or(0.0) / 100.0,
+            None => s.parse().unwrap_or(0.0),
+        }
+    });
+
+    GradientStop {
+        offset: offset.clamp(0.0, 1.0),
+        color: property("stop-color")
+            .and_then(|c| parse_color(c))
+            .unwrap_or(Color::BLACK),
+        opacity: property("stop-opacity")
+            .and_then(|o| o.trim().parse::<Scalar>().ok())
+            .unwrap_or(1.0)
+            .clamp(0.0, 1.0),
+    }
+}
+
+/// Append a stop to a gradient node; stops elsewhere are ignored.
+fn add_gradient_stop(node: &mut SvgNode, stop: GradientStop) {
+    match &mut node.kind {
+        SvgNodeKind::LinearGradient(gradient) => gradient.stops.push(stop),
+        SvgNodeKind::RadialGradient(gradient) => gradient.stops.push(stop),
+        _ => {}
+    }
+}
+
 /// Parse an SVG length value.
 fn parse_length(s: &str) -> Scalar {
     let s = s.trim();
@@ -371,6 +693,34 @@ fn parse_viewbox(s: &str) -> Option<Rect> {
     }
 }
 
+/// Parse a preserveAspectRatio attribute.
+fn parse_preserve_aspect_ratio(s: &str) -> PreserveAspectRatio {
+    let mut parts = s.split_whitespace();
+    let align = parts.next().unwrap_or("xMidYMid");
+    let slice = parts.next() == Some("slice");
+
+    let axis = |name: &str| match name {
+        "Min" => Some(AspectAlign::Min),
+        "Mid" => Some(AspectAlign::Mid),
+        "Max" => Some(AspectAlign::Max),
+        _ => None,
+    };
+    let align = if align == "none" {
+        None
+    } else {
+        // xMinYMid etc.
+        match (
+            align.get(1..4).and_then(axis),
+            align.get(5..8).and_then(axis),
+        ) {
+            (Some(x), Some(y)) => Some((x, y)),
+            _ => PreserveAspectRatio::default().align,
+        }
+    };
+
+    PreserveAspectRatio { align, slice }
+}
+
 /// Parse points attribute (for polyline/polygon).
 fn parse_points(s: &str) -> Vec<Point> {
     let nums: Vec<Scalar> = s
@@ -596,6 +946,54 @@ mod tests {
         assert_eq!(dom.height, 100.0);
     }
 
+    #[test]
+    fn test_parse_gradient_stops() {
+        let svg = r#"<svg>
+            <linearGradient id="g" gradientUnits="userSpaceOnUse" spreadMethod="reflect">
+                <stop offset="20%" stop-color="red"/>
+                <stop offset="1" style="stop-color:#00f;stop-opacity:0.5"/>
+            </linearGradient>
+        </svg>"#;
+
+        let dom = parse_svg(svg).unwrap();
+        let Some(SvgNodeKind::LinearGradient(gradient)) =
+            dom.root.find_by_id("g").map(|node| &node.kind)
+        else {
+            panic!("gradient not parsed");
+        };
+        assert_eq!(gradient.units, GradientUnits::UserSpaceOnUse);
+        assert_eq!(gradient.spread, SpreadMethod::Reflect);
+        assert_eq!(gradient.stops.len(), 2);
+        assert!((gradient.stops[0].offset - 0.2).abs() < 1e-6);
+        assert_eq!(gradient.stops[1].color, Color::from_rgb(0, 0, 255));
+        assert_eq!(gradient.stops[1].opacity, 0.5);
+    }
+
+    #[test]
+    fn test_parse_text_content() {
+        let svg = r#"<svg>
+            <text id="t" x="5" y="10">
+                Fish &amp;
+                <tspan font-weight="bold" dx="2">chips</tspan>   to go
+            </text>
+        </svg>"#;
+
+        let dom = parse_svg(svg).unwrap();
+        let text = dom.root.find_by_id("t").unwrap();
+        let SvgNodeKind::Text(svg_text) = &text.kind else {
+            panic!("text not parsed");
+        };
+        assert_eq!(svg_text.content, "Fish & ");
+        let SvgNodeKind::TSpan(span) = &text.children[0].kind else {
+            panic!("tspan not parsed");
+        };
+        assert_eq!(span.content, "chips");
+        assert_eq!(span.tail, " to go");
+        assert_eq!(span.font_weight, Some(700));
+        assert_eq!(span.x, None);
+        assert_eq!(span.dx, 2.0);
+    }
+
     #[test]
     fn test_parse_transform() {
         let m = parse_transform("translate(10, 20)");
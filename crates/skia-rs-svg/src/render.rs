@@ -1,10 +1,26 @@
 //! SVG rendering to canvas.
 
 use crate::dom::*;
-use skia_rs_canvas::{RasterCanvas, Surface};
-use skia_rs_core::{Color, Matrix, Point, Rect, Scalar};
-use skia_rs_paint::{Paint, Style};
+use skia_rs_canvas::{PixelBuffer, RasterCanvas, Surface};
+use skia_rs_core::{Color, Color4f, Matrix, Point, Rect, Scalar, Size};
+use skia_rs_paint::{
+    ImageShader, LinearGradient, Paint, RadialGradient, SamplingOptions, ShaderRef, Style,
+    TileMode, TwoPointConicalGradient,
+};
 use skia_rs_path::PathBuilder;
+use std::sync::Arc;
+
+/// Deepest nesting of patterns drawn inside other pattern tiles.
+const MAX_PATTERN_DEPTH: usize = 8;
+
+/// Largest side of a rendered pattern tile, in pixels.
+const MAX_PATTERN_TILE: Scalar = 2048.0;
+
+/// Deepest nesting of `<use>` instances, which stops reference cycles.
+const MAX_USE_DEPTH: usize = 16;
+
+/// Longest chain of gradient `href`s followed to find stops.
+const MAX_GRADIENT_HREF_DEPTH: usize = 16;
 
 /// Render an SVG DOM to a surface.
 pub fn render_svg_to_surface(dom: &SvgDom, surface: &mut Surface) {
@@ -13,27 +29,35 @@ pub fn render_svg_to_surface(dom: &SvgDom, surface: &mut Surface) {
 }
 
 /// Render an SVG DOM to a raster canvas.
+///
+/// The view box is fitted to the container size, or to the canvas if none
+/// is set.
 pub fn render_svg(dom: &SvgDom, canvas: &mut RasterCanvas<'_>) {
-    // Calculate scale to fit
-    let view_box = dom.get_view_box();
-    let scale_x = canvas.width() as Scalar / view_box.width();
-    let scale_y = canvas.height() as Scalar / view_box.height();
-    let scale = scale_x.min(scale_y);
-
-    canvas.save();
-
-    // Apply viewBox transform
-    canvas.scale(scale, scale);
-    canvas.translate(-view_box.left, -view_box.top);
+    dom.render(canvas);
+}
 
-    // Render root node
-    render_node(&dom.root, canvas, dom);
+impl SvgDom {
+    /// Render the document to a raster canvas.
+    ///
+    /// The view box is mapped onto the container size (or the canvas size
+    /// if none is set) according to `preserveAspectRatio`.
+    pub fn render(&self, canvas: &mut RasterCanvas<'_>) {
+        let size = self
+            .container_size()
+            .unwrap_or_else(|| Size::new(canvas.width() as Scalar, canvas.height() as Scalar));
 
-    canvas.restore();
+        canvas.save();
+        canvas.concat(&self.view_box_transform(size));
+        render_node(&self.root, canvas, self, 0);
+        canvas.restore();
+    }
 }
 
 /// Render a single SVG node.
-fn render_node(node: &SvgNode, canvas: &mut RasterCanvas<'_>, dom: &SvgDom) {
+///
+/// `depth` counts the pattern tiles and `<use>` instances being rendered
+/// around this node.
+fn render_node(node: &SvgNode, canvas: &mut RasterCanvas<'_>, dom: &SvgDom, depth: usize) {
     if !node.visible {
         return;
     }
@@ -44,14 +68,16 @@ fn render_node(node: &SvgNode, canvas: &mut RasterCanvas<'_>, dom: &SvgDom) {
     canvas.concat(&node.transform);
 
     // Create paint for fill
+    let matrix = *canvas.total_matrix();
     let fill_paint = node
         .fill
         .as_ref()
-        .and_then(|fill| create_paint_from_svg_paint(fill, Style::Fill, node, dom));
+        .and_then(|fill| create_paint_from_svg_paint(fill, Style::Fill, node, dom, &matrix, depth));
 
     // Create paint for stroke
     let stroke_paint = node.stroke.as_ref().and_then(|stroke| {
-        let mut paint = create_paint_from_svg_paint(stroke, Style::Stroke, node, dom)?;
+        let mut paint =
+            create_paint_from_svg_paint(stroke, Style::Stroke, node, dom, &matrix, depth)?;
         paint.set_stroke_width(node.stroke_width);
         Some(paint)
     });
@@ -146,32 +172,45 @@ fn render_node(node: &SvgNode, canvas: &mut RasterCanvas<'_>, dom: &SvgDom) {
                 canvas.draw_path(path, paint);
             }
         }
-        SvgNodeKind::Text(_text) => {
-            // Text rendering requires font support
-            // For now, skip text nodes
+        #[cfg(feature = "text")]
+        SvgNodeKind::Text(text) => {
+            render_text(node, text, fill_paint, canvas, dom, depth);
         }
-        SvgNodeKind::Use(href) => {
-            // Find referenced element
-            let id = href.trim_start_matches('#');
-            if let Some(referenced) = dom.root.find_by_id(id) {
-                render_node(referenced, canvas, dom);
+        #[cfg(not(feature = "text"))]
+        SvgNodeKind::Text(_) => {
+            // Text rendering requires the `text` feature
+        }
+        SvgNodeKind::TSpan(_) => {
+            // Spans are drawn by their text element
+        }
+        SvgNodeKind::Use(_) => {
+            if depth < MAX_USE_DEPTH {
+                if let Some(instance) = dom.resolve_use(node) {
+                    render_node(&instance, canvas, dom, depth + 1);
+                }
             }
         }
+        SvgNodeKind::Symbol(_) => {
+            // Symbols only draw through the <use> elements that reference them
+        }
         SvgNodeKind::Group | SvgNodeKind::Svg | SvgNodeKind::Defs => {
             // Render children (except for defs which is just definitions)
             if !matches!(node.kind, SvgNodeKind::Defs) {
                 for child in &node.children {
-                    render_node(child, canvas, dom);
+                    render_node(child, canvas, dom, depth);
                 }
             }
         }
+        SvgNodeKind::Pattern(_) => {
+            // Patterns only draw through the fills and strokes that use them
+        }
         SvgNodeKind::Image(_img) => {
             // Image rendering requires image loading support
         }
         _ => {
             // Render children for unknown elements
             for child in &node.children {
-                render_node(child, canvas, dom);
+                render_node(child, canvas, dom, depth);
             }
         }
     }
@@ -179,12 +218,191 @@ fn render_node(node: &SvgNode, canvas: &mut RasterCanvas<'_>, dom: &SvgDom) {
     canvas.restore();
 }
 
+/// Lay out and draw a text element and its spans.
+///
+/// Text is filled with the fill paint; strokes are not drawn.
+#[cfg(feature = "text")]
+fn render_text(
+    node: &SvgNode,
+    text: &SvgText,
+    fill_paint: Option<Paint>,
+    canvas: &mut RasterCanvas<'_>,
+    dom: &SvgDom,
+    depth: usize,
+) {
+    let start = Point::new(text.x + text.dx, text.y + text.dy);
+    let mut layout = TextLayout {
+        dom,
+        pen: start,
+        chunk_x: start.x,
+        anchor: text.text_anchor,
+        pieces: Vec::new(),
+    };
+    let font = TextFont {
+        family: text.font_family.as_deref(),
+        size: text.font_size,
+        weight: text.font_weight,
+    };
+
+    layout.add(&text.content, &font, fill_paint.as_ref());
+    let matrix = *canvas.total_matrix();
+    for child in &node.children {
+        layout.add_span(child, &font, fill_paint.as_ref(), canvas, &matrix, depth);
+    }
+    layout.finish_chunk(canvas);
+}
+
+/// Font properties inherited by text spans.
+#[cfg(feature = "text")]
+struct TextFont<'a> {
+    family: Option<&'a str>,
+    size: Scalar,
+    weight: u16,
+}
+
+/// Positions the text of a text element.
+///
+/// Text is drawn a chunk at a time: a chunk starts at each absolute x
+/// position and is shifted as a whole by its text anchor.
+#[cfg(feature = "text")]
+struct TextLayout<'a> {
+    dom: &'a SvgDom,
+    /// Where the next text starts.
+    pen: Point,
+    /// Where the current chunk starts.
+    chunk_x: Scalar,
+    anchor: TextAnchor,
+    /// The current chunk's text and where it's drawn.
+    pieces: Vec<(skia_rs_text::TextBlob, Point, Paint)>,
+}
+
+#[cfg(feature = "text")]
+impl TextLayout<'_> {
+    /// Lay out a span, its nested spans and the text following it.
+    fn add_span(
+        &mut self,
+        node: &SvgNode,
+        parent_font: &TextFont<'_>,
+        parent_paint: Option<&Paint>,
+        canvas: &mut RasterCanvas<'_>,
+        matrix: &Matrix,
+        depth: usize,
+    ) {
+        let SvgNodeKind::TSpan(span) = &node.kind else {
+            return;
+        };
+
+        if let Some(x) = span.x {
+            self.finish_chunk(canvas);
+            self.pen.x = x;
+            if let Some(anchor) = span.text_anchor {
+                self.anchor = anchor;
+            }
+        }
+        if let Some(y) = span.y {
+            self.pen.y = y;
+        }
+        self.pen.x += span.dx;
+        self.pen.y += span.dy;
+        if span.x.is_some() {
+            self.chunk_x = self.pen.x;
+        }
+
+        let font = TextFont {
+            family: span.font_family.as_deref().or(parent_font.family),
+            size: span.font_size.unwrap_or(parent_font.size),
+            weight: span.font_weight.unwrap_or(parent_font.weight),
+        };
+        // Hidden spans still take up space
+        let paint = node
+            .fill
+            .as_ref()
+            .filter(|_| node.visible)
+            .and_then(|fill| {
+                create_paint_from_svg_paint(fill, Style::Fill, node, self.dom, matrix, depth)
+            });
+
+        self.add(&span.content, &font, paint.as_ref());
+        for child in &node.children {
+            self.add_span(child, &font, paint.as_ref(), canvas, matrix, depth);
+        }
+        self.add(&span.tail, parent_font, parent_paint);
+    }
+
+    /// Shape text at the pen position and advance past it.
+    fn add(&mut self, text: &str, font: &TextFont<'_>, paint: Option<&Paint>) {
+        use skia_rs_text::{Shaper, TextBlob};
+
+        if text.is_empty() {
+            return;
+        }
+        let font = resolve_font(self.dom, font);
+        let (blob, width) = match Shaper::new().shape_auto(text, &font) {
+            Some(runs) => (
+                TextBlob::from_shaped_runs(&runs, Point::zero()),
+                runs.iter().map(|run| run.width).sum(),
+            ),
+            None => (
+                TextBlob::from_text(text, &font, Point::zero()),
+                font.measure_text(text).0,
+            ),
+        };
+
+        if let Some(paint) = paint {
+            self.pieces.push((blob, self.pen, paint.clone()));
+        }
+        self.pen.x += width;
+    }
+
+    /// Draw the current chunk, aligned by its text anchor.
+    fn finish_chunk(&mut self, canvas: &mut RasterCanvas<'_>) {
+        let width = self.pen.x - self.chunk_x;
+        let shift = match self.anchor {
+            TextAnchor::Start => 0.0,
+            TextAnchor::Middle => -width / 2.0,
+            TextAnchor::End => -width,
+        };
+        for (blob, origin, paint) in self.pieces.drain(..) {
+            canvas.draw_text_blob(&blob, origin.x + shift, origin.y, &paint);
+        }
+    }
+}
+
+/// Find the font for text, falling back to the font manager's default
+/// family, or the default typeface without a font manager.
+#[cfg(feature = "text")]
+fn resolve_font(dom: &SvgDom, font: &TextFont<'_>) -> skia_rs_text::Font {
+    use skia_rs_text::{Font, FontSlant, FontStyle, FontWeight, FontWidth};
+
+    let style = FontStyle::new(
+        FontWeight(font.weight),
+        FontWidth::NORMAL,
+        FontSlant::Upright,
+    );
+    let typeface = dom.font_mgr().and_then(|mgr| {
+        font.family
+            .into_iter()
+            .flat_map(|families| families.split(','))
+            .map(|family| family.trim().trim_matches(|c| c == '"' || c == '\''))
+            .find_map(|family| mgr.match_family_style(family, style))
+            .or_else(|| mgr.legacy_make_typeface(None, style))
+    });
+    match typeface {
+        Some(typeface) => Font::new(typeface, font.size),
+        None => Font::from_size(font.size),
+    }
+}
+
 /// Create a Paint from an SVG paint specification.
+///
+/// `matrix` is the canvas matrix the paint will be drawn with.
 fn create_paint_from_svg_paint(
     svg_paint: &SvgPaint,
     style: Style,
     node: &SvgNode,
-    _dom: &SvgDom,
+    dom: &SvgDom,
+    matrix: &Matrix,
+    depth: usize,
 ) -> Option<Paint> {
     match svg_paint {
         SvgPaint::None => None,
@@ -195,17 +413,261 @@ fn create_paint_from_svg_paint(
             paint.set_alpha(node.opacity);
             Some(paint)
         }
-        SvgPaint::Url(_url) => {
-            // Gradient/pattern lookup would go here
-            // For now, return a default paint
+        SvgPaint::Url(url) => {
             let mut paint = Paint::new();
             paint.set_style(style);
             paint.set_alpha(node.opacity);
+
+            // Unresolved references draw black
+            let id = url.trim_start_matches('#');
+            let shader = match dom.root.find_by_id(id) {
+                Some(
+                    pattern_node @ SvgNode {
+                        kind: SvgNodeKind::Pattern(pattern),
+                        ..
+                    },
+                ) => Some(pattern_shader(
+                    pattern_node,
+                    pattern,
+                    node.bounds(),
+                    matrix,
+                    dom,
+                    depth,
+                )?),
+                Some(SvgNode {
+                    kind: SvgNodeKind::LinearGradient(gradient),
+                    ..
+                }) => Some(linear_gradient_shader(gradient, node.bounds(), dom)?),
+                Some(SvgNode {
+                    kind: SvgNodeKind::RadialGradient(gradient),
+                    ..
+                }) => Some(radial_gradient_shader(gradient, node.bounds(), dom)?),
+                _ => None,
+            };
+            paint.set_shader(shader);
             Some(paint)
         }
     }
 }
 
+/// Build a shader for a `<linearGradient>`.
+///
+/// Returns `None` if nothing should be painted: the gradient has no stops, or
+/// uses bounding box units on a shape with an empty bounding box.
+fn linear_gradient_shader(
+    gradient: &SvgLinearGradient,
+    bounds: Rect,
+    dom: &SvgDom,
+) -> Option<ShaderRef> {
+    let (colors, positions) = gradient_stops(&gradient.stops, gradient.href.as_deref(), dom)?;
+    let local_matrix = gradient_matrix(gradient.units, &gradient.transform, bounds)?;
+    let shader = LinearGradient::new(
+        Point::new(gradient.x1, gradient.y1),
+        Point::new(gradient.x2, gradient.y2),
+        colors,
+        Some(positions),
+        spread_tile_mode(gradient.spread),
+    )
+    .with_local_matrix(local_matrix);
+    Some(Arc::new(shader))
+}
+
+/// Build a shader for a `<radialGradient>`.
+///
+/// A focal point away from the center makes a two-point conical gradient.
+/// Returns `None` under the same conditions as [`linear_gradient_shader`].
+fn radial_gradient_shader(
+    gradient: &SvgRadialGradient,
+    bounds: Rect,
+    dom: &SvgDom,
+) -> Option<ShaderRef> {
+    let (colors, positions) = gradient_stops(&gradient.stops, gradient.href.as_deref(), dom)?;
+    let local_matrix = gradient_matrix(gradient.units, &gradient.transform, bounds)?;
+    let center = Point::new(gradient.cx, gradient.cy);
+    let focus = Point::new(gradient.fx, gradient.fy);
+    let tile_mode = spread_tile_mode(gradient.spread);
+
+    let shader: ShaderRef = if focus == center {
+        Arc::new(
+            RadialGradient::new(center, gradient.r, colors, Some(positions), tile_mode)
+                .with_local_matrix(local_matrix),
+        )
+    } else {
+        Arc::new(
+            TwoPointConicalGradient::new(
+                focus,
+                0.0,
+                center,
+                gradient.r,
+                colors,
+                Some(positions),
+                tile_mode,
+            )
+            .with_local_matrix(local_matrix),
+        )
+    };
+    Some(shader)
+}
+
+/// Resolve a gradient's stops into shader colors and positions.
+///
+/// A gradient without stops of its own uses those of the gradient its `href`
+/// points to. Offsets are made non-decreasing, as SVG requires. Returns `None`
+/// if no stops are found.
+fn gradient_stops(
+    stops: &[GradientStop],
+    href: Option<&str>,
+    dom: &SvgDom,
+) -> Option<(Vec<Color4f>, Vec<Scalar>)> {
+    let mut stops = stops;
+    let mut href = href;
+    for _ in 0..MAX_GRADIENT_HREF_DEPTH {
+        if !stops.is_empty() {
+            break;
+        }
+        let referenced = dom.root.find_by_id(href?.trim_start_matches('#'))?;
+        (stops, href) = match &referenced.kind {
+            SvgNodeKind::LinearGradient(gradient) => (&gradient.stops, gradient.href.as_deref()),
+            SvgNodeKind::RadialGradient(gradient) => (&gradient.stops, gradient.href.as_deref()),
+            _ => return None,
+        };
+    }
+    if stops.is_empty() {
+        return None;
+    }
+
+    let colors = stops
+        .iter()
+        .map(|stop| {
+            let mut color = Color4f::from_color(stop.color);
+            color.a *= stop.opacity;
+            color
+        })
+        .collect();
+    let mut last = 0.0;
+    let positions = stops
+        .iter()
+        .map(|stop| {
+            last = stop.offset.max(last);
+            last
+        })
+        .collect();
+    Some((colors, positions))
+}
+
+/// The local matrix placing a gradient in the user space of the shape.
+///
+/// Returns `None` for bounding box units on an empty bounding box.
+fn gradient_matrix(units: GradientUnits, transform: &Matrix, bounds: Rect) -> Option<Matrix> {
+    match units {
+        GradientUnits::UserSpaceOnUse => Some(*transform),
+        GradientUnits::ObjectBoundingBox => {
+            if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+                return None;
+            }
+            Some(
+                Matrix::translate(bounds.left, bounds.top)
+                    .concat(&Matrix::scale(bounds.width(), bounds.height()))
+                    .concat(transform),
+            )
+        }
+    }
+}
+
+/// The shader tile mode for a gradient spread method.
+fn spread_tile_mode(spread: SpreadMethod) -> TileMode {
+    match spread {
+        SpreadMethod::Pad => TileMode::Clamp,
+        SpreadMethod::Reflect => TileMode::Mirror,
+        SpreadMethod::Repeat => TileMode::Repeat,
+    }
+}
+
+/// Render a pattern's content into a tile and repeat it with an image shader.
+///
+/// `bounds` is the bounding box of the shape being painted, and `matrix` the
+/// canvas matrix it is drawn with; the tile is rendered at that resolution.
+/// Returns `None` for an empty tile, in which case nothing is painted.
+fn pattern_shader(
+    node: &SvgNode,
+    pattern: &SvgPattern,
+    bounds: Rect,
+    matrix: &Matrix,
+    dom: &SvgDom,
+    depth: usize,
+) -> Option<ShaderRef> {
+    if depth >= MAX_PATTERN_DEPTH {
+        return None;
+    }
+
+    let tile = match pattern.units {
+        GradientUnits::UserSpaceOnUse => {
+            Rect::from_xywh(pattern.x, pattern.y, pattern.width, pattern.height)
+        }
+        GradientUnits::ObjectBoundingBox => Rect::from_xywh(
+            bounds.left + pattern.x * bounds.width(),
+            bounds.top + pattern.y * bounds.height(),
+            pattern.width * bounds.width(),
+            pattern.height * bounds.height(),
+        ),
+    };
+    if tile.is_empty() || !tile.is_finite() {
+        return None;
+    }
+
+    // Size the tile by how much the pattern is scaled on the canvas.
+    let to_device = matrix.concat(&pattern.transform).values;
+    let scale_x = to_device[0].hypot(to_device[3]);
+    let scale_y = to_device[1].hypot(to_device[4]);
+    let width = (tile.width() * scale_x).ceil().clamp(1.0, MAX_PATTERN_TILE) as i32;
+    let height = (tile.height() * scale_y)
+        .ceil()
+        .clamp(1.0, MAX_PATTERN_TILE) as i32;
+
+    let mut buffer = PixelBuffer::new(width, height);
+    {
+        let mut canvas = RasterCanvas::new(&mut buffer);
+        canvas.scale(
+            width as Scalar / tile.width(),
+            height as Scalar / tile.height(),
+        );
+        if let Some(view_box) = pattern.view_box {
+            canvas.scale(
+                tile.width() / view_box.width(),
+                tile.height() / view_box.height(),
+            );
+            canvas.translate(-view_box.left, -view_box.top);
+        } else if pattern.content_units == GradientUnits::ObjectBoundingBox {
+            canvas.scale(bounds.width(), bounds.height());
+        }
+        for child in &node.children {
+            render_node(child, &mut canvas, dom, depth + 1);
+        }
+    }
+
+    let pixels: Vec<Color4f> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| Color4f::from_color(buffer.get_pixel(x, y).unwrap_or(Color::TRANSPARENT)))
+        .collect();
+    let local_matrix = pattern
+        .transform
+        .concat(&Matrix::translate(tile.left, tile.top))
+        .concat(&Matrix::scale(
+            tile.width() / width as Scalar,
+            tile.height() / height as Scalar,
+        ));
+    let shader = ImageShader::from_pixels(
+        width,
+        height,
+        &pixels,
+        TileMode::Repeat,
+        TileMode::Repeat,
+        SamplingOptions::LINEAR,
+    )?
+    .with_local_matrix(local_matrix);
+    Some(Arc::new(shader))
+}
+
 /// Render an SVG string to a new surface.
 pub fn render_svg_string(svg: &str, width: i32, height: i32) -> Option<Surface> {
     let dom = crate::parse_svg(svg).ok()?;
@@ -249,6 +711,288 @@ mod tests {
         assert!(surface.is_some());
     }
 
+    #[test]
+    fn test_render_view_box_to_container() {
+        let svg = r#"<svg viewBox="0 0 10 10">
+            <rect width="10" height="10" fill="red"/>
+        </svg>"#;
+        let mut dom = crate::parse_svg(svg).unwrap();
+        dom.set_container_size(Size::new(100.0, 100.0));
+
+        let mut surface = Surface::new_raster_n32_premul(100, 100).unwrap();
+        dom.render(&mut surface.raster_canvas());
+
+        let buffer = surface.pixel_buffer();
+        let red = Color::from_rgb(255, 0, 0);
+        for (x, y) in [(0, 0), (50, 50), (99, 0), (0, 99), (99, 99)] {
+            assert_eq!(buffer.get_pixel(x, y).unwrap(), red, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn test_render_preserve_aspect_ratio() {
+        // A square view box in a wide container is centered by default, and
+        // pinned to the left with xMinYMid.
+        let render = |par: &str| {
+            let svg = format!(
+                r#"<svg viewBox="0 0 10 10" preserveAspectRatio="{par}">
+                    <rect width="10" height="10" fill="blue"/>
+                </svg>"#
+            );
+            let mut dom = crate::parse_svg(&svg).unwrap();
+            dom.set_container_size(Size::new(100.0, 50.0));
+            let mut surface = Surface::new_raster_n32_premul(100, 50).unwrap();
+            dom.render(&mut surface.raster_canvas());
+            let buffer = surface.pixel_buffer();
+            [5, 30, 70, 95].map(|x| buffer.get_pixel(x, 25).unwrap() == Color::from_rgb(0, 0, 255))
+        };
+
+        assert_eq!(render("xMidYMid meet"), [false, true, true, false]);
+        assert_eq!(render("xMinYMid"), [true, true, false, false]);
+        assert_eq!(render("none"), [true, true, true, true]);
+    }
+
+    /// Render SVG with the Tuffy test font as the only registered font.
+    #[cfg(feature = "text")]
+    fn render_with_test_font(svg: &str, width: i32, height: i32) -> Surface {
+        use skia_rs_text::{DefaultFontMgr, Typeface};
+
+        let data = include_bytes!("../../skia-rs-text/testdata/Tuffy.ttf");
+        let typeface = Typeface::from_bytes(data.to_vec(), 0).unwrap();
+        let mut dom = crate::parse_svg(svg).unwrap();
+        dom.set_font_mgr(Arc::new(DefaultFontMgr::new_with_fonts(vec![typeface])));
+
+        let mut surface = Surface::new_raster_n32_premul(width, height).unwrap();
+        dom.render(&mut surface.raster_canvas());
+        surface
+    }
+
+    /// The inked columns and rows of a surface, as (min x, max x, min y, max y).
+    #[cfg(feature = "text")]
+    fn ink_bounds(surface: &Surface) -> Option<(i32, i32, i32, i32)> {
+        let buffer = surface.pixel_buffer();
+        let mut bounds: Option<(i32, i32, i32, i32)> = None;
+        for y in 0..surface.height() {
+            for x in 0..surface.width() {
+                if buffer.get_pixel(x, y).unwrap().alpha() > 0 {
+                    let (x0, x1, y0, y1) = bounds.unwrap_or((x, x, y, y));
+                    bounds = Some((x0.min(x), x1.max(x), y0.min(y), y1.max(y)));
+                }
+            }
+        }
+        bounds
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_render_text() {
+        // The family isn't registered, so the default font is used
+        let svg = r#"<svg width="60" height="30">
+            <text x="0" y="20" font-family="Missing" font-size="16">Hi</text>
+        </svg>"#;
+
+        let surface = render_with_test_font(svg, 60, 30);
+        let (x0, x1, y0, y1) = ink_bounds(&surface).expect("no text drawn");
+        // Ink sits on the baseline, starting at the text position
+        assert!(x0 <= 3 && x1 < 30, "x = {x0}..={x1}");
+        assert!((3..12).contains(&y0), "top = {y0}");
+        assert!((18..=20).contains(&y1), "bottom = {y1}");
+        assert_eq!(
+            surface.pixel_buffer().get_pixel(x0 + 1, 18).unwrap(),
+            Color::BLACK
+        );
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_render_text_anchor_and_spans() {
+        let ink = |attrs: &str, content: &str| {
+            let svg = format!(
+                r#"<svg width="100" height="40">
+                    <text x="50" y="20" font-size="16"{attrs}>{content}</text>
+                </svg>"#
+            );
+            ink_bounds(&render_with_test_font(&svg, 100, 40)).expect("no text drawn")
+        };
+
+        let start = ink("", "Hi");
+        let end = ink(r#" text-anchor="end""#, "Hi");
+        let middle = ink(r#" text-anchor="middle""#, "Hi");
+        assert!(start.0 >= 50);
+        assert!(end.1 <= 50);
+        assert!(middle.0 < 50 && middle.1 > 50);
+
+        // A span continues after the text before it, shifted by dy
+        let spanned = ink("", r#"H<tspan dy="10">i</tspan>"#);
+        assert_eq!((spanned.0, spanned.1), (start.0, start.1));
+        assert_eq!(spanned.2, start.2);
+        assert!(spanned.3 >= start.3 + 9);
+    }
+
+    #[test]
+    fn test_render_use_symbol() {
+        let svg = r##"<svg width="100" height="100">
+            <defs>
+                <symbol id="dot" viewBox="0 0 2 2">
+                    <circle cx="1" cy="1" r="1" fill="red"/>
+                </symbol>
+            </defs>
+            <use xlink:href="#dot" x="10" y="10" width="20" height="20"/>
+            <use href="#dot" x="60" y="50" width="30" height="30" transform="translate(0 5)"/>
+        </svg>"##;
+
+        let surface = render_svg_string(svg, 100, 100).unwrap();
+        let buffer = surface.pixel_buffer();
+        let red = Color::from_rgb(255, 0, 0);
+        // Both instances are drawn, scaled from the symbol's view box
+        assert_eq!(buffer.get_pixel(20, 20).unwrap(), red);
+        assert_eq!(buffer.get_pixel(75, 70).unwrap(), red);
+        assert_eq!(buffer.get_pixel(62, 57).unwrap(), Color::WHITE);
+        // The symbol itself isn't drawn
+        assert_eq!(buffer.get_pixel(1, 1).unwrap(), Color::WHITE);
+    }
+
+    #[test]
+    fn test_render_use_cycle() {
+        let svg = r##"<svg width="20" height="20">
+            <g id="loop">
+                <rect width="5" height="5" fill="blue"/>
+                <use href="#loop" x="1"/>
+                <use href="#loop" y="1"/>
+            </g>
+        </svg>"##;
+
+        // Terminates, and still draws the content
+        let surface = render_svg_string(svg, 20, 20).unwrap();
+        let blue = Color::from_rgb(0, 0, 255);
+        assert_eq!(surface.pixel_buffer().get_pixel(2, 2).unwrap(), blue);
+    }
+
+    #[test]
+    fn test_render_stylesheet() {
+        let svg = r##"<svg width="30" height="10">
+            <style><![CDATA[
+                .red { fill: red }
+                rect { fill: blue !important }
+                #plain { fill: red }
+            ]]></style>
+            <rect class="red" width="10" height="10" fill="blue" style="stroke: none"/>
+            <rect id="plain" x="10" width="10" height="10" style="fill: red"/>
+            <circle class="red" cx="25" cy="5" r="5" style="fill: blue"/>
+        </svg>"##;
+
+        let surface = render_svg_string(svg, 30, 10).unwrap();
+        let buffer = surface.pixel_buffer();
+        let red = Color::from_rgb(255, 0, 0);
+        let blue = Color::from_rgb(0, 0, 255);
+        // !important beats both the class rule and inline style
+        assert_eq!(buffer.get_pixel(5, 5).unwrap(), blue);
+        assert_eq!(buffer.get_pixel(15, 5).unwrap(), blue);
+        // Inline style beats the class rule
+        assert_eq!(buffer.get_pixel(25, 5).unwrap(), blue);
+
+        let svg = r#"<svg width="10" height="10">
+            <style>.red { fill: red }</style>
+            <rect class="red" width="10" height="10"/>
+        </svg>"#;
+        let surface = render_svg_string(svg, 10, 10).unwrap();
+        assert_eq!(surface.pixel_buffer().get_pixel(5, 5).unwrap(), red);
+    }
+
+    #[test]
+    fn test_render_linear_gradient() {
+        let svg = r#"<svg width="100" height="100">
+            <defs>
+                <linearGradient id="fade">
+                    <stop offset="0" stop-color="red"/>
+                    <stop offset="100%" stop-color="blue"/>
+                </linearGradient>
+            </defs>
+            <rect x="0" y="0" width="100" height="100" fill="url(#fade)"/>
+        </svg>"#;
+
+        let surface = render_svg_string(svg, 100, 100).unwrap();
+        let buffer = surface.pixel_buffer();
+        let left = buffer.get_pixel(0, 50).unwrap();
+        let middle = buffer.get_pixel(50, 50).unwrap();
+        let right = buffer.get_pixel(99, 50).unwrap();
+
+        assert!(left.red() > 250 && left.blue() < 5, "{left:?}");
+        assert!(right.blue() > 250 && right.red() < 5, "{right:?}");
+        assert!(middle.red().abs_diff(middle.blue()) < 8, "{middle:?}");
+        // Horizontal, so each column is a single color
+        assert_eq!(buffer.get_pixel(0, 0).unwrap(), left);
+        assert_eq!(buffer.get_pixel(99, 99).unwrap(), right);
+    }
+
+    #[test]
+    fn test_render_radial_gradient_href() {
+        // The stops come from the referenced gradient; user space units put
+        // the center at (50, 50) regardless of the shape.
+        let svg = r##"<svg width="100" height="100">
+            <linearGradient id="base">
+                <stop offset="0" style="stop-color: #0f0"/>
+                <stop offset="1" stop-color="blue" stop-opacity="0"/>
+            </linearGradient>
+            <radialGradient id="glow" xlink:href="#base" gradientUnits="userSpaceOnUse"
+                cx="50" cy="50" r="40"/>
+            <rect x="0" y="0" width="100" height="100" fill="url(#glow)"/>
+        </svg>"##;
+
+        let surface = render_svg_string(svg, 100, 100).unwrap();
+        let buffer = surface.pixel_buffer();
+        let center = buffer.get_pixel(50, 50).unwrap();
+        assert!(center.green() > 240 && center.red() < 10, "{center:?}");
+        // Past the radius the transparent last stop pads over the white background
+        assert_eq!(buffer.get_pixel(2, 2).unwrap(), Color::WHITE);
+    }
+
+    #[test]
+    fn test_render_pattern_fill() {
+        let svg = r#"<svg width="100" height="100">
+            <defs>
+                <pattern id="dots" width="10" height="10" patternUnits="userSpaceOnUse">
+                    <rect x="3" y="3" width="4" height="4" fill="blue"/>
+                </pattern>
+            </defs>
+            <rect x="0" y="0" width="60" height="60" fill="url(#dots)"/>
+        </svg>"#;
+
+        let surface = render_svg_string(svg, 100, 100).unwrap();
+        let buffer = surface.pixel_buffer();
+        let pixel = |x, y| buffer.get_pixel(x, y).unwrap();
+        let blue = Color::from_rgb(0, 0, 255);
+
+        // A dot at the center of every tile, with white between them.
+        for ty in 0..6 {
+            for tx in 0..6 {
+                assert_eq!(pixel(tx * 10 + 5, ty * 10 + 5), blue, "tile ({tx}, {ty})");
+                assert_eq!(pixel(tx * 10 + 1, ty * 10 + 1), Color::WHITE);
+            }
+        }
+        // Nothing is drawn outside the filled rect, nor the pattern itself.
+        assert_eq!(pixel(75, 75), Color::WHITE);
+    }
+
+    #[test]
+    fn test_render_pattern_bounding_box_units() {
+        // Tiles are a quarter of the shape, so four dots fit along each side.
+        let svg = r#"<svg width="100" height="100">
+            <pattern id="checks" width="0.25" height="0.25">
+                <rect width="5" height="5" fill="black"/>
+            </pattern>
+            <rect x="20" y="20" width="40" height="40" fill="url(#checks)"/>
+        </svg>"#;
+
+        let surface = render_svg_string(svg, 100, 100).unwrap();
+        let buffer = surface.pixel_buffer();
+        let pixel = |x, y| buffer.get_pixel(x, y).unwrap();
+        for i in 0..4 {
+            assert_eq!(pixel(22 + i * 10, 22), Color::BLACK);
+            assert_eq!(pixel(27 + i * 10, 27), Color::WHITE);
+        }
+    }
+
     #[test]
     fn test_render_path() {
         let svg = r#"<svg width="100" height="100">
@@ -1,7 +1,8 @@
 //! Font configuration for text rendering.
 
+use crate::shaper::Shaper;
 use crate::typeface::{Typeface, TypefaceRef};
-use skia_rs_core::Scalar;
+use skia_rs_core::{Color, Rect, Scalar};
 use std::sync::Arc;
 
 /// Text baseline position.
@@ -299,19 +300,53 @@ impl Font {
         self.metrics().descent
     }
 
-    /// Measure the width of text.
-    pub fn measure_text(&self, text: &str) -> Scalar {
-        // Simple approximation: each character is about half the font size
-        // A real implementation would use glyph advances
-        let char_count = text.chars().count();
-        char_count as Scalar * self.size * 0.5 * self.scale_x
+    /// Measure text, returning its advance width and tight ink bounds.
+    ///
+    /// The text is shaped, so kerning and ligatures are taken into account.
+    /// The bounds are relative to the text origin on the baseline, with y
+    /// growing downward, and are empty if no glyph has an outline. Without
+    /// font data, both are approximated from the font size.
+    pub fn measure_text(&self, text: &str) -> (Scalar, Rect) {
+        let Some(runs) = Shaper::new().shape_auto(text, self) else {
+            let width = text.chars().count() as Scalar * self.size * 0.5 * self.scale_x;
+            let metrics = self.metrics();
+            let bounds = if width > 0.0 {
+                Rect::new(0.0, metrics.ascent, width, metrics.descent)
+            } else {
+                Rect::EMPTY
+            };
+            return (width, bounds);
+        };
+
+        let mut pen = skia_rs_core::Point::zero();
+        let mut bounds = Rect::EMPTY;
+        for glyph in runs.iter().flat_map(|run| &run.glyphs) {
+            if let Some(path) = self.get_glyph_path(glyph.glyph_id.0) {
+                let x = pen.x + glyph.x_offset * self.scale_x;
+                let y = pen.y - glyph.y_offset;
+                bounds = bounds.union(&path.bounds().offset(x, y));
+            }
+            pen.x += glyph.x_advance * self.scale_x;
+            pen.y -= glyph.y_advance;
+        }
+        (pen.x, bounds)
     }
 
-    /// Get glyph widths for text.
-    pub fn get_widths(&self, text: &str) -> Vec<Scalar> {
-        // Simple approximation
-        let width = self.size * 0.5 * self.scale_x;
-        text.chars().map(|_| width).collect()
+    /// Get the advance widths of glyphs.
+    ///
+    /// Advances come from the font's `hmtx` table, without kerning; use
+    /// [`Font::measure_text`] for the width of shaped text.
+    pub fn get_widths(&self, glyphs: &[u16]) -> Vec<Scalar> {
+        match self.typeface.face() {
+            Some(face) => {
+                let scale = self.typeface.units_to_px(self.size) * self.scale_x;
+                glyphs
+                    .iter()
+                    .map(|&g| face_advance(&face, g) * scale)
+                    .collect()
+            }
+            None => glyphs.iter().map(|&g| self.glyph_advance(g)).collect(),
+        }
     }
 
     /// Get glyph bounds for text.
@@ -352,7 +387,12 @@ impl Font {
     ///
     /// The advance is the horizontal distance to move after drawing this glyph.
     pub fn glyph_advance(&self, glyph: u16) -> Scalar {
-        // Simple approximation - real implementation would query the font
+        if let Some(face) = self.typeface.face() {
+            return face_advance(&face, glyph)
+                * self.typeface.units_to_px(self.size)
+                * self.scale_x;
+        }
+        // Without font data, approximate
         if glyph == 0 {
             0.0
         } else {
@@ -362,7 +402,7 @@ impl Font {
 
     /// Get advance widths for multiple glyphs.
     pub fn glyph_advances(&self, glyphs: &[u16]) -> Vec<Scalar> {
-        glyphs.iter().map(|&g| self.glyph_advance(g)).collect()
+        self.get_widths(glyphs)
     }
 
     /// Get the bounding box for a glyph.
@@ -389,32 +429,32 @@ impl Font {
         glyphs.iter().map(|&g| self.glyph_bounds(g)).collect()
     }
 
-    /// Get the path outline for a glyph.
+    /// Get the outline of a glyph as a path, scaled to the font size.
     ///
-    /// Returns a path that can be filled to render the glyph.
-    /// This is useful for vector text rendering or text effects.
-    pub fn glyph_path(&self, glyph: u16) -> Option<skia_rs_path::Path> {
-        if glyph == 0 {
-            return None;
-        }
-
-        // Placeholder - returns a simple rectangle
-        // Real implementation would extract the actual glyph outline from the font
-        let bounds = self.glyph_bounds(glyph);
-
-        let mut builder = skia_rs_path::PathBuilder::new();
-        builder.move_to(bounds.left, bounds.top);
-        builder.line_to(bounds.right, bounds.top);
-        builder.line_to(bounds.right, bounds.bottom);
-        builder.line_to(bounds.left, bounds.bottom);
-        builder.close();
-
-        Some(builder.build())
-    }
-
-    /// Get paths for multiple glyphs.
-    pub fn glyph_paths(&self, glyphs: &[u16]) -> Vec<Option<skia_rs_path::Path>> {
-        glyphs.iter().map(|&g| self.glyph_path(g)).collect()
+    /// The outline is read from the typeface's `glyf` or `CFF` table, with
+    /// composite glyphs assembled from their transformed components. The
+    /// glyph origin sits on the baseline at (0, 0) and y grows downward, so
+    /// the path can be drawn at a text position with a plain translation.
+    /// Horizontal scale and skew are applied.
+    ///
+    /// Returns `None` if the typeface has no font data or the glyph has no
+    /// outline, as for a space.
+    pub fn get_glyph_path(&self, glyph_id: u16) -> Option<skia_rs_path::Path> {
+        let face = self.typeface.face()?;
+        let scale = self.typeface.units_to_px(self.size);
+        let mut outline = GlyphOutline {
+            builder: skia_rs_path::PathBuilder::new(),
+            scale_x: scale * self.scale_x,
+            scale_y: scale,
+            skew_x: self.skew_x,
+        };
+        face.outline_glyph(ttf_parser::GlyphId(glyph_id), &mut outline)?;
+        Some(outline.builder.build())
+    }
+
+    /// Get the outlines of several glyphs, as [`Font::get_glyph_path`] does.
+    pub fn get_glyph_paths(&self, glyphs: &[u16]) -> Vec<Option<skia_rs_path::Path>> {
+        glyphs.iter().map(|&g| self.get_glyph_path(g)).collect()
     }
 
     /// Get the path for a string of text.
@@ -426,7 +466,7 @@ impl Font {
         let mut x_offset: Scalar = 0.0;
 
         for glyph in glyphs {
-            if let Some(glyph_path) = self.glyph_path(glyph) {
+            if let Some(glyph_path) = self.get_glyph_path(glyph) {
                 // Transform and add glyph path
                 let transform = skia_rs_core::Matrix::translate(x_offset, 0.0);
                 let transformed = glyph_path.transformed(&transform);
@@ -440,43 +480,75 @@ impl Font {
 
     /// Check if a glyph is a color/emoji glyph.
     ///
-    /// Color glyphs require special rendering (as images rather than outlines).
-    pub fn glyph_is_color(&self, glyph: u16) -> bool {
-        // Placeholder - real implementation would check font tables (COLR/CPAL or CBDT/CBLC)
-        // For now, assume high glyph IDs might be emoji
-        glyph > 0x1000
+    /// True for glyphs with `COLR` color layers, which are drawn from
+    /// [`Font::color_glyph_layers`] rather than as a single outline.
+    pub fn is_color_glyph(&self, glyph_id: u16) -> bool {
+        self.typeface
+            .face()
+            .is_some_and(|face| face.is_color_glyph(ttf_parser::GlyphId(glyph_id)))
     }
 
-    /// Get the image for a color glyph (emoji).
+    /// Get the colored layers of a `COLR` version 0 glyph, bottom first.
+    ///
+    /// Layer colors come from the first `CPAL` palette, and layers that use
+    /// the text color take `foreground`. Layer paths are positioned like
+    /// [`Font::get_glyph_path`].
+    ///
+    /// Returns `None` if the glyph has no color layers, or if it is a
+    /// COLRv1 glyph using gradients, clips or transforms, which are not
+    /// supported yet.
+    pub fn color_glyph_layers(
+        &self,
+        glyph_id: u16,
+        foreground: Color,
+    ) -> Option<Vec<ColorGlyphLayer>> {
+        let face = self.typeface.face()?;
+        let foreground = ttf_parser::RgbaColor::new(
+            foreground.red(),
+            foreground.green(),
+            foreground.blue(),
+            foreground.alpha(),
+        );
+        let mut painter = ColorLayerPainter {
+            font: self,
+            outline: None,
+            layers: Vec::new(),
+            supported: true,
+        };
+        face.paint_color_glyph(ttf_parser::GlyphId(glyph_id), 0, foreground, &mut painter)?;
+        (painter.supported && !painter.layers.is_empty()).then_some(painter.layers)
+    }
+
+    /// Get the embedded bitmap of a color glyph (emoji).
     ///
-    /// Returns the pixel data and size for rendering emoji and other color glyphs.
+    /// Reads uncompressed BGRA images from the `sbix` or `CBDT` strike
+    /// closest to the font size. The image keeps the strike's resolution;
+    /// `left` and `top` are scaled to the font size. PNG-compressed strikes
+    /// are not decoded and return `None`.
     pub fn glyph_image(&self, glyph: u16) -> Option<GlyphImage> {
-        if !self.glyph_is_color(glyph) {
+        let face = self.typeface.face()?;
+        let ppem = self.size.round().clamp(1.0, Scalar::from(u16::MAX)) as u16;
+        let image = face.glyph_raster_image(ttf_parser::GlyphId(glyph), ppem)?;
+        if image.format != ttf_parser::RasterImageFormat::BitmapPremulBgra32 {
             return None;
         }
 
-        // Placeholder - returns a simple colored rectangle
-        // Real implementation would extract the actual glyph image from CBDT/CBLC or SVG tables
-        let size = (self.size * 2.0).ceil() as i32;
-        let mut pixels = vec![0u8; (size * size * 4) as usize];
-
-        // Fill with a placeholder color
-        for y in 0..size {
-            for x in 0..size {
-                let offset = ((y * size + x) * 4) as usize;
-                pixels[offset] = 255; // R
-                pixels[offset + 1] = 200; // G
-                pixels[offset + 2] = 0; // B (yellow-ish for emoji placeholder)
-                pixels[offset + 3] = 255; // A
-            }
-        }
+        let pixels = image
+            .data
+            .chunks_exact(4)
+            .flat_map(|bgra| {
+                let color = Color::from_argb(bgra[3], bgra[2], bgra[1], bgra[0]).unpremultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        let scale = self.size / Scalar::from(image.pixels_per_em.max(1));
 
         Some(GlyphImage {
-            width: size,
-            height: size,
+            width: i32::from(image.width),
+            height: i32::from(image.height),
             pixels,
-            left: 0.0,
-            top: -self.size * 0.8,
+            left: Scalar::from(image.x) * scale,
+            top: -(Scalar::from(image.y) + Scalar::from(image.height)) * scale,
         })
     }
 
@@ -534,6 +606,109 @@ impl Font {
     }
 }
 
+/// Collects a glyph outline from font units into a path in text space.
+struct GlyphOutline {
+    builder: skia_rs_path::PathBuilder,
+    scale_x: Scalar,
+    scale_y: Scalar,
+    skew_x: Scalar,
+}
+
+impl GlyphOutline {
+    /// Map a point in font units, with y up, to text space, with y down.
+    fn map(&self, x: f32, y: f32) -> (Scalar, Scalar) {
+        let y = -y * self.scale_y;
+        (x * self.scale_x + self.skew_x * y, y)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x, y) = self.map(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x2, y2) = self.map(x2, y2);
+        let (x, y) = self.map(x, y);
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+/// Collects the solid layers of a COLRv0 glyph.
+struct ColorLayerPainter<'f> {
+    font: &'f Font,
+    outline: Option<skia_rs_path::Path>,
+    layers: Vec<ColorGlyphLayer>,
+    /// Cleared when the glyph uses a paint we can't represent as layers.
+    supported: bool,
+}
+
+impl<'a> ttf_parser::colr::Painter<'a> for ColorLayerPainter<'_> {
+    fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+        self.outline = self.font.get_glyph_path(glyph_id.0);
+    }
+
+    fn paint(&mut self, paint: ttf_parser::colr::Paint<'a>) {
+        match (paint, self.outline.take()) {
+            (ttf_parser::colr::Paint::Solid(c), Some(path)) => self.layers.push(ColorGlyphLayer {
+                path,
+                color: Color::from_argb(c.alpha, c.red, c.green, c.blue),
+            }),
+            // A layer without an outline draws nothing
+            (ttf_parser::colr::Paint::Solid(_), None) => {}
+            _ => self.supported = false,
+        }
+    }
+
+    fn push_clip(&mut self) {
+        self.supported = false;
+    }
+
+    fn push_clip_box(&mut self, _clipbox: ttf_parser::colr::ClipBox) {
+        self.supported = false;
+    }
+
+    fn pop_clip(&mut self) {}
+
+    fn push_layer(&mut self, _mode: ttf_parser::colr::CompositeMode) {
+        self.supported = false;
+    }
+
+    fn pop_layer(&mut self) {}
+
+    fn push_transform(&mut self, _transform: ttf_parser::Transform) {
+        self.supported = false;
+    }
+
+    fn pop_transform(&mut self) {}
+}
+
+/// A solid-colored layer of a color glyph.
+#[derive(Debug, Clone)]
+pub struct ColorGlyphLayer {
+    /// Outline of the layer.
+    pub path: skia_rs_path::Path,
+    /// Fill color.
+    pub color: Color,
+}
+
 /// Image data for a color glyph (emoji).
 #[derive(Debug, Clone)]
 pub struct GlyphImage {
@@ -549,6 +724,12 @@ pub struct GlyphImage {
     pub top: Scalar,
 }
 
+/// Read a glyph's horizontal advance in font units.
+fn face_advance(face: &ttf_parser::Face<'_>, glyph: u16) -> Scalar {
+    face.glyph_hor_advance(ttf_parser::GlyphId(glyph))
+        .unwrap_or(0) as Scalar
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -569,8 +750,92 @@ mod tests {
     #[test]
     fn test_font_measure_text() {
         let font = Font::from_size(20.0);
-        let width = font.measure_text("Hello");
+        let (width, bounds) = font.measure_text("Hello");
         assert!(width > 0.0);
+        assert!(!bounds.is_empty());
+    }
+
+    #[test]
+    fn test_measure_text_shaped() {
+        let (font, _) = tuffy(40.0);
+        let a = font.char_to_glyph('A');
+        assert_ne!(a, 0);
+        let advance = font.get_widths(&[a])[0];
+        assert!(advance > 0.0);
+
+        // Kerning may adjust the pair, but only by a fraction of the advance
+        let (width, bounds) = font.measure_text("AA");
+        assert!((width - 2.0 * advance).abs() < advance * 0.25);
+
+        // The ink sits above the baseline, within the advance
+        let (_, a_bounds) = font.measure_text("A");
+        assert!(a_bounds.top < -20.0 && a_bounds.bottom <= 0.5);
+        assert!(a_bounds.left >= -1.0 && a_bounds.right <= advance + 1.0);
+        assert!(bounds.width() > a_bounds.width() + advance * 0.5);
+
+        let (space, space_bounds) = font.measure_text(" ");
+        assert!(space > 0.0);
+        assert!(space_bounds.is_empty());
+    }
+
+    const TUFFY: &[u8] = include_bytes!("../testdata/Tuffy.ttf");
+
+    fn tuffy(size: Scalar) -> (Font, ttf_parser::Face<'static>) {
+        let typeface = Typeface::from_bytes(TUFFY.to_vec(), 0).unwrap();
+        let face = ttf_parser::Face::parse(TUFFY, 0).unwrap();
+        (Font::new(Arc::new(typeface), size), face)
+    }
+
+    #[test]
+    fn test_get_glyph_path() {
+        let (font, face) = tuffy(20.0);
+        let glyph = face.glyph_index('I').unwrap();
+        let bbox = face.glyph_bounding_box(glyph).unwrap();
+        let scale = 20.0 / Scalar::from(face.units_per_em());
+
+        // A sans-serif I is a single upright rectangle standing on the
+        // baseline, flipped to y-down.
+        let path = font.get_glyph_path(glyph.0).unwrap();
+        assert_eq!(path.contour_count(), 1);
+        let bounds = path.bounds();
+        let expected = skia_rs_core::Rect::new(
+            Scalar::from(bbox.x_min) * scale,
+            -Scalar::from(bbox.y_max) * scale,
+            Scalar::from(bbox.x_max) * scale,
+            -Scalar::from(bbox.y_min) * scale,
+        );
+        assert_eq!(bounds, expected);
+        assert_eq!(bounds.bottom, 0.0);
+        assert!(bounds.height() > 10.0 && bounds.height() < 20.0);
+        assert!(
+            path.points()
+                .iter()
+                .all(|p| (p.x == bounds.left || p.x == bounds.right)
+                    && (p.y == bounds.top || p.y == bounds.bottom))
+        );
+
+        // No outline for a space, nor without font data
+        let space = face.glyph_index(' ').unwrap();
+        assert!(font.get_glyph_path(space.0).is_none());
+        assert!(Font::from_size(20.0).get_glyph_path(glyph.0).is_none());
+    }
+
+    #[test]
+    fn test_get_glyph_paths_composite() {
+        let (font, face) = tuffy(40.0);
+        let glyphs: Vec<u16> = ['A', '\u{c5}']
+            .iter()
+            .map(|&c| face.glyph_index(c).unwrap().0)
+            .collect();
+        let paths = font.get_glyph_paths(&glyphs);
+        let base = paths[0].as_ref().unwrap().bounds();
+        let ring = paths[1].as_ref().unwrap();
+
+        // Å is A plus a ring above it; both components land in the path.
+        assert!(ring.contour_count() > paths[0].as_ref().unwrap().contour_count());
+        let bounds = ring.bounds();
+        assert_eq!(bounds.bottom, base.bottom);
+        assert!(bounds.top < base.top);
     }
 
     #[test]
@@ -580,4 +845,30 @@ mod tests {
         assert!(metrics.ascent < 0.0); // Above baseline
         assert!(metrics.descent > 0.0); // Below baseline
     }
+
+    #[test]
+    fn test_color_glyph_layers() {
+        let data = include_bytes!("../testdata/colr_1.ttf");
+        let typeface = Typeface::from_bytes(data.to_vec(), 0).unwrap();
+        let font = Font::new(Arc::new(typeface), 50.0);
+
+        // Glyph 168 is COLRv0, glyph 9 a COLRv1 gradient
+        assert!(font.is_color_glyph(168));
+        let layers = font
+            .color_glyph_layers(168, Color::from_rgb(0, 0, 0))
+            .unwrap();
+        assert_eq!(layers.len(), 8);
+        assert_eq!(layers[0].color, Color::from_rgb(255, 0, 0));
+        assert_eq!(layers[1].color, Color::from_rgb(255, 165, 0));
+        assert!(layers.iter().all(|layer| !layer.path.is_empty()));
+        assert!(font.is_color_glyph(9));
+        assert!(
+            font.color_glyph_layers(9, Color::from_rgb(0, 0, 0))
+                .is_none()
+        );
+
+        let (tuffy, _) = tuffy(50.0);
+        assert!(!tuffy.is_color_glyph(tuffy.char_to_glyph('A')));
+        assert!(tuffy.glyph_image(tuffy.char_to_glyph('A')).is_none());
+    }
 }
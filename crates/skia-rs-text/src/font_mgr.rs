@@ -6,7 +6,7 @@
 //! - Font style set management
 //! - Font fallback chains
 
-use crate::typeface::{FontStyle, Typeface, TypefaceRef};
+use crate::typeface::{FontSlant, FontStyle, FontWidth, Typeface, TypefaceRef};
 use std::sync::Arc;
 
 /// Font manager for system font enumeration.
@@ -25,6 +25,21 @@ pub trait FontMgr: Send + Sync {
     /// Match a family name and style to a typeface.
     fn match_family_style(&self, family_name: &str, style: FontStyle) -> Option<TypefaceRef>;
 
+    /// Resolve a family name and style the way Skia's legacy API does.
+    ///
+    /// Unlike [`FontMgr::match_family_style`], a missing or unknown family
+    /// falls back to the manager's first family. Within the family, the
+    /// closest available style is used when there is no exact match.
+    fn legacy_make_typeface(
+        &self,
+        family_name: Option<&str>,
+        style: FontStyle,
+    ) -> Option<TypefaceRef> {
+        family_name
+            .and_then(|name| self.match_family_style(name, style))
+            .or_else(|| self.match_family_style(&self.family_name(0)?, style))
+    }
+
     /// Match a character to find a fallback font.
     fn match_family_style_character(
         &self,
@@ -107,6 +122,21 @@ impl DefaultFontMgr {
         mgr
     }
 
+    /// Create a font manager holding only the given typefaces.
+    ///
+    /// Each typeface is registered under its own family name and style, in
+    /// order, so the family of the first typeface is the default family for
+    /// [`FontMgr::legacy_make_typeface`].
+    pub fn new_with_fonts(typefaces: Vec<Typeface>) -> Self {
+        let mut mgr = Self::default();
+        for typeface in typefaces {
+            let family_name = typeface.family_name().to_string();
+            let style = typeface.style();
+            mgr.register_typeface(&family_name, Arc::new(typeface), &style_name(style), style);
+        }
+        mgr
+    }
+
     /// Register a font family.
     pub fn register_family(&mut self, family: FontFamily) {
         self.families.push(family);
@@ -205,22 +235,105 @@ impl FontStyleSet for DefaultFontStyleSet {
     }
 
     fn match_style(&self, style: FontStyle) -> Option<TypefaceRef> {
-        // Find best match based on style distance
         self.family
             .typefaces
             .iter()
-            .min_by_key(|e| style_distance(&e.style, &style))
+            .min_by_key(|e| style_rank(&e.style, &style))
             .map(|e| e.typeface.clone())
     }
 }
 
-/// Calculate the "distance" between two font styles for matching.
-fn style_distance(a: &FontStyle, b: &FontStyle) -> u32 {
-    let weight_diff = (a.weight.0 as i32 - b.weight.0 as i32).unsigned_abs();
-    let width_diff = (a.width.0 as i32 - b.width.0 as i32).unsigned_abs();
-    let slant_diff = if a.slant == b.slant { 0 } else { 100 };
+/// Rank how well an available style matches a requested one; lower is
+/// better.
+///
+/// Follows the CSS font matching algorithm: width is narrowed first, then
+/// slant, then weight. Requests at or below normal width prefer narrower
+/// faces, italic and oblique prefer each other over upright, and weights
+/// fall back towards the nearest lighter or heavier face depending on the
+/// requested weight.
+fn style_rank(available: &FontStyle, requested: &FontStyle) -> (u32, u32, u32) {
+    (
+        width_rank(available.width.0, requested.width.0),
+        slant_rank(available.slant, requested.slant),
+        weight_rank(available.weight.0, requested.weight.0),
+    )
+}
+
+fn width_rank(available: u8, requested: u8) -> u32 {
+    let (available, requested) = (u32::from(available), u32::from(requested));
+    if requested <= u32::from(FontWidth::NORMAL.0) {
+        // Narrower first for condensed and normal requests
+        if available <= requested {
+            requested - available
+        } else {
+            100 + available - requested
+        }
+    } else if available >= requested {
+        available - requested
+    } else {
+        100 + requested - available
+    }
+}
+
+fn slant_rank(available: FontSlant, requested: FontSlant) -> u32 {
+    use FontSlant::{Italic, Oblique, Upright};
+    match (requested, available) {
+        (r, a) if r == a => 0,
+        (Italic, Oblique) | (Oblique, Italic) | (Upright, Oblique) => 1,
+        _ => 2,
+    }
+}
+
+fn weight_rank(available: u16, requested: u16) -> u32 {
+    let (available, requested) = (u32::from(available), u32::from(requested));
+    if (400..=500).contains(&requested) {
+        // Up to 500 first, then lighter, then heavier
+        if (requested..=500).contains(&available) {
+            available - requested
+        } else if available < requested {
+            1000 + requested - available
+        } else {
+            2000 + available - requested
+        }
+    } else if requested < 400 {
+        // Lighter first, then heavier
+        if available <= requested {
+            requested - available
+        } else {
+            1000 + available - requested
+        }
+    } else if available >= requested {
+        // Heavier first, then lighter
+        available - requested
+    } else {
+        1000 + requested - available
+    }
+}
 
-    weight_diff + width_diff * 10 + slant_diff
+/// Name a style the way font files usually do, e.g. "Bold Italic".
+fn style_name(style: FontStyle) -> String {
+    let weight = match style.weight.0 {
+        0..=149 => "Thin",
+        150..=249 => "Extra Light",
+        250..=349 => "Light",
+        350..=449 => "",
+        450..=549 => "Medium",
+        550..=649 => "Semi Bold",
+        650..=749 => "Bold",
+        750..=849 => "Extra Bold",
+        _ => "Black",
+    };
+    let slant = match style.slant {
+        FontSlant::Upright => "",
+        FontSlant::Italic => "Italic",
+        FontSlant::Oblique => "Oblique",
+    };
+    match (weight, slant) {
+        ("", "") => "Regular".to_string(),
+        ("", slant) => slant.to_string(),
+        (weight, "") => weight.to_string(),
+        (weight, slant) => format!("{weight} {slant}"),
+    }
 }
 
 /// Font fallback chain for handling missing glyphs.
@@ -268,6 +381,7 @@ impl FontFallback {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::typeface::FontWeight;
 
     #[test]
     fn test_default_font_mgr() {
@@ -283,6 +397,66 @@ mod tests {
         assert!(typeface.is_some());
     }
 
+    const TUFFY: &[u8] = include_bytes!("../testdata/Tuffy.ttf");
+
+    /// Load Tuffy with its `OS/2` weight class patched to `weight`.
+    fn tuffy_with_weight(weight: u16) -> Typeface {
+        let mut data = TUFFY.to_vec();
+        let num_tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+        let os2 = (0..num_tables)
+            .map(|i| 12 + i * 16)
+            .find(|&record| &data[record..record + 4] == b"OS/2")
+            .map(|record| u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap()))
+            .unwrap() as usize;
+        data[os2 + 4..os2 + 6].copy_from_slice(&weight.to_be_bytes());
+        Typeface::from_bytes(data, 0).unwrap()
+    }
+
+    #[test]
+    fn test_match_family_style_by_weight() {
+        let mgr =
+            DefaultFontMgr::new_with_fonts(vec![tuffy_with_weight(400), tuffy_with_weight(700)]);
+        assert_eq!(mgr.count_families(), 1);
+        let style_set = mgr.create_style_set("Tuffy").unwrap();
+        assert_eq!(style_set.style(1).unwrap().1, "Bold");
+
+        let bold = mgr.match_family_style("Tuffy", FontStyle::BOLD).unwrap();
+        assert_eq!(bold.style().weight, FontWeight::BOLD);
+        let regular = mgr.match_family_style("Tuffy", FontStyle::NORMAL).unwrap();
+        assert_eq!(regular.style().weight, FontWeight::NORMAL);
+        assert!(mgr.match_family_style("Missing", FontStyle::BOLD).is_none());
+
+        // Missing weights fall back to the closest face
+        let semi_bold =
+            FontStyle::new(FontWeight::SEMI_BOLD, FontWidth::NORMAL, FontSlant::Upright);
+        let style = mgr
+            .legacy_make_typeface(Some("Tuffy"), semi_bold)
+            .unwrap()
+            .style();
+        assert_eq!(style.weight, FontWeight::BOLD);
+        let medium = FontStyle::new(FontWeight::MEDIUM, FontWidth::NORMAL, FontSlant::Upright);
+        let style = mgr.legacy_make_typeface(None, medium).unwrap().style();
+        assert_eq!(style.weight, FontWeight::NORMAL);
+        let style = mgr
+            .legacy_make_typeface(Some("Missing"), FontStyle::BOLD_ITALIC)
+            .unwrap()
+            .style();
+        assert_eq!(style.weight, FontWeight::BOLD);
+    }
+
+    #[test]
+    fn test_weight_rank() {
+        let order = |requested: u16| {
+            let mut weights = vec![100, 300, 400, 500, 600, 900];
+            weights.sort_by_key(|&w| weight_rank(w, requested));
+            weights
+        };
+        assert_eq!(order(400), [400, 500, 300, 100, 600, 900]);
+        assert_eq!(order(500), [500, 400, 300, 100, 600, 900]);
+        assert_eq!(order(300), [300, 100, 400, 500, 600, 900]);
+        assert_eq!(order(700), [900, 600, 500, 400, 300, 100]);
+    }
+
     #[test]
     fn test_font_fallback() {
         let fallback = FontFallback::new();
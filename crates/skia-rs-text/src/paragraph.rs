@@ -7,9 +7,12 @@
 //! - Hyphenation support
 //! - Text alignment and justification
 
-use crate::font::{Font, FontMetrics};
+use crate::font::Font;
+use crate::shaper::Shaper;
 use crate::text_blob::{TextBlob, TextBlobBuilder};
+use crate::typeface::{Typeface, TypefaceRef};
 use skia_rs_core::{Point, Rect, Scalar};
+use std::sync::Arc;
 
 /// Text direction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -138,9 +141,52 @@ pub enum DecorationStyle {
     Wavy,
 }
 
+/// The fonts available to paragraph layout.
+///
+/// Text styles whose font carries no font data are laid out with the
+/// collection's default typeface at the style's size instead.
+///
+/// Corresponds to Skia's `skia::textlayout::FontCollection`.
+#[derive(Debug, Clone, Default)]
+pub struct FontCollection {
+    default_typeface: Option<TypefaceRef>,
+}
+
+impl FontCollection {
+    /// Create an empty font collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the typeface used for text styles without font data.
+    pub fn set_default_typeface(&mut self, typeface: TypefaceRef) -> &mut Self {
+        self.default_typeface = Some(typeface);
+        self
+    }
+
+    /// Get the typeface used for text styles without font data.
+    pub fn default_typeface(&self) -> Option<&TypefaceRef> {
+        self.default_typeface.as_ref()
+    }
+
+    /// Resolve the font a text style is laid out with.
+    fn resolve(&self, font: &Font) -> Font {
+        let has_data = font.typeface().and_then(Typeface::font_data).is_some();
+        match &self.default_typeface {
+            Some(typeface) if !has_data => {
+                let mut font = font.clone();
+                font.set_typeface(typeface.clone());
+                font
+            }
+            _ => font.clone(),
+        }
+    }
+}
+
 /// A builder for creating paragraphs.
 pub struct ParagraphBuilder {
     style: ParagraphStyle,
+    fonts: FontCollection,
     runs: Vec<TextRun>,
     current_style: TextStyle,
 }
@@ -153,10 +199,12 @@ struct TextRun {
 }
 
 impl ParagraphBuilder {
-    /// Create a new paragraph builder with the given style.
-    pub fn new(style: ParagraphStyle) -> Self {
+    /// Create a new paragraph builder with the given style, drawing fonts
+    /// from `fonts`.
+    pub fn new(style: ParagraphStyle, fonts: &FontCollection) -> Self {
         Self {
             style,
+            fonts: fonts.clone(),
             runs: Vec::new(),
             current_style: TextStyle::default(),
         }
@@ -177,9 +225,11 @@ impl ParagraphBuilder {
     /// Add text with the current style.
     pub fn add_text(&mut self, text: &str) -> &mut Self {
         if !text.is_empty() {
+            let mut style = self.current_style.clone();
+            style.font = self.fonts.resolve(&style.font);
             self.runs.push(TextRun {
                 text: text.to_string(),
-                style: self.current_style.clone(),
+                style,
             });
         }
         self
@@ -193,6 +243,7 @@ impl ParagraphBuilder {
             lines: Vec::new(),
             width: 0.0,
             height: 0.0,
+            max_intrinsic_width: 0.0,
             laid_out: false,
         }
     }
@@ -205,164 +256,332 @@ pub struct Paragraph {
     lines: Vec<TextLine>,
     width: Scalar,
     height: Scalar,
+    max_intrinsic_width: Scalar,
     laid_out: bool,
 }
 
 /// A line of text in a paragraph.
 #[derive(Debug, Clone)]
 struct TextLine {
-    /// Glyphs and positions for this line.
-    glyphs: Vec<(u16, Point)>,
-    /// Font for this line (simplified - assumes single font per line).
-    font: Font,
+    /// Glyphs grouped by font, positioned relative to the line's baseline.
+    runs: Vec<LineRun>,
     /// Line bounds.
     bounds: Rect,
     /// Baseline Y position.
     baseline: Scalar,
+    /// Advance width of the line, not counting trailing whitespace.
+    width: Scalar,
+}
+
+/// Glyphs on one line that share a font.
+#[derive(Debug, Clone)]
+struct LineRun {
+    font: Font,
+    glyphs: Vec<u16>,
+    positions: Vec<Point>,
+}
+
+/// A shaped glyph waiting to be placed on a line.
+#[derive(Debug, Clone, Copy)]
+struct LayoutGlyph {
+    glyph: u16,
+    /// Index of the text run the glyph was shaped from.
+    run: usize,
+    /// Byte offset of the glyph's cluster in the paragraph text.
+    cluster: usize,
+    /// Offset from the pen position, y down.
+    offset: Point,
+    advance: Scalar,
+    whitespace: bool,
+}
+
+/// Glyphs between two line break opportunities.
+#[derive(Debug, Default)]
+struct Word {
+    glyphs: Vec<LayoutGlyph>,
+    /// Whether a newline ends the line after this word.
+    hard_break: bool,
+}
+
+impl Word {
+    /// Width of the word without its trailing whitespace.
+    fn content_width(&self) -> Scalar {
+        content_width(&self.glyphs)
+    }
+}
+
+/// Advance up to the end of the last glyph that isn't whitespace.
+fn content_width(glyphs: &[LayoutGlyph]) -> Scalar {
+    let mut x = 0.0;
+    let mut width = 0.0;
+    for glyph in glyphs {
+        x += glyph.advance;
+        if !glyph.whitespace {
+            width = x;
+        }
+    }
+    width
 }
 
 impl Paragraph {
     /// Layout the paragraph to fit within the given width.
+    ///
+    /// Each run is shaped with its font. Lines break at whitespace and after
+    /// hyphens; a word wider than the whole line is broken between glyph
+    /// clusters. Trailing whitespace hangs past the line end and is ignored
+    /// when aligning. Justified lines stretch their spaces to fill the
+    /// width, except for the last line and lines ended by a newline.
     pub fn layout(&mut self, width: Scalar) {
         self.width = width;
         self.lines.clear();
+        self.height = 0.0;
 
-        // Collect run data first to avoid borrow issues
-        let runs_data: Vec<_> = self
-            .runs
-            .iter()
-            .map(|run| {
-                let font = run.style.font.clone();
-                let metrics = font.metrics();
-                let line_height = metrics.line_height() * self.style.height;
-                let char_width = font.size() * 0.5;
-                let chars: Vec<char> = run.text.chars().collect();
-                (
-                    font,
-                    line_height,
-                    char_width,
-                    run.style.letter_spacing,
-                    run.style.word_spacing,
-                    chars,
-                )
-            })
-            .collect();
-
-        let mut current_line_glyphs: Vec<(u16, Point)> = Vec::new();
-        let mut current_x: Scalar = 0.0;
-        let mut current_y: Scalar = 0.0;
-        let mut current_font = Font::default();
-        let mut line_height: Scalar = 0.0;
-
-        for (font, run_line_height, char_width, letter_spacing, word_spacing, chars) in runs_data {
-            current_font = font.clone();
-            line_height = line_height.max(run_line_height);
-
-            for c in chars {
-                // Handle newlines
-                if c == '\n' {
-                    self.add_line(
-                        &mut current_line_glyphs,
-                        &current_font,
-                        current_y,
-                        line_height,
-                    );
-                    current_x = 0.0;
-                    current_y += line_height;
-                    line_height = run_line_height;
-                    continue;
-                }
+        let words = self.shape_words();
+        self.max_intrinsic_width = intrinsic_width(&words);
 
-                // Check for word wrap
-                let advance = char_width + letter_spacing;
-                if current_x + advance > width && current_x > 0.0 {
-                    // Word wrap
-                    self.add_line(
-                        &mut current_line_glyphs,
-                        &current_font,
-                        current_y,
-                        line_height,
-                    );
-                    current_x = 0.0;
-                    current_y += line_height;
-
-                    // Check max lines
-                    if self.style.max_lines > 0 && self.lines.len() >= self.style.max_lines {
-                        self.laid_out = true;
-                        self.height = current_y;
-                        return;
+        let mut line: Vec<LayoutGlyph> = Vec::new();
+        let mut x: Scalar = 0.0;
+        for word in &words {
+            if self.is_full() {
+                break;
+            }
+            if !line.is_empty() && x + word.content_width() > width {
+                self.finish_line(&mut line, true);
+                x = 0.0;
+            }
+            if word.content_width() > width {
+                // Too long for any line: break between clusters
+                for cluster in word.glyphs.chunk_by(|a, b| a.cluster == b.cluster) {
+                    if !line.is_empty() && x + content_width(cluster) > width {
+                        if self.is_full() {
+                            break;
+                        }
+                        self.finish_line(&mut line, true);
+                        x = 0.0;
                     }
+                    line.extend_from_slice(cluster);
+                    x += cluster.iter().map(|g| g.advance).sum::<Scalar>();
                 }
-
-                let glyph_id = font.char_to_glyph(c);
-                current_line_glyphs.push((glyph_id, Point::new(current_x, 0.0)));
-                current_x += advance;
-
-                // Extra spacing for space characters
-                if c == ' ' {
-                    current_x += word_spacing;
-                }
+            } else {
+                line.extend_from_slice(&word.glyphs);
+                x += word.glyphs.iter().map(|g| g.advance).sum::<Scalar>();
+            }
+            if word.hard_break && !self.is_full() {
+                self.finish_line(&mut line, false);
+                x = 0.0;
             }
         }
-
-        // Finish last line
-        if !current_line_glyphs.is_empty() {
-            self.add_line(
-                &mut current_line_glyphs,
-                &current_font,
-                current_y,
-                line_height,
-            );
-            current_y += line_height;
+        let ends_with_newline = words.last().is_some_and(|word| word.hard_break);
+        if (!line.is_empty() || ends_with_newline) && !self.is_full() {
+            self.finish_line(&mut line, false);
         }
 
-        self.height = current_y;
         self.laid_out = true;
     }
 
-    fn add_line(&mut self, glyphs: &mut Vec<(u16, Point)>, font: &Font, y: Scalar, height: Scalar) {
-        if glyphs.is_empty() {
-            return;
+    /// Whether `max_lines` lines have been laid out.
+    fn is_full(&self) -> bool {
+        self.style.max_lines > 0 && self.lines.len() >= self.style.max_lines
+    }
+
+    /// Shape the text into words separated by line break opportunities.
+    fn shape_words(&self) -> Vec<Word> {
+        let text: String = self.runs.iter().map(|run| run.text.as_str()).collect();
+        let mut run_starts = Vec::with_capacity(self.runs.len());
+        let mut offset = 0;
+        for run in &self.runs {
+            run_starts.push(offset);
+            offset += run.text.len();
         }
+        let mut boundaries = run_starts.clone();
+        boundaries.push(offset);
+        let breaks = LineBreaker::new(&text);
+        boundaries.extend_from_slice(breaks.breaks());
+        for (i, c) in text.char_indices() {
+            if c == '\n' {
+                boundaries.extend([i, i + 1]);
+            }
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let shaper = Shaper::new();
+        let mut words = Vec::new();
+        let mut word = Word::default();
+        for piece in boundaries.windows(2) {
+            let (start, end) = (piece[0], piece[1]);
+            if start == end {
+                continue;
+            }
+            if &text[start..end] == "\n" {
+                word.hard_break = true;
+                words.push(std::mem::take(&mut word));
+                continue;
+            }
+            if breaks.breaks().binary_search(&start).is_ok() && !word.glyphs.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            let run = run_starts.partition_point(|&run_start| run_start <= start) - 1;
+            self.shape_piece(&shaper, &text[start..end], start, run, &mut word.glyphs);
+        }
+        if !word.glyphs.is_empty() {
+            words.push(word);
+        }
+        words
+    }
 
-        let metrics = font.metrics();
-        let baseline = y - metrics.ascent;
+    /// Shape a piece of one run, appending its glyphs.
+    fn shape_piece(
+        &self,
+        shaper: &Shaper,
+        text: &str,
+        start: usize,
+        run: usize,
+        glyphs: &mut Vec<LayoutGlyph>,
+    ) {
+        let style = &self.runs[run].style;
+        let spacing = |whitespace: bool| {
+            style.letter_spacing + if whitespace { style.word_spacing } else { 0.0 }
+        };
+        let is_whitespace = |cluster: usize| {
+            text[cluster..]
+                .chars()
+                .next()
+                .is_some_and(char::is_whitespace)
+        };
 
-        // Calculate line width
-        let line_width = glyphs
-            .last()
-            .map(|(_, p)| p.x + font.size() * 0.5)
-            .unwrap_or(0.0);
-
-        // Apply text alignment
-        let x_offset = match self.style.text_align {
-            TextAlign::Left | TextAlign::Start => 0.0,
-            TextAlign::Right | TextAlign::End => self.width - line_width,
-            TextAlign::Center => (self.width - line_width) / 2.0,
-            TextAlign::Justify => 0.0, // Would need more complex handling
+        if let Some(shaped) = shaper.shape_auto(text, &style.font) {
+            for glyph in shaped.iter().flat_map(|run| &run.glyphs) {
+                let cluster = glyph.cluster as usize;
+                let whitespace = is_whitespace(cluster);
+                glyphs.push(LayoutGlyph {
+                    glyph: glyph.glyph_id.0,
+                    run,
+                    cluster: start + cluster,
+                    offset: Point::new(glyph.x_offset, -glyph.y_offset),
+                    advance: glyph.x_advance + spacing(whitespace),
+                    whitespace,
+                });
+            }
+        } else {
+            // Without font data, fall back to the font's approximate advances
+            for (cluster, c) in text.char_indices() {
+                let glyph = style.font.char_to_glyph(c);
+                let whitespace = c.is_whitespace();
+                glyphs.push(LayoutGlyph {
+                    glyph,
+                    run,
+                    cluster: start + cluster,
+                    offset: Point::zero(),
+                    advance: style.font.glyph_advance(glyph) + spacing(whitespace),
+                    whitespace,
+                });
+            }
+        }
+    }
+
+    /// Align the glyphs of a finished line and add it below the others.
+    fn finish_line(&mut self, glyphs: &mut Vec<LayoutGlyph>, soft_break: bool) {
+        let y = self.height;
+        let line_width = content_width(glyphs);
+
+        // Metrics of every font on the line, or of the last run for a
+        // blank line
+        let mut fonts: Vec<usize> = glyphs.iter().map(|g| g.run).collect();
+        fonts.dedup();
+        if fonts.is_empty() {
+            let last = glyphs
+                .first()
+                .map_or(self.runs.len().saturating_sub(1), |g| g.run);
+            fonts.push(last);
+        }
+        let (mut ascent, mut descent, mut leading): (Scalar, Scalar, Scalar) = (0.0, 0.0, 0.0);
+        for &run in &fonts {
+            if let Some(run) = self.runs.get(run) {
+                let metrics = run.style.font.metrics();
+                ascent = ascent.min(metrics.ascent);
+                descent = descent.max(metrics.descent);
+                leading = leading.max(metrics.leading);
+            }
+        }
+        let height = (descent - ascent + leading) * self.style.height;
+        let baseline = y - ascent * self.style.height;
+
+        let rtl = self.style.text_direction == TextDirection::Rtl;
+        let slack = self.width - line_width;
+        let x_offset = match (self.style.text_align, rtl) {
+            (TextAlign::Left | TextAlign::Justify, _)
+            | (TextAlign::Start, false)
+            | (TextAlign::End, true) => 0.0,
+            (TextAlign::Right | TextAlign::Start | TextAlign::End, _) => slack,
+            (TextAlign::Center, _) => slack / 2.0,
         };
 
-        // Offset glyphs
-        let adjusted_glyphs: Vec<(u16, Point)> = glyphs
+        // Justified lines share the slack between their inner spaces
+        let content_end = glyphs.iter().rposition(|g| !g.whitespace).unwrap_or(0);
+        let inner_spaces = glyphs[..content_end]
             .iter()
-            .map(|(g, p)| (*g, Point::new(p.x + x_offset, p.y)))
-            .collect();
+            .filter(|g| g.whitespace)
+            .count();
+        let stretch = if self.style.text_align == TextAlign::Justify
+            && soft_break
+            && inner_spaces > 0
+            && slack > 0.0
+        {
+            slack / inner_spaces as Scalar
+        } else {
+            0.0
+        };
+
+        let mut runs: Vec<LineRun> = Vec::new();
+        let mut x = x_offset;
+        for glyph in glyphs.iter() {
+            let position = Point::new(x + glyph.offset.x, glyph.offset.y);
+            x += glyph.advance;
+            if glyph.whitespace {
+                x += stretch;
+            }
+            let font = &self.runs[glyph.run].style.font;
+            match runs.last_mut() {
+                Some(last)
+                    if Arc::ptr_eq(last.font.typeface_ref(), font.typeface_ref())
+                        && last.font.size() == font.size() =>
+                {
+                    last.glyphs.push(glyph.glyph);
+                    last.positions.push(position);
+                }
+                _ => runs.push(LineRun {
+                    font: font.clone(),
+                    glyphs: vec![glyph.glyph],
+                    positions: vec![position],
+                }),
+            }
+        }
 
         self.lines.push(TextLine {
-            glyphs: adjusted_glyphs,
-            font: font.clone(),
+            runs,
             bounds: Rect::from_xywh(0.0, y, self.width, height),
             baseline,
+            width: if stretch > 0.0 {
+                self.width
+            } else {
+                line_width
+            },
         });
-
+        self.height += height;
         glyphs.clear();
     }
 
-    /// Get the laid-out width.
-    pub fn max_intrinsic_width(&self) -> Scalar {
+    /// Get the width the paragraph was laid out to.
+    pub fn max_width(&self) -> Scalar {
         self.width
     }
 
+    /// Get the width the paragraph would take without soft line breaks.
+    pub fn max_intrinsic_width(&self) -> Scalar {
+        self.max_intrinsic_width
+    }
+
     /// Get the laid-out height.
     pub fn height(&self) -> Scalar {
         self.height
@@ -378,14 +597,9 @@ impl Paragraph {
         self.lines.get(line).map(|l| l.bounds.height())
     }
 
-    /// Get the width of a specific line.
+    /// Get the width of a specific line, not counting trailing whitespace.
     pub fn line_width(&self, line: usize) -> Option<Scalar> {
-        self.lines.get(line).map(|l| {
-            l.glyphs
-                .last()
-                .map(|(_, p)| p.x + l.font.size() * 0.5)
-                .unwrap_or(0.0)
-        })
+        self.lines.get(line).map(|l| l.width)
     }
 
     /// Convert the paragraph to a text blob for drawing.
@@ -397,15 +611,14 @@ impl Paragraph {
         let mut builder = TextBlobBuilder::new();
 
         for line in &self.lines {
-            let positions: Vec<Point> = line
-                .glyphs
-                .iter()
-                .map(|(_, p)| Point::new(p.x, line.baseline + p.y))
-                .collect();
-
-            let glyphs: Vec<u16> = line.glyphs.iter().map(|(g, _)| *g).collect();
-
-            builder.add_positioned_run(&line.font, &glyphs, &positions);
+            for run in &line.runs {
+                let positions: Vec<Point> = run
+                    .positions
+                    .iter()
+                    .map(|p| Point::new(p.x, line.baseline + p.y))
+                    .collect();
+                builder.add_positioned_run(&run.font, &run.glyphs, &positions);
+            }
         }
 
         builder.build()
@@ -417,6 +630,20 @@ impl Paragraph {
     }
 }
 
+/// Width of the widest line when only newlines break the text.
+fn intrinsic_width(words: &[Word]) -> Scalar {
+    let mut widest: Scalar = 0.0;
+    let mut line: Vec<LayoutGlyph> = Vec::new();
+    for word in words {
+        line.extend_from_slice(&word.glyphs);
+        if word.hard_break {
+            widest = widest.max(content_width(&line));
+            line.clear();
+        }
+    }
+    widest.max(content_width(&line))
+}
+
 // =============================================================================
 // Line Breaking
 // =============================================================================
@@ -536,7 +763,7 @@ mod tests {
     #[test]
     fn test_paragraph_builder() {
         let style = ParagraphStyle::default();
-        let mut builder = ParagraphBuilder::new(style);
+        let mut builder = ParagraphBuilder::new(style, &FontCollection::new());
         builder.add_text("Hello, world!");
         let paragraph = builder.build();
         assert!(!paragraph.laid_out);
@@ -545,7 +772,7 @@ mod tests {
     #[test]
     fn test_paragraph_layout() {
         let style = ParagraphStyle::default();
-        let mut builder = ParagraphBuilder::new(style);
+        let mut builder = ParagraphBuilder::new(style, &FontCollection::new());
         builder.add_text("Hello, world! This is a test.");
         let mut paragraph = builder.build();
         paragraph.layout(100.0);
@@ -554,6 +781,106 @@ mod tests {
         assert!(paragraph.height() > 0.0);
     }
 
+    fn tuffy_paragraph(style: ParagraphStyle, text: &str) -> Paragraph {
+        let data = include_bytes!("../testdata/Tuffy.ttf").to_vec();
+        let typeface = Typeface::from_bytes(data, 0).unwrap();
+        let mut fonts = FontCollection::new();
+        fonts.set_default_typeface(Arc::new(typeface));
+
+        let mut text_style = TextStyle::default();
+        text_style.font.set_size(16.0);
+        let mut builder = ParagraphBuilder::new(style, &fonts);
+        builder.push_style(&text_style).add_text(text);
+        builder.build()
+    }
+
+    #[test]
+    fn test_paragraph_wraps_to_width() {
+        let text = "The quick brown fox jumps over the lazy dog";
+        let mut paragraph = tuffy_paragraph(ParagraphStyle::default(), text);
+        paragraph.layout(100.0);
+
+        assert_eq!(paragraph.max_width(), 100.0);
+        assert!(paragraph.line_count() > 1);
+        let line_height = paragraph.line_height(0).unwrap();
+        let lines = paragraph.height() / line_height;
+        assert!((lines - paragraph.line_count() as Scalar).abs() < 1e-4);
+        for line in 0..paragraph.line_count() {
+            assert!(paragraph.line_width(line).unwrap() <= 100.0);
+        }
+        assert!(paragraph.max_intrinsic_width() > 100.0);
+
+        // Every glyph is placed, including spaces hanging at line ends
+        let blob = paragraph.to_text_blob().unwrap();
+        let glyphs: usize = blob.runs().iter().map(|run| run.glyphs.len()).sum();
+        assert_eq!(glyphs, text.len());
+    }
+
+    #[test]
+    fn test_paragraph_breaks_long_words() {
+        let mut paragraph = tuffy_paragraph(ParagraphStyle::default(), "Incomprehensibilities");
+        paragraph.layout(60.0);
+
+        assert!(paragraph.line_count() > 1);
+        for line in 0..paragraph.line_count() {
+            assert!(paragraph.line_width(line).unwrap() <= 60.0);
+        }
+    }
+
+    #[test]
+    fn test_paragraph_hard_breaks() {
+        let mut paragraph = tuffy_paragraph(ParagraphStyle::default(), "One\nTwo\n\nFour");
+        paragraph.layout(500.0);
+        assert_eq!(paragraph.line_count(), 4);
+
+        let style = ParagraphStyle {
+            max_lines: 2,
+            ..ParagraphStyle::default()
+        };
+        let mut paragraph = tuffy_paragraph(style, "One\nTwo\n\nFour");
+        paragraph.layout(500.0);
+        assert_eq!(paragraph.line_count(), 2);
+    }
+
+    #[test]
+    fn test_paragraph_alignment() {
+        let line_start = |align: TextAlign| {
+            let style = ParagraphStyle {
+                text_align: align,
+                ..ParagraphStyle::default()
+            };
+            let mut paragraph = tuffy_paragraph(style, "Hello");
+            paragraph.layout(200.0);
+            let width = paragraph.line_width(0).unwrap();
+            let blob = paragraph.to_text_blob().unwrap();
+            (blob.runs()[0].positions[0].x, width)
+        };
+
+        let (left, width) = line_start(TextAlign::Left);
+        assert_eq!(left, 0.0);
+        let (right, _) = line_start(TextAlign::Right);
+        assert!((right - (200.0 - width)).abs() < 1e-3);
+        let (center, _) = line_start(TextAlign::Center);
+        assert!((center - (200.0 - width) / 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_paragraph_justify() {
+        let style = ParagraphStyle {
+            text_align: TextAlign::Justify,
+            ..ParagraphStyle::default()
+        };
+        let mut paragraph = tuffy_paragraph(style, "aa bb cc dd ee ff gg hh ii jj kk");
+        paragraph.layout(100.0);
+
+        assert!(paragraph.line_count() > 1);
+        let last = paragraph.line_count() - 1;
+        for line in 0..last {
+            assert_eq!(paragraph.line_width(line), Some(100.0));
+        }
+        assert!(paragraph.line_width(last).unwrap() < 100.0);
+    }
+
     #[test]
     fn test_line_breaker() {
         let breaker = LineBreaker::new("Hello world");
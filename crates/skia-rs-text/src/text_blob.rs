@@ -3,6 +3,7 @@
 //! A TextBlob contains a sequence of positioned glyphs that can be drawn efficiently.
 
 use crate::font::Font;
+use crate::shaper::ShapedRun;
 use skia_rs_core::{Point, Rect, Scalar};
 use std::sync::Arc;
 
@@ -97,6 +98,35 @@ impl TextBlob {
         Self::from_runs(vec![run])
     }
 
+    /// Create a text blob from shaped runs, laid out one after another.
+    ///
+    /// Glyphs are placed by the shaper's advances and offsets, with offsets
+    /// flipped to point down like the rest of the canvas.
+    pub fn from_shaped_runs(runs: &[ShapedRun], origin: Point) -> Self {
+        let mut pen = origin;
+        let runs = runs
+            .iter()
+            .map(|run| {
+                let run_origin = pen;
+                let mut advance = Point::zero();
+                let mut glyphs = Vec::with_capacity(run.glyphs.len());
+                let mut positions = Vec::with_capacity(run.glyphs.len());
+                for glyph in &run.glyphs {
+                    glyphs.push(glyph.glyph_id.0);
+                    positions.push(Point::new(
+                        advance.x + glyph.x_offset,
+                        advance.y - glyph.y_offset,
+                    ));
+                    advance.x += glyph.x_advance;
+                    advance.y -= glyph.y_advance;
+                }
+                pen += advance;
+                GlyphRun::new(run.font.clone(), glyphs, positions, run_origin)
+            })
+            .collect();
+        Self::from_runs(runs)
+    }
+
     /// Get the bounds of the text blob.
     #[inline]
     pub fn bounds(&self) -> Rect {
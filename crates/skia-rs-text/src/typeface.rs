@@ -2,6 +2,7 @@
 //!
 //! A typeface represents a specific font file or font family member.
 
+use skia_rs_core::Scalar;
 use std::sync::Arc;
 
 /// Font weight (100-900).
@@ -150,6 +151,8 @@ pub struct Typeface {
     units_per_em: u16,
     /// Number of glyphs.
     glyph_count: u16,
+    /// Index of the face within a font collection.
+    index: u32,
 }
 
 impl Typeface {
@@ -164,6 +167,7 @@ impl Typeface {
             data: None,
             units_per_em: 2048,
             glyph_count: 256,
+            index: 0,
         }
     }
 
@@ -182,9 +186,62 @@ impl Typeface {
             data: Some(Arc::new(data)),
             units_per_em: 2048,
             glyph_count: 256,
+            index: 0,
         })
     }
 
+    /// Create a typeface from TrueType/OpenType data.
+    ///
+    /// For font collections (`.ttc`/`.otc`), `index` selects the face; use
+    /// [`Typeface::count_faces`] to enumerate them. For single fonts the index
+    /// must be 0. Returns `None` if the face cannot be parsed.
+    ///
+    /// The data is kept alive by the typeface without copying, so faces of
+    /// one collection can share a single `Arc`.
+    pub fn from_bytes(data: impl Into<Arc<Vec<u8>>>, index: u32) -> Option<Self> {
+        static NEXT_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(1);
+
+        let data = data.into();
+        let face = ttf_parser::Face::parse(&data, index).ok()?;
+
+        let family_name = face_family_name(&face).unwrap_or_else(|| "Unknown".to_string());
+        let slant = if face.is_italic() {
+            FontSlant::Italic
+        } else if face.is_oblique() {
+            FontSlant::Oblique
+        } else {
+            FontSlant::Upright
+        };
+        let style = FontStyle::new(
+            FontWeight(face.weight().to_number()),
+            FontWidth(face.width().to_number() as u8),
+            slant,
+        );
+        let units_per_em = face.units_per_em();
+        let glyph_count = face.number_of_glyphs();
+
+        Some(Self {
+            family_name,
+            style,
+            id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            data: Some(data),
+            units_per_em,
+            glyph_count,
+            index,
+        })
+    }
+
+    /// Count the faces in TrueType/OpenType data.
+    ///
+    /// Returns the number of faces in a font collection, 1 for a single font,
+    /// or 0 if the data is not a font.
+    pub fn count_faces(data: &[u8]) -> u32 {
+        match ttf_parser::fonts_in_collection(data) {
+            Some(count) => count,
+            None => u32::from(ttf_parser::Face::parse(data, 0).is_ok()),
+        }
+    }
+
     /// Get the family name.
     #[inline]
     pub fn family_name(&self) -> &str {
@@ -215,6 +272,14 @@ impl Typeface {
         self.glyph_count
     }
 
+    /// Scale from font units to pixels at a text size of `size` pixels.
+    ///
+    /// Multiply metrics read from the font tables by this to get pixels.
+    #[inline]
+    pub fn units_to_px(&self, size: Scalar) -> Scalar {
+        size / Scalar::from(self.units_per_em.max(1))
+    }
+
     /// Check if this is a bold typeface.
     #[inline]
     pub fn is_bold(&self) -> bool {
@@ -235,25 +300,57 @@ impl Typeface {
     }
 
     /// Get the glyph ID for a character.
+    ///
+    /// Reads the font's `cmap` table, returning 0 (`.notdef`) for characters
+    /// the font doesn't map. Without font data, ASCII characters map to
+    /// their code points.
     pub fn char_to_glyph(&self, c: char) -> u16 {
-        // Simple ASCII mapping for now
-        // A real implementation would use font tables
-        if c.is_ascii() {
-            c as u16
-        } else {
-            0 // .notdef glyph
+        match self.face() {
+            Some(face) => face_glyph(&face, c),
+            None if c.is_ascii() => c as u16,
+            None => 0, // .notdef glyph
         }
     }
 
     /// Get glyph IDs for a string.
     pub fn chars_to_glyphs(&self, chars: &str) -> Vec<u16> {
-        chars.chars().map(|c| self.char_to_glyph(c)).collect()
+        match self.face() {
+            Some(face) => chars.chars().map(|c| face_glyph(&face, c)).collect(),
+            None => chars.chars().map(|c| self.char_to_glyph(c)).collect(),
+        }
     }
 
     /// Get direct access to font data (for shaping).
     pub fn font_data(&self) -> Option<&[u8]> {
         self.data.as_ref().map(|d| d.as_slice())
     }
+
+    /// Get the index of this face within its font collection.
+    #[inline]
+    pub fn face_index(&self) -> u32 {
+        self.index
+    }
+
+    /// Parse the font tables of this face, if it was loaded from font data.
+    pub(crate) fn face(&self) -> Option<ttf_parser::Face<'_>> {
+        ttf_parser::Face::parse(self.font_data()?, self.index).ok()
+    }
+}
+
+/// Look up a character in a face's `cmap` table.
+fn face_glyph(face: &ttf_parser::Face<'_>, c: char) -> u16 {
+    face.glyph_index(c).map_or(0, |glyph| glyph.0)
+}
+
+/// Read the family name from a face, preferring the typographic family.
+fn face_family_name(face: &ttf_parser::Face<'_>) -> Option<String> {
+    let find = |id: u16| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == id)
+            .find_map(|name| name.to_string())
+    };
+    find(ttf_parser::name_id::TYPOGRAPHIC_FAMILY).or_else(|| find(ttf_parser::name_id::FAMILY))
 }
 
 /// A reference to a typeface (shared ownership).
@@ -281,6 +378,104 @@ mod tests {
         assert!(!tf.is_italic());
     }
 
+    const TUFFY: &[u8] = include_bytes!("../testdata/Tuffy.ttf");
+
+    fn read_u16(data: &[u8], offset: usize) -> u16 {
+        u16::from_be_bytes([data[offset], data[offset + 1]])
+    }
+
+    fn read_u32(data: &[u8], offset: usize) -> u32 {
+        u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Pack single-face fonts into a TrueType Collection.
+    fn make_ttc(fonts: &[Vec<u8>]) -> Vec<u8> {
+        let header_len = 12 + 4 * fonts.len();
+        let dir_len = |font: &[u8]| 12 + 16 * read_u16(font, 4) as usize;
+        let dirs_len: usize = fonts.iter().map(|f| dir_len(f)).sum();
+
+        let mut out = Vec::new();
+        out.extend_from_slice(b"ttcf");
+        out.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        out.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+        let mut dir_offset = header_len;
+        for font in fonts {
+            out.extend_from_slice(&(dir_offset as u32).to_be_bytes());
+            dir_offset += dir_len(font);
+        }
+
+        // Table directories, with table data appended after all of them.
+        let mut tables = Vec::new();
+        for font in fonts {
+            out.extend_from_slice(&font[..12]);
+            for i in 0..read_u16(font, 4) as usize {
+                let record = 12 + 16 * i;
+                let offset = read_u32(font, record + 8) as usize;
+                let length = read_u32(font, record + 12) as usize;
+                let new_offset = header_len + dirs_len + tables.len();
+                out.extend_from_slice(&font[record..record + 8]);
+                out.extend_from_slice(&(new_offset as u32).to_be_bytes());
+                out.extend_from_slice(&(length as u32).to_be_bytes());
+                tables.extend_from_slice(&font[offset..offset + length]);
+                tables.resize(tables.len().next_multiple_of(4), 0);
+            }
+        }
+        out.extend_from_slice(&tables);
+        out
+    }
+
+    /// Copy of Tuffy with its OS/2 weight class set to bold.
+    fn bold_tuffy() -> Vec<u8> {
+        let mut font = TUFFY.to_vec();
+        let os2 = (0..read_u16(&font, 4) as usize)
+            .map(|i| 12 + 16 * i)
+            .find(|&record| &font[record..record + 4] == b"OS/2")
+            .map(|record| read_u32(&font, record + 8) as usize)
+            .unwrap();
+        font[os2 + 4..os2 + 6].copy_from_slice(&700u16.to_be_bytes());
+        font
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let tf = Typeface::from_bytes(TUFFY.to_vec(), 0).unwrap();
+        assert_eq!(tf.family_name(), "Tuffy");
+        assert!(!tf.is_bold());
+        assert_eq!(tf.face_index(), 0);
+        assert!(tf.glyph_count() > 0);
+        assert_eq!(Typeface::count_faces(TUFFY), 1);
+
+        assert_eq!(tf.units_to_px(f32::from(tf.units_per_em())), 1.0);
+        assert_eq!(tf.units_to_px(0.0), 0.0);
+
+        assert!(Typeface::from_bytes(TUFFY.to_vec(), 1).is_none());
+        assert!(Typeface::from_bytes(vec![0; 64], 0).is_none());
+        assert_eq!(Typeface::count_faces(&[0; 64]), 0);
+    }
+
+    #[test]
+    fn test_from_bytes_collection() {
+        let ttc = make_ttc(&[TUFFY.to_vec(), bold_tuffy()]);
+        assert_eq!(Typeface::count_faces(&ttc), 2);
+
+        let ttc = Arc::new(ttc);
+        let regular = Typeface::from_bytes(Arc::clone(&ttc), 0).unwrap();
+        let bold = Typeface::from_bytes(Arc::clone(&ttc), 1).unwrap();
+        assert_eq!(Arc::strong_count(&ttc), 3);
+        assert!(std::ptr::eq(
+            regular.font_data().unwrap(),
+            bold.font_data().unwrap()
+        ));
+        assert_eq!(regular.face_index(), 0);
+        assert_eq!(bold.face_index(), 1);
+        assert_eq!(regular.family_name(), bold.family_name());
+        assert_ne!(regular.style(), bold.style());
+        assert!(!regular.is_bold());
+        assert!(bold.is_bold());
+
+        assert!(Typeface::from_bytes(ttc, 2).is_none());
+    }
+
     #[test]
     fn test_char_to_glyph() {
         let tf = Typeface::default_typeface();
@@ -8,7 +8,7 @@
 //!
 //! ## Quick Start
 //!
-//! ```rust,no_run
+//! ```rust
 //! use skia_rs::prelude::*;
 //!
 //! // Create a surface to draw on
@@ -85,10 +85,10 @@
 #![allow(clippy::module_inception)]
 
 // Re-export core crates
+pub use skia_rs_canvas as canvas;
 pub use skia_rs_core as core;
-pub use skia_rs_path as path;
 pub use skia_rs_paint as paint;
-pub use skia_rs_canvas as canvas;
+pub use skia_rs_path as path;
 pub use skia_rs_safe as safe;
 
 // Optional crate re-exports
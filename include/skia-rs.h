@@ -129,6 +129,9 @@ typedef struct sk_rect_t {
 // Opaque path builder type.
 typedef struct PathBuilder SkPathBuilder;
 
+// Opaque image type.
+typedef struct Image SkImage;
+
 // C-compatible matrix structure.
 typedef struct sk_matrix_t {
     // Matrix values (row-major).
@@ -249,6 +252,15 @@ bool sk_surface_peek_pixels(const SkSurface *surface,
                             const uint8_t **out_pixels,
                             uintptr_t *out_row_bytes);
 
+// Copy a rectangle of surface pixels into a caller-provided buffer,
+// converting to the color and alpha type of `info`.
+bool sk_surface_read_pixels(const SkSurface *surface,
+                            const struct sk_imageinfo_t *info,
+                            uint8_t *dst,
+                            uintptr_t dst_row_bytes,
+                            int32_t src_x,
+                            int32_t src_y);
+
 // Create a new paint.
 SkPaint *sk_paint_new(void);
 
@@ -390,6 +402,34 @@ void sk_matrix_map_point(const struct sk_matrix_t *matrix,
                          const struct sk_point_t *point,
                          struct sk_point_t *result);
 
+// Decode an image from encoded bytes (PNG, JPEG, GIF, WebP, ...).
+SkImage *sk_image_new_from_encoded(const uint8_t *data,
+                                   uintptr_t len);
+
+// Increment the reference count of an image.
+void sk_image_ref(SkImage *image);
+
+// Decrement the reference count of an image.
+void sk_image_unref(SkImage *image);
+
+// Get the reference count of an image.
+uint32_t sk_image_get_refcnt(const SkImage *image);
+
+// Get the width of an image.
+int32_t sk_image_get_width(const SkImage *image);
+
+// Get the height of an image.
+int32_t sk_image_get_height(const SkImage *image);
+
+// Encode an image as PNG.
+bool sk_image_encode_to_png(const SkImage *image,
+                            uint8_t **out_data,
+                            uintptr_t *out_len);
+
+// Free a buffer returned by an encode function.
+void sk_data_free(uint8_t *data,
+                  uintptr_t len);
+
 // Get the library version.
 const char *sk_version(void);
 
@@ -405,6 +445,18 @@ void sk_surface_draw_rect(SkSurface *surface,
                           const struct sk_rect_t *rect,
                           const SkPaint *paint);
 
+// Draw an oval inscribed in a rect on a surface.
+void sk_surface_draw_oval(SkSurface *surface,
+                          const struct sk_rect_t *rect,
+                          const SkPaint *paint);
+
+// Draw an image on a surface with its top-left corner at (x, y).
+void sk_surface_draw_image(SkSurface *surface,
+                           const SkImage *image,
+                           float x,
+                           float y,
+                           const SkPaint *paint);
+
 // Draw a circle on a surface.
 void sk_surface_draw_circle(SkSurface *surface,
                             float cx,
@@ -425,6 +477,17 @@ void sk_surface_draw_line(SkSurface *surface,
                           float y1,
                           const SkPaint *paint);
 
+// Set the transform applied to subsequent draws on a surface.
+void sk_surface_set_matrix(SkSurface *surface,
+                           const struct sk_matrix_t *matrix);
+
+// Reset the transform applied to subsequent draws on a surface to identity.
+void sk_surface_reset_matrix(SkSurface *surface);
+
+// Get the transform applied to subsequent draws on a surface.
+void sk_surface_get_matrix(const SkSurface *surface,
+                           struct sk_matrix_t *matrix);
+
 #ifdef __cplusplus
 }  // extern "C"
 #endif  // __cplusplus